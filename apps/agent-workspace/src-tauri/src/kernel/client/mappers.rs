@@ -137,6 +137,8 @@ pub(super) fn map_pid_status_to_workspace_snapshot(
             context_strategy: context.context_strategy.clone(),
             context_tokens_used: context.context_tokens_used,
             context_window_size: context.context_window_size,
+            compaction_trigger_tokens: context.compaction_trigger_tokens,
+            compaction_target_tokens: context.compaction_target_tokens,
             context_compressions: context.context_compressions,
             context_retrieval_hits: context.context_retrieval_hits,
             context_retrieval_requests: context.context_retrieval_requests,
@@ -156,6 +158,10 @@ pub(super) fn map_pid_status_to_workspace_snapshot(
             retrieve_candidate_limit: context.retrieve_candidate_limit,
             retrieve_max_segment_chars: context.retrieve_max_segment_chars,
             retrieve_min_score: context.retrieve_min_score,
+            pinned_segments: context.pinned_segments,
+            pinned_tokens: context.pinned_tokens,
+            policy_updates: context.policy_updates,
+            manual_compactions: context.manual_compactions,
         });
 
     WorkspaceSnapshot {
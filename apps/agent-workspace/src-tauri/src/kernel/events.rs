@@ -131,9 +131,18 @@ fn handle_kernel_event(
             );
             maybe_emit_lobby_snapshot(app, bridge, last_lobby_refresh, false);
         }
-        KernelEvent::LobbyChanged { .. } | KernelEvent::ModelChanged { .. } => {
+        KernelEvent::LobbyChanged { .. }
+        | KernelEvent::ModelChanged { .. }
+        | KernelEvent::WorkflowTaskChanged { .. }
+        | KernelEvent::JobRunStarted { .. }
+        | KernelEvent::ToolRegistryChanged { .. }
+        | KernelEvent::McpServerHealthChanged { .. }
+        | KernelEvent::BudgetExhausted { .. } => {
             maybe_emit_lobby_snapshot(app, bridge, last_lobby_refresh, false);
         }
+        KernelEvent::WorkflowFinished { .. } | KernelEvent::JobRunFinished { .. } => {
+            maybe_emit_lobby_snapshot(app, bridge, last_lobby_refresh, true);
+        }
         KernelEvent::WorkspaceChanged { pid, .. } => {
             maybe_emit_workspace_snapshot(
                 app,
@@ -570,6 +570,7 @@ mod tests {
                 kind: InvocationKind::Tool,
                 command: command.to_string(),
                 status,
+                cache_hit: false,
             })
             .expect("serialize invocation event"),
         )
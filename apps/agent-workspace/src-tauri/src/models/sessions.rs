@@ -117,6 +117,8 @@ pub struct WorkspaceContextSnapshot {
     pub context_strategy: String,
     pub context_tokens_used: usize,
     pub context_window_size: usize,
+    pub compaction_trigger_tokens: usize,
+    pub compaction_target_tokens: usize,
     pub context_compressions: u64,
     pub context_retrieval_hits: u64,
     pub context_retrieval_requests: u64,
@@ -136,6 +138,10 @@ pub struct WorkspaceContextSnapshot {
     pub retrieve_candidate_limit: usize,
     pub retrieve_max_segment_chars: usize,
     pub retrieve_min_score: f64,
+    pub pinned_segments: usize,
+    pub pinned_tokens: usize,
+    pub policy_updates: u64,
+    pub manual_compactions: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -190,6 +190,7 @@ fn live_timeline_renders_tool_only_from_structured_invocation_events() {
                 kind: InvocationKind::Tool,
                 command: r#"TOOL:calc {"expression":"1847*23"}"#.to_string(),
                 status: InvocationStatus::Dispatched,
+                cache_hit: false,
             },
         },
     );
@@ -113,6 +113,8 @@ export interface WorkspaceContextSnapshot {
   contextStrategy: string;
   contextTokensUsed: number;
   contextWindowSize: number;
+  compactionTriggerTokens: number;
+  compactionTargetTokens: number;
   contextCompressions: number;
   contextRetrievalHits: number;
   contextRetrievalRequests: number;
@@ -132,6 +134,8 @@ export interface WorkspaceContextSnapshot {
   retrieveCandidateLimit: number;
   retrieveMaxSegmentChars: number;
   retrieveMinScore: number;
+  pinnedSegments: number;
+  pinnedTokens: number;
 }
 
 export interface HumanInputRequest {
@@ -1139,6 +1143,8 @@ export interface WorkspaceSnapshotDto {
     context_strategy: string;
     context_tokens_used: number;
     context_window_size: number;
+    compaction_trigger_tokens?: number;
+    compaction_target_tokens?: number;
     context_compressions: number;
     context_retrieval_hits: number;
     context_retrieval_requests: number;
@@ -1158,6 +1164,8 @@ export interface WorkspaceSnapshotDto {
     retrieve_candidate_limit: number;
     retrieve_max_segment_chars: number;
     retrieve_min_score: number;
+    pinned_segments?: number;
+    pinned_tokens?: number;
   };
   pending_human_request: null | {
     request_id: string;
@@ -557,6 +557,8 @@ function mapWorkspaceContext(
     contextStrategy: context.context_strategy,
     contextTokensUsed: context.context_tokens_used,
     contextWindowSize: context.context_window_size,
+    compactionTriggerTokens: context.compaction_trigger_tokens ?? 0,
+    compactionTargetTokens: context.compaction_target_tokens ?? 0,
     contextCompressions: context.context_compressions,
     contextRetrievalHits: context.context_retrieval_hits,
     contextRetrievalRequests: context.context_retrieval_requests,
@@ -576,6 +578,8 @@ function mapWorkspaceContext(
     retrieveCandidateLimit: context.retrieve_candidate_limit,
     retrieveMaxSegmentChars: context.retrieve_max_segment_chars,
     retrieveMinScore: context.retrieve_min_score,
+    pinnedSegments: context.pinned_segments ?? 0,
+    pinnedTokens: context.pinned_tokens ?? 0,
   };
 }
 
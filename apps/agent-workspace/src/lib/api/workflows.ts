@@ -124,6 +124,8 @@ export async function fetchOrchestrationStatus(
             contextStrategy: task.context.context_strategy,
             contextTokensUsed: task.context.context_tokens_used,
             contextWindowSize: task.context.context_window_size,
+            compactionTriggerTokens: task.context.compaction_trigger_tokens ?? 0,
+            compactionTargetTokens: task.context.compaction_target_tokens ?? 0,
             contextCompressions: task.context.context_compressions,
             contextRetrievalHits: task.context.context_retrieval_hits,
             contextRetrievalRequests: task.context.context_retrieval_requests,
@@ -147,6 +149,8 @@ export async function fetchOrchestrationStatus(
             retrieveCandidateLimit: task.context.retrieve_candidate_limit,
             retrieveMaxSegmentChars: task.context.retrieve_max_segment_chars,
             retrieveMinScore: task.context.retrieve_min_score,
+            pinnedSegments: task.context.pinned_segments ?? 0,
+            pinnedTokens: task.context.pinned_tokens ?? 0,
           }
         : null,
       latestOutputPreview: task.latest_output_preview ?? null,
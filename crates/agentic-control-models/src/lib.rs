@@ -281,6 +281,28 @@ pub struct StatusResponse {
     pub jobs: JobsStatus,
     pub orchestrations: OrchestrationsStatus,
     pub processes: ProcessesStatus,
+    #[serde(default)]
+    pub budgets: Vec<BudgetStatusView>,
+    #[serde(default)]
+    pub tool_manifests: Option<ToolManifestsStatusView>,
+}
+
+/// Spend of one configured budget inside its current window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetStatusView {
+    pub budget_id: String,
+    pub scope: String,
+    #[serde(default)]
+    pub target: Option<String>,
+    /// Provider, model, session or orchestration the spend is summed over.
+    #[serde(default)]
+    pub scope_key: Option<String>,
+    pub window: String,
+    pub window_started_at_ms: i64,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+    pub exhausted: bool,
+    pub on_exhausted: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -307,6 +329,26 @@ pub struct ModelStatus {
     pub runtime_load_queue: Vec<RuntimeLoadQueueEntryView>,
 }
 
+/// Declarative tool manifests loaded from `tools.manifest_dir`.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ToolManifestsStatusView {
+    pub manifest_dir: String,
+    #[serde(default)]
+    pub manifests: Vec<ToolManifestView>,
+    #[serde(default)]
+    pub last_scan_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolManifestView {
+    pub path: String,
+    /// Tool registered from the file; absent while the manifest is invalid.
+    #[serde(default)]
+    pub tool_name: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct McpStatusView {
     #[serde(default)]
@@ -403,6 +445,18 @@ pub struct MemoryStatus {
     pub parked_pids: usize,
     pub oom_events: u64,
     pub swap_worker_crashes: u64,
+    #[serde(default)]
+    pub swap_snapshots: usize,
+    #[serde(default)]
+    pub swap_disk_bytes: u64,
+    #[serde(default)]
+    pub swap_raw_bytes: u64,
+    #[serde(default)]
+    pub swap_evictions: u64,
+    #[serde(default)]
+    pub swap_orphans_removed: u64,
+    #[serde(default)]
+    pub swap_integrity_failures: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -412,6 +466,35 @@ pub struct SchedulerStatus {
     pub priority_high: usize,
     pub priority_normal: usize,
     pub priority_low: usize,
+    #[serde(default)]
+    pub fairness: SchedulerFairnessStatus,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SchedulerFairnessStatus {
+    pub policy: String,
+    pub scope: String,
+    pub max_in_flight: usize,
+    pub dispatches_total: u64,
+    pub aged_dispatches: u64,
+    pub deadline_dispatches: u64,
+    pub dispatches_critical: u64,
+    pub dispatches_high: u64,
+    pub dispatches_normal: u64,
+    pub dispatches_low: u64,
+    /// Jain's fairness index over priority-weighted dispatches (1.0 = even).
+    pub fairness_index: f64,
+    /// Longest current wait of a process not running on the worker.
+    pub max_wait_ms: u64,
+    #[serde(default)]
+    pub groups: Vec<SchedulerFairnessGroupView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulerFairnessGroupView {
+    pub group: String,
+    pub processes: usize,
+    pub virtual_service: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -437,6 +520,12 @@ pub struct ScheduledJobView {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub backoff_ms: u64,
+    /// `forbid`, `queue`, `replace` or `allow:<max_concurrent>`.
+    #[serde(default)]
+    pub concurrency_policy: String,
+    /// Runs of this job currently in flight.
+    #[serde(default)]
+    pub active_runs: u32,
     #[serde(default)]
     pub last_run_started_at_ms: Option<i64>,
     #[serde(default)]
@@ -468,6 +557,9 @@ pub struct ScheduledJobRunView {
     pub deadline_at_ms: Option<i64>,
     #[serde(default)]
     pub error: Option<String>,
+    /// Why the scheduler started, skipped, queued, deferred or cancelled this run.
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -586,6 +678,10 @@ pub struct ContextStatusSnapshot {
     pub context_strategy: String,
     pub context_tokens_used: usize,
     pub context_window_size: usize,
+    #[serde(default)]
+    pub compaction_trigger_tokens: usize,
+    #[serde(default)]
+    pub compaction_target_tokens: usize,
     pub context_compressions: u64,
     pub context_retrieval_hits: u64,
     pub context_retrieval_requests: u64,
@@ -605,6 +701,26 @@ pub struct ContextStatusSnapshot {
     pub retrieve_candidate_limit: usize,
     pub retrieve_max_segment_chars: usize,
     pub retrieve_min_score: f64,
+    #[serde(default)]
+    pub pinned_segments: usize,
+    #[serde(default)]
+    pub pinned_tokens: usize,
+    #[serde(default)]
+    pub policy_updates: u64,
+    #[serde(default)]
+    pub manual_compactions: u64,
+    #[serde(default)]
+    pub dropped_segments: u64,
+    #[serde(default)]
+    pub segments: Vec<ContextSegmentView>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ContextSegmentView {
+    pub index: usize,
+    pub kind: String,
+    pub token_count: usize,
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -861,6 +977,21 @@ pub struct ModelCatalogEntry {
     pub selected: bool,
 }
 
+/// Effective context window for a model and the constraint that bound it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextWindowView {
+    pub window_tokens: usize,
+    pub source: String,
+    #[serde(default)]
+    pub model_max_tokens: Option<usize>,
+    #[serde(default)]
+    pub model_source: Option<String>,
+    #[serde(default)]
+    pub runtime_ctx_tokens: Option<usize>,
+    #[serde(default)]
+    pub memory_cap_tokens: Option<usize>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelRoutingRecommendation {
     pub workload: String,
@@ -905,6 +1036,8 @@ pub struct ModelInfoResponse {
     pub stop_markers: Option<Vec<String>>,
     pub capabilities: Option<BTreeMap<String, f64>>,
     pub selected: bool,
+    #[serde(default)]
+    pub context_window: Option<ContextWindowView>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -995,6 +1128,9 @@ pub struct InvocationEvent {
     pub kind: InvocationKind,
     pub command: String,
     pub status: InvocationStatus,
+    /// The tool output was served from the kernel tool result cache.
+    #[serde(default)]
+    pub cache_hit: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1047,5 +1183,68 @@ pub enum KernelEvent {
         selected_model_id: String,
         loaded_model_id: String,
     },
+    WorkflowTaskChanged {
+        orchestration_id: u64,
+        task_id: String,
+        status: String,
+        #[serde(default)]
+        attempt: Option<u32>,
+        #[serde(default)]
+        pid: Option<u64>,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    WorkflowFinished {
+        orchestration_id: u64,
+        status: String,
+        completed: usize,
+        failed: usize,
+        skipped: usize,
+    },
+    JobRunStarted {
+        job_id: u64,
+        run_id: u64,
+        attempt: u32,
+        trigger_at_ms: i64,
+        #[serde(default)]
+        orchestration_id: Option<u64>,
+        /// Process running an `exec` or `send_input` job target.
+        #[serde(default)]
+        pid: Option<u64>,
+    },
+    JobRunFinished {
+        job_id: u64,
+        run_id: u64,
+        attempt: u32,
+        status: String,
+        #[serde(default)]
+        error: Option<String>,
+    },
+    ToolRegistryChanged {
+        tool_name: String,
+        change: String,
+        source: String,
+    },
+    McpServerHealthChanged {
+        server_id: String,
+        health: String,
+        previous_health: String,
+        #[serde(default)]
+        last_error: Option<String>,
+    },
+    BudgetExhausted {
+        pid: u64,
+        #[serde(default)]
+        session_id: Option<String>,
+        budget_id: String,
+        scope: String,
+        #[serde(default)]
+        scope_key: Option<String>,
+        window: String,
+        spent_usd: f64,
+        limit_usd: f64,
+        /// `fail`, `route_local` or `await_approval`.
+        action: String,
+    },
     KernelShutdownRequested,
 }
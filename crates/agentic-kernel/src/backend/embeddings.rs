@@ -0,0 +1,284 @@
+//! Embedding providers for semantic retrieval.
+//!
+//! Two wire formats are supported: llama.cpp's native `/embedding` (either an
+//! explicit server or a dedicated runtime managed by the kernel, started with
+//! `--embeddings`) and the OpenAI-compatible `/embeddings` endpoint, reusing
+//! the endpoint and API key of a configured remote backend.
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use super::local::runtime_manager::ensure_embedding_runtime_for_target;
+use super::HttpEndpoint;
+use crate::config::EmbeddingsConfig;
+use crate::model_catalog::{ModelCatalog, ResolvedModelTarget};
+
+pub(crate) trait EmbeddingProvider: Send {
+    /// Stable identity of the vector space (provider + model): vectors from
+    /// different ids are never compared.
+    fn id(&self) -> String;
+
+    /// One vector per input, in input order.
+    fn embed(&mut self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String>;
+}
+
+/// Build the provider selected by `[context.embeddings]`, if any.
+pub(crate) fn embedding_provider_from_config(
+    config: &EmbeddingsConfig,
+) -> Option<Box<dyn EmbeddingProvider>> {
+    match config.provider.trim().to_ascii_lowercase().as_str() {
+        "" | "none" | "off" => None,
+        "llamacpp" | "llama.cpp" => Some(Box::new(LlamaCppEmbeddings {
+            selector: config.model.trim().to_string(),
+            explicit_endpoint: config.endpoint.trim().to_string(),
+            endpoint: None,
+            timeout_ms: config.timeout_ms,
+        })),
+        "openai" | "openai_compatible" => Some(Box::new(OpenAICompatibleEmbeddings {
+            backend_id: config.backend.trim().to_string(),
+            explicit_endpoint: config.endpoint.trim().to_string(),
+            model: config.model.trim().to_string(),
+            timeout_ms: config.timeout_ms,
+        })),
+        other => {
+            tracing::warn!(
+                provider = other,
+                "CONTEXT: unknown embedding provider, semantic retrieval stays lexical"
+            );
+            None
+        }
+    }
+}
+
+pub(crate) struct LlamaCppEmbeddings {
+    selector: String,
+    explicit_endpoint: String,
+    endpoint: Option<HttpEndpoint>,
+    timeout_ms: u64,
+}
+
+impl LlamaCppEmbeddings {
+    fn endpoint(&mut self) -> Result<&HttpEndpoint, String> {
+        if self.endpoint.is_none() {
+            let endpoint = if self.explicit_endpoint.is_empty() {
+                managed_embedding_endpoint(&self.selector)?
+            } else {
+                HttpEndpoint::parse(&self.explicit_endpoint).map_err(|err| err.to_string())?
+            };
+            self.endpoint = Some(endpoint);
+        }
+        self.endpoint
+            .as_ref()
+            .ok_or_else(|| "embedding endpoint unavailable".to_string())
+    }
+}
+
+impl EmbeddingProvider for LlamaCppEmbeddings {
+    fn id(&self) -> String {
+        let target = if self.explicit_endpoint.is_empty() {
+            &self.selector
+        } else {
+            &self.explicit_endpoint
+        };
+        format!("llamacpp:{target}")
+    }
+
+    fn embed(&mut self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let timeout_ms = self.timeout_ms;
+        let endpoint = self.endpoint()?.clone();
+        let response = endpoint
+            .request_json(
+                "POST",
+                &endpoint.joined_path("/embedding"),
+                Some(&json!({ "content": inputs })),
+                timeout_ms,
+            )
+            .map_err(|err| {
+                // A restarted runtime may move: resolve the endpoint again next time.
+                self.endpoint = None;
+                err.to_string()
+            })?;
+        if response.status_code != 200 {
+            return Err(format!("/embedding returned {}", response.status_line));
+        }
+        let body = response
+            .json
+            .ok_or_else(|| "/embedding returned no JSON body".to_string())?;
+        let vectors = parse_llamacpp_embeddings(&body)?;
+        expect_one_per_input(vectors, inputs.len())
+    }
+}
+
+fn managed_embedding_endpoint(selector: &str) -> Result<HttpEndpoint, String> {
+    if selector.is_empty() {
+        return Err("llamacpp embeddings need [context.embeddings].model or .endpoint".to_string());
+    }
+    let catalog = ModelCatalog::discover(crate::config::kernel_config().paths.models_dir.clone())
+        .map_err(|err| err.to_string())?;
+    match catalog
+        .resolve_load_target(selector)
+        .map_err(|err| err.to_string())?
+    {
+        ResolvedModelTarget::Local(target) => {
+            Ok(ensure_embedding_runtime_for_target(&target)?.endpoint)
+        }
+        ResolvedModelTarget::Remote(_) => Err(format!(
+            "embedding model '{selector}' is remote; use provider = \"openai_compatible\""
+        )),
+    }
+}
+
+/// Accepts both the pooled array form (`[{"index":0,"embedding":[[...]]}]`)
+/// and the older single-object form (`{"embedding":[...]}`). A server started
+/// with `--pooling none` returns one row per token: the rows are mean-pooled.
+pub(crate) fn parse_llamacpp_embeddings(body: &Value) -> Result<Vec<Vec<f32>>, String> {
+    let mut items = match body {
+        Value::Array(items) => items.iter().collect::<Vec<_>>(),
+        Value::Object(_) => vec![body],
+        _ => return Err("unexpected /embedding response".to_string()),
+    };
+    items.sort_by_key(|item| item.get("index").and_then(Value::as_u64).unwrap_or(0));
+    items
+        .into_iter()
+        .map(|item| {
+            let embedding = item
+                .get("embedding")
+                .ok_or_else(|| "/embedding item without 'embedding'".to_string())?;
+            match embedding.as_array().and_then(|rows| rows.first()) {
+                Some(Value::Array(_)) => {
+                    let rows = embedding
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .map(parse_vector)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    mean_pool(&rows)
+                }
+                Some(_) => parse_vector(embedding),
+                None => Err("empty embedding".to_string()),
+            }
+        })
+        .collect()
+}
+
+/// Component-wise mean of per-token rows; a single pooled row is returned as is.
+fn mean_pool(rows: &[Vec<f32>]) -> Result<Vec<f32>, String> {
+    let Some(first) = rows.first() else {
+        return Err("empty embedding".to_string());
+    };
+    if rows.iter().any(|row| row.len() != first.len()) {
+        return Err("per-token embeddings have mismatched dimensions".to_string());
+    }
+    let mut pooled = vec![0.0f32; first.len()];
+    for row in rows {
+        for (sum, component) in pooled.iter_mut().zip(row) {
+            *sum += component;
+        }
+    }
+    let count = rows.len() as f32;
+    pooled.iter_mut().for_each(|sum| *sum /= count);
+    Ok(pooled)
+}
+
+pub(crate) struct OpenAICompatibleEmbeddings {
+    backend_id: String,
+    explicit_endpoint: String,
+    model: String,
+    timeout_ms: u64,
+}
+
+impl EmbeddingProvider for OpenAICompatibleEmbeddings {
+    fn id(&self) -> String {
+        format!("openai:{}:{}", self.backend_id, self.model)
+    }
+
+    fn embed(&mut self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let runtime = super::remote_runtime_config_for_backend(&self.backend_id);
+        let endpoint = if self.explicit_endpoint.is_empty() {
+            runtime
+                .as_ref()
+                .map(|runtime| runtime.endpoint.clone())
+                .ok_or_else(|| format!("remote backend '{}' is not configured", self.backend_id))?
+        } else {
+            self.explicit_endpoint.clone()
+        };
+        if self.model.is_empty() {
+            return Err("openai_compatible embeddings need [context.embeddings].model".to_string());
+        }
+
+        let agent = ureq::AgentBuilder::new()
+            .timeout_connect(Duration::from_millis(self.timeout_ms))
+            .timeout_read(Duration::from_millis(self.timeout_ms))
+            .timeout_write(Duration::from_millis(self.timeout_ms))
+            .build();
+        let mut request = agent
+            .post(&format!("{}/embeddings", endpoint.trim_end_matches('/')))
+            .set("Content-Type", "application/json");
+        if let Some(api_key) = runtime
+            .as_ref()
+            .map(|runtime| runtime.api_key.as_str())
+            .filter(|key| !key.is_empty())
+        {
+            request = request.set("Authorization", &format!("Bearer {api_key}"));
+        }
+        let body = request
+            .send_json(json!({ "model": self.model, "input": inputs }))
+            .map_err(|err| match err {
+                ureq::Error::Status(code, _) => format!("/embeddings returned HTTP {code}"),
+                ureq::Error::Transport(transport) => transport.to_string(),
+            })?
+            .into_json::<Value>()
+            .map_err(|err| err.to_string())?;
+        let vectors = parse_openai_embeddings(&body)?;
+        expect_one_per_input(vectors, inputs.len())
+    }
+}
+
+pub(crate) fn parse_openai_embeddings(body: &Value) -> Result<Vec<Vec<f32>>, String> {
+    let mut items = body
+        .get("data")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "/embeddings response without 'data'".to_string())?
+        .iter()
+        .collect::<Vec<_>>();
+    items.sort_by_key(|item| item.get("index").and_then(Value::as_u64).unwrap_or(0));
+    items
+        .into_iter()
+        .map(|item| {
+            item.get("embedding")
+                .map(parse_vector)
+                .ok_or_else(|| "/embeddings item without 'embedding'".to_string())?
+        })
+        .collect()
+}
+
+fn parse_vector(value: &Value) -> Result<Vec<f32>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| "embedding is not an array".to_string())?
+        .iter()
+        .map(|component| {
+            component
+                .as_f64()
+                .map(|component| component as f32)
+                .ok_or_else(|| "embedding component is not a number".to_string())
+        })
+        .collect()
+}
+
+fn expect_one_per_input(vectors: Vec<Vec<f32>>, inputs: usize) -> Result<Vec<Vec<f32>>, String> {
+    if vectors.len() != inputs {
+        return Err(format!(
+            "embedding provider returned {} vectors for {} inputs",
+            vectors.len(),
+            inputs
+        ));
+    }
+    Ok(vectors)
+}
@@ -1,10 +1,11 @@
 use anyhow::{Error as E, Result};
 use serde_json::json;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use crate::memory::ContextSlotId;
 use crate::prompting::PromptFamily;
 
+use crate::backend::local::prefix_cache::{shared_prefix_cache, PrefixSeed, PrefixTemplateRef};
 use crate::backend::local::remote_adapter::{
     build_completion_request, decode_completion_response, select_completion_prompt_transport,
     PromptTransportStrategy,
@@ -23,6 +24,16 @@ pub(crate) struct ExternalLlamaCppBackend {
     family: PromptFamily,
     timeout_ms: u64,
     chunk_tokens: usize,
+    prefix_scope: Option<PrefixCacheScope>,
+    context_window_tokens: Option<usize>,
+}
+
+/// Model and slot directory the shared prefix templates belong to; only
+/// runtimes known to the runtime manager have one.
+#[derive(Clone)]
+struct PrefixCacheScope {
+    model: String,
+    slot_save_dir: PathBuf,
 }
 
 impl ExternalLlamaCppBackend {
@@ -32,7 +43,7 @@ impl ExternalLlamaCppBackend {
     }
 
     pub(crate) fn from_runtime_lease(lease: &ManagedLocalRuntimeLease) -> Self {
-        Self::from_endpoint(
+        let mut backend = Self::from_endpoint(
             lease.endpoint.clone(),
             lease.family,
             crate::config::kernel_config().external_llamacpp.timeout_ms,
@@ -40,7 +51,13 @@ impl ExternalLlamaCppBackend {
                 .external_llamacpp
                 .chunk_tokens
                 .max(1),
-        )
+        );
+        backend.prefix_scope = Some(PrefixCacheScope {
+            model: lease.logical_model_id.clone(),
+            slot_save_dir: lease.slot_save_dir.clone(),
+        });
+        backend.context_window_tokens = lease.context_window_tokens;
+        backend
     }
 
     pub(crate) fn for_diagnostics(
@@ -54,6 +71,8 @@ impl ExternalLlamaCppBackend {
             family,
             timeout_ms,
             chunk_tokens,
+            prefix_scope: None,
+            context_window_tokens: None,
         }
     }
 
@@ -68,6 +87,8 @@ impl ExternalLlamaCppBackend {
             family,
             timeout_ms,
             chunk_tokens: chunk_tokens.max(1),
+            prefix_scope: None,
+            context_window_tokens: None,
         }
     }
 
@@ -165,6 +186,81 @@ impl ExternalLlamaCppBackend {
         )?;
         Ok(())
     }
+
+    /// Seed a slot about to evaluate `prompt` from scratch with the template
+    /// of its shared prefix. Returns the template to capture after the step
+    /// when this prompt is the one that revealed a new shared prefix.
+    fn seed_prefix_template(
+        &self,
+        slot_id: ContextSlotId,
+        prompt: &str,
+    ) -> Option<PrefixTemplateRef> {
+        let scope = self.prefix_scope.as_ref()?;
+        let seed = shared_prefix_cache()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .plan(&scope.model, slot_id, prompt)?;
+        match seed {
+            PrefixSeed::Capture(template) => Some(template),
+            PrefixSeed::Restore(template) => {
+                let restored = self
+                    .slot_action(slot_id, "restore", json!({ "filename": template.filename }))
+                    .map_err(|err| {
+                        tracing::warn!(
+                            slot_id,
+                            prefix_hash = %template.prefix_hash,
+                            %err,
+                            "LLAMACPP: prefix template restore failed"
+                        );
+                    })
+                    .is_ok();
+                shared_prefix_cache()
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .record_restore(&scope.model, slot_id, &template, restored);
+                None
+            }
+        }
+    }
+
+    fn capture_prefix_template(
+        &self,
+        slot_id: ContextSlotId,
+        template: PrefixTemplateRef,
+        evaluated: bool,
+    ) {
+        let Some(scope) = self.prefix_scope.as_ref() else {
+            return;
+        };
+        let saved = evaluated
+            && self
+                .slot_action(slot_id, "save", json!({ "filename": template.filename }))
+                .map_err(|err| {
+                    tracing::warn!(
+                        slot_id,
+                        prefix_hash = %template.prefix_hash,
+                        %err,
+                        "LLAMACPP: prefix template capture failed"
+                    );
+                })
+                .is_ok();
+        let evicted = shared_prefix_cache()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .record_capture(&scope.model, slot_id, template, saved);
+        if let Some(filename) = evicted {
+            let _ = std::fs::remove_file(scope.slot_save_dir.join(filename));
+        }
+    }
+
+    fn forget_prefix_slot(&self, slot_id: ContextSlotId) {
+        if let Some(scope) = self.prefix_scope.as_ref() {
+            shared_prefix_cache()
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .forget_slot(&scope.model, slot_id);
+        }
+    }
 }
 
 impl InferenceBackend for ExternalLlamaCppBackend {
@@ -216,6 +312,18 @@ impl InferenceBackend for ExternalLlamaCppBackend {
                 "LLAMACPP: append-only transport unavailable, falling back to full prompt reuse"
             );
         }
+        let capture = match context_slot_id {
+            Some(slot_id)
+                if index_pos == 0
+                    && matches!(
+                        prompt_transport.strategy,
+                        PromptTransportStrategy::FullPrompt
+                    ) =>
+            {
+                self.seed_prefix_template(slot_id, prompt_transport.prompt)
+            }
+            _ => None,
+        };
         let decoded = self.post_streaming_completion(
             build_completion_request(
                 prompt_transport.prompt,
@@ -226,7 +334,11 @@ impl InferenceBackend for ExternalLlamaCppBackend {
             ),
             tokenizer,
             stream_observer,
-        )?;
+        );
+        if let (Some(slot_id), Some(template)) = (context_slot_id, capture) {
+            self.capture_prefix_template(slot_id, template, decoded.is_ok());
+        }
+        let decoded = decoded?;
 
         let finished_due_to_budget =
             !decoded.finished && decoded.appended_tokens.len() >= remaining_generation_budget;
@@ -250,6 +362,10 @@ impl InferenceBackend for ExternalLlamaCppBackend {
     fn duplicate_boxed(&self) -> Option<Box<dyn ModelBackend>> {
         Some(Box::new(self.clone()))
     }
+
+    fn runtime_context_window_tokens(&self) -> Option<usize> {
+        self.context_window_tokens
+    }
 }
 
 impl ContextSlotPersistence for ExternalLlamaCppBackend {
@@ -260,10 +376,12 @@ impl ContextSlotPersistence for ExternalLlamaCppBackend {
 
     fn load_context_slot(&mut self, slot_id: ContextSlotId, path: &Path) -> Result<()> {
         let filename = Self::slot_filename(path)?;
+        self.forget_prefix_slot(slot_id);
         self.slot_action(slot_id, "restore", json!({ "filename": filename }))
     }
 
     fn free_context_slot(&mut self, slot_id: ContextSlotId) -> Result<()> {
+        self.forget_prefix_slot(slot_id);
         self.slot_action(slot_id, "erase", json!({}))
     }
 }
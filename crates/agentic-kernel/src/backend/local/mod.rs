@@ -11,6 +11,7 @@ use crate::prompting::PromptFamily;
 use super::{BackendCapabilities, BackendClass, ContextSlotPersistence, DriverDescriptor};
 
 pub(crate) mod llamacpp;
+pub(crate) mod prefix_cache;
 pub(crate) mod remote_adapter;
 pub(crate) mod runtime_manager;
 
@@ -132,6 +133,10 @@ pub(crate) fn diagnose_external_backend() -> Result<serde_json::Value> {
         "health": health_entry,
         "props": props_entry,
         "slots": slots_entry,
+        "prompt_prefix_cache": prefix_cache::shared_prefix_cache()
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .diagnostics(),
         "summary": {
             "model_path": props_json.and_then(|value| value.get("model_path")).cloned(),
             "total_slots": props_json.and_then(|value| value.get("total_slots")).cloned(),
@@ -147,6 +152,14 @@ pub(crate) fn backend_for_target(
     Ok(ExternalLlamaCppBackend::from_runtime_lease(&lease))
 }
 
+/// Backend on the dedicated summarizer runtime instead of the family one.
+pub(crate) fn summarizer_backend_for_target(
+    target: &LocalLoadTarget,
+) -> Result<ExternalLlamaCppBackend, String> {
+    let lease = runtime_manager::ensure_summarizer_runtime_for_target(target)?;
+    Ok(ExternalLlamaCppBackend::from_runtime_lease(&lease))
+}
+
 pub(crate) fn backend_for_reference(
     reference: &str,
     family: PromptFamily,
@@ -170,6 +183,18 @@ pub(crate) fn persist_context_slot_payload_for_backend(
     }
 }
 
+pub(crate) fn context_slot_snapshot_dir(
+    backend_id: &str,
+    family: PromptFamily,
+) -> Option<std::path::PathBuf> {
+    match backend_id {
+        "external-llamacpp" => runtime_manager::ensure_runtime_for_reference("", family)
+            .ok()
+            .map(|lease| lease.slot_save_dir),
+        _ => None,
+    }
+}
+
 fn persist_external_context_slot_snapshot(
     family: PromptFamily,
     slot_id: ContextSlotId,
@@ -0,0 +1,287 @@
+//! Prompt-prefix KV reuse across processes on llama.cpp.
+//!
+//! Processes started with the same system prompt render the same long prefix
+//! and llama.cpp evaluates it again for every slot. Once two first-turn
+//! prompts of a model share at least `min_prefix_chars`, the slot that just
+//! evaluated one of them is saved as the template of that prefix; a later
+//! process whose prompt starts with it restores the template into its own
+//! slot and `cache_prompt` only evaluates the tail.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+
+use crate::config::PromptPrefixCacheConfig;
+use crate::memory::ContextSlotId;
+
+/// Template snapshot a slot is seeded from or saved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PrefixTemplateRef {
+    pub(crate) prefix_hash: String,
+    pub(crate) prefix_chars: usize,
+    /// File name inside the runtime `--slot-save-path`.
+    pub(crate) filename: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PrefixSeed {
+    /// Restore the template before evaluating the prompt.
+    Restore(PrefixTemplateRef),
+    /// Save the slot as the template once the prompt has been evaluated.
+    Capture(PrefixTemplateRef),
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct PrefixCacheStats {
+    pub(crate) hits: u64,
+    pub(crate) misses: u64,
+    pub(crate) captures: u64,
+    pub(crate) restore_failures: u64,
+    pub(crate) capture_failures: u64,
+    pub(crate) reused_prefix_chars: u64,
+}
+
+#[derive(Debug)]
+struct PrefixTemplate {
+    template: PrefixTemplateRef,
+    hits: u64,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct ModelPrefixes {
+    templates: Vec<PrefixTemplate>,
+    candidates: VecDeque<String>,
+    pending: BTreeSet<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct PromptPrefixCache {
+    config: PromptPrefixCacheConfig,
+    models: BTreeMap<String, ModelPrefixes>,
+    /// Prefix each live slot already holds, so later turns skip the restore.
+    slots: BTreeMap<(String, ContextSlotId), String>,
+    stats: PrefixCacheStats,
+    clock: u64,
+}
+
+impl PromptPrefixCache {
+    pub(crate) fn new(config: PromptPrefixCacheConfig) -> Self {
+        Self {
+            config,
+            models: BTreeMap::new(),
+            slots: BTreeMap::new(),
+            stats: PrefixCacheStats::default(),
+            clock: 0,
+        }
+    }
+
+    /// Decide how to seed `slot_id` before it evaluates `prompt` from scratch.
+    pub(crate) fn plan(
+        &mut self,
+        model: &str,
+        slot_id: ContextSlotId,
+        prompt: &str,
+    ) -> Option<PrefixSeed> {
+        if !self.config.enabled || prompt.len() < self.config.min_prefix_chars.max(1) {
+            return None;
+        }
+        let entry = self.models.entry(model.to_string()).or_default();
+        let matched = entry
+            .templates
+            .iter()
+            .filter(|known| {
+                prompt.is_char_boundary(known.template.prefix_chars)
+                    && prefix_hash(&prompt[..known.template.prefix_chars])
+                        == known.template.prefix_hash
+            })
+            .max_by_key(|known| known.template.prefix_chars);
+        if let Some(known) = matched {
+            let warm = self.slots.get(&(model.to_string(), slot_id));
+            return (warm != Some(&known.template.prefix_hash))
+                .then(|| PrefixSeed::Restore(known.template.clone()));
+        }
+
+        self.stats.misses += 1;
+        let shared = entry
+            .candidates
+            .iter()
+            .map(|candidate| shared_line_prefix(candidate, prompt))
+            .max()
+            .unwrap_or(0);
+        entry.candidates.push_front(prompt.to_string());
+        entry
+            .candidates
+            .truncate(self.config.candidate_prompts.max(1));
+        if shared < self.config.min_prefix_chars.max(1) {
+            return None;
+        }
+        let hash = prefix_hash(&prompt[..shared]);
+        if !entry.pending.insert(hash.clone()) {
+            return None;
+        }
+        Some(PrefixSeed::Capture(PrefixTemplateRef {
+            filename: template_filename(model, &hash),
+            prefix_hash: hash,
+            prefix_chars: shared,
+        }))
+    }
+
+    pub(crate) fn record_restore(
+        &mut self,
+        model: &str,
+        slot_id: ContextSlotId,
+        template: &PrefixTemplateRef,
+        restored: bool,
+    ) {
+        self.clock += 1;
+        let clock = self.clock;
+        let Some(entry) = self.models.get_mut(model) else {
+            return;
+        };
+        if !restored {
+            // The snapshot is gone (runtime restarted elsewhere, file pruned):
+            // forget it so the next shared prompt captures a fresh one.
+            self.stats.restore_failures += 1;
+            self.stats.misses += 1;
+            entry
+                .templates
+                .retain(|known| known.template.prefix_hash != template.prefix_hash);
+            self.slots.remove(&(model.to_string(), slot_id));
+            return;
+        }
+        self.stats.hits += 1;
+        self.stats.reused_prefix_chars += template.prefix_chars as u64;
+        if let Some(known) = entry
+            .templates
+            .iter_mut()
+            .find(|known| known.template.prefix_hash == template.prefix_hash)
+        {
+            known.hits += 1;
+            known.last_used = clock;
+        }
+        self.slots
+            .insert((model.to_string(), slot_id), template.prefix_hash.clone());
+    }
+
+    /// Register a captured template; returns the file of the template evicted
+    /// to stay within `max_templates_per_model`, if any.
+    pub(crate) fn record_capture(
+        &mut self,
+        model: &str,
+        slot_id: ContextSlotId,
+        template: PrefixTemplateRef,
+        saved: bool,
+    ) -> Option<String> {
+        self.clock += 1;
+        let entry = self.models.entry(model.to_string()).or_default();
+        entry.pending.remove(&template.prefix_hash);
+        if !saved {
+            self.stats.capture_failures += 1;
+            return None;
+        }
+        self.stats.captures += 1;
+        self.slots
+            .insert((model.to_string(), slot_id), template.prefix_hash.clone());
+        entry
+            .templates
+            .retain(|known| known.template.prefix_hash != template.prefix_hash);
+        entry.templates.push(PrefixTemplate {
+            template,
+            hits: 0,
+            last_used: self.clock,
+        });
+        if entry.templates.len() <= self.config.max_templates_per_model.max(1) {
+            return None;
+        }
+        let (oldest, _) = entry
+            .templates
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, known)| known.last_used)?;
+        let evicted = entry.templates.remove(oldest);
+        Some(evicted.template.filename)
+    }
+
+    /// The slot was erased or overwritten by a swap restore.
+    pub(crate) fn forget_slot(&mut self, model: &str, slot_id: ContextSlotId) {
+        self.slots.remove(&(model.to_string(), slot_id));
+    }
+
+    pub(crate) fn diagnostics(&self) -> serde_json::Value {
+        let templates = self
+            .models
+            .iter()
+            .flat_map(|(model, entry)| {
+                entry.templates.iter().map(move |known| {
+                    json!({
+                        "model": model,
+                        "prefix_hash": known.template.prefix_hash,
+                        "prefix_chars": known.template.prefix_chars,
+                        "hits": known.hits,
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "enabled": self.config.enabled,
+            "min_prefix_chars": self.config.min_prefix_chars,
+            "stats": self.stats,
+            "templates": templates,
+        })
+    }
+}
+
+pub(crate) fn shared_prefix_cache() -> &'static Mutex<PromptPrefixCache> {
+    static PREFIX_CACHE: OnceLock<Mutex<PromptPrefixCache>> = OnceLock::new();
+    PREFIX_CACHE.get_or_init(|| {
+        Mutex::new(PromptPrefixCache::new(
+            crate::config::kernel_config()
+                .external_llamacpp
+                .prefix_cache
+                .clone(),
+        ))
+    })
+}
+
+/// Longest common prefix of `left` and `right`, cut after its last newline so
+/// the template never ends in the middle of a rendered line.
+fn shared_line_prefix(left: &str, right: &str) -> usize {
+    let shared = left
+        .bytes()
+        .zip(right.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    right.as_bytes()[..shared]
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map(|index| index + 1)
+        .unwrap_or(0)
+}
+
+fn prefix_hash(prefix: &str) -> String {
+    hex_prefix(&Sha256::digest(prefix.as_bytes()), 8)
+}
+
+fn template_filename(model: &str, prefix_hash: &str) -> String {
+    format!(
+        "prefix-{}-{}.bin",
+        hex_prefix(&Sha256::digest(model.as_bytes()), 6),
+        prefix_hash
+    )
+}
+
+fn hex_prefix(digest: &[u8], bytes: usize) -> String {
+    digest
+        .iter()
+        .take(bytes)
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "tests/prefix_cache.rs"]
+mod tests;
@@ -98,7 +98,10 @@ pub(super) fn wait_until_runtime_ready(
         }
 
         match probe_runtime(&entry.endpoint, expected_model_path) {
-            Ok(()) => {
+            Ok(reported_ctx) => {
+                if reported_ctx.is_some() {
+                    entry.context_window_tokens = reported_ctx;
+                }
                 entry.state = if entry.managed_by_kernel {
                     ManagedLocalRuntimeState::Ready
                 } else {
@@ -124,7 +127,12 @@ pub(super) fn wait_until_runtime_ready(
     }
 }
 
-pub(super) fn probe_runtime(endpoint: &str, expected_model_path: &Path) -> Result<(), String> {
+/// Verifica che il runtime sia sano e serva il modello atteso; ritorna il
+/// `n_ctx` per slot riportato da `/props`, se presente.
+pub(super) fn probe_runtime(
+    endpoint: &str,
+    expected_model_path: &Path,
+) -> Result<Option<usize>, String> {
     let endpoint = HttpEndpoint::parse(endpoint).map_err(|err| err.to_string())?;
     let timeout_ms = crate::config::kernel_config().external_llamacpp.timeout_ms;
     let health = endpoint
@@ -155,5 +163,17 @@ pub(super) fn probe_runtime(endpoint: &str, expected_model_path: &Path) -> Resul
         ));
     }
 
-    Ok(())
+    Ok(props.json.as_ref().and_then(reported_context_window))
+}
+
+/// `n_ctx` effettivo del server: le build recenti lo espongono in
+/// `default_generation_settings`, quelle più vecchie al livello radice.
+pub(super) fn reported_context_window(props: &serde_json::Value) -> Option<usize> {
+    props
+        .get("default_generation_settings")
+        .and_then(|settings| settings.get("n_ctx"))
+        .or_else(|| props.get("n_ctx"))
+        .and_then(|value| value.as_u64())
+        .filter(|n_ctx| *n_ctx > 0)
+        .and_then(|n_ctx| usize::try_from(n_ctx).ok())
 }
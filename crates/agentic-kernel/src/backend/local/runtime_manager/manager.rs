@@ -10,8 +10,9 @@ use crate::prompting::PromptFamily;
 
 use super::health::{probe_runtime, wait_until_runtime_ready};
 use super::paths::{
-    current_timestamp_ms, family_key, family_label, port_for_family, same_model_path,
-    slot_save_dir_for_family,
+    current_timestamp_ms, family_key, family_label, port_for_embedding_runtime, port_for_family,
+    port_for_summarizer_runtime, same_model_path, slot_save_dir_for_key, EMBEDDING_RUNTIME_KEY,
+    SUMMARIZER_RUNTIME_KEY,
 };
 use super::spawn::{
     legacy_endpoint_override, resolve_llama_server_executable, spawn_llama_server,
@@ -45,6 +46,10 @@ impl ManagedLocalRuntimeState {
 pub(crate) struct ManagedLocalRuntimeLease {
     pub(crate) endpoint: HttpEndpoint,
     pub(crate) family: PromptFamily,
+    pub(crate) logical_model_id: String,
+    pub(crate) slot_save_dir: PathBuf,
+    /// Finestra con cui il runtime è stato avviato (`-c`) o che riporta.
+    pub(crate) context_window_tokens: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +72,36 @@ pub(super) struct RequestedLocalRuntime {
     pub(super) model_path: PathBuf,
     pub(super) logical_model_id: String,
     pub(super) context_window_tokens: Option<usize>,
+    pub(super) role: LocalRuntimeRole,
+}
+
+/// Which runtime slot a request occupies: the per-family runtime serving
+/// processes, or one of the dedicated auxiliary runtimes that must never
+/// evict it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum LocalRuntimeRole {
+    Family,
+    /// Started with `--embeddings`.
+    Embedding,
+    Summarizer,
+}
+
+impl RequestedLocalRuntime {
+    pub(super) fn runtime_key(&self) -> String {
+        match self.role {
+            LocalRuntimeRole::Family => family_key(self.family),
+            LocalRuntimeRole::Embedding => EMBEDDING_RUNTIME_KEY.to_string(),
+            LocalRuntimeRole::Summarizer => SUMMARIZER_RUNTIME_KEY.to_string(),
+        }
+    }
+
+    fn port(&self) -> u16 {
+        match self.role {
+            LocalRuntimeRole::Family => port_for_family(self.family),
+            LocalRuntimeRole::Embedding => port_for_embedding_runtime(),
+            LocalRuntimeRole::Summarizer => port_for_summarizer_runtime(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -104,6 +139,9 @@ impl ManagedLocalRuntimeEntry {
         Ok(ManagedLocalRuntimeLease {
             endpoint: HttpEndpoint::parse(&self.endpoint).map_err(|err| err.to_string())?,
             family: self.family,
+            logical_model_id: self.logical_model_id.clone(),
+            slot_save_dir: self.slot_save_dir.clone(),
+            context_window_tokens: self.context_window_tokens,
         })
     }
 }
@@ -176,10 +214,10 @@ impl LocalRuntimeManager {
             )
         })?;
 
-        let key = family_key(request.family);
-        let desired_port = port_for_family(request.family);
+        let key = request.runtime_key();
+        let desired_port = request.port();
         let endpoint = format!("http://127.0.0.1:{desired_port}");
-        let slot_save_dir = slot_save_dir_for_family(request.family);
+        let slot_save_dir = slot_save_dir_for_key(&key);
         fs::create_dir_all(&slot_save_dir).map_err(|err| {
             format!(
                 "Failed to prepare slot-save directory '{}': {}",
@@ -191,7 +229,10 @@ impl LocalRuntimeManager {
         if let Some(entry) = self.entries.get_mut(&key) {
             let same_model = same_model_path(&entry.model_path, &request.model_path);
             if same_model {
-                if probe_runtime(&entry.endpoint, &request.model_path).is_ok() {
+                if let Ok(reported_ctx) = probe_runtime(&entry.endpoint, &request.model_path) {
+                    if reported_ctx.is_some() {
+                        entry.context_window_tokens = reported_ctx;
+                    }
                     entry.state = ManagedLocalRuntimeState::Ready;
                     entry.last_error = None;
                     entry.updated_at_ms = current_timestamp_ms();
@@ -215,7 +256,7 @@ impl LocalRuntimeManager {
                     request.model_path.display()
                 ));
             }
-        } else if probe_runtime(&endpoint, &request.model_path).is_ok() {
+        } else if let Ok(reported_ctx) = probe_runtime(&endpoint, &request.model_path) {
             let entry = ManagedLocalRuntimeEntry {
                 family: request.family,
                 logical_model_id: request.logical_model_id.clone(),
@@ -224,7 +265,7 @@ impl LocalRuntimeManager {
                 port: desired_port,
                 slot_save_dir: slot_save_dir.clone(),
                 state: ManagedLocalRuntimeState::Ready,
-                context_window_tokens: request.context_window_tokens,
+                context_window_tokens: reported_ctx.or(request.context_window_tokens),
                 last_error: None,
                 managed_by_kernel: false,
                 process: None,
@@ -247,11 +288,10 @@ impl LocalRuntimeManager {
 
         let process = spawn_llama_server(
             &executable,
-            &request.model_path,
+            &request,
             desired_port,
             context_window_tokens,
             &slot_save_dir,
-            request.family,
         )?;
 
         let entry = self
@@ -299,7 +339,7 @@ impl LocalRuntimeManager {
         request: RequestedLocalRuntime,
         endpoint: &str,
     ) -> Result<ManagedLocalRuntimeLease, String> {
-        let key = family_key(request.family);
+        let key = request.runtime_key();
         let endpoint = endpoint.trim_end_matches('/').to_string();
         let port = HttpEndpoint::parse(&endpoint)
             .ok()
@@ -313,7 +353,7 @@ impl LocalRuntimeManager {
                 model_path: request.model_path,
                 endpoint,
                 port,
-                slot_save_dir: slot_save_dir_for_family(request.family),
+                slot_save_dir: slot_save_dir_for_key(&key),
                 state: ManagedLocalRuntimeState::ExternalOverride,
                 context_window_tokens: request.context_window_tokens,
                 last_error: None,
@@ -351,7 +391,10 @@ impl LocalRuntimeManager {
             return Ok(());
         }
 
-        probe_runtime(&entry.endpoint, &entry.model_path)?;
+        let reported_ctx = probe_runtime(&entry.endpoint, &entry.model_path)?;
+        if reported_ctx.is_some() {
+            entry.context_window_tokens = reported_ctx;
+        }
         entry.state = if entry.managed_by_kernel {
             ManagedLocalRuntimeState::Ready
         } else {
@@ -398,6 +441,7 @@ pub(crate) fn ensure_runtime_for_reference(
             model_path: entry.model_path.clone(),
             logical_model_id: entry.logical_model_id.clone(),
             context_window_tokens: entry.context_window_tokens,
+            role: LocalRuntimeRole::Family,
         }
     } else {
         RequestedLocalRuntime::from_reference(reference, family)?
@@ -405,6 +449,37 @@ pub(crate) fn ensure_runtime_for_reference(
     guard.ensure_for_request(request)
 }
 
+/// Lease on the dedicated embedding runtime serving `target`, started with
+/// `--embeddings` on its own port so it never evicts a family runtime.
+pub(crate) fn ensure_embedding_runtime_for_target(
+    target: &LocalLoadTarget,
+) -> Result<ManagedLocalRuntimeLease, String> {
+    let request = RequestedLocalRuntime {
+        role: LocalRuntimeRole::Embedding,
+        ..RequestedLocalRuntime::from_target(target)?
+    };
+    manager()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .ensure_for_request(request)
+}
+
+/// Lease on the dedicated runtime of the designated context summarizer, on
+/// its own port so loading it never replaces the family runtime of the
+/// processes being summarized.
+pub(crate) fn ensure_summarizer_runtime_for_target(
+    target: &LocalLoadTarget,
+) -> Result<ManagedLocalRuntimeLease, String> {
+    let request = RequestedLocalRuntime {
+        role: LocalRuntimeRole::Summarizer,
+        ..RequestedLocalRuntime::from_target(target)?
+    };
+    manager()
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .ensure_for_request(request)
+}
+
 pub(crate) fn ensure_runtime_ready_for_family(family: PromptFamily) -> Result<(), String> {
     manager()
         .lock()
@@ -776,6 +851,9 @@ mod tests {
                                         "model_path": request.model_path.display().to_string(),
                                         "total_slots": 4,
                                         "ctx_size": request.context_window_tokens,
+                                        "default_generation_settings": {
+                                            "n_ctx": request.context_window_tokens,
+                                        },
                                         "slot_save_path": request.slot_save_dir.display().to_string(),
                                         "family": family_label(request.family),
                                     })
@@ -832,6 +910,7 @@ mod tests {
         assert!(views[0].managed_by_kernel);
         assert_eq!(views[0].port, port_base);
         assert_eq!(views[0].context_window_tokens, Some(131_072));
+        assert_eq!(first.context_window_tokens, Some(131_072));
     }
 
     #[test]
@@ -855,6 +934,32 @@ mod tests {
         assert_eq!(views[0].state, "ready");
     }
 
+    #[test]
+    fn summarizer_runtime_runs_next_to_the_family_runtime() {
+        let spawn_count = Arc::new(AtomicUsize::new(0));
+        let port_base = reserve_port_base();
+        let _guard = TestManagedRuntimeProvisionGuard::set(
+            port_base,
+            mock_runtime_spawn_hook(spawn_count.clone()),
+        );
+        let process_target = test_target(PromptFamily::Qwen, "qwen-process-model", Some(32_768));
+        let summarizer_target =
+            test_target(PromptFamily::Qwen, "qwen-summarizer-model", Some(8_192));
+
+        let process = ensure_runtime_for_target(&process_target).expect("family runtime");
+        let summarizer =
+            ensure_summarizer_runtime_for_target(&summarizer_target).expect("summarizer runtime");
+        let process_again =
+            ensure_runtime_for_target(&process_target).expect("family runtime kept");
+
+        assert_eq!(spawn_count.load(Ordering::SeqCst), 2);
+        assert_eq!(summarizer.endpoint.port, port_base + 96);
+        assert_eq!(process_again.endpoint.port, process.endpoint.port);
+        assert_eq!(process_again.logical_model_id, "qwen-process-model");
+        assert_eq!(summarizer.logical_model_id, "qwen-summarizer-model");
+        assert_eq!(managed_runtime_views().len(), 2);
+    }
+
     #[test]
     fn managed_runtime_requires_max_context_metadata_to_spawn() {
         let spawn_count = Arc::new(AtomicUsize::new(0));
@@ -1,43 +1,76 @@
+use std::path::Path;
+
 use crate::model_catalog::{
-    infer_metadata_path, load_model_metadata, LocalLoadTarget, ModelMetadata,
+    load_local_model_metadata, ContextWindowResolution, LocalLoadTarget, ModelMetadata,
+    SOURCE_MEMORY_BUDGET,
 };
 use crate::prompting::PromptFamily;
+use crate::resource_governor::estimate_context_window_cap;
 
-use super::manager::RequestedLocalRuntime;
+use super::manager::{LocalRuntimeRole, RequestedLocalRuntime};
 use super::paths::normalize_model_path;
 
 impl RequestedLocalRuntime {
     pub(super) fn from_target(target: &LocalLoadTarget) -> Result<Self, String> {
         let model_path = normalize_model_path(&target.display_path);
+        let logical_model_id = target
+            .model_id
+            .clone()
+            .unwrap_or_else(|| target.display_path.display().to_string());
         Ok(Self {
             family: target.family,
+            context_window_tokens: launch_context_window(
+                &model_path,
+                &logical_model_id,
+                target.metadata.as_ref(),
+            ),
             model_path,
-            logical_model_id: target
-                .model_id
-                .clone()
-                .unwrap_or_else(|| target.display_path.display().to_string()),
-            context_window_tokens: target
-                .metadata
-                .as_ref()
-                .and_then(|metadata| metadata.max_context_tokens),
+            logical_model_id,
+            role: LocalRuntimeRole::Family,
         })
     }
 
     pub(super) fn from_reference(reference: &str, family: PromptFamily) -> Result<Self, String> {
         let fallback_path = std::path::PathBuf::from(reference);
         let model_path = normalize_model_path(&fallback_path);
-        let metadata = load_local_runtime_metadata(&model_path);
+        let metadata = load_local_model_metadata(&model_path);
         Ok(Self {
             family,
             model_path: model_path.clone(),
             logical_model_id: reference.to_string(),
-            context_window_tokens: metadata.and_then(|entry| entry.max_context_tokens),
+            context_window_tokens: launch_context_window(&model_path, reference, metadata.as_ref()),
+            role: LocalRuntimeRole::Family,
         })
     }
 }
 
-pub(super) fn load_local_runtime_metadata(model_path: &std::path::Path) -> Option<ModelMetadata> {
-    infer_metadata_path(model_path)
-        .as_ref()
-        .and_then(|path| load_model_metadata(path))
+/// `-c` per llama-server: il massimo dichiarato dal modello, ristretto a
+/// quanto la KV cache può occupare nei budget del resource governor.
+fn launch_context_window(
+    model_path: &Path,
+    logical_model_id: &str,
+    metadata: Option<&ModelMetadata>,
+) -> Option<usize> {
+    let declared = ContextWindowResolution::declared_window(metadata, None)?;
+    let memory_cap = estimate_context_window_cap(
+        model_path,
+        logical_model_id,
+        metadata.and_then(|metadata| metadata.kv_cache_bytes_per_token),
+        &crate::config::kernel_config().resources,
+    );
+    let resolution = ContextWindowResolution::resolve(
+        Some(declared),
+        None,
+        memory_cap,
+        crate::config::kernel_config().context.default_window_tokens,
+    );
+    if resolution.source == SOURCE_MEMORY_BUDGET {
+        tracing::info!(
+            logical_model_id,
+            model_max_tokens = ?resolution.model_max_tokens,
+            window_tokens = resolution.window_tokens,
+            "LOCAL_RUNTIME: context window clamped by memory budget"
+        );
+    }
+    Some(resolution.window_tokens)
 }
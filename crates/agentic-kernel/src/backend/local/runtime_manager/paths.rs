@@ -11,31 +11,32 @@ pub(super) fn same_model_path(left: &Path, right: &Path) -> bool {
     normalize_model_path(left) == normalize_model_path(right)
 }
 
-pub(super) fn slot_save_dir_for_family(family: PromptFamily) -> PathBuf {
+/// Key of the dedicated embedding runtime, next to the per-family ones.
+pub(super) const EMBEDDING_RUNTIME_KEY: &str = "embedding";
+
+/// Key of the dedicated runtime of the designated context summarizer.
+pub(super) const SUMMARIZER_RUNTIME_KEY: &str = "summarizer";
+
+pub(super) fn slot_save_dir_for_key(key: &str) -> PathBuf {
     crate::config::kernel_config()
         .paths
         .workspace_dir
         .join("local-runtimes")
         .join("slots")
-        .join(family_key(family))
+        .join(key)
 }
 
-pub(super) fn log_path_for_family(family: PromptFamily) -> PathBuf {
+pub(super) fn log_path_for_key(key: &str) -> PathBuf {
     crate::config::kernel_config()
         .paths
         .workspace_dir
         .join("local-runtimes")
         .join("logs")
-        .join(format!("{}.log", family_key(family)))
+        .join(format!("{key}.log"))
 }
 
 pub(super) fn port_for_family(family: PromptFamily) -> u16 {
-    #[cfg(test)]
-    let base = super::manager::test_port_base_override_get()
-        .unwrap_or(crate::config::kernel_config().external_llamacpp.port_base);
-    #[cfg(not(test))]
-    let base = crate::config::kernel_config().external_llamacpp.port_base;
-    base.saturating_add(match family {
+    port_base().saturating_add(match family {
         PromptFamily::Qwen => 0,
         PromptFamily::Llama => 1,
         PromptFamily::Mistral => 2,
@@ -43,6 +44,23 @@ pub(super) fn port_for_family(family: PromptFamily) -> u16 {
     })
 }
 
+pub(super) fn port_for_embedding_runtime() -> u16 {
+    port_base().saturating_add(95)
+}
+
+pub(super) fn port_for_summarizer_runtime() -> u16 {
+    port_base().saturating_add(96)
+}
+
+fn port_base() -> u16 {
+    #[cfg(test)]
+    let base = super::manager::test_port_base_override_get()
+        .unwrap_or(crate::config::kernel_config().external_llamacpp.port_base);
+    #[cfg(not(test))]
+    let base = crate::config::kernel_config().external_llamacpp.port_base;
+    base
+}
+
 pub(super) fn family_key(family: PromptFamily) -> String {
     family_label(family).to_ascii_lowercase()
 }
@@ -2,21 +2,22 @@ use std::fs::{self, OpenOptions};
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
-use crate::prompting::PromptFamily;
-
 #[cfg(test)]
 use super::manager::TestSpawnRequest;
-use super::manager::{ManagedLocalRuntimeEntry, ManagedRuntimeProcess};
-use super::paths::{current_timestamp_ms, family_label, log_path_for_family};
+use super::manager::{
+    LocalRuntimeRole, ManagedLocalRuntimeEntry, ManagedRuntimeProcess, RequestedLocalRuntime,
+};
+use super::paths::{current_timestamp_ms, family_label, log_path_for_key};
 
 pub(super) fn spawn_llama_server(
     executable: &Path,
-    model_path: &Path,
+    request: &RequestedLocalRuntime,
     port: u16,
     context_window_tokens: usize,
     slot_save_dir: &Path,
-    family: PromptFamily,
 ) -> Result<ManagedRuntimeProcess, String> {
+    let model_path = request.model_path.as_path();
+    let family = request.family;
     #[cfg(test)]
     if let Some(spawn_hook) = super::manager::test_spawn_hook_get() {
         return spawn_hook(TestSpawnRequest {
@@ -28,7 +29,7 @@ pub(super) fn spawn_llama_server(
         });
     }
 
-    let log_path = log_path_for_family(family);
+    let log_path = log_path_for_key(&request.runtime_key());
     if let Some(parent) = log_path.parent() {
         fs::create_dir_all(parent).map_err(|err| {
             format!(
@@ -57,7 +58,11 @@ pub(super) fn spawn_llama_server(
         )
     })?;
 
-    let child = Command::new(executable)
+    let mut command = Command::new(executable);
+    if request.role == LocalRuntimeRole::Embedding {
+        command.arg("--embeddings");
+    }
+    let child = command
         .arg("-m")
         .arg(model_path)
         .arg("--port")
@@ -0,0 +1,101 @@
+use super::{PrefixSeed, PromptPrefixCache};
+use crate::config::PromptPrefixCacheConfig;
+
+fn test_cache() -> PromptPrefixCache {
+    PromptPrefixCache::new(PromptPrefixCacheConfig {
+        min_prefix_chars: 64,
+        max_templates_per_model: 1,
+        ..PromptPrefixCacheConfig::default()
+    })
+}
+
+fn system_prompt(tools: &str) -> String {
+    format!(
+        "<|system|>\nYou are an agent running inside AgenticOS.\nAvailable tools: {tools}\nAnswer with TOOL:<name> <json> when a tool is needed.\n"
+    )
+}
+
+fn prompt(system: &str, user: &str) -> String {
+    format!("{system}<|user|>\n{user}\n<|assistant|>\n")
+}
+
+#[test]
+fn second_prompt_with_shared_prefix_captures_and_later_ones_restore() {
+    let mut cache = test_cache();
+    let system = system_prompt("read_file, list_dir, search_text, kb_search");
+
+    assert_eq!(
+        cache.plan("qwen", 0, &prompt(&system, "list the repo")),
+        None
+    );
+    let Some(PrefixSeed::Capture(template)) =
+        cache.plan("qwen", 1, &prompt(&system, "summarize README"))
+    else {
+        panic!("a shared system prompt should be captured as a template");
+    };
+    assert_eq!(template.prefix_chars, system.len() + "<|user|>\n".len());
+    assert!(template.filename.starts_with("prefix-"));
+    // Concurrent processes do not save the same prefix twice.
+    assert_eq!(cache.plan("qwen", 2, &prompt(&system, "count files")), None);
+    assert_eq!(
+        cache.record_capture("qwen", 1, template.clone(), true),
+        None
+    );
+
+    let third = prompt(&system, "explain main.rs");
+    assert_eq!(
+        cache.plan("qwen", 3, &third),
+        Some(PrefixSeed::Restore(template.clone()))
+    );
+    cache.record_restore("qwen", 3, &template, true);
+    // The slot already holds the prefix; a re-render after compaction skips it.
+    assert_eq!(cache.plan("qwen", 3, &third), None);
+    cache.forget_slot("qwen", 3);
+    assert!(matches!(
+        cache.plan("qwen", 3, &third),
+        Some(PrefixSeed::Restore(_))
+    ));
+
+    // Templates are per model.
+    assert_eq!(cache.plan("llama", 4, &third), None);
+
+    assert_eq!(cache.stats.hits, 1);
+    assert_eq!(cache.stats.captures, 1);
+    assert_eq!(cache.stats.misses, 4);
+    let report = cache.diagnostics();
+    assert_eq!(report["stats"]["hits"], 1);
+    assert_eq!(report["templates"][0]["model"], "qwen");
+    assert_eq!(report["templates"][0]["hits"], 1);
+}
+
+#[test]
+fn failed_restores_drop_the_template_and_new_prefixes_evict_the_oldest() {
+    let mut cache = test_cache();
+    let first = system_prompt("read_file, list_dir");
+    cache.plan("qwen", 0, &prompt(&first, "a"));
+    let Some(PrefixSeed::Capture(old)) = cache.plan("qwen", 1, &prompt(&first, "b")) else {
+        panic!("first prefix should be captured");
+    };
+    cache.record_capture("qwen", 1, old.clone(), true);
+
+    let second = system_prompt("write_file, run_command, python");
+    cache.plan("qwen", 2, &prompt(&second, "c"));
+    let Some(PrefixSeed::Capture(new)) = cache.plan("qwen", 3, &prompt(&second, "d")) else {
+        panic!("second prefix should be captured");
+    };
+    assert_eq!(
+        cache.record_capture("qwen", 3, new.clone(), true),
+        Some(old.filename)
+    );
+
+    cache.record_restore("qwen", 4, &new, false);
+    assert_eq!(cache.stats.restore_failures, 1);
+    assert_eq!(cache.diagnostics()["templates"], serde_json::json!([]));
+
+    let short = PromptPrefixCache::new(PromptPrefixCacheConfig {
+        enabled: false,
+        ..PromptPrefixCacheConfig::default()
+    })
+    .plan("qwen", 0, &prompt(&second, "e"));
+    assert_eq!(short, None);
+}
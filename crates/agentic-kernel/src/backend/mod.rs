@@ -1,7 +1,7 @@
 use agentic_control_models::{BackendCapabilitiesView, BackendTelemetryView};
 use anyhow::{Error as E, Result};
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokenizers::Tokenizer;
 
 use crate::memory::{ContextSlotId, SlotPersistenceKind};
@@ -10,6 +10,7 @@ use crate::prompting::{GenerationConfig, PromptFamily};
 use crate::services::accounting::BackendAccountingEvent;
 
 pub(crate) mod common;
+pub(crate) mod embeddings;
 mod local;
 mod remote;
 
@@ -260,6 +261,12 @@ pub(crate) fn persist_context_slot_payload_for_backend(
     local::persist_context_slot_payload_for_backend(backend_id, family, slot_id, final_path)
 }
 
+/// Directory where the backend runtime writes slot snapshots, when it is a
+/// local one the kernel can read from.
+pub(crate) fn context_slot_snapshot_dir(backend_id: &str, family: PromptFamily) -> Option<PathBuf> {
+    local::context_slot_snapshot_dir(backend_id, family)
+}
+
 #[cfg_attr(not(test), allow(dead_code))]
 pub fn resolve_driver_for_family(
     family: PromptFamily,
@@ -413,6 +420,10 @@ pub trait InferenceBackend: Send {
     fn runtime_capabilities(&self) -> Option<BackendCapabilities> {
         None
     }
+    /// Context window the underlying runtime runs with (e.g. llama.cpp `-c`).
+    fn runtime_context_window_tokens(&self) -> Option<usize> {
+        None
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -525,6 +536,28 @@ impl RuntimeModel {
         Ok(Self { inner: backend })
     }
 
+    /// Like [`Self::load_target`], but a local model is served by the
+    /// dedicated summarizer runtime so it never replaces the family runtime
+    /// of live processes.
+    pub(crate) fn load_summarizer_target(target: &ResolvedModelTarget) -> Result<Self> {
+        let ResolvedModelTarget::Local(local) = target else {
+            return Self::load_target(target);
+        };
+        let descriptor = resolve_loadable_driver_descriptor(
+            &target.driver_resolution().resolved_backend_id,
+            target.family(),
+        )?;
+        if descriptor.id != "external-llamacpp" {
+            return Err(E::msg(format!(
+                "Backend '{}' is registered but has no typed local loader implementation.",
+                descriptor.id
+            )));
+        }
+        Ok(Self {
+            inner: Box::new(local::summarizer_backend_for_target(local).map_err(E::msg)?),
+        })
+    }
+
     pub fn load_from_reference(
         reference: &str,
         family: PromptFamily,
@@ -572,6 +605,10 @@ impl RuntimeModel {
         })
     }
 
+    pub fn runtime_context_window_tokens(&self) -> Option<usize> {
+        self.inner.runtime_context_window_tokens()
+    }
+
     pub fn backend_telemetry(&self) -> Option<BackendTelemetryView> {
         runtime_backend_telemetry(self.backend_id())
     }
@@ -1,3 +1,6 @@
+use super::embeddings::{
+    embedding_provider_from_config, parse_llamacpp_embeddings, parse_openai_embeddings,
+};
 use super::{
     combine_completion_reasoning, combine_completion_text, completion_is_finished,
     diagnose_external_backend, persist_context_slot_payload_for_backend, resolve_driver_for_family,
@@ -6,7 +9,7 @@ use super::{
     InferenceStepResult, PromptFamily, RuntimeModel, TestExternalEndpointOverrideGuard,
     TestRemoteOpenAIConfigOverrideGuard, TestRuntimeDriverAvailabilityGuard,
 };
-use crate::config::{RemoteAdapterKind, RemoteProviderRuntimeConfig};
+use crate::config::{EmbeddingsConfig, RemoteAdapterKind, RemoteProviderRuntimeConfig};
 use crate::memory::{ContextSlotId, SlotPersistenceKind};
 use crate::model_catalog::{RemoteModelEntry, ResolvedModelTarget};
 use crate::prompting::GenerationConfig;
@@ -91,6 +94,9 @@ fn spawn_mock_llamacpp_server(
 
             let body = match path.as_str() {
                 "/completion" => r#"{"content":"hello","tokens":[1]}"#,
+                "/embedding" => {
+                    r#"[{"index":1,"embedding":[[0.0,2.0]]},{"index":0,"embedding":[[1.0,0.0]]}]"#
+                }
                 "/slots/7?action=save" | "/slots/7?action=restore" | "/slots/7?action=erase" => {
                     r#"{"ok":true}"#
                 }
@@ -1169,6 +1175,7 @@ fn external_backend_diagnostic_reports_health_props_and_slots() {
     assert_eq!(report["health"]["status_code"].as_u64(), Some(200));
     assert_eq!(report["props"]["json"]["total_slots"].as_u64(), Some(4));
     assert_eq!(report["summary"]["visible_slots"].as_u64(), Some(2));
+    assert!(report["prompt_prefix_cache"]["stats"]["hits"].is_u64());
 
     server_handle.join().expect("join mock diag server");
 
@@ -1177,3 +1184,70 @@ fn external_backend_diagnostic_reports_health_props_and_slots() {
         &["/health", "/props", "/slots"]
     );
 }
+
+#[test]
+fn llamacpp_embeddings_are_returned_in_input_order() {
+    let (endpoint, paths, bodies, server_handle) = spawn_mock_llamacpp_server(1);
+    let mut provider = embedding_provider_from_config(&EmbeddingsConfig {
+        provider: "llamacpp".to_string(),
+        endpoint: endpoint.clone(),
+        ..EmbeddingsConfig::default()
+    })
+    .expect("llamacpp provider");
+
+    let vectors = provider
+        .embed(&["scheduler".to_string(), "pianificatore".to_string()])
+        .expect("embed through /embedding");
+    server_handle.join().expect("join mock server");
+
+    assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.0, 2.0]]);
+    assert_eq!(provider.id(), format!("llamacpp:{endpoint}"));
+    assert_eq!(
+        paths.lock().expect("lock paths").as_slice(),
+        &["/embedding"]
+    );
+    assert!(bodies.lock().expect("lock bodies")[0]
+        .contains(r#""content":["scheduler","pianificatore"]"#));
+}
+
+#[test]
+fn openai_embeddings_parse_data_by_index_and_reject_garbage() {
+    let vectors = parse_openai_embeddings(&serde_json::json!({
+        "object": "list",
+        "data": [
+            {"object": "embedding", "index": 1, "embedding": [0.5, 0.5]},
+            {"object": "embedding", "index": 0, "embedding": [1.0, 0.0]}
+        ]
+    }))
+    .expect("parse embeddings");
+    assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.5, 0.5]]);
+
+    assert!(parse_openai_embeddings(&serde_json::json!({"error": "nope"})).is_err());
+    assert!(parse_openai_embeddings(&serde_json::json!({
+        "data": [{"index": 0, "embedding": ["x"]}]
+    }))
+    .is_err());
+    assert!(embedding_provider_from_config(&EmbeddingsConfig::default()).is_none());
+}
+
+#[test]
+fn llamacpp_embeddings_mean_pool_per_token_rows() {
+    let vectors = parse_llamacpp_embeddings(&serde_json::json!([
+        {"index": 1, "embedding": [[1.0, 3.0], [3.0, 5.0]]},
+        {"index": 0, "embedding": [[0.5, 0.25]]}
+    ]))
+    .expect("parse embeddings");
+    assert_eq!(vectors, vec![vec![0.5, 0.25], vec![2.0, 4.0]]);
+
+    let legacy = parse_llamacpp_embeddings(&serde_json::json!({"embedding": [1.0, 2.0]}))
+        .expect("parse single-object form");
+    assert_eq!(legacy, vec![vec![1.0, 2.0]]);
+
+    assert!(parse_llamacpp_embeddings(&serde_json::json!([
+        {"index": 0, "embedding": [[1.0, 2.0], [1.0]]}
+    ]))
+    .is_err());
+    assert!(
+        parse_llamacpp_embeddings(&serde_json::json!([{"index": 0, "embedding": []}])).is_err()
+    );
+}
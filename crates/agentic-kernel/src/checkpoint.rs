@@ -2,16 +2,29 @@
 //!
 //! Serialises the *metadata* portion of kernel state (scheduler, process list,
 //! config, metrics) to a JSON file.  Model weights and tensor data are NOT
-//! included — processes are marked `Orphaned` on restore and require a fresh
-//! `LOAD` + `EXEC` cycle.
+//! included: on restore each process bound to a session is rehydrated on its
+//! runtime (see `services::checkpoint_runtime`), rebuilding the prompt from
+//! its persisted turns or reloading its parked KV slot from swap.  Processes
+//! that cannot be rehydrated keep the old metadata-only `Orphaned` view.
+//!
+//! The same snapshot types back the SQLite journal
+//! (`services::checkpoint_journal`): a generation stores a full
+//! `KernelSnapshot`, its deltas one `ProcessSnapshot` (plus scheduler entry)
+//! each, and `materialize_generation` replays them in order.
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::process::{ContextPolicy, ContextState, HumanInputRequest};
+use crate::process::{
+    AgentProcess, ContextPolicy, ContextState, HumanInputRequest, ProcessLifecyclePolicy,
+    ResidentSlotState,
+};
+use crate::storage::{LoadedCheckpointGeneration, StoredCheckpointDelta};
 use crate::tools::invocation::{
     PathGrantAccessMode, ProcessPathGrant, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
 };
@@ -73,6 +86,21 @@ pub struct ProcessSnapshot {
     pub context_state: ContextState,
     #[serde(default)]
     pub pending_human_request: Option<HumanInputRequest>,
+    /// Session and runtime the process was bound to; only processes that
+    /// carry both can be rehydrated.
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub runtime_id: Option<String>,
+    #[serde(default = "default_lifecycle_policy")]
+    pub lifecycle_policy: ProcessLifecyclePolicy,
+    /// Swap snapshot of the resident slot, set while the process is parked.
+    #[serde(default)]
+    pub resident_slot_snapshot_path: Option<PathBuf>,
+}
+
+fn default_lifecycle_policy() -> ProcessLifecyclePolicy {
+    ProcessLifecyclePolicy::Interactive
 }
 
 fn default_context_policy() -> ContextPolicy {
@@ -212,22 +240,28 @@ pub fn snapshot_scheduler(
     let entries = scheduler
         .registered_pids()
         .into_iter()
-        .filter_map(|pid| {
-            scheduler.snapshot(pid).map(|s| SchedulerEntrySnapshot {
-                pid,
-                priority: s.priority.to_string(),
-                workload: format!("{:?}", s.workload),
-                max_tokens: s.quota.max_tokens,
-                max_syscalls: s.quota.max_syscalls,
-                tokens_generated: s.tokens_generated,
-                syscalls_used: s.syscalls_used,
-                elapsed_secs: s.elapsed_secs,
-            })
-        })
+        .filter_map(|pid| snapshot_scheduler_entry(scheduler, pid))
         .collect();
     SchedulerStateSnapshot { entries }
 }
 
+/// Scheduler metadata of a single PID.
+pub fn snapshot_scheduler_entry(
+    scheduler: &crate::scheduler::ProcessScheduler,
+    pid: u64,
+) -> Option<SchedulerEntrySnapshot> {
+    scheduler.snapshot(pid).map(|s| SchedulerEntrySnapshot {
+        pid,
+        priority: s.priority.to_string(),
+        workload: format!("{:?}", s.workload),
+        max_tokens: s.quota.max_tokens,
+        max_syscalls: s.quota.max_syscalls,
+        tokens_generated: s.tokens_generated,
+        syscalls_used: s.syscalls_used,
+        elapsed_secs: s.elapsed_secs,
+    })
+}
+
 /// Build a `MemoryCountersSnapshot` from the live `NeuralMemory`.
 pub fn snapshot_memory(memory: &crate::memory::NeuralMemory) -> MemoryCountersSnapshot {
     let s = memory.snapshot();
@@ -245,10 +279,202 @@ pub fn snapshot_memory(memory: &crate::memory::NeuralMemory) -> MemoryCountersSn
     }
 }
 
+fn restored_process_snapshot(
+    pid: u64,
+    metadata: &crate::scheduler::RestoredProcessMetadata,
+) -> ProcessSnapshot {
+    ProcessSnapshot {
+        pid,
+        owner_id: metadata.owner_id,
+        tool_caller: metadata.tool_caller.clone(),
+        permission_policy: metadata.permission_policy.clone(),
+        state: metadata.state.clone(),
+        token_count: metadata.token_count,
+        max_tokens: metadata.max_tokens,
+        context_policy: metadata.context_policy.clone(),
+        context_state: metadata.context_state.clone(),
+        pending_human_request: metadata.pending_human_request.clone(),
+        session_id: None,
+        runtime_id: None,
+        lifecycle_policy: default_lifecycle_policy(),
+        resident_slot_snapshot_path: metadata
+            .resident_slot_snapshot_path
+            .as_ref()
+            .map(PathBuf::from),
+    }
+}
+
+fn live_process_snapshot(
+    pid: u64,
+    process: &AgentProcess,
+    session_registry: &crate::session::SessionRegistry,
+) -> ProcessSnapshot {
+    ProcessSnapshot {
+        pid,
+        owner_id: process.owner_id,
+        tool_caller: process.tool_caller.clone(),
+        permission_policy: process.permission_policy.clone(),
+        state: format!("{:?}", process.state),
+        token_count: process.tokens.len(),
+        max_tokens: process.max_tokens,
+        context_policy: process.context_policy.clone(),
+        context_state: process.context_state.clone(),
+        pending_human_request: process.pending_human_request.clone(),
+        session_id: session_registry.session_id_for_pid(pid).map(str::to_string),
+        runtime_id: session_registry.runtime_id_for_pid(pid).map(str::to_string),
+        lifecycle_policy: process.lifecycle_policy,
+        resident_slot_snapshot_path: (process.resident_slot_state
+            == ResidentSlotState::SnapshotSaved)
+            .then(|| process.resident_slot_snapshot_path().map(Path::to_path_buf))
+            .flatten(),
+    }
+}
+
+/// Snapshot of one checkpointed PID: the live process when the engine has
+/// it, otherwise the metadata a previous restore left behind.
+pub fn snapshot_process(
+    engine_state: Option<&crate::engine::LLMEngine>,
+    scheduler: &crate::scheduler::ProcessScheduler,
+    session_registry: &crate::session::SessionRegistry,
+    pid: u64,
+) -> Option<ProcessSnapshot> {
+    engine_state
+        .and_then(|engine| engine.processes.get(&pid))
+        .map(|process| live_process_snapshot(pid, process, session_registry))
+        .or_else(|| {
+            scheduler
+                .restored_process(pid)
+                .map(|metadata| restored_process_snapshot(pid, metadata))
+        })
+}
+
+/// Cheap fingerprint of every PID a checkpoint would carry.
+///
+/// It covers what a journal delta has to follow (state, context turns and
+/// compactions, pending human request, parked slot, session binding,
+/// scheduling) and leaves out counters that move on every token.
+pub fn process_fingerprints(
+    engine_state: Option<&crate::engine::LLMEngine>,
+    scheduler: &crate::scheduler::ProcessScheduler,
+    session_registry: &crate::session::SessionRegistry,
+) -> BTreeMap<u64, u64> {
+    let mut fingerprints = BTreeMap::new();
+    if let Some(engine) = engine_state {
+        for (pid, process) in &engine.processes {
+            let mut hasher = DefaultHasher::new();
+            format!("{:?}", process.state).hash(&mut hasher);
+            process.context_state.segments.len().hash(&mut hasher);
+            process
+                .context_state
+                .episodic_segments
+                .len()
+                .hash(&mut hasher);
+            process.context_state.context_compressions.hash(&mut hasher);
+            // Operator edits to the context (SET_CONTEXT_POLICY, PIN/DROP_SEGMENT).
+            process.context_state.policy_updates.hash(&mut hasher);
+            process.context_state.dropped_segments.hash(&mut hasher);
+            for (index, segment) in process.context_state.segments.iter().enumerate() {
+                if segment.pinned {
+                    index.hash(&mut hasher);
+                }
+            }
+            process
+                .pending_human_request
+                .as_ref()
+                .map(|request| request.request_id.as_str())
+                .hash(&mut hasher);
+            format!("{:?}", process.resident_slot_state).hash(&mut hasher);
+            session_registry.session_id_for_pid(*pid).hash(&mut hasher);
+            session_registry.runtime_id_for_pid(*pid).hash(&mut hasher);
+            hash_scheduling(scheduler, *pid, &mut hasher);
+            fingerprints.insert(*pid, hasher.finish());
+        }
+    }
+    for pid in scheduler.restored_pids() {
+        if fingerprints.contains_key(&pid) {
+            continue;
+        }
+        if let Some(metadata) = scheduler.restored_process(pid) {
+            let mut hasher = DefaultHasher::new();
+            metadata.state.hash(&mut hasher);
+            metadata.context_state.segments.len().hash(&mut hasher);
+            hash_scheduling(scheduler, pid, &mut hasher);
+            fingerprints.insert(pid, hasher.finish());
+        }
+    }
+    fingerprints
+}
+
+fn hash_scheduling(
+    scheduler: &crate::scheduler::ProcessScheduler,
+    pid: u64,
+    hasher: &mut DefaultHasher,
+) {
+    if let Some(entry) = scheduler.snapshot(pid) {
+        entry.priority.to_string().hash(hasher);
+        format!("{:?}", entry.workload).hash(hasher);
+        entry.quota.max_tokens.hash(hasher);
+        entry.quota.max_syscalls.hash(hasher);
+    }
+}
+
+/// Rebuild the snapshot a SQLite generation stands for: its base snapshot
+/// with every journaled delta applied in order.
+pub fn materialize_generation(
+    loaded: &LoadedCheckpointGeneration,
+) -> Result<KernelSnapshot, String> {
+    let mut snapshot: KernelSnapshot =
+        serde_json::from_str(&loaded.snapshot_json).map_err(|e| {
+            format!(
+                "corrupt checkpoint generation {}: {}",
+                loaded.summary.generation, e
+            )
+        })?;
+    for delta in &loaded.deltas {
+        apply_checkpoint_delta(&mut snapshot, delta)?;
+    }
+    Ok(snapshot)
+}
+
+pub fn apply_checkpoint_delta(
+    snapshot: &mut KernelSnapshot,
+    delta: &StoredCheckpointDelta,
+) -> Result<(), String> {
+    snapshot
+        .processes
+        .retain(|process| process.pid != delta.pid);
+    snapshot
+        .scheduler
+        .entries
+        .retain(|entry| entry.pid != delta.pid);
+    match delta.delta_kind.as_str() {
+        "remove" => Ok(()),
+        "upsert" => {
+            let process_json = delta.process_json.as_deref().ok_or_else(|| {
+                format!("checkpoint delta {} has no process payload", delta.delta_id)
+            })?;
+            let process: ProcessSnapshot = serde_json::from_str(process_json)
+                .map_err(|e| format!("corrupt checkpoint delta {}: {}", delta.delta_id, e))?;
+            snapshot.processes.push(process);
+            if let Some(scheduler_json) = delta.scheduler_json.as_deref() {
+                let entry: SchedulerEntrySnapshot = serde_json::from_str(scheduler_json)
+                    .map_err(|e| format!("corrupt checkpoint delta {}: {}", delta.delta_id, e))?;
+                snapshot.scheduler.entries.push(entry);
+            }
+            Ok(())
+        }
+        other => Err(format!(
+            "checkpoint delta {} has unknown kind '{}'",
+            delta.delta_id, other
+        )),
+    }
+}
+
 pub fn build_kernel_snapshot(
     engine_state: Option<&crate::engine::LLMEngine>,
     model_catalog: &crate::model_catalog::ModelCatalog,
     scheduler: &crate::scheduler::ProcessScheduler,
+    session_registry: &crate::session::SessionRegistry,
     metrics: &crate::commands::MetricsState,
     memory: &crate::memory::NeuralMemory,
 ) -> KernelSnapshot {
@@ -261,18 +487,7 @@ pub fn build_kernel_snapshot(
             .iter()
             .map(|(pid, process)| {
                 live_pids.insert(*pid);
-                ProcessSnapshot {
-                    pid: *pid,
-                    owner_id: process.owner_id,
-                    tool_caller: process.tool_caller.clone(),
-                    permission_policy: process.permission_policy.clone(),
-                    state: format!("{:?}", process.state),
-                    token_count: process.tokens.len(),
-                    max_tokens: process.max_tokens,
-                    context_policy: process.context_policy.clone(),
-                    context_state: process.context_state.clone(),
-                    pending_human_request: process.pending_human_request.clone(),
-                }
+                live_process_snapshot(*pid, process, session_registry)
             })
             .collect();
         processes.extend(
@@ -283,18 +498,7 @@ pub fn build_kernel_snapshot(
                 .filter_map(|pid| {
                     scheduler
                         .restored_process(pid)
-                        .map(|metadata| ProcessSnapshot {
-                            pid,
-                            owner_id: metadata.owner_id,
-                            tool_caller: metadata.tool_caller.clone(),
-                            permission_policy: metadata.permission_policy.clone(),
-                            state: metadata.state.clone(),
-                            token_count: metadata.token_count,
-                            max_tokens: metadata.max_tokens,
-                            context_policy: metadata.context_policy.clone(),
-                            context_state: metadata.context_state.clone(),
-                            pending_human_request: metadata.pending_human_request.clone(),
-                        })
+                        .map(|metadata| restored_process_snapshot(pid, metadata))
                 }),
         );
         let cfg = engine.generation_config();
@@ -313,18 +517,7 @@ pub fn build_kernel_snapshot(
                 .filter_map(|pid| {
                     scheduler
                         .restored_process(pid)
-                        .map(|metadata| ProcessSnapshot {
-                            pid,
-                            owner_id: metadata.owner_id,
-                            tool_caller: metadata.tool_caller.clone(),
-                            permission_policy: metadata.permission_policy.clone(),
-                            state: metadata.state.clone(),
-                            token_count: metadata.token_count,
-                            max_tokens: metadata.max_tokens,
-                            context_policy: metadata.context_policy.clone(),
-                            context_state: metadata.context_state.clone(),
-                            pending_human_request: metadata.pending_human_request.clone(),
-                        })
+                        .map(|metadata| restored_process_snapshot(pid, metadata))
                 })
                 .collect(),
             None,
@@ -1,15 +1,88 @@
 use crate::checkpoint;
+use crate::config::kernel_config;
 use crate::diagnostics::audit::{self, AuditContext};
 use crate::protocol;
 use crate::scheduler::{ProcessPriority, ProcessQuota, RestoredProcessMetadata};
+use crate::services::checkpoint_journal::write_checkpoint_generation;
+use crate::services::checkpoint_runtime::rehydrate_checkpoint_process;
+use crate::storage::{StorageService, StoredCheckpointGeneration};
 use agentic_control_models::KernelEvent;
 use agentic_protocol::ControlErrorCode;
 
+use serde::Deserialize;
 use serde_json::json;
+use std::path::PathBuf;
 
 use super::context::CheckpointCommandContext;
 use super::diagnostics::log_event;
 
+/// Where a `RESTORE` reads its snapshot from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RestoreSource {
+    /// A SQLite generation; `None` is the newest one.
+    Generation(Option<i64>),
+    File(PathBuf),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RestoreGenerationRequest {
+    generation: i64,
+}
+
+/// Empty payload: the newest generation when the journal is on, the default
+/// JSON file otherwise. `{"generation": N}` picks a retained generation;
+/// anything else is a checkpoint file path.
+fn parse_restore_source(
+    payload_text: &str,
+    journal_enabled: bool,
+) -> Result<RestoreSource, String> {
+    if payload_text.is_empty() {
+        return Ok(if journal_enabled {
+            RestoreSource::Generation(None)
+        } else {
+            RestoreSource::File(checkpoint::default_checkpoint_path())
+        });
+    }
+    if payload_text.starts_with('{') {
+        return serde_json::from_str::<RestoreGenerationRequest>(payload_text)
+            .map(|request| RestoreSource::Generation(Some(request.generation)))
+            .map_err(|err| format!("invalid RESTORE payload: {}", err));
+    }
+    Ok(RestoreSource::File(PathBuf::from(payload_text)))
+}
+
+fn load_restore_snapshot(
+    storage: &StorageService,
+    source: &RestoreSource,
+) -> Result<(checkpoint::KernelSnapshot, Option<i64>, String), String> {
+    match source {
+        RestoreSource::File(path) => checkpoint::load_checkpoint(path)
+            .map(|snapshot| (snapshot, None, path.display().to_string())),
+        RestoreSource::Generation(generation) => {
+            let loaded = storage
+                .load_checkpoint_generation(*generation)
+                .map_err(|err| err.to_string())?;
+            match (loaded, generation) {
+                (Some(loaded), _) => {
+                    let snapshot = checkpoint::materialize_generation(&loaded)?;
+                    let id = loaded.summary.generation;
+                    Ok((snapshot, Some(id), format!("generation:{}", id)))
+                }
+                (None, Some(generation)) => Err(format!(
+                    "checkpoint generation {} is not retained",
+                    generation
+                )),
+                // No generation yet: fall back to the legacy JSON checkpoint.
+                (None, None) => load_restore_snapshot(
+                    storage,
+                    &RestoreSource::File(checkpoint::default_checkpoint_path()),
+                ),
+            }
+        }
+    }
+}
+
 pub(crate) fn handle_checkpoint(ctx: CheckpointCommandContext<'_>, payload: &[u8]) -> Vec<u8> {
     let CheckpointCommandContext {
         client,
@@ -17,26 +90,64 @@ pub(crate) fn handle_checkpoint(ctx: CheckpointCommandContext<'_>, payload: &[u8
         runtime_registry,
         model_catalog,
         scheduler,
+        session_registry,
         metrics,
         memory,
+        storage,
         client_id,
         ..
     } = ctx;
     let payload_text = String::from_utf8_lossy(payload).trim().to_string();
-    let path = if payload_text.is_empty() {
-        checkpoint::default_checkpoint_path()
-    } else {
-        std::path::PathBuf::from(&payload_text)
-    };
+    let checkpoint_config = &kernel_config().checkpoint;
 
     let snapshot = checkpoint::build_kernel_snapshot(
         runtime_registry.current_engine(),
         model_catalog,
         scheduler,
+        session_registry,
         metrics,
         memory,
     );
 
+    if payload_text.is_empty() && checkpoint_config.journal_enabled {
+        return match write_checkpoint_generation(
+            storage,
+            session_registry.boot_id(),
+            "manual",
+            &snapshot,
+            checkpoint_config.retained_generations,
+        ) {
+            Ok(generation) => {
+                let msg = format!(
+                    "checkpoint generation {} saved ({} processes)",
+                    generation,
+                    snapshot.processes.len()
+                );
+                log_event("checkpoint_save", client_id, None, &msg);
+                protocol::response_protocol_ok(
+                    client,
+                    request_id,
+                    "CHECKPOINT",
+                    protocol::schema::CHECKPOINT,
+                    &json!({"message": msg, "generation": generation}),
+                    Some(&msg),
+                )
+            }
+            Err(e) => protocol::response_protocol_err_typed(
+                client,
+                request_id,
+                ControlErrorCode::CheckpointFailed,
+                protocol::schema::ERROR,
+                &e,
+            ),
+        };
+    }
+
+    let path = if payload_text.is_empty() {
+        checkpoint::default_checkpoint_path()
+    } else {
+        PathBuf::from(&payload_text)
+    };
     match checkpoint::save_checkpoint(&snapshot, &path) {
         Ok(msg) => {
             log_event("checkpoint_save", client_id, None, &msg);
@@ -64,8 +175,12 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
         client,
         request_id,
         runtime_registry,
+        resource_governor,
         model_catalog,
         scheduler,
+        session_registry,
+        tool_registry,
+        memory,
         storage,
         in_flight,
         pending_events,
@@ -73,11 +188,19 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
         ..
     } = ctx;
     let payload_text = String::from_utf8_lossy(payload).trim().to_string();
-    let path = if payload_text.is_empty() {
-        checkpoint::default_checkpoint_path()
-    } else {
-        std::path::PathBuf::from(&payload_text)
-    };
+    let source =
+        match parse_restore_source(&payload_text, kernel_config().checkpoint.journal_enabled) {
+            Ok(source) => source,
+            Err(message) => {
+                return protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::RestoreFailed,
+                    protocol::schema::ERROR,
+                    &message,
+                );
+            }
+        };
 
     if !in_flight.is_empty() {
         return protocol::response_protocol_err_typed(
@@ -103,8 +226,8 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
         );
     }
 
-    match checkpoint::load_checkpoint(&path) {
-        Ok(snap) => {
+    match load_restore_snapshot(storage, &source) {
+        Ok((snap, generation, source_label)) => {
             let cleared_scheduler_entries = apply_restore_snapshot(&snap, scheduler, model_catalog);
             if let Err(err) = runtime_registry.clear_loaded_runtimes(storage) {
                 return protocol::response_protocol_err_typed(
@@ -115,17 +238,57 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
                     &err.to_string(),
                 );
             }
+
+            let pid_floor = snap
+                .processes
+                .iter()
+                .map(|process| process.pid + 1)
+                .fold(runtime_registry.next_pid_floor(), u64::max);
+            let mut restored = Vec::new();
+            let mut orphaned = Vec::new();
+            for process in &snap.processes {
+                match rehydrate_checkpoint_process(
+                    runtime_registry,
+                    resource_governor,
+                    model_catalog,
+                    memory,
+                    scheduler,
+                    session_registry,
+                    storage,
+                    pending_events,
+                    tool_registry,
+                    client_id,
+                    pid_floor,
+                    process,
+                ) {
+                    Ok(rehydrated) => {
+                        // The live process replaces the metadata-only entry.
+                        scheduler.unregister(process.pid);
+                        restored.push(rehydrated);
+                    }
+                    Err(reason) => {
+                        tracing::warn!(
+                            pid = process.pid,
+                            %reason,
+                            "CHECKPOINT: process left orphaned on restore"
+                        );
+                        orphaned.push(json!({"pid": process.pid, "reason": reason}));
+                    }
+                }
+            }
+
             pending_events.push(KernelEvent::LobbyChanged {
                 reason: "restore_applied".to_string(),
             });
             audit::record(
                 storage,
-                audit::KERNEL_LEGACY_RESTORE_APPLIED,
+                audit::KERNEL_RESTORE_APPLIED,
                 format!(
-                    "path={} restored_scheduler_entries={} processes_metadata={} semantics=legacy_metadata_only_diagnostic",
-                    path.display(),
+                    "source={} restored_scheduler_entries={} processes_restored={} processes_orphaned={}",
+                    source_label,
                     snap.scheduler.entries.len(),
-                    snap.processes.len()
+                    restored.len(),
+                    orphaned.len()
                 ),
                 AuditContext::default(),
             );
@@ -133,17 +296,29 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
             let response = json!({
                 "version": snap.version,
                 "timestamp": snap.timestamp,
+                "source": source_label,
+                "generation": generation,
                 "cleared_scheduler_entries": cleared_scheduler_entries,
                 "restored_scheduler_entries": snap.scheduler.entries.len(),
                 "processes_metadata": snap.processes.len(),
                 "selected_model": snap.selected_model.clone().unwrap_or_default(),
-                "restore_semantics": "legacy_metadata_only_diagnostic",
+                "restore_semantics": "live_rehydration",
                 "primary_persistence": "sqlite_control_plane",
+                "restored_processes": restored
+                    .iter()
+                    .map(|process| json!({
+                        "checkpoint_pid": process.checkpoint_pid,
+                        "pid": process.pid,
+                        "session_id": process.session_id,
+                        "runtime_id": process.runtime_id,
+                        "state": format!("{:?}", process.state),
+                        "prompt_source": process.prompt_source.as_str(),
+                        "kv_restored": process.kv_restored,
+                    }))
+                    .collect::<Vec<_>>(),
+                "orphaned_processes": orphaned,
                 "limitations": [
-                    "not_primary_persistence_path",
-                    "live_processes_not_restored",
-                    "model_weights_not_restored",
-                    "tensor_data_not_restored",
+                    "pending_syscalls_not_restored",
                     "output_buffers_not_restored"
                 ]
             });
@@ -153,12 +328,13 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
                 client_id,
                 None,
                 &format!(
-                    "version={} cleared_sched={} restored_sched={} procs_metadata={} from={:?}",
+                    "version={} cleared_sched={} restored_sched={} procs_restored={} procs_orphaned={} from={}",
                     snap.version,
                     cleared_scheduler_entries,
                     snap.scheduler.entries.len(),
-                    snap.processes.len(),
-                    path
+                    restored.len(),
+                    snap.processes.len() - restored.len(),
+                    source_label
                 ),
             );
             protocol::response_protocol_ok(
@@ -180,6 +356,67 @@ pub(crate) fn handle_restore(ctx: CheckpointCommandContext<'_>, payload: &[u8])
     }
 }
 
+pub(crate) fn handle_list_checkpoints(
+    ctx: CheckpointCommandContext<'_>,
+    payload: &[u8],
+) -> Vec<u8> {
+    let CheckpointCommandContext {
+        client,
+        request_id,
+        storage,
+        ..
+    } = ctx;
+    if !String::from_utf8_lossy(payload).trim().is_empty() {
+        return protocol::response_protocol_err_typed(
+            client,
+            request_id,
+            ControlErrorCode::ListCheckpointsInvalid,
+            protocol::schema::ERROR,
+            "LIST_CHECKPOINTS takes no payload",
+        );
+    }
+
+    match storage.list_checkpoint_generations() {
+        Ok(generations) => {
+            let checkpoint_config = &kernel_config().checkpoint;
+            let response = json!({
+                "journal_enabled": checkpoint_config.journal_enabled,
+                "retained_generations": checkpoint_config.retained_generations,
+                "legacy_checkpoint_path": checkpoint::default_checkpoint_path(),
+                "generations": generations.iter().map(generation_json).collect::<Vec<_>>(),
+            });
+            protocol::response_protocol_ok(
+                client,
+                request_id,
+                "LIST_CHECKPOINTS",
+                protocol::schema::LIST_CHECKPOINTS,
+                &response,
+                None,
+            )
+        }
+        Err(err) => protocol::response_protocol_err_typed(
+            client,
+            request_id,
+            ControlErrorCode::ListCheckpointsInvalid,
+            protocol::schema::ERROR,
+            &err.to_string(),
+        ),
+    }
+}
+
+fn generation_json(generation: &StoredCheckpointGeneration) -> serde_json::Value {
+    json!({
+        "generation": generation.generation,
+        "boot_id": generation.boot_id,
+        "created_at_ms": generation.created_at_ms,
+        "reason": generation.reason,
+        "kernel_version": generation.kernel_version,
+        "process_count": generation.process_count,
+        "delta_count": generation.delta_count,
+        "last_delta_at_ms": generation.last_delta_at_ms,
+    })
+}
+
 fn apply_restore_snapshot(
     snap: &checkpoint::KernelSnapshot,
     scheduler: &mut crate::scheduler::ProcessScheduler,
@@ -202,6 +439,7 @@ fn apply_restore_snapshot(
         let quota = ProcessQuota {
             max_tokens: entry.max_tokens,
             max_syscalls: entry.max_syscalls,
+            ..ProcessQuota::defaults_for(workload)
         };
         scheduler.set_quota(entry.pid, quota);
     }
@@ -221,6 +459,9 @@ fn apply_restore_snapshot(
                         crate::model_catalog::WorkloadClass::General,
                     )
                     .1,
+                    limits: crate::policy::scheduler_quota_limit_defaults(
+                        crate::model_catalog::WorkloadClass::General,
+                    ),
                 },
             );
         }
@@ -236,7 +477,10 @@ fn apply_restore_snapshot(
                 context_slot_id: None,
                 resident_slot_policy: None,
                 resident_slot_state: None,
-                resident_slot_snapshot_path: None,
+                resident_slot_snapshot_path: process
+                    .resident_slot_snapshot_path
+                    .as_ref()
+                    .map(|path| path.display().to_string()),
                 backend_id: None,
                 backend_class: None,
                 backend_capabilities: None,
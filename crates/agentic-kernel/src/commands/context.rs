@@ -38,6 +38,7 @@ pub(crate) struct CommandContext<'a> {
     pub client_id: usize,
     pub shutdown_requested: &'a Arc<AtomicBool>,
     pub mcp_bridge: Option<&'a crate::mcp::bridge::McpBridgeRuntime>,
+    pub tool_manifests: Option<&'a crate::tools::manifests::ToolManifestLoader>,
     // ── Inference worker (checkout/checkin) ──────────────────────
     pub in_flight: &'a HashSet<u64>,
     pub pending_kills: &'a mut Vec<u64>,
@@ -131,6 +132,7 @@ pub(crate) struct ToolsCommandContext<'a> {
     pub client: &'a mut Client,
     pub request_id: &'a str,
     pub tool_registry: &'a mut ToolRegistry,
+    pub storage: &'a mut StorageService,
 }
 
 pub(crate) struct MiscCommandContext<'a> {
@@ -151,12 +153,26 @@ pub(crate) struct MemoryCommandContext<'a> {
     pub pending_events: &'a mut Vec<KernelEvent>,
 }
 
+pub(crate) struct ContextControlCommandContext<'a> {
+    pub client: &'a mut Client,
+    pub request_id: &'a str,
+    pub runtime_registry: &'a mut RuntimeRegistry,
+    pub session_registry: &'a SessionRegistry,
+    pub storage: &'a mut StorageService,
+    pub in_flight: &'a HashSet<u64>,
+    pub pending_events: &'a mut Vec<KernelEvent>,
+    pub client_id: usize,
+}
+
 pub(crate) struct CheckpointCommandContext<'a> {
     pub client: &'a mut Client,
     pub request_id: &'a str,
     pub runtime_registry: &'a mut RuntimeRegistry,
+    pub resource_governor: &'a mut ResourceGovernor,
     pub model_catalog: &'a mut ModelCatalog,
     pub scheduler: &'a mut ProcessScheduler,
+    pub session_registry: &'a mut SessionRegistry,
+    pub tool_registry: &'a ToolRegistry,
     pub metrics: &'a mut MetricsState,
     pub memory: &'a mut NeuralMemory,
     pub storage: &'a mut StorageService,
@@ -196,6 +212,7 @@ impl<'a> CommandContext<'a> {
                 session_registry: &*self.session_registry,
                 storage: &*self.storage,
                 mcp_bridge: self.mcp_bridge,
+                tool_manifests: self.tool_manifests,
             },
         }
     }
@@ -290,6 +307,7 @@ impl<'a> CommandContext<'a> {
             client: &mut *self.client,
             request_id: self.request_id.as_str(),
             tool_registry: &mut *self.tool_registry,
+            storage: &mut *self.storage,
         }
     }
 
@@ -315,13 +333,29 @@ impl<'a> CommandContext<'a> {
         }
     }
 
+    pub fn context_control_view(&mut self) -> ContextControlCommandContext<'_> {
+        ContextControlCommandContext {
+            client: &mut *self.client,
+            request_id: self.request_id.as_str(),
+            runtime_registry: &mut *self.runtime_registry,
+            session_registry: &*self.session_registry,
+            storage: &mut *self.storage,
+            in_flight: self.in_flight,
+            pending_events: &mut *self.pending_events,
+            client_id: self.client_id,
+        }
+    }
+
     pub fn checkpoint_view(&mut self) -> CheckpointCommandContext<'_> {
         CheckpointCommandContext {
             client: &mut *self.client,
             request_id: self.request_id.as_str(),
             runtime_registry: &mut *self.runtime_registry,
+            resource_governor: &mut *self.resource_governor,
             model_catalog: &mut *self.model_catalog,
             scheduler: &mut *self.scheduler,
+            session_registry: &mut *self.session_registry,
+            tool_registry: &*self.tool_registry,
             metrics: &mut *self.metrics,
             memory: &mut *self.memory,
             storage: &mut *self.storage,
@@ -0,0 +1,373 @@
+use agentic_control_models::KernelEvent;
+use agentic_protocol::ControlErrorCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::process::{ContextPolicyUpdate, ContextStrategy};
+use crate::protocol;
+
+use super::context::ContextControlCommandContext;
+use super::diagnostics::log_event;
+
+/// Controlli operatore sul contesto di un processo vivo, per salvare un
+/// processo che continua a compattare senza doverlo uccidere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ContextControlOp {
+    SetPolicy,
+    CompactNow,
+    PinSegment,
+    DropSegment,
+}
+
+impl ContextControlOp {
+    fn code(self) -> &'static str {
+        match self {
+            Self::SetPolicy => "SET_CONTEXT_POLICY",
+            Self::CompactNow => "COMPACT_NOW",
+            Self::PinSegment => "PIN_SEGMENT",
+            Self::DropSegment => "DROP_SEGMENT",
+        }
+    }
+
+    fn schema(self) -> &'static str {
+        match self {
+            Self::SetPolicy => protocol::schema::SET_CONTEXT_POLICY,
+            Self::CompactNow => protocol::schema::COMPACT_NOW,
+            Self::PinSegment => protocol::schema::PIN_SEGMENT,
+            Self::DropSegment => protocol::schema::DROP_SEGMENT,
+        }
+    }
+
+    fn payload_hint(self) -> &'static str {
+        match self {
+            Self::SetPolicy => {
+                "{\"pid\":...,\"strategy\"?,\"window_size_tokens\"?,\"compaction_trigger_tokens\"?,\"compaction_target_tokens\"?,\"retrieve_top_k\"?}"
+            }
+            Self::CompactNow => "{\"pid\":...,\"target_tokens\"?}",
+            Self::PinSegment => "{\"pid\":...,\"segment\":...,\"pinned\"?}",
+            Self::DropSegment => "{\"pid\":...,\"segment\":...}",
+        }
+    }
+
+    fn event_reason(self) -> &'static str {
+        match self {
+            Self::SetPolicy => "context_policy_updated",
+            Self::CompactNow => "context_compacted",
+            Self::PinSegment => "context_segment_pinned",
+            Self::DropSegment => "context_segment_dropped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ContextControlRequest {
+    SetPolicy(ContextPolicyUpdate),
+    CompactNow { target_tokens: Option<usize> },
+    PinSegment { segment: usize, pinned: bool },
+    DropSegment { segment: usize },
+}
+
+#[derive(Debug, Deserialize)]
+struct SetContextPolicyPayload {
+    pid: u64,
+    strategy: Option<String>,
+    window_size_tokens: Option<usize>,
+    compaction_trigger_tokens: Option<usize>,
+    compaction_target_tokens: Option<usize>,
+    retrieve_top_k: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompactNowPayload {
+    pid: u64,
+    target_tokens: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SegmentPayload {
+    pid: u64,
+    segment: usize,
+    pinned: Option<bool>,
+}
+
+pub(crate) fn parse_context_control(
+    op: ContextControlOp,
+    payload: &[u8],
+) -> Result<(u64, ContextControlRequest), String> {
+    match op {
+        ContextControlOp::SetPolicy => {
+            let payload = serde_json::from_slice::<SetContextPolicyPayload>(payload)
+                .map_err(|err| err.to_string())?;
+            let strategy = payload
+                .strategy
+                .as_deref()
+                .map(|raw| {
+                    ContextStrategy::parse(raw).ok_or_else(|| {
+                        format!(
+                            "unknown strategy '{raw}' (use sliding_window, summarize or retrieve)"
+                        )
+                    })
+                })
+                .transpose()?;
+            let update = ContextPolicyUpdate {
+                strategy,
+                window_size_tokens: payload.window_size_tokens,
+                compaction_trigger_tokens: payload.compaction_trigger_tokens,
+                compaction_target_tokens: payload.compaction_target_tokens,
+                retrieve_top_k: payload.retrieve_top_k,
+            };
+            if update.is_empty() {
+                return Err("no policy field to change".to_string());
+            }
+            if [
+                update.window_size_tokens,
+                update.compaction_trigger_tokens,
+                update.compaction_target_tokens,
+                update.retrieve_top_k,
+            ]
+            .contains(&Some(0))
+            {
+                return Err("token limits and retrieve_top_k must be positive".to_string());
+            }
+            Ok((payload.pid, ContextControlRequest::SetPolicy(update)))
+        }
+        ContextControlOp::CompactNow => {
+            let payload = serde_json::from_slice::<CompactNowPayload>(payload)
+                .map_err(|err| err.to_string())?;
+            if payload.target_tokens == Some(0) {
+                return Err("target_tokens must be positive".to_string());
+            }
+            Ok((
+                payload.pid,
+                ContextControlRequest::CompactNow {
+                    target_tokens: payload.target_tokens,
+                },
+            ))
+        }
+        ContextControlOp::PinSegment => {
+            let payload =
+                serde_json::from_slice::<SegmentPayload>(payload).map_err(|err| err.to_string())?;
+            Ok((
+                payload.pid,
+                ContextControlRequest::PinSegment {
+                    segment: payload.segment,
+                    pinned: payload.pinned.unwrap_or(true),
+                },
+            ))
+        }
+        ContextControlOp::DropSegment => {
+            let payload =
+                serde_json::from_slice::<SegmentPayload>(payload).map_err(|err| err.to_string())?;
+            if payload.pinned.is_some() {
+                return Err("DROP_SEGMENT does not take 'pinned'".to_string());
+            }
+            Ok((
+                payload.pid,
+                ContextControlRequest::DropSegment {
+                    segment: payload.segment,
+                },
+            ))
+        }
+    }
+}
+
+pub(crate) fn handle_context_control(
+    ctx: ContextControlCommandContext<'_>,
+    op: ContextControlOp,
+    payload: &[u8],
+) -> Vec<u8> {
+    let ContextControlCommandContext {
+        client,
+        request_id,
+        runtime_registry,
+        session_registry,
+        storage,
+        in_flight,
+        pending_events,
+        client_id,
+    } = ctx;
+
+    let (pid, request) = match parse_context_control(op, payload) {
+        Ok(parsed) => parsed,
+        Err(detail) => {
+            return protocol::response_protocol_err_typed(
+                client,
+                request_id,
+                ControlErrorCode::ContextControlInvalid,
+                protocol::schema::ERROR,
+                &format!(
+                    "{} expects JSON payload {}: {}",
+                    op.code(),
+                    op.payload_hint(),
+                    detail
+                ),
+            );
+        }
+    };
+
+    // Un processo in checkout è sul worker: il suo contesto torna nel motore
+    // solo al checkin, quindi qui non c'è nulla da modificare in sicurezza.
+    if in_flight.contains(&pid) {
+        return protocol::response_protocol_err_typed(
+            client,
+            request_id,
+            ControlErrorCode::ContextControlFailed,
+            protocol::schema::ERROR,
+            &format!(
+                "PID {} is running an inference step; retry {} once it completes",
+                pid,
+                op.code()
+            ),
+        );
+    }
+
+    let Some(process) = runtime_registry
+        .engine_for_pid_mut(pid)
+        .and_then(|engine| engine.processes.get_mut(&pid))
+    else {
+        return protocol::response_protocol_err_typed(
+            client,
+            request_id,
+            ControlErrorCode::PidNotFound,
+            protocol::schema::ERROR,
+            &format!("PID {} has no live context", pid),
+        );
+    };
+
+    let applied: Result<(Value, String), String> = match request {
+        ContextControlRequest::SetPolicy(update) => {
+            let policy = process.context_policy.with_update(&update);
+            let detail = format!(
+                "strategy={} window={} trigger={} target={} top_k={}",
+                policy.strategy.label(),
+                policy.window_size_tokens,
+                policy.compaction_trigger_tokens,
+                policy.compaction_target_tokens,
+                policy.retrieve_top_k
+            );
+            process.set_context_policy(policy);
+            Ok((json!({"policy": &process.context_policy}), detail))
+        }
+        ContextControlRequest::CompactNow { target_tokens } => {
+            let tokens_before = process.tokens.len();
+            match process.compact_now(target_tokens) {
+                Some(event) => {
+                    let detail = format!(
+                        "tokens_before={} tokens_after={} {}",
+                        tokens_before, event.tokens_after, event.reason
+                    );
+                    Ok((
+                        json!({
+                            "compacted": true,
+                            "strategy": event.strategy.label(),
+                            "dropped_segments": event.dropped_segments,
+                            "dropped_tokens": event.dropped_tokens,
+                            "tokens_before": tokens_before,
+                            "tokens_after": event.tokens_after,
+                            "reason": event.reason,
+                        }),
+                        detail,
+                    ))
+                }
+                None => {
+                    let reason = process
+                        .context_state
+                        .last_compaction_reason
+                        .clone()
+                        .unwrap_or_else(|| "no_compaction".to_string());
+                    Ok((
+                        json!({
+                            "compacted": false,
+                            "strategy": process.context_policy.strategy.label(),
+                            "tokens_before": tokens_before,
+                            "tokens_after": process.tokens.len(),
+                            "reason": reason,
+                        }),
+                        format!("tokens={} skipped={}", tokens_before, reason),
+                    ))
+                }
+            }
+        }
+        ContextControlRequest::PinSegment { segment, pinned } => {
+            process.pin_segment(segment, pinned).map(|pinned_segment| {
+                (
+                    json!({
+                        "segment": segment,
+                        "kind": pinned_segment.kind.label(),
+                        "token_count": pinned_segment.token_count,
+                        "pinned": pinned,
+                    }),
+                    format!(
+                        "segment={} kind={} pinned={}",
+                        segment,
+                        pinned_segment.kind.label(),
+                        pinned
+                    ),
+                )
+            })
+        }
+        ContextControlRequest::DropSegment { segment } => {
+            process.drop_segment(segment).map(|dropped| {
+                (
+                    json!({
+                        "segment": segment,
+                        "kind": dropped.kind.label(),
+                        "dropped_tokens": dropped.token_count,
+                    }),
+                    format!(
+                        "segment={} kind={} dropped_tokens={}",
+                        segment,
+                        dropped.kind.label(),
+                        dropped.token_count
+                    ),
+                )
+            })
+        }
+    };
+    let snapshot = process.context_status_snapshot();
+
+    let (mut data, detail) = match applied {
+        Ok(applied) => applied,
+        Err(err) => {
+            return protocol::response_protocol_err_typed(
+                client,
+                request_id,
+                ControlErrorCode::ContextControlFailed,
+                protocol::schema::ERROR,
+                &format!("{} failed for PID {}: {}", op.code(), pid, err),
+            );
+        }
+    };
+
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid,
+        reason: op.event_reason().to_string(),
+    });
+    log_event(op.event_reason(), client_id, Some(pid), &detail);
+    audit::record(
+        storage,
+        audit::PROCESS_CONTEXT_CONTROL,
+        format!("pid={} op={} {}", pid, op.code(), detail),
+        AuditContext::for_process(
+            session_registry.session_id_for_pid(pid),
+            pid,
+            runtime_registry.runtime_id_for_pid(pid),
+        ),
+    );
+
+    data["pid"] = json!(pid);
+    data["context"] = json!(snapshot);
+    protocol::response_protocol_ok(
+        client,
+        request_id,
+        op.code(),
+        op.schema(),
+        &data,
+        Some(&format!("PID {} {}: {}", pid, op.code(), detail)),
+    )
+}
+
+#[cfg(test)]
+#[path = "tests/context_cmd.rs"]
+mod tests;
@@ -1,8 +1,9 @@
+use crate::errors::CatalogError;
 use crate::policy::resolve_exec_policy;
 use crate::process::ProcessLifecyclePolicy;
 use crate::protocol;
 use crate::scheduler::ProcessPriority;
-use crate::scheduler::ProcessQuota;
+use crate::scheduler::{ProcessQuota, QuotaLimits};
 use crate::services::model_runtime::{activate_model_target, ModelActivationError};
 use crate::services::process_runtime::{spawn_managed_process_with_session, ManagedProcessRequest};
 use crate::tools::invocation::{
@@ -11,6 +12,7 @@ use crate::tools::invocation::{
 use agentic_control_models::{ExecStartPayload, KernelEvent};
 use agentic_protocol::ControlErrorCode;
 use serde::Deserialize;
+use serde_json::Value;
 
 use super::context::ExecCommandContext;
 use super::diagnostics::log_event;
@@ -51,6 +53,7 @@ pub(crate) fn handle_exec(ctx: ExecCommandContext<'_>, payload: &[u8]) -> Option
     };
     let ResolvedExecRequest {
         prompt_raw,
+        model_selector,
         quota_override,
         permission_overrides,
     } = requested;
@@ -74,7 +77,61 @@ pub(crate) fn handle_exec(ctx: ExecCommandContext<'_>, payload: &[u8]) -> Option
     let mut runtime_id = runtime_registry
         .current_runtime_id()
         .map(ToString::to_string);
-    if can_scheduler_switch {
+    if let Some(selector) = model_selector.as_deref() {
+        // An explicit model wins over workload routing, exactly like a LOAD
+        // issued right before the EXEC.
+        let target = match model_catalog.resolve_load_target(selector) {
+            Ok(target) => target,
+            Err(CatalogError::DriverResolutionFailed(detail)) => {
+                return Some(protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::DriverUnresolved,
+                    protocol::schema::ERROR,
+                    &detail,
+                ));
+            }
+            Err(e) => {
+                return Some(protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::ModelSelector,
+                    protocol::schema::ERROR,
+                    &e.to_string(),
+                ));
+            }
+        };
+        match activate_model_target(
+            runtime_registry,
+            resource_governor,
+            session_registry,
+            storage,
+            model_catalog,
+            &target,
+        ) {
+            Ok(loaded) => {
+                runtime_id = Some(loaded.runtime_id);
+            }
+            Err(ModelActivationError::Busy(e)) => {
+                return Some(protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::LoadBusy,
+                    protocol::schema::ERROR,
+                    &e,
+                ));
+            }
+            Err(ModelActivationError::Failed(e)) => {
+                return Some(protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::LoadFailed,
+                    protocol::schema::ERROR,
+                    &e,
+                ));
+            }
+        }
+    } else if can_scheduler_switch {
         match model_catalog.resolve_workload_target(workload) {
             Ok(Some(target)) => {
                 match activate_model_target(
@@ -308,6 +365,8 @@ pub(crate) fn handle_exec(ctx: ExecCommandContext<'_>, payload: &[u8]) -> Option
 struct ExecRequestPayload {
     prompt: String,
     #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
     max_tokens: Option<u64>,
     #[serde(default)]
     max_syscalls: Option<u64>,
@@ -317,42 +376,71 @@ struct ExecRequestPayload {
     path_scopes: Option<Vec<String>>,
     #[serde(default)]
     path_grants: Option<Vec<ProcessPathGrant>>,
+    #[serde(flatten)]
+    limits: QuotaLimits,
 }
 
 struct ResolvedExecRequest {
     prompt_raw: String,
+    model_selector: Option<String>,
     quota_override: Option<ProcessQuota>,
     permission_overrides: Option<ProcessPermissionOverrides>,
 }
 
+/// Keys that make a JSON EXEC carry its own token/syscall quota; without any
+/// of them the process keeps the workload quota, like a plain-text EXEC.
+const EXEC_QUOTA_KEYS: [&str; 2] = ["max_tokens", "max_syscalls"];
+
 fn parse_exec_request(payload: &[u8]) -> Result<ResolvedExecRequest, String> {
-    if let Ok(parsed) = serde_json::from_slice::<ExecRequestPayload>(payload) {
-        return Ok(ResolvedExecRequest {
-            prompt_raw: parsed.prompt,
-            quota_override: Some(ProcessQuota {
-                max_tokens: parse_exec_quota_limit("max_tokens", parsed.max_tokens)?,
-                max_syscalls: parse_exec_quota_limit("max_syscalls", parsed.max_syscalls)?,
-            }),
-            permission_overrides: (parsed.allowed_tools.is_some()
-                || parsed.path_scopes.is_some()
-                || parsed.path_grants.is_some())
-            .then_some(ProcessPermissionOverrides {
-                trust_scope: None,
-                allow_actions: None,
-                allowed_tools: parsed.allowed_tools,
-                path_scopes: parsed.path_scopes,
-                path_grants: parsed.path_grants,
-            }),
-        });
+    if let Ok(object) = serde_json::from_slice::<serde_json::Map<String, Value>>(payload) {
+        let has_quota = EXEC_QUOTA_KEYS.iter().any(|key| object.contains_key(*key));
+        if let Ok(parsed) = serde_json::from_value::<ExecRequestPayload>(Value::Object(object)) {
+            return resolve_json_exec_request(parsed, has_quota);
+        }
     }
 
     Ok(ResolvedExecRequest {
         prompt_raw: String::from_utf8_lossy(payload).to_string(),
+        model_selector: None,
         quota_override: None,
         permission_overrides: None,
     })
 }
 
+fn resolve_json_exec_request(
+    parsed: ExecRequestPayload,
+    has_quota: bool,
+) -> Result<ResolvedExecRequest, String> {
+    parsed.limits.validate()?;
+    let quota_override = if has_quota || !parsed.limits.is_empty() {
+        Some(ProcessQuota {
+            max_tokens: parse_exec_quota_limit("max_tokens", parsed.max_tokens)?,
+            max_syscalls: parse_exec_quota_limit("max_syscalls", parsed.max_syscalls)?,
+            limits: parsed.limits,
+        })
+    } else {
+        None
+    };
+    Ok(ResolvedExecRequest {
+        prompt_raw: parsed.prompt,
+        model_selector: parsed
+            .model
+            .map(|model| model.trim().to_string())
+            .filter(|model| !model.is_empty()),
+        quota_override,
+        permission_overrides: (parsed.allowed_tools.is_some()
+            || parsed.path_scopes.is_some()
+            || parsed.path_grants.is_some())
+        .then_some(ProcessPermissionOverrides {
+            trust_scope: None,
+            allow_actions: None,
+            allowed_tools: parsed.allowed_tools,
+            path_scopes: parsed.path_scopes,
+            path_grants: parsed.path_grants,
+        }),
+    })
+}
+
 fn parse_exec_quota_limit(label: &str, value: Option<u64>) -> Result<usize, String> {
     match value {
         None => Ok(usize::MAX),
@@ -387,6 +475,35 @@ mod tests {
         assert_eq!(quota.max_syscalls, 12);
     }
 
+    #[test]
+    fn json_exec_request_selects_model_without_overriding_quota() {
+        let parsed = parse_exec_request(
+            br#"{"prompt":"ciao","model":" cloud:openrouter:qwen/qwen3-4b:free "}"#,
+        )
+        .expect("parse request");
+
+        assert_eq!(
+            parsed.model_selector.as_deref(),
+            Some("cloud:openrouter:qwen/qwen3-4b:free")
+        );
+        assert!(parsed.quota_override.is_none());
+    }
+
+    #[test]
+    fn json_exec_request_parses_extended_quota_limits() {
+        let parsed = parse_exec_request(
+            br#"{"prompt":"riassumi","max_wall_clock_secs":300,"max_cost_usd":0.25}"#,
+        )
+        .expect("parse request");
+
+        let limits = parsed.quota_override.expect("quota override").limits;
+        assert_eq!(limits.max_wall_clock_secs, Some(300));
+        assert_eq!(limits.max_cost_usd, Some(0.25));
+        assert_eq!(limits.max_tool_wall_ms, None);
+
+        assert!(parse_exec_request(br#"{"prompt":"riassumi","max_tool_wall_ms":0}"#).is_err());
+    }
+
     #[test]
     fn exec_quota_rejects_zero_when_limit_is_explicit() {
         let err = parse_exec_quota_limit("max_tokens", Some(0)).expect_err("zero must fail");
@@ -1,5 +1,6 @@
 mod checkpoint_cmd;
 mod context;
+mod context_cmd;
 mod core_dump;
 mod diagnostics;
 mod exec;
@@ -68,6 +69,7 @@ pub fn execute_command(
     metrics: &mut MetricsState,
     auth_token: &str,
     mcp_bridge: Option<&crate::mcp::bridge::McpBridgeRuntime>,
+    tool_manifests: Option<&crate::tools::manifests::ToolManifestLoader>,
 ) {
     let request_id = client.allocate_request_id(&header.agent_id);
 
@@ -146,6 +148,7 @@ pub fn execute_command(
         client_id,
         shutdown_requested,
         mcp_bridge,
+        tool_manifests,
         in_flight,
         pending_kills,
         pending_events,
@@ -198,6 +201,15 @@ pub fn execute_command(
                 return;
             }
         }
+        OpCode::RunJobNow => {
+            if let Some(r) =
+                workflow_commands::jobs::handle_run_job_now(ctx.orchestration_view(), &payload)
+            {
+                r
+            } else {
+                return;
+            }
+        }
         OpCode::SendInput => {
             self::process_commands::handle_send_input(ctx.process_view(), &payload)
         }
@@ -248,6 +260,26 @@ pub fn execute_command(
         OpCode::SetGen => misc::handle_set_gen(ctx.misc_view(), &payload),
         OpCode::GetGen => misc::handle_get_gen(ctx.misc_view()),
         OpCode::MemoryWrite => memory_cmd::handle_memory_write(ctx.memory_view(), &payload),
+        OpCode::SetContextPolicy => context_cmd::handle_context_control(
+            ctx.context_control_view(),
+            context_cmd::ContextControlOp::SetPolicy,
+            &payload,
+        ),
+        OpCode::CompactNow => context_cmd::handle_context_control(
+            ctx.context_control_view(),
+            context_cmd::ContextControlOp::CompactNow,
+            &payload,
+        ),
+        OpCode::PinSegment => context_cmd::handle_context_control(
+            ctx.context_control_view(),
+            context_cmd::ContextControlOp::PinSegment,
+            &payload,
+        ),
+        OpCode::DropSegment => context_cmd::handle_context_control(
+            ctx.context_control_view(),
+            context_cmd::ContextControlOp::DropSegment,
+            &payload,
+        ),
         OpCode::SetPriority => {
             self::process_commands::lifecycle::handle_set_priority(ctx.scheduler_view(), &payload)
         }
@@ -261,6 +293,9 @@ pub fn execute_command(
         OpCode::CoreDump => core_dump::handle_core_dump(ctx.core_dump_view(), &payload),
         OpCode::CoreDumpInfo => core_dump::handle_core_dump_info(ctx.core_dump_view(), &payload),
         OpCode::Restore => checkpoint_cmd::handle_restore(ctx.checkpoint_view(), &payload),
+        OpCode::ListCheckpoints => {
+            checkpoint_cmd::handle_list_checkpoints(ctx.checkpoint_view(), &payload)
+        }
         OpCode::Orchestrate => {
             if let Some(r) = workflow_commands::orchestration::handle_orchestrate(
                 ctx.orchestration_view(),
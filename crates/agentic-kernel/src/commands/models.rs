@@ -1,5 +1,6 @@
 use crate::backend;
 use crate::errors::CatalogError;
+use crate::model_catalog::ContextWindowResolution;
 use crate::protocol;
 use crate::services::model_runtime::{activate_model_target, ModelActivationError};
 use agentic_control_models::{KernelEvent, LoadModelResult, SelectModelResult};
@@ -211,7 +212,11 @@ pub(crate) fn handle_model_info(ctx: ModelCommandContext<'_>, payload: &[u8]) ->
     } else {
         match ctx.model_catalog.format_info_json(&model_id) {
             Ok(info) => {
-                let data: Value = serde_json::from_str(&info).unwrap_or(Value::Null);
+                let mut data: Value = serde_json::from_str(&info).unwrap_or(Value::Null);
+                if let Some(context_window) = model_info_context_window(&ctx, &model_id) {
+                    data["context_window"] = serde_json::json!(context_window);
+                }
+                let info = serde_json::to_string(&data).unwrap_or(info);
                 protocol::response_protocol_ok(
                     ctx.client,
                     ctx.request_id,
@@ -232,6 +237,48 @@ pub(crate) fn handle_model_info(ctx: ModelCommandContext<'_>, payload: &[u8]) ->
     }
 }
 
+/// La finestra in cache nel catalogo ignora runtime e budget: se il modello è
+/// caricato vale quella risolta dal motore, altrimenti si applica il tetto di
+/// memoria del resource governor.
+fn model_info_context_window(
+    ctx: &ModelCommandContext<'_>,
+    model_id: &str,
+) -> Option<agentic_control_models::ContextWindowView> {
+    let registry = &ctx.runtime_registry;
+    let loaded = registry
+        .loaded_runtime_ids()
+        .iter()
+        .filter_map(|runtime_id| registry.engine(runtime_id))
+        .find(|engine| {
+            current_loaded_model_id(
+                ctx.model_catalog,
+                std::path::Path::new(engine.loaded_model_path()),
+                engine.loaded_remote_model(),
+            ) == model_id
+        })
+        .map(|engine| engine.context_window().view());
+    if loaded.is_some() {
+        return loaded;
+    }
+
+    let entry = ctx.model_catalog.find_by_id(model_id)?;
+    let metadata = entry.metadata.as_ref();
+    let memory_cap = ctx.resource_governor.context_window_cap(
+        &entry.path,
+        &entry.id,
+        metadata.and_then(|metadata| metadata.kv_cache_bytes_per_token),
+    );
+    Some(
+        ContextWindowResolution::resolve(
+            ContextWindowResolution::declared_window(metadata, None),
+            None,
+            memory_cap,
+            crate::config::kernel_config().context.default_window_tokens,
+        )
+        .view(),
+    )
+}
+
 pub(crate) fn handle_backend_diag(ctx: ModelCommandContext<'_>) -> Vec<u8> {
     match backend::diagnose_external_backend() {
         Ok(report) => {
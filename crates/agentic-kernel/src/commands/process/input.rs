@@ -1,6 +1,5 @@
-use crate::diagnostics::audit::{self, AuditContext};
 use crate::protocol;
-use crate::runtime::AssistantTurnRuntimeBoundary;
+use crate::services::session_runtime::append_session_input;
 use agentic_control_models::{KernelEvent, SendInputResult, TurnControlResult};
 use agentic_protocol::ControlErrorCode;
 use serde::Deserialize;
@@ -65,105 +64,18 @@ pub(crate) fn handle_send_input(mut ctx: ProcessCommandContext<'_>, payload: &[u
         }
     };
 
-    let Some(engine) = ctx.runtime_registry.engine_mut(&target.runtime_id) else {
-        return protocol::response_protocol_err_typed(
-            ctx.client,
-            ctx.request_id,
-            ControlErrorCode::NoModel,
-            protocol::schema::ERROR,
-            "No Model Loaded",
-        );
-    };
-
-    let Some(process) = engine.processes.get(&target.pid) else {
-        return protocol::response_protocol_err_typed(
-            ctx.client,
-            ctx.request_id,
-            ControlErrorCode::PidNotFound,
-            protocol::schema::ERROR,
-            &format!("PID {} not found", target.pid),
-        );
-    };
-
-    let had_pending_human_request = process.pending_human_request.is_some();
-
-    if !matches!(
-        process.state,
-        crate::process::ProcessState::WaitingForInput
-            | crate::process::ProcessState::WaitingForHumanInput
+    match append_session_input(
+        ctx.runtime_registry,
+        ctx.scheduler,
+        ctx.session_registry,
+        ctx.storage,
+        ctx.turn_assembly,
+        ctx.pending_events,
+        &target,
+        prompt,
+        "send_input",
     ) {
-        return protocol::response_protocol_err_typed(
-            ctx.client,
-            ctx.request_id,
-            ControlErrorCode::InvalidSessionState,
-            protocol::schema::ERROR,
-            &format!(
-                "PID {} is not waiting for input (state={:?})",
-                target.pid, process.state
-            ),
-        );
-    }
-
-    match engine.send_user_input(target.pid, prompt) {
         Ok(()) => {
-            ctx.turn_assembly
-                .apply_runtime_boundary(target.pid, AssistantTurnRuntimeBoundary::RuntimeClosed);
-            let session_id = ctx
-                .session_registry
-                .session_id_for_pid(target.pid)
-                .map(ToString::to_string);
-            let workload = ctx
-                .scheduler
-                .snapshot(target.pid)
-                .map(|snapshot| format!("{:?}", snapshot.workload).to_lowercase())
-                .unwrap_or_else(|| "general".to_string());
-            if let Some(session_id_ref) = session_id.as_deref() {
-                match ctx.storage.start_session_turn(
-                    session_id_ref,
-                    target.pid,
-                    &workload,
-                    "send_input",
-                    prompt,
-                    "input",
-                ) {
-                    Ok(turn_id) => ctx
-                        .session_registry
-                        .remember_active_turn(target.pid, turn_id),
-                    Err(err) => {
-                        tracing::warn!(
-                            pid = target.pid,
-                            session_id = session_id_ref,
-                            %err,
-                            "PROCESS_CMD: failed to persist SEND_INPUT turn"
-                        );
-                    }
-                }
-            }
-            ctx.pending_events.push(KernelEvent::WorkspaceChanged {
-                pid: target.pid,
-                reason: "input_received".to_string(),
-            });
-            ctx.pending_events.push(KernelEvent::LobbyChanged {
-                reason: "input_received".to_string(),
-            });
-            audit::record(
-                ctx.storage,
-                if had_pending_human_request {
-                    audit::PROCESS_HUMAN_INPUT_RECEIVED
-                } else {
-                    audit::PROCESS_INPUT_RECEIVED
-                },
-                format!(
-                    "source=send_input chars={} hitl={}",
-                    prompt.chars().count(),
-                    had_pending_human_request
-                ),
-                AuditContext::for_process(
-                    session_id.as_deref(),
-                    target.pid,
-                    Some(&target.runtime_id),
-                ),
-            );
             crate::commands::diagnostics::log_event(
                 "process_continue",
                 ctx.client_id,
@@ -194,12 +106,12 @@ pub(crate) fn handle_send_input(mut ctx: ProcessCommandContext<'_>, payload: &[u
                 )),
             )
         }
-        Err(err) => protocol::response_protocol_err_typed(
+        Err((code, detail)) => protocol::response_protocol_err_typed(
             ctx.client,
             ctx.request_id,
-            ControlErrorCode::InvalidSessionState,
+            code,
             protocol::schema::ERROR,
-            &err.to_string(),
+            &detail,
         ),
     }
 }
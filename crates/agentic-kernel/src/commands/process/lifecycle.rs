@@ -99,6 +99,13 @@ pub(crate) fn handle_get_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
                 "tokens_generated": snap.tokens_generated,
                 "syscalls_used": snap.syscalls_used,
                 "elapsed_secs": format!("{:.2}", snap.elapsed_secs).parse::<f64>().unwrap_or(snap.elapsed_secs),
+                "max_wall_clock_secs": snap.quota.limits.max_wall_clock_secs,
+                "max_cost_usd": snap.quota.limits.max_cost_usd,
+                "max_remote_input_tokens": snap.quota.limits.max_remote_input_tokens,
+                "max_tool_wall_ms": snap.quota.limits.max_tool_wall_ms,
+                "cost_usd": snap.cost_usd,
+                "remote_input_tokens": snap.remote_input_tokens,
+                "tool_wall_ms": snap.tool_wall_ms,
             });
             protocol::response_protocol_ok(
                 client,
@@ -144,7 +151,7 @@ pub(crate) fn handle_set_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
             request_id,
             ControlErrorCode::SetQuotaInvalid,
             protocol::schema::ERROR,
-            "SET_QUOTA requires: <PID> <max_tokens=N,max_syscalls=N>",
+            "SET_QUOTA requires: <PID> <key=value,...>",
         )
     } else if let Ok(pid) = parts[0].parse::<u64>() {
         if let Some(current) = scheduler.quota(pid).copied() {
@@ -168,14 +175,19 @@ pub(crate) fn handle_set_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
                                 parse_ok = false;
                             }
                         }
-                        _ => {
-                            parse_ok = false;
+                        key => {
+                            if !matches!(new_quota.limits.apply_setting(key, v), Ok(true)) {
+                                parse_ok = false;
+                            }
                         }
                     }
                 } else {
                     parse_ok = false;
                 }
             }
+            if parse_ok && new_quota.limits.validate().is_err() {
+                parse_ok = false;
+            }
             if parse_ok {
                 scheduler.set_quota(pid, new_quota);
                 pending_events.push(KernelEvent::WorkspaceChanged {
@@ -187,8 +199,10 @@ pub(crate) fn handle_set_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
                     client_id,
                     Some(pid),
                     &format!(
-                        "max_tokens={} max_syscalls={}",
-                        new_quota.max_tokens, new_quota.max_syscalls
+                        "max_tokens={} max_syscalls={} limits={}",
+                        new_quota.max_tokens,
+                        new_quota.max_syscalls,
+                        serde_json::to_string(&new_quota.limits).unwrap_or_default()
                     ),
                 );
                 let message = format!(
@@ -204,6 +218,10 @@ pub(crate) fn handle_set_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
                         "pid": pid,
                         "max_tokens": new_quota.max_tokens,
                         "max_syscalls": new_quota.max_syscalls,
+                        "max_wall_clock_secs": new_quota.limits.max_wall_clock_secs,
+                        "max_cost_usd": new_quota.limits.max_cost_usd,
+                        "max_remote_input_tokens": new_quota.limits.max_remote_input_tokens,
+                        "max_tool_wall_ms": new_quota.limits.max_tool_wall_ms,
                     }),
                     Some(&message),
                 )
@@ -213,7 +231,7 @@ pub(crate) fn handle_set_quota(ctx: SchedulerCommandContext<'_>, payload: &[u8])
                     request_id,
                     ControlErrorCode::SetQuotaInvalid,
                     protocol::schema::ERROR,
-                    "Invalid quota format. Use: max_tokens=N,max_syscalls=N",
+                    "Invalid quota format. Use: max_tokens=N,max_syscalls=N,max_wall_clock_secs=N,max_cost_usd=X,max_remote_input_tokens=N,max_tool_wall_ms=N (limits accept 'none')",
                 )
             }
         } else {
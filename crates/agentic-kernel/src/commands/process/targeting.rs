@@ -2,16 +2,11 @@ use agentic_protocol::ControlErrorCode;
 
 use crate::commands::context::ProcessCommandContext;
 
+use crate::services::session_runtime::SessionContinuationTarget;
+
 use super::input::SendInputPayload;
 use super::resume::ensure_live_session_binding;
 
-pub(super) struct SessionContinuationTarget {
-    pub(super) session_id: String,
-    pub(super) runtime_id: String,
-    pub(super) pid: u64,
-    pub(super) resumed_from_history: bool,
-}
-
 pub(super) fn resolve_send_input_target(
     ctx: &mut ProcessCommandContext<'_>,
     payload: &SendInputPayload,
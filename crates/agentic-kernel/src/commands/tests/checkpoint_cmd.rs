@@ -4,7 +4,7 @@ use crate::checkpoint::{
     SchedulerEntrySnapshot, SchedulerStateSnapshot,
 };
 use crate::model_catalog::ModelCatalog;
-use crate::process::{ContextPolicy, ContextState, ContextStrategy};
+use crate::process::{ContextPolicy, ContextState, ContextStrategy, ProcessLifecyclePolicy};
 use crate::scheduler::ProcessScheduler;
 use crate::tools::invocation::{
     default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
@@ -68,6 +68,10 @@ fn apply_restore_snapshot_clears_existing_scheduler_state() {
                 ..ContextState::default()
             },
             pending_human_request: None,
+            session_id: Some("sess-7".to_string()),
+            runtime_id: Some("rt-qwen".to_string()),
+            lifecycle_policy: ProcessLifecyclePolicy::Interactive,
+            resident_slot_snapshot_path: Some(PathBuf::from("pid_7_slot_1_2.swap")),
         }],
         scheduler: SchedulerStateSnapshot {
             entries: vec![SchedulerEntrySnapshot {
@@ -111,10 +115,35 @@ fn apply_restore_snapshot_clears_existing_scheduler_state() {
         .expect("restored process exists");
     assert_eq!(restored.context_policy.strategy, ContextStrategy::Summarize);
     assert_eq!(restored.context_state.tokens_used, 12);
+    assert_eq!(
+        restored.resident_slot_snapshot_path.as_deref(),
+        Some("pid_7_slot_1_2.swap")
+    );
 
     let _ = fs::remove_dir_all(base);
 }
 
+#[test]
+fn restore_payload_selects_generation_or_checkpoint_file() {
+    assert_eq!(
+        parse_restore_source("", true),
+        Ok(RestoreSource::Generation(None))
+    );
+    assert_eq!(
+        parse_restore_source("", false),
+        Ok(RestoreSource::File(checkpoint::default_checkpoint_path()))
+    );
+    assert_eq!(
+        parse_restore_source(r#"{"generation": 3}"#, true),
+        Ok(RestoreSource::Generation(Some(3)))
+    );
+    assert_eq!(
+        parse_restore_source("/tmp/known-good.json", true),
+        Ok(RestoreSource::File(PathBuf::from("/tmp/known-good.json")))
+    );
+    assert!(parse_restore_source(r#"{"generation": "latest"}"#, true).is_err());
+}
+
 fn test_permissions() -> ProcessPermissionPolicy {
     ProcessPermissionPolicy {
         trust_scope: ProcessTrustScope::InteractiveChat,
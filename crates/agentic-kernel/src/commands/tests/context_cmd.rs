@@ -0,0 +1,86 @@
+use super::*;
+
+#[test]
+fn context_control_payloads_parse_per_opcode() {
+    let (pid, request) = parse_context_control(
+        ContextControlOp::SetPolicy,
+        br#"{"pid":7,"strategy":"summary","window_size_tokens":4096}"#,
+    )
+    .expect("set policy payload");
+    assert_eq!(pid, 7);
+    assert_eq!(
+        request,
+        ContextControlRequest::SetPolicy(ContextPolicyUpdate {
+            strategy: Some(ContextStrategy::Summarize),
+            window_size_tokens: Some(4096),
+            ..ContextPolicyUpdate::default()
+        })
+    );
+
+    let (_, request) =
+        parse_context_control(ContextControlOp::CompactNow, br#"{"pid":7}"#).expect("compact");
+    assert_eq!(
+        request,
+        ContextControlRequest::CompactNow {
+            target_tokens: None
+        }
+    );
+
+    let (_, request) =
+        parse_context_control(ContextControlOp::PinSegment, br#"{"pid":7,"segment":0}"#)
+            .expect("pin defaults to pinned=true");
+    assert_eq!(
+        request,
+        ContextControlRequest::PinSegment {
+            segment: 0,
+            pinned: true
+        }
+    );
+
+    let (_, request) = parse_context_control(
+        ContextControlOp::PinSegment,
+        br#"{"pid":7,"segment":2,"pinned":false}"#,
+    )
+    .expect("unpin");
+    assert_eq!(
+        request,
+        ContextControlRequest::PinSegment {
+            segment: 2,
+            pinned: false
+        }
+    );
+
+    let (_, request) =
+        parse_context_control(ContextControlOp::DropSegment, br#"{"pid":7,"segment":3}"#)
+            .expect("drop");
+    assert_eq!(request, ContextControlRequest::DropSegment { segment: 3 });
+}
+
+#[test]
+fn context_control_rejects_empty_or_invalid_changes() {
+    for (op, payload) in [
+        (ContextControlOp::SetPolicy, r#"{"pid":7}"#),
+        (ContextControlOp::SetPolicy, r#"{"pid":7,"strategy":"lru"}"#),
+        (
+            ContextControlOp::SetPolicy,
+            r#"{"pid":7,"compaction_target_tokens":0}"#,
+        ),
+        (
+            ContextControlOp::CompactNow,
+            r#"{"pid":7,"target_tokens":0}"#,
+        ),
+        (ContextControlOp::PinSegment, r#"{"pid":7}"#),
+        (
+            ContextControlOp::DropSegment,
+            r#"{"pid":7,"segment":1,"pinned":true}"#,
+        ),
+        (ContextControlOp::DropSegment, "7 1"),
+    ] {
+        assert!(
+            parse_context_control(op, payload.as_bytes()).is_err(),
+            "{} accepted {}",
+            op.code(),
+            payload
+        );
+    }
+}
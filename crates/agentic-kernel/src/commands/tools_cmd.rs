@@ -6,6 +6,7 @@ use agentic_protocol::ControlErrorCode;
 use crate::config::kernel_config;
 use crate::protocol;
 use crate::tool_registry::{ToolRegistryEntry, ToolSource};
+use crate::tools::registry_store::{forget_runtime_tool, persist_runtime_tool};
 
 use super::context::ToolsCommandContext;
 
@@ -25,6 +26,7 @@ pub(crate) fn handle_list_tools(ctx: ToolsCommandContext<'_>) -> Vec<u8> {
         client,
         request_id,
         tool_registry,
+        ..
     } = ctx;
     let tools: Vec<serde_json::Value> = tool_registry
         .list()
@@ -52,6 +54,7 @@ pub(crate) fn handle_tool_info(ctx: ToolsCommandContext<'_>, payload: &[u8]) ->
         client,
         request_id,
         tool_registry,
+        ..
     } = ctx;
     let tool_name = String::from_utf8_lossy(payload).trim().to_string();
     if tool_name.is_empty() {
@@ -105,6 +108,7 @@ pub(crate) fn handle_register_tool(mut ctx: ToolsCommandContext<'_>, payload: &[
         client,
         request_id,
         tool_registry,
+        storage,
     } = ctx;
     let payload_text = String::from_utf8_lossy(payload).trim().to_string();
     let mut request = match serde_json::from_str::<RegisterToolRequest>(&payload_text) {
@@ -141,6 +145,17 @@ pub(crate) fn handle_register_tool(mut ctx: ToolsCommandContext<'_>, payload: &[
             let registered = tool_registry
                 .get(&requested_name)
                 .expect("registered tool must be retrievable");
+            if let Err(err) = persist_runtime_tool(storage, registered) {
+                let canonical_name = registered.descriptor.name.clone();
+                let _ = tool_registry.unregister(&canonical_name);
+                return protocol::response_protocol_err_typed(
+                    client,
+                    request_id,
+                    ControlErrorCode::RegisterToolFailed,
+                    protocol::schema::ERROR,
+                    &err,
+                );
+            }
             let payload = json!({
                 "tool": registered,
             });
@@ -172,6 +187,7 @@ pub(crate) fn handle_unregister_tool(mut ctx: ToolsCommandContext<'_>, payload:
         client,
         request_id,
         tool_registry,
+        storage,
     } = ctx;
     let payload_text = String::from_utf8_lossy(payload).trim().to_string();
     let request = match serde_json::from_str::<UnregisterToolRequest>(&payload_text) {
@@ -187,8 +203,27 @@ pub(crate) fn handle_unregister_tool(mut ctx: ToolsCommandContext<'_>, payload:
         }
     };
 
+    if let Some(existing) = tool_registry.get(&request.name) {
+        if existing.descriptor.source == ToolSource::Manifest {
+            return protocol::response_protocol_err_typed(
+                client,
+                request_id,
+                ControlErrorCode::UnregisterToolFailed,
+                protocol::schema::ERROR,
+                &format!(
+                    "Tool '{}' is declared by a manifest in '{}'; remove the manifest file instead.",
+                    existing.descriptor.name,
+                    kernel_config().tools.manifest_dir.display()
+                ),
+            );
+        }
+    }
+
     match tool_registry.unregister(&request.name) {
         Ok(removed) => {
+            if removed.descriptor.source == ToolSource::Runtime {
+                forget_runtime_tool(storage, &removed.descriptor.name);
+            }
             let payload = json!({
                 "tool": removed,
             });
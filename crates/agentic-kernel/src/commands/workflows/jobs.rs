@@ -1,7 +1,11 @@
 use crate::commands::context::OrchestrationCommandContext;
 use crate::commands::diagnostics::log_event;
 use crate::protocol;
-use crate::services::job_scheduler::{ScheduledJobTriggerInput, ScheduledWorkflowJobRequest};
+use crate::services::job_runtime::scheduled_tool_name;
+use crate::services::job_scheduler::{
+    ConcurrencyPolicy, ScheduledJobRequest, ScheduledJobTarget, ScheduledJobTargetInput,
+    ScheduledJobTriggerInput,
+};
 use agentic_control_models::ScheduledJobControlResult;
 use agentic_protocol::ControlErrorCode;
 use serde::Deserialize;
@@ -9,7 +13,11 @@ use serde::Deserialize;
 #[derive(Debug, Deserialize)]
 struct ScheduleJobPayload {
     name: String,
-    workflow: serde_json::Value,
+    /// Shorthand for `target: {"kind": "workflow", "workflow": ...}`.
+    #[serde(default)]
+    workflow: Option<serde_json::Value>,
+    #[serde(default)]
+    target: Option<ScheduledJobTargetInput>,
     trigger: ScheduledJobTriggerInput,
     #[serde(default)]
     timeout_ms: Option<u64>,
@@ -17,6 +25,8 @@ struct ScheduleJobPayload {
     max_retries: Option<u32>,
     #[serde(default)]
     backoff_ms: Option<u64>,
+    #[serde(default)]
+    concurrency_policy: ConcurrencyPolicy,
     #[serde(default = "schedule_job_enabled_default")]
     enabled: bool,
 }
@@ -32,6 +42,11 @@ struct DeleteJobPayload {
     job_id: u64,
 }
 
+#[derive(Debug, Deserialize)]
+struct RunJobNowPayload {
+    job_id: u64,
+}
+
 fn schedule_job_enabled_default() -> bool {
     true
 }
@@ -54,41 +69,43 @@ pub(crate) fn handle_schedule_job(
         }
     };
 
-    let workflow_payload = match serde_json::to_string(&request.workflow) {
-        Ok(payload) => payload,
-        Err(err) => {
-            return Some(protocol::response_protocol_err_typed(
-                ctx.client,
-                ctx.request_id,
-                ControlErrorCode::ScheduleJobInvalid,
-                protocol::schema::ERROR,
-                &format!("Invalid workflow payload: {}", err),
-            ));
+    let target = match (request.workflow, request.target) {
+        (Some(workflow), None) => {
+            ScheduledJobTarget::from_request(ScheduledJobTargetInput::Workflow { workflow })
         }
-    };
-    let workflow = match serde_json::from_value(request.workflow) {
-        Ok(workflow) => workflow,
-        Err(err) => {
+        (None, Some(target)) => ScheduledJobTarget::from_request(target),
+        _ => Err("Scheduler job requires exactly one of 'workflow' or 'target'".to_string()),
+    }
+    .and_then(|target| match &target {
+        ScheduledJobTarget::Tool(tool) => {
+            scheduled_tool_name(ctx.tool_registry, &tool.tool).map(|_| target)
+        }
+        _ => Ok(target),
+    });
+    let target = match target {
+        Ok(target) => target,
+        Err(detail) => {
             return Some(protocol::response_protocol_err_typed(
                 ctx.client,
                 ctx.request_id,
                 ControlErrorCode::ScheduleJobInvalid,
                 protocol::schema::ERROR,
-                &format!("Workflow definition is invalid: {}", err),
+                &detail,
             ));
         }
     };
+    let target_kind = target.kind();
 
-    match ctx.job_scheduler.schedule_workflow_job(
+    match ctx.job_scheduler.schedule_job(
         ctx.storage,
-        ScheduledWorkflowJobRequest {
+        ScheduledJobRequest {
             name: request.name.trim().to_string(),
-            workflow,
-            workflow_payload,
+            target,
             trigger: request.trigger,
             timeout_ms: request.timeout_ms,
             max_retries: request.max_retries,
             backoff_ms: request.backoff_ms,
+            concurrency_policy: request.concurrency_policy,
             enabled: request.enabled,
         },
     ) {
@@ -98,8 +115,9 @@ pub(crate) fn handle_schedule_job(
                 ctx.client_id,
                 None,
                 &format!(
-                    "job_id={} trigger={} next_run_at_ms={}",
+                    "job_id={} target={} trigger={} next_run_at_ms={}",
                     result.job_id,
+                    target_kind,
                     result.trigger_kind,
                     result
                         .next_run_at_ms
@@ -245,3 +263,62 @@ pub(crate) fn handle_delete_job(
         )),
     }
 }
+
+pub(crate) fn handle_run_job_now(
+    ctx: OrchestrationCommandContext<'_>,
+    payload: &[u8],
+) -> Option<Vec<u8>> {
+    let request = match serde_json::from_slice::<RunJobNowPayload>(payload) {
+        Ok(request) => request,
+        Err(err) => {
+            return Some(protocol::response_protocol_err_typed(
+                ctx.client,
+                ctx.request_id,
+                ControlErrorCode::RunJobNowInvalid,
+                protocol::schema::ERROR,
+                &format!("Invalid run job now payload JSON: {}", err),
+            ));
+        }
+    };
+
+    match ctx.job_scheduler.run_now(ctx.storage, request.job_id) {
+        Ok(job) => {
+            let result = ScheduledJobControlResult {
+                job_id: job.job_id,
+                enabled: job.enabled,
+                state: job.state,
+            };
+            log_event(
+                "run_job_now",
+                ctx.client_id,
+                None,
+                &format!(
+                    "job_id={} state={} policy={}",
+                    result.job_id, result.state, job.concurrency_policy
+                ),
+            );
+            ctx.pending_events
+                .push(agentic_control_models::KernelEvent::LobbyChanged {
+                    reason: "scheduled_job_mutated".to_string(),
+                });
+            Some(protocol::response_protocol_ok(
+                ctx.client,
+                ctx.request_id,
+                "RUN_JOB_NOW",
+                protocol::schema::RUN_JOB_NOW,
+                &result,
+                Some(
+                    &serde_json::to_string(&result)
+                        .expect("ScheduledJobControlResult is serializable"),
+                ),
+            ))
+        }
+        Err(err) => Some(protocol::response_protocol_err_typed(
+            ctx.client,
+            ctx.request_id,
+            ControlErrorCode::RunJobNowInvalid,
+            protocol::schema::ERROR,
+            &err,
+        )),
+    }
+}
@@ -45,6 +45,7 @@ pub(crate) fn handle_orchestrate(
                 ctx.tool_registry,
                 ctx.client_id,
                 graph,
+                None,
             ) {
                 Ok(started) => {
                     for _ in 0..started.spawned {
@@ -22,10 +22,14 @@ pub struct KernelConfig {
     pub openrouter: OpenRouterConfig,
     pub exec: ExecConfig,
     pub orchestrator: OrchestratorConfig,
+    pub jobs: JobsConfig,
     pub tools: ToolsRuntimeConfig,
     pub mcp: McpConfig,
+    pub openai_api: OpenAIApiConfig,
+    pub mcp_export: McpExportConfig,
     pub generation: GenerationProfilesConfig,
     pub scheduler: SchedulerConfig,
+    pub budgets: BudgetsConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -123,7 +127,12 @@ impl Default for PathsConfig {
 pub struct MemoryRuntimeConfig {
     pub swap_async: bool,
     pub swap_dir: PathBuf,
+    /// Disk budget of the compressed swap snapshots; the coldest are evicted
+    /// past it. 0 means unbounded.
+    pub swap_max_bytes: u64,
+    pub swap_compression_level: i32,
     pub token_slot_quota_per_pid: usize,
+    pub long_term: LongTermMemoryConfig,
 }
 
 impl Default for MemoryRuntimeConfig {
@@ -131,7 +140,46 @@ impl Default for MemoryRuntimeConfig {
         Self {
             swap_async: true,
             swap_dir: repository_path("workspace/swap"),
+            swap_max_bytes: 4 * 1024 * 1024 * 1024,
+            swap_compression_level: 3,
             token_slot_quota_per_pid: 4096,
+            long_term: LongTermMemoryConfig::default(),
+        }
+    }
+}
+
+/// Cross-session memory behind the `memory_remember`, `memory_recall` and
+/// `memory_forget` tools.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct LongTermMemoryConfig {
+    pub enabled: bool,
+    /// Key of the `user` scope (the kernel serves a single operator).
+    pub user_scope: String,
+    /// Scope of `memory_remember` calls that do not name one.
+    pub default_scope: String,
+    pub max_entries_per_scope: usize,
+    pub max_content_chars: usize,
+    pub recall_limit: usize,
+    /// Inject the memories relevant to the first prompt of a new session as
+    /// a `RetrievedMemory` segment.
+    pub auto_inject: bool,
+    pub auto_inject_top_k: usize,
+    pub auto_inject_max_chars: usize,
+}
+
+impl Default for LongTermMemoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            user_scope: "local".to_string(),
+            default_scope: "workspace".to_string(),
+            max_entries_per_scope: 1_000,
+            max_content_chars: 2_000,
+            recall_limit: 5,
+            auto_inject: false,
+            auto_inject_top_k: 3,
+            auto_inject_max_chars: 1_200,
         }
     }
 }
@@ -177,6 +225,8 @@ pub struct ContextConfig {
     pub retrieve_candidate_limit: usize,
     pub retrieve_max_segment_chars: usize,
     pub retrieve_min_score: f64,
+    pub summarizer: ContextSummarizerConfig,
+    pub embeddings: EmbeddingsConfig,
 }
 
 impl Default for ContextConfig {
@@ -190,14 +240,94 @@ impl Default for ContextConfig {
             retrieve_candidate_limit: 64,
             retrieve_max_segment_chars: 768,
             retrieve_min_score: 0.12,
+            summarizer: ContextSummarizerConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Default)]
+/// Embedding provider of the `retrieve` strategy.
+///
+/// `provider` is `none`, `llamacpp` (native `/embedding`: `endpoint` of a
+/// running server, or `model` as catalog selector of a GGUF served by a
+/// dedicated managed runtime) or `openai_compatible` (`/embeddings` with
+/// `model`, on `endpoint` or on the endpoint and key of remote `backend`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EmbeddingsConfig {
+    pub provider: String,
+    pub model: String,
+    pub endpoint: String,
+    pub backend: String,
+    pub timeout_ms: u64,
+    /// Share of the vector similarity in the hybrid retrieval score.
+    pub vector_weight: f64,
+    pub max_cached_vectors: usize,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        Self {
+            provider: "none".to_string(),
+            model: String::new(),
+            endpoint: String::new(),
+            backend: "openai-responses".to_string(),
+            timeout_ms: 10_000,
+            vector_weight: 0.6,
+            max_cached_vectors: 2_048,
+        }
+    }
+}
+
+/// Summarizer used by the `summarize` context strategy.
+///
+/// `mode` is `heuristic` (keyword digest, no inference; the default),
+/// `process_model` (the process's own model) or `model` (the catalog selector
+/// in `model`, e.g. a small local model or `cloud:groq:...`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ContextSummarizerConfig {
+    pub mode: String,
+    pub model: String,
+    pub timeout_ms: u64,
+    pub max_summary_tokens: usize,
+    pub temperature: f64,
+}
+
+impl Default for ContextSummarizerConfig {
+    fn default() -> Self {
+        Self {
+            mode: "heuristic".to_string(),
+            model: String::new(),
+            timeout_ms: 20_000,
+            max_summary_tokens: 384,
+            temperature: 0.2,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct CheckpointConfig {
+    /// Compaction period: a full snapshot opens a new generation. With the
+    /// journal disabled it is the period of the legacy JSON checkpoint.
     pub interval_secs: u64,
+    /// Journal per-process deltas into SQLite on every state transition.
+    pub journal_enabled: bool,
+    pub retained_generations: usize,
+    /// Deltas after which a generation is compacted even before the interval.
+    pub max_deltas_per_generation: usize,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 0,
+            journal_enabled: true,
+            retained_generations: 5,
+            max_deltas_per_generation: 512,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -252,6 +382,7 @@ pub struct ExternalLlamaCppConfig {
     pub legacy_endpoint_override: String,
     pub timeout_ms: u64,
     pub chunk_tokens: usize,
+    pub prefix_cache: PromptPrefixCacheConfig,
 }
 
 impl Default for ExternalLlamaCppConfig {
@@ -264,6 +395,30 @@ impl Default for ExternalLlamaCppConfig {
             legacy_endpoint_override: String::new(),
             timeout_ms: 300_000,
             chunk_tokens: 64,
+            prefix_cache: PromptPrefixCacheConfig::default(),
+        }
+    }
+}
+
+/// Warm template slots shared by processes whose prompts start alike.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct PromptPrefixCacheConfig {
+    pub enabled: bool,
+    /// Shorter common prefixes are not worth a slot snapshot.
+    pub min_prefix_chars: usize,
+    pub max_templates_per_model: usize,
+    /// First prompts remembered per model to detect a shared prefix.
+    pub candidate_prompts: usize,
+}
+
+impl Default for PromptPrefixCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_prefix_chars: 2_048,
+            max_templates_per_model: 4,
+            candidate_prompts: 8,
         }
     }
 }
@@ -400,6 +555,21 @@ impl Default for OrchestratorConfig {
     }
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct JobsConfig {
+    /// Scheduled job runs allowed in flight at once, across all jobs.
+    pub max_concurrent_runs: usize,
+}
+
+impl Default for JobsConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_runs: 4,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct ToolsRuntimeConfig {
@@ -415,6 +585,11 @@ pub struct ToolsRuntimeConfig {
     pub remote_http_max_response_bytes: usize,
     pub audit_log_file: String,
     pub temp_script_prefix: String,
+    /// Directory of declarative tool manifests (`*.toml` / `*.json`),
+    /// loaded at boot and reloaded when its files change.
+    pub manifest_dir: PathBuf,
+    pub result_cache: ToolResultCacheConfig,
+    pub knowledge_base: KnowledgeBaseConfig,
 }
 
 impl Default for ToolsRuntimeConfig {
@@ -432,6 +607,162 @@ impl Default for ToolsRuntimeConfig {
             remote_http_max_response_bytes: 64 * 1024,
             audit_log_file: "syscall_audit.log".to_string(),
             temp_script_prefix: "agent_script_".to_string(),
+            manifest_dir: repository_path("config/tools.d"),
+            result_cache: ToolResultCacheConfig::default(),
+            knowledge_base: KnowledgeBaseConfig::default(),
+        }
+    }
+}
+
+/// Chunk index of the granted workspace roots behind `kb_search`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct KnowledgeBaseConfig {
+    pub enabled: bool,
+    pub chunk_lines: usize,
+    pub chunk_overlap_lines: usize,
+    pub max_file_bytes: u64,
+    /// Files indexed per root; the walk stops there.
+    pub max_files: usize,
+    /// Directory names never walked.
+    pub skip_dirs: Vec<String>,
+    /// A root is re-walked for mtime/size changes at most this often.
+    pub refresh_interval_ms: u64,
+    pub default_max_results: usize,
+    /// Rank with the `[context.embeddings]` provider too.
+    pub embeddings: bool,
+    pub vector_weight: f64,
+    /// Chunks embedded per search; the rest stay lexical until a later one.
+    pub max_embeddings_per_search: usize,
+    pub max_cached_vectors: usize,
+}
+
+impl Default for KnowledgeBaseConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            chunk_lines: 40,
+            chunk_overlap_lines: 8,
+            max_file_bytes: 1024 * 1024,
+            max_files: 5_000,
+            skip_dirs: [".git", "target", "node_modules", ".venv", "__pycache__"]
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            refresh_interval_ms: 2_000,
+            default_max_results: 8,
+            embeddings: false,
+            vector_weight: 0.5,
+            max_embeddings_per_search: 256,
+            max_cached_vectors: 8_192,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ToolResultCacheConfig {
+    pub enabled: bool,
+    pub max_entries: usize,
+    pub max_total_bytes: usize,
+    /// TTL of MCP and remote tools whose interop hints declare them
+    /// read-only or idempotent (and not destructive); `0` leaves them uncached.
+    pub hinted_ttl_s: u64,
+    pub hinted_max_entry_bytes: usize,
+    /// Per-tool cache policies keyed by canonical tool name. Configured
+    /// entries are merged field by field over the built-in defaults.
+    #[serde(deserialize_with = "deserialize_tool_cache_policies")]
+    pub tools: BTreeMap<String, ToolCachePolicyConfig>,
+}
+
+impl Default for ToolResultCacheConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_entries: 512,
+            max_total_bytes: 16 * 1024 * 1024,
+            hinted_ttl_s: 0,
+            hinted_max_entry_bytes: 256 * 1024,
+            tools: default_tool_cache_policies(),
+        }
+    }
+}
+
+fn default_tool_cache_policies() -> BTreeMap<String, ToolCachePolicyConfig> {
+    let workspace_read = |ttl_s: u64, path_fields: &[&str]| ToolCachePolicyConfig {
+        ttl_s,
+        path_fields: path_fields.iter().map(ToString::to_string).collect(),
+        ..ToolCachePolicyConfig::default()
+    };
+    let mut tools = BTreeMap::new();
+    for name in [
+        "read_file",
+        "read_file_range",
+        "path_info",
+        "inspect_document",
+        "find_files",
+        "search_text",
+        "list_tree",
+    ] {
+        tools.insert(name.to_string(), workspace_read(300, &["path"]));
+    }
+    tools.insert(
+        "diff_files".to_string(),
+        workspace_read(300, &["left_path", "right_path"]),
+    );
+    tools
+}
+
+fn deserialize_tool_cache_policies<'de, D>(
+    deserializer: D,
+) -> Result<BTreeMap<String, ToolCachePolicyConfig>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(deny_unknown_fields)]
+    struct PolicyOverride {
+        ttl_s: Option<u64>,
+        max_entry_bytes: Option<usize>,
+        path_fields: Option<Vec<String>>,
+    }
+
+    let overrides = BTreeMap::<String, PolicyOverride>::deserialize(deserializer)?;
+    let mut tools = default_tool_cache_policies();
+    for (name, entry) in overrides {
+        let policy = tools.entry(name).or_default();
+        if let Some(ttl_s) = entry.ttl_s {
+            policy.ttl_s = ttl_s;
+        }
+        if let Some(max_entry_bytes) = entry.max_entry_bytes {
+            policy.max_entry_bytes = max_entry_bytes;
+        }
+        if let Some(path_fields) = entry.path_fields {
+            policy.path_fields = path_fields;
+        }
+    }
+    Ok(tools)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ToolCachePolicyConfig {
+    /// Lifetime of a cached result; `0` disables caching for the tool.
+    pub ttl_s: u64,
+    pub max_entry_bytes: usize,
+    /// Input fields naming workspace paths: the size and mtime of each path
+    /// (recursively for directories) join the cache key, so edits invalidate
+    /// the entry. A declared field missing from the input fingerprints the
+    /// workspace root.
+    pub path_fields: Vec<String>,
+}
+
+impl Default for ToolCachePolicyConfig {
+    fn default() -> Self {
+        Self {
+            ttl_s: 300,
+            max_entry_bytes: 256 * 1024,
+            path_fields: Vec::new(),
         }
     }
 }
@@ -443,6 +774,7 @@ pub struct McpConfig {
     pub bridge_host: String,
     pub bridge_port: u16,
     pub bridge_token_header: String,
+    pub bridge_max_request_bytes: usize,
     pub servers: Vec<McpServerConfig>,
 }
 
@@ -453,6 +785,7 @@ impl Default for McpConfig {
             bridge_host: "127.0.0.1".to_string(),
             bridge_port: 0,
             bridge_token_header: "X-AgenticOS-MCP-Bridge-Token".to_string(),
+            bridge_max_request_bytes: 1024 * 1024,
             servers: Vec::new(),
         }
     }
@@ -521,6 +854,21 @@ pub enum McpTransportConfig {
         #[serde(default = "default_mcp_timeout_ms")]
         timeout_ms: u64,
     },
+    /// Remote or containerized server reached over the MCP streamable HTTP
+    /// transport. Secrets never live in the config: `bearer_token_env` and
+    /// `headers_env` name variables loaded from the env file.
+    StreamableHttp {
+        url: String,
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+        #[serde(default)]
+        bearer_token_env: Option<String>,
+        /// Header name -> environment variable holding its value.
+        #[serde(default)]
+        headers_env: BTreeMap<String, String>,
+        #[serde(default = "default_mcp_timeout_ms")]
+        timeout_ms: u64,
+    },
 }
 
 impl Default for McpTransportConfig {
@@ -539,6 +887,7 @@ impl McpTransportConfig {
     pub fn kind(&self) -> &'static str {
         match self {
             Self::Stdio { .. } => "stdio",
+            Self::StreamableHttp { .. } => "streamable_http",
         }
     }
 }
@@ -547,6 +896,56 @@ fn default_mcp_timeout_ms() -> u64 {
     15_000
 }
 
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct OpenAIApiConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub request_timeout_ms: u64,
+    pub max_request_bytes: usize,
+}
+
+impl Default for OpenAIApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: 6381,
+            request_timeout_ms: 600_000,
+            max_request_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct McpExportConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub max_request_bytes: usize,
+    pub exposed_tools: Vec<String>,
+    pub allow_dangerous_tools: bool,
+    pub resource_list_limit: usize,
+    pub workflow_templates_dir: PathBuf,
+}
+
+impl Default for McpExportConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "127.0.0.1".to_string(),
+            port: 6382,
+            max_request_bytes: 1024 * 1024,
+            exposed_tools: Vec::new(),
+            allow_dangerous_tools: false,
+            resource_list_limit: 100,
+            workflow_templates_dir: repository_path("workspace/workflow_templates"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct GenerationProfilesConfig {
@@ -613,6 +1012,17 @@ impl Default for GenerationProfile {
 #[derive(Debug, Clone, Deserialize)]
 #[serde(default)]
 pub struct SchedulerConfig {
+    /// Dispatch ordering: `fair_share`, `deadline` or `priority` (strict).
+    pub policy: String,
+    /// Fair-share grouping: `process`, `owner` or `session`.
+    pub fairness_scope: String,
+    /// Waiting time that earns a process one step of service credit.
+    pub aging_ms: u64,
+    /// Processes queued at the inference worker at once; `0` = unbounded.
+    pub max_in_flight: usize,
+    /// Share of a wall-clock, cost, remote-input or tool-time limit at which
+    /// the process is warned before the hard stop.
+    pub quota_warning_ratio: f64,
     pub fast: SchedulerQuotaConfig,
     pub code: SchedulerQuotaConfig,
     pub reasoning: SchedulerQuotaConfig,
@@ -622,21 +1032,30 @@ pub struct SchedulerConfig {
 impl Default for SchedulerConfig {
     fn default() -> Self {
         Self {
+            policy: "priority".to_string(),
+            fairness_scope: "process".to_string(),
+            aging_ms: 2_000,
+            max_in_flight: 0,
+            quota_warning_ratio: 0.8,
             fast: SchedulerQuotaConfig {
                 max_tokens: 512,
                 max_syscalls: 2,
+                ..SchedulerQuotaConfig::default()
             },
             code: SchedulerQuotaConfig {
                 max_tokens: 4096,
                 max_syscalls: 16,
+                ..SchedulerQuotaConfig::default()
             },
             reasoning: SchedulerQuotaConfig {
                 max_tokens: 8192,
                 max_syscalls: 8,
+                ..SchedulerQuotaConfig::default()
             },
             general: SchedulerQuotaConfig {
                 max_tokens: 2048,
                 max_syscalls: 8,
+                ..SchedulerQuotaConfig::default()
             },
         }
     }
@@ -647,6 +1066,10 @@ impl Default for SchedulerConfig {
 pub struct SchedulerQuotaConfig {
     pub max_tokens: usize,
     pub max_syscalls: usize,
+    pub max_wall_clock_secs: Option<u64>,
+    pub max_cost_usd: Option<f64>,
+    pub max_remote_input_tokens: Option<u64>,
+    pub max_tool_wall_ms: Option<u64>,
 }
 
 impl Default for SchedulerQuotaConfig {
@@ -654,6 +1077,116 @@ impl Default for SchedulerQuotaConfig {
         Self {
             max_tokens: 2048,
             max_syscalls: 8,
+            max_wall_clock_secs: None,
+            max_cost_usd: None,
+            max_remote_input_tokens: None,
+            max_tool_wall_ms: None,
+        }
+    }
+}
+
+/// Spending ceilings on remote inference, checked before every remote step.
+#[derive(Debug, Clone, Deserialize, Default)]
+#[serde(default)]
+pub struct BudgetsConfig {
+    /// Action when a budget without its own `on_exhausted` runs out.
+    pub on_exhausted: BudgetAction,
+    pub limits: Vec<BudgetLimitConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BudgetLimitConfig {
+    /// Stable name shown in STATUS and events; derived from scope, target and
+    /// window when empty.
+    pub id: String,
+    pub scope: BudgetScope,
+    /// Provider or model id for `provider`/`model` budgets; when unset the
+    /// limit applies to each provider, model, session or orchestration.
+    pub target: Option<String>,
+    pub window: BudgetWindow,
+    /// Window length of `rolling` budgets.
+    pub rolling_secs: u64,
+    pub max_usd: f64,
+    pub on_exhausted: Option<BudgetAction>,
+}
+
+impl Default for BudgetLimitConfig {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            scope: BudgetScope::Global,
+            target: None,
+            window: BudgetWindow::Daily,
+            rolling_secs: 3_600,
+            max_usd: 0.0,
+            on_exhausted: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetScope {
+    #[default]
+    Global,
+    Provider,
+    Model,
+    Session,
+    Orchestration,
+}
+
+impl BudgetScope {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Provider => "provider",
+            Self::Model => "model",
+            Self::Session => "session",
+            Self::Orchestration => "orchestration",
+        }
+    }
+}
+
+/// Daily and monthly windows reset at UTC midnight and on the first of the
+/// month; rolling windows cover the last `rolling_secs`.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetWindow {
+    #[default]
+    Daily,
+    Monthly,
+    Rolling,
+}
+
+impl BudgetWindow {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Monthly => "monthly",
+            Self::Rolling => "rolling",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetAction {
+    /// Stop the process with `budget_exhausted`.
+    #[default]
+    Fail,
+    /// Move the session onto a local model and continue there.
+    RouteLocal,
+    /// Park the process on a human approval request.
+    AwaitApproval,
+}
+
+impl BudgetAction {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fail => "fail",
+            Self::RouteLocal => "route_local",
+            Self::AwaitApproval => "await_approval",
         }
     }
 }
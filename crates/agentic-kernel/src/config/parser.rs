@@ -167,9 +167,12 @@ pub(crate) fn normalize_config_paths(config: &mut KernelConfig, config_path: &Pa
     absolutize_from(&base_dir, &mut config.paths.remote_provider_catalog_path);
     absolutize_from(&base_dir, &mut config.memory.swap_dir);
     absolutize_from(&base_dir, &mut config.core_dump.dump_dir);
+    absolutize_from(&base_dir, &mut config.mcp_export.workflow_templates_dir);
+    absolutize_from(&base_dir, &mut config.tools.manifest_dir);
     for server in &mut config.mcp.servers {
-        let crate::config::McpTransportConfig::Stdio { cwd, .. } = &mut server.transport;
-        if let Some(cwd) = cwd.as_mut() {
+        if let crate::config::McpTransportConfig::Stdio { cwd: Some(cwd), .. } =
+            &mut server.transport
+        {
             absolutize_from(&base_dir, cwd);
         }
     }
@@ -226,6 +229,15 @@ pub(crate) fn apply_env_overrides(config: &mut KernelConfig) {
     if let Some(value) = env_string("AGENTIC_MEMORY_SWAP_DIR") {
         config.memory.swap_dir = PathBuf::from(value);
     }
+    if let Some(value) = env_u64_opt("AGENTIC_MEMORY_SWAP_MAX_BYTES") {
+        config.memory.swap_max_bytes = value;
+    }
+    if let Some(value) = env_bool_opt("AGENTIC_MEMORY_LONG_TERM_ENABLED") {
+        config.memory.long_term.enabled = value;
+    }
+    if let Some(value) = env_bool_opt("AGENTIC_MEMORY_LONG_TERM_AUTO_INJECT") {
+        config.memory.long_term.auto_inject = value;
+    }
     if let Some(value) = env_string("AGENTIC_CONTEXT_DEFAULT_STRATEGY") {
         config.context.default_strategy = value;
     }
@@ -250,9 +262,39 @@ pub(crate) fn apply_env_overrides(config: &mut KernelConfig) {
     if let Some(value) = env_f64_opt("AGENTIC_CONTEXT_RETRIEVE_MIN_SCORE") {
         config.context.retrieve_min_score = value.max(0.0);
     }
+    if let Some(value) = env_string("AGENTIC_CONTEXT_SUMMARIZER_MODE") {
+        config.context.summarizer.mode = value;
+    }
+    if let Some(value) = env_string("AGENTIC_CONTEXT_SUMMARIZER_MODEL") {
+        config.context.summarizer.model = value;
+    }
+    if let Some(value) = env_u64_opt("AGENTIC_CONTEXT_SUMMARIZER_TIMEOUT_MS") {
+        config.context.summarizer.timeout_ms = value;
+    }
+    if let Some(value) = env_string("AGENTIC_CONTEXT_EMBEDDINGS_PROVIDER") {
+        config.context.embeddings.provider = value;
+    }
+    if let Some(value) = env_string("AGENTIC_CONTEXT_EMBEDDINGS_MODEL") {
+        config.context.embeddings.model = value;
+    }
+    if let Some(value) = env_string("AGENTIC_CONTEXT_EMBEDDINGS_ENDPOINT") {
+        config.context.embeddings.endpoint = value;
+    }
+    if let Some(value) = env_f64_opt("AGENTIC_CONTEXT_EMBEDDINGS_VECTOR_WEIGHT") {
+        config.context.embeddings.vector_weight = value.clamp(0.0, 1.0);
+    }
     if let Some(value) = env_u64_opt("AGENTIC_CHECKPOINT_INTERVAL_SECS") {
         config.checkpoint.interval_secs = value;
     }
+    if let Some(value) = env_bool_opt("AGENTIC_CHECKPOINT_JOURNAL_ENABLED") {
+        config.checkpoint.journal_enabled = value;
+    }
+    if let Some(value) = env_usize_opt("AGENTIC_CHECKPOINT_RETAINED_GENERATIONS") {
+        config.checkpoint.retained_generations = value.max(1);
+    }
+    if let Some(value) = env_usize_opt("AGENTIC_CHECKPOINT_MAX_DELTAS_PER_GENERATION") {
+        config.checkpoint.max_deltas_per_generation = value.max(1);
+    }
     if let Some(value) = env_bool_opt("AGENTIC_CORE_DUMP_ENABLED") {
         config.core_dump.enabled = value;
     }
@@ -307,6 +349,9 @@ pub(crate) fn apply_env_overrides(config: &mut KernelConfig) {
     if let Some(value) = env_usize_opt("AGENTIC_LLAMACPP_CHUNK_TOKENS") {
         config.external_llamacpp.chunk_tokens = value.max(1);
     }
+    if let Some(value) = env_bool_opt("AGENTIC_LLAMACPP_PREFIX_CACHE") {
+        config.external_llamacpp.prefix_cache.enabled = value;
+    }
     if let Some(value) = env_string("AGENTIC_OPENAI_ENDPOINT") {
         config.openai_responses.endpoint = value;
     }
@@ -415,6 +460,21 @@ pub(crate) fn apply_env_overrides(config: &mut KernelConfig) {
     if let Some(value) = env_usize_opt("AGENTIC_ORCH_MAX_OUTPUT_CHARS") {
         config.orchestrator.max_output_chars = value.max(1);
     }
+    if let Some(value) = env_usize_opt("AGENTIC_JOBS_MAX_CONCURRENT_RUNS") {
+        config.jobs.max_concurrent_runs = value.max(1);
+    }
+    if let Some(value) = env_string("AGENTIC_SCHEDULER_POLICY") {
+        config.scheduler.policy = value;
+    }
+    if let Some(value) = env_string("AGENTIC_SCHEDULER_FAIRNESS_SCOPE") {
+        config.scheduler.fairness_scope = value;
+    }
+    if let Some(value) = env_u64_opt("AGENTIC_SCHEDULER_AGING_MS") {
+        config.scheduler.aging_ms = value.max(1);
+    }
+    if let Some(value) = env_usize_opt("AGENTIC_SCHEDULER_MAX_IN_FLIGHT") {
+        config.scheduler.max_in_flight = value;
+    }
     if let Some(value) = env_string("AGENTIC_SANDBOX_MODE") {
         config.tools.sandbox_mode = value;
     }
@@ -449,4 +509,37 @@ pub(crate) fn apply_env_overrides(config: &mut KernelConfig) {
     if let Some(value) = env_bool_opt("AGENTIC_MCP_ENABLED") {
         config.mcp.enabled = value;
     }
+    if let Some(value) = env_bool_opt("AGENTIC_OPENAI_API_ENABLED") {
+        config.openai_api.enabled = value;
+    }
+    if let Some(value) = env_string("AGENTIC_OPENAI_API_HOST") {
+        config.openai_api.host = value;
+    }
+    if let Some(value) = env_u16("AGENTIC_OPENAI_API_PORT") {
+        config.openai_api.port = value;
+    }
+    if let Some(value) = env_u64_opt("AGENTIC_OPENAI_API_TIMEOUT_MS") {
+        config.openai_api.request_timeout_ms = value.max(1);
+    }
+    if let Some(value) = env_bool_opt("AGENTIC_MCP_EXPORT_ENABLED") {
+        config.mcp_export.enabled = value;
+    }
+    if let Some(value) = env_string("AGENTIC_MCP_EXPORT_HOST") {
+        config.mcp_export.host = value;
+    }
+    if let Some(value) = env_u16("AGENTIC_MCP_EXPORT_PORT") {
+        config.mcp_export.port = value;
+    }
+    if let Some(value) = env_string("AGENTIC_MCP_EXPORT_TEMPLATES_DIR") {
+        config.mcp_export.workflow_templates_dir = PathBuf::from(value);
+    }
+    if let Some(value) = env_string("AGENTIC_TOOLS_MANIFEST_DIR") {
+        config.tools.manifest_dir = PathBuf::from(value);
+    }
+    if let Some(value) = env_bool_opt("AGENTIC_TOOLS_KB_ENABLED") {
+        config.tools.knowledge_base.enabled = value;
+    }
+    if let Some(value) = env_bool_opt("AGENTIC_TOOLS_KB_EMBEDDINGS") {
+        config.tools.knowledge_base.embeddings = value;
+    }
 }
@@ -242,6 +242,8 @@ pub(crate) struct CoreDumpToolInvocation {
     #[serde(default)]
     pub duration_ms: Option<u128>,
     pub kill: bool,
+    #[serde(default)]
+    pub cache_hit: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
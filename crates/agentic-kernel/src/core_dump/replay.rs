@@ -474,6 +474,7 @@ fn recorded_tool_outcome(invocation: &CoreDumpToolInvocation) -> Option<SysCallO
         warnings: invocation.warnings.clone(),
         error_kind: invocation.error_kind.clone(),
         effects: invocation.effects.clone(),
+        cache_hit: invocation.cache_hit,
     })
 }
 
@@ -49,6 +49,7 @@ pub(crate) fn load_manifest_tool_invocation_history(
                 effects,
                 duration_ms: record.duration_ms,
                 kill: record.kill,
+                cache_hit: record.cache_hit,
             })
         })
         .collect()
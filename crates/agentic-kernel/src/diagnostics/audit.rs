@@ -72,6 +72,11 @@ pub(crate) const PROCESS_SPAWNED: AuditSpec = AuditSpec {
     kind: "spawned",
     title: "Process spawned",
 };
+pub(crate) const PROCESS_RESTORED: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "restored",
+    title: "Process restored from checkpoint",
+};
 pub(crate) const PROCESS_REPLAY_STARTED: AuditSpec = AuditSpec {
     category: "process",
     kind: "replay_started",
@@ -107,11 +112,41 @@ pub(crate) const PROCESS_TURN_COMPLETED: AuditSpec = AuditSpec {
     kind: "output_turn_completed",
     title: "Turn completed",
 };
+pub(crate) const PROCESS_CONTEXT_SUMMARIZED: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "context_summarized",
+    title: "Context summarized",
+};
+pub(crate) const PROCESS_MEMORY_INJECTED: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "memory_injected",
+    title: "Long-term memory injected",
+};
+pub(crate) const PROCESS_CONTEXT_CONTROL: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "context_control",
+    title: "Context control applied",
+};
 pub(crate) const PROCESS_TERMINATED: AuditSpec = AuditSpec {
     category: "process",
     kind: "terminated",
     title: "Termination requested",
 };
+pub(crate) const PROCESS_QUOTA_WARNING: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "quota_warning",
+    title: "Quota warning",
+};
+pub(crate) const PROCESS_QUOTA_EXCEEDED: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "quota_exceeded",
+    title: "Quota exceeded",
+};
+pub(crate) const PROCESS_BUDGET_EXHAUSTED: AuditSpec = AuditSpec {
+    category: "process",
+    kind: "budget_exhausted",
+    title: "Spending budget exhausted",
+};
 pub(crate) const PROCESS_KILLED: AuditSpec = AuditSpec {
     category: "process",
     kind: "killed",
@@ -197,10 +232,10 @@ pub(crate) const KERNEL_BOOT_RECOVERED: AuditSpec = AuditSpec {
     title: "Boot recovery applied",
 };
 
-pub(crate) const KERNEL_LEGACY_RESTORE_APPLIED: AuditSpec = AuditSpec {
+pub(crate) const KERNEL_RESTORE_APPLIED: AuditSpec = AuditSpec {
     category: "kernel",
-    kind: "legacy_restore_applied",
-    title: "Legacy restore applied",
+    kind: "restore_applied",
+    title: "Checkpoint restore applied",
 };
 
 #[derive(Debug, Clone, Default)]
@@ -1,8 +1,13 @@
-pub(crate) fn initialize_subscriber() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::try_from_default_env()
-                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
-        )
-        .init();
+/// Con `log_to_stderr` i log non sporcano stdout, riservato al trasporto
+/// MCP stdio quando il kernel viene lanciato da un client MCP.
+pub(crate) fn initialize_subscriber(log_to_stderr: bool) {
+    let builder = tracing_subscriber::fmt().with_env_filter(
+        tracing_subscriber::EnvFilter::try_from_default_env()
+            .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+    );
+    if log_to_stderr {
+        builder.with_writer(std::io::stderr).init();
+    } else {
+        builder.init();
+    }
 }
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 
 use crate::backend::{DriverResolution, RuntimeModel};
 use crate::memory::ContextSlotId;
-use crate::model_catalog::{ModelMetadata, ResolvedModelTarget};
+use crate::model_catalog::{ContextWindowResolution, ModelMetadata, ResolvedModelTarget};
 use crate::process::{
     AgentProcess, ContextPolicy, InitialContextSeed, ProcessLifecyclePolicy, ProcessState,
     ResidentSlotPolicy,
@@ -86,6 +86,12 @@ impl LLMEngine {
             eot_token_id,
             "ENGINE: Special Tokens Identified"
         );
+        let context_window = resolve_loaded_context_window(&model, metadata.as_ref(), target);
+        tracing::info!(
+            window_tokens = context_window.window_tokens,
+            source = context_window.source,
+            "ENGINE: Context window resolved"
+        );
         tracing::info!("ENGINE: Master Model & Tokenizer Ready. Backend abstraction enabled.");
 
         Ok(Self {
@@ -101,6 +107,7 @@ impl LLMEngine {
             next_pid: 1,
             family: resolved_family,
             metadata,
+            context_window,
             generation: crate::policy::generation_defaults(resolved_family),
             eos_token_id,
             eot_token_id,
@@ -411,6 +418,25 @@ impl LLMEngine {
         Ok(())
     }
 
+    /// Prefix a process that has not run yet with long-term memories, as a
+    /// system message held in a `RetrievedMemory` segment.
+    pub fn inject_retrieved_memory(&mut self, pid: u64, text: &str) -> Result<usize> {
+        let formatted_text = self.format_system_message(text);
+        let new_tokens = self
+            .tokenizer
+            .encode(formatted_text.as_str(), true)
+            .map_err(E::msg)?
+            .get_ids()
+            .to_vec();
+        let injected_len = new_tokens.len();
+        let process = self
+            .processes
+            .get_mut(&pid)
+            .ok_or(E::msg("PID not found"))?;
+        process.prepend_retrieved_memory(&formatted_text, new_tokens);
+        Ok(injected_len)
+    }
+
     pub fn send_user_input(&mut self, pid: u64, prompt: &str) -> Result<()> {
         let formatted_prompt =
             format_user_message_with_metadata(prompt, self.family, self.metadata.as_ref());
@@ -631,15 +657,14 @@ impl LLMEngine {
         self.metadata.as_ref()
     }
 
+    pub fn context_window(&self) -> &ContextWindowResolution {
+        &self.context_window
+    }
+
+    /// Finestra risolta al load; `None` se nessuna sorgente la dichiara e
+    /// resta valido il default di `[context]`.
     pub fn effective_context_window_tokens(&self) -> Option<usize> {
-        self.metadata
-            .as_ref()
-            .and_then(|metadata| metadata.max_context_tokens)
-            .or_else(|| {
-                self.loaded_remote_model
-                    .as_ref()
-                    .and_then(|model| model.context_window_tokens)
-            })
+        (!self.context_window.is_config_default()).then_some(self.context_window.window_tokens)
     }
 
     pub fn free_context_slot(&mut self, slot_id: ContextSlotId) -> Result<()> {
@@ -677,6 +702,36 @@ impl LLMEngine {
     }
 }
 
+/// Massimo del modello (o del catalogo remoto), ristretto dal `-c` del runtime
+/// e, per i backend residenti, dalla memoria rimasta per la KV cache.
+fn resolve_loaded_context_window(
+    model: &RuntimeModel,
+    metadata: Option<&ModelMetadata>,
+    target: &ResolvedModelTarget,
+) -> ContextWindowResolution {
+    let remote_window = target
+        .remote_model_view()
+        .and_then(|model| model.context_window_tokens);
+    let memory_cap = if target.driver_resolution().backend_class
+        == crate::backend::BackendClass::ResidentLocal
+    {
+        crate::resource_governor::estimate_context_window_cap(
+            target.display_path(),
+            &target.logical_model_id(),
+            metadata.and_then(|metadata| metadata.kv_cache_bytes_per_token),
+            &crate::config::kernel_config().resources,
+        )
+    } else {
+        None
+    };
+    ContextWindowResolution::resolve(
+        ContextWindowResolution::declared_window(metadata, remote_window),
+        model.runtime_context_window_tokens(),
+        memory_cap,
+        crate::config::kernel_config().context.default_window_tokens,
+    )
+}
+
 #[cfg(test)]
 #[path = "tests/lifecycle.rs"]
 mod tests;
@@ -7,7 +7,7 @@ use agentic_control_models::RemoteModelRuntimeView;
 use tokenizers::Tokenizer;
 
 use crate::backend::RuntimeModel;
-use crate::model_catalog::ModelMetadata;
+use crate::model_catalog::{ContextWindowResolution, ModelMetadata};
 use crate::process::AgentProcess;
 use crate::prompting::{GenerationConfig, PromptFamily};
 use slot_manager::ResidentSlotManager;
@@ -25,6 +25,7 @@ pub struct LLMEngine {
     pub(super) next_pid: u64,
     pub(super) family: PromptFamily,
     pub(super) metadata: Option<ModelMetadata>,
+    pub(super) context_window: ContextWindowResolution,
     pub(super) generation: GenerationConfig,
     pub(super) eos_token_id: u32,
     pub(super) eot_token_id: u32,
@@ -5,7 +5,7 @@ use crate::backend::{
 };
 use crate::engine::slot_manager::ResidentSlotManager;
 use crate::memory::ContextSlotId;
-use crate::model_catalog::ModelCatalog;
+use crate::model_catalog::{ContextWindowResolution, ModelCatalog};
 use crate::process::{
     AgentProcess, ContextPolicy, ContextStrategy, HumanInputRequest, HumanInputRequestKind,
     InitialContextSeed, ProcessLifecyclePolicy, ProcessState, ResidentSlotPolicy,
@@ -151,6 +151,7 @@ fn test_engine() -> (LLMEngine, SavedSlots, SavedSlots, FreedSlots) {
             next_pid: 2,
             family: PromptFamily::Qwen,
             metadata: None,
+            context_window: ContextWindowResolution::resolve(None, None, None, 2048),
             generation,
             eos_token_id: 0,
             eot_token_id: 0,
@@ -115,6 +115,9 @@ pub enum OrchestratorError {
     #[error("task permission policy is invalid: {0}")]
     InvalidTaskPermissions(String),
 
+    #[error("task '{task}' quota is invalid: {reason}")]
+    InvalidTaskQuota { task: String, reason: String },
+
     #[error("orchestration {orchestration_id} has no task '{task}'")]
     RetryTaskNotFound { orchestration_id: u64, task: String },
 
@@ -17,10 +17,12 @@ use crate::resource_governor::ResourceGovernor;
 use crate::runtime::syscalls::{self, SyscallCmd, SyscallCompletion};
 use crate::runtime::TurnAssemblyStore;
 use crate::runtimes::RuntimeRegistry;
-use crate::scheduler::ProcessScheduler;
+use crate::scheduler::{FairShareConfig, ProcessScheduler};
+use crate::services::checkpoint_journal::CheckpointJournal;
+use crate::services::job_runtime::JobToolRunner;
 use crate::services::job_scheduler::JobScheduler;
 use crate::session::SessionRegistry;
-use crate::storage::{BootRecoveryReport, StorageService};
+use crate::storage::{current_timestamp_ms, BootRecoveryReport, StorageService};
 use crate::tool_registry::ToolRegistry;
 use crate::tools::SyscallRateMap;
 use crate::transport::Client;
@@ -65,8 +67,9 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         Arc::clone(&syscall_rates),
         syscall_result_tx,
         syscall_cmd_rx,
-        Some(worker_waker),
+        Some(worker_waker.clone()),
     );
+    let job_tool_runner = JobToolRunner::new(Arc::clone(&syscall_rates), Some(worker_waker));
 
     // 5. Setup dello storage SQLite, record di boot e procedure di recovery
     let mut storage =
@@ -83,7 +86,9 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         ResourceGovernor::load(&mut storage, config.resources.clone()).map_err(io::Error::other)?;
     let session_registry =
         SessionRegistry::load(&mut storage, boot_record.boot_id).map_err(io::Error::other)?;
-    let job_scheduler = JobScheduler::load(&mut storage).map_err(io::Error::other)?;
+    let mut job_scheduler = JobScheduler::load(&mut storage).map_err(io::Error::other)?;
+    job_scheduler.set_max_concurrent_runs(config.jobs.max_concurrent_runs);
+    let scheduler = build_scheduler(config, &storage);
 
     // 6. Generazione del token di autenticazione per la sessione corrente
     let auth_disabled = config.auth.disabled;
@@ -95,6 +100,31 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         .as_ref()
         .map(|bridge| bridge.registered_tool_names().len())
         .unwrap_or(0);
+    let restored_runtime_tools =
+        crate::tools::registry_store::restore_runtime_tools(&mut tool_registry, &storage);
+    let mut tool_manifests =
+        crate::tools::manifests::ToolManifestLoader::new(config.tools.manifest_dir.clone());
+    tool_manifests.reload(&mut tool_registry);
+    let mcp_export = crate::mcp::export::McpExportRuntime::start(
+        config,
+        &tool_registry,
+        Arc::clone(&syscall_rates),
+        (!auth_disabled).then(|| auth_token.clone()),
+        Arc::clone(&shutdown_requested),
+    )
+    .map_err(io::Error::other)?;
+    let mcp_export_addr = mcp_export
+        .as_ref()
+        .and_then(|export| export.listen_addr())
+        .map(|addr| addr.to_string())
+        .unwrap_or_default();
+    let listen_addr = server.local_addr()?;
+    let openai_api = crate::openai_api::OpenAIApiRuntime::start(config, listen_addr)
+        .map_err(io::Error::other)?;
+    let openai_api_addr = openai_api
+        .as_ref()
+        .map(|facade| facade.listen_addr().to_string())
+        .unwrap_or_default();
 
     tracing::info!(
         version = env!("CARGO_PKG_VERSION"),
@@ -122,6 +152,12 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         auth_disabled,
         mcp_enabled = config.mcp.enabled,
         mcp_registered_tools,
+        restored_runtime_tools,
+        tool_manifest_dir = %config.tools.manifest_dir.display(),
+        openai_api_enabled = config.openai_api.enabled,
+        openai_api_addr,
+        mcp_export_enabled = mcp_export.is_some(),
+        mcp_export_addr,
         "AgenticOS Kernel ready"
     );
 
@@ -138,8 +174,9 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         resource_governor,
         shutdown_requested,
         model_catalog,
-        scheduler: ProcessScheduler::new(),
+        scheduler,
         job_scheduler,
+        job_tool_runner,
         orchestrator: Orchestrator::new(),
         remote_deadline_timeout: std::time::Duration::from_millis(
             config
@@ -152,13 +189,16 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         syscall_deadline_timeout: std::time::Duration::from_secs(config.tools.timeout_s.max(1)),
         checkpoint_interval_secs,
         last_checkpoint: Instant::now(),
+        checkpoint_journal: CheckpointJournal::new(&config.checkpoint),
         cmd_tx,
         result_rx,
         syscall_cmd_tx,
+        syscall_rates,
         syscall_result_rx,
         in_flight: HashSet::new(),
         pending_kills: Vec::new(),
         pending_events: Vec::new(),
+        job_events_cursor: 0,
         turn_assembly: TurnAssemblyStore::default(),
         syscall_wait_since: HashMap::new(),
         remote_timeout_reported: HashSet::new(),
@@ -167,7 +207,10 @@ pub(crate) fn build_kernel(config: &config::KernelConfig) -> io::Result<Kernel>
         syscall_worker_handle: Some(syscall_worker_handle),
         metrics: MetricsState::new(),
         tool_registry,
+        tool_manifests,
         mcp_bridge,
+        mcp_export,
+        openai_api,
         auth_token,
         auth_disabled,
         session_registry,
@@ -190,6 +233,31 @@ fn build_memory(config: &config::KernelConfig) -> io::Result<NeuralMemory> {
     Ok(memory)
 }
 
+/// Costruisce lo scheduler con fair-share e budget di spesa.
+///
+/// Il ledger dei budget riparte dalla spesa remota già registrata in
+/// `accounting_events` dentro le finestre configurate.
+fn build_scheduler(config: &config::KernelConfig, storage: &StorageService) -> ProcessScheduler {
+    let mut scheduler =
+        ProcessScheduler::with_fair_share(FairShareConfig::from_config(&config.scheduler));
+    scheduler.configure_budgets(&config.budgets);
+    if !scheduler.budgets_enabled() {
+        return scheduler;
+    }
+    let now_ms = current_timestamp_ms();
+    match storage.accounting_spend_since(scheduler.budget_retention_start_ms(now_ms)) {
+        Ok(records) => {
+            for record in records {
+                scheduler.record_budget_spend(None, record);
+            }
+        }
+        Err(err) => {
+            tracing::warn!(%err, "BUDGET: failed to seed spend from accounting events");
+        }
+    }
+    scheduler
+}
+
 /// Genera un token randomico (32 byte), lo formatta in esadecimale e lo salva su disco.
 ///
 /// Questo token viene utilizzato dai client (come la GUI Tauri) per autenticarsi via TCP.
@@ -3,7 +3,7 @@ use mio::{Events, Interest, Poll, Token};
 use std::collections::{HashMap, HashSet};
 use std::io;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{mpsc, Arc};
+use std::sync::{mpsc, Arc, Mutex};
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 
@@ -11,27 +11,36 @@ use crate::backend::shutdown_managed_runtimes;
 use crate::checkpoint;
 use crate::commands::MetricsState;
 use crate::config;
+use crate::diagnostics::audit::AuditContext;
 use crate::engine::LLMEngine;
 use crate::events::flush_pending_events;
 use crate::inference_worker::{InferenceCmd, InferenceResult};
+use crate::mcp::bridge::McpResourceUpdateDelivery;
 use crate::memory::NeuralMemory;
 use crate::model_catalog::ModelCatalog;
 use crate::orchestrator::Orchestrator;
+use crate::process::ProcessState;
 use crate::resource_governor::ResourceGovernor;
 use crate::runtime::deadlines::{
     compute_poll_timeout, pick_next_deadline, DeadlineCandidate, DeadlineReason, NextDeadline,
 };
-use crate::runtime::run_engine_tick;
 use crate::runtime::syscalls::{SyscallCmd, SyscallCompletion};
 use crate::runtime::TurnAssemblyStore;
+use crate::runtime::{apply_quota_check, run_engine_tick};
 use crate::runtimes::RuntimeRegistry;
-use crate::scheduler::ProcessScheduler;
-use crate::services::job_scheduler::{JobScheduler, SCHEDULER_SYSTEM_OWNER_ID};
+use crate::scheduler::{ProcessScheduler, SchedulingPolicy};
+use crate::services::checkpoint_journal::{CheckpointJournal, CheckpointSnapshotDeps};
+use crate::services::job_runtime::{start_exec_job, start_session_input_job, JobToolRunner};
+use crate::services::job_scheduler::{
+    DueJobDispatch, JobAdmission, JobScheduler, ScheduledJobTarget, ScheduledRunHandle,
+    SCHEDULER_SYSTEM_OWNER_ID,
+};
 use crate::services::orchestration_runtime::{start_orchestration, OrchestrationStartError};
 use crate::services::process_runtime::kill_managed_process_with_session;
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 use crate::tool_registry::ToolRegistry;
+use crate::tools::SyscallRateMap;
 use crate::transport::{
     handle_read_with_registry, handle_write, needs_writable_interest, writable_interest, Client,
 };
@@ -67,18 +76,25 @@ pub(crate) struct Kernel {
     pub(crate) model_catalog: ModelCatalog,
     pub(crate) scheduler: ProcessScheduler,
     pub(crate) job_scheduler: JobScheduler,
+    pub(crate) job_tool_runner: JobToolRunner,
     pub(crate) orchestrator: Orchestrator,
     pub(crate) remote_deadline_timeout: Duration,
     pub(crate) syscall_deadline_timeout: Duration,
     pub(crate) checkpoint_interval_secs: u64,
     pub(crate) last_checkpoint: Instant,
+    pub(crate) checkpoint_journal: CheckpointJournal,
     pub(crate) cmd_tx: mpsc::Sender<InferenceCmd>,
     pub(crate) result_rx: mpsc::Receiver<InferenceResult>,
     pub(crate) syscall_cmd_tx: mpsc::Sender<SyscallCmd>,
+    /// Rate-limit state shared with the syscall worker, for the tools served
+    /// on the event loop.
+    pub(crate) syscall_rates: Arc<Mutex<SyscallRateMap>>,
     pub(crate) syscall_result_rx: mpsc::Receiver<SyscallCompletion>,
     pub(crate) in_flight: HashSet<u64>,
     pub(crate) pending_kills: Vec<u64>,
     pub(crate) pending_events: Vec<agentic_control_models::KernelEvent>,
+    /// Prefix of `pending_events` already scanned for scheduled process runs.
+    pub(crate) job_events_cursor: usize,
     pub(crate) turn_assembly: TurnAssemblyStore,
     pub(crate) syscall_wait_since: HashMap<u64, Instant>,
     pub(crate) remote_timeout_reported: HashSet<u64>,
@@ -87,7 +103,10 @@ pub(crate) struct Kernel {
     pub(crate) syscall_worker_handle: Option<JoinHandle<()>>,
     pub(crate) metrics: MetricsState,
     pub(crate) tool_registry: ToolRegistry,
+    pub(crate) tool_manifests: crate::tools::manifests::ToolManifestLoader,
     pub(crate) mcp_bridge: Option<crate::mcp::bridge::McpBridgeRuntime>,
+    pub(crate) mcp_export: Option<crate::mcp::export::McpExportRuntime>,
+    pub(crate) openai_api: Option<crate::openai_api::OpenAIApiRuntime>,
     pub(crate) auth_token: String,
     pub(crate) auth_disabled: bool,
     pub(crate) session_registry: SessionRegistry,
@@ -120,6 +139,12 @@ impl Kernel {
                 if let Some(mcp_bridge) = self.mcp_bridge.as_mut() {
                     mcp_bridge.shutdown();
                 }
+                if let Some(mcp_export) = self.mcp_export.as_mut() {
+                    mcp_export.shutdown();
+                }
+                if let Some(openai_api) = self.openai_api.as_mut() {
+                    openai_api.shutdown();
+                }
                 shutdown_managed_runtimes();
                 break;
             }
@@ -158,6 +183,7 @@ impl Kernel {
             }
 
             // 5. Avanzamento dell'engine LLM, schedulatore e orchestratore
+            self.sync_scheduling_deadlines();
             let tick_report = run_engine_tick(
                 &mut self.runtime_registry,
                 &mut self.resource_governor,
@@ -170,6 +196,7 @@ impl Kernel {
                 &self.cmd_tx,
                 &self.result_rx,
                 &self.syscall_cmd_tx,
+                &self.syscall_rates,
                 &self.syscall_result_rx,
                 &mut self.session_registry,
                 &mut self.storage,
@@ -179,6 +206,7 @@ impl Kernel {
                 &mut self.pending_events,
                 &self.tool_registry,
             );
+            self.reroute_budget_exhausted_processes();
             self.reconcile_scheduled_job_runs();
 
             let wake_reason = classify_wake_reason(
@@ -202,8 +230,10 @@ impl Kernel {
                 "KERNEL_LOOP_WAKE"
             );
 
+            self.journal_checkpoint(None);
             self.prune_remote_timeout_reports();
             self.drain_pending_diagnostics();
+            self.drain_lifecycle_events();
 
             // 6. Invio eventi asincroni accumulati ai client iscritti (es. GUI)
             flush_pending_events(
@@ -215,6 +245,7 @@ impl Kernel {
                 &mut self.turn_assembly,
                 &mut self.pending_events,
             );
+            self.job_events_cursor = 0;
         }
 
         Ok(())
@@ -226,6 +257,7 @@ impl Kernel {
     /// - Checkpoint periodico.
     /// - Timeout di richieste LLM remote (es. API OpenAI / Groq).
     /// - Timeout delle syscall in esecuzione.
+    /// - Quote wall-clock dei processi fermi (avviso e limite).
     /// - Manutenzione e backoff programmato.
     fn next_deadline(&self, now: Instant) -> Option<NextDeadline> {
         let mut candidates = Vec::new();
@@ -267,6 +299,18 @@ impl Kernel {
             });
         }
 
+        // I processi in inferenza vengono controllati al rientro dello step.
+        for (pid, at) in self.scheduler.wall_clock_quota_deadlines() {
+            if self.in_flight.contains(&pid) || !self.is_live_process(pid) {
+                continue;
+            }
+            candidates.push(DeadlineCandidate {
+                reason: DeadlineReason::WallClockQuota,
+                at,
+                subject_id: Some(pid),
+            });
+        }
+
         if let Some(next_run_at_ms) = self.job_scheduler.next_due_at_ms() {
             candidates.push(DeadlineCandidate {
                 reason: DeadlineReason::ScheduledJob,
@@ -283,6 +327,32 @@ impl Kernel {
             });
         }
 
+        if let Some(poll_at_ms) = self.job_scheduler.next_event_poll_at_ms() {
+            candidates.push(DeadlineCandidate {
+                reason: DeadlineReason::JobEventPoll,
+                at: instant_for_timestamp(now, now_ms, poll_at_ms),
+                subject_id: None,
+            });
+        }
+
+        if let Some(at) = self
+            .mcp_bridge
+            .as_ref()
+            .and_then(|mcp_bridge| mcp_bridge.next_resource_poll_at())
+        {
+            candidates.push(DeadlineCandidate {
+                reason: DeadlineReason::McpResourcePoll,
+                at,
+                subject_id: None,
+            });
+        }
+
+        candidates.push(DeadlineCandidate {
+            reason: DeadlineReason::ToolManifestPoll,
+            at: instant_for_timestamp(now, now_ms, self.tool_manifests.next_poll_at_ms()),
+            subject_id: None,
+        });
+
         let next = pick_next_deadline(&candidates);
         if next.is_none() {
             tracing::trace!("KERNEL_DEADLINE: no candidate; waiting for real event");
@@ -298,12 +368,17 @@ impl Kernel {
 
     fn handle_elapsed_deadline(&mut self, deadline: NextDeadline, now: Instant) {
         match deadline.reason {
+            DeadlineReason::Checkpoint if self.checkpoint_journal.enabled() => {
+                self.last_checkpoint = now;
+                self.journal_checkpoint(Some("interval"));
+            }
             DeadlineReason::Checkpoint => {
                 self.last_checkpoint = now;
                 run_auto_checkpoint(
                     self.runtime_registry.current_engine(),
                     &self.model_catalog,
                     &self.scheduler,
+                    &self.session_registry,
                     &self.metrics,
                     &self.memory,
                 );
@@ -324,72 +399,223 @@ impl Kernel {
             }
             DeadlineReason::ScheduledJob => self.dispatch_due_scheduled_jobs(),
             DeadlineReason::ScheduledJobTimeout => self.enforce_scheduled_job_timeouts(),
+            DeadlineReason::JobEventPoll => {
+                self.job_scheduler.poll_event_sources(&self.storage);
+                self.dispatch_due_scheduled_jobs();
+            }
+            DeadlineReason::McpResourcePoll => self.dispatch_mcp_resource_updates(),
+            DeadlineReason::ToolManifestPoll => {
+                self.tool_manifests.reload(&mut self.tool_registry);
+            }
+            DeadlineReason::WallClockQuota => {
+                if let Some(pid) = deadline.subject_id {
+                    self.enforce_wall_clock_quota(pid);
+                }
+            }
         }
     }
 
+    fn is_live_process(&self, pid: u64) -> bool {
+        self.runtime_registry
+            .runtime_id_for_pid(pid)
+            .and_then(|runtime_id| self.runtime_registry.engine(runtime_id))
+            .and_then(|engine| engine.processes.get(&pid))
+            .is_some_and(|process| process.state != ProcessState::Finished)
+    }
+
+    /// Quota wall-clock di un processo che non sta generando (in attesa di
+    /// input, di una syscall o di un umano): al primo superamento della soglia
+    /// riceve l'avviso, al limite viene chiuso e il tick successivo ne libera
+    /// le risorse come per ogni altro processo terminato.
+    fn enforce_wall_clock_quota(&mut self, pid: u64) {
+        if self.in_flight.contains(&pid) {
+            return;
+        }
+        let Some(runtime_id) = self
+            .runtime_registry
+            .runtime_id_for_pid(pid)
+            .map(ToString::to_string)
+        else {
+            return;
+        };
+        let audit_context = AuditContext::for_process(
+            self.session_registry.session_id_for_pid(pid),
+            pid,
+            Some(&runtime_id),
+        );
+        let check = self.scheduler.check_wall_clock(pid);
+        let Some(engine) = self.runtime_registry.engine_mut(&runtime_id) else {
+            return;
+        };
+        if apply_quota_check(engine, &mut self.storage, pid, check, audit_context) {
+            self.syscall_wait_since.remove(&pid);
+        }
+    }
+
+    /// Sveglia i processi in attesa di input iscritti a una risorsa MCP
+    /// aggiornata; quelli occupati ricevono l'avviso al turno successivo.
+    fn dispatch_mcp_resource_updates(&mut self) {
+        let Some(mcp_bridge) = self.mcp_bridge.as_mut() else {
+            return;
+        };
+        let runtime_registry = &mut self.runtime_registry;
+        mcp_bridge.dispatch_resource_updates(|update| {
+            let Some(runtime_id) = runtime_registry
+                .runtime_id_for_pid(update.pid)
+                .map(ToString::to_string)
+            else {
+                return McpResourceUpdateDelivery::Dropped;
+            };
+            let Some(engine) = runtime_registry.engine_mut(&runtime_id) else {
+                return McpResourceUpdateDelivery::Dropped;
+            };
+            match engine.processes.get(&update.pid).map(|process| &process.state) {
+                None => McpResourceUpdateDelivery::Dropped,
+                Some(ProcessState::WaitingForInput) => {
+                    let notice = engine.format_system_message(&update.notice());
+                    match engine.inject_context(update.pid, &notice) {
+                        Ok(()) => {
+                            tracing::info!(
+                                pid = update.pid,
+                                server_id = update.server_id,
+                                uri = update.uri,
+                                "MCP_RESOURCE: woke subscribed process"
+                            );
+                            McpResourceUpdateDelivery::Delivered
+                        }
+                        Err(err) => {
+                            tracing::warn!(pid = update.pid, %err, "MCP_RESOURCE: wake-up injection failed");
+                            McpResourceUpdateDelivery::Deferred
+                        }
+                    }
+                }
+                Some(_) => McpResourceUpdateDelivery::Deferred,
+            }
+        });
+    }
+
     fn dispatch_due_scheduled_jobs(&mut self) {
         let due_job_ids = self
             .job_scheduler
             .due_job_ids(crate::storage::current_timestamp_ms());
         for job_id in due_job_ids {
-            let plan = match self.job_scheduler.dispatch_plan(job_id) {
-                Ok(plan) => plan,
+            // La policy di concorrenza e il limite globale decidono se il job
+            // parte, attende o salta l'occorrenza; la decisione finisce in `recent_runs`.
+            let plan = match self.job_scheduler.admit_due_job(&mut self.storage, job_id) {
+                Ok(JobAdmission::Start { plan, replaced }) => {
+                    if let Some(handle) = replaced {
+                        self.stop_scheduled_run(handle, "scheduled_job_replaced");
+                    }
+                    plan
+                }
+                Ok(JobAdmission::Held) => {
+                    self.pending_events
+                        .push(agentic_control_models::KernelEvent::LobbyChanged {
+                            reason: "scheduled_job_held".to_string(),
+                        });
+                    continue;
+                }
+                Ok(JobAdmission::Waiting) => continue,
                 Err(err) => {
-                    tracing::warn!(job_id, %err, "SCHEDULER: failed to build dispatch plan");
+                    tracing::warn!(job_id, %err, "SCHEDULER: failed to admit due job");
                     continue;
                 }
             };
 
-            match start_orchestration(
-                &mut self.runtime_registry,
-                &mut self.resource_governor,
-                &mut self.memory,
-                &mut self.model_catalog,
-                &mut self.scheduler,
-                &mut self.orchestrator,
-                &mut self.session_registry,
-                &mut self.storage,
-                &mut self.pending_events,
-                &self.tool_registry,
-                SCHEDULER_SYSTEM_OWNER_ID,
-                plan.workflow,
-            ) {
-                Ok(started) => {
-                    for _ in 0..started.spawned {
-                        self.metrics.inc_exec_started();
-                    }
-                    if let Err(err) = self.job_scheduler.mark_started(
-                        &mut self.storage,
-                        plan.job_id,
-                        plan.trigger_at_ms,
-                        plan.attempt,
-                        started.orchestration_id,
-                    ) {
-                        tracing::error!(
-                            job_id = plan.job_id,
-                            orchestration_id = started.orchestration_id,
-                            %err,
-                            "SCHEDULER: failed to persist running job state"
-                        );
+            let mut tool_call = None;
+            let started = match &plan.target {
+                ScheduledJobTarget::Workflow { .. } => self.start_scheduled_workflow(&plan),
+                ScheduledJobTarget::Exec(target) => start_exec_job(
+                    &mut self.runtime_registry,
+                    &mut self.resource_governor,
+                    &mut self.memory,
+                    &mut self.model_catalog,
+                    &mut self.scheduler,
+                    &mut self.session_registry,
+                    &mut self.storage,
+                    &mut self.pending_events,
+                    &self.tool_registry,
+                    target,
+                    &plan.prompt_with_trigger(&target.prompt),
+                )
+                .map(|pid| {
+                    self.metrics.inc_exec_started();
+                    ScheduledRunHandle::Process(pid)
+                }),
+                ScheduledJobTarget::SendInput(target) => start_session_input_job(
+                    &mut self.runtime_registry,
+                    &mut self.resource_governor,
+                    &mut self.memory,
+                    &mut self.model_catalog,
+                    &mut self.scheduler,
+                    &mut self.session_registry,
+                    &mut self.storage,
+                    &mut self.turn_assembly,
+                    &mut self.pending_events,
+                    &self.tool_registry,
+                    target,
+                    &plan.prompt_with_trigger(&target.prompt),
+                )
+                .map(ScheduledRunHandle::SessionInput),
+                ScheduledJobTarget::Tool(target) => {
+                    JobToolRunner::prepare(&self.tool_registry, target).map(|prepared| {
+                        tool_call = Some(prepared);
+                        ScheduledRunHandle::Tool
+                    })
+                }
+            };
+
+            match started {
+                Ok(handle) => {
+                    let marked = if plan.overlap {
+                        self.job_scheduler
+                            .mark_overlap_started(&mut self.storage, &plan, handle)
+                    } else {
+                        self.job_scheduler.mark_started(
+                            &mut self.storage,
+                            plan.job_id,
+                            plan.trigger_at_ms,
+                            plan.attempt,
+                            handle,
+                        )
+                    };
+                    let run_id = match marked {
+                        Ok(run_id) => run_id,
+                        Err(err) => {
+                            tracing::error!(
+                                job_id = plan.job_id,
+                                ?handle,
+                                %err,
+                                "SCHEDULER: failed to persist running job state"
+                            );
+                            continue;
+                        }
+                    };
+                    if let Some((invocation, context)) = tool_call {
+                        self.spawn_scheduled_tool_call(plan.job_id, run_id, invocation, context);
                     }
                     self.pending_events
                         .push(agentic_control_models::KernelEvent::LobbyChanged {
                             reason: "scheduled_job_started".to_string(),
                         });
                 }
-                Err(err) => {
-                    let detail = match err {
-                        OrchestrationStartError::NoModelLoaded => "no_model_loaded".to_string(),
-                        OrchestrationStartError::InvalidGraph(inner) => inner.to_string(),
-                        OrchestrationStartError::RoutingFailed(inner) => inner,
+                Err(detail) => {
+                    let persisted = if plan.overlap {
+                        self.job_scheduler.mark_overlap_dispatch_failed(
+                            &mut self.storage,
+                            &plan,
+                            &detail,
+                        )
+                    } else {
+                        self.job_scheduler.mark_dispatch_failed(
+                            &mut self.storage,
+                            plan.job_id,
+                            plan.trigger_at_ms,
+                            plan.attempt,
+                            &detail,
+                        )
                     };
-                    if let Err(persist_err) = self.job_scheduler.mark_dispatch_failed(
-                        &mut self.storage,
-                        plan.job_id,
-                        plan.trigger_at_ms,
-                        plan.attempt,
-                        &detail,
-                    ) {
+                    if let Err(persist_err) = persisted {
                         tracing::error!(
                             job_id = plan.job_id,
                             %persist_err,
@@ -405,7 +631,120 @@ impl Kernel {
         }
     }
 
+    fn start_scheduled_workflow(
+        &mut self,
+        plan: &DueJobDispatch,
+    ) -> Result<ScheduledRunHandle, String> {
+        let ScheduledJobTarget::Workflow { workflow, .. } = &plan.target else {
+            return Err("scheduled job target is not a workflow".to_string());
+        };
+        let started = start_orchestration(
+            &mut self.runtime_registry,
+            &mut self.resource_governor,
+            &mut self.memory,
+            &mut self.model_catalog,
+            &mut self.scheduler,
+            &mut self.orchestrator,
+            &mut self.session_registry,
+            &mut self.storage,
+            &mut self.pending_events,
+            &self.tool_registry,
+            SCHEDULER_SYSTEM_OWNER_ID,
+            workflow.clone(),
+            plan.workflow_trigger(),
+        )
+        .map_err(|err| match err {
+            OrchestrationStartError::NoModelLoaded => "no_model_loaded".to_string(),
+            OrchestrationStartError::InvalidGraph(inner) => inner.to_string(),
+            OrchestrationStartError::RoutingFailed(inner) => inner,
+        })?;
+        for _ in 0..started.spawned {
+            self.metrics.inc_exec_started();
+        }
+        Ok(ScheduledRunHandle::Orchestration(started.orchestration_id))
+    }
+
+    fn spawn_scheduled_tool_call(
+        &mut self,
+        job_id: u64,
+        run_id: u64,
+        invocation: crate::tools::invocation::ToolInvocation,
+        context: crate::tools::invocation::ToolContext,
+    ) {
+        if let Err(err) =
+            self.job_tool_runner
+                .spawn(&self.tool_registry, job_id, run_id, invocation, context)
+        {
+            if let Err(persist_err) = self.job_scheduler.complete_tool_run(
+                &mut self.storage,
+                job_id,
+                run_id,
+                "failed",
+                Some(&err),
+            ) {
+                tracing::error!(job_id, %persist_err, "SCHEDULER: failed to finalize tool job run");
+            }
+        }
+    }
+
+    /// Chiude i run `exec` / `send_input` i cui processi hanno emesso
+    /// `SessionFinished` o `SessionErrored` dall'ultima scansione, e i run
+    /// `tool` i cui thread hanno restituito un esito.
+    fn settle_scheduled_process_and_tool_runs(&mut self) {
+        let cursor = self.job_events_cursor.min(self.pending_events.len());
+        let mut settled = self
+            .job_scheduler
+            .observe_process_events(&mut self.storage, &self.pending_events[cursor..]);
+        self.job_events_cursor = self.pending_events.len();
+
+        for outcome in self.job_tool_runner.drain() {
+            let (status, error) = if outcome.success {
+                ("completed", None)
+            } else {
+                ("failed", outcome.error.as_deref())
+            };
+            match self.job_scheduler.complete_tool_run(
+                &mut self.storage,
+                outcome.job_id,
+                outcome.run_id,
+                status,
+                error,
+            ) {
+                Ok(()) => settled += 1,
+                Err(err) => tracing::error!(
+                    job_id = outcome.job_id,
+                    %err,
+                    "SCHEDULER: failed to finalize tool job run"
+                ),
+            }
+        }
+
+        if settled > 0 {
+            self.pending_events
+                .push(agentic_control_models::KernelEvent::LobbyChanged {
+                    reason: "scheduled_job_completed".to_string(),
+                });
+        }
+    }
+
+    /// Sposta su un modello locale i processi rimasti senza budget remoto.
+    fn reroute_budget_exhausted_processes(&mut self) {
+        crate::services::budget_runtime::reroute_budget_exhausted_processes(
+            &mut self.runtime_registry,
+            &mut self.resource_governor,
+            &mut self.model_catalog,
+            &mut self.memory,
+            &mut self.scheduler,
+            &mut self.session_registry,
+            &mut self.storage,
+            &mut self.turn_assembly,
+            &mut self.pending_events,
+            &self.tool_registry,
+        );
+    }
+
     fn reconcile_scheduled_job_runs(&mut self) {
+        self.settle_scheduled_process_and_tool_runs();
         let orch_ids = self.job_scheduler.orchestration_ids();
         for orch_id in orch_ids {
             let Some(orchestration) = self.orchestrator.get(orch_id) else {
@@ -441,71 +780,63 @@ impl Kernel {
         }
     }
 
+    /// Con la policy `deadline`, i processi legati a un job con timeout
+    /// (orchestrazione o processo effimero) ereditano la scadenza del run.
+    fn sync_scheduling_deadlines(&mut self) {
+        if self.scheduler.fair_share_config().policy != SchedulingPolicy::Deadline {
+            return;
+        }
+        let mut orchestration_deadlines = HashMap::new();
+        let mut process_deadlines = HashMap::new();
+        for (handle, deadline) in self.job_scheduler.run_deadlines() {
+            if let Some(orch_id) = handle.orchestration_id() {
+                orchestration_deadlines.insert(orch_id, deadline);
+            } else if let Some(pid) = handle.pid() {
+                process_deadlines.insert(pid, deadline);
+            }
+        }
+        let deadlines: HashMap<u64, i64> = self
+            .scheduler
+            .registered_pids()
+            .into_iter()
+            .filter_map(|pid| {
+                let deadline = self
+                    .orchestrator
+                    .task_binding_for_pid(pid)
+                    .and_then(|binding| orchestration_deadlines.get(&binding.orch_id))
+                    .or_else(|| process_deadlines.get(&pid))?;
+                Some((pid, *deadline))
+            })
+            .collect();
+        self.scheduler.set_run_deadlines(&deadlines);
+    }
+
     fn enforce_scheduled_job_timeouts(&mut self) {
-        let timed_out_job_ids = self
-            .job_scheduler
-            .timeout_job_ids(crate::storage::current_timestamp_ms());
+        let now_ms = crate::storage::current_timestamp_ms();
+        let timed_out_job_ids = self.job_scheduler.timeout_job_ids(now_ms);
 
         for job_id in timed_out_job_ids {
-            let orchestration_id =
-                match self.job_scheduler.mark_timed_out(&mut self.storage, job_id) {
-                    Ok(value) => value,
-                    Err(err) => {
-                        tracing::error!(job_id, %err, "SCHEDULER: failed to mark timeout");
-                        continue;
-                    }
-                };
-
-            if let Some(orch_id) = orchestration_id {
-                let running_pids = self
-                    .orchestrator
-                    .get(orch_id)
-                    .map(|orch| orch.running_pids())
-                    .unwrap_or_default();
-                for pid in running_pids {
-                    let Some(runtime_id) = self
-                        .runtime_registry
-                        .runtime_id_for_pid(pid)
-                        .map(ToString::to_string)
-                    else {
-                        continue;
-                    };
-                    let Some(engine) = self.runtime_registry.engine_mut(&runtime_id) else {
-                        continue;
-                    };
-                    kill_managed_process_with_session(
-                        engine,
-                        &mut self.memory,
-                        &mut self.scheduler,
-                        &mut self.session_registry,
-                        &mut self.storage,
-                        pid,
-                        "scheduled_job_timeout",
-                    );
-                    if let Err(err) = self.runtime_registry.release_pid(&mut self.storage, pid) {
-                        tracing::warn!(
-                            pid,
-                            %err,
-                            "SCHEDULER: failed to release pid after job timeout"
-                        );
-                    }
-                    self.pending_events.push(
-                        agentic_control_models::KernelEvent::SessionFinished {
-                            pid,
-                            tokens_generated: None,
-                            elapsed_secs: None,
-                            reason: "scheduled_job_timeout".to_string(),
-                        },
-                    );
-                    self.pending_events.push(
-                        agentic_control_models::KernelEvent::WorkspaceChanged {
-                            pid,
-                            reason: "scheduled_job_timeout".to_string(),
-                        },
-                    );
+            let handle = match self.job_scheduler.mark_timed_out(&mut self.storage, job_id) {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::error!(job_id, %err, "SCHEDULER: failed to mark timeout");
+                    continue;
                 }
+            };
+            if let Some(handle) = handle {
+                self.stop_scheduled_run(handle, "scheduled_job_timeout");
             }
+            self.pending_events
+                .push(agentic_control_models::KernelEvent::LobbyChanged {
+                    reason: "scheduled_job_timeout".to_string(),
+                });
+        }
 
+        for handle in self
+            .job_scheduler
+            .expire_overlap_runs(&mut self.storage, now_ms)
+        {
+            self.stop_scheduled_run(handle, "scheduled_job_timeout");
             self.pending_events
                 .push(agentic_control_models::KernelEvent::LobbyChanged {
                     reason: "scheduled_job_timeout".to_string(),
@@ -513,6 +844,61 @@ impl Kernel {
         }
     }
 
+    /// Termina i processi posseduti da un run schedulato scaduto o sostituito.
+    fn stop_scheduled_run(&mut self, handle: ScheduledRunHandle, reason: &str) {
+        // Solo i processi posseduti dal job vengono terminati: una sessione
+        // raggiunta via `send_input` continua il suo turno.
+        let owned_pids = match handle {
+            ScheduledRunHandle::Orchestration(orch_id) => self
+                .orchestrator
+                .get(orch_id)
+                .map(|orch| orch.running_pids())
+                .unwrap_or_default(),
+            ScheduledRunHandle::Process(pid) => vec![pid],
+            ScheduledRunHandle::SessionInput(_) | ScheduledRunHandle::Tool => Vec::new(),
+        };
+        for pid in owned_pids {
+            let Some(runtime_id) = self
+                .runtime_registry
+                .runtime_id_for_pid(pid)
+                .map(ToString::to_string)
+            else {
+                continue;
+            };
+            let Some(engine) = self.runtime_registry.engine_mut(&runtime_id) else {
+                continue;
+            };
+            kill_managed_process_with_session(
+                engine,
+                &mut self.memory,
+                &mut self.scheduler,
+                &mut self.session_registry,
+                &mut self.storage,
+                pid,
+                reason,
+            );
+            if let Err(err) = self.runtime_registry.release_pid(&mut self.storage, pid) {
+                tracing::warn!(
+                    pid,
+                    %err,
+                    "SCHEDULER: failed to release pid of stopped job run"
+                );
+            }
+            self.pending_events
+                .push(agentic_control_models::KernelEvent::SessionFinished {
+                    pid,
+                    tokens_generated: None,
+                    elapsed_secs: None,
+                    reason: reason.to_string(),
+                });
+            self.pending_events
+                .push(agentic_control_models::KernelEvent::WorkspaceChanged {
+                    pid,
+                    reason: reason.to_string(),
+                });
+        }
+    }
+
     fn drain_pending_diagnostics(&mut self) {
         self.pending_events.extend(
             self.storage
@@ -522,6 +908,30 @@ impl Kernel {
         );
     }
 
+    /// Raccoglie le transizioni di workflow, job schedulati, tool registry e
+    /// server MCP accumulate durante il tick e le accoda per i client iscritti.
+    /// Le completion alimentano anche i trigger `completion` dei job.
+    fn drain_lifecycle_events(&mut self) {
+        self.settle_scheduled_process_and_tool_runs();
+        let workflow_events = self.orchestrator.take_lifecycle_events();
+        let job_events = self.job_scheduler.take_lifecycle_events();
+        self.job_scheduler
+            .observe_lifecycle_events(&workflow_events);
+        self.job_scheduler.observe_lifecycle_events(&job_events);
+        self.pending_events.extend(workflow_events);
+        self.pending_events.extend(job_events);
+        let tool_registry_events = self.tool_registry.take_lifecycle_events();
+        if !tool_registry_events.is_empty() {
+            if let Some(mcp_export) = self.mcp_export.as_ref() {
+                mcp_export.sync_tool_registry(&self.tool_registry);
+            }
+        }
+        self.pending_events.extend(tool_registry_events);
+        if let Some(mcp_bridge) = self.mcp_bridge.as_ref() {
+            self.pending_events.extend(mcp_bridge.take_health_events());
+        }
+    }
+
     /// Interrompe forzatamente un processo se una syscall supera il tempo massimo
     /// di esecuzione consentito (configurato globalmente).
     fn enforce_syscall_timeout(&mut self, pid: u64) {
@@ -582,6 +992,38 @@ impl Kernel {
         self.syscall_wait_since.remove(&pid);
     }
 
+    /// Journal the checkpoint deltas of this tick, or compact them into a new
+    /// generation when `compact_reason` is set.
+    fn journal_checkpoint(&mut self, compact_reason: Option<&str>) {
+        let deps = CheckpointSnapshotDeps {
+            engine_state: self.runtime_registry.current_engine(),
+            model_catalog: &self.model_catalog,
+            scheduler: &self.scheduler,
+            session_registry: &self.session_registry,
+            metrics: &self.metrics,
+            memory: &self.memory,
+        };
+        let result = match compact_reason {
+            Some(reason) => self
+                .checkpoint_journal
+                .compact(reason, &deps, &mut self.storage)
+                .map(|generation| {
+                    tracing::debug!(generation, reason, "CHECKPOINT: generation compacted");
+                }),
+            None => self
+                .checkpoint_journal
+                .record_changes(&deps, &mut self.storage)
+                .map(|deltas| {
+                    if deltas > 0 {
+                        tracing::trace!(deltas, "CHECKPOINT: journaled process deltas");
+                    }
+                }),
+        };
+        if let Err(err) = result {
+            tracing::warn!(%err, "CHECKPOINT: journal write failed");
+        }
+    }
+
     fn prune_remote_timeout_reports(&mut self) {
         let active_remote_waiting: HashSet<u64> = self
             .scheduler
@@ -600,12 +1042,19 @@ fn run_auto_checkpoint(
     engine_state: Option<&LLMEngine>,
     model_catalog: &ModelCatalog,
     scheduler: &ProcessScheduler,
+    session_registry: &SessionRegistry,
     metrics: &MetricsState,
     memory: &NeuralMemory,
 ) {
     let path = checkpoint::default_checkpoint_path();
-    let snap =
-        checkpoint::build_kernel_snapshot(engine_state, model_catalog, scheduler, metrics, memory);
+    let snap = checkpoint::build_kernel_snapshot(
+        engine_state,
+        model_catalog,
+        scheduler,
+        session_registry,
+        metrics,
+        memory,
+    );
 
     match checkpoint::save_checkpoint(&snap, &path) {
         Ok(msg) => tracing::debug!(msg, "auto-checkpoint"),
@@ -673,6 +1122,7 @@ fn handle_client_event(
                 &mut kernel.turn_assembly,
                 &kernel.auth_token,
                 kernel.mcp_bridge.as_ref(),
+                Some(&kernel.tool_manifests),
             )
         {
             should_close = true;
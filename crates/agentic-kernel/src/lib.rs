@@ -12,6 +12,7 @@ mod kernel;
 mod mcp;
 mod memory;
 mod model_catalog;
+mod openai_api;
 mod orchestrator;
 mod policy;
 mod process;
@@ -45,7 +46,7 @@ use kernel::event_loop::Kernel;
 use std::io;
 
 pub fn run() -> io::Result<()> {
-    diagnostics::tracing::initialize_subscriber();
+    diagnostics::tracing::initialize_subscriber(mcp::export::stdio_requested());
 
     let config = config::initialize().map_err(io::Error::other)?;
     tools::cleanup_stale_temp_scripts();
@@ -1,19 +1,22 @@
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
+use agentic_control_models::KernelEvent;
 use serde_json::{json, Value};
 
 use crate::config::{KernelConfig, McpServerConfig, McpTransportConfig, McpTrustLevel};
-use crate::mcp::client::McpStdioClient;
+use crate::mcp::client::McpClient;
 use crate::mcp::http_bridge;
 use crate::mcp::models::{
     McpBridgeErrorBody, McpBridgeErrorResponse, McpBridgeInvocationRequest, McpBridgeToolResponse,
-    McpCallToolResult, McpInitializeResult, McpInvocationMetadata, McpPromptDefinition,
-    McpResourceDefinition, McpToolAnnotations, McpToolDefinition,
+    McpCallToolResult, McpGetPromptResult, McpInitializeResult, McpInvocationMetadata,
+    McpPromptDefinition, McpResourceContent, McpResourceDefinition, McpRoot, McpToolAnnotations,
+    McpToolDefinition,
 };
-use crate::mcp::roots::roots_for_context;
+use crate::mcp::roots::{file_uri_within_roots, roots_for_context};
 use crate::mcp::status::{
     McpBridgeStatusSnapshot, McpDiscoveredToolSnapshot, McpPromptStatusSnapshot,
     McpResourceStatusSnapshot, McpServerStatusSnapshot,
@@ -25,12 +28,55 @@ use crate::tool_registry::{
 use crate::tools::invocation::{normalize_tool_name, ToolCaller};
 use crate::tools::schema::{ensure_valid_schema, validate_value};
 
+/// Generated per-server tools exposing MCP resources and prompts.
+const READ_RESOURCE_TOOL: &str = "mcp_read_resource";
+const GET_PROMPT_TOOL: &str = "mcp_get_prompt";
+/// Resources listed in the generated reader's description.
+const MAX_DESCRIBED_RESOURCES: usize = 20;
+/// How often the event loop drains MCP notifications while subscriptions exist.
+const MCP_RESOURCE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A subscribed MCP resource changed; `pid` asked to be woken for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct McpResourceUpdate {
+    pub(crate) server_id: String,
+    pub(crate) uri: String,
+    pub(crate) pid: u64,
+    pub(crate) read_tool: Option<String>,
+}
+
+impl McpResourceUpdate {
+    pub(crate) fn notice(&self) -> String {
+        match self.read_tool.as_deref() {
+            Some(read_tool) => format!(
+                "MCP resource updated: {} (server '{}'). Call {} to read the new content.",
+                self.uri, self.server_id, read_tool
+            ),
+            None => format!(
+                "MCP resource updated: {} (server '{}').",
+                self.uri, self.server_id
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum McpResourceUpdateDelivery {
+    Delivered,
+    /// The process is busy; keep the update until it waits for input again.
+    Deferred,
+    /// The process is gone; its subscriptions are released.
+    Dropped,
+}
+
 #[derive(Debug)]
 pub(crate) struct McpBridgeRuntime {
     state: Arc<Mutex<McpBridgeState>>,
     shutdown_tx: Option<std::sync::mpsc::Sender<()>>,
     server_handle: Option<JoinHandle<()>>,
     registered_tool_names: Vec<String>,
+    resource_watch_active: Arc<AtomicBool>,
+    last_resource_poll: Instant,
 }
 
 impl McpBridgeRuntime {
@@ -53,13 +99,18 @@ impl McpBridgeRuntime {
             return Ok(None);
         }
 
-        let state = Arc::new(Mutex::new(McpBridgeState::new(enabled_servers)));
+        let resource_watch_active = Arc::new(AtomicBool::new(false));
+        let state = Arc::new(Mutex::new(McpBridgeState::new(
+            enabled_servers,
+            Arc::clone(&resource_watch_active),
+        )));
         let token = random_token()?;
         let (listen_addr, shutdown_tx, server_handle) = http_bridge::spawn(
             &config.mcp.bridge_host,
             config.mcp.bridge_port,
             &config.mcp.bridge_token_header,
             &token,
+            config.mcp.bridge_max_request_bytes,
             Arc::clone(&state),
         )?;
 
@@ -68,12 +119,14 @@ impl McpBridgeRuntime {
             let mut guard = state
                 .lock()
                 .map_err(|_| "MCP bridge state lock poisoned during startup.".to_string())?;
-            guard.sync_registry(
+            let registered = guard.sync_registry(
                 tool_registry,
                 &base_url,
                 &config.mcp.bridge_token_header,
                 &token,
-            )
+            );
+            guard.take_health_events();
+            registered
         };
 
         Ok(Some(Self {
@@ -81,6 +134,8 @@ impl McpBridgeRuntime {
             shutdown_tx: Some(shutdown_tx),
             server_handle: Some(server_handle),
             registered_tool_names,
+            resource_watch_active,
+            last_resource_poll: Instant::now(),
         }))
     }
 
@@ -98,10 +153,36 @@ impl McpBridgeRuntime {
     }
 
     pub(crate) fn status_snapshot(&self) -> Option<McpBridgeStatusSnapshot> {
+        self.state.lock().ok().map(|state| state.status_snapshot())
+    }
+
+    pub(crate) fn take_health_events(&self) -> Vec<KernelEvent> {
         self.state
             .lock()
-            .ok()
-            .map(|state| state.status_snapshot())
+            .map(|mut state| state.take_health_events())
+            .unwrap_or_default()
+    }
+
+    /// Next time the event loop should drain MCP notifications, if any
+    /// process is subscribed to a resource or an update is still pending.
+    pub(crate) fn next_resource_poll_at(&self) -> Option<Instant> {
+        self.resource_watch_active
+            .load(Ordering::SeqCst)
+            .then_some(self.last_resource_poll + MCP_RESOURCE_POLL_INTERVAL)
+    }
+
+    /// Collect resource updates and hand each one to `deliver`. Skipped while
+    /// a tool call holds the bridge state, so the event loop never blocks on
+    /// an MCP server.
+    pub(crate) fn dispatch_resource_updates(
+        &mut self,
+        deliver: impl FnMut(&McpResourceUpdate) -> McpResourceUpdateDelivery,
+    ) {
+        self.last_resource_poll = Instant::now();
+        let Ok(mut state) = self.state.try_lock() else {
+            return;
+        };
+        state.dispatch_resource_updates(deliver);
     }
 }
 
@@ -111,29 +192,139 @@ impl Drop for McpBridgeRuntime {
     }
 }
 
+/// Boxed body keeps the `Result`s of the invocation path small; the response
+/// carries the full invocation metadata.
 #[derive(Debug)]
 pub(super) struct BridgeInvocationError {
     pub(super) status_code: u16,
-    pub(super) body: McpBridgeErrorResponse,
+    pub(super) body: Box<McpBridgeErrorResponse>,
 }
 
 #[derive(Debug)]
 pub(super) struct McpBridgeState {
     servers: HashMap<String, McpServerSession>,
     tools: BTreeMap<String, RegisteredMcpTool>,
+    reported_health: HashMap<String, String>,
+    /// (server id, resource uri) -> pids to wake when it changes.
+    resource_subscribers: BTreeMap<(String, String), BTreeSet<u64>>,
+    pending_resource_updates: Vec<McpResourceUpdate>,
+    resource_watch_active: Arc<AtomicBool>,
 }
 
 impl McpBridgeState {
-    fn new(servers: Vec<McpServerConfig>) -> Self {
+    fn new(servers: Vec<McpServerConfig>, resource_watch_active: Arc<AtomicBool>) -> Self {
         Self {
             servers: servers
                 .into_iter()
                 .map(|server| (server.id.clone(), McpServerSession::new(server)))
                 .collect(),
             tools: BTreeMap::new(),
+            reported_health: HashMap::new(),
+            resource_subscribers: BTreeMap::new(),
+            pending_resource_updates: Vec::new(),
+            resource_watch_active,
         }
     }
 
+    fn dispatch_resource_updates(
+        &mut self,
+        mut deliver: impl FnMut(&McpResourceUpdate) -> McpResourceUpdateDelivery,
+    ) {
+        for server in self.servers.values_mut() {
+            for uri in server.poll_updated_resources() {
+                let key = (server.config.id.clone(), uri);
+                let Some(pids) = self.resource_subscribers.get(&key) else {
+                    continue;
+                };
+                let read_tool = self
+                    .tools
+                    .values()
+                    .find(|tool| {
+                        tool.server_id == key.0 && tool.kind == McpBridgeToolKind::ReadResource
+                    })
+                    .map(|tool| tool.agentic_name.clone());
+                for pid in pids {
+                    let update = McpResourceUpdate {
+                        server_id: key.0.clone(),
+                        uri: key.1.clone(),
+                        pid: *pid,
+                        read_tool: read_tool.clone(),
+                    };
+                    if !self.pending_resource_updates.contains(&update) {
+                        self.pending_resource_updates.push(update);
+                    }
+                }
+            }
+        }
+
+        let mut dropped_pids = BTreeSet::new();
+        self.pending_resource_updates
+            .retain(|update| match deliver(update) {
+                McpResourceUpdateDelivery::Delivered => false,
+                McpResourceUpdateDelivery::Deferred => true,
+                McpResourceUpdateDelivery::Dropped => {
+                    dropped_pids.insert(update.pid);
+                    false
+                }
+            });
+        for pid in dropped_pids {
+            self.release_resource_subscriber(pid);
+        }
+        self.refresh_resource_watch();
+    }
+
+    fn release_resource_subscriber(&mut self, pid: u64) {
+        let mut released = Vec::new();
+        self.resource_subscribers.retain(|key, pids| {
+            pids.remove(&pid);
+            if pids.is_empty() {
+                released.push(key.clone());
+                return false;
+            }
+            true
+        });
+        for (server_id, uri) in released {
+            if let Some(server) = self.servers.get_mut(&server_id) {
+                server.unsubscribe_resource(&uri);
+            }
+        }
+    }
+
+    fn refresh_resource_watch(&self) {
+        self.resource_watch_active.store(
+            !self.resource_subscribers.is_empty() || !self.pending_resource_updates.is_empty(),
+            Ordering::SeqCst,
+        );
+    }
+
+    /// Compares each server's current health with the last value handed to
+    /// the event loop and returns one event per server whose health moved.
+    fn take_health_events(&mut self) -> Vec<KernelEvent> {
+        let mut server_ids = self.servers.keys().cloned().collect::<Vec<_>>();
+        server_ids.sort();
+        let mut events = Vec::new();
+        for server_id in server_ids {
+            let Some(server) = self.servers.get(&server_id) else {
+                continue;
+            };
+            let health = server_health(server);
+            let previous_health = self
+                .reported_health
+                .insert(server_id.clone(), health.clone())
+                .unwrap_or_else(|| "unknown".to_string());
+            if previous_health == health {
+                continue;
+            }
+            events.push(KernelEvent::McpServerHealthChanged {
+                server_id,
+                health,
+                previous_health,
+                last_error: server.last_error.clone(),
+            });
+        }
+        events
+    }
+
     fn sync_registry(
         &mut self,
         tool_registry: &mut ToolRegistry,
@@ -161,7 +352,7 @@ impl McpBridgeState {
                     );
                     continue;
                 }
-                let Some(agentic_name) = build_agentic_tool_name(&server.config, tool) else {
+                let Some(agentic_name) = build_agentic_tool_name(&server.config, &tool.name) else {
                     tracing::warn!(
                         server_id = server.config.id,
                         tool_name = tool.name,
@@ -174,6 +365,10 @@ impl McpBridgeState {
                     RegisteredMcpTool::from_session(server, tool, agentic_name),
                 );
             }
+
+            for tool in RegisteredMcpTool::generated_for_session(server) {
+                discovered_tools.insert(tool.agentic_name.clone(), tool);
+            }
         }
 
         for tool in discovered_tools.values() {
@@ -260,88 +455,274 @@ impl McpBridgeState {
             Vec::new()
         };
 
-        let started_at = Instant::now();
-        let call_result = server
-            .call_tool(&tool.target_name, request.input, roots.clone())
-            .map_err(|err| {
-                let metadata = Some(base_metadata(
+        match tool.kind {
+            McpBridgeToolKind::Tool => invoke_server_tool(server, &tool, request.input, roots),
+            McpBridgeToolKind::ReadResource => {
+                let (response, subscribed_uri) = read_server_resource(
+                    server,
                     &tool,
-                    &roots,
-                    started_at.elapsed().as_millis(),
-                    false,
-                    false,
-                    vec![],
-                ));
-                BridgeInvocationError {
-                    status_code: if err.contains("timed out") { 504 } else { 502 },
-                    body: error_response("mcp_transport_failed", &err, metadata),
+                    &request.input,
+                    request.context.pid,
+                    roots,
+                )?;
+                if let (Some(uri), Some(pid)) = (subscribed_uri, request.context.pid) {
+                    self.resource_subscribers
+                        .entry((tool.server_id.clone(), uri))
+                        .or_default()
+                        .insert(pid);
+                    self.refresh_resource_watch();
                 }
-            })?;
-        let latency_ms = started_at.elapsed().as_millis();
-        let mut trust_filters = Vec::new();
-        let (validation_attempted, validation_passed) =
-            validate_result(&tool, &call_result, &mut trust_filters).map_err(|err| {
-                BridgeInvocationError {
-                    status_code: 422,
-                    body: error_response(
-                        "mcp_result_validation_failed",
-                        &err,
-                        Some(base_metadata(
-                            &tool,
-                            &roots,
-                            latency_ms,
-                            true,
-                            false,
-                            trust_filters.clone(),
-                        )),
-                    ),
-                }
-            })?;
-
-        let output = render_tool_output(&call_result);
-        let metadata = base_metadata(
-            &tool,
-            &roots,
-            latency_ms,
-            validation_attempted,
-            validation_passed,
-            trust_filters.clone(),
-        );
+                Ok(response)
+            }
+            McpBridgeToolKind::GetPrompt => get_server_prompt(server, &tool, &request.input, roots),
+        }
+    }
+}
+
+fn invoke_server_tool(
+    server: &mut McpServerSession,
+    tool: &RegisteredMcpTool,
+    input: Value,
+    roots: Vec<McpRoot>,
+) -> Result<McpBridgeToolResponse, BridgeInvocationError> {
+    let started_at = Instant::now();
+    let call_result = server
+        .call_tool(&tool.target_name, input, roots.clone())
+        .map_err(|err| transport_error(tool, &roots, started_at, &err))?;
+    let latency_ms = started_at.elapsed().as_millis();
+    let mut trust_filters = Vec::new();
+    let (validation_attempted, validation_passed) =
+        validate_result(tool, &call_result, &mut trust_filters).map_err(|err| {
+            BridgeInvocationError {
+                status_code: 422,
+                body: error_response(
+                    "mcp_result_validation_failed",
+                    &err,
+                    Some(base_metadata(
+                        tool,
+                        &roots,
+                        latency_ms,
+                        true,
+                        false,
+                        trust_filters.clone(),
+                    )),
+                ),
+            }
+        })?;
 
-        if call_result.is_error {
+    let output = render_tool_output(&call_result);
+    let metadata = base_metadata(
+        tool,
+        &roots,
+        latency_ms,
+        validation_attempted,
+        validation_passed,
+        trust_filters.clone(),
+    );
+
+    if call_result.is_error {
+        return Err(BridgeInvocationError {
+            status_code: 500,
+            body: error_response("mcp_tool_execution_failed", &output, Some(metadata)),
+        });
+    }
+
+    server.last_latency_ms = Some(latency_ms);
+    server.last_error = None;
+
+    Ok(McpBridgeToolResponse {
+        output,
+        content: call_result.content,
+        structured_content: call_result.structured_content,
+        is_error: false,
+        warnings: trust_filters
+            .iter()
+            .map(|filter| format!("MCP trust filter applied: {filter}"))
+            .collect(),
+        mcp: metadata,
+    })
+}
+
+/// Read a resource and inline its text as the tool output. Returns the uri
+/// when the caller also subscribed to updates.
+fn read_server_resource(
+    server: &mut McpServerSession,
+    tool: &RegisteredMcpTool,
+    input: &Value,
+    pid: Option<u64>,
+    roots: Vec<McpRoot>,
+) -> Result<(McpBridgeToolResponse, Option<String>), BridgeInvocationError> {
+    let uri = input
+        .get("uri")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|uri| !uri.is_empty())
+        .ok_or_else(|| invalid_input(&format!("{READ_RESOURCE_TOOL} requires a 'uri'.")))?;
+    let subscribe = input
+        .get("subscribe")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+
+    if uri.starts_with("file://") {
+        if !server.config.roots_enabled || !file_uri_within_roots(uri, &roots) {
             return Err(BridgeInvocationError {
-                status_code: 500,
-                body: error_response("mcp_tool_execution_failed", &output, Some(metadata)),
+                status_code: 403,
+                body: error_response(
+                    "resource_outside_roots",
+                    "MCP resource path is outside the process path grants.",
+                    None,
+                ),
             });
         }
+    } else if server.config.trust_level == McpTrustLevel::Untrusted
+        && !server.resources.iter().any(|resource| resource.uri == uri)
+    {
+        return Err(BridgeInvocationError {
+            status_code: 403,
+            body: error_response(
+                "resource_not_discovered",
+                "Untrusted MCP servers may only serve resources they listed during discovery.",
+                None,
+            ),
+        });
+    }
+    if subscribe {
+        if !server.supports_resource_subscriptions() {
+            return Err(invalid_input(
+                "MCP server does not support resource subscriptions.",
+            ));
+        }
+        if pid.is_none() {
+            return Err(invalid_input(
+                "Resource subscriptions require a calling process.",
+            ));
+        }
+    }
 
-        server.last_latency_ms = Some(latency_ms);
-        server.last_error = None;
-
-        Ok(McpBridgeToolResponse {
-            output,
-            content: call_result.content,
-            structured_content: call_result.structured_content,
+    let started_at = Instant::now();
+    let contents = server
+        .read_resource(uri, roots.clone())
+        .map_err(|err| transport_error(tool, &roots, started_at, &err))?;
+    if subscribe {
+        server
+            .subscribe_resource(uri)
+            .map_err(|err| transport_error(tool, &roots, started_at, &err))?;
+    }
+    let latency_ms = started_at.elapsed().as_millis();
+    server.last_latency_ms = Some(latency_ms);
+
+    Ok((
+        McpBridgeToolResponse {
+            output: render_resource_output(&contents),
+            content: contents.iter().map(resource_content_block).collect(),
+            structured_content: Some(json!({
+                "uri": uri,
+                "subscribed": subscribe,
+            })),
             is_error: false,
-            warnings: trust_filters
-                .iter()
-                .map(|filter| format!("MCP trust filter applied: {filter}"))
-                .collect(),
-            mcp: metadata,
-        })
+            warnings: Vec::new(),
+            mcp: base_metadata(tool, &roots, latency_ms, false, true, Vec::new()),
+        },
+        subscribe.then(|| uri.to_string()),
+    ))
+}
+
+fn get_server_prompt(
+    server: &mut McpServerSession,
+    tool: &RegisteredMcpTool,
+    input: &Value,
+    roots: Vec<McpRoot>,
+) -> Result<McpBridgeToolResponse, BridgeInvocationError> {
+    let name = input
+        .get("name")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .ok_or_else(|| invalid_input(&format!("{GET_PROMPT_TOOL} requires a 'name'.")))?;
+    let Some(prompt) = server.prompts.iter().find(|prompt| prompt.name == name) else {
+        return Err(BridgeInvocationError {
+            status_code: 404,
+            body: error_response(
+                "mcp_prompt_not_found",
+                &format!("MCP server did not list a prompt named '{name}'."),
+                None,
+            ),
+        });
+    };
+    let arguments = input.get("arguments").cloned().unwrap_or_else(|| json!({}));
+    if let Some(missing) = prompt
+        .arguments
+        .iter()
+        .find(|argument| argument.required && arguments.get(&argument.name).is_none())
+    {
+        return Err(invalid_input(&format!(
+            "MCP prompt '{name}' requires argument '{}'.",
+            missing.name
+        )));
+    }
+
+    let started_at = Instant::now();
+    let result = server
+        .get_prompt(name, arguments, roots.clone())
+        .map_err(|err| transport_error(tool, &roots, started_at, &err))?;
+    let latency_ms = started_at.elapsed().as_millis();
+    server.last_latency_ms = Some(latency_ms);
+
+    Ok(McpBridgeToolResponse {
+        output: render_prompt_output(&result),
+        content: result
+            .messages
+            .iter()
+            .map(|message| json!({ "role": message.role, "content": message.content }))
+            .collect(),
+        structured_content: Some(json!({
+            "name": name,
+            "description": result.description,
+            "message_count": result.messages.len(),
+        })),
+        is_error: false,
+        warnings: Vec::new(),
+        mcp: base_metadata(tool, &roots, latency_ms, false, true, Vec::new()),
+    })
+}
+
+fn transport_error(
+    tool: &RegisteredMcpTool,
+    roots: &[McpRoot],
+    started_at: Instant,
+    err: &str,
+) -> BridgeInvocationError {
+    let metadata = Some(base_metadata(
+        tool,
+        roots,
+        started_at.elapsed().as_millis(),
+        false,
+        false,
+        vec![],
+    ));
+    BridgeInvocationError {
+        status_code: if err.contains("timed out") { 504 } else { 502 },
+        body: error_response("mcp_transport_failed", err, metadata),
+    }
+}
+
+fn invalid_input(message: &str) -> BridgeInvocationError {
+    BridgeInvocationError {
+        status_code: 400,
+        body: error_response("invalid_input", message, None),
     }
 }
 
 #[derive(Debug)]
 struct McpServerSession {
     config: McpServerConfig,
-    client: Option<McpStdioClient>,
+    client: Option<McpClient>,
     initialize: Option<McpInitializeResult>,
     tools: Vec<McpToolDefinition>,
-    #[allow(dead_code)]
     prompts: Vec<McpPromptDefinition>,
-    #[allow(dead_code)]
     resources: Vec<McpResourceDefinition>,
+    /// Resource subscriptions, replayed whenever the client reconnects.
+    subscribed_uris: BTreeSet<String>,
     last_latency_ms: Option<u128>,
     last_error: Option<String>,
 }
@@ -355,6 +736,7 @@ impl McpServerSession {
             tools: Vec::new(),
             prompts: Vec::new(),
             resources: Vec::new(),
+            subscribed_uris: BTreeSet::new(),
             last_latency_ms: None,
             last_error: None,
         }
@@ -401,28 +783,93 @@ impl McpServerSession {
     }
 
     fn selected_tools(&self) -> impl Iterator<Item = &McpToolDefinition> {
-        let exposed = self
-            .config
-            .exposed_tools
-            .iter()
-            .filter_map(|tool_name| normalize_tool_name(tool_name).ok())
-            .collect::<Vec<_>>();
-
-        self.tools.iter().filter(move |tool| {
-            exposed.is_empty()
-                || normalize_tool_name(&tool.name)
-                    .ok()
-                    .is_some_and(|tool_name| exposed.contains(&tool_name))
-        })
+        self.tools.iter().filter(|tool| self.exposes(&tool.name))
+    }
+
+    /// An empty `exposed_tools` exposes everything, generated tools included.
+    fn exposes(&self, tool_name: &str) -> bool {
+        let Ok(tool_name) = normalize_tool_name(tool_name) else {
+            return false;
+        };
+        self.config.exposed_tools.is_empty()
+            || self
+                .config
+                .exposed_tools
+                .iter()
+                .filter_map(|exposed| normalize_tool_name(exposed).ok())
+                .any(|exposed| exposed == tool_name)
+    }
+
+    fn supports_resource_subscriptions(&self) -> bool {
+        self.initialize
+            .as_ref()
+            .is_some_and(|initialize| initialize.capabilities.resources_subscribe)
     }
 
     fn call_tool(
         &mut self,
         tool_name: &str,
         input: Value,
-        roots: Vec<crate::mcp::models::McpRoot>,
+        roots: Vec<McpRoot>,
     ) -> Result<McpCallToolResult, String> {
-        let result = self.ensure_client()?.call_tool(tool_name, input, roots);
+        self.with_client(|client| client.call_tool(tool_name, input, roots))
+    }
+
+    fn read_resource(
+        &mut self,
+        uri: &str,
+        roots: Vec<McpRoot>,
+    ) -> Result<Vec<McpResourceContent>, String> {
+        self.with_client(|client| client.read_resource(uri, roots))
+    }
+
+    fn get_prompt(
+        &mut self,
+        name: &str,
+        arguments: Value,
+        roots: Vec<McpRoot>,
+    ) -> Result<McpGetPromptResult, String> {
+        self.with_client(|client| client.get_prompt(name, arguments, roots))
+    }
+
+    fn subscribe_resource(&mut self, uri: &str) -> Result<(), String> {
+        self.with_client(|client| client.subscribe_resource(uri))?;
+        self.subscribed_uris.insert(uri.to_string());
+        Ok(())
+    }
+
+    fn unsubscribe_resource(&mut self, uri: &str) {
+        self.subscribed_uris.remove(uri);
+        if let Some(client) = self.client.as_mut() {
+            if let Err(err) = client.unsubscribe_resource(uri) {
+                tracing::debug!(server_id = self.config.id, uri, %err, "MCP unsubscribe failed");
+            }
+        }
+    }
+
+    /// Drain notifications from an already connected client; a disconnected
+    /// server is not reconnected here, that happens on the next tool call.
+    fn poll_updated_resources(&mut self) -> Vec<String> {
+        if self.subscribed_uris.is_empty() {
+            return Vec::new();
+        }
+        let Some(client) = self.client.as_mut() else {
+            return Vec::new();
+        };
+        match client.poll_notifications() {
+            Ok(()) => client.take_updated_resources(),
+            Err(err) => {
+                self.record_transport_failure(&err);
+                Vec::new()
+            }
+        }
+    }
+
+    fn with_client<T>(
+        &mut self,
+        operation: impl FnOnce(&mut McpClient) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let result = operation(self.ensure_client()?);
         match result {
             Ok(value) => {
                 self.last_error = None;
@@ -435,10 +882,15 @@ impl McpServerSession {
         }
     }
 
-    fn ensure_client(&mut self) -> Result<&mut McpStdioClient, String> {
+    fn ensure_client(&mut self) -> Result<&mut McpClient, String> {
         if self.client.is_none() {
-            let mut client = McpStdioClient::connect(&self.config)?;
+            let mut client = McpClient::connect(&self.config)?;
             let initialize = client.initialize()?;
+            for uri in &self.subscribed_uris {
+                if let Err(err) = client.subscribe_resource(uri) {
+                    tracing::warn!(server_id = self.config.id, uri, %err, "MCP resubscribe failed");
+                }
+            }
             self.initialize = Some(initialize);
             self.client = Some(client);
         }
@@ -524,8 +976,16 @@ impl McpServerSession {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum McpBridgeToolKind {
+    Tool,
+    ReadResource,
+    GetPrompt,
+}
+
 #[derive(Debug, Clone)]
 struct RegisteredMcpTool {
+    kind: McpBridgeToolKind,
     agentic_name: String,
     server_id: String,
     server_label: Option<String>,
@@ -559,6 +1019,7 @@ impl RegisteredMcpTool {
         };
 
         Self {
+            kind: McpBridgeToolKind::Tool,
             agentic_name,
             server_id: session.config.id.clone(),
             server_label: session.config.label.clone(),
@@ -584,6 +1045,145 @@ impl RegisteredMcpTool {
         }
     }
 
+    /// `mcp_read_resource` / `mcp_get_prompt` for servers advertising the
+    /// matching capability, filtered by `exposed_tools` like server tools.
+    fn generated_for_session(session: &McpServerSession) -> Vec<Self> {
+        let Some(capabilities) = session
+            .initialize
+            .as_ref()
+            .map(|initialize| &initialize.capabilities)
+        else {
+            return Vec::new();
+        };
+        let mut tools = Vec::new();
+
+        if capabilities.resources && session.exposes(READ_RESOURCE_TOOL) {
+            let mut description = format!(
+                "Read a resource from MCP server '{}' and return its content as retrieved context. file:// URIs must lie inside the process path grants.",
+                session.config.id
+            );
+            let mut properties = serde_json::Map::new();
+            properties.insert("uri".to_string(), json!({"type": "string", "minLength": 1}));
+            if capabilities.resources_subscribe {
+                description.push_str(
+                    " Set subscribe=true to be woken with a notice when the resource changes.",
+                );
+                properties.insert("subscribe".to_string(), json!({"type": "boolean"}));
+            }
+            if !session.resources.is_empty() {
+                description.push_str("\nKnown resources:");
+                for resource in session.resources.iter().take(MAX_DESCRIBED_RESOURCES) {
+                    description.push_str(&format!("\n- {} ({})", resource.uri, resource.name));
+                }
+            }
+            if let Some(agentic_name) = build_agentic_tool_name(&session.config, READ_RESOURCE_TOOL)
+            {
+                tools.push(Self::generated(
+                    session,
+                    McpBridgeToolKind::ReadResource,
+                    agentic_name,
+                    "resources/read",
+                    description,
+                    json!({
+                        "type": "object",
+                        "required": ["uri"],
+                        "properties": properties,
+                        "additionalProperties": false
+                    }),
+                ));
+            }
+        }
+
+        if capabilities.prompts && !session.prompts.is_empty() && session.exposes(GET_PROMPT_TOOL) {
+            let mut description = format!(
+                "Render a prompt template from MCP server '{}'. Available prompts:",
+                session.config.id
+            );
+            for prompt in &session.prompts {
+                description.push_str(&format!("\n- {}", prompt.name));
+                if let Some(summary) = prompt.description.as_deref() {
+                    description.push_str(&format!(": {summary}"));
+                }
+                if !prompt.arguments.is_empty() {
+                    let arguments = prompt
+                        .arguments
+                        .iter()
+                        .map(|argument| {
+                            if argument.required {
+                                format!("{}*", argument.name)
+                            } else {
+                                argument.name.clone()
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    description.push_str(&format!(" (arguments: {})", arguments.join(", ")));
+                }
+            }
+            let names = session
+                .prompts
+                .iter()
+                .map(|prompt| prompt.name.clone())
+                .collect::<Vec<_>>();
+            if let Some(agentic_name) = build_agentic_tool_name(&session.config, GET_PROMPT_TOOL) {
+                tools.push(Self::generated(
+                    session,
+                    McpBridgeToolKind::GetPrompt,
+                    agentic_name,
+                    "prompts/get",
+                    description,
+                    json!({
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": {"type": "string", "enum": names},
+                            "arguments": {
+                                "type": "object",
+                                "additionalProperties": {"type": "string"}
+                            }
+                        },
+                        "additionalProperties": false
+                    }),
+                ));
+            }
+        }
+
+        tools
+    }
+
+    fn generated(
+        session: &McpServerSession,
+        kind: McpBridgeToolKind,
+        agentic_name: String,
+        target_name: &str,
+        description: String,
+        input_schema: Value,
+    ) -> Self {
+        Self {
+            kind,
+            agentic_name,
+            server_id: session.config.id.clone(),
+            server_label: session.config.label.clone(),
+            transport: session.config.transport.kind().to_string(),
+            timeout_ms: transport_timeout_ms(&session.config.transport),
+            target_name: target_name.to_string(),
+            trust_level: session.config.trust_level.as_str().to_string(),
+            auth_mode: auth_mode_for_transport(&session.config.transport),
+            default_allowlisted: session.config.default_allowlisted,
+            approval_required: session.config.approval_required,
+            dangerous: session.config.trust_level == McpTrustLevel::Untrusted,
+            hints: McpToolAnnotations {
+                title: None,
+                read_only_hint: true,
+                destructive_hint: false,
+                idempotent_hint: true,
+                open_world_hint: false,
+            },
+            description,
+            input_schema,
+            output_schema: None,
+        }
+    }
+
     fn registry_entry(
         &self,
         base_url: &str,
@@ -642,20 +1242,34 @@ impl RegisteredMcpTool {
     }
 }
 
-fn build_agentic_tool_name(config: &McpServerConfig, tool: &McpToolDefinition) -> Option<String> {
+fn build_agentic_tool_name(config: &McpServerConfig, tool_name: &str) -> Option<String> {
     let prefix = config.tool_prefix.as_deref().unwrap_or(&config.id);
-    normalize_tool_name(&format!("{prefix}.{}", tool.name)).ok()
+    normalize_tool_name(&format!("{prefix}.{tool_name}")).ok()
 }
 
 fn auth_mode_for_transport(transport: &McpTransportConfig) -> String {
     match transport {
         McpTransportConfig::Stdio { .. } => "environment".to_string(),
+        McpTransportConfig::StreamableHttp {
+            bearer_token_env,
+            headers_env,
+            ..
+        } => {
+            if bearer_token_env.is_some() {
+                "bearer_env".to_string()
+            } else if !headers_env.is_empty() {
+                "header_env".to_string()
+            } else {
+                "none".to_string()
+            }
+        }
     }
 }
 
 fn transport_timeout_ms(transport: &McpTransportConfig) -> u64 {
     match transport {
-        McpTransportConfig::Stdio { timeout_ms, .. } => (*timeout_ms).max(1),
+        McpTransportConfig::Stdio { timeout_ms, .. }
+        | McpTransportConfig::StreamableHttp { timeout_ms, .. } => (*timeout_ms).max(1),
     }
 }
 
@@ -676,9 +1290,71 @@ fn bridge_output_schema() -> Value {
 }
 
 fn render_tool_output(result: &McpCallToolResult) -> String {
+    let lines = render_content_blocks(&result.content);
+
+    if lines.is_empty() {
+        if let Some(structured) = result.structured_content.as_ref() {
+            if let Ok(text) = serde_json::to_string_pretty(structured) {
+                return text;
+            }
+        }
+        return "MCP tool completed.".to_string();
+    }
+
+    lines.join("\n")
+}
+
+fn render_resource_output(contents: &[McpResourceContent]) -> String {
+    if contents.is_empty() {
+        return "MCP resource is empty.".to_string();
+    }
+    contents
+        .iter()
+        .map(|content| {
+            let header = match content.mime_type.as_deref() {
+                Some(mime_type) => format!("Resource {} ({mime_type}):", content.uri),
+                None => format!("Resource {}:", content.uri),
+            };
+            match (content.text.as_deref(), content.blob.as_ref()) {
+                (Some(text), _) => format!("{header}\n{text}"),
+                (None, Some(_)) => format!("{header}\n[binary content omitted]"),
+                (None, None) => header,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+fn resource_content_block(content: &McpResourceContent) -> Value {
+    let mut resource = json!({ "uri": content.uri });
+    if let Some(mime_type) = content.mime_type.as_deref() {
+        resource["mimeType"] = json!(mime_type);
+    }
+    if let Some(text) = content.text.as_deref() {
+        resource["text"] = json!(text);
+    }
+    if content.blob.is_some() {
+        resource["blobOmitted"] = json!(true);
+    }
+    json!({ "type": "resource", "resource": resource })
+}
+
+fn render_prompt_output(result: &McpGetPromptResult) -> String {
+    let mut sections = Vec::new();
+    if let Some(description) = result.description.as_deref() {
+        sections.push(description.to_string());
+    }
+    for message in &result.messages {
+        let text = render_content_blocks(std::slice::from_ref(&message.content)).join("\n");
+        sections.push(format!("[{}]\n{}", message.role, text));
+    }
+    sections.join("\n\n")
+}
+
+fn render_content_blocks(blocks: &[Value]) -> Vec<String> {
     let mut lines = Vec::new();
 
-    for block in &result.content {
+    for block in blocks {
         match block.get("type").and_then(Value::as_str) {
             Some("text") => {
                 if let Some(text) = block.get("text").and_then(Value::as_str) {
@@ -711,16 +1387,7 @@ fn render_tool_output(result: &McpCallToolResult) -> String {
         }
     }
 
-    if lines.is_empty() {
-        if let Some(structured) = result.structured_content.as_ref() {
-            if let Ok(text) = serde_json::to_string_pretty(structured) {
-                return text;
-            }
-        }
-        return "MCP tool completed.".to_string();
-    }
-
-    lines.join("\n")
+    lines
 }
 
 fn validate_discovered_tool(tool: &McpToolDefinition) -> Result<(), String> {
@@ -767,7 +1434,7 @@ fn validate_result(
 
 fn base_metadata(
     tool: &RegisteredMcpTool,
-    roots: &[crate::mcp::models::McpRoot],
+    roots: &[McpRoot],
     latency_ms: u128,
     validation_attempted: bool,
     validation_passed: bool,
@@ -797,6 +1464,9 @@ fn should_reset_client(err: &str) -> bool {
         || err.contains("Failed to write MCP stdio message")
         || err.contains("stdin pipe is unavailable")
         || err.contains("stdout pipe is unavailable")
+        || err.contains("MCP session expired")
+        || err.contains("closed the SSE stream")
+        || err.contains("Failed to reach MCP server")
 }
 
 fn server_health(server: &McpServerSession) -> String {
@@ -813,14 +1483,14 @@ fn error_response(
     kind: &str,
     message: &str,
     metadata: Option<McpInvocationMetadata>,
-) -> McpBridgeErrorResponse {
-    McpBridgeErrorResponse {
+) -> Box<McpBridgeErrorResponse> {
+    Box::new(McpBridgeErrorResponse {
         error: McpBridgeErrorBody {
             kind: kind.to_string(),
             message: message.to_string(),
             mcp: metadata,
         },
-    }
+    })
 }
 
 fn random_token() -> Result<String, String> {
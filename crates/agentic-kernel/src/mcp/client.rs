@@ -1,4 +1,7 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
 use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
 use std::process::{Child, ChildStdin, Command, Stdio};
 use std::sync::mpsc;
 use std::thread;
@@ -9,147 +12,100 @@ use serde_json::{json, Value};
 use crate::config::{McpServerConfig, McpTransportConfig};
 use crate::mcp::jsonrpc;
 use crate::mcp::models::{
-    McpCallToolResult, McpImplementation, McpInitializeResult, McpPromptDefinition,
+    McpCallToolResult, McpGetPromptResult, McpImplementation, McpInitializeResult,
+    McpPromptArgument, McpPromptDefinition, McpPromptMessage, McpResourceContent,
     McpResourceDefinition, McpRoot, McpServerCapabilities, McpToolAnnotations, McpToolDefinition,
     MCP_PROTOCOL_VERSION,
 };
 
+const MCP_SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+const MCP_PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+
 #[derive(Debug)]
 enum InboundMessage {
     Json(Value),
     ReadError(String),
     Closed,
+    /// An SSE response stream ended. `request_id` is the JSON-RPC request the
+    /// stream was opened for, `last_event_id` the cursor to resume it from.
+    StreamEnded {
+        request_id: Option<u64>,
+        last_event_id: Option<String>,
+    },
 }
 
+/// MCP client shared by every transport: discovery, `roots`, server-initiated
+/// requests and timeouts live here, only framing differs per transport.
 #[derive(Debug)]
-pub(crate) struct McpStdioClient {
+pub(crate) struct McpClient {
     server_id: String,
-    child: Child,
-    stdin: ChildStdin,
+    transport: McpClientTransport,
     inbound_rx: mpsc::Receiver<InboundMessage>,
     timeout: Duration,
     next_id: u64,
     current_roots: Vec<McpRoot>,
+    subscriptions: BTreeSet<String>,
+    /// URIs reported by `notifications/resources/updated`, not yet collected.
+    updated_resources: Vec<String>,
 }
 
-impl McpStdioClient {
-    pub(crate) fn connect(config: &McpServerConfig) -> Result<Self, String> {
-        let McpTransportConfig::Stdio {
-            command,
-            args,
-            cwd,
-            env,
-            timeout_ms,
-        } = &config.transport;
-
-        if command.trim().is_empty() {
-            return Err(format!(
-                "MCP server '{}' stdio command is empty.",
-                config.id
-            ));
-        }
-
-        let mut child = Command::new(command);
-        child.args(args);
-        child.stdin(Stdio::piped());
-        child.stdout(Stdio::piped());
-        child.stderr(Stdio::piped());
-        if let Some(cwd) = cwd {
-            child.current_dir(cwd);
-        }
-        for (key, value) in env {
-            child.env(key, value);
-        }
-
-        let mut child = child.spawn().map_err(|err| {
-            format!(
-                "Failed to spawn MCP server '{}' using command '{}': {}",
-                config.id, command, err
-            )
-        })?;
-
-        let stdin = child
-            .stdin
-            .take()
-            .ok_or_else(|| format!("MCP server '{}' stdin pipe is unavailable.", config.id))?;
-        let stdout = child
-            .stdout
-            .take()
-            .ok_or_else(|| format!("MCP server '{}' stdout pipe is unavailable.", config.id))?;
+#[derive(Debug)]
+enum McpClientTransport {
+    Stdio(StdioTransport),
+    StreamableHttp(StreamableHttpTransport),
+}
 
+impl McpClient {
+    pub(crate) fn connect(config: &McpServerConfig) -> Result<Self, String> {
         let (inbound_tx, inbound_rx) = mpsc::channel();
-        let server_id = config.id.clone();
-        thread::Builder::new()
-            .name(format!("mcp-stdout-{}", server_id))
-            .spawn(move || {
-                let mut reader = BufReader::new(stdout);
-                loop {
-                    let mut line = String::new();
-                    match reader.read_line(&mut line) {
-                        Ok(0) => {
-                            let _ = inbound_tx.send(InboundMessage::Closed);
-                            break;
-                        }
-                        Ok(_) => {
-                            let trimmed = line.trim();
-                            if trimmed.is_empty() {
-                                continue;
-                            }
-                            match serde_json::from_str::<Value>(trimmed) {
-                                Ok(message) => {
-                                    let _ = inbound_tx.send(InboundMessage::Json(message));
-                                }
-                                Err(err) => {
-                                    let _ = inbound_tx.send(InboundMessage::ReadError(format!(
-                                        "Invalid MCP JSON from server '{}': {}",
-                                        server_id, err
-                                    )));
-                                    break;
-                                }
-                            }
-                        }
-                        Err(err) => {
-                            let _ = inbound_tx.send(InboundMessage::ReadError(format!(
-                                "Failed reading MCP server '{}': {}",
-                                server_id, err
-                            )));
-                            break;
-                        }
-                    }
-                }
-            })
-            .map_err(|err| format!("Failed to start MCP stdout reader thread: {err}"))?;
-
-        if let Some(stderr) = child.stderr.take() {
-            let server_id = config.id.clone();
-            let _ = thread::Builder::new()
-                .name(format!("mcp-stderr-{}", server_id))
-                .spawn(move || {
-                    let mut reader = BufReader::new(stderr);
-                    loop {
-                        let mut line = String::new();
-                        match reader.read_line(&mut line) {
-                            Ok(0) => break,
-                            Ok(_) => {
-                                let message = line.trim();
-                                if !message.is_empty() {
-                                    tracing::debug!(server_id, message, "MCP_STDERR");
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                });
-        }
+        let (transport, timeout_ms) = match &config.transport {
+            McpTransportConfig::Stdio {
+                command,
+                args,
+                cwd,
+                env,
+                timeout_ms,
+            } => (
+                McpClientTransport::Stdio(StdioTransport::spawn(
+                    &config.id, command, args, cwd, env, inbound_tx,
+                )?),
+                *timeout_ms,
+            ),
+            McpTransportConfig::StreamableHttp {
+                url,
+                headers,
+                bearer_token_env,
+                headers_env,
+                timeout_ms,
+            } => {
+                let headers = resolve_http_headers(
+                    &config.id,
+                    headers,
+                    bearer_token_env.as_deref(),
+                    headers_env,
+                )?;
+                (
+                    McpClientTransport::StreamableHttp(StreamableHttpTransport::new(
+                        &config.id,
+                        url,
+                        headers,
+                        Duration::from_millis((*timeout_ms).max(1)),
+                        inbound_tx,
+                    )?),
+                    *timeout_ms,
+                )
+            }
+        };
 
         Ok(Self {
             server_id: config.id.clone(),
-            child,
-            stdin,
+            transport,
             inbound_rx,
-            timeout: Duration::from_millis((*timeout_ms).max(1)),
+            timeout: Duration::from_millis(timeout_ms.max(1)),
             next_id: 1,
             current_roots: Vec::new(),
+            subscriptions: BTreeSet::new(),
+            updated_resources: Vec::new(),
         })
     }
 
@@ -177,6 +133,9 @@ impl McpStdioClient {
                 initialize.protocol_version
             ));
         }
+        if let McpClientTransport::StreamableHttp(http) = &mut self.transport {
+            http.protocol_version = Some(initialize.protocol_version.clone());
+        }
 
         self.write_message(&jsonrpc::notification("notifications/initialized", None))?;
         Ok(initialize)
@@ -213,14 +172,7 @@ impl McpStdioClient {
         input: Value,
         roots: Vec<McpRoot>,
     ) -> Result<McpCallToolResult, String> {
-        if self.current_roots != roots {
-            self.current_roots = roots;
-            self.write_message(&jsonrpc::notification(
-                "notifications/roots/list_changed",
-                None,
-            ))?;
-        }
-
+        self.sync_roots(roots)?;
         let result = self.request(
             "tools/call",
             json!({
@@ -232,6 +184,137 @@ impl McpStdioClient {
         parse_call_tool_result(&result)
     }
 
+    pub(crate) fn read_resource(
+        &mut self,
+        uri: &str,
+        roots: Vec<McpRoot>,
+    ) -> Result<Vec<McpResourceContent>, String> {
+        self.sync_roots(roots)?;
+        let result = self.request("resources/read", json!({ "uri": uri }))?;
+        parse_resource_contents(&result)
+    }
+
+    pub(crate) fn get_prompt(
+        &mut self,
+        name: &str,
+        arguments: Value,
+        roots: Vec<McpRoot>,
+    ) -> Result<McpGetPromptResult, String> {
+        self.sync_roots(roots)?;
+        let result = self.request(
+            "prompts/get",
+            json!({
+                "name": name,
+                "arguments": arguments,
+            }),
+        )?;
+        parse_get_prompt_result(&result)
+    }
+
+    pub(crate) fn subscribe_resource(&mut self, uri: &str) -> Result<(), String> {
+        if !self.subscriptions.insert(uri.to_string()) {
+            return Ok(());
+        }
+        // Registered first: an update may arrive alongside the response.
+        if let Err(err) = self.request("resources/subscribe", json!({ "uri": uri })) {
+            self.subscriptions.remove(uri);
+            return Err(err);
+        }
+        self.ensure_listen_stream();
+        Ok(())
+    }
+
+    pub(crate) fn unsubscribe_resource(&mut self, uri: &str) -> Result<(), String> {
+        if !self.subscriptions.remove(uri) {
+            return Ok(());
+        }
+        self.request("resources/unsubscribe", json!({ "uri": uri }))?;
+        Ok(())
+    }
+
+    /// Handle whatever the server pushed while no request was in flight:
+    /// pings and `roots/list` get answered, resource updates are queued.
+    pub(crate) fn poll_notifications(&mut self) -> Result<(), String> {
+        loop {
+            match self.inbound_rx.try_recv() {
+                Ok(InboundMessage::Json(message)) => {
+                    if let Some(server_method) = jsonrpc::extract_request_method(&message) {
+                        self.handle_server_request(server_method, &message)?;
+                    } else {
+                        self.handle_notification(&message);
+                    }
+                }
+                Ok(InboundMessage::ReadError(err)) => return Err(err),
+                Ok(InboundMessage::Closed) => {
+                    return Err("MCP server closed the stdio stream while idle.".to_string());
+                }
+                Ok(InboundMessage::StreamEnded { request_id, .. }) => {
+                    if request_id.is_none() {
+                        self.mark_listen_stream_closed();
+                    }
+                }
+                Err(mpsc::TryRecvError::Empty) => break,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    return Err(format!("MCP server '{}' disconnected.", self.server_id));
+                }
+            }
+        }
+        self.ensure_listen_stream();
+        Ok(())
+    }
+
+    pub(crate) fn take_updated_resources(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.updated_resources)
+    }
+
+    fn sync_roots(&mut self, roots: Vec<McpRoot>) -> Result<(), String> {
+        if self.current_roots != roots {
+            self.current_roots = roots;
+            self.write_message(&jsonrpc::notification(
+                "notifications/roots/list_changed",
+                None,
+            ))?;
+        }
+        Ok(())
+    }
+
+    fn handle_notification(&mut self, message: &Value) {
+        if jsonrpc::extract_notification_method(message) != Some("notifications/resources/updated")
+        {
+            return;
+        }
+        let Some(uri) = message
+            .get("params")
+            .and_then(|params| params.get("uri"))
+            .and_then(Value::as_str)
+        else {
+            return;
+        };
+        if self.subscriptions.contains(uri)
+            && !self.updated_resources.iter().any(|seen| seen == uri)
+        {
+            self.updated_resources.push(uri.to_string());
+        }
+    }
+
+    /// Over HTTP, subscription updates travel on the standalone GET stream.
+    fn ensure_listen_stream(&mut self) {
+        if self.subscriptions.is_empty() {
+            return;
+        }
+        if let McpClientTransport::StreamableHttp(http) = &mut self.transport {
+            http.ensure_listen_stream();
+        }
+    }
+
+    fn mark_listen_stream_closed(&mut self) {
+        if let McpClientTransport::StreamableHttp(http) = &mut self.transport {
+            if http.listen_stream == ListenStreamState::Open {
+                http.listen_stream = ListenStreamState::Closed;
+            }
+        }
+    }
+
     fn request(&mut self, method: &str, params: Value) -> Result<Value, String> {
         let request_id = self.next_request_id();
         self.write_message(&jsonrpc::request(request_id, method, params))?;
@@ -240,22 +323,24 @@ impl McpStdioClient {
         loop {
             let remaining = deadline.saturating_duration_since(Instant::now());
             if remaining.is_zero() {
-                self.abort_request(method);
+                self.abort_request(request_id, method);
                 return Err(format!(
-                    "MCP request '{}' timed out after {} ms; stdio server was cancelled.",
+                    "MCP request '{}' timed out after {} ms; {} was cancelled.",
                     method,
-                    self.timeout.as_millis()
+                    self.timeout.as_millis(),
+                    self.transport.cancellation_target()
                 ));
             }
 
             let message = match self.inbound_rx.recv_timeout(remaining) {
                 Ok(message) => message,
                 Err(mpsc::RecvTimeoutError::Timeout) => {
-                    self.abort_request(method);
+                    self.abort_request(request_id, method);
                     return Err(format!(
-                        "MCP request '{}' timed out waiting for response after {} ms; stdio server was cancelled.",
+                        "MCP request '{}' timed out waiting for response after {} ms; {} was cancelled.",
                         method,
-                        self.timeout.as_millis()
+                        self.timeout.as_millis(),
+                        self.transport.cancellation_target()
                     ));
                 }
                 Err(mpsc::RecvTimeoutError::Disconnected) => {
@@ -292,6 +377,7 @@ impl McpStdioClient {
                     }
 
                     if jsonrpc::extract_notification_method(&message).is_some() {
+                        self.handle_notification(&message);
                         continue;
                     }
                 }
@@ -302,6 +388,34 @@ impl McpStdioClient {
                         method
                     ));
                 }
+                InboundMessage::StreamEnded {
+                    request_id: ended_request_id,
+                    last_event_id,
+                } => {
+                    // Streams of earlier requests end after their response;
+                    // only the stream still owing our response matters.
+                    if ended_request_id.is_none() {
+                        self.mark_listen_stream_closed();
+                    }
+                    if ended_request_id != Some(request_id) {
+                        continue;
+                    }
+                    let McpClientTransport::StreamableHttp(http) = &mut self.transport else {
+                        continue;
+                    };
+                    let Some(last_event_id) = last_event_id else {
+                        return Err(format!(
+                            "MCP server closed the SSE stream while handling '{}' without a resumable event id.",
+                            method
+                        ));
+                    };
+                    http.resume(request_id, &last_event_id).map_err(|err| {
+                        format!(
+                            "MCP server closed the SSE stream while handling '{}' and it could not be resumed: {}",
+                            method, err
+                        )
+                    })?;
+                }
             }
         }
     }
@@ -325,6 +439,180 @@ impl McpStdioClient {
         self.write_message(&response)
     }
 
+    fn write_message(&mut self, value: &Value) -> Result<(), String> {
+        match &mut self.transport {
+            McpClientTransport::Stdio(stdio) => stdio.write_message(value),
+            McpClientTransport::StreamableHttp(http) => http.post(value),
+        }
+    }
+
+    fn next_request_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn abort_request(&mut self, request_id: u64, method: &str) {
+        match &mut self.transport {
+            McpClientTransport::Stdio(stdio) => {
+                if let Err(err) = stdio.child.kill() {
+                    tracing::debug!(
+                        server_id = self.server_id,
+                        request_method = method,
+                        %err,
+                        "MCP timeout cancellation kill failed"
+                    );
+                }
+                let _ = stdio.child.wait();
+            }
+            McpClientTransport::StreamableHttp(http) => {
+                let cancelled = jsonrpc::notification(
+                    "notifications/cancelled",
+                    Some(json!({
+                        "requestId": request_id,
+                        "reason": "timeout",
+                    })),
+                );
+                if let Err(err) = http.post(&cancelled) {
+                    tracing::debug!(
+                        server_id = self.server_id,
+                        request_method = method,
+                        %err,
+                        "MCP timeout cancellation notification failed"
+                    );
+                }
+            }
+        }
+    }
+}
+
+impl McpClientTransport {
+    fn cancellation_target(&self) -> &'static str {
+        match self {
+            Self::Stdio(_) => "stdio server",
+            Self::StreamableHttp(_) => "HTTP request",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StdioTransport {
+    child: Child,
+    stdin: ChildStdin,
+}
+
+impl StdioTransport {
+    fn spawn(
+        server_id: &str,
+        command: &str,
+        args: &[String],
+        cwd: &Option<PathBuf>,
+        env: &BTreeMap<String, String>,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+    ) -> Result<Self, String> {
+        if command.trim().is_empty() {
+            return Err(format!(
+                "MCP server '{}' stdio command is empty.",
+                server_id
+            ));
+        }
+
+        let mut child = Command::new(command);
+        child.args(args);
+        child.stdin(Stdio::piped());
+        child.stdout(Stdio::piped());
+        child.stderr(Stdio::piped());
+        if let Some(cwd) = cwd {
+            child.current_dir(cwd);
+        }
+        for (key, value) in env {
+            child.env(key, value);
+        }
+
+        let mut child = child.spawn().map_err(|err| {
+            format!(
+                "Failed to spawn MCP server '{}' using command '{}': {}",
+                server_id, command, err
+            )
+        })?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| format!("MCP server '{}' stdin pipe is unavailable.", server_id))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| format!("MCP server '{}' stdout pipe is unavailable.", server_id))?;
+
+        let reader_server_id = server_id.to_string();
+        thread::Builder::new()
+            .name(format!("mcp-stdout-{}", server_id))
+            .spawn(move || {
+                let server_id = reader_server_id;
+                let mut reader = BufReader::new(stdout);
+                loop {
+                    let mut line = String::new();
+                    match reader.read_line(&mut line) {
+                        Ok(0) => {
+                            let _ = inbound_tx.send(InboundMessage::Closed);
+                            break;
+                        }
+                        Ok(_) => {
+                            let trimmed = line.trim();
+                            if trimmed.is_empty() {
+                                continue;
+                            }
+                            match serde_json::from_str::<Value>(trimmed) {
+                                Ok(message) => {
+                                    let _ = inbound_tx.send(InboundMessage::Json(message));
+                                }
+                                Err(err) => {
+                                    let _ = inbound_tx.send(InboundMessage::ReadError(format!(
+                                        "Invalid MCP JSON from server '{}': {}",
+                                        server_id, err
+                                    )));
+                                    break;
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            let _ = inbound_tx.send(InboundMessage::ReadError(format!(
+                                "Failed reading MCP server '{}': {}",
+                                server_id, err
+                            )));
+                            break;
+                        }
+                    }
+                }
+            })
+            .map_err(|err| format!("Failed to start MCP stdout reader thread: {err}"))?;
+
+        if let Some(stderr) = child.stderr.take() {
+            let server_id = server_id.to_string();
+            let _ = thread::Builder::new()
+                .name(format!("mcp-stderr-{}", server_id))
+                .spawn(move || {
+                    let mut reader = BufReader::new(stderr);
+                    loop {
+                        let mut line = String::new();
+                        match reader.read_line(&mut line) {
+                            Ok(0) => break,
+                            Ok(_) => {
+                                let message = line.trim();
+                                if !message.is_empty() {
+                                    tracing::debug!(server_id, message, "MCP_STDERR");
+                                }
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                });
+        }
+
+        Ok(Self { child, stdin })
+    }
+
     fn write_message(&mut self, value: &Value) -> Result<(), String> {
         let serialized = serde_json::to_string(value)
             .map_err(|err| format!("Failed to serialize MCP message: {err}"))?;
@@ -334,30 +622,357 @@ impl McpStdioClient {
             .and_then(|_| self.stdin.flush())
             .map_err(|err| format!("Failed to write MCP stdio message: {err}"))
     }
+}
 
-    fn next_request_id(&mut self) -> u64 {
-        let id = self.next_id;
-        self.next_id += 1;
-        id
+impl Drop for StdioTransport {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Streamable HTTP transport: every client message is a POST, answered with
+/// plain JSON, an SSE stream, or `202 Accepted`. Inbound messages from either
+/// body shape are fed into the same channel the stdio reader uses.
+struct StreamableHttpTransport {
+    server_id: String,
+    url: String,
+    agent: ureq::Agent,
+    headers: Vec<(String, String)>,
+    session_id: Option<String>,
+    protocol_version: Option<String>,
+    listen_stream: ListenStreamState,
+    inbound_tx: mpsc::Sender<InboundMessage>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ListenStreamState {
+    Closed,
+    Open,
+    /// The server answered the GET with 405: notifications only arrive on
+    /// POST response streams.
+    Unsupported,
+}
+
+// Resolved headers carry credentials and stay out of debug output.
+impl fmt::Debug for StreamableHttpTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamableHttpTransport")
+            .field("server_id", &self.server_id)
+            .field("url", &self.url)
+            .field("session_id", &self.session_id)
+            .field("protocol_version", &self.protocol_version)
+            .finish_non_exhaustive()
     }
+}
 
-    fn abort_request(&mut self, method: &str) {
-        if let Err(err) = self.child.kill() {
-            tracing::debug!(
-                server_id = self.server_id,
-                request_method = method,
-                %err,
-                "MCP timeout cancellation kill failed"
-            );
+impl StreamableHttpTransport {
+    fn new(
+        server_id: &str,
+        url: &str,
+        headers: Vec<(String, String)>,
+        timeout: Duration,
+        inbound_tx: mpsc::Sender<InboundMessage>,
+    ) -> Result<Self, String> {
+        let url = url.trim();
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!(
+                "MCP server '{}' streamable HTTP url must start with http:// or https://.",
+                server_id
+            ));
         }
-        let _ = self.child.wait();
+
+        Ok(Self {
+            server_id: server_id.to_string(),
+            url: url.to_string(),
+            agent: ureq::AgentBuilder::new()
+                .timeout_connect(timeout)
+                .timeout_read(timeout)
+                .timeout_write(timeout)
+                .build(),
+            headers,
+            session_id: None,
+            protocol_version: None,
+            listen_stream: ListenStreamState::Closed,
+            inbound_tx,
+        })
+    }
+
+    fn post(&mut self, message: &Value) -> Result<(), String> {
+        let body = serde_json::to_string(message)
+            .map_err(|err| format!("Failed to serialize MCP message: {err}"))?;
+        let request = self
+            .with_headers(self.agent.post(&self.url))
+            .set("Content-Type", "application/json")
+            .set("Accept", "application/json, text/event-stream");
+        let response = self.check_response(request.send_string(&body))?;
+        if let Some(session_id) = response.header(MCP_SESSION_ID_HEADER) {
+            self.session_id = Some(session_id.to_string());
+        }
+        if response.status() == 202 {
+            return Ok(());
+        }
+
+        let request_id = jsonrpc::extract_request_method(message)
+            .and_then(|_| message.get("id"))
+            .and_then(Value::as_u64);
+        if is_event_stream(&response) {
+            return self.spawn_sse_reader(response, request_id);
+        }
+
+        let body = response.into_string().map_err(|err| {
+            format!(
+                "Failed reading MCP server '{}' HTTP response: {err}",
+                self.server_id
+            )
+        })?;
+        if body.trim().is_empty() {
+            return Ok(());
+        }
+        let messages = match serde_json::from_str::<Value>(&body) {
+            Ok(Value::Array(batch)) => batch,
+            Ok(message) => vec![message],
+            Err(err) => {
+                return Err(format!(
+                    "Invalid MCP JSON from server '{}': {}",
+                    self.server_id, err
+                ))
+            }
+        };
+        for message in messages {
+            let _ = self.inbound_tx.send(InboundMessage::Json(message));
+        }
+        Ok(())
+    }
+
+    /// Reopen an interrupted SSE stream with `Last-Event-ID`, so the server
+    /// can replay the messages sent after that event.
+    fn resume(&mut self, request_id: u64, last_event_id: &str) -> Result<(), String> {
+        let request = self
+            .with_headers(self.agent.get(&self.url))
+            .set("Accept", "text/event-stream")
+            .set("Last-Event-ID", last_event_id);
+        let response = self.check_response(request.call())?;
+        if !is_event_stream(&response) {
+            return Err(format!(
+                "MCP server '{}' did not answer the resumption with an SSE stream.",
+                self.server_id
+            ));
+        }
+        self.spawn_sse_reader(response, Some(request_id))
+    }
+
+    fn ensure_listen_stream(&mut self) {
+        if self.listen_stream != ListenStreamState::Closed {
+            return;
+        }
+        let request = self
+            .with_headers(self.agent.get(&self.url))
+            .set("Accept", "text/event-stream");
+        let opened = match request.call() {
+            Err(ureq::Error::Status(405, _)) => {
+                self.listen_stream = ListenStreamState::Unsupported;
+                return;
+            }
+            result => self.check_response(result).and_then(|response| {
+                if is_event_stream(&response) {
+                    self.spawn_sse_reader(response, None)
+                } else {
+                    Err("listen stream is not text/event-stream".to_string())
+                }
+            }),
+        };
+        match opened {
+            Ok(()) => self.listen_stream = ListenStreamState::Open,
+            Err(err) => {
+                tracing::debug!(server_id = self.server_id, %err, "MCP listen stream unavailable");
+            }
+        }
+    }
+
+    fn with_headers(&self, mut request: ureq::Request) -> ureq::Request {
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        if let Some(session_id) = self.session_id.as_deref() {
+            request = request.set(MCP_SESSION_ID_HEADER, session_id);
+        }
+        if let Some(protocol_version) = self.protocol_version.as_deref() {
+            request = request.set(MCP_PROTOCOL_VERSION_HEADER, protocol_version);
+        }
+        request
+    }
+
+    fn check_response(
+        &self,
+        result: Result<ureq::Response, ureq::Error>,
+    ) -> Result<ureq::Response, String> {
+        match result {
+            Ok(response) => Ok(response),
+            Err(ureq::Error::Status(404, _)) if self.session_id.is_some() => Err(format!(
+                "MCP session expired on server '{}' (HTTP 404).",
+                self.server_id
+            )),
+            Err(ureq::Error::Status(code, response)) => {
+                let detail = response.into_string().unwrap_or_default();
+                Err(format!(
+                    "MCP server '{}' rejected the HTTP request with status {}: {}",
+                    self.server_id,
+                    code,
+                    detail.trim()
+                ))
+            }
+            Err(ureq::Error::Transport(err)) => Err(format!(
+                "Failed to reach MCP server '{}' over HTTP: {}",
+                self.server_id, err
+            )),
+        }
+    }
+
+    fn spawn_sse_reader(
+        &self,
+        response: ureq::Response,
+        request_id: Option<u64>,
+    ) -> Result<(), String> {
+        let inbound_tx = self.inbound_tx.clone();
+        let server_id = self.server_id.clone();
+        thread::Builder::new()
+            .name(format!("mcp-sse-{}", self.server_id))
+            .spawn(move || {
+                let mut reader = BufReader::new(response.into_reader());
+                let mut parser = SseEventParser::default();
+                let mut last_event_id = None;
+                loop {
+                    let mut line = String::new();
+                    match reader.read_line(&mut line) {
+                        Ok(0) => break,
+                        Ok(_) => {
+                            let Some(event) = parser.push_line(&line) else {
+                                continue;
+                            };
+                            if event.id.is_some() {
+                                last_event_id = event.id;
+                            }
+                            if event.data.trim().is_empty() {
+                                continue;
+                            }
+                            match serde_json::from_str::<Value>(&event.data) {
+                                Ok(message) => {
+                                    let _ = inbound_tx.send(InboundMessage::Json(message));
+                                }
+                                Err(err) => {
+                                    let _ = inbound_tx.send(InboundMessage::ReadError(format!(
+                                        "Invalid MCP JSON from server '{}': {}",
+                                        server_id, err
+                                    )));
+                                    return;
+                                }
+                            }
+                        }
+                        Err(err) => {
+                            tracing::debug!(server_id, %err, "MCP SSE stream interrupted");
+                            break;
+                        }
+                    }
+                }
+                let _ = inbound_tx.send(InboundMessage::StreamEnded {
+                    request_id,
+                    last_event_id,
+                });
+            })
+            .map(|_| ())
+            .map_err(|err| format!("Failed to start MCP SSE reader thread: {err}"))
     }
 }
 
-impl Drop for McpStdioClient {
+impl Drop for StreamableHttpTransport {
+    /// Best-effort session teardown; servers that do not support it answer 405.
     fn drop(&mut self) {
-        let _ = self.child.kill();
-        let _ = self.child.wait();
+        if self.session_id.is_some() {
+            let _ = self.with_headers(self.agent.delete(&self.url)).call();
+        }
+    }
+}
+
+fn is_event_stream(response: &ureq::Response) -> bool {
+    response
+        .header("Content-Type")
+        .is_some_and(|content_type| content_type.starts_with("text/event-stream"))
+}
+
+/// Static headers plus credentials read from the environment (populated from
+/// the env file at boot), so secrets never appear in the TOML config.
+fn resolve_http_headers(
+    server_id: &str,
+    headers: &BTreeMap<String, String>,
+    bearer_token_env: Option<&str>,
+    headers_env: &BTreeMap<String, String>,
+) -> Result<Vec<(String, String)>, String> {
+    let mut resolved = headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect::<Vec<_>>();
+    for (name, variable) in headers_env {
+        resolved.push((name.clone(), read_env_credential(server_id, variable)?));
+    }
+    if let Some(variable) = bearer_token_env {
+        let token = read_env_credential(server_id, variable)?;
+        resolved.push(("Authorization".to_string(), format!("Bearer {token}")));
+    }
+    Ok(resolved)
+}
+
+fn read_env_credential(server_id: &str, variable: &str) -> Result<String, String> {
+    std::env::var(variable)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| {
+            format!(
+                "MCP server '{}' requires environment variable '{}', which is not set.",
+                server_id, variable
+            )
+        })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SseEvent {
+    id: Option<String>,
+    data: String,
+}
+
+/// Line-oriented `text/event-stream` parser; only `id` and `data` matter to MCP.
+#[derive(Debug, Default)]
+struct SseEventParser {
+    id: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseEventParser {
+    fn push_line(&mut self, line: &str) -> Option<SseEvent> {
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if self.id.is_none() && self.data.is_empty() {
+                return None;
+            }
+            return Some(SseEvent {
+                id: self.id.take(),
+                data: std::mem::take(&mut self.data).join("\n"),
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "id" => self.id = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
     }
 }
 
@@ -387,6 +1002,8 @@ fn parse_initialize_result(result: &Value) -> Result<McpInitializeResult, String
 
 fn parse_server_capabilities(value: &Value) -> McpServerCapabilities {
     McpServerCapabilities {
+        resources: value.get("resources").is_some(),
+        prompts: value.get("prompts").is_some(),
         tools_list_changed: value
             .get("tools")
             .and_then(|tools| tools.get("listChanged"))
@@ -531,11 +1148,82 @@ fn parse_prompts(result: &Value) -> Result<Vec<McpPromptDefinition>, String> {
                     .get("description")
                     .and_then(Value::as_str)
                     .map(ToString::to_string),
+                arguments: prompt
+                    .get("arguments")
+                    .and_then(Value::as_array)
+                    .map(|arguments| {
+                        arguments
+                            .iter()
+                            .filter_map(|argument| {
+                                Some(McpPromptArgument {
+                                    name: argument.get("name")?.as_str()?.to_string(),
+                                    description: argument
+                                        .get("description")
+                                        .and_then(Value::as_str)
+                                        .map(ToString::to_string),
+                                    required: argument
+                                        .get("required")
+                                        .and_then(Value::as_bool)
+                                        .unwrap_or(false),
+                                })
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
             })
         })
         .collect())
 }
 
+fn parse_resource_contents(result: &Value) -> Result<Vec<McpResourceContent>, String> {
+    let contents = result
+        .get("contents")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "MCP resources/read result is missing contents.".to_string())?;
+    Ok(contents
+        .iter()
+        .filter_map(|content| {
+            Some(McpResourceContent {
+                uri: content.get("uri")?.as_str()?.to_string(),
+                mime_type: content
+                    .get("mimeType")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string),
+                text: content
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string),
+                blob: content
+                    .get("blob")
+                    .and_then(Value::as_str)
+                    .map(ToString::to_string),
+            })
+        })
+        .collect())
+}
+
+fn parse_get_prompt_result(result: &Value) -> Result<McpGetPromptResult, String> {
+    let messages = result
+        .get("messages")
+        .and_then(Value::as_array)
+        .ok_or_else(|| "MCP prompts/get result is missing messages.".to_string())?;
+    Ok(McpGetPromptResult {
+        description: result
+            .get("description")
+            .and_then(Value::as_str)
+            .map(ToString::to_string),
+        messages: messages
+            .iter()
+            .filter_map(|message| {
+                Some(McpPromptMessage {
+                    role: message.get("role")?.as_str()?.to_string(),
+                    content: message.get("content").cloned().unwrap_or(Value::Null),
+                })
+            })
+            .collect(),
+    })
+}
+
 fn parse_call_tool_result(result: &Value) -> Result<McpCallToolResult, String> {
     Ok(McpCallToolResult {
         content: result
@@ -550,3 +1238,7 @@ fn parse_call_tool_result(result: &Value) -> Result<McpCallToolResult, String> {
             .unwrap_or(false),
     })
 }
+
+#[cfg(test)]
+#[path = "tests/client.rs"]
+mod tests;
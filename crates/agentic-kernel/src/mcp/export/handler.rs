@@ -0,0 +1,321 @@
+use std::collections::BTreeSet;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Map, Value};
+
+use crate::config::McpExportConfig;
+use crate::mcp::jsonrpc;
+use crate::mcp::models::MCP_PROTOCOL_VERSION;
+use crate::tool_registry::{ToolRegistry, ToolRegistryEntry};
+use crate::tools::governance::govern_tool_execution;
+use crate::tools::invocation::{
+    normalize_tool_name, ProcessPermissionPolicy, ToolCaller, ToolContext, ToolInvocation,
+    ToolInvocationTransport,
+};
+use crate::tools::SyscallRateMap;
+
+use super::prompts::WorkflowTemplateCatalog;
+use super::resources::KernelResourceCatalog;
+
+/// Rate-limit and audit bucket shared by every external MCP client.
+pub(super) const MCP_EXPORT_PID: u64 = 0;
+
+const JSONRPC_PARSE_ERROR: i64 = -32700;
+const JSONRPC_INVALID_REQUEST: i64 = -32600;
+const JSONRPC_METHOD_NOT_FOUND: i64 = -32601;
+const JSONRPC_INVALID_PARAMS: i64 = -32602;
+const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+
+/// Shared state behind both MCP export transports.
+///
+/// Tool calls run in-process through `govern_tool_execution` with the
+/// `mcp_export` trust scope, so they share rate limits and audit with the
+/// syscall worker. The registry is a snapshot refreshed by the event loop.
+pub(crate) struct McpExportState {
+    registry: Mutex<ToolRegistry>,
+    syscall_rates: Arc<Mutex<SyscallRateMap>>,
+    exposed_tools: Vec<String>,
+    allow_dangerous_tools: bool,
+    tools_list_changed: bool,
+    resources: KernelResourceCatalog,
+    templates: WorkflowTemplateCatalog,
+}
+
+impl McpExportState {
+    pub(crate) fn new(
+        config: &McpExportConfig,
+        database_path: std::path::PathBuf,
+        registry: &ToolRegistry,
+        syscall_rates: Arc<Mutex<SyscallRateMap>>,
+        tools_list_changed: bool,
+    ) -> Result<Self, String> {
+        let exposed_tools = config
+            .exposed_tools
+            .iter()
+            .map(|name| normalize_tool_name(name))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            registry: Mutex::new(registry.clone()),
+            syscall_rates,
+            exposed_tools,
+            allow_dangerous_tools: config.allow_dangerous_tools,
+            tools_list_changed,
+            resources: KernelResourceCatalog::new(database_path, config.resource_list_limit),
+            templates: WorkflowTemplateCatalog::new(config.workflow_templates_dir.clone()),
+        })
+    }
+
+    pub(crate) fn sync_tool_registry(&self, registry: &ToolRegistry) {
+        if let Ok(mut snapshot) = self.registry.lock() {
+            *snapshot = registry.clone();
+        }
+    }
+
+    /// Handle one raw JSON-RPC message. Returns `None` for notifications and
+    /// client responses, which never get a reply.
+    pub(crate) fn handle_raw(&self, raw: &[u8]) -> Option<Value> {
+        match serde_json::from_slice::<Value>(raw) {
+            Ok(message) => self.handle_message(&message),
+            Err(err) => Some(error_without_id(
+                JSONRPC_PARSE_ERROR,
+                &format!("Invalid JSON-RPC message: {err}"),
+            )),
+        }
+    }
+
+    pub(crate) fn handle_message(&self, message: &Value) -> Option<Value> {
+        if !message.is_object() {
+            return Some(error_without_id(
+                JSONRPC_INVALID_REQUEST,
+                "JSON-RPC message must be an object.",
+            ));
+        }
+        if jsonrpc::extract_notification_method(message).is_some()
+            || jsonrpc::extract_response_id(message).is_some()
+        {
+            return None;
+        }
+        let Some(method) = jsonrpc::extract_request_method(message) else {
+            return Some(error_without_id(
+                JSONRPC_INVALID_REQUEST,
+                "JSON-RPC request is missing 'method'.",
+            ));
+        };
+        let id = message.get("id").cloned().unwrap_or(Value::Null);
+        let params = message
+            .get("params")
+            .cloned()
+            .unwrap_or_else(|| Value::Object(Map::new()));
+
+        let outcome = match method {
+            "initialize" => Ok(self.initialize_result()),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(self.list_tools()),
+            "tools/call" => self.call_tool(&params, &id),
+            "resources/list" => self.resources.list(),
+            "resources/templates/list" => Ok(self.resources.list_templates()),
+            "resources/read" => self.resources.read(&params),
+            "prompts/list" => self.templates.list(),
+            "prompts/get" => self.templates.get(&params),
+            other => Err((
+                JSONRPC_METHOD_NOT_FOUND,
+                format!("Method '{other}' is not supported by the AgenticOS MCP server."),
+            )),
+        };
+
+        Some(response_with_id(id, outcome))
+    }
+
+    fn initialize_result(&self) -> Value {
+        json!({
+            "protocolVersion": MCP_PROTOCOL_VERSION,
+            "capabilities": {
+                "tools": { "listChanged": self.tools_list_changed },
+                "resources": { "listChanged": false, "subscribe": false },
+                "prompts": { "listChanged": false },
+            },
+            "serverInfo": {
+                "name": "agenticos",
+                "title": "AgenticOS",
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "instructions": "Tools run under the AgenticOS 'mcp_export' trust scope. Resources expose persisted sessions, workflow artifacts and core dumps; prompts expose stored workflow templates.",
+        })
+    }
+
+    fn list_tools(&self) -> Value {
+        let Ok(registry) = self.registry.lock() else {
+            return json!({ "tools": [] });
+        };
+        let tools =
+            exported_tool_entries(&registry, &self.exposed_tools, self.allow_dangerous_tools)
+                .into_iter()
+                .map(tool_definition)
+                .collect::<Vec<_>>();
+        json!({ "tools": tools })
+    }
+
+    fn call_tool(&self, params: &Value, id: &Value) -> Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str).ok_or_else(|| {
+            (
+                JSONRPC_INVALID_PARAMS,
+                "tools/call requires a 'name'.".to_string(),
+            )
+        })?;
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| Value::Object(Map::new()));
+
+        let registry = self
+            .registry
+            .lock()
+            .map_err(|_| {
+                (
+                    JSONRPC_INTERNAL_ERROR,
+                    "MCP export registry lock poisoned.".to_string(),
+                )
+            })?
+            .clone();
+        let exported =
+            exported_tool_entries(&registry, &self.exposed_tools, self.allow_dangerous_tools)
+                .into_iter()
+                .map(|entry| entry.descriptor.name.clone())
+                .collect::<Vec<_>>();
+        let canonical = registry
+            .resolve_invocation_name(name)
+            .map(|entry| entry.descriptor.name.clone())
+            .filter(|canonical| exported.contains(canonical))
+            .ok_or_else(|| {
+                (
+                    JSONRPC_INVALID_PARAMS,
+                    format!("Tool '{name}' is not exported by this AgenticOS kernel."),
+                )
+            })?;
+
+        let call_id = Some(format!("mcp-export-{}", request_id_label(id)));
+        let invocation = ToolInvocation::new(canonical, arguments, call_id.clone())
+            .map_err(|err| (JSONRPC_INVALID_PARAMS, err.to_string()))?;
+        let permissions = ProcessPermissionPolicy::mcp_export(&registry, exported)
+            .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?;
+        let context = ToolContext {
+            pid: None,
+            session_id: None,
+            caller: ToolCaller::Programmatic,
+            permissions,
+            transport: ToolInvocationTransport::Structured,
+            call_id,
+        };
+
+        let result = {
+            let mut rates = self.syscall_rates.lock().map_err(|_| {
+                (
+                    JSONRPC_INTERNAL_ERROR,
+                    "Syscall rate-limit state is unavailable.".to_string(),
+                )
+            })?;
+            govern_tool_execution(&invocation, &context, &registry, MCP_EXPORT_PID, &mut rates)
+        };
+
+        let mut payload = json!({
+            "content": [{ "type": "text", "text": result.output }],
+            "isError": !result.success,
+        });
+        if let Some(structured) = result.output_json.filter(Value::is_object) {
+            payload["structuredContent"] = structured;
+        }
+        Ok(payload)
+    }
+}
+
+/// Tools an external MCP client may see and call.
+///
+/// Only enabled tools open to programmatic callers are eligible; tools that
+/// need human approval or input are never exported, dangerous ones only on
+/// opt-in.
+/// A non-empty `exposed_tools` list replaces the default allowlist.
+pub(super) fn exported_tool_entries<'a>(
+    registry: &'a ToolRegistry,
+    exposed_tools: &[String],
+    allow_dangerous_tools: bool,
+) -> Vec<&'a ToolRegistryEntry> {
+    let exposed = exposed_tools.iter().collect::<BTreeSet<_>>();
+    registry
+        .list()
+        .into_iter()
+        .filter(|entry| entry.descriptor.enabled)
+        .filter(|entry| {
+            entry
+                .descriptor
+                .allowed_callers
+                .contains(&ToolCaller::Programmatic)
+        })
+        .filter(|entry| !entry.descriptor.approval_required)
+        // Human-in-the-loop tools pause a live process; there is none here.
+        .filter(|entry| {
+            !entry
+                .descriptor
+                .capabilities
+                .iter()
+                .any(|capability| capability == "hitl")
+        })
+        .filter(|entry| allow_dangerous_tools || !entry.descriptor.dangerous)
+        .filter(|entry| {
+            if exposed.is_empty() {
+                entry.descriptor.default_allowlisted
+            } else {
+                exposed.contains(&entry.descriptor.name)
+            }
+        })
+        .collect()
+}
+
+fn tool_definition(entry: &ToolRegistryEntry) -> Value {
+    let descriptor = &entry.descriptor;
+    let hints = descriptor
+        .interop
+        .as_ref()
+        .map(|interop| interop.hints.clone())
+        .unwrap_or_default();
+    let mut definition = json!({
+        "name": descriptor.name,
+        "description": descriptor.description,
+        "inputSchema": descriptor.input_schema,
+        "outputSchema": descriptor.output_schema,
+        "annotations": {
+            "readOnlyHint": hints.read_only_hint,
+            "destructiveHint": hints.destructive_hint || descriptor.dangerous,
+            "idempotentHint": hints.idempotent_hint,
+            "openWorldHint": hints.open_world_hint,
+        },
+    });
+    if let Some(title) = hints.title {
+        definition["title"] = Value::String(title);
+    }
+    definition
+}
+
+fn request_id_label(id: &Value) -> String {
+    match id {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn response_with_id(id: Value, outcome: Result<Value, (i64, String)>) -> Value {
+    let mut response = match outcome {
+        Ok(result) => jsonrpc::success_response(0, result),
+        Err((code, message)) => jsonrpc::error_response(0, code, &message),
+    };
+    response["id"] = id;
+    response
+}
+
+fn error_without_id(code: i64, message: &str) -> Value {
+    response_with_id(Value::Null, Err((code, message.to_string())))
+}
+
+#[cfg(test)]
+#[path = "tests/handler.rs"]
+mod tests;
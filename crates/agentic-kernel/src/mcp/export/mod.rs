@@ -0,0 +1,260 @@
+//! AgenticOS exported as an MCP server.
+//!
+//! The same JSON-RPC handler serves two transports: streamable HTTP on
+//! `[mcp_export]` host/port (bearer auth with the kernel token) and stdio when
+//! the kernel is launched with `--mcp-stdio` by an MCP client.
+
+mod handler;
+mod prompts;
+mod resources;
+
+use std::io::{BufRead, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use serde_json::Value;
+
+use crate::config::KernelConfig;
+use crate::mcp::jsonrpc;
+use crate::tool_registry::ToolRegistry;
+use crate::tools::SyscallRateMap;
+use crate::transport::http;
+
+use handler::McpExportState;
+
+const MCP_EXPORT_PATH: &str = "/mcp";
+const MCP_STDIO_FLAG: &str = "--mcp-stdio";
+
+/// `true` when an MCP client spawned the kernel to talk over stdin/stdout.
+pub(crate) fn stdio_requested() -> bool {
+    std::env::args().any(|arg| arg == MCP_STDIO_FLAG)
+}
+
+pub(crate) struct McpExportRuntime {
+    state: Arc<McpExportState>,
+    listen_addr: Option<SocketAddr>,
+    stdio_writer: Option<Arc<Mutex<std::io::Stdout>>>,
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    server_handle: Option<JoinHandle<()>>,
+}
+
+impl McpExportRuntime {
+    pub(crate) fn start(
+        config: &KernelConfig,
+        registry: &ToolRegistry,
+        syscall_rates: Arc<Mutex<SyscallRateMap>>,
+        auth_token: Option<String>,
+        shutdown_requested: Arc<AtomicBool>,
+    ) -> Result<Option<Self>, String> {
+        let stdio = stdio_requested();
+        if !config.mcp_export.enabled && !stdio {
+            return Ok(None);
+        }
+
+        let state = Arc::new(McpExportState::new(
+            &config.mcp_export,
+            config.paths.database_path.clone(),
+            registry,
+            syscall_rates,
+            stdio,
+        )?);
+        let mut runtime = Self {
+            state: Arc::clone(&state),
+            listen_addr: None,
+            stdio_writer: None,
+            shutdown_tx: None,
+            server_handle: None,
+        };
+
+        if config.mcp_export.enabled {
+            let (listen_addr, shutdown_tx, handle) =
+                spawn_http(config, Arc::clone(&state), auth_token)?;
+            runtime.listen_addr = Some(listen_addr);
+            runtime.shutdown_tx = Some(shutdown_tx);
+            runtime.server_handle = Some(handle);
+        }
+        if stdio {
+            runtime.stdio_writer = Some(spawn_stdio(state, shutdown_requested)?);
+        }
+
+        Ok(Some(runtime))
+    }
+
+    pub(crate) fn listen_addr(&self) -> Option<SocketAddr> {
+        self.listen_addr
+    }
+
+    /// Refresh the exported tool snapshot after registry changes.
+    pub(crate) fn sync_tool_registry(&self, registry: &ToolRegistry) {
+        self.state.sync_tool_registry(registry);
+        if let Some(writer) = self.stdio_writer.as_ref() {
+            write_stdio_message(
+                writer,
+                &jsonrpc::notification("notifications/tools/list_changed", None),
+            );
+        }
+    }
+
+    pub(crate) fn shutdown(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(handle) = self.server_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn spawn_http(
+    config: &KernelConfig,
+    state: Arc<McpExportState>,
+    auth_token: Option<String>,
+) -> Result<(SocketAddr, mpsc::Sender<()>, JoinHandle<()>), String> {
+    let host = config.mcp_export.host.as_str();
+    let port = config.mcp_export.port;
+    let listener = TcpListener::bind((host, port))
+        .map_err(|err| format!("Failed to bind MCP export server on {host}:{port}: {err}"))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|err| format!("Failed to set MCP export listener non-blocking: {err}"))?;
+    let listen_addr = listener
+        .local_addr()
+        .map_err(|err| format!("Failed to query MCP export server address: {err}"))?;
+    let (shutdown_tx, shutdown_rx) = mpsc::channel();
+    let max_request_bytes = config.mcp_export.max_request_bytes;
+    let auth_token = auth_token.map(Arc::new);
+
+    let handle = thread::Builder::new()
+        .name("mcp-export-http".to_string())
+        .spawn(move || loop {
+            if shutdown_rx.try_recv().is_ok() {
+                break;
+            }
+
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let state = Arc::clone(&state);
+                    let auth_token = auth_token.clone();
+                    let spawned = thread::Builder::new()
+                        .name("mcp-export-request".to_string())
+                        .spawn(move || {
+                            let mut stream = stream;
+                            if let Err(err) = handle_http_connection(
+                                &mut stream,
+                                &state,
+                                auth_token.as_deref().map(String::as_str),
+                                max_request_bytes,
+                            ) {
+                                tracing::debug!(%err, "MCP export request failed");
+                            }
+                        });
+                    if let Err(err) = spawned {
+                        tracing::warn!(%err, "MCP export server could not spawn a request thread");
+                    }
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                    thread::sleep(Duration::from_millis(20));
+                }
+                Err(err) => {
+                    tracing::warn!(%err, "MCP export accept failed");
+                    thread::sleep(Duration::from_millis(50));
+                }
+            }
+        })
+        .map_err(|err| format!("Failed to start MCP export server thread: {err}"))?;
+
+    Ok((listen_addr, shutdown_tx, handle))
+}
+
+fn handle_http_connection(
+    stream: &mut TcpStream,
+    state: &McpExportState,
+    auth_token: Option<&str>,
+    max_request_bytes: usize,
+) -> Result<(), String> {
+    stream
+        .set_nonblocking(false)
+        .map_err(|err| format!("Failed to configure MCP export connection: {err}"))?;
+    let limits = http::HttpReadLimits::with_max_body_bytes(max_request_bytes);
+    let request = match http::read_http_request(stream, limits) {
+        Ok(request) => request,
+        Err(err) => return write_http_error(stream, err.status_code, &err.message),
+    };
+
+    if request.path != MCP_EXPORT_PATH {
+        return write_http_error(stream, 404, "Unknown MCP export route.");
+    }
+    if !request.origin_allowed() {
+        return write_http_error(stream, 403, "Origin not allowed.");
+    }
+    if let Some(expected) = auth_token {
+        match request.bearer_token() {
+            None => return write_http_error(stream, 401, "Missing bearer token."),
+            Some(token) if token != expected => {
+                return write_http_error(stream, 403, "Invalid bearer token.")
+            }
+            Some(_) => {}
+        }
+    }
+    // No server-initiated SSE stream: GET/DELETE are not offered.
+    if request.method != "POST" {
+        return write_http_error(stream, 405, "Only POST is supported.");
+    }
+
+    match state.handle_raw(&request.body) {
+        Some(response) => http::write_json_response(stream, 200, &response),
+        None => http::write_empty_response(stream, 202),
+    }
+}
+
+fn write_http_error(stream: &mut TcpStream, status_code: u16, message: &str) -> Result<(), String> {
+    http::write_json_response(stream, status_code, &jsonrpc_error_body(message))
+}
+
+fn jsonrpc_error_body(message: &str) -> Value {
+    let mut body = jsonrpc::error_response(0, -32600, message);
+    body["id"] = Value::Null;
+    body
+}
+
+/// Newline-delimited JSON-RPC over stdin/stdout. Closing stdin means the MCP
+/// client went away, so the kernel shuts down with it.
+fn spawn_stdio(
+    state: Arc<McpExportState>,
+    shutdown_requested: Arc<AtomicBool>,
+) -> Result<Arc<Mutex<std::io::Stdout>>, String> {
+    let writer = Arc::new(Mutex::new(std::io::stdout()));
+    let thread_writer = Arc::clone(&writer);
+    thread::Builder::new()
+        .name("mcp-export-stdio".to_string())
+        .spawn(move || {
+            let stdin = std::io::stdin();
+            for line in stdin.lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if let Some(response) = state.handle_raw(line.as_bytes()) {
+                    write_stdio_message(&thread_writer, &response);
+                }
+            }
+            tracing::info!("MCP export stdin closed; requesting kernel shutdown");
+            shutdown_requested.store(true, Ordering::SeqCst);
+        })
+        .map_err(|err| format!("Failed to start MCP export stdio thread: {err}"))?;
+    Ok(writer)
+}
+
+fn write_stdio_message(writer: &Mutex<std::io::Stdout>, message: &Value) {
+    let Ok(mut stdout) = writer.lock() else {
+        return;
+    };
+    let _ = serde_json::to_writer(&mut *stdout, message);
+    let _ = stdout.write_all(b"\n");
+    let _ = stdout.flush();
+}
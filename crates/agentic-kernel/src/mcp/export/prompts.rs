@@ -0,0 +1,159 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const JSONRPC_INVALID_PARAMS: i64 = -32602;
+const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+
+/// Workflow template as stored under `mcp_export.workflow_templates_dir`.
+///
+/// The shape mirrors the workspace app's template definitions, so a template
+/// exported from the GUI as JSON can be dropped into the directory unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct StoredWorkflowTemplate {
+    pub(super) id: String,
+    pub(super) name: String,
+    #[serde(default)]
+    pub(super) description: Option<String>,
+    #[serde(default)]
+    pub(super) summary: Option<String>,
+    #[serde(default, alias = "failurePolicy")]
+    pub(super) failure_policy: Option<String>,
+    pub(super) tasks: Vec<StoredWorkflowTemplateTask>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct StoredWorkflowTemplateTask {
+    pub(super) id: String,
+    #[serde(default)]
+    pub(super) role: Option<String>,
+    pub(super) prompt: String,
+    #[serde(default)]
+    pub(super) deps: Vec<String>,
+}
+
+/// Stored workflow templates published as MCP prompts.
+pub(super) struct WorkflowTemplateCatalog {
+    templates_dir: PathBuf,
+}
+
+impl WorkflowTemplateCatalog {
+    pub(super) fn new(templates_dir: PathBuf) -> Self {
+        Self { templates_dir }
+    }
+
+    pub(super) fn list(&self) -> Result<Value, (i64, String)> {
+        let prompts = load_workflow_templates(&self.templates_dir)
+            .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?
+            .into_iter()
+            .map(|template| {
+                json!({
+                    "name": template.id,
+                    "title": template.name,
+                    "description": template.summary.or(template.description),
+                    "arguments": [{
+                        "name": "input",
+                        "description": "Workflow input passed to the first tasks.",
+                        "required": true,
+                    }],
+                })
+            })
+            .collect::<Vec<_>>();
+        Ok(json!({ "prompts": prompts }))
+    }
+
+    pub(super) fn get(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let name = params.get("name").and_then(Value::as_str).ok_or_else(|| {
+            (
+                JSONRPC_INVALID_PARAMS,
+                "prompts/get requires a 'name'.".to_string(),
+            )
+        })?;
+        let input = params
+            .get("arguments")
+            .and_then(|arguments| arguments.get("input"))
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let template = load_workflow_templates(&self.templates_dir)
+            .map_err(|err| (JSONRPC_INTERNAL_ERROR, err))?
+            .into_iter()
+            .find(|template| template.id == name)
+            .ok_or_else(|| {
+                (
+                    JSONRPC_INVALID_PARAMS,
+                    format!("Unknown workflow template '{name}'."),
+                )
+            })?;
+
+        Ok(json!({
+            "description": template.description.clone().or(template.summary.clone()),
+            "messages": [{
+                "role": "user",
+                "content": { "type": "text", "text": render_workflow_prompt(&template, input) },
+            }],
+        }))
+    }
+}
+
+/// Load every `*.json` template in the directory, sorted by id.
+/// A missing directory simply means no templates are stored yet.
+pub(super) fn load_workflow_templates(dir: &Path) -> Result<Vec<StoredWorkflowTemplate>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(format!(
+                "Failed to read workflow templates from '{}': {err}",
+                dir.display()
+            ))
+        }
+    };
+
+    let mut templates = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let raw = match fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(err) => {
+                tracing::warn!(path = %path.display(), %err, "MCP_EXPORT: unreadable workflow template");
+                continue;
+            }
+        };
+        match serde_json::from_str::<StoredWorkflowTemplate>(&raw) {
+            Ok(template) => templates.push(template),
+            Err(err) => {
+                tracing::warn!(path = %path.display(), %err, "MCP_EXPORT: invalid workflow template");
+            }
+        }
+    }
+    templates.sort_by(|left, right| left.id.cmp(&right.id));
+    Ok(templates)
+}
+
+pub(super) fn render_workflow_prompt(template: &StoredWorkflowTemplate, input: &str) -> String {
+    let mut text = format!("Run the AgenticOS workflow template \"{}\".", template.name);
+    if let Some(description) = template.description.as_deref() {
+        text.push_str(&format!("\n{description}"));
+    }
+    if let Some(policy) = template.failure_policy.as_deref() {
+        text.push_str(&format!("\nFailure policy: {policy}."));
+    }
+    text.push_str(&format!("\n\nWorkflow input:\n{}", input.trim()));
+    text.push_str("\n\nTasks:");
+    for (index, task) in template.tasks.iter().enumerate() {
+        let role = task.role.as_deref().unwrap_or(task.id.as_str());
+        text.push_str(&format!("\n{}. [{}] {}", index + 1, task.id, role));
+        if !task.deps.is_empty() {
+            text.push_str(&format!(" (after: {})", task.deps.join(", ")));
+        }
+        for line in task.prompt.trim().lines() {
+            text.push_str(&format!("\n   {line}"));
+        }
+    }
+    text
+}
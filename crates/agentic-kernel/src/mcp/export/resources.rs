@@ -0,0 +1,213 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde_json::{json, Value};
+
+use crate::core_dump::{list_core_dumps, load_core_dump_info};
+use crate::storage::StorageService;
+
+const SESSION_URI_PREFIX: &str = "agenticos://sessions/";
+const ARTIFACT_URI_PREFIX: &str = "agenticos://artifacts/";
+const CORE_DUMP_URI_PREFIX: &str = "agenticos://coredumps/";
+
+const JSONRPC_INVALID_PARAMS: i64 = -32602;
+const JSONRPC_INTERNAL_ERROR: i64 = -32603;
+/// MCP "resource not found" error code.
+const MCP_RESOURCE_NOT_FOUND: i64 = -32002;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum KernelResourceKind {
+    Session,
+    Artifact,
+    CoreDump,
+}
+
+/// Persisted sessions, workflow artifacts and core dumps as MCP resources.
+///
+/// Reads go through a dedicated SQLite connection opened lazily, so the
+/// event loop's own `StorageService` is never shared across threads.
+pub(super) struct KernelResourceCatalog {
+    database_path: PathBuf,
+    list_limit: usize,
+    storage: Mutex<Option<StorageService>>,
+}
+
+impl KernelResourceCatalog {
+    pub(super) fn new(database_path: PathBuf, list_limit: usize) -> Self {
+        Self {
+            database_path,
+            list_limit: list_limit.max(1),
+            storage: Mutex::new(None),
+        }
+    }
+
+    pub(super) fn list_templates(&self) -> Value {
+        json!({
+            "resourceTemplates": [
+                {
+                    "uriTemplate": format!("{SESSION_URI_PREFIX}{{session_id}}"),
+                    "name": "session",
+                    "title": "Session transcript",
+                    "mimeType": "application/json",
+                },
+                {
+                    "uriTemplate": format!("{ARTIFACT_URI_PREFIX}{{artifact_id}}"),
+                    "name": "artifact",
+                    "title": "Workflow artifact",
+                },
+                {
+                    "uriTemplate": format!("{CORE_DUMP_URI_PREFIX}{{dump_id}}"),
+                    "name": "coredump",
+                    "title": "Core dump manifest",
+                    "mimeType": "application/json",
+                },
+            ]
+        })
+    }
+
+    pub(super) fn list(&self) -> Result<Value, (i64, String)> {
+        self.with_storage(|storage| {
+            let mut resources = Vec::new();
+
+            let mut sessions = storage.load_sessions().map_err(|err| err.to_string())?;
+            sessions.sort_by_key(|session| std::cmp::Reverse(session.updated_at_ms));
+            for session in sessions.into_iter().take(self.list_limit) {
+                resources.push(json!({
+                    "uri": format!("{SESSION_URI_PREFIX}{}", session.session_id),
+                    "name": session.session_id,
+                    "title": session.title,
+                    "description": format!("Session ({})", session.status),
+                    "mimeType": "application/json",
+                }));
+            }
+
+            for artifact in storage
+                .load_recent_workflow_artifacts(self.list_limit)
+                .map_err(|err| err.to_string())?
+            {
+                resources.push(json!({
+                    "uri": format!("{ARTIFACT_URI_PREFIX}{}", artifact.artifact_id),
+                    "name": artifact.artifact_id,
+                    "title": artifact.label,
+                    "description": format!(
+                        "Workflow {} task '{}' attempt {}",
+                        artifact.orchestration_id, artifact.producer_task_id, artifact.producer_attempt
+                    ),
+                    "mimeType": artifact.mime_type,
+                    "size": artifact.bytes,
+                }));
+            }
+
+            for dump in list_core_dumps(storage, Some(self.list_limit))?.dumps {
+                resources.push(json!({
+                    "uri": format!("{CORE_DUMP_URI_PREFIX}{}", dump.dump_id),
+                    "name": dump.dump_id,
+                    "title": format!("Core dump ({})", dump.reason),
+                    "mimeType": "application/json",
+                }));
+            }
+
+            Ok(json!({ "resources": resources }))
+        })
+    }
+
+    pub(super) fn read(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params.get("uri").and_then(Value::as_str).ok_or_else(|| {
+            (
+                JSONRPC_INVALID_PARAMS,
+                "resources/read requires a 'uri'.".to_string(),
+            )
+        })?;
+        let (kind, id) = parse_resource_uri(uri).ok_or_else(|| {
+            (
+                MCP_RESOURCE_NOT_FOUND,
+                format!("Unknown AgenticOS resource '{uri}'."),
+            )
+        })?;
+
+        let content = self.with_storage(|storage| match kind {
+            KernelResourceKind::Session => {
+                let Some(session) = storage
+                    .load_sessions()
+                    .map_err(|err| err.to_string())?
+                    .into_iter()
+                    .find(|session| session.session_id == id)
+                else {
+                    return Ok(None);
+                };
+                let messages = storage
+                    .load_replay_messages_for_session(id)
+                    .map_err(|err| err.to_string())?
+                    .into_iter()
+                    .map(|message| {
+                        json!({
+                            "role": message.role,
+                            "kind": message.kind,
+                            "content": message.content,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let body = json!({
+                    "session_id": session.session_id,
+                    "title": session.title,
+                    "status": session.status,
+                    "runtime_id": session.runtime_id,
+                    "active_pid": session.active_pid,
+                    "created_at_ms": session.created_at_ms,
+                    "updated_at_ms": session.updated_at_ms,
+                    "messages": messages,
+                });
+                Ok(Some(("application/json".to_string(), body.to_string())))
+            }
+            KernelResourceKind::Artifact => Ok(storage
+                .workflow_artifact(id)
+                .map_err(|err| err.to_string())?
+                .map(|artifact| (artifact.mime_type, artifact.content_text))),
+            KernelResourceKind::CoreDump => Ok(load_core_dump_info(storage, id)?
+                .map(|info| ("application/json".to_string(), info.manifest_json))),
+        })?;
+
+        let Some((mime_type, text)) = content else {
+            return Err((
+                MCP_RESOURCE_NOT_FOUND,
+                format!("AgenticOS resource '{uri}' was not found."),
+            ));
+        };
+        Ok(json!({
+            "contents": [{ "uri": uri, "mimeType": mime_type, "text": text }]
+        }))
+    }
+
+    fn with_storage<T>(
+        &self,
+        read: impl FnOnce(&StorageService) -> Result<T, String>,
+    ) -> Result<T, (i64, String)> {
+        let mut guard = self.storage.lock().map_err(|_| {
+            (
+                JSONRPC_INTERNAL_ERROR,
+                "MCP export storage lock poisoned.".to_string(),
+            )
+        })?;
+        let storage = match guard.as_mut() {
+            Some(storage) => storage,
+            None => guard.insert(
+                StorageService::open(&self.database_path)
+                    .map_err(|err| (JSONRPC_INTERNAL_ERROR, err.to_string()))?,
+            ),
+        };
+        read(storage).map_err(|err| (JSONRPC_INTERNAL_ERROR, err))
+    }
+}
+
+pub(super) fn parse_resource_uri(uri: &str) -> Option<(KernelResourceKind, &str)> {
+    let (kind, id) = if let Some(id) = uri.strip_prefix(SESSION_URI_PREFIX) {
+        (KernelResourceKind::Session, id)
+    } else if let Some(id) = uri.strip_prefix(ARTIFACT_URI_PREFIX) {
+        (KernelResourceKind::Artifact, id)
+    } else if let Some(id) = uri.strip_prefix(CORE_DUMP_URI_PREFIX) {
+        (KernelResourceKind::CoreDump, id)
+    } else {
+        return None;
+    };
+    (!id.is_empty()).then_some((kind, id))
+}
@@ -0,0 +1,277 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use serde_json::{json, Value};
+
+use super::super::prompts::{load_workflow_templates, render_workflow_prompt};
+use super::super::resources::{parse_resource_uri, KernelResourceKind};
+use super::{exported_tool_entries, McpExportState};
+use crate::config::McpExportConfig;
+use crate::tool_registry::{
+    CommandOutputFormat, ToolBackendConfig, ToolBackendKind, ToolDescriptor, ToolRegistry,
+    ToolRegistryEntry, ToolSource,
+};
+use crate::tools::invocation::{ProcessPermissionPolicy, ProcessTrustScope, ToolCaller};
+use crate::tools::SyscallRateMap;
+
+fn unique_temp_dir() -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("clock")
+        .as_nanos();
+    dir.push(format!("agenticos-mcp-export-test-{nanos}"));
+    dir
+}
+
+fn export_state(config: McpExportConfig, temp_dir: &std::path::Path) -> McpExportState {
+    McpExportState::new(
+        &config,
+        temp_dir.join("agenticos.db"),
+        &ToolRegistry::with_builtins(),
+        Arc::new(Mutex::new(SyscallRateMap::new())),
+        false,
+    )
+    .expect("export state")
+}
+
+fn request(id: u64, method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params })
+}
+
+#[test]
+fn initialize_advertises_tools_resources_and_prompts() {
+    let temp_dir = unique_temp_dir();
+    let state = export_state(McpExportConfig::default(), &temp_dir);
+
+    let response = state
+        .handle_message(&request(1, "initialize", json!({})))
+        .expect("initialize response");
+    assert_eq!(response["id"], 1);
+    assert_eq!(response["result"]["serverInfo"]["name"], "agenticos");
+    assert!(response["result"]["capabilities"]["resources"].is_object());
+    assert!(response["result"]["capabilities"]["prompts"].is_object());
+
+    let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+    assert!(state.handle_message(&notification).is_none());
+
+    let unknown = state
+        .handle_message(&request(2, "sampling/createMessage", json!({})))
+        .expect("error response");
+    assert_eq!(unknown["error"]["code"], -32601);
+}
+
+#[test]
+fn dangerous_and_human_tools_are_not_exported_by_default() {
+    let registry = ToolRegistry::with_builtins();
+    let exported = exported_tool_entries(&registry, &[], false)
+        .into_iter()
+        .map(|entry| entry.descriptor.name.clone())
+        .collect::<Vec<_>>();
+
+    assert!(exported.contains(&"get_time".to_string()));
+    assert!(!exported.contains(&"python".to_string()));
+    assert!(!exported.contains(&"exec_command".to_string()));
+    assert!(!exported.contains(&"ask_human".to_string()));
+    assert!(!exported.contains(&"memory_recall".to_string()));
+
+    let memory = exported_tool_entries(&registry, &["memory_recall".to_string()], true);
+    assert!(memory.is_empty());
+
+    let restricted = exported_tool_entries(&registry, &["get_time".to_string()], true);
+    assert_eq!(restricted.len(), 1);
+    assert_eq!(restricted[0].descriptor.name, "get_time");
+
+    let policy = ProcessPermissionPolicy::mcp_export(&registry, vec!["get_time".to_string()])
+        .expect("policy");
+    assert_eq!(policy.trust_scope, ProcessTrustScope::McpExport);
+    assert!(!policy.actions_allowed);
+    assert_eq!(policy.allowed_tools, vec!["get_time".to_string()]);
+}
+
+#[test]
+fn command_backed_tools_are_exported_only_with_dangerous_opt_in() {
+    let mut registry = ToolRegistry::with_builtins();
+    registry
+        .register(ToolRegistryEntry {
+            descriptor: ToolDescriptor {
+                name: "list_words".to_string(),
+                aliases: vec![],
+                description: "command template tool".to_string(),
+                input_schema: json!({
+                    "type": "object",
+                    "properties": {"word": {"type": "string"}}
+                }),
+                input_example: None,
+                output_schema: json!({}),
+                allowed_callers: vec![ToolCaller::Programmatic],
+                backend_kind: ToolBackendKind::Command,
+                capabilities: vec![],
+                dangerous: false,
+                enabled: true,
+                default_allowlisted: true,
+                approval_required: false,
+                interop: None,
+                source: ToolSource::Manifest,
+            },
+            backend: ToolBackendConfig::Command {
+                program: "echo".to_string(),
+                args: vec!["{word}".to_string()],
+                cwd: None,
+                timeout_ms: None,
+                path_fields: vec![],
+                write_path_fields: vec![],
+                success_exit_codes: vec![0],
+                output: CommandOutputFormat::Text,
+            },
+        })
+        .expect("register command tool");
+    let exported_names = |exposed: &[String], allow_dangerous: bool| {
+        exported_tool_entries(&registry, exposed, allow_dangerous)
+            .into_iter()
+            .map(|entry| entry.descriptor.name.clone())
+            .collect::<Vec<_>>()
+    };
+
+    assert!(!exported_names(&[], false).contains(&"list_words".to_string()));
+    let exposed = vec!["list_words".to_string()];
+    assert!(exported_names(&exposed, false).is_empty());
+    assert_eq!(exported_names(&exposed, true), exposed);
+}
+
+#[test]
+fn tool_calls_run_through_governance_and_reject_unexported_tools() {
+    let temp_dir = unique_temp_dir();
+    let state = export_state(
+        McpExportConfig {
+            exposed_tools: vec!["get_time".to_string()],
+            ..McpExportConfig::default()
+        },
+        &temp_dir,
+    );
+
+    let listed = state
+        .handle_message(&request(1, "tools/list", json!({})))
+        .expect("tools/list response");
+    let tools = listed["result"]["tools"].as_array().expect("tools");
+    assert_eq!(tools.len(), 1);
+    assert_eq!(tools[0]["name"], "get_time");
+    assert!(tools[0]["inputSchema"].is_object());
+
+    let called = state
+        .handle_message(&request(
+            2,
+            "tools/call",
+            json!({ "name": "get_time", "arguments": {} }),
+        ))
+        .expect("tools/call response");
+    assert_eq!(called["result"]["isError"], false);
+    assert!(called["result"]["structuredContent"]["unix_timestamp_ms"].is_number());
+
+    let denied = state
+        .handle_message(&request(
+            3,
+            "tools/call",
+            json!({ "name": "python", "arguments": { "code": "print(1)" } }),
+        ))
+        .expect("denied response");
+    assert_eq!(denied["error"]["code"], -32602);
+}
+
+#[test]
+fn workflow_templates_are_published_as_prompts() {
+    let temp_dir = unique_temp_dir();
+    let templates_dir = temp_dir.join("workflow_templates");
+    fs::create_dir_all(&templates_dir).expect("templates dir");
+    fs::write(
+        templates_dir.join("research.json"),
+        json!({
+            "id": "research-pipeline",
+            "name": "Research Pipeline",
+            "summary": "Scout, synthesize, brief.",
+            "failurePolicy": "best_effort",
+            "tasks": [
+                { "id": "scout", "role": "Scout", "prompt": "Collect evidence." },
+                { "id": "brief", "prompt": "Write the brief.", "deps": ["scout"] }
+            ]
+        })
+        .to_string(),
+    )
+    .expect("write template");
+    fs::write(templates_dir.join("notes.txt"), "ignored").expect("write note");
+
+    let templates = load_workflow_templates(&templates_dir).expect("templates");
+    assert_eq!(templates.len(), 1);
+    let rendered = render_workflow_prompt(&templates[0], "  Rust async runtimes ");
+    assert!(rendered.contains("Failure policy: best_effort."));
+    assert!(rendered.contains("Workflow input:\nRust async runtimes"));
+    assert!(rendered.contains("2. [brief] brief (after: scout)\n   Write the brief."));
+
+    let state = export_state(
+        McpExportConfig {
+            workflow_templates_dir: templates_dir,
+            ..McpExportConfig::default()
+        },
+        &temp_dir,
+    );
+    let listed = state
+        .handle_message(&request(1, "prompts/list", json!({})))
+        .expect("prompts/list response");
+    assert_eq!(listed["result"]["prompts"][0]["name"], "research-pipeline");
+    assert_eq!(
+        listed["result"]["prompts"][0]["description"],
+        "Scout, synthesize, brief."
+    );
+
+    let prompt = state
+        .handle_message(&request(
+            2,
+            "prompts/get",
+            json!({ "name": "research-pipeline", "arguments": { "input": "MCP" } }),
+        ))
+        .expect("prompts/get response");
+    assert_eq!(prompt["result"]["messages"][0]["role"], "user");
+
+    let missing = state
+        .handle_message(&request(3, "prompts/get", json!({ "name": "nope" })))
+        .expect("missing prompt response");
+    assert_eq!(missing["error"]["code"], -32602);
+
+    let _ = fs::remove_dir_all(temp_dir);
+}
+
+#[test]
+fn kernel_resources_are_addressed_by_uri() {
+    assert_eq!(
+        parse_resource_uri("agenticos://sessions/sess-1"),
+        Some((KernelResourceKind::Session, "sess-1"))
+    );
+    assert_eq!(
+        parse_resource_uri("agenticos://artifacts/orch:1:task:a:attempt:1:result"),
+        Some((
+            KernelResourceKind::Artifact,
+            "orch:1:task:a:attempt:1:result"
+        ))
+    );
+    assert_eq!(parse_resource_uri("agenticos://coredumps/"), None);
+    assert_eq!(parse_resource_uri("file:///etc/passwd"), None);
+
+    let temp_dir = unique_temp_dir();
+    let state = export_state(McpExportConfig::default(), &temp_dir);
+    let listed = state
+        .handle_message(&request(1, "resources/list", json!({})))
+        .expect("resources/list response");
+    assert_eq!(listed["result"]["resources"], json!([]));
+
+    let missing = state
+        .handle_message(&request(
+            2,
+            "resources/read",
+            json!({ "uri": "agenticos://sessions/unknown" }),
+        ))
+        .expect("resources/read response");
+    assert_eq!(missing["error"]["code"], -32002);
+
+    let _ = fs::remove_dir_all(temp_dir);
+}
@@ -1,5 +1,3 @@
-use std::collections::HashMap;
-use std::io::{Read, Write};
 use std::net::{SocketAddr, TcpListener, TcpStream};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread;
@@ -7,12 +5,14 @@ use std::time::Duration;
 
 use crate::mcp::bridge::McpBridgeState;
 use crate::mcp::models::{McpBridgeErrorResponse, McpBridgeInvocationRequest};
+use crate::transport::http::{read_http_request, write_json_response, HttpReadLimits};
 
 pub(super) fn spawn(
     host: &str,
     port: u16,
     token_header: &str,
     token: &str,
+    max_request_bytes: usize,
     state: Arc<Mutex<McpBridgeState>>,
 ) -> Result<(SocketAddr, mpsc::Sender<()>, thread::JoinHandle<()>), String> {
     let listener = TcpListener::bind((host, port))
@@ -26,6 +26,7 @@ pub(super) fn spawn(
     let (shutdown_tx, shutdown_rx) = mpsc::channel();
     let token_header = token_header.to_ascii_lowercase();
     let token = token.to_string();
+    let limits = HttpReadLimits::with_max_body_bytes(max_request_bytes);
 
     let handle = thread::Builder::new()
         .name("mcp-http-bridge".to_string())
@@ -36,7 +37,8 @@ pub(super) fn spawn(
 
             match listener.accept() {
                 Ok((mut stream, _)) => {
-                    if let Err(err) = handle_connection(&mut stream, &token_header, &token, &state)
+                    if let Err(err) =
+                        handle_connection(&mut stream, &token_header, &token, limits, &state)
                     {
                         tracing::debug!(%err, "MCP bridge request failed");
                     }
@@ -59,9 +61,25 @@ fn handle_connection(
     stream: &mut TcpStream,
     token_header: &str,
     token: &str,
+    limits: HttpReadLimits,
     state: &Arc<Mutex<McpBridgeState>>,
 ) -> Result<(), String> {
-    let request = read_http_request(stream)?;
+    let request = match read_http_request(stream, limits) {
+        Ok(request) => request,
+        Err(err) => {
+            return write_json_response(
+                stream,
+                err.status_code,
+                &McpBridgeErrorResponse {
+                    error: crate::mcp::models::McpBridgeErrorBody {
+                        kind: "bad_request".to_string(),
+                        message: err.message,
+                        mcp: None,
+                    },
+                },
+            );
+        }
+    };
     if request.method != "POST" {
         write_json_response(
             stream,
@@ -135,123 +153,3 @@ fn handle_connection(
 
     Ok(())
 }
-
-struct HttpRequest {
-    method: String,
-    path: String,
-    headers: HashMap<String, String>,
-    body: Vec<u8>,
-}
-
-fn read_http_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
-    let mut buffer = Vec::new();
-    let mut header_end = None;
-    let mut chunk = [0u8; 4096];
-
-    loop {
-        let read = stream
-            .read(&mut chunk)
-            .map_err(|err| format!("Failed to read MCP bridge HTTP request: {err}"))?;
-        if read == 0 {
-            break;
-        }
-        buffer.extend_from_slice(&chunk[..read]);
-        if header_end.is_none() {
-            header_end = find_header_end(&buffer);
-        }
-        if let Some(end) = header_end {
-            let headers_text = std::str::from_utf8(&buffer[..end])
-                .map_err(|err| format!("MCP bridge request headers are not UTF-8: {err}"))?;
-            let content_length = parse_content_length(headers_text)?;
-            if buffer.len() >= end + content_length {
-                let body = buffer[end..end + content_length].to_vec();
-                return parse_http_request(headers_text, body);
-            }
-        }
-    }
-
-    Err("Incomplete MCP bridge HTTP request.".to_string())
-}
-
-fn parse_http_request(headers: &str, body: Vec<u8>) -> Result<HttpRequest, String> {
-    let mut lines = headers.lines();
-    let request_line = lines
-        .next()
-        .ok_or_else(|| "Missing HTTP request line.".to_string())?;
-    let mut request_parts = request_line.split_whitespace();
-    let method = request_parts
-        .next()
-        .ok_or_else(|| "Missing HTTP method.".to_string())?
-        .to_string();
-    let path = request_parts
-        .next()
-        .ok_or_else(|| "Missing HTTP path.".to_string())?
-        .to_string();
-
-    let mut parsed_headers = HashMap::new();
-    for line in lines {
-        if let Some((name, value)) = line.split_once(':') {
-            parsed_headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
-        }
-    }
-
-    Ok(HttpRequest {
-        method,
-        path,
-        headers: parsed_headers,
-        body,
-    })
-}
-
-fn write_json_response<T: serde::Serialize>(
-    stream: &mut TcpStream,
-    status_code: u16,
-    body: &T,
-) -> Result<(), String> {
-    let payload = serde_json::to_string(body)
-        .map_err(|err| format!("Failed to serialize MCP bridge response: {err}"))?;
-    let status_text = match status_code {
-        200 => "OK",
-        401 => "Unauthorized",
-        403 => "Forbidden",
-        404 => "Not Found",
-        405 => "Method Not Allowed",
-        422 => "Unprocessable Entity",
-        500 => "Internal Server Error",
-        502 => "Bad Gateway",
-        504 => "Gateway Timeout",
-        _ => "Error",
-    };
-    let response = format!(
-        "HTTP/1.1 {status_code} {status_text}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
-        payload.len(),
-        payload
-    );
-    stream
-        .write_all(response.as_bytes())
-        .and_then(|_| stream.flush())
-        .map_err(|err| format!("Failed to write MCP bridge HTTP response: {err}"))
-}
-
-fn find_header_end(bytes: &[u8]) -> Option<usize> {
-    bytes
-        .windows(4)
-        .position(|window| window == b"\r\n\r\n")
-        .map(|index| index + 4)
-}
-
-fn parse_content_length(headers: &str) -> Result<usize, String> {
-    for line in headers.lines().skip(1) {
-        let Some((name, value)) = line.split_once(':') else {
-            continue;
-        };
-        if name.eq_ignore_ascii_case("Content-Length") {
-            return value
-                .trim()
-                .parse::<usize>()
-                .map_err(|err| format!("Invalid Content-Length header: {err}"));
-        }
-    }
-
-    Ok(0)
-}
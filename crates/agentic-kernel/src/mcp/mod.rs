@@ -1,5 +1,6 @@
 pub(crate) mod bridge;
 pub(crate) mod client;
+pub(crate) mod export;
 pub(crate) mod http_bridge;
 pub(crate) mod jsonrpc;
 pub(crate) mod models;
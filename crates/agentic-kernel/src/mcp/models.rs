@@ -11,6 +11,8 @@ pub(crate) struct McpImplementation {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub(crate) struct McpServerCapabilities {
+    pub(crate) resources: bool,
+    pub(crate) prompts: bool,
     pub(crate) tools_list_changed: bool,
     pub(crate) resources_list_changed: bool,
     pub(crate) resources_subscribe: bool,
@@ -49,6 +51,14 @@ pub(crate) struct McpPromptDefinition {
     pub(crate) name: String,
     pub(crate) title: Option<String>,
     pub(crate) description: Option<String>,
+    pub(crate) arguments: Vec<McpPromptArgument>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub(crate) struct McpPromptArgument {
+    pub(crate) name: String,
+    pub(crate) description: Option<String>,
+    pub(crate) required: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -60,6 +70,27 @@ pub(crate) struct McpResourceDefinition {
     pub(crate) mime_type: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub(crate) struct McpResourceContent {
+    pub(crate) uri: String,
+    pub(crate) mime_type: Option<String>,
+    pub(crate) text: Option<String>,
+    /// Base64 payload of binary resources; never inlined into model context.
+    pub(crate) blob: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct McpPromptMessage {
+    pub(crate) role: String,
+    pub(crate) content: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct McpGetPromptResult {
+    pub(crate) description: Option<String>,
+    pub(crate) messages: Vec<McpPromptMessage>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub(crate) struct McpRoot {
     pub(crate) uri: String,
@@ -17,6 +17,44 @@ pub(crate) fn roots_for_context(context: &ToolContext) -> Result<Vec<McpRoot>, S
     Ok(values)
 }
 
+/// `true` when a `file://` URI names a path inside one of the roots.
+/// Paths are compared component-wise after percent-decoding, and `..`
+/// segments are rejected instead of being resolved.
+pub(crate) fn file_uri_within_roots(uri: &str, roots: &[McpRoot]) -> bool {
+    let Some(path) = decoded_file_uri_path(uri) else {
+        return false;
+    };
+    if path
+        .components()
+        .any(|component| matches!(component, std::path::Component::ParentDir))
+    {
+        return false;
+    }
+    roots.iter().any(|root| {
+        decoded_file_uri_path(&root.uri).is_some_and(|root_path| path.starts_with(root_path))
+    })
+}
+
+fn decoded_file_uri_path(uri: &str) -> Option<std::path::PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(index + 1..index + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded)
+        .ok()
+        .map(std::path::PathBuf::from)
+}
+
 fn file_uri_for_path(path: &std::path::Path) -> String {
     let display = path.to_string_lossy().replace('\\', "/");
     let encoded = display
@@ -36,7 +74,8 @@ fn percent_encode_byte(byte: u8) -> Vec<char> {
 
 #[cfg(test)]
 mod tests {
-    use super::roots_for_context;
+    use super::{file_uri_within_roots, roots_for_context};
+    use crate::mcp::models::McpRoot;
     use crate::tools::invocation::{
         default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller, ToolContext,
         ToolInvocationTransport,
@@ -63,4 +102,30 @@ mod tests {
         assert_eq!(roots.len(), 1);
         assert!(roots[0].uri.starts_with("file:///"));
     }
+
+    #[test]
+    fn file_uris_must_stay_inside_roots() {
+        let roots = vec![McpRoot {
+            uri: "file:///work/My%20Project".to_string(),
+            name: None,
+        }];
+
+        assert!(file_uri_within_roots(
+            "file:///work/My Project/notes.md",
+            &roots
+        ));
+        assert!(file_uri_within_roots(
+            "file:///work/My%20Project/src/lib.rs",
+            &roots
+        ));
+        assert!(!file_uri_within_roots(
+            "file:///work/My Project Other/x",
+            &roots
+        ));
+        assert!(!file_uri_within_roots(
+            "file:///work/My%20Project/../secret",
+            &roots
+        ));
+        assert!(!file_uri_within_roots("https://example.com/a", &roots));
+    }
 }
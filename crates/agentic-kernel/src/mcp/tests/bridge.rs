@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 
 use serde_json::json;
 
-use super::McpBridgeRuntime;
+use super::{McpBridgeRuntime, McpResourceUpdate, McpResourceUpdateDelivery};
 use crate::config::{KernelConfig, McpServerConfig, McpTransportConfig, McpTrustLevel};
 use crate::tool_registry::ToolRegistry;
 use crate::tools::dispatcher::ToolDispatcher;
@@ -176,6 +176,144 @@ fn roots_follow_process_path_grants() {
     assert_eq!(roots[0].as_str(), Some(expected_root.as_str()));
 }
 
+#[test]
+fn exposes_mcp_resources_and_prompts_as_governed_tools() {
+    let temp_dir = unique_temp_dir();
+    let allowed_dir = temp_dir.join("allowed");
+    fs::create_dir_all(&allowed_dir).expect("create allowed dir");
+    let script_path = write_server_script(
+        &temp_dir,
+        "resources-mcp.sh",
+        &resources_stdio_server_script(),
+    );
+    let mut server = stdio_server_config(
+        "demo",
+        &script_path,
+        &temp_dir,
+        McpTrustLevel::Untrusted,
+        5_000,
+    );
+    server.exposed_tools = Vec::new();
+
+    let mut registry = ToolRegistry::with_builtins();
+    let mut bridge =
+        McpBridgeRuntime::start(&kernel_config_for_servers(vec![server]), &mut registry)
+            .expect("start mcp bridge")
+            .expect("bridge enabled");
+    assert_eq!(
+        bridge.registered_tool_names(),
+        &[
+            "demo.mcp_get_prompt".to_string(),
+            "demo.mcp_read_resource".to_string(),
+        ]
+    );
+    let reader = registry
+        .get("demo.mcp_read_resource")
+        .expect("resource reader registered");
+    assert!(reader.descriptor.description.contains("memo://notes"));
+    assert!(
+        reader.descriptor.dangerous,
+        "untrusted server tools stay dangerous"
+    );
+
+    let grants = vec![ProcessPathGrant {
+        root: allowed_dir.to_string_lossy().to_string(),
+        access_mode: PathGrantAccessMode::ReadOnly,
+        capsule: None,
+        label: None,
+    }];
+    let allowed = vec![
+        "demo.mcp_read_resource".to_string(),
+        "demo.mcp_get_prompt".to_string(),
+    ];
+
+    let read = dispatch_tool(
+        &registry,
+        "demo.mcp_read_resource",
+        json!({"uri": "memo://notes", "subscribe": true}),
+        tool_context(allowed.clone(), grants.clone()),
+    )
+    .expect("read resource");
+    assert_eq!(
+        read.output
+            .get("output")
+            .and_then(serde_json::Value::as_str),
+        Some("Resource memo://notes (text/plain):\nremember the milk")
+    );
+
+    let outside = dispatch_tool(
+        &registry,
+        "demo.mcp_read_resource",
+        json!({"uri": "file:///definitely/outside/secret.txt"}),
+        tool_context(allowed.clone(), grants.clone()),
+    );
+    assert!(
+        outside.is_err(),
+        "file resources outside path grants are denied"
+    );
+    let unlisted = dispatch_tool(
+        &registry,
+        "demo.mcp_read_resource",
+        json!({"uri": "memo://unlisted"}),
+        tool_context(allowed.clone(), grants.clone()),
+    );
+    assert!(
+        unlisted.is_err(),
+        "untrusted servers only serve listed resources"
+    );
+
+    let prompt = dispatch_tool(
+        &registry,
+        "demo.mcp_get_prompt",
+        json!({"name": "review", "arguments": {"topic": "bridge"}}),
+        tool_context(allowed.clone(), grants.clone()),
+    )
+    .expect("get prompt");
+    assert_eq!(
+        prompt
+            .output
+            .get("output")
+            .and_then(serde_json::Value::as_str),
+        Some("Review a change\n\n[user]\nReview the topic carefully.")
+    );
+    let missing_argument = dispatch_tool(
+        &registry,
+        "demo.mcp_get_prompt",
+        json!({"name": "review"}),
+        tool_context(allowed, grants),
+    );
+    assert!(
+        missing_argument.is_err(),
+        "required prompt arguments are enforced"
+    );
+
+    assert!(bridge.next_resource_poll_at().is_some());
+    let mut delivered = Vec::new();
+    for _ in 0..50 {
+        bridge.dispatch_resource_updates(|update: &McpResourceUpdate| {
+            delivered.push(update.clone());
+            McpResourceUpdateDelivery::Delivered
+        });
+        if !delivered.is_empty() {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(20));
+    }
+    assert_eq!(delivered.len(), 1);
+    assert_eq!(delivered[0].pid, 7);
+    assert_eq!(delivered[0].uri, "memo://notes");
+    assert!(delivered[0].notice().contains("demo.mcp_read_resource"));
+
+    let mut stale = 0;
+    for _ in 0..5 {
+        bridge.dispatch_resource_updates(|_| {
+            stale += 1;
+            McpResourceUpdateDelivery::Dropped
+        });
+    }
+    assert_eq!(stale, 0);
+}
+
 #[test]
 fn replay_safe_policy_filters_dangerous_mcp_tools() {
     let temp_dir = unique_temp_dir();
@@ -407,6 +545,17 @@ fn disabled_mcp_rollout_registers_nothing() {
     assert!(registry.get("demo.echo").is_none());
 }
 
+fn dispatch_tool(
+    registry: &ToolRegistry,
+    tool_name: &str,
+    input: serde_json::Value,
+    context: ToolContext,
+) -> Result<crate::tools::api::ToolResult, ToolError> {
+    let invocation =
+        ToolInvocation::new(tool_name, input, Some("call-1".to_string())).expect("invocation");
+    ToolDispatcher::new().dispatch(&invocation, &context, registry)
+}
+
 fn dispatch_echo(
     registry: &ToolRegistry,
     context: ToolContext,
@@ -521,6 +670,45 @@ done
     template.replace("__TOUCH__\n", &touch_line)
 }
 
+fn resources_stdio_server_script() -> String {
+    r#"#!/bin/sh
+reply() {
+  printf '{"jsonrpc":"2.0","id":%s,"result":%s}\n' "$1" "$2"
+}
+while IFS= read -r line; do
+  id=$(printf '%s' "$line" | sed -n 's/.*"id":\([0-9][0-9]*\).*/\1/p')
+  case "$line" in
+    *'"method":"initialize"'*)
+      reply "$id" '{"protocolVersion":"2025-11-25","capabilities":{"tools":{},"prompts":{},"resources":{"subscribe":true}},"serverInfo":{"name":"fake-mcp","version":"1.0.0"}}'
+      ;;
+    *'"method":"tools/list"'*)
+      reply "$id" '{"tools":[]}'
+      ;;
+    *'"method":"prompts/list"'*)
+      reply "$id" '{"prompts":[{"name":"review","description":"Review a change","arguments":[{"name":"topic","required":true}]}]}'
+      ;;
+    *'"method":"resources/list"'*)
+      reply "$id" '{"resources":[{"name":"notes","uri":"memo://notes","mimeType":"text/plain"}]}'
+      ;;
+    *'"method":"resources/read"'*)
+      reply "$id" '{"contents":[{"uri":"memo://notes","mimeType":"text/plain","text":"remember the milk"}]}'
+      ;;
+    *'"method":"resources/subscribe"'*)
+      reply "$id" '{}'
+      printf '%s\n' '{"jsonrpc":"2.0","method":"notifications/resources/updated","params":{"uri":"memo://notes"}}'
+      ;;
+    *'"method":"resources/unsubscribe"'*)
+      reply "$id" '{}'
+      ;;
+    *'"method":"prompts/get"'*)
+      reply "$id" '{"description":"Review a change","messages":[{"role":"user","content":{"type":"text","text":"Review the topic carefully."}}]}'
+      ;;
+  esac
+done
+"#
+    .to_string()
+}
+
 fn slow_stdio_server_script(delay_s: u64) -> String {
     format!(
         r#"#!/bin/sh
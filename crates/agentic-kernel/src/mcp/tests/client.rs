@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use super::{McpClient, SseEvent, SseEventParser};
+use crate::config::{KernelConfig, McpServerConfig, McpTransportConfig, McpTrustLevel};
+use crate::mcp::bridge::McpBridgeRuntime;
+use crate::mcp::models::McpRoot;
+use crate::tool_registry::ToolRegistry;
+
+const STUB_SESSION_ID: &str = "stub-session-1";
+
+#[derive(Debug, Clone)]
+struct RecordedRequest {
+    method: String,
+    headers: HashMap<String, String>,
+    body: Value,
+}
+
+/// In-process streamable HTTP MCP server. `tools/call` answers over SSE,
+/// asks the client for `roots/list`, then drops the stream before the result
+/// so the client has to resume it with `Last-Event-ID`.
+struct StubMcpServer {
+    addr: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl StubMcpServer {
+    fn start(expected_token: &str) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub MCP server");
+        let addr = listener.local_addr().expect("stub address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let thread_requests = Arc::clone(&requests);
+        let expected_token = expected_token.to_string();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    break;
+                };
+                let requests = Arc::clone(&thread_requests);
+                let expected_token = expected_token.clone();
+                thread::spawn(move || handle_stub_connection(stream, &requests, &expected_token));
+            }
+        });
+        Self { addr, requests }
+    }
+
+    fn url(&self) -> String {
+        format!("http://{}/mcp", self.addr)
+    }
+
+    fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().expect("stub requests").clone()
+    }
+}
+
+fn handle_stub_connection(
+    mut stream: TcpStream,
+    requests: &Mutex<Vec<RecordedRequest>>,
+    expected_token: &str,
+) {
+    let Some(request) = read_stub_request(&mut stream) else {
+        return;
+    };
+    requests
+        .lock()
+        .expect("stub requests")
+        .push(request.clone());
+
+    if request.headers.get("authorization").map(String::as_str)
+        != Some(format!("Bearer {expected_token}").as_str())
+    {
+        write_stub_response(&mut stream, "401 Unauthorized", &[], "");
+        return;
+    }
+    let rpc_method = request.body.get("method").and_then(Value::as_str);
+    if rpc_method != Some("initialize")
+        && request.headers.get("mcp-session-id").map(String::as_str) != Some(STUB_SESSION_ID)
+    {
+        write_stub_response(&mut stream, "404 Not Found", &[], "");
+        return;
+    }
+
+    match (request.method.as_str(), rpc_method) {
+        ("DELETE", _) => write_stub_response(&mut stream, "200 OK", &[], ""),
+        ("GET", _) => {
+            let recorded = requests.lock().expect("stub requests").clone();
+            let call_id = recorded
+                .iter()
+                .find(|recorded| recorded.body["method"] == "tools/call")
+                .map(|recorded| recorded.body["id"].clone())
+                .unwrap_or(Value::Null);
+            let roots_seen = recorded.iter().any(|recorded| {
+                recorded.body["id"] == 900 && recorded.body["result"]["roots"][0]["uri"].is_string()
+            });
+            let text = if roots_seen {
+                "hello over http"
+            } else {
+                "roots missing"
+            };
+            let result = json!({
+                "jsonrpc": "2.0",
+                "id": call_id,
+                "result": {
+                    "content": [{ "type": "text", "text": text }],
+                    "structuredContent": { "echo": text },
+                    "isError": false
+                }
+            });
+            write_sse(&mut stream, &[("call-2", Some(result))]);
+        }
+        ("POST", Some("initialize")) => write_stub_response(
+            &mut stream,
+            "200 OK",
+            &[
+                ("Content-Type", "application/json"),
+                ("Mcp-Session-Id", STUB_SESSION_ID),
+            ],
+            &json!({
+                "jsonrpc": "2.0",
+                "id": request.body["id"],
+                "result": {
+                    "protocolVersion": "2025-11-25",
+                    "capabilities": { "tools": { "listChanged": true } },
+                    "serverInfo": { "name": "stub-http-mcp", "version": "1.0.0" }
+                }
+            })
+            .to_string(),
+        ),
+        ("POST", Some("tools/list")) => write_sse(
+            &mut stream,
+            &[(
+                "list-1",
+                Some(json!({
+                    "jsonrpc": "2.0",
+                    "id": request.body["id"],
+                    "result": {
+                        "tools": [{
+                            "name": "echo",
+                            "description": "Echo over streamable HTTP",
+                            "inputSchema": {
+                                "type": "object",
+                                "properties": { "message": { "type": "string" } }
+                            }
+                        }]
+                    }
+                })),
+            )],
+        ),
+        ("POST", Some("resources/list")) => {
+            write_json_result(&mut stream, &request, json!({ "resources": [] }))
+        }
+        ("POST", Some("prompts/list")) => {
+            write_json_result(&mut stream, &request, json!({ "prompts": [] }))
+        }
+        ("POST", Some("tools/call")) => {
+            write_sse_head(&mut stream);
+            write_sse_event(
+                &mut stream,
+                "call-1",
+                Some(&json!({ "jsonrpc": "2.0", "id": 900, "method": "roots/list" })),
+            );
+            // Wait for the roots answer, then drop the stream without a result.
+            let deadline = Instant::now() + Duration::from_secs(5);
+            while Instant::now() < deadline
+                && !requests
+                    .lock()
+                    .expect("stub requests")
+                    .iter()
+                    .any(|recorded| recorded.body["id"] == 900)
+            {
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+        _ => write_stub_response(&mut stream, "202 Accepted", &[], ""),
+    }
+}
+
+fn read_stub_request(stream: &mut TcpStream) -> Option<RecordedRequest> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        let read = stream.read(&mut chunk).ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        let Some(header_end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") else {
+            continue;
+        };
+        let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+        let mut lines = head.lines();
+        let method = lines.next()?.split_whitespace().next()?.to_string();
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect::<HashMap<_, _>>();
+        let content_length = headers
+            .get("content-length")
+            .and_then(|value| value.parse::<usize>().ok())
+            .unwrap_or(0);
+        let body_start = header_end + 4;
+        while buffer.len() < body_start + content_length {
+            let read = stream.read(&mut chunk).ok()?;
+            if read == 0 {
+                return None;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        }
+        let body = serde_json::from_slice(&buffer[body_start..body_start + content_length])
+            .unwrap_or(Value::Null);
+        return Some(RecordedRequest {
+            method,
+            headers,
+            body,
+        });
+    }
+}
+
+fn write_stub_response(stream: &mut TcpStream, status: &str, headers: &[(&str, &str)], body: &str) {
+    let mut response = format!(
+        "HTTP/1.1 {status}\r\nConnection: close\r\nContent-Length: {}\r\n",
+        body.len()
+    );
+    for (name, value) in headers {
+        response.push_str(&format!("{name}: {value}\r\n"));
+    }
+    response.push_str("\r\n");
+    response.push_str(body);
+    let _ = stream.write_all(response.as_bytes());
+}
+
+fn write_json_result(stream: &mut TcpStream, request: &RecordedRequest, result: Value) {
+    let body = json!({ "jsonrpc": "2.0", "id": request.body["id"], "result": result });
+    write_stub_response(
+        stream,
+        "200 OK",
+        &[("Content-Type", "application/json")],
+        &body.to_string(),
+    );
+}
+
+fn write_sse(stream: &mut TcpStream, events: &[(&str, Option<Value>)]) {
+    write_sse_head(stream);
+    for (id, message) in events {
+        write_sse_event(stream, id, message.as_ref());
+    }
+}
+
+fn write_sse_head(stream: &mut TcpStream) {
+    let _ = stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    );
+}
+
+fn write_sse_event(stream: &mut TcpStream, id: &str, message: Option<&Value>) {
+    let data = message.map(Value::to_string).unwrap_or_default();
+    let _ = stream.write_all(format!(": keep-alive\nid: {id}\ndata: {data}\n\n").as_bytes());
+    let _ = stream.flush();
+}
+
+fn http_server_config(id: &str, url: String, token_env: &str) -> McpServerConfig {
+    McpServerConfig {
+        id: id.to_string(),
+        label: Some(format!("{id} MCP")),
+        enabled: true,
+        tool_prefix: Some(id.to_string()),
+        exposed_tools: vec!["echo".to_string()],
+        default_allowlisted: false,
+        approval_required: false,
+        roots_enabled: true,
+        trust_level: McpTrustLevel::Trusted,
+        transport: McpTransportConfig::StreamableHttp {
+            url,
+            headers: [("X-Client".to_string(), "agenticos-test".to_string())]
+                .into_iter()
+                .collect(),
+            bearer_token_env: Some(token_env.to_string()),
+            headers_env: Default::default(),
+            timeout_ms: 5_000,
+        },
+    }
+}
+
+#[test]
+fn sse_parser_reads_ids_multiline_data_and_skips_comments() {
+    let mut parser = SseEventParser::default();
+    let mut events = Vec::new();
+    for line in [
+        ": ping\n",
+        "id: 7\r\n",
+        "data: {\"a\":\n",
+        "data:1}\n",
+        "event: message\n",
+        "\n",
+        "id: 8\n",
+        "\n",
+        "\n",
+    ] {
+        events.extend(parser.push_line(line));
+    }
+
+    assert_eq!(
+        events,
+        vec![
+            SseEvent {
+                id: Some("7".to_string()),
+                data: "{\"a\":\n1}".to_string(),
+            },
+            SseEvent {
+                id: Some("8".to_string()),
+                data: String::new(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn streamable_http_client_answers_roots_and_resumes_dropped_sse_stream() {
+    let token_env = "AGENTIC_TEST_MCP_HTTP_TOKEN_CLIENT";
+    std::env::set_var(token_env, "secret-token");
+    let server = StubMcpServer::start("secret-token");
+
+    let mut client = McpClient::connect(&http_server_config("remote", server.url(), token_env))
+        .expect("connect");
+    let initialize = client.initialize().expect("initialize");
+    assert_eq!(initialize.server_info.name, "stub-http-mcp");
+    let tools = client.list_tools().expect("tools over SSE");
+    assert_eq!(tools[0].name, "echo");
+
+    let result = client
+        .call_tool(
+            "echo",
+            json!({ "message": "hi" }),
+            vec![McpRoot {
+                uri: "file:///workspace".to_string(),
+                name: Some("workspace".to_string()),
+            }],
+        )
+        .expect("tool call resumed");
+    assert_eq!(result.content[0]["text"].as_str(), Some("hello over http"));
+    drop(client);
+
+    let requests = server.requests();
+    let resumed = requests
+        .iter()
+        .find(|request| request.method == "GET")
+        .expect("resumption GET");
+    assert_eq!(
+        resumed.headers.get("last-event-id").map(String::as_str),
+        Some("call-1")
+    );
+    for request in requests.iter().skip(1) {
+        assert_eq!(
+            request.headers.get("mcp-session-id").map(String::as_str),
+            Some(STUB_SESSION_ID)
+        );
+        assert_eq!(
+            request
+                .headers
+                .get("mcp-protocol-version")
+                .map(String::as_str),
+            Some("2025-11-25")
+        );
+    }
+    assert!(requests.iter().all(|request| {
+        request.headers.get("x-client").map(String::as_str) == Some("agenticos-test")
+    }));
+    assert!(requests
+        .iter()
+        .any(|request| request.body["method"] == "notifications/roots/list_changed"));
+    assert!(
+        requests.iter().any(|request| request.method == "DELETE"),
+        "dropping the client must end the session"
+    );
+}
+
+#[test]
+fn streamable_http_connect_requires_auth_env_variable() {
+    let err = McpClient::connect(&http_server_config(
+        "remote",
+        "http://127.0.0.1:9/mcp".to_string(),
+        "AGENTIC_TEST_MCP_HTTP_TOKEN_MISSING",
+    ))
+    .expect_err("missing credential");
+    assert!(err.contains("AGENTIC_TEST_MCP_HTTP_TOKEN_MISSING"));
+
+    let mut config = http_server_config(
+        "remote",
+        "ftp://example.invalid/mcp".to_string(),
+        "AGENTIC_TEST_MCP_HTTP_TOKEN_MISSING",
+    );
+    if let McpTransportConfig::StreamableHttp {
+        bearer_token_env, ..
+    } = &mut config.transport
+    {
+        *bearer_token_env = None;
+    }
+    assert!(McpClient::connect(&config).is_err());
+}
+
+#[test]
+fn streamable_http_server_shares_bridge_discovery_and_status() {
+    let token_env = "AGENTIC_TEST_MCP_HTTP_TOKEN_BRIDGE";
+    std::env::set_var(token_env, "bridge-token");
+    let server = StubMcpServer::start("bridge-token");
+
+    let mut config = KernelConfig::default();
+    config.mcp.enabled = true;
+    config.mcp.servers = vec![http_server_config("remote", server.url(), token_env)];
+    let mut registry = ToolRegistry::with_builtins();
+    let mut bridge = McpBridgeRuntime::start(&config, &mut registry)
+        .expect("start mcp bridge")
+        .expect("bridge enabled");
+
+    assert!(registry.get("remote.echo").is_some());
+    let snapshot = bridge.status_snapshot().expect("status snapshot");
+    let status = &snapshot.servers[0];
+    assert_eq!(status.transport, "streamable_http");
+    assert_eq!(status.auth_mode, "bearer_env");
+    assert_eq!(status.health, "ready");
+    assert_eq!(status.discovered_tools[0].agentic_tool_name, "remote.echo");
+    bridge.shutdown();
+}
@@ -138,6 +138,7 @@ impl NeuralMemory {
 
     pub fn snapshot(&self) -> MemorySnapshot {
         let residency = self.residency.snapshot();
+        let store = self.residency.swap_store_usage();
 
         MemorySnapshot {
             active: self.active,
@@ -154,6 +155,12 @@ impl NeuralMemory {
             parked_pids: residency.parked_pids,
             oom_events: self.counters.oom_events,
             swap_worker_crashes: residency.swap_worker_crashes,
+            swap_snapshots: store.snapshots,
+            swap_disk_bytes: store.disk_bytes,
+            swap_raw_bytes: store.raw_bytes,
+            swap_evictions: store.evictions,
+            swap_orphans_removed: store.orphans_removed,
+            swap_integrity_failures: store.integrity_failures,
         }
     }
 
@@ -173,6 +180,17 @@ impl NeuralMemory {
         events
     }
 
+    /// The backend has read the snapshot staged by `restore_swapped_pid`.
+    pub fn discard_staged_snapshot(&self, swap_path: &Path) {
+        self.residency.discard_staged_swap_snapshot(swap_path);
+    }
+
+    /// Drop the stored snapshots of a PID that will never swap them back in,
+    /// e.g. a checkpointed process rehydrated under a new PID.
+    pub fn release_swap_snapshots(&self, pid: u64) -> usize {
+        self.residency.release_swap_snapshots(pid)
+    }
+
     pub fn restore_swapped_pid(
         &mut self,
         pid: u64,
@@ -188,12 +206,14 @@ impl NeuralMemory {
                         pid
                     )));
                 };
+                let staged = self.residency.stage_swap_snapshot(path)?;
 
                 Ok(format!(
-                    "resident backend slot snapshot ready pid={} slot={} snapshot={}",
+                    "resident backend slot snapshot ready pid={} slot={} snapshot={}{}",
                     pid,
                     slot_id,
-                    path.display()
+                    path.display(),
+                    if staged { " (decompressed)" } else { "" }
                 ))
             }
             SlotPersistenceKind::Unknown => Err(MemoryError::Swap(format!(
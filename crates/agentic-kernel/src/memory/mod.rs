@@ -2,6 +2,7 @@ mod core;
 mod residency;
 mod restore;
 pub(crate) mod swap;
+mod swap_store;
 mod types;
 
 pub use core::NeuralMemory;
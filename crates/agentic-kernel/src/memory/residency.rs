@@ -1,7 +1,8 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use super::swap::{SwapCounterDeltas, SwapManager};
+use super::swap_store::SwapStoreUsage;
 use super::types::{ContextSlotId, ResidencySnapshot, SwapEvent};
 use crate::errors::MemoryError;
 use crate::prompting::PromptFamily;
@@ -72,6 +73,7 @@ impl LogicalResidencyManager {
 
     pub(super) fn release_process(&mut self, pid: u64) -> Option<ContextSlotId> {
         self.swap.remove_waiting(pid);
+        self.swap.release_pid_snapshots(pid);
         let slot_id = self.pid_to_slot.remove(&pid)?;
         self.slots.remove(&slot_id);
         Some(slot_id)
@@ -119,6 +121,22 @@ impl LogicalResidencyManager {
         self.swap.poll_events()
     }
 
+    pub(super) fn stage_swap_snapshot(&self, handle: &Path) -> Result<bool, MemoryError> {
+        self.swap.stage_for_restore(handle)
+    }
+
+    pub(super) fn discard_staged_swap_snapshot(&self, handle: &Path) {
+        self.swap.discard_staged(handle);
+    }
+
+    pub(super) fn release_swap_snapshots(&self, pid: u64) -> usize {
+        self.swap.release_pid_snapshots(pid)
+    }
+
+    pub(super) fn swap_store_usage(&self) -> SwapStoreUsage {
+        self.swap.store_usage()
+    }
+
     pub(super) fn snapshot(&self) -> ResidencySnapshot {
         ResidencySnapshot {
             tracked_pids: self.tracked_pids(),
@@ -5,12 +5,14 @@
 //! so parking and persistence stay backend-neutral.
 
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
 use std::thread;
 
 use super::restore;
 use super::restore::PreparedSwapTarget;
+use super::swap_store::{SwapStore, SwapStoreConfig, SwapStoreUsage};
 use super::types::ContextSlotId;
 use super::types::SlotPersistenceKind;
 use super::types::SwapEvent;
@@ -67,6 +69,7 @@ pub(super) struct SwapManager {
     rx: Option<Receiver<SwapResult>>,
     waiting: HashSet<u64>,
     worker_crashes: u64,
+    store: Option<Arc<Mutex<SwapStore>>>,
 }
 
 impl SwapManager {
@@ -78,6 +81,7 @@ impl SwapManager {
             rx: None,
             waiting: HashSet::new(),
             worker_crashes: 0,
+            store: None,
         }
     }
 
@@ -97,6 +101,10 @@ impl SwapManager {
         }
 
         let validated = restore::resolve_valid_swap_dir(swap_dir).map_err(MemoryError::Swap)?;
+        self.store = Some(Arc::new(Mutex::new(SwapStore::open(
+            &validated,
+            SwapStoreConfig::from_kernel_config(),
+        ))));
         self.dir = validated;
         self.spawn_worker()
     }
@@ -105,6 +113,7 @@ impl SwapManager {
     fn spawn_worker(&mut self) -> Result<(), MemoryError> {
         let (tx_job, rx_job) = mpsc::channel::<SwapJob>();
         let (tx_result, rx_result) = mpsc::channel::<SwapResult>();
+        let store = self.store.clone();
         thread::Builder::new()
             .name("agentic_swap_worker".to_string())
             .spawn(move || {
@@ -116,6 +125,20 @@ impl SwapManager {
                         &job.target.final_path,
                     )
                     .map(|persistence_kind| {
+                        if let Some(store) = store.as_ref() {
+                            let raw_dir =
+                                backend::context_slot_snapshot_dir(&job.backend_id, job.family);
+                            if let Err(err) = lock_store(store).store(
+                                &job.target.final_path,
+                                job.pid,
+                                job.slot_id,
+                                raw_dir.as_deref(),
+                            ) {
+                                // The raw snapshot is still where the runtime
+                                // wrote it, so the restore keeps working.
+                                tracing::warn!(pid = job.pid, %err, "MEMORY: swap snapshot left uncompressed");
+                            }
+                        }
                         (
                             format!(
                                 "resident slot parked pid={} slot={} backend={} kind={} hint_bytes={} snapshot={}",
@@ -187,6 +210,38 @@ impl SwapManager {
         self.waiting.remove(&pid);
     }
 
+    pub fn store_usage(&self) -> SwapStoreUsage {
+        self.store
+            .as_ref()
+            .map(|store| lock_store(store).usage())
+            .unwrap_or_default()
+    }
+
+    // ── Store ───────────────────────────────────────────────────────
+
+    /// Stage a stored snapshot back where the backend restores it from.
+    pub fn stage_for_restore(&self, handle: &Path) -> Result<bool, MemoryError> {
+        let Some(store) = self.store.as_ref() else {
+            return Ok(false);
+        };
+        lock_store(store)
+            .stage_for_restore(handle)
+            .map_err(MemoryError::Swap)
+    }
+
+    pub fn discard_staged(&self, handle: &Path) {
+        if let Some(store) = self.store.as_ref() {
+            lock_store(store).discard_staged(handle);
+        }
+    }
+
+    pub fn release_pid_snapshots(&self, pid: u64) -> usize {
+        self.store
+            .as_ref()
+            .map(|store| lock_store(store).release_pid(pid))
+            .unwrap_or(0)
+    }
+
     // ── Enqueue ─────────────────────────────────────────────────────
 
     /// Send a swap job to the background worker.
@@ -318,3 +373,9 @@ impl SwapManager {
         Ok((target.final_path, persistence_kind))
     }
 }
+
+fn lock_store(store: &Mutex<SwapStore>) -> std::sync::MutexGuard<'_, SwapStore> {
+    store
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
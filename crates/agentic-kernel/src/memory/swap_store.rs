@@ -0,0 +1,324 @@
+//! Compressed, quota-managed store for parked context-slot snapshots.
+//!
+//! The resident runtime writes the raw slot snapshot into its own
+//! `--slot-save-path`; the swap worker then moves it into `swap_dir` as a
+//! zstd frame next to its SHA-256, and the event loop stages it back (after
+//! verifying the hash) right before the backend restore. The swap path handed
+//! to the engine stays the logical handle `<stem>.swap`; the bytes live in
+//! `<stem>.swap.zst`.
+//!
+//! `manifest.json` keeps the index across reboots so the boot cleanup can
+//! tell live snapshots from orphans.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const COMPRESSED_SUFFIX: &str = ".zst";
+
+#[derive(Debug, Clone, Copy)]
+pub(super) struct SwapStoreConfig {
+    /// Budget for the compressed snapshots on disk; 0 disables eviction.
+    pub max_bytes: u64,
+    pub compression_level: i32,
+}
+
+impl SwapStoreConfig {
+    pub(super) fn from_kernel_config() -> Self {
+        let memory = &crate::config::kernel_config().memory;
+        Self {
+            max_bytes: memory.swap_max_bytes,
+            compression_level: memory.swap_compression_level,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SwapStoreEntry {
+    pid: u64,
+    slot_id: u64,
+    raw_bytes: u64,
+    stored_bytes: u64,
+    sha256: String,
+    /// Runtime `--slot-save-path` the snapshot is staged back into.
+    raw_dir: PathBuf,
+    last_access: u64,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SwapManifest {
+    entries: BTreeMap<String, SwapStoreEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SwapStoreUsage {
+    pub snapshots: usize,
+    pub disk_bytes: u64,
+    pub raw_bytes: u64,
+    pub evictions: u64,
+    pub orphans_removed: u64,
+    pub integrity_failures: u64,
+}
+
+#[derive(Debug)]
+pub(super) struct SwapStore {
+    dir: PathBuf,
+    config: SwapStoreConfig,
+    manifest: SwapManifest,
+    clock: u64,
+    evictions: u64,
+    orphans_removed: u64,
+    integrity_failures: u64,
+}
+
+impl SwapStore {
+    /// Load the manifest of `dir` and drop what it does not account for:
+    /// entries whose file is gone, and `.swap`, `.zst` or `.tmp` files no
+    /// entry points at (raw leftovers, half-written frames, dead sessions).
+    pub(super) fn open(dir: &Path, config: SwapStoreConfig) -> Self {
+        let manifest = fs::read(dir.join(MANIFEST_FILE))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<SwapManifest>(&bytes).ok())
+            .unwrap_or_default();
+        let mut store = Self {
+            dir: dir.to_path_buf(),
+            config,
+            clock: manifest
+                .entries
+                .values()
+                .map(|entry| entry.last_access)
+                .max()
+                .unwrap_or(0),
+            manifest,
+            evictions: 0,
+            orphans_removed: 0,
+            integrity_failures: 0,
+        };
+
+        let before = store.manifest.entries.len();
+        let dir = store.dir.clone();
+        store
+            .manifest
+            .entries
+            .retain(|handle, _| dir.join(compressed_name(handle)).is_file());
+        store.orphans_removed += (before - store.manifest.entries.len()) as u64;
+
+        if let Ok(listing) = fs::read_dir(&store.dir) {
+            for file in listing.flatten() {
+                let name = file.file_name().to_string_lossy().into_owned();
+                let swap_file = name.ends_with(".swap")
+                    || name.ends_with(".tmp")
+                    || name.ends_with(COMPRESSED_SUFFIX);
+                let known = name
+                    .strip_suffix(COMPRESSED_SUFFIX)
+                    .is_some_and(|handle| store.manifest.entries.contains_key(handle));
+                if swap_file && !known && fs::remove_file(file.path()).is_ok() {
+                    store.orphans_removed += 1;
+                }
+            }
+        }
+        if store.orphans_removed > 0 {
+            tracing::info!(
+                removed = store.orphans_removed,
+                dir = %store.dir.display(),
+                "MEMORY: removed orphaned swap snapshots"
+            );
+        }
+        store.enforce_budget(None);
+        store.save_manifest();
+        store
+    }
+
+    /// Move the raw snapshot the runtime wrote for `handle` into the store.
+    /// Returns `false` when the runtime keeps its snapshots out of the
+    /// kernel's reach; the handle then stays backend-owned.
+    pub(super) fn store(
+        &mut self,
+        handle: &Path,
+        pid: u64,
+        slot_id: u64,
+        raw_dir: Option<&Path>,
+    ) -> Result<bool, String> {
+        let name = handle_name(handle)?;
+        let Some(raw_dir) = raw_dir else {
+            return Ok(false);
+        };
+        let raw_path = raw_dir.join(&name);
+        let Ok(raw) = fs::read(&raw_path) else {
+            return Ok(false);
+        };
+        let compressed = zstd::stream::encode_all(raw.as_slice(), self.config.compression_level)
+            .map_err(|err| format!("Failed to compress swap snapshot {}: {}", name, err))?;
+        let final_path = self.dir.join(compressed_name(&name));
+        let tmp_path = self.dir.join(format!("{name}.tmp"));
+        fs::write(&tmp_path, &compressed)
+            .and_then(|_| fs::rename(&tmp_path, &final_path))
+            .map_err(|err| {
+                let _ = fs::remove_file(&tmp_path);
+                format!(
+                    "Failed to write swap snapshot {}: {}",
+                    final_path.display(),
+                    err
+                )
+            })?;
+        let _ = fs::remove_file(&raw_path);
+
+        self.clock += 1;
+        self.manifest.entries.insert(
+            name.clone(),
+            SwapStoreEntry {
+                pid,
+                slot_id,
+                raw_bytes: raw.len() as u64,
+                stored_bytes: compressed.len() as u64,
+                sha256: sha256_hex(&raw),
+                raw_dir: raw_dir.to_path_buf(),
+                last_access: self.clock,
+            },
+        );
+        self.enforce_budget(Some(&name));
+        self.save_manifest();
+        Ok(true)
+    }
+
+    /// Decompress the snapshot behind `handle` into the runtime slot
+    /// directory so the backend can restore it. Returns `false` for handles
+    /// the store does not own.
+    pub(super) fn stage_for_restore(&mut self, handle: &Path) -> Result<bool, String> {
+        let name = handle_name(handle)?;
+        let Some(entry) = self.manifest.entries.get(&name).cloned() else {
+            return Ok(false);
+        };
+        let raw = fs::read(self.dir.join(compressed_name(&name)))
+            .map_err(|err| err.to_string())
+            .and_then(|bytes| {
+                zstd::stream::decode_all(bytes.as_slice()).map_err(|err| err.to_string())
+            });
+        let raw = match raw {
+            Ok(raw) if sha256_hex(&raw) == entry.sha256 => raw,
+            Ok(_) | Err(_) => {
+                self.integrity_failures += 1;
+                self.remove(&name);
+                self.save_manifest();
+                return Err(format!(
+                    "Swap snapshot {} failed its integrity check and was discarded",
+                    name
+                ));
+            }
+        };
+        fs::create_dir_all(&entry.raw_dir)
+            .and_then(|_| fs::write(entry.raw_dir.join(&name), raw))
+            .map_err(|err| format!("Failed to stage swap snapshot {}: {}", name, err))?;
+
+        self.clock += 1;
+        if let Some(entry) = self.manifest.entries.get_mut(&name) {
+            entry.last_access = self.clock;
+        }
+        self.save_manifest();
+        Ok(true)
+    }
+
+    /// Drop the raw copy staged by `stage_for_restore` once the backend read it.
+    pub(super) fn discard_staged(&self, handle: &Path) {
+        let Ok(name) = handle_name(handle) else {
+            return;
+        };
+        if let Some(entry) = self.manifest.entries.get(&name) {
+            let _ = fs::remove_file(entry.raw_dir.join(&name));
+        }
+    }
+
+    /// Remove every snapshot of a process that will not be resumed again.
+    pub(super) fn release_pid(&mut self, pid: u64) -> usize {
+        let handles = self
+            .manifest
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.pid == pid)
+            .map(|(handle, _)| handle.clone())
+            .collect::<Vec<_>>();
+        for handle in &handles {
+            self.remove(handle);
+        }
+        if !handles.is_empty() {
+            self.save_manifest();
+        }
+        handles.len()
+    }
+
+    pub(super) fn usage(&self) -> SwapStoreUsage {
+        let entries = self.manifest.entries.values();
+        SwapStoreUsage {
+            snapshots: self.manifest.entries.len(),
+            disk_bytes: entries.clone().map(|entry| entry.stored_bytes).sum(),
+            raw_bytes: entries.map(|entry| entry.raw_bytes).sum(),
+            evictions: self.evictions,
+            orphans_removed: self.orphans_removed,
+            integrity_failures: self.integrity_failures,
+        }
+    }
+
+    /// Evict the least recently used snapshots until the budget holds,
+    /// never the one that was just written.
+    fn enforce_budget(&mut self, keep: Option<&str>) {
+        if self.config.max_bytes == 0 {
+            return;
+        }
+        while self.usage().disk_bytes > self.config.max_bytes {
+            let Some(coldest) = self
+                .manifest
+                .entries
+                .iter()
+                .filter(|(handle, _)| Some(handle.as_str()) != keep)
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(handle, _)| handle.clone())
+            else {
+                break;
+            };
+            self.remove(&coldest);
+            self.evictions += 1;
+        }
+    }
+
+    fn remove(&mut self, handle: &str) {
+        if self.manifest.entries.remove(handle).is_some() {
+            let _ = fs::remove_file(self.dir.join(compressed_name(handle)));
+        }
+    }
+
+    fn save_manifest(&self) {
+        let Ok(bytes) = serde_json::to_vec_pretty(&self.manifest) else {
+            return;
+        };
+        let tmp_path = self.dir.join(format!("{MANIFEST_FILE}.tmp"));
+        if let Err(err) = fs::write(&tmp_path, bytes)
+            .and_then(|_| fs::rename(&tmp_path, self.dir.join(MANIFEST_FILE)))
+        {
+            tracing::warn!(%err, dir = %self.dir.display(), "MEMORY: swap manifest write failed");
+        }
+    }
+}
+
+fn handle_name(handle: &Path) -> Result<String, String> {
+    handle
+        .file_name()
+        .and_then(|name| name.to_str())
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid swap handle '{}'", handle.display()))
+}
+
+fn compressed_name(handle: &str) -> String {
+    format!("{handle}{COMPRESSED_SUFFIX}")
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
+
+#[cfg(test)]
+#[path = "tests/swap_store.rs"]
+mod tests;
@@ -0,0 +1,132 @@
+use super::{SwapStore, SwapStoreConfig, MANIFEST_FILE};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct StoreDirs {
+    root: PathBuf,
+    swap: PathBuf,
+    raw: PathBuf,
+}
+
+impl StoreDirs {
+    fn new(prefix: &str) -> Self {
+        let now_ns = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let root = std::env::temp_dir().join(format!("{prefix}_{}_{now_ns}", std::process::id()));
+        let swap = root.join("swap");
+        let raw = root.join("slots");
+        fs::create_dir_all(&swap).expect("create swap dir");
+        fs::create_dir_all(&raw).expect("create raw dir");
+        Self { root, swap, raw }
+    }
+
+    /// What llama-server leaves in its slot-save-path after `action=save`.
+    fn runtime_snapshot(&self, name: &str, fill: u8) -> PathBuf {
+        fs::write(self.raw.join(name), vec![fill; 64 * 1024]).expect("write raw snapshot");
+        self.swap.join(name)
+    }
+}
+
+impl Drop for StoreDirs {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn config(max_bytes: u64) -> SwapStoreConfig {
+    SwapStoreConfig {
+        max_bytes,
+        compression_level: 3,
+    }
+}
+
+#[test]
+fn snapshots_are_compressed_verified_and_staged_back_for_restore() {
+    let dirs = StoreDirs::new("agenticos_swap_store");
+    let mut store = SwapStore::open(&dirs.swap, config(0));
+    let handle = dirs.runtime_snapshot("pid_1_slot_2_1.swap", 7);
+
+    assert!(store
+        .store(&handle, 1, 2, Some(&dirs.raw))
+        .expect("store snapshot"));
+    assert!(!dirs.raw.join("pid_1_slot_2_1.swap").exists());
+    let usage = store.usage();
+    assert_eq!(usage.snapshots, 1);
+    assert_eq!(usage.raw_bytes, 64 * 1024);
+    assert!(usage.disk_bytes < usage.raw_bytes / 10);
+
+    assert!(store.stage_for_restore(&handle).expect("stage snapshot"));
+    let staged = fs::read(dirs.raw.join("pid_1_slot_2_1.swap")).expect("staged bytes");
+    assert_eq!(staged, vec![7; 64 * 1024]);
+    store.discard_staged(&handle);
+    assert!(!dirs.raw.join("pid_1_slot_2_1.swap").exists());
+
+    // Unknown handles (backend-owned snapshots) pass through untouched.
+    assert!(!store
+        .stage_for_restore(Path::new("pid_9_slot_9_9.swap"))
+        .expect("pass through"));
+    assert!(!store
+        .store(&dirs.swap.join("missing.swap"), 9, 9, Some(&dirs.raw))
+        .expect("nothing to store"));
+
+    // A corrupted frame is rejected and dropped.
+    fs::write(dirs.swap.join("pid_1_slot_2_1.swap.zst"), b"garbage").expect("corrupt frame");
+    assert!(store.stage_for_restore(&handle).is_err());
+    assert_eq!(store.usage().integrity_failures, 1);
+    assert_eq!(store.usage().snapshots, 0);
+}
+
+#[test]
+fn budget_evicts_the_coldest_snapshot_and_release_removes_a_pid() {
+    let dirs = StoreDirs::new("agenticos_swap_budget");
+    let mut store = SwapStore::open(&dirs.swap, config(u64::MAX));
+    let first = dirs.runtime_snapshot("pid_1_slot_1_1.swap", 1);
+    let second = dirs.runtime_snapshot("pid_2_slot_2_1.swap", 2);
+    store
+        .store(&first, 1, 1, Some(&dirs.raw))
+        .expect("store first");
+    store
+        .store(&second, 2, 2, Some(&dirs.raw))
+        .expect("store second");
+    let one_snapshot = store.usage().disk_bytes / 2;
+
+    // Restoring the first makes the second the coldest one.
+    store.stage_for_restore(&first).expect("touch first");
+    store.discard_staged(&first);
+    store.config.max_bytes = one_snapshot * 2 + one_snapshot / 2;
+    let third = dirs.runtime_snapshot("pid_3_slot_3_1.swap", 3);
+    store
+        .store(&third, 3, 3, Some(&dirs.raw))
+        .expect("store third");
+    assert_eq!(store.usage().evictions, 1);
+    assert!(!dirs.swap.join("pid_2_slot_2_1.swap.zst").exists());
+    assert!(dirs.swap.join("pid_1_slot_1_1.swap.zst").exists());
+
+    assert_eq!(store.release_pid(1), 1);
+    assert_eq!(store.usage().snapshots, 1);
+    assert!(!dirs.swap.join("pid_1_slot_1_1.swap.zst").exists());
+}
+
+#[test]
+fn reopening_keeps_indexed_snapshots_and_removes_orphans() {
+    let dirs = StoreDirs::new("agenticos_swap_boot");
+    {
+        let mut store = SwapStore::open(&dirs.swap, config(0));
+        let kept = dirs.runtime_snapshot("pid_4_slot_4_1.swap", 4);
+        store.store(&kept, 4, 4, Some(&dirs.raw)).expect("store");
+    }
+    fs::write(dirs.swap.join("pid_5_slot_5_1.swap"), b"raw leftover").expect("raw orphan");
+    fs::write(dirs.swap.join("pid_6_slot_6_1.swap.zst"), b"unindexed").expect("zst orphan");
+    fs::write(dirs.swap.join("pid_7_slot_7_1.swap.tmp"), b"partial").expect("tmp orphan");
+    fs::write(dirs.swap.join("README"), b"not a snapshot").expect("unrelated file");
+
+    let store = SwapStore::open(&dirs.swap, config(0));
+    assert_eq!(store.usage().snapshots, 1);
+    assert_eq!(store.usage().orphans_removed, 3);
+    assert!(dirs.swap.join("pid_4_slot_4_1.swap.zst").exists());
+    assert!(dirs.swap.join("README").exists());
+    assert!(dirs.swap.join(MANIFEST_FILE).exists());
+}
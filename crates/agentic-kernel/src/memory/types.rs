@@ -38,6 +38,13 @@ pub struct MemorySnapshot {
     pub parked_pids: usize,
     pub oom_events: u64,
     pub swap_worker_crashes: u64,
+    /// Compressed snapshots kept in `swap_dir` and their size on disk.
+    pub swap_snapshots: usize,
+    pub swap_disk_bytes: u64,
+    pub swap_raw_bytes: u64,
+    pub swap_evictions: u64,
+    pub swap_orphans_removed: u64,
+    pub swap_integrity_failures: u64,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -0,0 +1,111 @@
+//! Finestra di contesto effettiva di un modello.
+//!
+//! Il massimo dichiarato dal modello (sidecar, GGUF, config HF o catalogo
+//! remoto) viene ristretto dal `-c` con cui gira llama.cpp e dalla quota di
+//! memoria che la KV cache può occupare; la sorgente registra il vincolo che
+//! ha deciso, così `MODEL_INFO` può spiegare la scelta.
+
+use agentic_control_models::ContextWindowView;
+use serde::Serialize;
+
+use super::ModelMetadata;
+
+const SOURCE_CONFIG_DEFAULT: &str = "config_default";
+const SOURCE_RUNTIME_CTX: &str = "runtime_ctx";
+pub(crate) const SOURCE_MEMORY_BUDGET: &str = "memory_budget";
+const SOURCE_REMOTE_CATALOG: &str = "remote_catalog";
+const SOURCE_METADATA: &str = "metadata";
+
+/// Sotto questa soglia il modello non entra comunque nel budget e l'admission
+/// control lo rifiuta: non ha senso avviare llama.cpp con un `-c` minuscolo.
+const MIN_MEMORY_CAPPED_WINDOW_TOKENS: usize = 512;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContextWindowResolution {
+    pub window_tokens: usize,
+    pub source: String,
+    pub model_max_tokens: Option<usize>,
+    pub model_source: Option<String>,
+    pub runtime_ctx_tokens: Option<usize>,
+    pub memory_cap_tokens: Option<usize>,
+}
+
+impl ContextWindowResolution {
+    /// A parità di valore vince la sorgente del modello, poi il runtime.
+    pub(crate) fn resolve(
+        declared: Option<(usize, String)>,
+        runtime_ctx_tokens: Option<usize>,
+        memory_cap_tokens: Option<usize>,
+        default_window_tokens: usize,
+    ) -> Self {
+        let declared = declared.filter(|(tokens, _)| *tokens > 0);
+        let runtime_ctx_tokens = runtime_ctx_tokens.filter(|tokens| *tokens > 0);
+        let (mut window_tokens, mut source) = match &declared {
+            Some((tokens, source)) => (*tokens, source.clone()),
+            None => (
+                default_window_tokens.max(1),
+                SOURCE_CONFIG_DEFAULT.to_string(),
+            ),
+        };
+        if let Some(runtime_ctx) = runtime_ctx_tokens {
+            // Senza un massimo dichiarato il `-c` del server è l'unico dato certo.
+            if declared.is_none() || runtime_ctx < window_tokens {
+                window_tokens = runtime_ctx;
+                source = SOURCE_RUNTIME_CTX.to_string();
+            }
+        }
+        if let Some(cap) = memory_cap_tokens {
+            let cap = cap.max(MIN_MEMORY_CAPPED_WINDOW_TOKENS);
+            if cap < window_tokens {
+                window_tokens = cap;
+                source = SOURCE_MEMORY_BUDGET.to_string();
+            }
+        }
+
+        let (model_max_tokens, model_source) = declared.unzip();
+        Self {
+            window_tokens,
+            source,
+            model_max_tokens,
+            model_source,
+            runtime_ctx_tokens,
+            memory_cap_tokens,
+        }
+    }
+
+    /// Massimo dichiarato: metadati locali prima, poi il catalogo remoto.
+    pub(crate) fn declared_window(
+        metadata: Option<&ModelMetadata>,
+        remote_context_window_tokens: Option<usize>,
+    ) -> Option<(usize, String)> {
+        metadata
+            .and_then(|metadata| {
+                metadata.max_context_tokens.map(|tokens| {
+                    let source = metadata
+                        .context_window_source
+                        .clone()
+                        .unwrap_or_else(|| SOURCE_METADATA.to_string());
+                    (tokens, source)
+                })
+            })
+            .or_else(|| {
+                remote_context_window_tokens
+                    .map(|tokens| (tokens, SOURCE_REMOTE_CATALOG.to_string()))
+            })
+    }
+
+    pub(crate) fn is_config_default(&self) -> bool {
+        self.source == SOURCE_CONFIG_DEFAULT
+    }
+
+    pub(crate) fn view(&self) -> ContextWindowView {
+        ContextWindowView {
+            window_tokens: self.window_tokens,
+            source: self.source.clone(),
+            model_max_tokens: self.model_max_tokens,
+            model_source: self.model_source.clone(),
+            runtime_ctx_tokens: self.runtime_ctx_tokens,
+            memory_cap_tokens: self.memory_cap_tokens,
+        }
+    }
+}
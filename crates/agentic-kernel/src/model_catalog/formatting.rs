@@ -7,7 +7,7 @@ use crate::errors::CatalogError;
 
 use super::driver::driver_view_for_entry;
 use super::routing::recommend_for_workload;
-use super::{ModelCatalog, ModelEntry, WorkloadClass};
+use super::{ContextWindowResolution, ModelCatalog, ModelEntry, WorkloadClass};
 
 pub(super) fn format_list_json(catalog: &ModelCatalog) -> String {
     let selected = catalog.selected_id.as_deref();
@@ -158,6 +158,15 @@ pub(super) fn format_info_json(
             .as_ref()
             .and_then(|meta| meta.capabilities.clone()),
         selected: catalog.selected_id.as_deref() == Some(entry.id.as_str()),
+        context_window: Some(
+            ContextWindowResolution::resolve(
+                ContextWindowResolution::declared_window(entry.metadata.as_ref(), None),
+                None,
+                None,
+                crate::config::kernel_config().context.default_window_tokens,
+            )
+            .view(),
+        ),
     };
 
     Ok(serde_json::to_string(&payload).expect("ModelInfoResponse is serializable"))
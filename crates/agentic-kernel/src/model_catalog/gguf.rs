@@ -0,0 +1,237 @@
+//! Lettura minimale dell'header GGUF.
+//!
+//! Legge solo la sezione key/value dei metadati, senza caricare tensori né
+//! array (il vocabolario del tokenizer può contenere centinaia di migliaia di
+//! stringhe): ci servono architettura, `context_length` e la geometria di
+//! attenzione per stimare il costo della KV cache per token.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek};
+use std::path::Path;
+
+const GGUF_MAGIC: &[u8; 4] = b"GGUF";
+const MAX_KV_COUNT: u64 = 1 << 20;
+const MAX_KEY_BYTES: u64 = 64 * 1024;
+const MAX_ARCH_BYTES: u64 = 256;
+/// KV cache in f16, il default di llama.cpp.
+const KV_CACHE_ELEMENT_BYTES: u64 = 2;
+
+const TYPE_UINT8: u32 = 0;
+const TYPE_INT8: u32 = 1;
+const TYPE_UINT16: u32 = 2;
+const TYPE_INT16: u32 = 3;
+const TYPE_UINT32: u32 = 4;
+const TYPE_INT32: u32 = 5;
+const TYPE_FLOAT32: u32 = 6;
+const TYPE_BOOL: u32 = 7;
+const TYPE_STRING: u32 = 8;
+const TYPE_ARRAY: u32 = 9;
+const TYPE_UINT64: u32 = 10;
+const TYPE_INT64: u32 = 11;
+const TYPE_FLOAT64: u32 = 12;
+
+const ARCH_KEY_SUFFIXES: &[&str] = &[
+    "context_length",
+    "block_count",
+    "embedding_length",
+    "attention.head_count",
+    "attention.head_count_kv",
+    "attention.key_length",
+    "attention.value_length",
+];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct GgufHeader {
+    pub(crate) architecture: Option<String>,
+    pub(crate) context_length: Option<u64>,
+    pub(crate) block_count: Option<u64>,
+    pub(crate) embedding_length: Option<u64>,
+    pub(crate) head_count: Option<u64>,
+    pub(crate) head_count_kv: Option<u64>,
+    pub(crate) key_length: Option<u64>,
+    pub(crate) value_length: Option<u64>,
+}
+
+impl GgufHeader {
+    /// Byte di KV cache per token di contesto:
+    /// `layers * kv_heads * (key_len + value_len) * sizeof(f16)`.
+    pub(crate) fn kv_cache_bytes_per_token(&self) -> Option<u64> {
+        let layers = self.block_count.filter(|value| *value > 0)?;
+        let heads = self.head_count.filter(|value| *value > 0)?;
+        let kv_heads = self
+            .head_count_kv
+            .filter(|value| *value > 0)
+            .unwrap_or(heads);
+        let default_head_dim = self.embedding_length.map(|embedding| embedding / heads);
+        let key_length = self.key_length.or(default_head_dim)?;
+        let value_length = self.value_length.or(default_head_dim)?;
+        layers
+            .checked_mul(kv_heads)?
+            .checked_mul(key_length.checked_add(value_length)?)?
+            .checked_mul(KV_CACHE_ELEMENT_BYTES)
+            .filter(|bytes| *bytes > 0)
+    }
+}
+
+/// Legge l'header di un file GGUF (v2/v3). Ritorna `None` per file non GGUF,
+/// versioni non supportate o header troncati.
+pub(crate) fn read_gguf_header(path: &Path) -> Option<GgufHeader> {
+    let is_gguf = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gguf"));
+    if !is_gguf {
+        return None;
+    }
+    let file = File::open(path).ok()?;
+    parse_gguf_header(&mut BufReader::new(file)).ok().flatten()
+}
+
+fn parse_gguf_header<R: Read + Seek>(reader: &mut R) -> io::Result<Option<GgufHeader>> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != GGUF_MAGIC {
+        return Ok(None);
+    }
+    // La v1 usava contatori a 32 bit: non più prodotta da llama.cpp.
+    let version = read_u32(reader)?;
+    if !(2..=3).contains(&version) {
+        return Ok(None);
+    }
+    let _tensor_count = read_u64(reader)?;
+    let kv_count = read_u64(reader)?;
+    if kv_count > MAX_KV_COUNT {
+        return Ok(None);
+    }
+
+    let mut architecture = None;
+    let mut numeric = BTreeMap::new();
+    for _ in 0..kv_count {
+        let key_len = read_u64(reader)?;
+        if key_len > MAX_KEY_BYTES {
+            return Ok(None);
+        }
+        let key = read_string_bytes(reader, key_len)?;
+        let value_type = read_u32(reader)?;
+
+        if key == "general.architecture" && value_type == TYPE_STRING {
+            let len = read_u64(reader)?;
+            if len > MAX_ARCH_BYTES {
+                skip_bytes(reader, len)?;
+            } else {
+                architecture = Some(read_string_bytes(reader, len)?);
+            }
+            continue;
+        }
+        let wanted = ARCH_KEY_SUFFIXES
+            .iter()
+            .any(|suffix| key.ends_with(&format!(".{suffix}")));
+        if !wanted {
+            skip_value(reader, value_type)?;
+        } else if let Some(value) = read_unsigned_value(reader, value_type)? {
+            numeric.insert(key, value);
+        }
+    }
+
+    let Some(arch) = architecture else {
+        return Ok(Some(GgufHeader::default()));
+    };
+    let field = |suffix: &str| numeric.get(&format!("{arch}.{suffix}")).copied();
+    Ok(Some(GgufHeader {
+        context_length: field("context_length"),
+        block_count: field("block_count"),
+        embedding_length: field("embedding_length"),
+        head_count: field("attention.head_count"),
+        head_count_kv: field("attention.head_count_kv"),
+        key_length: field("attention.key_length"),
+        value_length: field("attention.value_length"),
+        architecture: Some(arch),
+    }))
+}
+
+/// Legge un valore intero non negativo; per gli altri tipi consuma il valore
+/// e ritorna `None`.
+fn read_unsigned_value<R: Read + Seek>(reader: &mut R, value_type: u32) -> io::Result<Option<u64>> {
+    let value = match value_type {
+        TYPE_UINT8 => u64::from(read_array::<1, _>(reader)?[0]),
+        TYPE_INT8 => u64::try_from(i8::from_le_bytes(read_array(reader)?)).unwrap_or(0),
+        TYPE_UINT16 => u64::from(u16::from_le_bytes(read_array(reader)?)),
+        TYPE_INT16 => u64::try_from(i16::from_le_bytes(read_array(reader)?)).unwrap_or(0),
+        TYPE_UINT32 => u64::from(read_u32(reader)?),
+        TYPE_INT32 => u64::try_from(i32::from_le_bytes(read_array(reader)?)).unwrap_or(0),
+        TYPE_UINT64 => read_u64(reader)?,
+        TYPE_INT64 => u64::try_from(i64::from_le_bytes(read_array(reader)?)).unwrap_or(0),
+        other => {
+            skip_value(reader, other)?;
+            return Ok(None);
+        }
+    };
+    Ok((value > 0).then_some(value))
+}
+
+fn skip_value<R: Read + Seek>(reader: &mut R, value_type: u32) -> io::Result<()> {
+    match value_type {
+        TYPE_STRING => {
+            let len = read_u64(reader)?;
+            skip_bytes(reader, len)
+        }
+        TYPE_ARRAY => {
+            let element_type = read_u32(reader)?;
+            let len = read_u64(reader)?;
+            if let Some(size) = fixed_value_size(element_type) {
+                let total = len
+                    .checked_mul(size)
+                    .ok_or_else(|| invalid("gguf array too large"))?;
+                return skip_bytes(reader, total);
+            }
+            for _ in 0..len {
+                skip_value(reader, element_type)?;
+            }
+            Ok(())
+        }
+        other => {
+            let size = fixed_value_size(other).ok_or_else(|| invalid("unknown gguf value type"))?;
+            skip_bytes(reader, size)
+        }
+    }
+}
+
+fn fixed_value_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        TYPE_UINT8 | TYPE_INT8 | TYPE_BOOL => Some(1),
+        TYPE_UINT16 | TYPE_INT16 => Some(2),
+        TYPE_UINT32 | TYPE_INT32 | TYPE_FLOAT32 => Some(4),
+        TYPE_UINT64 | TYPE_INT64 | TYPE_FLOAT64 => Some(8),
+        _ => None,
+    }
+}
+
+fn skip_bytes<R: Read + Seek>(reader: &mut R, len: u64) -> io::Result<()> {
+    let offset = i64::try_from(len).map_err(|_| invalid("gguf length overflow"))?;
+    reader.seek_relative(offset)
+}
+
+fn read_string_bytes<R: Read>(reader: &mut R, len: u64) -> io::Result<String> {
+    let mut bytes = vec![0u8; len as usize];
+    reader.read_exact(&mut bytes)?;
+    String::from_utf8(bytes).map_err(|_| invalid("gguf string is not utf-8"))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    Ok(u32::from_le_bytes(read_array(reader)?))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    Ok(u64::from_le_bytes(read_array(reader)?))
+}
+
+fn read_array<const N: usize, R: Read>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut buf = [0u8; N];
+    reader.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
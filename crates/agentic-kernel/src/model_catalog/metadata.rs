@@ -6,6 +6,11 @@ use serde::{Deserialize, Serialize};
 
 use crate::prompting::PromptFamily;
 
+use super::gguf::read_gguf_header;
+
+/// Valori come `model_max_length = 1e30` sono sentinelle HF per "nessun limite".
+const MAX_PLAUSIBLE_CONTEXT_TOKENS: u64 = 16 * 1024 * 1024;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ModelMetadata {
     #[serde(default)]
@@ -14,6 +19,13 @@ pub struct ModelMetadata {
     pub architecture: Option<String>,
     #[serde(default)]
     pub max_context_tokens: Option<usize>,
+    /// Da dove viene `max_context_tokens`: `sidecar`, `gguf`,
+    /// `tokenizer_config` o `model_config`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window_source: Option<String>,
+    /// Costo stimato della KV cache per token di contesto (f16).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kv_cache_bytes_per_token: Option<u64>,
     #[serde(default)]
     pub backend_preference: Option<String>,
     #[serde(default)]
@@ -52,16 +64,108 @@ pub(crate) fn infer_metadata_path(model_path: &Path) -> Option<PathBuf> {
 
 pub(crate) fn load_model_metadata(path: &Path) -> Option<ModelMetadata> {
     let raw = fs::read_to_string(path).ok()?;
-    serde_json::from_str::<ModelMetadata>(&raw).ok()
+    let mut metadata = serde_json::from_str::<ModelMetadata>(&raw).ok()?;
+    if metadata.max_context_tokens.is_some() && metadata.context_window_source.is_none() {
+        metadata.context_window_source = Some("sidecar".to_string());
+    }
+    Some(metadata)
+}
+
+/// Metadati di un artefatto locale fuori dal catalogo: header nativo più
+/// sidecar, con le stesse precedenze della discovery.
+pub(crate) fn load_local_model_metadata(model_path: &Path) -> Option<ModelMetadata> {
+    let (native_metadata, _, _) = load_native_model_metadata(model_path, None);
+    let sidecar_metadata = infer_metadata_path(model_path)
+        .as_ref()
+        .and_then(|path| load_model_metadata(path));
+    merge_model_metadata(native_metadata, sidecar_metadata)
 }
 
 pub(super) fn load_native_model_metadata(
-    _model_path: &Path,
+    model_path: &Path,
     tokenizer_path: Option<&Path>,
 ) -> (Option<ModelMetadata>, bool, bool) {
-    let tokenizer_metadata = tokenizer_path.and_then(load_tokenizer_native_metadata);
+    let gguf_metadata = read_gguf_header(model_path).and_then(|header| {
+        let metadata = ModelMetadata {
+            architecture: header.architecture.clone(),
+            max_context_tokens: header.context_length.and_then(plausible_context_tokens),
+            context_window_source: header.context_length.map(|_| "gguf".to_string()),
+            kv_cache_bytes_per_token: header.kv_cache_bytes_per_token(),
+            ..ModelMetadata::default()
+        };
+        (metadata != ModelMetadata::default()).then_some(metadata)
+    });
+    let native_from_gguf = gguf_metadata.is_some();
+
+    let mut tokenizer_metadata = tokenizer_path.and_then(load_tokenizer_native_metadata);
+    // La finestra dichiarata dal GGUF vince sulla config HF accanto al tokenizer.
+    if gguf_metadata
+        .as_ref()
+        .and_then(|metadata| metadata.max_context_tokens)
+        .is_none()
+    {
+        if let Some((tokens, source)) = load_config_context_window(model_path, tokenizer_path) {
+            let metadata = tokenizer_metadata.get_or_insert_with(ModelMetadata::default);
+            metadata.max_context_tokens = Some(tokens);
+            metadata.context_window_source = Some(source.to_string());
+        }
+    }
     let native_from_tokenizer = tokenizer_metadata.is_some();
-    (tokenizer_metadata, false, native_from_tokenizer)
+    (
+        merge_model_metadata(gguf_metadata, tokenizer_metadata),
+        native_from_gguf,
+        native_from_tokenizer,
+    )
+}
+
+/// Cerca la finestra in `tokenizer_config.json` (`model_max_length`) e poi in
+/// `config.json` (`max_position_embeddings`), accanto al tokenizer o al modello.
+fn load_config_context_window(
+    model_path: &Path,
+    tokenizer_path: Option<&Path>,
+) -> Option<(usize, &'static str)> {
+    let mut dirs = Vec::new();
+    for dir in [tokenizer_path.and_then(Path::parent), model_path.parent()]
+        .into_iter()
+        .flatten()
+    {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    let lookups = [
+        (
+            "tokenizer_config.json",
+            "model_max_length",
+            "tokenizer_config",
+        ),
+        ("config.json", "max_position_embeddings", "model_config"),
+    ];
+    lookups.iter().find_map(|(file_name, key, source)| {
+        dirs.iter().find_map(|dir| {
+            let raw = fs::read_to_string(dir.join(file_name)).ok()?;
+            let tokens = parse_config_context_window(&raw, key)?;
+            Some((tokens, *source))
+        })
+    })
+}
+
+pub(super) fn parse_config_context_window(raw: &str, key: &str) -> Option<usize> {
+    let json: serde_json::Value = serde_json::from_str(raw).ok()?;
+    let value = json.get(key)?;
+    let tokens = value.as_u64().or_else(|| {
+        value
+            .as_f64()
+            .filter(|v| v.fract() == 0.0 && *v > 0.0)
+            .map(|v| v as u64)
+    })?;
+    plausible_context_tokens(tokens)
+}
+
+fn plausible_context_tokens(tokens: u64) -> Option<usize> {
+    (tokens > 0 && tokens <= MAX_PLAUSIBLE_CONTEXT_TOKENS)
+        .then(|| usize::try_from(tokens).ok())
+        .flatten()
 }
 
 fn load_tokenizer_native_metadata(path: &Path) -> Option<ModelMetadata> {
@@ -155,6 +259,10 @@ pub(super) fn merge_model_metadata(
             }
             if overlay.max_context_tokens.is_some() {
                 base.max_context_tokens = overlay.max_context_tokens;
+                base.context_window_source = overlay.context_window_source;
+            }
+            if overlay.kv_cache_bytes_per_token.is_some() {
+                base.kv_cache_bytes_per_token = overlay.kv_cache_bytes_per_token;
             }
             if overlay.backend_preference.is_some() {
                 base.backend_preference = overlay.backend_preference;
@@ -6,9 +6,11 @@ use crate::errors::CatalogError;
 use crate::prompting::PromptFamily;
 
 mod cache;
+mod context_window;
 mod discovery;
 mod driver;
 mod formatting;
+mod gguf;
 mod metadata;
 mod remote_catalog;
 mod routing;
@@ -17,8 +19,10 @@ mod routing;
 mod tests;
 mod workload;
 
+pub use context_window::ContextWindowResolution;
+pub(crate) use context_window::SOURCE_MEMORY_BUDGET;
+pub(crate) use metadata::load_local_model_metadata;
 pub use metadata::ModelMetadata;
-pub(crate) use metadata::{infer_metadata_path, load_model_metadata};
 pub use remote_catalog::{RemoteModelEntry, RemoteProviderEntry};
 pub use workload::{infer_workload_class, parse_workload_label, WorkloadClass};
 
@@ -500,6 +500,150 @@ fn format_info_json_exposes_unresolved_driver_for_unsupported_architecture() {
     let _ = fs::remove_dir_all(base);
 }
 
+#[test]
+fn discover_reads_context_window_and_kv_geometry_from_gguf_header() {
+    let base = mk_temp_dir("agenticos_catalog_gguf_header");
+    let models = base.join("models");
+    let qwen_dir = models.join("qwen2.5-7b");
+    fs::create_dir_all(&qwen_dir).expect("create qwen dir");
+    write_test_gguf(&qwen_dir.join("qwen.gguf"), "qwen2", 32_768);
+
+    let catalog = ModelCatalog::discover(&models).expect("discover models");
+    let entry = catalog.entries.first().expect("entry present");
+    let metadata = entry.metadata.as_ref().expect("native gguf metadata");
+    assert_eq!(metadata.architecture.as_deref(), Some("qwen2"));
+    assert_eq!(metadata.max_context_tokens, Some(32_768));
+    assert_eq!(metadata.context_window_source.as_deref(), Some("gguf"));
+    // 28 layer * 4 kv head * (128 + 128) * 2 byte
+    assert_eq!(metadata.kv_cache_bytes_per_token, Some(57_344));
+    assert_eq!(entry.metadata_source.as_deref(), Some("native:gguf"));
+
+    let info: ModelInfoResponse = serde_json::from_str(
+        &catalog
+            .format_info_json("qwen2.5-7b/qwen")
+            .expect("model info"),
+    )
+    .expect("json info");
+    let window = info.context_window.expect("context window");
+    assert_eq!(window.window_tokens, 32_768);
+    assert_eq!(window.source, "gguf");
+
+    fs::write(
+        qwen_dir.join("metadata.json"),
+        r#"{ "max_context_tokens": 8192 }"#,
+    )
+    .expect("write sidecar");
+    let catalog = ModelCatalog::discover(&models).expect("rediscover models");
+    let metadata = catalog.entries[0].metadata.as_ref().expect("metadata");
+    assert_eq!(metadata.max_context_tokens, Some(8192));
+    assert_eq!(metadata.context_window_source.as_deref(), Some("sidecar"));
+    assert_eq!(metadata.kv_cache_bytes_per_token, Some(57_344));
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn discover_falls_back_to_hf_config_context_window() {
+    let base = mk_temp_dir("agenticos_catalog_hf_config_window");
+    let models = base.join("models");
+    let llama_dir = models.join("llama-3-8b");
+    fs::create_dir_all(&llama_dir).expect("create llama dir");
+    fs::write(llama_dir.join("llama.gguf"), b"stub").expect("write gguf stub");
+    fs::write(
+        llama_dir.join("tokenizer_config.json"),
+        r#"{ "model_max_length": 1000000000000000019884624838656 }"#,
+    )
+    .expect("write tokenizer config");
+    fs::write(
+        llama_dir.join("config.json"),
+        r#"{ "max_position_embeddings": 8192 }"#,
+    )
+    .expect("write model config");
+
+    let catalog = ModelCatalog::discover(&models).expect("discover models");
+    let metadata = catalog.entries[0].metadata.as_ref().expect("metadata");
+    assert_eq!(metadata.max_context_tokens, Some(8192));
+    assert_eq!(
+        metadata.context_window_source.as_deref(),
+        Some("model_config")
+    );
+
+    let _ = fs::remove_dir_all(base);
+}
+
+#[test]
+fn context_window_resolution_reports_the_binding_constraint() {
+    let declared = Some((32_768, "gguf".to_string()));
+
+    let unclamped = ContextWindowResolution::resolve(declared.clone(), None, None, 2048);
+    assert_eq!(unclamped.window_tokens, 32_768);
+    assert_eq!(unclamped.source, "gguf");
+
+    let runtime = ContextWindowResolution::resolve(declared.clone(), Some(16_384), None, 2048);
+    assert_eq!(runtime.window_tokens, 16_384);
+    assert_eq!(runtime.source, "runtime_ctx");
+    assert_eq!(runtime.model_max_tokens, Some(32_768));
+
+    let memory =
+        ContextWindowResolution::resolve(declared.clone(), Some(16_384), Some(12_000), 2048);
+    assert_eq!(memory.window_tokens, 12_000);
+    assert_eq!(memory.source, "memory_budget");
+
+    let tie = ContextWindowResolution::resolve(declared, Some(32_768), None, 2048);
+    assert_eq!(tie.source, "gguf");
+
+    let fallback = ContextWindowResolution::resolve(None, None, Some(100_000), 2048);
+    assert_eq!(fallback.window_tokens, 2048);
+    assert!(fallback.is_config_default());
+
+    let runtime_only = ContextWindowResolution::resolve(None, Some(4096), None, 2048);
+    assert_eq!(runtime_only.window_tokens, 4096);
+    assert_eq!(runtime_only.source, "runtime_ctx");
+
+    let remote = ContextWindowResolution::declared_window(None, Some(200_000));
+    assert_eq!(remote, Some((200_000, "remote_catalog".to_string())));
+}
+
+fn write_test_gguf(path: &std::path::Path, arch: &str, context_length: u32) {
+    fn string(out: &mut Vec<u8>, value: &str) {
+        out.extend_from_slice(&(value.len() as u64).to_le_bytes());
+        out.extend_from_slice(value.as_bytes());
+    }
+    fn kv_u32(out: &mut Vec<u8>, key: &str, value: u32) {
+        string(out, key);
+        out.extend_from_slice(&4u32.to_le_bytes());
+        out.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let mut out = b"GGUF".to_vec();
+    out.extend_from_slice(&3u32.to_le_bytes());
+    out.extend_from_slice(&0u64.to_le_bytes());
+    out.extend_from_slice(&9u64.to_le_bytes());
+    string(&mut out, "general.architecture");
+    out.extend_from_slice(&8u32.to_le_bytes());
+    string(&mut out, arch);
+    // array di stringhe da saltare, come il vocabolario del tokenizer
+    string(&mut out, "tokenizer.ggml.tokens");
+    out.extend_from_slice(&9u32.to_le_bytes());
+    out.extend_from_slice(&8u32.to_le_bytes());
+    out.extend_from_slice(&3u64.to_le_bytes());
+    for token in ["<|im_start|>", "<|im_end|>", "hello"] {
+        string(&mut out, token);
+    }
+    string(&mut out, "general.name");
+    out.extend_from_slice(&8u32.to_le_bytes());
+    string(&mut out, "Qwen2.5 7B Instruct");
+    kv_u32(&mut out, &format!("{arch}.context_length"), context_length);
+    kv_u32(&mut out, &format!("{arch}.block_count"), 28);
+    kv_u32(&mut out, &format!("{arch}.embedding_length"), 3584);
+    kv_u32(&mut out, &format!("{arch}.attention.head_count"), 28);
+    kv_u32(&mut out, &format!("{arch}.attention.head_count_kv"), 4);
+    string(&mut out, &format!("{arch}.rope.freq_base"));
+    out.extend_from_slice(&6u32.to_le_bytes());
+    out.extend_from_slice(&1_000_000f32.to_le_bytes());
+    fs::write(path, out).expect("write gguf");
+}
+
 fn mk_temp_dir(prefix: &str) -> PathBuf {
     let ts = SystemTime::now()
         .duration_since(UNIX_EPOCH)
@@ -0,0 +1,513 @@
+use std::net::{SocketAddr, TcpStream};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use agentic_control_models::{
+    AssistantSegmentKind, ExecStartPayload, KernelEvent, ModelCatalogSnapshot, PidStatusResponse,
+    SendInputResult,
+};
+use agentic_protocol::OpCode;
+use serde_json::{json, Value};
+
+use super::http::{
+    write_error_response, write_json_response, write_sse_data, write_sse_headers, HttpRequest,
+};
+use super::kernel_client::{KernelClientError, KernelConnection};
+use super::models::{
+    AssistantMessage, ChatCompletionChoice, ChatCompletionChunk, ChatCompletionChunkChoice,
+    ChatCompletionRequest, ChatCompletionResponse, ChatCompletionUsage, ChatMessage, ChunkDelta,
+    ModelList, ModelObject,
+};
+
+const DEFAULT_MODEL_NAME: &str = "agenticos";
+
+pub(super) fn handle_chat_completion(
+    stream: &mut TcpStream,
+    request: &HttpRequest,
+    kernel_addr: SocketAddr,
+    timeout: Duration,
+) -> Result<(), String> {
+    let chat: ChatCompletionRequest = match serde_json::from_slice(&request.body) {
+        Ok(chat) => chat,
+        Err(err) => {
+            return write_error_response(
+                stream,
+                400,
+                "invalid_request_error",
+                None,
+                &format!("Invalid chat completion body: {err}"),
+            );
+        }
+    };
+    let prompt = match chat.session_id.as_deref() {
+        Some(_) => last_user_message(&chat.messages),
+        None => render_exec_prompt(&chat.messages),
+    };
+    let prompt = match prompt {
+        Ok(prompt) => prompt,
+        Err(message) => {
+            return write_error_response(stream, 400, "invalid_request_error", None, &message);
+        }
+    };
+
+    let mut kernel = match open_kernel(kernel_addr, timeout, request.bearer_token()) {
+        Ok(kernel) => kernel,
+        Err(err) => return write_kernel_error(stream, &err),
+    };
+    // A named model must be in the catalog and is loaded for the turn through
+    // EXEC's `model` selector. Without one the kernel picks the model, and a
+    // resumed session stays on its runtime: both report the facade alias.
+    let selector = match requested_model(&chat) {
+        None => None,
+        Some(requested) => match kernel.request::<ModelCatalogSnapshot>(OpCode::ListModels, b"") {
+            Ok(snapshot) => match catalog_model_selector(&snapshot, requested) {
+                Some(selector) => Some(selector),
+                None => {
+                    return write_error_response(
+                        stream,
+                        404,
+                        "invalid_request_error",
+                        Some("model_not_found"),
+                        &format!("The model '{requested}' does not exist."),
+                    );
+                }
+            },
+            Err(err) => return write_kernel_error(stream, &err),
+        },
+    };
+    let model = requested_model(&chat)
+        .filter(|_| chat.session_id.is_none())
+        .unwrap_or(DEFAULT_MODEL_NAME)
+        .to_string();
+    let (pid, session_id) = match start_turn(&mut kernel, &chat, &prompt, selector.as_deref()) {
+        Ok(started) => started,
+        Err(err) => return write_kernel_error(stream, &err),
+    };
+
+    let completion_id = format!("chatcmpl-{session_id}-{pid}");
+    let created = unix_now_secs();
+
+    let result = if chat.stream {
+        stream_turn(
+            stream,
+            &mut kernel,
+            pid,
+            &completion_id,
+            created,
+            &model,
+            &session_id,
+        )
+    } else {
+        match follow_turn(&mut kernel, pid, |_| Ok(())) {
+            Ok(outcome) => {
+                let usage = turn_usage(&mut kernel, pid, outcome.completion_tokens);
+                write_json_response(
+                    stream,
+                    200,
+                    &ChatCompletionResponse {
+                        id: completion_id,
+                        object: "chat.completion",
+                        created,
+                        model,
+                        choices: vec![ChatCompletionChoice {
+                            index: 0,
+                            message: AssistantMessage {
+                                role: "assistant",
+                                content: outcome.text,
+                            },
+                            finish_reason: outcome.finish_reason.to_string(),
+                        }],
+                        usage,
+                        session_id: session_id.clone(),
+                    },
+                )
+            }
+            Err(TurnError::Kernel(err)) => write_kernel_error(stream, &err),
+            Err(TurnError::Client(err)) => Err(err),
+        }
+    };
+
+    // Every HTTP request releases its process; the conversation lives on in the
+    // persisted session and can be resumed by passing `session_id`.
+    let _ = kernel.request::<Value>(OpCode::Term, pid.to_string().as_bytes());
+    result
+}
+
+pub(super) fn handle_list_models(
+    stream: &mut TcpStream,
+    request: &HttpRequest,
+    kernel_addr: SocketAddr,
+    timeout: Duration,
+) -> Result<(), String> {
+    let snapshot = open_kernel(kernel_addr, timeout, request.bearer_token())
+        .and_then(|mut kernel| kernel.request::<ModelCatalogSnapshot>(OpCode::ListModels, b""));
+    match snapshot {
+        Ok(snapshot) => write_json_response(stream, 200, &model_list_from_catalog(&snapshot)),
+        Err(err) => write_kernel_error(stream, &err),
+    }
+}
+
+fn open_kernel(
+    kernel_addr: SocketAddr,
+    timeout: Duration,
+    bearer_token: Option<&str>,
+) -> Result<KernelConnection, KernelClientError> {
+    let mut kernel = KernelConnection::connect(kernel_addr, timeout)?;
+    kernel.handshake(bearer_token)?;
+    Ok(kernel)
+}
+
+fn start_turn(
+    kernel: &mut KernelConnection,
+    chat: &ChatCompletionRequest,
+    prompt: &str,
+    model_selector: Option<&str>,
+) -> Result<(u64, String), KernelClientError> {
+    if let Some(session_id) = chat.session_id.as_deref() {
+        let payload = json!({ "session_id": session_id, "prompt": prompt }).to_string();
+        let result: SendInputResult = kernel.request(OpCode::SendInput, payload.as_bytes())?;
+        return Ok((result.pid, session_id.to_string()));
+    }
+
+    // A plain-text EXEC keeps the kernel's workload quota; the JSON form is only
+    // used for an explicit token cap or model, and omits the quota keys when
+    // only the model is set.
+    let max_tokens = chat.effective_max_tokens();
+    let payload = if max_tokens.is_none() && model_selector.is_none() {
+        prompt.to_string()
+    } else {
+        let mut payload = json!({ "prompt": prompt });
+        if let Some(max_tokens) = max_tokens {
+            payload["max_tokens"] = json!(max_tokens);
+            payload["max_syscalls"] = json!(
+                crate::config::kernel_config()
+                    .scheduler
+                    .general
+                    .max_syscalls
+            );
+        }
+        if let Some(selector) = model_selector {
+            payload["model"] = json!(selector);
+        }
+        payload.to_string()
+    };
+    let started: ExecStartPayload = kernel.request(OpCode::Exec, payload.as_bytes())?;
+    Ok((started.pid, started.session_id))
+}
+
+fn stream_turn(
+    stream: &mut TcpStream,
+    kernel: &mut KernelConnection,
+    pid: u64,
+    completion_id: &str,
+    created: u64,
+    model: &str,
+    session_id: &str,
+) -> Result<(), String> {
+    let chunk = |delta: ChunkDelta, finish_reason: Option<String>| {
+        serde_json::to_string(&ChatCompletionChunk {
+            id: completion_id.to_string(),
+            object: "chat.completion.chunk",
+            created,
+            model: model.to_string(),
+            choices: vec![ChatCompletionChunkChoice {
+                index: 0,
+                delta,
+                finish_reason,
+            }],
+            session_id: session_id.to_string(),
+        })
+        .map_err(|err| format!("Failed to serialize chat completion chunk: {err}"))
+    };
+
+    write_sse_headers(stream)?;
+    write_sse_data(
+        stream,
+        &chunk(
+            ChunkDelta {
+                role: Some("assistant"),
+                content: None,
+            },
+            None,
+        )?,
+    )?;
+
+    let outcome = follow_turn(kernel, pid, |text| {
+        write_sse_data(
+            stream,
+            &chunk(
+                ChunkDelta {
+                    role: None,
+                    content: Some(text.to_string()),
+                },
+                None,
+            )?,
+        )
+    });
+    match outcome {
+        Ok(outcome) => {
+            write_sse_data(
+                stream,
+                &chunk(
+                    ChunkDelta::default(),
+                    Some(outcome.finish_reason.to_string()),
+                )?,
+            )?;
+        }
+        Err(TurnError::Kernel(err)) => {
+            let error = json!({
+                "error": {
+                    "message": err.message,
+                    "type": "kernel_error",
+                    "code": err.code,
+                }
+            });
+            write_sse_data(stream, &error.to_string())?;
+        }
+        Err(TurnError::Client(err)) => return Err(err),
+    }
+    write_sse_data(stream, "[DONE]")
+}
+
+/// Usage of a finished turn, read from the PID's context before it is released.
+fn turn_usage(
+    kernel: &mut KernelConnection,
+    pid: u64,
+    completion_tokens: u64,
+) -> Option<ChatCompletionUsage> {
+    let status: PidStatusResponse = kernel
+        .request(OpCode::Status, pid.to_string().as_bytes())
+        .ok()?;
+    Some(usage_from_context(status.tokens as u64, completion_tokens))
+}
+
+/// The context holds everything the model read plus what it generated, so
+/// whatever is not completion counts as prompt.
+pub(crate) fn usage_from_context(
+    context_tokens: u64,
+    completion_tokens: u64,
+) -> ChatCompletionUsage {
+    let prompt_tokens = context_tokens.saturating_sub(completion_tokens);
+    ChatCompletionUsage {
+        prompt_tokens,
+        completion_tokens,
+        total_tokens: prompt_tokens + completion_tokens,
+    }
+}
+
+pub(crate) struct TurnOutcome {
+    pub text: String,
+    pub finish_reason: &'static str,
+    pub completion_tokens: u64,
+}
+
+pub(crate) enum TurnError {
+    Kernel(KernelClientError),
+    Client(String),
+}
+
+fn follow_turn(
+    kernel: &mut KernelConnection,
+    pid: u64,
+    mut on_delta: impl FnMut(&str) -> Result<(), String>,
+) -> Result<TurnOutcome, TurnError> {
+    let mut text = String::new();
+    loop {
+        let event = kernel.next_event().map_err(TurnError::Kernel)?;
+        match apply_turn_event(pid, &event) {
+            TurnStep::Ignore => {}
+            TurnStep::Delta(delta) => {
+                on_delta(delta).map_err(TurnError::Client)?;
+                text.push_str(delta);
+            }
+            TurnStep::Finished {
+                finish_reason,
+                completion_tokens,
+            } => {
+                return Ok(TurnOutcome {
+                    text,
+                    finish_reason,
+                    completion_tokens,
+                })
+            }
+            TurnStep::Errored(message) => {
+                return Err(TurnError::Kernel(KernelClientError {
+                    code: "SESSION_ERRORED".to_string(),
+                    message,
+                }))
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum TurnStep<'a> {
+    Ignore,
+    Delta(&'a str),
+    Finished {
+        finish_reason: &'static str,
+        completion_tokens: u64,
+    },
+    Errored(String),
+}
+
+pub(crate) fn apply_turn_event(pid: u64, event: &KernelEvent) -> TurnStep<'_> {
+    match event {
+        KernelEvent::TimelineSegment {
+            pid: event_pid,
+            segment_kind: AssistantSegmentKind::Message,
+            text,
+        } if *event_pid == pid => TurnStep::Delta(text),
+        KernelEvent::SessionFinished {
+            pid: event_pid,
+            tokens_generated,
+            reason,
+            ..
+        } if *event_pid == pid => TurnStep::Finished {
+            finish_reason: finish_reason_for(reason),
+            completion_tokens: tokens_generated.unwrap_or(0),
+        },
+        KernelEvent::SessionErrored {
+            pid: event_pid,
+            message,
+        } if *event_pid == pid => TurnStep::Errored(message.clone()),
+        _ => TurnStep::Ignore,
+    }
+}
+
+pub(crate) fn finish_reason_for(reason: &str) -> &'static str {
+    match reason {
+        "awaiting_turn_decision" => "length",
+        _ => "stop",
+    }
+}
+
+/// Flatten a stateless OpenAI message list into a single EXEC prompt.
+pub(crate) fn render_exec_prompt(messages: &[ChatMessage]) -> Result<String, String> {
+    let last_user = last_user_message(messages)?;
+    let (history, _) = messages.split_at(messages.len() - 1);
+
+    let mut system = Vec::new();
+    let mut transcript = Vec::new();
+    for message in history {
+        let text = message.text();
+        if text.trim().is_empty() {
+            continue;
+        }
+        match message.role.as_str() {
+            "system" | "developer" => system.push(text),
+            "assistant" => transcript.push(format!("Assistant: {text}")),
+            "tool" | "function" => transcript.push(format!("Tool: {text}")),
+            _ => transcript.push(format!("User: {text}")),
+        }
+    }
+
+    let mut sections = Vec::new();
+    if !system.is_empty() {
+        sections.push(system.join("\n\n"));
+    }
+    if !transcript.is_empty() {
+        sections.push(format!("Conversation so far:\n{}", transcript.join("\n")));
+    }
+    sections.push(last_user);
+    Ok(sections.join("\n\n"))
+}
+
+pub(crate) fn last_user_message(messages: &[ChatMessage]) -> Result<String, String> {
+    let Some(last) = messages.last() else {
+        return Err("messages must contain at least one entry.".to_string());
+    };
+    if last.role != "user" {
+        return Err("The last message must have role 'user'.".to_string());
+    }
+    let text = last.text();
+    if text.trim().is_empty() {
+        return Err("The last user message must contain text content.".to_string());
+    }
+    Ok(text)
+}
+
+pub(crate) fn model_list_from_catalog(snapshot: &ModelCatalogSnapshot) -> ModelList {
+    let mut data: Vec<ModelObject> = snapshot
+        .models
+        .iter()
+        .map(|entry| ModelObject {
+            id: entry.id.clone(),
+            object: "model",
+            created: 0,
+            owned_by: "agenticos".to_string(),
+        })
+        .collect();
+    for provider in &snapshot.remote_providers {
+        data.extend(provider.models.iter().map(|model| ModelObject {
+            id: model.id.clone(),
+            object: "model",
+            created: 0,
+            owned_by: provider.backend_id.clone(),
+        }));
+    }
+    ModelList {
+        object: "list",
+        data,
+    }
+}
+
+/// Model named by the request, if any; the facade alias counts as none.
+pub(crate) fn requested_model(chat: &ChatCompletionRequest) -> Option<&str> {
+    chat.model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty() && *model != DEFAULT_MODEL_NAME)
+}
+
+/// EXEC `model` selector for a `GET /v1/models` id, if the catalog lists it.
+pub(crate) fn catalog_model_selector(
+    snapshot: &ModelCatalogSnapshot,
+    model: &str,
+) -> Option<String> {
+    if snapshot.models.iter().any(|entry| entry.id == model) {
+        return Some(model.to_string());
+    }
+    snapshot.remote_providers.iter().find_map(|provider| {
+        provider
+            .models
+            .iter()
+            .any(|entry| entry.id == model)
+            .then(|| format!("cloud:{}:{}", provider.id, model))
+    })
+}
+
+pub(crate) fn http_status_for_kernel_error(code: &str) -> u16 {
+    match code {
+        "AUTH_FAILED" | "AUTH_REQUIRED" => 401,
+        "MISSING_PROMPT" | "SEND_INPUT_INVALID" => 400,
+        "PID_NOT_FOUND" => 404,
+        "NO_MODEL"
+        | "LOAD_BUSY"
+        | "LOAD_FAILED"
+        | "SCHEDULER_LOAD_FAILED"
+        | "SCHEDULER_TARGET_FAILED" => 503,
+        "TIMEOUT" => 504,
+        _ => 502,
+    }
+}
+
+fn write_kernel_error(stream: &mut TcpStream, err: &KernelClientError) -> Result<(), String> {
+    let status = http_status_for_kernel_error(&err.code);
+    let kind = match status {
+        401 => "authentication_error",
+        400 | 404 => "invalid_request_error",
+        _ => "kernel_error",
+    };
+    write_error_response(stream, status, kind, Some(&err.code), &err.message)
+}
+
+fn unix_now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+#[path = "tests/chat.rs"]
+mod tests;
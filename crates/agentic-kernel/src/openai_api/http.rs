@@ -0,0 +1,39 @@
+use std::net::TcpStream;
+
+use crate::transport::http::write_all;
+pub(crate) use crate::transport::http::{
+    read_http_request, write_json_response, HttpReadLimits, HttpRequest,
+};
+
+use super::models::{ApiErrorBody, ApiErrorResponse};
+
+pub(crate) fn write_error_response(
+    stream: &mut TcpStream,
+    status_code: u16,
+    kind: &str,
+    code: Option<&str>,
+    message: &str,
+) -> Result<(), String> {
+    write_json_response(
+        stream,
+        status_code,
+        &ApiErrorResponse {
+            error: ApiErrorBody {
+                message: message.to_string(),
+                kind: kind.to_string(),
+                code: code.map(ToString::to_string),
+            },
+        },
+    )
+}
+
+pub(crate) fn write_sse_headers(stream: &mut TcpStream) -> Result<(), String> {
+    write_all(
+        stream,
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nCache-Control: no-cache\r\nConnection: close\r\n\r\n",
+    )
+}
+
+pub(crate) fn write_sse_data(stream: &mut TcpStream, data: &str) -> Result<(), String> {
+    write_all(stream, format!("data: {data}\n\n").as_bytes())
+}
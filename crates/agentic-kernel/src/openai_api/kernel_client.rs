@@ -0,0 +1,215 @@
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use agentic_control_models::{KernelEvent, KernelEventEnvelope};
+use agentic_protocol::{
+    encode_command, HelloRequest, OpCode, ProtocolEnvelope, PROTOCOL_VERSION_V1,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+const FACADE_AGENT_ID: &str = "openai_api";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct KernelClientError {
+    pub code: String,
+    pub message: String,
+}
+
+impl KernelClientError {
+    fn transport(message: impl Into<String>) -> Self {
+        Self {
+            code: "TRANSPORT".to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum KernelFrame {
+    Ok { code: String, payload: Vec<u8> },
+    Err { code: String, payload: Vec<u8> },
+    Data { kind: String, payload: Vec<u8> },
+}
+
+/// Blocking loopback connection to the kernel control plane.
+///
+/// The facade speaks the same TCP protocol as every other client so that
+/// requests go through AUTH, scheduler, quota and tool governance unchanged.
+pub(crate) struct KernelConnection {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    events: VecDeque<KernelEvent>,
+}
+
+impl KernelConnection {
+    pub(crate) fn connect(addr: SocketAddr, timeout: Duration) -> Result<Self, KernelClientError> {
+        let stream = TcpStream::connect_timeout(&addr, Duration::from_secs(5))
+            .map_err(|err| KernelClientError::transport(format!("kernel connect failed: {err}")))?;
+        stream
+            .set_read_timeout(Some(timeout))
+            .map_err(|err| KernelClientError::transport(err.to_string()))?;
+        let _ = stream.set_nodelay(true);
+        Ok(Self {
+            stream,
+            buffer: Vec::new(),
+            events: VecDeque::new(),
+        })
+    }
+
+    /// HELLO → AUTH (when a bearer token is provided) → SUBSCRIBE.
+    pub(crate) fn handshake(
+        &mut self,
+        bearer_token: Option<&str>,
+    ) -> Result<(), KernelClientError> {
+        let hello = HelloRequest {
+            supported_versions: vec![PROTOCOL_VERSION_V1.to_string()],
+            required_capabilities: vec!["event_stream_v1".to_string()],
+        };
+        let hello = serde_json::to_vec(&hello)
+            .map_err(|err| KernelClientError::transport(err.to_string()))?;
+        self.request::<Value>(OpCode::Hello, &hello)?;
+        if let Some(token) = bearer_token {
+            self.request::<Value>(OpCode::Auth, token.as_bytes())?;
+        }
+        self.request::<Value>(OpCode::Subscribe, b"")?;
+        Ok(())
+    }
+
+    pub(crate) fn request<T: DeserializeOwned>(
+        &mut self,
+        opcode: OpCode,
+        payload: &[u8],
+    ) -> Result<T, KernelClientError> {
+        let frame = encode_command(opcode, FACADE_AGENT_ID, payload)
+            .map_err(|err| KernelClientError::transport(err.to_string()))?;
+        self.stream
+            .write_all(&frame)
+            .and_then(|_| self.stream.flush())
+            .map_err(|err| KernelClientError::transport(format!("kernel write failed: {err}")))?;
+
+        loop {
+            match self.read_frame()? {
+                KernelFrame::Ok { payload, .. } => return decode_ok_payload(&payload),
+                KernelFrame::Err { code, payload } => {
+                    return Err(KernelClientError {
+                        code,
+                        message: decode_err_message(&payload),
+                    })
+                }
+                KernelFrame::Data { kind, payload } => self.queue_event(&kind, &payload),
+            }
+        }
+    }
+
+    pub(crate) fn next_event(&mut self) -> Result<KernelEvent, KernelClientError> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+            match self.read_frame()? {
+                KernelFrame::Data { kind, payload } => self.queue_event(&kind, &payload),
+                KernelFrame::Ok { .. } | KernelFrame::Err { .. } => {}
+            }
+        }
+    }
+
+    fn queue_event(&mut self, kind: &str, payload: &[u8]) {
+        if kind != "event" {
+            return;
+        }
+        match serde_json::from_slice::<KernelEventEnvelope>(payload) {
+            Ok(envelope) => self.events.push_back(envelope.event),
+            Err(err) => tracing::debug!(%err, "OPENAI_API: skipping undecodable kernel event"),
+        }
+    }
+
+    fn read_frame(&mut self) -> Result<KernelFrame, KernelClientError> {
+        let mut chunk = [0u8; 8192];
+        loop {
+            if let Some((frame, consumed)) = parse_frame(&self.buffer)? {
+                self.buffer.drain(..consumed);
+                return Ok(frame);
+            }
+            let read = self.stream.read(&mut chunk).map_err(|err| {
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut
+                ) {
+                    KernelClientError {
+                        code: "TIMEOUT".to_string(),
+                        message: "Timed out waiting for the kernel.".to_string(),
+                    }
+                } else {
+                    KernelClientError::transport(format!("kernel read failed: {err}"))
+                }
+            })?;
+            if read == 0 {
+                return Err(KernelClientError::transport(
+                    "kernel closed the control connection",
+                ));
+            }
+            self.buffer.extend_from_slice(&chunk[..read]);
+        }
+    }
+}
+
+/// Parse one `+OK|-ERR|DATA <code> <len>\r\n<payload>` frame from the buffer.
+pub(crate) fn parse_frame(
+    buffer: &[u8],
+) -> Result<Option<(KernelFrame, usize)>, KernelClientError> {
+    let Some(line_end) = buffer.windows(2).position(|window| window == b"\r\n") else {
+        return Ok(None);
+    };
+    let header = std::str::from_utf8(&buffer[..line_end])
+        .map_err(|err| KernelClientError::transport(format!("invalid frame header: {err}")))?;
+    let mut parts = header.split_whitespace();
+    let (Some(marker), Some(code), Some(len)) = (parts.next(), parts.next(), parts.next()) else {
+        return Err(KernelClientError::transport(format!(
+            "malformed frame header '{header}'"
+        )));
+    };
+    let len = len
+        .parse::<usize>()
+        .map_err(|_| KernelClientError::transport(format!("invalid frame length '{len}'")))?;
+    let body_start = line_end + 2;
+    if buffer.len() < body_start + len {
+        return Ok(None);
+    }
+    let payload = buffer[body_start..body_start + len].to_vec();
+    let code = code.to_string();
+    let frame = match marker {
+        "+OK" => KernelFrame::Ok { code, payload },
+        "-ERR" => KernelFrame::Err { code, payload },
+        "DATA" => KernelFrame::Data {
+            kind: code,
+            payload,
+        },
+        other => {
+            return Err(KernelClientError::transport(format!(
+                "unknown frame marker '{other}'"
+            )))
+        }
+    };
+    Ok(Some((frame, body_start + len)))
+}
+
+fn decode_ok_payload<T: DeserializeOwned>(payload: &[u8]) -> Result<T, KernelClientError> {
+    if let Ok(envelope) = serde_json::from_slice::<ProtocolEnvelope<Value>>(payload) {
+        return serde_json::from_value(envelope.data.unwrap_or(Value::Null)).map_err(|err| {
+            KernelClientError::transport(format!("unexpected kernel reply: {err}"))
+        });
+    }
+    serde_json::from_slice(payload)
+        .or_else(|_| serde_json::from_value(Value::String(String::from_utf8_lossy(payload).into())))
+        .map_err(|err| KernelClientError::transport(format!("unexpected kernel reply: {err}")))
+}
+
+fn decode_err_message(payload: &[u8]) -> String {
+    serde_json::from_slice::<ProtocolEnvelope<Value>>(payload)
+        .ok()
+        .and_then(|envelope| envelope.error.map(|error| error.message))
+        .unwrap_or_else(|| String::from_utf8_lossy(payload).trim().to_string())
+}
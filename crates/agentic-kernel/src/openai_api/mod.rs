@@ -0,0 +1,171 @@
+//! OpenAI-compatible HTTP facade over the kernel control plane.
+//!
+//! `/v1/chat/completions` is mapped onto `EXEC`/`SEND_INPUT` through a
+//! loopback protocol client, so facade traffic inherits the scheduler, quota,
+//! tool governance and accounting of every other kernel client.
+
+mod chat;
+mod http;
+mod kernel_client;
+mod models;
+
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use crate::config::KernelConfig;
+
+pub(crate) struct OpenAIApiRuntime {
+    listen_addr: SocketAddr,
+    shutdown_tx: Option<mpsc::Sender<()>>,
+    server_handle: Option<JoinHandle<()>>,
+}
+
+impl OpenAIApiRuntime {
+    pub(crate) fn start(
+        config: &KernelConfig,
+        kernel_addr: SocketAddr,
+    ) -> Result<Option<Self>, String> {
+        if !config.openai_api.enabled {
+            return Ok(None);
+        }
+
+        let host = config.openai_api.host.as_str();
+        let port = config.openai_api.port;
+        let listener = TcpListener::bind((host, port))
+            .map_err(|err| format!("Failed to bind OpenAI API facade on {host}:{port}: {err}"))?;
+        listener
+            .set_nonblocking(true)
+            .map_err(|err| format!("Failed to set OpenAI API listener non-blocking: {err}"))?;
+        let listen_addr = listener
+            .local_addr()
+            .map_err(|err| format!("Failed to query OpenAI API facade address: {err}"))?;
+        let (shutdown_tx, shutdown_rx) = mpsc::channel();
+        let options = FacadeOptions {
+            kernel_addr: loopback_kernel_addr(kernel_addr),
+            request_timeout: Duration::from_millis(config.openai_api.request_timeout_ms.max(1)),
+            max_request_bytes: config.openai_api.max_request_bytes,
+        };
+
+        let server_handle = thread::Builder::new()
+            .name("openai-api-facade".to_string())
+            .spawn(move || loop {
+                if shutdown_rx.try_recv().is_ok() {
+                    break;
+                }
+
+                match listener.accept() {
+                    Ok((stream, _)) => spawn_connection(stream, options),
+                    Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {
+                        thread::sleep(Duration::from_millis(20));
+                    }
+                    Err(err) => {
+                        tracing::warn!(%err, "OpenAI API facade accept failed");
+                        thread::sleep(Duration::from_millis(50));
+                    }
+                }
+            })
+            .map_err(|err| format!("Failed to start OpenAI API facade thread: {err}"))?;
+
+        Ok(Some(Self {
+            listen_addr,
+            shutdown_tx: Some(shutdown_tx),
+            server_handle: Some(server_handle),
+        }))
+    }
+
+    pub(crate) fn listen_addr(&self) -> SocketAddr {
+        self.listen_addr
+    }
+
+    pub(crate) fn shutdown(&mut self) {
+        if let Some(shutdown_tx) = self.shutdown_tx.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(handle) = self.server_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct FacadeOptions {
+    kernel_addr: SocketAddr,
+    request_timeout: Duration,
+    max_request_bytes: usize,
+}
+
+fn spawn_connection(mut stream: TcpStream, options: FacadeOptions) {
+    let spawned = thread::Builder::new()
+        .name("openai-api-request".to_string())
+        .spawn(move || {
+            if let Err(err) = handle_connection(&mut stream, options) {
+                tracing::debug!(%err, "OpenAI API facade request failed");
+            }
+        });
+    if let Err(err) = spawned {
+        tracing::warn!(%err, "OpenAI API facade could not spawn a request thread");
+    }
+}
+
+fn handle_connection(stream: &mut TcpStream, options: FacadeOptions) -> Result<(), String> {
+    stream
+        .set_nonblocking(false)
+        .map_err(|err| format!("Failed to configure OpenAI API connection: {err}"))?;
+    let limits = http::HttpReadLimits::with_max_body_bytes(options.max_request_bytes);
+    let request = match http::read_http_request(stream, limits) {
+        Ok(request) => request,
+        Err(err) => {
+            return http::write_error_response(
+                stream,
+                err.status_code,
+                "invalid_request_error",
+                None,
+                &err.message,
+            );
+        }
+    };
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("POST", "/v1/chat/completions") => chat::handle_chat_completion(
+            stream,
+            &request,
+            options.kernel_addr,
+            options.request_timeout,
+        ),
+        ("GET", "/v1/models") => chat::handle_list_models(
+            stream,
+            &request,
+            options.kernel_addr,
+            options.request_timeout,
+        ),
+        (_, "/v1/chat/completions") | (_, "/v1/models") => http::write_error_response(
+            stream,
+            405,
+            "invalid_request_error",
+            None,
+            "Method not allowed.",
+        ),
+        _ => http::write_error_response(
+            stream,
+            404,
+            "invalid_request_error",
+            None,
+            "Unknown OpenAI API route.",
+        ),
+    }
+}
+
+/// The kernel may listen on a wildcard address; the facade always dials loopback.
+fn loopback_kernel_addr(addr: SocketAddr) -> SocketAddr {
+    if addr.ip().is_unspecified() {
+        let loopback = match addr {
+            SocketAddr::V4(_) => std::net::IpAddr::V4(std::net::Ipv4Addr::LOCALHOST),
+            SocketAddr::V6(_) => std::net::IpAddr::V6(std::net::Ipv6Addr::LOCALHOST),
+        };
+        SocketAddr::new(loopback, addr.port())
+    } else {
+        addr
+    }
+}
@@ -0,0 +1,201 @@
+use agentic_control_models::{
+    AssistantSegmentKind, KernelEvent, ModelCatalogSnapshot, RemoteModelCatalogEntry,
+    RemoteProviderCatalogEntry,
+};
+use serde_json::json;
+
+use super::super::kernel_client::{parse_frame, KernelFrame};
+use super::super::models::{ChatCompletionRequest, ChatMessage};
+use super::{
+    apply_turn_event, catalog_model_selector, http_status_for_kernel_error, last_user_message,
+    model_list_from_catalog, render_exec_prompt, requested_model, usage_from_context, TurnStep,
+};
+
+fn message(role: &str, content: serde_json::Value) -> ChatMessage {
+    serde_json::from_value(json!({ "role": role, "content": content })).expect("chat message")
+}
+
+#[test]
+fn single_user_message_is_forwarded_verbatim() {
+    let prompt = render_exec_prompt(&[message("user", json!("ciao kernel"))]).expect("prompt");
+    assert_eq!(prompt, "ciao kernel");
+}
+
+#[test]
+fn history_is_rendered_as_system_plus_transcript() {
+    let prompt = render_exec_prompt(&[
+        message("system", json!("Rispondi in italiano.")),
+        message("user", json!("Chi sei?")),
+        message("assistant", json!("Un agente.")),
+        message(
+            "user",
+            json!([{ "type": "text", "text": "E cosa sai fare?" }, { "type": "image_url" }]),
+        ),
+    ])
+    .expect("prompt");
+
+    assert_eq!(
+        prompt,
+        "Rispondi in italiano.\n\nConversation so far:\nUser: Chi sei?\nAssistant: Un agente.\n\nE cosa sai fare?"
+    );
+}
+
+#[test]
+fn last_message_must_come_from_user() {
+    let err = last_user_message(&[
+        message("user", json!("ciao")),
+        message("assistant", json!("ciao!")),
+    ])
+    .expect_err("assistant tail must be rejected");
+    assert!(err.contains("role 'user'"));
+    assert!(render_exec_prompt(&[]).is_err());
+}
+
+#[test]
+fn turn_events_are_filtered_by_pid_and_kind() {
+    let delta = KernelEvent::TimelineSegment {
+        pid: 7,
+        segment_kind: AssistantSegmentKind::Message,
+        text: "hello".to_string(),
+    };
+    let thinking = KernelEvent::TimelineSegment {
+        pid: 7,
+        segment_kind: AssistantSegmentKind::Thinking,
+        text: "hmm".to_string(),
+    };
+    let other_pid = KernelEvent::TimelineSegment {
+        pid: 8,
+        segment_kind: AssistantSegmentKind::Message,
+        text: "nope".to_string(),
+    };
+    let finished = KernelEvent::SessionFinished {
+        pid: 7,
+        tokens_generated: Some(12),
+        elapsed_secs: Some(0.5),
+        reason: "awaiting_turn_decision".to_string(),
+    };
+
+    assert_eq!(apply_turn_event(7, &delta), TurnStep::Delta("hello"));
+    assert_eq!(apply_turn_event(7, &thinking), TurnStep::Ignore);
+    assert_eq!(apply_turn_event(7, &other_pid), TurnStep::Ignore);
+    assert_eq!(
+        apply_turn_event(7, &finished),
+        TurnStep::Finished {
+            finish_reason: "length",
+            completion_tokens: 12,
+        }
+    );
+}
+
+#[test]
+fn kernel_frames_are_parsed_incrementally() {
+    let mut buffer = b"+OK EXEC 5\r\n{\"a\"".to_vec();
+    assert_eq!(parse_frame(&buffer).expect("partial frame"), None);
+
+    buffer.extend_from_slice(b"}DATA event 2\r\n{}");
+    let (frame, consumed) = parse_frame(&buffer)
+        .expect("parse frame")
+        .expect("complete frame");
+    assert_eq!(
+        frame,
+        KernelFrame::Ok {
+            code: "EXEC".to_string(),
+            payload: b"{\"a\"}".to_vec(),
+        }
+    );
+    let (frame, _) = parse_frame(&buffer[consumed..])
+        .expect("parse data frame")
+        .expect("complete data frame");
+    assert_eq!(
+        frame,
+        KernelFrame::Data {
+            kind: "event".to_string(),
+            payload: b"{}".to_vec(),
+        }
+    );
+}
+
+#[test]
+fn usage_splits_the_context_into_prompt_and_completion() {
+    let usage = usage_from_context(1_200, 200);
+    assert_eq!(usage.prompt_tokens, 1_000);
+    assert_eq!(usage.completion_tokens, 200);
+    assert_eq!(usage.total_tokens, 1_200);
+
+    let compacted = usage_from_context(50, 200);
+    assert_eq!(compacted.prompt_tokens, 0);
+    assert_eq!(compacted.total_tokens, 200);
+}
+
+#[test]
+fn kernel_error_codes_map_to_http_statuses() {
+    assert_eq!(http_status_for_kernel_error("AUTH_REQUIRED"), 401);
+    assert_eq!(http_status_for_kernel_error("NO_MODEL"), 503);
+    assert_eq!(http_status_for_kernel_error("TIMEOUT"), 504);
+    assert_eq!(http_status_for_kernel_error("SPAWN_FAILED"), 502);
+}
+
+fn remote_catalog() -> ModelCatalogSnapshot {
+    ModelCatalogSnapshot {
+        selected_model_id: None,
+        total_models: 0,
+        models: Vec::new(),
+        routing_recommendations: Vec::new(),
+        remote_providers: vec![RemoteProviderCatalogEntry {
+            id: "openrouter".to_string(),
+            backend_id: "openrouter".to_string(),
+            adapter_kind: "openai_compatible".to_string(),
+            label: "OpenRouter".to_string(),
+            note: None,
+            credential_hint: None,
+            default_model_id: "qwen/qwen3-4b:free".to_string(),
+            models: vec![RemoteModelCatalogEntry {
+                id: "qwen/qwen3-4b:free".to_string(),
+                label: "Qwen3 4B".to_string(),
+                context_window_tokens: None,
+                max_output_tokens: None,
+                supports_structured_output: false,
+                input_price_usd_per_mtok: None,
+                output_price_usd_per_mtok: None,
+            }],
+        }],
+    }
+}
+
+#[test]
+fn model_list_includes_local_and_remote_models() {
+    let snapshot = remote_catalog();
+
+    let list = model_list_from_catalog(&snapshot);
+    assert_eq!(list.object, "list");
+    assert_eq!(list.data.len(), 1);
+    assert_eq!(list.data[0].id, "qwen/qwen3-4b:free");
+    assert_eq!(list.data[0].owned_by, "openrouter");
+}
+
+#[test]
+fn requested_model_must_be_listed_in_the_catalog() {
+    let chat = |model: serde_json::Value| -> ChatCompletionRequest {
+        serde_json::from_value(json!({
+            "model": model,
+            "messages": [{ "role": "user", "content": "ciao" }],
+        }))
+        .expect("chat request")
+    };
+
+    assert_eq!(requested_model(&chat(json!(null))), None);
+    assert_eq!(requested_model(&chat(json!(" "))), None);
+    assert_eq!(requested_model(&chat(json!("agenticos"))), None);
+    assert_eq!(
+        requested_model(&chat(json!("gpt-4o"))),
+        Some("gpt-4o"),
+        "a foreign name is checked, not silently echoed"
+    );
+
+    let snapshot = remote_catalog();
+    assert_eq!(
+        catalog_model_selector(&snapshot, "qwen/qwen3-4b:free").as_deref(),
+        Some("cloud:openrouter:qwen/qwen3-4b:free")
+    );
+    assert_eq!(catalog_model_selector(&snapshot, "gpt-4o"), None);
+}
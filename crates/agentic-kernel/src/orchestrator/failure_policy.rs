@@ -55,7 +55,13 @@ impl Orchestrator {
             }
         }
 
+        orch.finish_reported = false;
         for candidate in &reset_tasks {
+            self.lifecycle_events.push(task_changed_event(
+                orch_id,
+                candidate,
+                &TaskStatus::Pending,
+            ));
             orch.status.insert(candidate.clone(), TaskStatus::Pending);
             orch.running_output.remove(candidate);
             orch.latest_artifacts.remove(candidate);
@@ -90,9 +96,11 @@ impl Orchestrator {
 
             if has_failure && orch.failure_policy == FailurePolicy::FailFast {
                 kill_pids.extend(orch.running_pids());
-                for status in orch.status.values_mut() {
+                for (task_id, status) in orch.status.iter_mut() {
                     if matches!(status, TaskStatus::Pending | TaskStatus::Running { .. }) {
                         *status = TaskStatus::Skipped;
+                        self.lifecycle_events
+                            .push(task_changed_event(orch_id, task_id, status));
                     }
                 }
                 self.pid_to_task
@@ -117,6 +125,11 @@ impl Orchestrator {
                 });
                 if any_dep_failed {
                     orch.status.insert(task_id.clone(), TaskStatus::Skipped);
+                    self.lifecycle_events.push(task_changed_event(
+                        orch_id,
+                        task_id,
+                        &TaskStatus::Skipped,
+                    ));
                 }
             }
 
@@ -137,7 +150,7 @@ impl Orchestrator {
                     continue;
                 }
 
-                let input_artifacts = task
+                let mut input_artifacts = task
                     .deps
                     .iter()
                     .filter_map(|dep| orch.latest_artifacts.get(dep))
@@ -149,6 +162,9 @@ impl Orchestrator {
                         content_text: artifact.content_text.clone(),
                     })
                     .collect::<Vec<_>>();
+                if task.deps.is_empty() {
+                    input_artifacts.extend(orch.trigger_artifact.clone());
+                }
                 all_requests.push(
                     build_spawn_request(orch_id, owner_id, orch, task_id, &task, input_artifacts)
                         .expect("orchestration task permissions must be validated at registration"),
@@ -169,6 +185,11 @@ impl Orchestrator {
                 Some(TaskStatus::Running { pid, attempt }) => {
                     let output = orch.running_output.remove(&task_id);
                     orch.status.insert(task_id.clone(), TaskStatus::Skipped);
+                    self.lifecycle_events.push(task_changed_event(
+                        orch_id,
+                        &task_id,
+                        &TaskStatus::Skipped,
+                    ));
                     plan.kill_pids.push(pid);
                     plan.finalized_attempts.push(TaskAttemptFinalization {
                         orch_id,
@@ -185,6 +206,11 @@ impl Orchestrator {
                     });
                 }
                 Some(TaskStatus::Pending) => {
+                    self.lifecycle_events.push(task_changed_event(
+                        orch_id,
+                        &task_id,
+                        &TaskStatus::Skipped,
+                    ));
                     orch.status.insert(task_id, TaskStatus::Skipped);
                 }
                 _ => {}
@@ -7,6 +7,7 @@ impl Orchestrator {
             next_id: 1,
             pid_to_task: HashMap::new(),
             max_output_chars: crate::config::kernel_config().orchestrator.max_output_chars,
+            lifecycle_events: Vec::new(),
         }
     }
 
@@ -14,6 +15,17 @@ impl Orchestrator {
         &mut self,
         graph: TaskGraphDef,
         owner_id: usize,
+    ) -> Result<(u64, Vec<SpawnRequest>), OrchestratorError> {
+        self.register_triggered(graph, owner_id, None)
+    }
+
+    /// Register a workflow whose root tasks receive `trigger` as an input
+    /// artifact, on the first attempt and on every retry.
+    pub fn register_triggered(
+        &mut self,
+        graph: TaskGraphDef,
+        owner_id: usize,
+        trigger: Option<WorkflowTrigger>,
     ) -> Result<(u64, Vec<SpawnRequest>), OrchestratorError> {
         let topo_order = validate_and_sort(&graph.tasks)?;
 
@@ -33,6 +45,13 @@ impl Orchestrator {
 
         let mut orchestration =
             Orchestration::new(owner_id, graph.failure_policy, tasks, topo_order, status);
+        orchestration.trigger_artifact = trigger.map(|trigger| TaskInputArtifact {
+            artifact_id: trigger_artifact_id(orch_id),
+            producer_task_id: WORKFLOW_TRIGGER_TASK_ID.to_string(),
+            producer_attempt: 1,
+            mime_type: trigger.mime_type,
+            content_text: trigger.content_text,
+        });
         let root_ids = orchestration
             .topo_order
             .iter()
@@ -53,13 +72,14 @@ impl Orchestrator {
                 .get(task_id.as_str())
                 .expect("task must exist")
                 .clone();
+            let input_artifacts = orchestration.trigger_artifact.iter().cloned().collect();
             spawn_requests.push(build_spawn_request(
                 orch_id,
                 owner_id,
                 &mut orchestration,
                 &task_id,
                 &task,
-                Vec::new(),
+                input_artifacts,
             )?);
         }
 
@@ -129,6 +149,10 @@ impl Orchestrator {
     }
 }
 
+pub(crate) fn trigger_artifact_id(orch_id: u64) -> String {
+    format!("orch:{orch_id}:trigger")
+}
+
 pub(super) fn build_spawn_request(
     orch_id: u64,
     owner_id: usize,
@@ -149,6 +173,7 @@ pub(super) fn build_spawn_request(
         owner_id,
         context_policy: task.resolved_context_policy(),
         permission_overrides: task.permission_overrides()?,
+        quota_limits: task.quota_limits,
     })
 }
 
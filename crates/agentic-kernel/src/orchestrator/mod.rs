@@ -19,15 +19,18 @@ mod validation;
 
 use std::collections::HashMap;
 
+use agentic_control_models::KernelEvent;
+
 use crate::errors::OrchestratorError;
 use crate::policy::workload_from_label_or_default;
 
 use artifacts::refresh_output_metrics;
 use graph::build_spawn_request;
 use output::{append_with_cap, build_task_prompt};
+use transitions::task_changed_event;
 pub use types::{
     FailurePolicy, Orchestration, Orchestrator, RetryPlan, RunningTaskOutput, SpawnRequest,
     TaskArtifact, TaskAttemptFinalization, TaskGraphDef, TaskInputArtifact, TaskNodeDef,
-    TaskPidBinding, TaskStatus,
+    TaskPidBinding, TaskStatus, WorkflowTrigger, WORKFLOW_TRIGGER_TASK_ID,
 };
 use validation::validate_and_sort;
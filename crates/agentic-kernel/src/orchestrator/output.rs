@@ -1,4 +1,4 @@
-use super::{TaskInputArtifact, TaskNodeDef};
+use super::{TaskInputArtifact, TaskNodeDef, WORKFLOW_TRIGGER_TASK_ID};
 
 const TRUNCATION_MARKER: &str = "\n[TRUNCATED]\n";
 const WORKFLOW_TASK_CONTRACT: &str = "\
@@ -35,6 +35,14 @@ pub(crate) fn build_task_prompt(
             continue;
         }
 
+        if artifact.producer_task_id == WORKFLOW_TRIGGER_TASK_ID {
+            artifact_sections.push(format!(
+                "[Workflow trigger event | id={} | type={}]\n{}",
+                artifact.artifact_id, artifact.mime_type, artifact.content_text
+            ));
+            continue;
+        }
+
         artifact_sections.push(format!(
             "[Result artifact from task \"{}\" attempt {} | id={} | type={}]\n{}",
             artifact.producer_task_id,
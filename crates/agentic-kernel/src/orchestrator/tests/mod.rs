@@ -24,6 +24,7 @@ fn task_node(id: &str, prompt: &str, workload: Option<&str>, deps: Vec<&str>) ->
         allowed_tools: None,
         path_scopes: None,
         path_grants: None,
+        quota_limits: Default::default(),
         deps: deps.into_iter().map(str::to_string).collect(),
     }
 }
@@ -82,6 +83,7 @@ fn task_context_policy_overrides_are_preserved_in_spawn_requests() {
             allowed_tools: None,
             path_scopes: None,
             path_grants: None,
+            quota_limits: Default::default(),
             deps: vec![],
         }],
         failure_policy: FailurePolicy::FailFast,
@@ -117,6 +119,7 @@ fn task_backend_class_is_preserved_in_spawn_requests() {
             allowed_tools: None,
             path_scopes: None,
             path_grants: None,
+            quota_limits: Default::default(),
             deps: vec![],
         }],
         failure_policy: FailurePolicy::FailFast,
@@ -139,6 +142,38 @@ fn parallel_graph_spawns_single_root() {
     assert_eq!(spawns[0].task_id, "A");
 }
 
+#[test]
+fn trigger_artifact_is_an_input_of_root_tasks_only() {
+    let mut orch = Orchestrator::new();
+    let (id, spawns) = orch
+        .register_triggered(
+            make_linear_graph(),
+            1,
+            Some(WorkflowTrigger {
+                mime_type: "application/json".to_string(),
+                content_text: "{\"kind\":\"ipc_message\"}".to_string(),
+            }),
+        )
+        .expect("register");
+
+    assert_eq!(spawns.len(), 1);
+    let inputs = &spawns[0].input_artifacts;
+    assert_eq!(inputs.len(), 1);
+    assert_eq!(inputs[0].producer_task_id, WORKFLOW_TRIGGER_TASK_ID);
+    assert!(spawns[0].prompt.contains("Workflow trigger event"));
+    assert!(spawns[0].prompt.contains("ipc_message"));
+
+    let pid_a = 100;
+    orch.register_pid(pid_a, id, &spawns[0].task_id, spawns[0].attempt);
+    orch.mark_completed(pid_a, None);
+    let (ready, _) = orch.advance();
+    assert_eq!(ready.len(), 1);
+    assert!(ready[0]
+        .input_artifacts
+        .iter()
+        .all(|artifact| artifact.producer_task_id != WORKFLOW_TRIGGER_TASK_ID));
+}
+
 #[test]
 fn linear_graph_advances_step_by_step() {
     let mut orch = Orchestrator::new();
@@ -289,6 +324,45 @@ fn fail_fast_skips_pending_on_failure() {
     assert!(orch_state.is_finished());
 }
 
+#[test]
+fn lifecycle_events_report_task_transitions_and_finish_once() {
+    let mut orch = Orchestrator::new();
+    let (id, spawns) = orch.register(make_linear_graph(), 1).unwrap();
+
+    orch.register_pid(100, id, &spawns[0].task_id, spawns[0].attempt);
+    orch.mark_failed(100, "process error", None);
+    orch.advance();
+
+    let events = orch.take_lifecycle_events();
+    let transitions = events
+        .iter()
+        .filter_map(|event| match event {
+            agentic_control_models::KernelEvent::WorkflowTaskChanged {
+                orchestration_id,
+                task_id,
+                status,
+                ..
+            } if *orchestration_id == id => Some((task_id.clone(), status.clone())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(transitions[0], ("A".to_string(), "running".to_string()));
+    assert_eq!(transitions[1], ("A".to_string(), "failed".to_string()));
+    assert!(transitions.contains(&("B".to_string(), "skipped".to_string())));
+    assert!(transitions.contains(&("C".to_string(), "skipped".to_string())));
+    assert!(matches!(
+        events.last(),
+        Some(agentic_control_models::KernelEvent::WorkflowFinished {
+            status,
+            failed: 1,
+            skipped: 2,
+            ..
+        }) if status == "failed"
+    ));
+
+    assert!(orch.take_lifecycle_events().is_empty());
+}
+
 #[test]
 fn fail_fast_kills_running_tasks() {
     let mut orch = Orchestrator::new();
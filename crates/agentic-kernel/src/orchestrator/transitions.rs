@@ -5,8 +5,10 @@ impl Orchestrator {
         self.pid_to_task
             .insert(pid, (orch_id, task_id.to_string(), attempt));
         if let Some(orch) = self.orchestrations.get_mut(&orch_id) {
-            orch.status
-                .insert(task_id.to_string(), TaskStatus::Running { pid, attempt });
+            let status = TaskStatus::Running { pid, attempt };
+            self.lifecycle_events
+                .push(task_changed_event(orch_id, task_id, &status));
+            orch.status.insert(task_id.to_string(), status);
             orch.running_output.insert(
                 task_id.to_string(),
                 RunningTaskOutput {
@@ -38,13 +40,13 @@ impl Orchestrator {
         let orch = self.orchestrations.get_mut(&orch_id)?;
         orch.running_output.remove(task_id);
         orch.latest_artifacts.remove(task_id);
-        orch.status.insert(
-            task_id.to_string(),
-            TaskStatus::Failed {
-                error: error.to_string(),
-                attempt,
-            },
-        );
+        let status = TaskStatus::Failed {
+            error: error.to_string(),
+            attempt,
+        };
+        self.lifecycle_events
+            .push(task_changed_event(orch_id, task_id, &status));
+        orch.status.insert(task_id.to_string(), status);
         refresh_output_metrics(orch);
         Some(TaskAttemptFinalization {
             orch_id,
@@ -70,8 +72,10 @@ impl Orchestrator {
         let (orch_id, task_id, attempt) = self.pid_to_task.remove(&pid)?;
         let orch = self.orchestrations.get_mut(&orch_id)?;
         let output = orch.running_output.remove(&task_id);
-        orch.status
-            .insert(task_id.clone(), TaskStatus::Completed { attempt });
+        let status = TaskStatus::Completed { attempt };
+        self.lifecycle_events
+            .push(task_changed_event(orch_id, &task_id, &status));
+        orch.status.insert(task_id.clone(), status);
         refresh_output_metrics(orch);
         Some(TaskAttemptFinalization {
             orch_id,
@@ -97,13 +101,13 @@ impl Orchestrator {
         let (orch_id, task_id, attempt) = self.pid_to_task.remove(&pid)?;
         let orch = self.orchestrations.get_mut(&orch_id)?;
         let output = orch.running_output.remove(&task_id);
-        orch.status.insert(
-            task_id.clone(),
-            TaskStatus::Failed {
-                error: error.to_string(),
-                attempt,
-            },
-        );
+        let status = TaskStatus::Failed {
+            error: error.to_string(),
+            attempt,
+        };
+        self.lifecycle_events
+            .push(task_changed_event(orch_id, &task_id, &status));
+        orch.status.insert(task_id.clone(), status);
         refresh_output_metrics(orch);
         Some(TaskAttemptFinalization {
             orch_id,
@@ -122,9 +126,53 @@ impl Orchestrator {
         })
     }
 
+    /// Drains the task transitions recorded since the last call and appends a
+    /// `WorkflowFinished` event for every orchestration that reached a
+    /// terminal state and has not been reported yet.
+    pub(crate) fn take_lifecycle_events(&mut self) -> Vec<KernelEvent> {
+        let mut events = std::mem::take(&mut self.lifecycle_events);
+        let mut orch_ids = self.orchestrations.keys().copied().collect::<Vec<_>>();
+        orch_ids.sort_unstable();
+        for orch_id in orch_ids {
+            let Some(orch) = self.orchestrations.get_mut(&orch_id) else {
+                continue;
+            };
+            if orch.finish_reported || !orch.is_finished() {
+                continue;
+            }
+            orch.finish_reported = true;
+            let (_, _, completed, failed, skipped) = orch.counts();
+            events.push(KernelEvent::WorkflowFinished {
+                orchestration_id: orch_id,
+                status: if failed > 0 { "failed" } else { "completed" }.to_string(),
+                completed,
+                failed,
+                skipped,
+            });
+        }
+        events
+    }
+
     pub fn remove(&mut self, orch_id: u64) -> bool {
         self.pid_to_task
             .retain(|_, (existing_orch_id, _, _)| *existing_orch_id != orch_id);
         self.orchestrations.remove(&orch_id).is_some()
     }
 }
+
+pub(super) fn task_changed_event(orch_id: u64, task_id: &str, status: &TaskStatus) -> KernelEvent {
+    let (attempt, pid, error) = match status {
+        TaskStatus::Pending | TaskStatus::Skipped => (None, None, None),
+        TaskStatus::Running { pid, attempt } => (Some(*attempt), Some(*pid), None),
+        TaskStatus::Completed { attempt } => (Some(*attempt), None, None),
+        TaskStatus::Failed { error, attempt } => (Some(*attempt), None, Some(error.clone())),
+    };
+    KernelEvent::WorkflowTaskChanged {
+        orchestration_id: orch_id,
+        task_id: task_id.to_string(),
+        status: status.label().to_string(),
+        attempt,
+        pid,
+        error,
+    }
+}
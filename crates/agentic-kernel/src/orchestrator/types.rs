@@ -1,3 +1,4 @@
+use agentic_control_models::KernelEvent;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::time::Instant;
@@ -6,6 +7,7 @@ use crate::backend::BackendClass;
 use crate::errors::OrchestratorError;
 use crate::model_catalog::WorkloadClass;
 use crate::process::{ContextPolicy, ContextStrategy};
+use crate::scheduler::{ProcessQuota, QuotaLimits};
 use crate::tools::invocation::{ProcessPathGrant, ProcessPermissionOverrides, ProcessTrustScope};
 
 /// Failure policy for an orchestration.
@@ -53,6 +55,9 @@ pub struct TaskNodeDef {
     pub path_scopes: Option<Vec<String>>,
     #[serde(default)]
     pub path_grants: Option<Vec<ProcessPathGrant>>,
+    /// Wall-clock, spend, remote-input and tool-time limits of the task process.
+    #[serde(flatten)]
+    pub quota_limits: QuotaLimits,
     #[serde(default)]
     pub deps: Vec<String>,
 }
@@ -158,6 +163,17 @@ pub struct TaskInputArtifact {
     pub content_text: String,
 }
 
+/// Producer id used for the trigger artifact handed to root tasks.
+pub const WORKFLOW_TRIGGER_TASK_ID: &str = "@trigger";
+
+/// Event payload that started a workflow (file change, upstream completion,
+/// IPC message). Root tasks receive it as an input artifact.
+#[derive(Debug, Clone)]
+pub struct WorkflowTrigger {
+    pub mime_type: String,
+    pub content_text: String,
+}
+
 #[derive(Debug, Clone)]
 pub struct RunningTaskOutput {
     pub attempt: u32,
@@ -197,11 +213,13 @@ pub struct Orchestration {
     pub topo_order: Vec<String>,
     pub status: HashMap<String, TaskStatus>,
     pub latest_artifacts: HashMap<String, TaskArtifact>,
+    pub trigger_artifact: Option<TaskInputArtifact>,
     pub running_output: HashMap<String, RunningTaskOutput>,
     pub next_attempt: HashMap<String, u32>,
     pub truncated_outputs: usize,
     pub output_chars_stored: usize,
     pub created_at: Instant,
+    pub(crate) finish_reported: bool,
 }
 
 impl Orchestration {
@@ -219,11 +237,13 @@ impl Orchestration {
             topo_order,
             status,
             latest_artifacts: HashMap::new(),
+            trigger_artifact: None,
             running_output: HashMap::new(),
             next_attempt: HashMap::new(),
             truncated_outputs: 0,
             output_chars_stored: 0,
             created_at: Instant::now(),
+            finish_reported: false,
         }
     }
 
@@ -270,6 +290,17 @@ pub struct SpawnRequest {
     pub owner_id: usize,
     pub context_policy: ContextPolicy,
     pub permission_overrides: ProcessPermissionOverrides,
+    pub quota_limits: QuotaLimits,
+}
+
+impl SpawnRequest {
+    /// Scheduler quota for the task process when the task sets any limit.
+    pub fn quota_override(&self) -> Option<ProcessQuota> {
+        (!self.quota_limits.is_empty()).then(|| ProcessQuota {
+            limits: self.quota_limits,
+            ..ProcessQuota::defaults_for(self.workload)
+        })
+    }
 }
 
 /// Manages all active orchestrations.
@@ -278,4 +309,5 @@ pub struct Orchestrator {
     pub(crate) next_id: u64,
     pub(crate) pid_to_task: HashMap<u64, (u64, String, u32)>,
     pub(crate) max_output_chars: usize,
+    pub(crate) lifecycle_events: Vec<KernelEvent>,
 }
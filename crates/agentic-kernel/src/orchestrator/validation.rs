@@ -18,6 +18,12 @@ pub(crate) fn validate_and_sort(tasks: &[TaskNodeDef]) -> Result<Vec<String>, Or
 
     let task_ids: HashSet<&str> = tasks.iter().map(|task| task.id.as_str()).collect();
     for task in tasks {
+        task.quota_limits
+            .validate()
+            .map_err(|reason| OrchestratorError::InvalidTaskQuota {
+                task: task.id.clone(),
+                reason,
+            })?;
         if task.deps.iter().any(|dep| dep == &task.id) {
             return Err(OrchestratorError::SelfDependency(task.id.clone()));
         }
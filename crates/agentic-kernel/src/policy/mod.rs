@@ -116,6 +116,31 @@ pub fn scheduler_quota_defaults(workload: WorkloadClass) -> (usize, usize) {
     (quota.max_tokens, quota.max_syscalls)
 }
 
+pub fn scheduler_quota_limit_defaults(workload: WorkloadClass) -> crate::scheduler::QuotaLimits {
+    let scheduler = &crate::config::kernel_config().scheduler;
+    let quota = match workload {
+        WorkloadClass::Fast => scheduler.fast,
+        WorkloadClass::Code => scheduler.code,
+        WorkloadClass::Reasoning => scheduler.reasoning,
+        WorkloadClass::General => scheduler.general,
+    };
+
+    crate::scheduler::QuotaLimits {
+        max_wall_clock_secs: quota.max_wall_clock_secs,
+        max_cost_usd: quota.max_cost_usd,
+        max_remote_input_tokens: quota.max_remote_input_tokens,
+        max_tool_wall_ms: quota.max_tool_wall_ms,
+    }
+}
+
+/// Share of a quota limit at which the process receives its soft warning.
+pub fn quota_warning_ratio() -> f64 {
+    crate::config::kernel_config()
+        .scheduler
+        .quota_warning_ratio
+        .clamp(0.0, 1.0)
+}
+
 #[cfg(test)]
 #[path = "tests/mod.rs"]
 mod tests;
@@ -108,6 +108,37 @@ impl ContextPolicy {
         adjusted
     }
 
+    /// Applica una modifica a runtime (`SET_CONTEXT_POLICY`). Se cambia solo la
+    /// finestra, trigger e target vengono riscalati come allo spawn; i campi
+    /// espliciti vincono e vengono riportati nei limiti dalla clamp di `new`.
+    pub fn with_update(&self, update: &ContextPolicyUpdate) -> Self {
+        let strategy = update.strategy.unwrap_or(self.strategy);
+        let window_size_tokens = update.window_size_tokens.unwrap_or(self.window_size_tokens);
+        let retrieve_top_k = update.retrieve_top_k.unwrap_or(self.retrieve_top_k);
+        let mut updated = if update.window_size_tokens.is_some()
+            && update.compaction_trigger_tokens.is_none()
+            && update.compaction_target_tokens.is_none()
+        {
+            Self::new_with_window(strategy, window_size_tokens, retrieve_top_k)
+        } else {
+            Self::new(
+                strategy,
+                window_size_tokens,
+                update
+                    .compaction_trigger_tokens
+                    .unwrap_or(self.compaction_trigger_tokens),
+                update
+                    .compaction_target_tokens
+                    .unwrap_or(self.compaction_target_tokens),
+                retrieve_top_k,
+            )
+        };
+        updated.retrieve_candidate_limit = self.retrieve_candidate_limit.max(retrieve_top_k);
+        updated.retrieve_max_segment_chars = self.retrieve_max_segment_chars;
+        updated.retrieve_min_score = self.retrieve_min_score;
+        updated
+    }
+
     pub fn new(
         strategy: ContextStrategy,
         window_size_tokens: usize,
@@ -145,6 +176,22 @@ impl Default for ContextPolicy {
     }
 }
 
+/// Campi opzionali di `SET_CONTEXT_POLICY`; quelli assenti restano invariati.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContextPolicyUpdate {
+    pub strategy: Option<ContextStrategy>,
+    pub window_size_tokens: Option<usize>,
+    pub compaction_trigger_tokens: Option<usize>,
+    pub compaction_target_tokens: Option<usize>,
+    pub retrieve_top_k: Option<usize>,
+}
+
+impl ContextPolicyUpdate {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
 fn scaled_compaction_thresholds(window_size_tokens: usize) -> (usize, usize) {
     let config = &crate::config::kernel_config().context;
     let default_window = config.default_window_tokens.max(1) as f64;
@@ -194,6 +241,9 @@ pub struct ContextSegment {
     pub kind: ContextSegmentKind,
     pub token_count: usize,
     pub text: String,
+    /// Pinned segments survive every compaction strategy and cannot be dropped.
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 impl ContextSegment {
@@ -202,8 +252,24 @@ impl ContextSegment {
             kind,
             token_count,
             text,
+            pinned: false,
         }
     }
+
+    pub(crate) fn with_pinned(mut self, pinned: bool) -> Self {
+        self.pinned = pinned;
+        self
+    }
+}
+
+/// Vista leggera di un segmento per lo status: l'indice è quello accettato da
+/// `PIN_SEGMENT` e `DROP_SEGMENT`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextSegmentSummary {
+    pub index: usize,
+    pub kind: String,
+    pub token_count: usize,
+    pub pinned: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -222,6 +288,9 @@ pub struct ContextState {
     pub last_retrieval_top_score: Option<f64>,
     pub last_compaction_reason: Option<String>,
     pub last_summary_ts: Option<String>,
+    pub policy_updates: u64,
+    pub manual_compactions: u64,
+    pub dropped_segments: u64,
     pub segments: Vec<ContextSegment>,
     pub episodic_segments: Vec<ContextSegment>,
 }
@@ -233,6 +302,11 @@ pub struct ContextCompactionEvent {
     pub dropped_tokens: usize,
     pub tokens_after: usize,
     pub reason: String,
+    /// Who wrote the summary segment (`process_model`, `model:<selector>` or
+    /// `heuristic`); `None` for strategies that do not summarize.
+    pub summarizer: Option<String>,
+    /// Why a model summary was abandoned for the heuristic digest.
+    pub summary_fallback: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -240,6 +314,8 @@ pub struct ContextStatusSnapshot {
     pub context_strategy: String,
     pub context_tokens_used: usize,
     pub context_window_size: usize,
+    pub compaction_trigger_tokens: usize,
+    pub compaction_target_tokens: usize,
     pub context_compressions: u64,
     pub context_retrieval_hits: u64,
     pub context_retrieval_requests: u64,
@@ -259,6 +335,12 @@ pub struct ContextStatusSnapshot {
     pub retrieve_candidate_limit: usize,
     pub retrieve_max_segment_chars: usize,
     pub retrieve_min_score: f64,
+    pub pinned_segments: usize,
+    pub pinned_tokens: usize,
+    pub policy_updates: u64,
+    pub manual_compactions: u64,
+    pub dropped_segments: u64,
+    pub segments: Vec<ContextSegmentSummary>,
 }
 
 impl ContextStatusSnapshot {
@@ -268,10 +350,18 @@ impl ContextStatusSnapshot {
             .iter()
             .map(|segment| segment.token_count)
             .sum();
+        let pinned_tokens = state
+            .segments
+            .iter()
+            .filter(|segment| segment.pinned)
+            .map(|segment| segment.token_count)
+            .sum();
         Self {
             context_strategy: policy.strategy.label().to_string(),
             context_tokens_used: state.tokens_used,
             context_window_size: policy.window_size_tokens,
+            compaction_trigger_tokens: policy.compaction_trigger_tokens,
+            compaction_target_tokens: policy.compaction_target_tokens,
             context_compressions: state.context_compressions,
             context_retrieval_hits: state.context_retrieval_hits,
             context_retrieval_requests: state.context_retrieval_requests,
@@ -291,6 +381,26 @@ impl ContextStatusSnapshot {
             retrieve_candidate_limit: policy.retrieve_candidate_limit,
             retrieve_max_segment_chars: policy.retrieve_max_segment_chars,
             retrieve_min_score: policy.retrieve_min_score,
+            pinned_segments: state
+                .segments
+                .iter()
+                .filter(|segment| segment.pinned)
+                .count(),
+            pinned_tokens,
+            policy_updates: state.policy_updates,
+            manual_compactions: state.manual_compactions,
+            dropped_segments: state.dropped_segments,
+            segments: state
+                .segments
+                .iter()
+                .enumerate()
+                .map(|(index, segment)| ContextSegmentSummary {
+                    index,
+                    kind: segment.kind.label().to_string(),
+                    token_count: segment.token_count,
+                    pinned: segment.pinned,
+                })
+                .collect(),
         }
     }
 }
@@ -16,7 +16,10 @@ mod model;
 mod quota;
 mod registry;
 mod retrieval;
+mod services;
 mod states;
+mod summarizer;
+mod vectors;
 
 #[cfg(test)]
 #[path = "tests/mod.rs"]
@@ -24,4 +27,8 @@ mod tests;
 
 pub use context::*;
 pub use model::*;
+pub use services::ContextServices;
 pub use states::*;
+
+pub(crate) use retrieval::normalized_terms;
+pub(crate) use vectors::SegmentVectorIndex;
@@ -3,8 +3,13 @@ use std::path::{Path, PathBuf};
 use tokenizers::Tokenizer;
 
 use super::context::*;
-use super::retrieval::rank_retrieval_candidates;
+use super::retrieval::{
+    rank_retrieval_candidates, retrieval_candidate_excerpts, retrieval_query_excerpt, VectorScores,
+};
+use super::services::{retrieval_needs_worker, summaries_need_worker, ContextServices};
 use super::states::*;
+use super::summarizer::{ContextSummarizer, SUMMARY_SEGMENT_HEADER};
+use super::vectors::SegmentVectorIndex;
 use crate::backend::RuntimeModel;
 use crate::memory::ContextSlotId;
 use crate::prompting::GenerationConfig;
@@ -33,6 +38,7 @@ pub struct AgentProcess {
     pub termination_reason: Option<String>,
     rendered_prompt_cache: String,
     resident_prompt_checkpoint_bytes: usize,
+    segment_vectors: SegmentVectorIndex,
 }
 
 impl AgentProcess {
@@ -83,6 +89,9 @@ impl AgentProcess {
                 last_retrieval_top_score: None,
                 last_compaction_reason: None,
                 last_summary_ts: None,
+                policy_updates: 0,
+                manual_compactions: 0,
+                dropped_segments: 0,
                 segments: vec![ContextSegment::new(
                     ContextSegmentKind::UserTurn,
                     initial_token_count,
@@ -94,6 +103,12 @@ impl AgentProcess {
             termination_reason: None,
             rendered_prompt_cache: initial_segment_text,
             resident_prompt_checkpoint_bytes: 0,
+            segment_vectors: SegmentVectorIndex::new(
+                crate::config::kernel_config()
+                    .context
+                    .embeddings
+                    .max_cached_vectors,
+            ),
         }
     }
 
@@ -115,6 +130,27 @@ impl AgentProcess {
         );
     }
 
+    /// Put `text` in front of the context as a `RetrievedMemory` segment, where
+    /// the Retrieve strategy keeps its payload. Meant for processes that have
+    /// not run yet: the prompt is rebuilt from the first token.
+    pub fn prepend_retrieved_memory(&mut self, text: &str, tokens: Vec<u32>) {
+        if text.is_empty() && tokens.is_empty() {
+            return;
+        }
+        self.context_state.segments.insert(
+            0,
+            ContextSegment::new(
+                ContextSegmentKind::RetrievedMemory,
+                tokens.len(),
+                text.to_string(),
+            ),
+        );
+        self.tokens.splice(0..0, tokens);
+        self.index_pos = 0;
+        self.context_state.tokens_used = self.tokens.len();
+        self.rebuild_rendered_prompt_cache();
+    }
+
     pub fn context_status_snapshot(&self) -> ContextStatusSnapshot {
         ContextStatusSnapshot::from_parts(&self.context_policy, &self.context_state)
     }
@@ -123,7 +159,6 @@ impl AgentProcess {
         &self.rendered_prompt_cache
     }
 
-    #[cfg_attr(not(test), allow(dead_code))]
     pub fn inference_prompt_text_with_continuation(&self, continuation: &str) -> String {
         if continuation.is_empty() {
             return self.rendered_prompt_cache.clone();
@@ -152,7 +187,6 @@ impl AgentProcess {
         &self.rendered_prompt_cache[start..]
     }
 
-    #[cfg_attr(not(test), allow(dead_code))]
     pub fn pending_inference_prompt_suffix_with_continuation(&self, continuation: &str) -> String {
         let checkpoint = self
             .resident_prompt_checkpoint_bytes
@@ -283,20 +317,245 @@ impl AgentProcess {
     pub fn abandon_current_turn(&mut self) {}
 
     pub fn enforce_context_budget(&mut self) -> Option<ContextCompactionEvent> {
+        self.enforce_context_budget_with(None)
+    }
+
+    /// Whether the pending compaction must run on the inference worker, where
+    /// the configured summarizer or embedding provider can be called without
+    /// blocking the event loop.
+    pub fn defers_compaction_to_worker(&self) -> bool {
+        if self.tokens.len() <= self.context_policy.compaction_trigger_tokens {
+            return false;
+        }
+        let config = crate::config::kernel_config();
+        match self.context_policy.strategy {
+            ContextStrategy::Summarize => summaries_need_worker(config),
+            ContextStrategy::Retrieve => retrieval_needs_worker(config),
+            ContextStrategy::SlidingWindow => false,
+        }
+    }
+
+    /// Same as [`Self::enforce_context_budget`], but the `summarize` strategy
+    /// drafts its summary through the services' summarizer and `retrieve`
+    /// blends embedding similarity into the lexical ranking; both keep the
+    /// heuristic path as a fallback.
+    pub fn enforce_context_budget_with(
+        &mut self,
+        services: Option<&mut ContextServices>,
+    ) -> Option<ContextCompactionEvent> {
         self.context_state.tokens_used = self.tokens.len();
 
         if self.context_state.tokens_used <= self.context_policy.compaction_trigger_tokens {
             return None;
         }
 
+        self.run_compaction_strategy(services)
+    }
+
+    /// `COMPACT_NOW`: compatta subito, senza aspettare il trigger, fino a
+    /// `target_tokens` (o al target della policy). Gira sul loop degli eventi,
+    /// quindi usa sempre il percorso euristico delle strategie.
+    pub fn compact_now(&mut self, target_tokens: Option<usize>) -> Option<ContextCompactionEvent> {
+        self.context_state.tokens_used = self.tokens.len();
+        let policy_target = self.context_policy.compaction_target_tokens;
+        let target = target_tokens.unwrap_or(policy_target).max(1);
+        if self.context_state.tokens_used <= target {
+            self.context_state.last_compaction_reason =
+                Some(format!("manual_compaction_within_target={target}"));
+            return None;
+        }
+
+        self.context_policy.compaction_target_tokens = target;
+        let event = self.run_compaction_strategy(None);
+        self.context_policy.compaction_target_tokens = policy_target;
+        let mut event = event?;
+        event.reason = format!("manual {}", event.reason);
+        self.context_state.manual_compactions += 1;
+        self.context_state.last_compaction_reason = Some(event.reason.clone());
+        Some(event)
+    }
+
+    /// `SET_CONTEXT_POLICY`: la nuova policy vale dal prossimo step, dove il
+    /// controllo del trigger decide se compattare.
+    pub fn set_context_policy(&mut self, policy: ContextPolicy) {
+        self.context_policy = policy;
+        self.context_state.policy_updates += 1;
+    }
+
+    pub fn pin_segment(&mut self, index: usize, pinned: bool) -> Result<&ContextSegment, String> {
+        let segment_count = self.context_state.segments.len();
+        let segment =
+            self.context_state.segments.get_mut(index).ok_or_else(|| {
+                format!("segment {index} out of range ({segment_count} segments)")
+            })?;
+        segment.pinned = pinned;
+        Ok(segment)
+    }
+
+    /// `DROP_SEGMENT`: toglie un singolo segmento non pinnato dal prompt e dai
+    /// token, come farebbe una compattazione, e resetta lo slot del backend.
+    pub fn drop_segment(&mut self, index: usize) -> Result<ContextSegment, String> {
+        let segment_count = self.context_state.segments.len();
+        let segment =
+            self.context_state.segments.get(index).ok_or_else(|| {
+                format!("segment {index} out of range ({segment_count} segments)")
+            })?;
+        if segment.pinned {
+            return Err(format!("segment {index} is pinned; unpin it first"));
+        }
+        if !self.segments_aligned_with_tokens() {
+            return Err("context segments are not aligned with the token buffer".to_string());
+        }
+
+        let start: usize = self.context_state.segments[..index]
+            .iter()
+            .map(|segment| segment.token_count)
+            .sum();
+        let end = start + segment.token_count;
+        self.reset_backend_context_slot_for_replay("drop_segment")?;
+
+        let removed = self.context_state.segments.remove(index);
+        self.tokens.drain(start..end);
+        if self.turn_start_index >= end {
+            self.turn_start_index -= removed.token_count;
+        } else if self.turn_start_index > start {
+            self.turn_start_index = start;
+        }
+        self.index_pos = 0;
+        self.context_state.tokens_used = self.tokens.len();
+        self.context_state.dropped_segments += 1;
+        self.context_state.last_compaction_reason = Some(format!(
+            "manual_drop_segment index={} kind={} dropped_tokens={}",
+            index,
+            removed.kind.label(),
+            removed.token_count
+        ));
+        self.rebuild_rendered_prompt_cache();
+        Ok(removed)
+    }
+
+    fn segments_aligned_with_tokens(&self) -> bool {
+        self.context_state
+            .segments
+            .iter()
+            .map(|segment| segment.token_count)
+            .sum::<usize>()
+            == self.tokens.len()
+    }
+
+    fn run_compaction_strategy(
+        &mut self,
+        services: Option<&mut ContextServices>,
+    ) -> Option<ContextCompactionEvent> {
+        if self
+            .context_state
+            .segments
+            .iter()
+            .any(|segment| segment.pinned)
+        {
+            return self.compact_around_pinned_segments(services);
+        }
+
         match self.context_policy.strategy {
             ContextStrategy::SlidingWindow => self.enforce_sliding_window_budget(),
-            ContextStrategy::Summarize => self.enforce_summary_budget(),
-            ContextStrategy::Retrieve => self.enforce_retrieve_budget(),
+            ContextStrategy::Summarize => {
+                self.enforce_summary_budget(services.map(|services| &mut services.summarizer))
+            }
+            ContextStrategy::Retrieve => self.enforce_retrieve_budget(services),
+        }
+    }
+
+    /// I segmenti pinnati vengono messi da parte, la strategia lavora solo sul
+    /// resto con finestra e target ridotti della loro quota, e al termine i
+    /// pinnati tornano nella loro posizione originale tra i sopravvissuti.
+    fn compact_around_pinned_segments(
+        &mut self,
+        services: Option<&mut ContextServices>,
+    ) -> Option<ContextCompactionEvent> {
+        if !self.segments_aligned_with_tokens() {
+            self.context_state.last_compaction_reason =
+                Some("pinned_segments_unaligned".to_string());
+            return None;
+        }
+
+        let original_segments = self.context_state.segments.clone();
+        let original_tokens = self.tokens.clone();
+        let mut pinned_segments = Vec::new();
+        let mut pinned_tokens = Vec::new();
+        let mut unpinned_segments = Vec::new();
+        let mut unpinned_tokens = Vec::new();
+        let mut offset = 0usize;
+        for segment in original_segments.iter().cloned() {
+            let end = offset + segment.token_count;
+            if segment.pinned {
+                pinned_tokens.extend_from_slice(&original_tokens[offset..end]);
+                pinned_segments.push(segment);
+            } else {
+                unpinned_tokens.extend_from_slice(&original_tokens[offset..end]);
+                unpinned_segments.push(segment);
+            }
+            offset = end;
+        }
+        if unpinned_segments.is_empty() {
+            self.context_state.last_compaction_reason = Some("all_segments_pinned".to_string());
+            return None;
         }
+
+        let policy = self.context_policy.clone();
+        let reserved = pinned_tokens.len();
+        self.context_policy.window_size_tokens =
+            policy.window_size_tokens.saturating_sub(reserved).max(1);
+        self.context_policy.compaction_target_tokens = policy
+            .compaction_target_tokens
+            .saturating_sub(reserved)
+            .max(1);
+        self.context_state.segments = unpinned_segments;
+        self.tokens = unpinned_tokens;
+        self.context_state.tokens_used = self.tokens.len();
+
+        let event = self.run_compaction_strategy(services);
+        self.context_policy = policy;
+
+        let Some(mut event) = event else {
+            self.context_state.segments = original_segments;
+            self.tokens = original_tokens;
+            self.context_state.tokens_used = self.tokens.len();
+            return None;
+        };
+
+        let compacted_segments = std::mem::take(&mut self.context_state.segments);
+        let compacted_tokens = std::mem::take(&mut self.tokens);
+        let (segments, tokens) = if compacted_segments
+            .iter()
+            .map(|segment| segment.token_count)
+            .sum::<usize>()
+            == compacted_tokens.len()
+        {
+            merge_pinned_in_original_order(
+                &original_segments,
+                &original_tokens,
+                compacted_segments,
+                compacted_tokens,
+            )
+        } else {
+            // Senza allineamento non si sa dove finisce ogni segmento: i
+            // pinnati restano almeno interi, in testa.
+            pinned_segments.extend(compacted_segments);
+            pinned_tokens.extend(compacted_tokens);
+            (pinned_segments, pinned_tokens)
+        };
+        self.context_state.segments = segments;
+        self.tokens = tokens;
+        self.context_state.tokens_used = self.tokens.len();
+        self.rebuild_rendered_prompt_cache();
+        event.tokens_after = self.tokens.len();
+        Some(event)
     }
 
-    fn enforce_retrieve_budget(&mut self) -> Option<ContextCompactionEvent> {
+    fn enforce_retrieve_budget(
+        &mut self,
+        services: Option<&mut ContextServices>,
+    ) -> Option<ContextCompactionEvent> {
         let retrieved_prefix_segments = self
             .context_state
             .segments
@@ -351,7 +610,18 @@ impl AgentProcess {
             .window_size_tokens
             .saturating_sub(base_tokens_after_archive);
         self.context_state.context_retrieval_requests += 1;
-        let retrieval_payload = self.build_retrieval_payload(&retrieval_corpus, remaining_budget);
+        let vectors =
+            services.and_then(|services| self.retrieval_vector_scores(services, &retrieval_corpus));
+        let scoring = if vectors.is_some() {
+            "hybrid"
+        } else {
+            "lexical"
+        };
+        let retrieval_payload = self.build_scored_retrieval_payload(
+            &retrieval_corpus,
+            remaining_budget,
+            vectors.as_ref(),
+        );
         self.context_state.context_retrieval_candidates_scored +=
             retrieval_payload.candidates_scored as u64;
         self.context_state.last_retrieval_candidates_scored = retrieval_payload.candidates_scored;
@@ -420,7 +690,7 @@ impl AgentProcess {
         self.context_state.tokens_used = self.tokens.len();
         self.rebuild_rendered_prompt_cache();
         let reason = format!(
-            "retrieve_archived_segments={} archived_tokens={} retrieval_hits={} candidates_scored={} selected_segments={} latency_ms={} top_score={}",
+            "retrieve_archived_segments={} archived_tokens={} retrieval_hits={} candidates_scored={} selected_segments={} latency_ms={} top_score={} scoring={}",
             archived_count,
             archived_tokens,
             retrieval_hits,
@@ -429,7 +699,8 @@ impl AgentProcess {
             elapsed_ms,
             top_score
                 .map(|score| format!("{score:.3}"))
-                .unwrap_or_else(|| "none".to_string())
+                .unwrap_or_else(|| "none".to_string()),
+            scoring
         );
         self.context_state.last_compaction_reason = Some(reason.clone());
 
@@ -439,6 +710,8 @@ impl AgentProcess {
             dropped_tokens: archived_tokens + retrieved_prefix_tokens,
             tokens_after: self.tokens.len(),
             reason,
+            summarizer: None,
+            summary_fallback: None,
         })
     }
 
@@ -490,10 +763,15 @@ impl AgentProcess {
             dropped_tokens,
             tokens_after: self.tokens.len(),
             reason,
+            summarizer: None,
+            summary_fallback: None,
         })
     }
 
-    fn enforce_summary_budget(&mut self) -> Option<ContextCompactionEvent> {
+    fn enforce_summary_budget(
+        &mut self,
+        summarizer: Option<&mut ContextSummarizer>,
+    ) -> Option<ContextCompactionEvent> {
         let total_segments = self.context_state.segments.len();
         if total_segments < 2 {
             self.context_state.last_compaction_reason =
@@ -501,6 +779,11 @@ impl AgentProcess {
             return None;
         }
 
+        // Il modello viene interpellato una sola volta, sul primo taglio che
+        // lascia spazio al riassunto; altrimenti si ripiega sul digest euristico.
+        let mut model_summarizer = summarizer.filter(|summarizer| summarizer.uses_model());
+        let mut summarizer_label = "heuristic".to_string();
+        let mut summary_fallback = None;
         let mut dropped_segments = 1usize;
         let mut dropped_tokens = self.context_state.segments[0].token_count;
         let (summary_text, summary_tokens) = loop {
@@ -525,6 +808,49 @@ impl AgentProcess {
                 continue;
             }
 
+            if let Some(summarizer) = model_summarizer.take() {
+                let header_tokens = self
+                    .tokenizer
+                    .encode(SUMMARY_SEGMENT_HEADER, false)
+                    .map(|encoding| encoding.get_ids().len())
+                    .unwrap_or(0);
+                let drafted = summarizer
+                    .summarize(
+                        &mut self.model,
+                        &self.tokenizer,
+                        self.generation,
+                        source_segments,
+                        available_summary_tokens.saturating_sub(header_tokens),
+                    )
+                    .and_then(|summary| {
+                        let text = format!("{SUMMARY_SEGMENT_HEADER}{summary}\n\n");
+                        let tokens = self
+                            .tokenizer
+                            .encode(text.as_str(), true)
+                            .map_err(|err| format!("tokenize:{err}"))?
+                            .get_ids()
+                            .to_vec();
+                        if tokens.is_empty() || tokens.len() > available_summary_tokens {
+                            return Err("overflow".to_string());
+                        }
+                        Ok((text, tokens))
+                    });
+                match drafted {
+                    Ok(drafted) => {
+                        summarizer_label = summarizer.label();
+                        break drafted;
+                    }
+                    Err(reason) => {
+                        tracing::warn!(
+                            summarizer = %summarizer.label(),
+                            %reason,
+                            "CONTEXT: model summary unavailable, using heuristic digest"
+                        );
+                        summary_fallback = Some(reason);
+                    }
+                }
+            }
+
             let summary_text = build_summary_text(source_segments, available_summary_tokens);
             let summary_tokens = self
                 .tokenizer
@@ -566,10 +892,13 @@ impl AgentProcess {
         self.context_state.last_summary_ts = Some(crate::checkpoint::now_timestamp());
         self.rebuild_rendered_prompt_cache();
 
-        let reason = format!(
-            "summarize_compacted_segments={} replaced_tokens={}",
-            dropped_segments, dropped_tokens
+        let mut reason = format!(
+            "summarize_compacted_segments={} replaced_tokens={} summarizer={}",
+            dropped_segments, dropped_tokens, summarizer_label
         );
+        if let Some(fallback) = &summary_fallback {
+            reason.push_str(&format!(" fallback={fallback}"));
+        }
         self.context_state.last_compaction_reason = Some(reason.clone());
 
         Some(ContextCompactionEvent {
@@ -578,6 +907,8 @@ impl AgentProcess {
             dropped_tokens,
             tokens_after: self.tokens.len(),
             reason,
+            summarizer: Some(summarizer_label),
+            summary_fallback,
         })
     }
 
@@ -590,16 +921,59 @@ impl AgentProcess {
             .ok()
     }
 
+    /// Embedding similarities of the retrieval candidates, or `None` when no
+    /// provider is configured or it failed (the ranking stays lexical).
+    fn retrieval_vector_scores(
+        &mut self,
+        services: &mut ContextServices,
+        corpus: &[ContextSegment],
+    ) -> Option<VectorScores> {
+        let provider = services.embeddings.as_deref_mut()?;
+        let query = retrieval_query_excerpt(&self.context_policy, &self.context_state.segments);
+        let candidates = retrieval_candidate_excerpts(&self.context_policy, corpus);
+        match self
+            .segment_vectors
+            .similarities(provider, &query, &candidates)
+        {
+            Ok(similarity) => Some(VectorScores {
+                weight: services.vector_weight,
+                similarity,
+            }),
+            Err(err) => {
+                tracing::warn!(
+                    provider = %provider.id(),
+                    %err,
+                    "CONTEXT: embedding request failed, retrieval falls back to lexical scoring"
+                );
+                None
+            }
+        }
+    }
+
+    #[cfg(test)]
     pub(crate) fn build_retrieval_payload(
         &self,
         corpus: &[ContextSegment],
         remaining_budget: usize,
+    ) -> RetrievalPayloadBuild {
+        self.build_scored_retrieval_payload(corpus, remaining_budget, None)
+    }
+
+    pub(crate) fn build_scored_retrieval_payload(
+        &self,
+        corpus: &[ContextSegment],
+        remaining_budget: usize,
+        vectors: Option<&VectorScores>,
     ) -> RetrievalPayloadBuild {
         if corpus.is_empty() || remaining_budget == 0 {
             return RetrievalPayloadBuild::default();
         }
-        let ranking =
-            rank_retrieval_candidates(&self.context_policy, &self.context_state.segments, corpus);
+        let ranking = rank_retrieval_candidates(
+            &self.context_policy,
+            &self.context_state.segments,
+            corpus,
+            vectors,
+        );
         let mut chosen: Vec<(usize, String)> = Vec::new();
         let mut selected_hits = 0usize;
         let mut selected_texts: Vec<String> = Vec::new();
@@ -656,7 +1030,7 @@ impl AgentProcess {
 
         if merge_tail {
             if let Some(last) = self.context_state.segments.last_mut() {
-                if last.kind == kind {
+                if last.kind == kind && !last.pinned {
                     last.token_count += token_count;
                     last.text.push_str(text);
                     self.rendered_prompt_cache.push_str(text);
@@ -706,6 +1080,78 @@ pub(crate) struct RetrievalPayloadBuild {
     pub top_score: Option<f64>,
 }
 
+/// Ogni strategia scarta un prefisso dei segmenti non pinnati e può anteporre
+/// segmenti nuovi (riassunto, memorie recuperate): i sopravvissuti sono il
+/// suffisso comune con gli originali, i nuovi prendono il posto del primo
+/// segmento non pinnato e i pinnati tornano al loro indice originale.
+fn merge_pinned_in_original_order(
+    original_segments: &[ContextSegment],
+    original_tokens: &[u32],
+    compacted_segments: Vec<ContextSegment>,
+    compacted_tokens: Vec<u32>,
+) -> (Vec<ContextSegment>, Vec<u32>) {
+    let unpinned = original_segments
+        .iter()
+        .filter(|segment| !segment.pinned)
+        .collect::<Vec<_>>();
+    let survivors = compacted_segments
+        .iter()
+        .rev()
+        .zip(unpinned.iter().rev())
+        .take_while(|(compacted, original)| {
+            compacted.kind == original.kind
+                && compacted.token_count == original.token_count
+                && compacted.text == original.text
+        })
+        .count();
+    let dropped = unpinned.len() - survivors;
+    let inserted = compacted_segments.len() - survivors;
+
+    let mut offset = 0usize;
+    let mut compacted = compacted_segments
+        .into_iter()
+        .map(|segment| {
+            let end = offset + segment.token_count;
+            let slice = &compacted_tokens[offset..end];
+            offset = end;
+            (segment, slice)
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    let mut segments = Vec::new();
+    let mut tokens = Vec::new();
+    let mut offset = 0usize;
+    let mut unpinned_seen = 0usize;
+    for segment in original_segments {
+        let end = offset + segment.token_count;
+        if segment.pinned {
+            segments.push(segment.clone());
+            tokens.extend_from_slice(&original_tokens[offset..end]);
+        } else {
+            if unpinned_seen == 0 {
+                for (new_segment, new_tokens) in compacted.by_ref().take(inserted) {
+                    segments.push(new_segment);
+                    tokens.extend_from_slice(new_tokens);
+                }
+            }
+            if unpinned_seen >= dropped {
+                if let Some((survivor, survivor_tokens)) = compacted.next() {
+                    segments.push(survivor);
+                    tokens.extend_from_slice(survivor_tokens);
+                }
+            }
+            unpinned_seen += 1;
+        }
+        offset = end;
+    }
+    for (segment, segment_tokens) in compacted {
+        segments.push(segment);
+        tokens.extend_from_slice(segment_tokens);
+    }
+    (segments, tokens)
+}
+
 fn build_summary_text(segments: &[ContextSegment], max_summary_tokens: usize) -> String {
     if segments.is_empty() || max_summary_tokens == 0 {
         return "Summary of earlier context: no retained details.".to_string();
@@ -795,11 +1241,10 @@ fn retokenize_prompt_segments(
             .len();
         let delta = token_count.saturating_sub(previous_tokens);
         previous_tokens = token_count;
-        rebuilt.push(ContextSegment::new(
-            segment.kind,
-            delta,
-            segment.text.clone(),
-        ));
+        rebuilt.push(
+            ContextSegment::new(segment.kind, delta, segment.text.clone())
+                .with_pinned(segment.pinned),
+        );
     }
 
     Ok(rebuilt)
@@ -822,11 +1267,10 @@ fn retokenize_independent_segments(
                 })?
                 .get_ids()
                 .len();
-            Ok(ContextSegment::new(
-                segment.kind,
-                token_count,
-                segment.text.clone(),
-            ))
+            Ok(
+                ContextSegment::new(segment.kind, token_count, segment.text.clone())
+                    .with_pinned(segment.pinned),
+            )
         })
         .collect()
 }
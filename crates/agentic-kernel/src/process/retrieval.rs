@@ -10,6 +10,14 @@ pub(crate) struct RankedRetrievalCandidate {
     pub score: f64,
 }
 
+/// Cosine similarities of retrieval candidates (by corpus index) to the query,
+/// blended into the lexical score with `weight`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VectorScores {
+    pub weight: f64,
+    pub similarity: HashMap<usize, f64>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub(crate) struct RetrievalRanking {
     pub candidates: Vec<RankedRetrievalCandidate>,
@@ -18,16 +26,11 @@ pub(crate) struct RetrievalRanking {
     pub elapsed_ms: u64,
 }
 
-pub(crate) fn rank_retrieval_candidates(
+/// Query of a retrieval: excerpt of the last live turns.
+pub(crate) fn retrieval_query_excerpt(
     policy: &ContextPolicy,
     live_segments: &[ContextSegment],
-    corpus: &[ContextSegment],
-) -> RetrievalRanking {
-    let started_at = Instant::now();
-    if corpus.is_empty() {
-        return RetrievalRanking::default();
-    }
-
+) -> String {
     let query_text = live_segments
         .iter()
         .filter(|segment| segment.kind != ContextSegmentKind::RetrievedMemory)
@@ -36,26 +39,52 @@ pub(crate) fn rank_retrieval_candidates(
         .map(|segment| segment.text.as_str())
         .collect::<Vec<_>>()
         .join(" ");
-    let query_fingerprint = SemanticFingerprint::from_text(&semantic_excerpt(
+    semantic_excerpt(
         &query_text,
         policy.retrieve_max_segment_chars.saturating_mul(2),
-    ));
+    )
+}
 
+/// Non-empty excerpts of the candidate window, with their corpus index.
+pub(crate) fn retrieval_candidate_excerpts(
+    policy: &ContextPolicy,
+    corpus: &[ContextSegment],
+) -> Vec<(usize, String)> {
     let start_idx = corpus.len().saturating_sub(policy.retrieve_candidate_limit);
-    let candidate_window = &corpus[start_idx..];
+    corpus[start_idx..]
+        .iter()
+        .enumerate()
+        .filter_map(|(offset, segment)| {
+            let excerpt = semantic_excerpt(&segment.text, policy.retrieve_max_segment_chars);
+            (!excerpt.is_empty()).then_some((start_idx + offset, excerpt))
+        })
+        .collect()
+}
+
+pub(crate) fn rank_retrieval_candidates(
+    policy: &ContextPolicy,
+    live_segments: &[ContextSegment],
+    corpus: &[ContextSegment],
+    vectors: Option<&VectorScores>,
+) -> RetrievalRanking {
+    let started_at = Instant::now();
+    if corpus.is_empty() {
+        return RetrievalRanking::default();
+    }
+
+    let query_fingerprint =
+        SemanticFingerprint::from_text(&retrieval_query_excerpt(policy, live_segments));
+
     let mut prepared = Vec::new();
-    for (offset, segment) in candidate_window.iter().enumerate() {
-        let candidate_text = semantic_excerpt(&segment.text, policy.retrieve_max_segment_chars);
-        if candidate_text.is_empty() {
-            continue;
-        }
+    for (idx, candidate_text) in retrieval_candidate_excerpts(policy, corpus) {
         let fingerprint = SemanticFingerprint::from_text(&candidate_text);
-        if fingerprint.is_empty() {
+        let embedded = vectors.is_some_and(|vectors| vectors.similarity.contains_key(&idx));
+        if fingerprint.is_empty() && !embedded {
             continue;
         }
         prepared.push(PreparedCandidate {
-            idx: start_idx + offset,
-            kind: segment.kind,
+            idx,
+            kind: corpus[idx].kind,
             text: candidate_text,
             fingerprint,
         });
@@ -74,7 +103,15 @@ pub(crate) fn rank_retrieval_candidates(
     let mut ranked = prepared
         .into_iter()
         .filter_map(|candidate| {
-            let score = semantic_score(&query_fingerprint, &idf, &candidate, total_candidates);
+            let lexical = semantic_score(&query_fingerprint, &idf, &candidate, total_candidates);
+            let score = match vectors.and_then(|vectors| {
+                Some((vectors.weight, *vectors.similarity.get(&candidate.idx)?))
+            }) {
+                Some((weight, similarity)) => {
+                    (1.0 - weight) * lexical + weight * similarity.max(0.0)
+                }
+                None => lexical,
+            };
             (score >= policy.retrieve_min_score).then_some(RankedRetrievalCandidate {
                 idx: candidate.idx,
                 text: candidate.text,
@@ -257,7 +294,7 @@ fn semantic_excerpt(text: &str, max_chars: usize) -> String {
     format!("{head}\n...\n{tail}")
 }
 
-fn normalized_terms(text: &str) -> Vec<String> {
+pub(crate) fn normalized_terms(text: &str) -> Vec<String> {
     text.split(|ch: char| !ch.is_alphanumeric())
         .filter_map(normalize_term)
         .collect()
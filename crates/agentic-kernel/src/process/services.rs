@@ -0,0 +1,62 @@
+//! Context helpers owned by the inference worker.
+//!
+//! Compactions that need a model (summaries) or an embedding provider (hybrid
+//! retrieval) run on the worker with these services; the event loop keeps
+//! calling [`AgentProcess::enforce_context_budget`](super::AgentProcess) without them.
+use super::summarizer::{model_summaries_enabled, ContextSummarizer};
+use crate::backend::embeddings::{embedding_provider_from_config, EmbeddingProvider};
+use crate::config::KernelConfig;
+use crate::services::accounting::BackendAccountingEvent;
+
+pub struct ContextServices {
+    pub(crate) summarizer: ContextSummarizer,
+    pub(crate) embeddings: Option<Box<dyn EmbeddingProvider>>,
+    pub(crate) vector_weight: f64,
+}
+
+impl ContextServices {
+    pub fn from_kernel_config() -> Self {
+        let config = &crate::config::kernel_config().context;
+        Self {
+            summarizer: ContextSummarizer::new(&config.summarizer),
+            embeddings: embedding_provider_from_config(&config.embeddings),
+            vector_weight: config.embeddings.vector_weight.clamp(0.0, 1.0),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn new(
+        summarizer: ContextSummarizer,
+        embeddings: Option<Box<dyn EmbeddingProvider>>,
+        vector_weight: f64,
+    ) -> Self {
+        Self {
+            summarizer,
+            embeddings,
+            vector_weight: vector_weight.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Usage of the model requests issued since the last call.
+    pub(crate) fn take_accounting_events(&mut self) -> Vec<BackendAccountingEvent> {
+        self.summarizer.take_accounting_events()
+    }
+}
+
+/// Whether `config` gives the worker something the event loop lacks.
+pub(crate) fn summaries_need_worker(config: &KernelConfig) -> bool {
+    model_summaries_enabled(&config.context.summarizer)
+}
+
+pub(crate) fn retrieval_needs_worker(config: &KernelConfig) -> bool {
+    !matches!(
+        config
+            .context
+            .embeddings
+            .provider
+            .trim()
+            .to_ascii_lowercase()
+            .as_str(),
+        "" | "none" | "off"
+    )
+}
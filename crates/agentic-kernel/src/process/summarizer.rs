@@ -0,0 +1,317 @@
+//! Model-drafted summaries for the `summarize` context strategy.
+//!
+//! The summarizer lives on the inference worker: compaction of a process whose
+//! strategy is `summarize` is deferred from checkout to the worker, which asks
+//! either the process's own model or a designated catalog model for a summary
+//! of the segments being dropped. Any failure (load error, backend error,
+//! timeout, empty or oversized output) is reported as a fallback reason and the
+//! caller keeps the heuristic digest.
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::time::{Duration, Instant};
+
+use tokenizers::Tokenizer;
+
+use super::context::{ContextSegment, ContextSegmentKind};
+use crate::backend::{InferenceStepRequest, RuntimeModel};
+use crate::config::ContextSummarizerConfig;
+use crate::model_catalog::ModelCatalog;
+use crate::prompting::{format_initial_prompt_with_metadata, GenerationConfig};
+use crate::services::accounting::BackendAccountingEvent;
+
+/// Prefix of model-drafted summary segments.
+pub(crate) const SUMMARY_SEGMENT_HEADER: &str = "Summary of earlier context:\n";
+
+/// Below this many tokens of room a model summary is not worth a request.
+const MIN_MODEL_SUMMARY_TOKENS: usize = 8;
+
+/// A designated model that failed to load is retried at most this often.
+const DESIGNATED_LOAD_RETRY: Duration = Duration::from_secs(60);
+
+const SUMMARY_SYSTEM_PROMPT: &str = "You compress conversation history for an AI agent. \
+Write a faithful, self-contained summary in plain prose. Keep the user's goals and \
+constraints, decisions taken, facts learned, tool calls with their outcomes, file and \
+identifier names, and any open question or pending step. Do not invent details and do \
+not address the user.";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SummarizerSource {
+    Heuristic,
+    ProcessModel,
+    /// Catalog selector of a designated model (local id or `cloud:...`).
+    Model(String),
+}
+
+/// Whether `config` asks for model-drafted summaries at all.
+pub(crate) fn model_summaries_enabled(config: &ContextSummarizerConfig) -> bool {
+    !matches!(
+        config.mode.trim().to_ascii_lowercase().as_str(),
+        "heuristic" | "off" | "none" | ""
+    )
+}
+
+impl SummarizerSource {
+    pub(crate) fn from_config(config: &ContextSummarizerConfig) -> Self {
+        if !model_summaries_enabled(config) {
+            return Self::Heuristic;
+        }
+        match config.mode.trim().to_ascii_lowercase().as_str() {
+            "model" if !config.model.trim().is_empty() => {
+                Self::Model(config.model.trim().to_string())
+            }
+            "model" => {
+                tracing::warn!(
+                    "CONTEXT: summarizer mode 'model' without a model selector, using the process model"
+                );
+                Self::ProcessModel
+            }
+            "process_model" | "process" => Self::ProcessModel,
+            other => {
+                tracing::warn!(
+                    mode = other,
+                    "CONTEXT: unknown summarizer mode, using the heuristic digest"
+                );
+                Self::Heuristic
+            }
+        }
+    }
+
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Self::Heuristic => "heuristic".to_string(),
+            Self::ProcessModel => "process_model".to_string(),
+            Self::Model(selector) => format!("model:{selector}"),
+        }
+    }
+}
+
+pub struct ContextSummarizer {
+    source: SummarizerSource,
+    timeout: Duration,
+    max_summary_tokens: usize,
+    temperature: f64,
+    designated: Option<RuntimeModel>,
+    /// Background load of the designated model; the worker keeps stepping
+    /// (with the heuristic digest) until it lands.
+    designated_loading: Option<Receiver<Result<RuntimeModel, String>>>,
+    designated_failed_at: Option<Instant>,
+    accounting_events: Vec<BackendAccountingEvent>,
+}
+
+impl ContextSummarizer {
+    pub(crate) fn new(config: &ContextSummarizerConfig) -> Self {
+        Self {
+            source: SummarizerSource::from_config(config),
+            timeout: Duration::from_millis(config.timeout_ms),
+            max_summary_tokens: config.max_summary_tokens.max(MIN_MODEL_SUMMARY_TOKENS),
+            temperature: config.temperature,
+            designated: None,
+            designated_loading: None,
+            designated_failed_at: None,
+            accounting_events: Vec::new(),
+        }
+    }
+
+    /// Whether compaction should be deferred to the worker for this summarizer.
+    pub fn uses_model(&self) -> bool {
+        self.source != SummarizerSource::Heuristic
+    }
+
+    pub(crate) fn label(&self) -> String {
+        self.source.label()
+    }
+
+    /// Usage of the summary requests issued since the last call.
+    pub(crate) fn take_accounting_events(&mut self) -> Vec<BackendAccountingEvent> {
+        std::mem::take(&mut self.accounting_events)
+    }
+
+    /// Draft a summary of `segments` in at most `max_tokens` tokens.
+    ///
+    /// Returns the summary body (without [`SUMMARY_SEGMENT_HEADER`]) or the
+    /// reason the heuristic digest has to be used instead.
+    pub(crate) fn summarize(
+        &mut self,
+        process_model: &mut RuntimeModel,
+        tokenizer: &Tokenizer,
+        generation: GenerationConfig,
+        segments: &[ContextSegment],
+        max_tokens: usize,
+    ) -> Result<String, String> {
+        let budget = max_tokens.min(self.max_summary_tokens);
+        if budget < MIN_MODEL_SUMMARY_TOKENS {
+            return Err("budget".to_string());
+        }
+        let generation = GenerationConfig {
+            temperature: self.temperature,
+            max_tokens: budget,
+            ..generation
+        };
+        let timeout = self.timeout;
+        let (text, accounting) = {
+            let model = match self.source.clone() {
+                SummarizerSource::Heuristic => return Err("heuristic".to_string()),
+                SummarizerSource::ProcessModel => process_model,
+                SummarizerSource::Model(selector) => self.designated_model(&selector)?,
+            };
+            let prompt = format_initial_prompt_with_metadata(
+                Some(SUMMARY_SYSTEM_PROMPT),
+                &summary_request(segments, budget),
+                model.family(),
+                None,
+            );
+            generate_summary(model, tokenizer, &prompt, generation, timeout)
+        };
+        self.accounting_events.extend(accounting);
+
+        let summary = text?.trim().to_string();
+        if summary.is_empty() {
+            return Err("empty".to_string());
+        }
+        Ok(summary)
+    }
+
+    /// The designated model, once its background load has completed. Until
+    /// then (or while a failed load waits for its retry) the caller falls
+    /// back to the heuristic digest instead of blocking the worker.
+    fn designated_model(&mut self, selector: &str) -> Result<&mut RuntimeModel, String> {
+        if self.designated.is_none() {
+            self.poll_designated_load(selector)?;
+        }
+        self.designated
+            .as_mut()
+            .ok_or_else(|| "model_unavailable".to_string())
+    }
+
+    fn poll_designated_load(&mut self, selector: &str) -> Result<(), String> {
+        let Some(loading) = self.designated_loading.as_ref() else {
+            if self
+                .designated_failed_at
+                .is_some_and(|failed_at| failed_at.elapsed() < DESIGNATED_LOAD_RETRY)
+            {
+                return Err("model_unavailable".to_string());
+            }
+            self.designated_loading = Some(spawn_designated_load(selector.to_string()));
+            return Err("model_loading".to_string());
+        };
+        let loaded = match loading.try_recv() {
+            Ok(loaded) => loaded,
+            Err(TryRecvError::Empty) => return Err("model_loading".to_string()),
+            Err(TryRecvError::Disconnected) => Err("loader thread exited".to_string()),
+        };
+        self.designated_loading = None;
+        match loaded {
+            Ok(model) => {
+                tracing::info!(
+                    selector,
+                    backend = model.backend_id(),
+                    "CONTEXT: summarizer model loaded"
+                );
+                self.designated = Some(model);
+                self.designated_failed_at = None;
+                Ok(())
+            }
+            Err(err) => {
+                tracing::warn!(selector, %err, "CONTEXT: summarizer model failed to load");
+                self.designated_failed_at = Some(Instant::now());
+                Err("model_unavailable".to_string())
+            }
+        }
+    }
+}
+
+fn spawn_designated_load(selector: String) -> Receiver<Result<RuntimeModel, String>> {
+    let (tx, rx) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("summarizer-load".to_string())
+        .spawn(move || {
+            let _ = tx.send(load_designated_model(&selector));
+        });
+    if let Err(err) = spawned {
+        tracing::warn!(%err, "CONTEXT: failed to start the summarizer loader thread");
+    }
+    rx
+}
+
+/// Local models run on the dedicated summarizer runtime, never on the family
+/// runtime that serves the processes being summarized.
+fn load_designated_model(selector: &str) -> Result<RuntimeModel, String> {
+    let catalog = ModelCatalog::discover(crate::config::kernel_config().paths.models_dir.clone())
+        .map_err(|err| err.to_string())?;
+    let target = catalog
+        .resolve_load_target(selector)
+        .map_err(|err| err.to_string())?;
+    RuntimeModel::load_summarizer_target(&target).map_err(|err| err.to_string())
+}
+
+/// Run generation steps until the model stops, the budget is spent or the
+/// deadline passes. The deadline is checked between steps: a single remote
+/// request is bounded by the provider timeout instead.
+fn generate_summary(
+    model: &mut RuntimeModel,
+    tokenizer: &Tokenizer,
+    prompt: &str,
+    generation: GenerationConfig,
+    timeout: Duration,
+) -> (Result<String, String>, Vec<BackendAccountingEvent>) {
+    let deadline = Instant::now() + timeout;
+    let mut accounting = Vec::new();
+    let mut text = String::new();
+    let mut generated = 0usize;
+
+    loop {
+        let rendered_prompt = format!("{prompt}{text}");
+        let step = match model.generate_step(InferenceStepRequest {
+            context_slot_id: None,
+            tokens: &[],
+            rendered_prompt: &rendered_prompt,
+            resident_prompt_suffix: "",
+            index_pos: 0,
+            remaining_generation_budget: generation.max_tokens.saturating_sub(generated),
+            tokenizer,
+            generation,
+            stream_observer: None,
+            eos_token_id: 0,
+            eot_token_id: 0,
+        }) {
+            Ok(step) => step,
+            Err(err) => {
+                accounting.extend(model.take_last_accounting_event());
+                return (Err(format!("error:{err}")), accounting);
+            }
+        };
+        accounting.extend(model.take_last_accounting_event());
+        generated = generated.saturating_add(step.appended_tokens.len());
+        text.push_str(&step.emitted_text);
+
+        if step.finished || generated >= generation.max_tokens {
+            return (Ok(text), accounting);
+        }
+        if Instant::now() >= deadline {
+            return (Err("timeout".to_string()), accounting);
+        }
+        if step.appended_tokens.is_empty() && step.emitted_text.is_empty() {
+            return (Ok(text), accounting);
+        }
+    }
+}
+
+fn summary_request(segments: &[ContextSegment], budget_tokens: usize) -> String {
+    let mut request = format!(
+        "Summarize the earlier part of this conversation so the agent can continue it \
+         without the original text. Use at most {} words. Reply with the summary only.\n\n\
+         <conversation>\n",
+        (budget_tokens * 3 / 4).max(1)
+    );
+    for segment in segments {
+        let label = match segment.kind {
+            ContextSegmentKind::UserTurn => "user",
+            ContextSegmentKind::AssistantTurn => "assistant",
+            ContextSegmentKind::InjectedContext => "context",
+            ContextSegmentKind::Summary => "previous summary",
+            ContextSegmentKind::RetrievedMemory => "memory",
+        };
+        request.push_str(&format!("[{label}]\n{}\n\n", segment.text.trim()));
+    }
+    request.push_str("</conversation>");
+    request
+}
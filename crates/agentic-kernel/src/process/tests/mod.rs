@@ -1,12 +1,18 @@
+use super::services::summaries_need_worker;
+use super::summarizer::ContextSummarizer;
+use super::vectors::SegmentVectorIndex;
 /// Unit tests for process and context management.
 use super::{
-    AgentProcess, ContextPolicy, ContextSegment, ContextSegmentKind, ContextStrategy,
-    InitialContextSeed, ProcessLifecyclePolicy, ResidentSlotPolicy,
+    AgentProcess, ContextPolicy, ContextPolicyUpdate, ContextSegment, ContextSegmentKind,
+    ContextServices, ContextStrategy, InitialContextSeed, ProcessLifecyclePolicy,
+    ResidentSlotPolicy,
 };
+use crate::backend::embeddings::EmbeddingProvider;
 use crate::backend::{
     ContextSlotPersistence, InferenceBackend, InferenceStepRequest, InferenceStepResult,
     RuntimeModel,
 };
+use crate::config::{ContextSummarizerConfig, KernelConfig};
 use crate::memory::ContextSlotId;
 use crate::prompting::GenerationConfig;
 use crate::tools::invocation::{
@@ -484,3 +490,575 @@ fn resident_slot_state_tracks_snapshot_lifecycle() {
         super::ResidentSlotState::Allocated
     );
 }
+
+fn pinned_prompt_process(policy: ContextPolicy) -> (AgentProcess, Arc<Mutex<Vec<ContextSlotId>>>) {
+    let (mut process, frees) = test_process(policy);
+    process.bind_context_slot(21, ResidentSlotPolicy::ParkAndResume);
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::UserTurn,
+        "\nsystem note turn",
+        &[5, 6, 2],
+    );
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::AssistantTurn,
+        "\nassistant reply reply",
+        &[3, 4, 4],
+    );
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::UserTurn,
+        "\nuser user",
+        &[1, 1],
+    );
+    process.pin_segment(0, true).expect("pin the task prompt");
+    (process, frees)
+}
+
+#[test]
+fn pinned_segments_survive_sliding_window_compaction() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 16, 10, 6, 2);
+    let (mut process, frees) = pinned_prompt_process(policy);
+
+    let event = process
+        .enforce_context_budget()
+        .expect("compaction should run");
+
+    assert_eq!(event.dropped_segments, 2);
+    assert_eq!(event.tokens_after, 5);
+    assert_eq!(process.tokens, vec![1, 2, 1, 1, 1]);
+    assert!(process.context_state.segments[0].pinned);
+    assert_eq!(process.prompt_text(), "user turn user\nuser user");
+    assert_eq!(process.context_policy.compaction_target_tokens, 6);
+    assert_eq!(*frees.lock().expect("lock frees"), vec![21]);
+
+    let snapshot = process.context_status_snapshot();
+    assert_eq!(snapshot.pinned_segments, 1);
+    assert_eq!(snapshot.pinned_tokens, 3);
+    assert!(snapshot.segments[0].pinned);
+}
+
+#[test]
+fn pinned_segments_stay_ahead_of_the_summary() {
+    let policy = ContextPolicy::new(ContextStrategy::Summarize, 16, 10, 7, 2);
+    let (mut process, _frees) = pinned_prompt_process(policy);
+
+    process
+        .enforce_context_budget()
+        .expect("summary compaction should run");
+
+    let segments = &process.context_state.segments;
+    assert!(segments[0].pinned);
+    assert_eq!(segments[0].text, "user turn user");
+    assert_eq!(segments[1].kind, ContextSegmentKind::Summary);
+    assert_eq!(&process.tokens[..3], &[1, 2, 1]);
+    assert!(process.tokens.len() <= process.context_policy.compaction_target_tokens);
+}
+
+#[test]
+fn pinned_segment_keeps_its_place_between_surviving_turns() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 16, 10, 8, 2);
+    let (mut process, _frees) = pinned_prompt_process(policy);
+    process
+        .pin_segment(0, false)
+        .expect("unpin the task prompt");
+    process
+        .pin_segment(2, true)
+        .expect("pin the assistant reply");
+
+    process
+        .enforce_context_budget()
+        .expect("compaction should run");
+
+    let kinds = process
+        .context_state
+        .segments
+        .iter()
+        .map(|segment| (segment.kind, segment.pinned))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        vec![
+            (ContextSegmentKind::UserTurn, false),
+            (ContextSegmentKind::AssistantTurn, true),
+            (ContextSegmentKind::UserTurn, false),
+        ]
+    );
+    assert_eq!(process.tokens, vec![5, 6, 2, 3, 4, 4, 1, 1]);
+    assert_eq!(
+        process.prompt_text(),
+        "\nsystem note turn\nassistant reply reply\nuser user"
+    );
+}
+
+#[test]
+fn pinned_tail_segment_does_not_absorb_streamed_output() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 32, 24, 16, 2);
+    let (mut process, _frees) = test_process(policy);
+    process.record_model_output("assistant", 1);
+    process.pin_segment(1, true).expect("pin assistant segment");
+
+    process.record_model_output(" reply", 1);
+
+    assert_eq!(process.context_state.segments.len(), 3);
+    assert_eq!(process.context_state.segments[1].token_count, 1);
+    assert!(!process.context_state.segments[2].pinned);
+}
+
+#[test]
+fn compact_now_ignores_the_trigger_and_restores_the_policy_target() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 32, 24, 16, 2);
+    let (mut process, _frees) = pinned_prompt_process(policy);
+
+    assert!(process.enforce_context_budget().is_none());
+    assert!(process.compact_now(None).is_none());
+    assert_eq!(
+        process.context_state.last_compaction_reason.as_deref(),
+        Some("manual_compaction_within_target=16")
+    );
+
+    let event = process.compact_now(Some(6)).expect("manual compaction");
+
+    assert!(event.reason.starts_with("manual sliding_window"));
+    assert_eq!(process.tokens, vec![1, 2, 1, 1, 1]);
+    assert_eq!(process.context_policy.compaction_target_tokens, 16);
+    assert_eq!(process.context_state.manual_compactions, 1);
+}
+
+#[test]
+fn drop_segment_removes_tokens_but_refuses_pinned_segments() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 32, 24, 16, 2);
+    let (mut process, frees) = pinned_prompt_process(policy);
+    process.begin_next_turn();
+
+    assert!(process.drop_segment(0).is_err());
+    assert!(process.drop_segment(9).is_err());
+
+    let dropped = process.drop_segment(2).expect("drop assistant segment");
+
+    assert_eq!(dropped.kind, ContextSegmentKind::AssistantTurn);
+    assert_eq!(process.tokens, vec![1, 2, 1, 5, 6, 2, 1, 1]);
+    assert_eq!(process.turn_start_index, process.tokens.len());
+    assert_eq!(process.index_pos, 0);
+    assert_eq!(
+        process.prompt_text(),
+        "user turn user\nsystem note turn\nuser user"
+    );
+    assert_eq!(process.context_state.dropped_segments, 1);
+    assert_eq!(*frees.lock().expect("lock frees"), vec![21]);
+}
+
+#[test]
+fn context_policy_update_rescales_or_clamps_like_spawn() {
+    let policy = ContextPolicy::new(ContextStrategy::SlidingWindow, 4096, 3072, 2048, 3);
+    let (mut process, _frees) = test_process(policy.clone());
+
+    let window_only = policy.with_update(&ContextPolicyUpdate {
+        window_size_tokens: Some(8192),
+        ..ContextPolicyUpdate::default()
+    });
+    let rescaled = ContextPolicy::new_with_window(ContextStrategy::SlidingWindow, 8192, 3);
+    assert_eq!(window_only.window_size_tokens, 8192);
+    assert_eq!(
+        window_only.compaction_trigger_tokens,
+        rescaled.compaction_trigger_tokens
+    );
+    assert_eq!(
+        window_only.compaction_target_tokens,
+        rescaled.compaction_target_tokens
+    );
+
+    let explicit = policy.with_update(&ContextPolicyUpdate {
+        strategy: Some(ContextStrategy::Retrieve),
+        compaction_trigger_tokens: Some(10_000),
+        compaction_target_tokens: Some(1024),
+        ..ContextPolicyUpdate::default()
+    });
+    assert_eq!(explicit.strategy, ContextStrategy::Retrieve);
+    assert_eq!(explicit.window_size_tokens, 4096);
+    assert_eq!(explicit.compaction_trigger_tokens, 4096);
+    assert_eq!(explicit.compaction_target_tokens, 1024);
+    assert_eq!(explicit.retrieve_min_score, policy.retrieve_min_score);
+
+    process.set_context_policy(explicit);
+    let snapshot = process.context_status_snapshot();
+    assert_eq!(snapshot.context_strategy, "retrieve");
+    assert_eq!(snapshot.compaction_trigger_tokens, 4096);
+    assert_eq!(snapshot.policy_updates, 1);
+}
+
+#[derive(Clone)]
+struct SummaryBackend {
+    reply: Option<&'static str>,
+    finished: bool,
+    prompts: Arc<Mutex<Vec<String>>>,
+}
+
+impl InferenceBackend for SummaryBackend {
+    fn backend_id(&self) -> &'static str {
+        "summary"
+    }
+
+    fn family(&self) -> crate::prompting::PromptFamily {
+        crate::prompting::PromptFamily::Unknown
+    }
+
+    fn generate_step(&mut self, request: InferenceStepRequest<'_>) -> Result<InferenceStepResult> {
+        self.prompts
+            .lock()
+            .expect("lock prompts")
+            .push(request.rendered_prompt.to_string());
+        let reply = self
+            .reply
+            .ok_or_else(|| anyhow::anyhow!("summary backend offline"))?;
+        Ok(InferenceStepResult {
+            appended_tokens: vec![3; reply.split_whitespace().count()],
+            emitted_text: reply.to_string(),
+            emitted_reasoning_text: String::new(),
+            finished: self.finished,
+            finish_reason: None,
+            next_index_pos: 0,
+        })
+    }
+
+    fn duplicate_boxed(&self) -> Option<Box<dyn crate::backend::ModelBackend>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+impl ContextSlotPersistence for SummaryBackend {
+    fn save_context_slot(&self, _slot_id: ContextSlotId, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn load_context_slot(&mut self, _slot_id: ContextSlotId, _path: &Path) -> Result<()> {
+        Ok(())
+    }
+
+    fn free_context_slot(&mut self, _slot_id: ContextSlotId) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 43 tokens over three segments: with target 36 the summary replaces the
+/// first two and gets a 16-token budget.
+fn summarizing_process(
+    reply: Option<&'static str>,
+    finished: bool,
+) -> (AgentProcess, Arc<Mutex<Vec<String>>>) {
+    let policy = ContextPolicy::new(ContextStrategy::Summarize, 64, 40, 36, 3);
+    let (mut process, _frees) = test_process(policy);
+    let prompts = Arc::new(Mutex::new(Vec::new()));
+    process.model = RuntimeModel::from_boxed_backend(Box::new(SummaryBackend {
+        reply,
+        finished,
+        prompts: Arc::clone(&prompts),
+    }));
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::AssistantTurn,
+        "assistant reply about the deploy checklist",
+        &[3; 20],
+    );
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::UserTurn,
+        "user turn",
+        &[1; 20],
+    );
+    (process, prompts)
+}
+
+fn summary_services(mode: &str, timeout_ms: u64) -> ContextServices {
+    let summarizer = ContextSummarizer::new(&ContextSummarizerConfig {
+        mode: mode.to_string(),
+        timeout_ms,
+        ..ContextSummarizerConfig::default()
+    });
+    ContextServices::new(summarizer, None, 0.0)
+}
+
+#[test]
+fn summarize_stores_model_drafted_summary_segment() {
+    let (mut process, prompts) = summarizing_process(Some("user asked assistant reply"), true);
+    let mut services = summary_services("process_model", 5_000);
+
+    let event = process
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("summary compaction should run");
+
+    assert_eq!(event.summarizer.as_deref(), Some("process_model"));
+    assert_eq!(event.summary_fallback, None);
+    assert_eq!(event.dropped_segments, 2);
+    assert!(event.reason.contains("summarizer=process_model"));
+    let summary = &process.context_state.segments[0];
+    assert_eq!(summary.kind, ContextSegmentKind::Summary);
+    assert!(summary.text.starts_with("Summary of earlier context:"));
+    assert!(summary.text.contains("user asked assistant reply"));
+    assert!(process.tokens.len() <= process.context_policy.compaction_target_tokens);
+    assert!(process.prompt_text().starts_with(&summary.text));
+
+    let prompts = prompts.lock().expect("lock prompts");
+    assert_eq!(prompts.len(), 1);
+    assert!(prompts[0].contains("deploy checklist"));
+}
+
+#[test]
+fn summarize_falls_back_to_heuristic_digest_when_the_model_fails() {
+    let (mut process, _prompts) = summarizing_process(None, true);
+    let mut services = summary_services("process_model", 5_000);
+
+    let event = process
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("heuristic compaction should still run");
+
+    assert_eq!(event.summarizer.as_deref(), Some("heuristic"));
+    assert!(event
+        .summary_fallback
+        .as_deref()
+        .is_some_and(|reason| reason.starts_with("error:")));
+    assert!(event.reason.contains("fallback=error:"));
+    assert_eq!(
+        process.context_state.segments[0].kind,
+        ContextSegmentKind::Summary
+    );
+    assert!(!process.context_state.segments[0]
+        .text
+        .starts_with("Summary of earlier context:"));
+}
+
+#[test]
+fn summarize_falls_back_when_the_model_summary_times_out() {
+    let (mut process, prompts) = summarizing_process(Some("still"), false);
+    let mut services = summary_services("process_model", 0);
+
+    let event = process
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("heuristic compaction should still run");
+
+    assert_eq!(event.summary_fallback.as_deref(), Some("timeout"));
+    assert_eq!(prompts.lock().expect("lock prompts").len(), 1);
+    assert!(process.tokens.len() <= process.context_policy.compaction_target_tokens);
+}
+
+#[test]
+fn heuristic_summarizer_never_calls_the_model() {
+    let (mut process, prompts) = summarizing_process(Some("unused"), true);
+    let mut services = summary_services("heuristic", 5_000);
+    assert!(!services.summarizer.uses_model());
+
+    let event = process
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("heuristic compaction should run");
+
+    assert_eq!(event.summarizer.as_deref(), Some("heuristic"));
+    assert_eq!(event.summary_fallback, None);
+    assert!(prompts.lock().expect("lock prompts").is_empty());
+}
+
+#[test]
+fn only_model_summaries_are_deferred_to_the_worker() {
+    // The default heuristic digest stays on the event loop at checkout.
+    let (process, _prompts) = summarizing_process(Some("unused"), true);
+    assert!(!process.defers_compaction_to_worker());
+
+    let mut config = KernelConfig::default();
+    assert!(!summaries_need_worker(&config));
+    config.context.summarizer.mode = "process_model".to_string();
+    assert!(summaries_need_worker(&config));
+
+    let (mut sliding, _frees) = test_process(ContextPolicy::new(
+        ContextStrategy::SlidingWindow,
+        64,
+        40,
+        36,
+        3,
+    ));
+    append_segment_tokens(
+        &mut sliding,
+        ContextSegmentKind::UserTurn,
+        "user turn",
+        &[1; 50],
+    );
+    assert!(!sliding.defers_compaction_to_worker());
+}
+
+/// Deterministic embedder: one dimension per concept, with the English and
+/// Italian terms mapped onto the same one.
+struct ConceptEmbedder {
+    fail: bool,
+    calls: Arc<Mutex<Vec<usize>>>,
+}
+
+const CONCEPTS: &[(&str, usize)] = &[
+    ("scheduler", 0),
+    ("pianificatore", 0),
+    ("quota", 1),
+    ("limite", 1),
+    ("deploy", 2),
+    ("rilascio", 2),
+];
+
+impl EmbeddingProvider for ConceptEmbedder {
+    fn id(&self) -> String {
+        "concepts".to_string()
+    }
+
+    fn embed(&mut self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        self.calls.lock().expect("lock calls").push(inputs.len());
+        if self.fail {
+            return Err("embedder offline".to_string());
+        }
+        Ok(inputs
+            .iter()
+            .map(|input| {
+                let mut vector = vec![0.0f32; 3];
+                for word in input.split_whitespace() {
+                    let word = word.to_ascii_lowercase();
+                    for (term, dimension) in CONCEPTS {
+                        if word == *term {
+                            vector[*dimension] += 1.0;
+                        }
+                    }
+                }
+                vector
+            })
+            .collect())
+    }
+}
+
+fn concept_embedder(fail: bool) -> (ConceptEmbedder, Arc<Mutex<Vec<usize>>>) {
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    (
+        ConceptEmbedder {
+            fail,
+            calls: Arc::clone(&calls),
+        },
+        calls,
+    )
+}
+
+fn embedding_services(embedder: ConceptEmbedder) -> ContextServices {
+    ContextServices::new(
+        ContextSummarizer::new(&ContextSummarizerConfig {
+            mode: "heuristic".to_string(),
+            ..ContextSummarizerConfig::default()
+        }),
+        Some(Box::new(embedder)),
+        0.6,
+    )
+}
+
+/// The archive holds an Italian segment sharing no terms with the English
+/// question: only vector similarity lifts it above the threshold.
+fn bilingual_retrieving_process() -> AgentProcess {
+    let mut policy = ContextPolicy::new(ContextStrategy::Retrieve, 24, 12, 10, 1);
+    policy.retrieve_min_score = 0.2;
+    let (mut process, _frees) = test_process(policy);
+    process.context_state.episodic_segments = vec![
+        ContextSegment::new(
+            ContextSegmentKind::AssistantTurn,
+            3,
+            "generic unrelated archive".to_string(),
+        ),
+        ContextSegment::new(
+            ContextSegmentKind::AssistantTurn,
+            3,
+            "pianificatore limite superato".to_string(),
+        ),
+    ];
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::AssistantTurn,
+        "assistant reply",
+        &[3; 6],
+    );
+    append_segment_tokens(
+        &mut process,
+        ContextSegmentKind::UserTurn,
+        "explain scheduler quota",
+        &[1; 6],
+    );
+    process
+}
+
+fn retrieved_memory(process: &AgentProcess) -> Option<&str> {
+    process
+        .context_state
+        .segments
+        .first()
+        .filter(|segment| segment.kind == ContextSegmentKind::RetrievedMemory)
+        .map(|segment| segment.text.as_str())
+}
+
+#[test]
+fn hybrid_retrieval_finds_cross_language_matches() {
+    let mut lexical = bilingual_retrieving_process();
+    let event = lexical
+        .enforce_context_budget()
+        .expect("lexical retrieve compaction should run");
+    assert!(event.reason.contains("scoring=lexical"));
+    assert!(!retrieved_memory(&lexical).is_some_and(|text| text.contains("pianificatore")));
+
+    let (embedder, calls) = concept_embedder(false);
+    let mut services = embedding_services(embedder);
+    let mut hybrid = bilingual_retrieving_process();
+    let event = hybrid
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("hybrid retrieve compaction should run");
+
+    assert!(event.reason.contains("scoring=hybrid"));
+    let memory = retrieved_memory(&hybrid).expect("retrieved memory segment");
+    assert!(memory.contains("pianificatore limite superato"));
+    assert!(!memory.contains("generic unrelated archive"));
+    assert!(hybrid.context_state.last_retrieval_top_score.unwrap_or(0.0) >= 0.6);
+    assert_eq!(calls.lock().expect("lock calls").len(), 1);
+}
+
+#[test]
+fn retrieval_stays_lexical_when_the_embedder_fails() {
+    let (embedder, calls) = concept_embedder(true);
+    let mut services = embedding_services(embedder);
+    let mut process = bilingual_retrieving_process();
+
+    let event = process
+        .enforce_context_budget_with(Some(&mut services))
+        .expect("retrieve compaction should still run");
+
+    assert!(event.reason.contains("scoring=lexical"));
+    assert_eq!(process.context_state.episodic_segments.len(), 4);
+    assert!(!retrieved_memory(&process).is_some_and(|text| text.contains("pianificatore")));
+    assert_eq!(calls.lock().expect("lock calls").len(), 1);
+}
+
+#[test]
+fn segment_vectors_are_cached_per_process() {
+    let (mut embedder, calls) = concept_embedder(false);
+    let mut index = SegmentVectorIndex::new(2);
+    let candidates = vec![
+        (0, "deploy checklist".to_string()),
+        (1, "rilascio completato".to_string()),
+    ];
+
+    let first = index
+        .similarities(&mut embedder, "deploy status", &candidates)
+        .expect("first embedding");
+    let second = index
+        .similarities(&mut embedder, "rilascio in corso", &candidates)
+        .expect("cached embedding");
+
+    assert_eq!(*calls.lock().expect("lock calls"), vec![3, 1]);
+    assert_eq!(index.len(), 2);
+    assert!((first[&1] - 1.0).abs() < 1e-6);
+    assert_eq!(first, second);
+
+    index
+        .similarities(
+            &mut embedder,
+            "deploy",
+            &[(2, "scheduler quota".to_string())],
+        )
+        .expect("third embedding");
+    assert_eq!(index.len(), 2);
+}
@@ -0,0 +1,123 @@
+//! Per-process cache of segment embeddings for hybrid retrieval.
+//!
+//! Vectors are keyed by the provider id and the excerpt text, so an archived
+//! segment is embedded once for the lifetime of the PID; the query is embedded
+//! on every retrieval together with the missing candidates.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+
+use crate::backend::embeddings::EmbeddingProvider;
+
+#[derive(Debug, Default)]
+pub(crate) struct SegmentVectorIndex {
+    vectors: HashMap<u64, Vec<f32>>,
+    insertion_order: VecDeque<u64>,
+    capacity: usize,
+}
+
+impl SegmentVectorIndex {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub(crate) fn contains(&self, provider_id: &str, text: &str) -> bool {
+        self.vectors.contains_key(&vector_key(provider_id, text))
+    }
+
+    /// Cosine similarity of every candidate to `query`, by candidate index.
+    /// Only the query and the candidates not cached yet reach the provider.
+    pub(crate) fn similarities(
+        &mut self,
+        provider: &mut dyn EmbeddingProvider,
+        query: &str,
+        candidates: &[(usize, String)],
+    ) -> Result<HashMap<usize, f64>, String> {
+        if query.trim().is_empty() || candidates.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let provider_id = provider.id();
+        let keys = candidates
+            .iter()
+            .map(|(_, text)| vector_key(&provider_id, text))
+            .collect::<Vec<_>>();
+
+        let mut inputs = vec![query.to_string()];
+        let mut missing = Vec::new();
+        for (position, key) in keys.iter().enumerate() {
+            if !self.vectors.contains_key(key) && !missing.contains(key) {
+                missing.push(*key);
+                inputs.push(candidates[position].1.clone());
+            }
+        }
+
+        let mut embedded = provider.embed(&inputs)?.into_iter();
+        let query_vector = embedded
+            .next()
+            .ok_or_else(|| "embedding provider returned no query vector".to_string())?;
+        let fresh = missing
+            .iter()
+            .copied()
+            .zip(embedded)
+            .collect::<HashMap<_, _>>();
+
+        let mut similarities = HashMap::new();
+        for ((idx, _), key) in candidates.iter().zip(&keys) {
+            if let Some(vector) = fresh.get(key).or_else(|| self.vectors.get(key)) {
+                similarities.insert(*idx, cosine_similarity(&query_vector, vector));
+            }
+        }
+        for (key, vector) in fresh {
+            self.insert(key, vector);
+        }
+        Ok(similarities)
+    }
+
+    fn insert(&mut self, key: u64, vector: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.vectors.insert(key, vector).is_none() {
+            self.insertion_order.push_back(key);
+        }
+        while self.vectors.len() > self.capacity {
+            let Some(oldest) = self.insertion_order.pop_front() else {
+                break;
+            };
+            self.vectors.remove(&oldest);
+        }
+    }
+}
+
+fn vector_key(provider_id: &str, text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    provider_id.hash(&mut hasher);
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cosine similarity in [-1, 1]; zero for empty or mismatched vectors.
+pub(crate) fn cosine_similarity(left: &[f32], right: &[f32]) -> f64 {
+    if left.is_empty() || left.len() != right.len() {
+        return 0.0;
+    }
+    let (mut dot, mut left_norm, mut right_norm) = (0.0f64, 0.0f64, 0.0f64);
+    for (l, r) in left.iter().zip(right) {
+        let (l, r) = (f64::from(*l), f64::from(*r));
+        dot += l * r;
+        left_norm += l * l;
+        right_norm += r * r;
+    }
+    if left_norm == 0.0 || right_norm == 0.0 {
+        return 0.0;
+    }
+    dot / (left_norm.sqrt() * right_norm.sqrt())
+}
@@ -49,6 +49,46 @@ pub(crate) fn fallback_model_bytes(path: &Path, logical_model_id: &str) -> u64 {
     gib.saturating_mul(1024_u64.pow(3))
 }
 
+/// Token di contesto la cui KV cache entra nei budget finiti dopo aver
+/// riservato i pesi del modello. `None` se i budget sono illimitati o se il
+/// costo per token non è noto.
+pub(crate) fn estimate_context_window_cap(
+    model_path: &Path,
+    logical_model_id: &str,
+    kv_cache_bytes_per_token: Option<u64>,
+    config: &ResourceGovernorConfig,
+) -> Option<usize> {
+    let bytes_per_token = kv_cache_bytes_per_token.filter(|bytes| *bytes > 0)?;
+    let base_bytes = std::fs::metadata(model_path)
+        .map(|metadata| metadata.len())
+        .unwrap_or_else(|_| fallback_model_bytes(model_path, logical_model_id));
+    let remaining = [
+        (
+            config.ram_budget_bytes,
+            config.min_ram_headroom_bytes,
+            config.local_runtime_ram_scale,
+            config.local_runtime_ram_overhead_bytes,
+        ),
+        (
+            config.vram_budget_bytes,
+            config.min_vram_headroom_bytes,
+            config.local_runtime_vram_scale,
+            config.local_runtime_vram_overhead_bytes,
+        ),
+    ]
+    .into_iter()
+    .filter(|(budget, ..)| *budget > 0)
+    .map(|(budget, headroom, scale, overhead)| {
+        budget_available(
+            budget,
+            headroom,
+            scaled_reservation_bytes(base_bytes, scale, overhead),
+        )
+    })
+    .min()?;
+    usize::try_from(remaining / bytes_per_token).ok()
+}
+
 pub(crate) fn scaled_reservation_bytes(base_bytes: u64, scale: f64, overhead_bytes: u64) -> u64 {
     ((base_bytes as f64) * scale).ceil() as u64 + overhead_bytes
 }
@@ -10,6 +10,7 @@ use crate::runtimes::StoredRuntimeLoadQueueEntry;
 use crate::runtimes::{runtime_key_for_target, RuntimeRegistry, RuntimeReservation};
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
+use std::path::Path;
 
 pub(crate) struct ResourceGovernor {
     config: ResourceGovernorConfig,
@@ -209,6 +210,21 @@ impl ResourceGovernor {
         )))
     }
 
+    /// Token di contesto che la KV cache del modello può occupare nei budget.
+    pub(crate) fn context_window_cap(
+        &self,
+        model_path: &Path,
+        logical_model_id: &str,
+        kv_cache_bytes_per_token: Option<u64>,
+    ) -> Option<usize> {
+        estimate_context_window_cap(
+            model_path,
+            logical_model_id,
+            kv_cache_bytes_per_token,
+            &self.config,
+        )
+    }
+
     pub(crate) fn try_acquire_loader_lock(
         &mut self,
         reason: &str,
@@ -20,5 +20,6 @@ mod state;
 #[path = "tests/mod.rs"]
 mod tests;
 
+pub(crate) use admission::estimate_context_window_cap;
 pub(crate) use governor::*;
 pub(crate) use state::*;
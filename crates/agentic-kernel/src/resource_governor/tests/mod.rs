@@ -1,5 +1,5 @@
 /// Unit tests for resource governor and admission logic.
-use super::{ResourceGovernor, ResourceGovernorError};
+use super::{estimate_context_window_cap, ResourceGovernor, ResourceGovernorError};
 use crate::backend::{resolve_driver_for_model, TestExternalEndpointOverrideGuard};
 use crate::config::ResourceGovernorConfig;
 use crate::model_catalog::ResolvedModelTarget;
@@ -208,6 +208,45 @@ fn admission_refuses_when_single_runtime_exceeds_budget() {
     }
 }
 
+#[test]
+fn context_window_cap_fits_kv_cache_in_remaining_finite_budget() {
+    let dir = make_temp_dir("agenticos-resource-governor-ctx-cap");
+    let model_path = write_model_file(&dir, "cap.gguf", 1024 * 1024 * 1024);
+    let config = ResourceGovernorConfig {
+        ram_budget_bytes: 4 * 1024 * 1024 * 1024,
+        vram_budget_bytes: 0,
+        min_ram_headroom_bytes: 0,
+        local_runtime_ram_scale: 1.0,
+        local_runtime_ram_overhead_bytes: 0,
+        ..ResourceGovernorConfig::default()
+    };
+
+    // 3 GiB liberi dopo i pesi, 1 MiB di KV cache per token.
+    assert_eq!(
+        estimate_context_window_cap(&model_path, "cap", Some(1024 * 1024), &config),
+        Some(3072)
+    );
+    assert_eq!(
+        estimate_context_window_cap(&model_path, "cap", None, &config),
+        None
+    );
+    assert_eq!(
+        estimate_context_window_cap(
+            &model_path,
+            "cap",
+            Some(1024 * 1024),
+            &ResourceGovernorConfig {
+                ram_budget_bytes: 0,
+                vram_budget_bytes: 0,
+                ..config
+            }
+        ),
+        None
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
 fn local_target(model_path: &Path, tokenizer_path: &Path) -> ResolvedModelTarget {
     let driver = resolve_driver_for_model(PromptFamily::Mistral, None, Some("external-llamacpp"))
         .expect("resolve driver");
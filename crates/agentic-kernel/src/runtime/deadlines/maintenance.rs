@@ -8,6 +8,10 @@ impl DeadlineReason {
             Self::Checkpoint => "checkpoint",
             Self::ScheduledJob => "scheduled_job",
             Self::ScheduledJobTimeout => "scheduled_job_timeout",
+            Self::JobEventPoll => "job_event_poll",
+            Self::McpResourcePoll => "mcp_resource_poll",
+            Self::ToolManifestPoll => "tool_manifest_poll",
+            Self::WallClockQuota => "wall_clock_quota",
         }
     }
 }
@@ -8,6 +8,10 @@ pub enum DeadlineReason {
     Checkpoint,
     ScheduledJob,
     ScheduledJobTimeout,
+    JobEventPoll,
+    McpResourcePoll,
+    ToolManifestPoll,
+    WallClockQuota,
 }
 
 #[derive(Debug, Clone, Copy)]
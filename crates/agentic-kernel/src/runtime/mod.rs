@@ -9,7 +9,7 @@ pub(crate) use syscalls::parser as actions;
 
 use mio::{Poll, Token};
 use std::collections::{HashMap, HashSet};
-use std::sync::mpsc;
+use std::sync::{mpsc, Mutex};
 
 use agentic_control_models::KernelEvent;
 
@@ -23,6 +23,7 @@ use crate::scheduler::ProcessScheduler;
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 use crate::tool_registry::ToolRegistry;
+use crate::tools::SyscallRateMap;
 use crate::transport::Client;
 
 pub(crate) use output::assistant_output::should_emit_session_finished;
@@ -30,7 +31,8 @@ pub(crate) use output::assistant_turn_store::AssistantTurnRuntimeBoundary;
 pub(crate) use output::drain_worker_results;
 pub(crate) use output::TurnAssemblySnapshot;
 pub(crate) use output::TurnAssemblyStore;
-use process::{checkout_active_processes, handle_finished_processes};
+pub(crate) use process::apply_quota_check;
+use process::{checkout_active_processes, enforce_spending_budgets, handle_finished_processes};
 use syscalls::{drain_syscall_results, SyscallCmd, SyscallCompletion};
 use workflows::advance_orchestrator;
 
@@ -62,6 +64,7 @@ pub fn run_engine_tick(
     cmd_tx: &mpsc::Sender<InferenceCmd>,
     result_rx: &mpsc::Receiver<InferenceResult>,
     syscall_cmd_tx: &mpsc::Sender<SyscallCmd>,
+    syscall_rates: &Mutex<SyscallRateMap>,
     syscall_result_rx: &mpsc::Receiver<SyscallCompletion>,
     session_registry: &mut SessionRegistry,
     storage: &mut StorageService,
@@ -123,7 +126,9 @@ pub fn run_engine_tick(
                             continue;
                         }
 
-                        if let Err(err) = engine.load_process_context_slot(event.pid, path) {
+                        let loaded = engine.load_process_context_slot(event.pid, path);
+                        memory.discard_staged_snapshot(path);
+                        if let Err(err) = loaded {
                             pending_events.push(KernelEvent::WorkspaceChanged {
                                 pid: event.pid,
                                 reason: "swap_restore_failed".to_string(),
@@ -212,6 +217,7 @@ pub fn run_engine_tick(
         orchestrator,
         result_rx,
         syscall_cmd_tx,
+        syscall_rates,
         session_registry,
         storage,
         turn_assembly,
@@ -221,6 +227,15 @@ pub fn run_engine_tick(
         tool_registry,
     );
 
+    enforce_spending_budgets(
+        runtime_registry,
+        scheduler,
+        session_registry,
+        storage,
+        in_flight,
+        pending_events,
+    );
+
     report.finished_processes = handle_finished_processes(
         runtime_registry,
         memory,
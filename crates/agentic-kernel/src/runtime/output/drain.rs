@@ -1,6 +1,6 @@
 use mio::{Poll, Token};
 use std::collections::{HashMap, HashSet};
-use std::sync::mpsc;
+use std::sync::{mpsc, Mutex};
 
 use agentic_control_models::KernelEvent;
 
@@ -18,12 +18,13 @@ use crate::services::process_runtime::release_process_resources_with_session;
 use crate::session::SessionRegistry;
 use crate::storage::{current_timestamp_ms, StorageService};
 use crate::tool_registry::ToolRegistry;
+use crate::tools::SyscallRateMap;
 use crate::transport::Client;
 
 use crate::runtime::syscalls::SyscallCmd;
 
 use super::stream_path::handle_stream_chunk;
-use super::token_path::{handle_token_result, persist_accounting_event};
+use super::token_path::{handle_token_result, persist_accounting_event, record_worker_compaction};
 use super::turn_assembly::TurnAssemblyStore;
 
 #[allow(clippy::too_many_arguments)]
@@ -36,6 +37,7 @@ pub(crate) fn drain_worker_results(
     orchestrator: &mut Orchestrator,
     result_rx: &mpsc::Receiver<InferenceResult>,
     syscall_cmd_tx: &mpsc::Sender<SyscallCmd>,
+    syscall_rates: &Mutex<SyscallRateMap>,
     session_registry: &mut SessionRegistry,
     storage: &mut StorageService,
     turn_assembly: &mut TurnAssemblyStore,
@@ -75,7 +77,18 @@ pub(crate) fn drain_worker_results(
                 finished,
                 finish_reason,
                 accounting_event,
+                compaction,
             } => {
+                if let Some(compaction) = compaction {
+                    record_worker_compaction(
+                        storage,
+                        session_registry,
+                        runtime_registry,
+                        scheduler,
+                        pid,
+                        compaction,
+                    );
+                }
                 let checked_out = scheduler.take_checked_out_process(pid);
                 handle_token_result(
                     runtime_registry,
@@ -93,6 +106,7 @@ pub(crate) fn drain_worker_results(
                     finish_reason,
                     accounting_event,
                     syscall_cmd_tx,
+                    syscall_rates,
                     session_registry,
                     storage,
                     turn_assembly,
@@ -120,9 +134,11 @@ pub(crate) fn drain_worker_results(
                     storage,
                     session_registry,
                     runtime_registry,
+                    scheduler,
                     pid,
                     &runtime_id,
                     accounting_event,
+                    "inference_step",
                 );
                 tracing::error!(pid, %error, "Process error from worker, killing");
                 match maybe_capture_automatic_core_dump(
@@ -7,30 +7,35 @@ use mio::{Poll, Token};
 use crate::backend::InferenceFinishReason;
 use crate::core_dump::record_live_debug_checkpoint;
 use crate::diagnostics::audit::{self, AuditContext};
+use crate::inference_worker::InferenceCompaction;
 use crate::memory::NeuralMemory;
 use crate::orchestrator::Orchestrator;
 use crate::process::{AgentProcess, ProcessState};
 use crate::runtimes::RuntimeRegistry;
-use crate::scheduler::{CheckedOutProcessMetadata, ProcessScheduler};
+use crate::scheduler::{CheckedOutProcessMetadata, ProcessScheduler, SpendRecord};
 use crate::services::accounting::{AccountingEventStatus, BackendAccountingEvent};
 use crate::services::process_runtime::kill_managed_process_with_session;
 use crate::session::SessionRegistry;
-use crate::storage::{StorageService, StoredAccountingEvent};
+use crate::storage::{current_timestamp_ms, StorageService, StoredAccountingEvent};
 use crate::tool_registry::ToolRegistry;
 use crate::transport::Client;
 
+use super::super::process::apply_quota_check;
 use super::assistant_output::emit_assistant_timeline_output;
 use super::assistant_turn_store::AssistantTurnRuntimeBoundary;
 use super::turn_assembly::TurnAssemblyStore;
 use super::turn_completion::emit_turn_completion_events;
 
+#[allow(clippy::too_many_arguments)]
 pub(super) fn persist_accounting_event(
     storage: &mut StorageService,
     session_registry: &SessionRegistry,
     runtime_registry: &RuntimeRegistry,
+    scheduler: &mut ProcessScheduler,
     pid: u64,
     runtime_id: &str,
     accounting_event: Option<BackendAccountingEvent>,
+    request_kind: &str,
 ) {
     let Some(event) = accounting_event else {
         return;
@@ -55,7 +60,7 @@ pub(super) fn persist_accounting_event(
             .and_then(|runtime| runtime.remote_model_id.clone())
             .or(event.model_id.clone())
             .or_else(|| descriptor.map(|runtime| runtime.logical_model_id.clone())),
-        request_kind: "inference_step".to_string(),
+        request_kind: request_kind.to_string(),
         status: event.status,
         request_count: event.request_count,
         stream: event.stream,
@@ -65,6 +70,18 @@ pub(super) fn persist_accounting_event(
         error_code: event.error_code,
         error_message: event.error_message,
     };
+    // La spesa entra nel ledger dei budget anche se la persistenza fallisce.
+    scheduler.record_budget_spend(
+        Some(pid),
+        SpendRecord {
+            at_ms: current_timestamp_ms(),
+            provider_id: record.provider_id.clone(),
+            model_id: record.model_id.clone(),
+            session_id: record.session_id.clone(),
+            orchestration_id: None,
+            cost_usd: record.estimated_cost_usd,
+        },
+    );
 
     if let Err(err) = storage.record_accounting_event(&record) {
         tracing::warn!(
@@ -131,6 +148,64 @@ pub(super) fn persist_accounting_event(
     }
 }
 
+/// Usage and audit trail of a context compaction run on the inference worker.
+pub(super) fn record_worker_compaction(
+    storage: &mut StorageService,
+    session_registry: &SessionRegistry,
+    runtime_registry: &RuntimeRegistry,
+    scheduler: &mut ProcessScheduler,
+    pid: u64,
+    compaction: InferenceCompaction,
+) {
+    let Some(runtime_id) = runtime_registry
+        .runtime_id_for_pid(pid)
+        .map(ToString::to_string)
+    else {
+        return;
+    };
+    for accounting_event in compaction.accounting_events {
+        persist_accounting_event(
+            storage,
+            session_registry,
+            runtime_registry,
+            scheduler,
+            pid,
+            &runtime_id,
+            Some(accounting_event),
+            "context_summary",
+        );
+    }
+    let Some(event) = compaction.event else {
+        return;
+    };
+    tracing::info!(
+        pid,
+        strategy = event.strategy.label(),
+        dropped_segments = event.dropped_segments,
+        dropped_tokens = event.dropped_tokens,
+        tokens_after = event.tokens_after,
+        reason = %event.reason,
+        "CONTEXT: worker compaction applied"
+    );
+    audit::record(
+        storage,
+        audit::PROCESS_CONTEXT_SUMMARIZED,
+        format!(
+            "dropped_segments={} dropped_tokens={} tokens_after={} summarizer={} fallback={}",
+            event.dropped_segments,
+            event.dropped_tokens,
+            event.tokens_after,
+            event.summarizer.as_deref().unwrap_or("heuristic"),
+            event.summary_fallback.as_deref().unwrap_or("none")
+        ),
+        AuditContext::for_process(
+            session_registry.session_id_for_pid(pid),
+            pid,
+            Some(&runtime_id),
+        ),
+    );
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(super) fn handle_token_result(
     runtime_registry: &mut RuntimeRegistry,
@@ -148,6 +223,7 @@ pub(super) fn handle_token_result(
     finish_reason: Option<InferenceFinishReason>,
     accounting_event: Option<BackendAccountingEvent>,
     syscall_cmd_tx: &mpsc::Sender<crate::runtime::syscalls::SyscallCmd>,
+    syscall_rates: &std::sync::Mutex<crate::tools::SyscallRateMap>,
     session_registry: &mut SessionRegistry,
     storage: &mut StorageService,
     turn_assembly: &mut TurnAssemblyStore,
@@ -168,13 +244,19 @@ pub(super) fn handle_token_result(
         );
         return;
     };
+    // Solo i backend remoti producono eventi di accounting.
+    let remote_usage = accounting_event
+        .as_ref()
+        .map(|event| (event.input_tokens, event.estimated_cost_usd));
     persist_accounting_event(
         storage,
         session_registry,
         runtime_registry,
+        scheduler,
         pid,
         &runtime_id,
         accounting_event,
+        "inference_step",
     );
 
     let owner_id_from_checkout = runtime_checked_out
@@ -333,6 +415,7 @@ pub(super) fn handle_token_result(
                     &content,
                     turn_assembly,
                     syscall_cmd_tx,
+                    syscall_rates,
                     session_registry,
                     storage,
                     pending_events,
@@ -355,6 +438,12 @@ pub(super) fn handle_token_result(
     }
 
     let token_quota_exceeded = (0..generated_tokens).any(|_| scheduler.record_token(pid));
+    let quota_check = match remote_usage {
+        Some((input_tokens, cost_usd)) => {
+            scheduler.record_remote_usage(pid, input_tokens, cost_usd)
+        }
+        None => scheduler.check_wall_clock(pid),
+    };
 
     if let crate::runtime::syscalls::SyscallDispatchOutcome::Spawned(spawned_pid) = syscall_dispatch
     {
@@ -376,6 +465,8 @@ pub(super) fn handle_token_result(
                 process.termination_reason = Some("token_quota_reached".to_string());
             }
         }
+    } else if let Some(engine) = runtime_registry.engine_mut(&runtime_id) {
+        apply_quota_check(engine, storage, pid, quota_check, audit_context.clone());
     }
 
     emit_turn_completion_events(
@@ -0,0 +1,159 @@
+use std::collections::HashSet;
+
+use agentic_control_models::KernelEvent;
+
+use crate::config::BudgetAction;
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::process::ProcessState;
+use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::{BudgetExhaustion, BudgetSubject, ProcessScheduler};
+use crate::session::SessionRegistry;
+use crate::storage::{current_timestamp_ms, StorageService};
+use crate::tools::human_tools::build_approval_request;
+
+use super::waiting_states::is_checkout_eligible;
+
+/// Admission check of the spending budgets before the next remote step.
+///
+/// Runs over the remote processes that checkout would dispatch: an exhausted
+/// budget fails the process, parks it on a human approval or queues it for
+/// the kernel to move onto a local model.
+pub(crate) fn enforce_spending_budgets(
+    runtime_registry: &mut RuntimeRegistry,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &SessionRegistry,
+    storage: &mut StorageService,
+    in_flight: &HashSet<u64>,
+    pending_events: &mut Vec<KernelEvent>,
+) {
+    if !scheduler.budgets_enabled() {
+        return;
+    }
+    let now_ms = current_timestamp_ms();
+
+    for pid in runtime_registry.all_active_pids() {
+        if in_flight.contains(&pid) || scheduler.budget_reroute_pending(pid) {
+            continue;
+        }
+        let Some(runtime_id) = runtime_registry
+            .runtime_id_for_pid(pid)
+            .map(ToString::to_string)
+        else {
+            continue;
+        };
+        let Some(descriptor) = runtime_registry.descriptor(&runtime_id) else {
+            continue;
+        };
+        let session_id = session_registry.session_id_for_pid(pid);
+        let subject = BudgetSubject {
+            provider_id: descriptor.provider_id.as_deref(),
+            model_id: descriptor.remote_model_id.as_deref(),
+            session_id,
+        };
+        let exhaustion = {
+            let Some(process) = runtime_registry
+                .engine(&runtime_id)
+                .and_then(|engine| engine.processes.get(&pid))
+            else {
+                continue;
+            };
+            if !is_checkout_eligible(&process.state)
+                || process.model.backend_class().as_str() != "remote_stateless"
+            {
+                continue;
+            }
+            scheduler.check_budgets(pid, &subject, now_ms)
+        };
+        let Some(exhaustion) = exhaustion else {
+            continue;
+        };
+
+        tracing::warn!(
+            pid,
+            budget = %exhaustion.budget_id,
+            spent_usd = exhaustion.spent_usd,
+            limit_usd = exhaustion.limit_usd,
+            action = exhaustion.action.as_str(),
+            "BUDGET: spending budget exhausted before remote step"
+        );
+        let audit_context = AuditContext::for_process(session_id, pid, Some(&runtime_id));
+        audit::record(
+            storage,
+            audit::PROCESS_BUDGET_EXHAUSTED,
+            format!(
+                "budget={} scope={} key={} window={} spent=${:.6} limit=${:.6} action={}",
+                exhaustion.budget_id,
+                exhaustion.scope.as_str(),
+                exhaustion.scope_key.as_deref().unwrap_or("-"),
+                exhaustion.window.as_str(),
+                exhaustion.spent_usd,
+                exhaustion.limit_usd,
+                exhaustion.action.as_str()
+            ),
+            audit_context,
+        );
+        pending_events.push(budget_exhausted_event(
+            pid,
+            session_id.map(ToString::to_string),
+            &exhaustion,
+        ));
+
+        let Some(process) = runtime_registry
+            .engine_mut(&runtime_id)
+            .and_then(|engine| engine.processes.get_mut(&pid))
+        else {
+            continue;
+        };
+        match exhaustion.action {
+            BudgetAction::Fail => {
+                process.state = ProcessState::Finished;
+                process.termination_reason = Some("budget_exhausted".to_string());
+            }
+            BudgetAction::AwaitApproval => {
+                let request = build_approval_request(
+                    format!(
+                        "Spending budget '{}' is exhausted. Continue on the remote model?",
+                        exhaustion.budget_id
+                    ),
+                    Some(format!(
+                        "spent ${:.4} of ${:.4} ({} window)",
+                        exhaustion.spent_usd,
+                        exhaustion.limit_usd,
+                        exhaustion.window.as_str()
+                    )),
+                );
+                scheduler.request_budget_approval(pid, &request.request_id, &exhaustion.budget_id);
+                process.set_pending_human_request(request);
+                process.state = ProcessState::WaitingForHumanInput;
+                pending_events.push(KernelEvent::WorkspaceChanged {
+                    pid,
+                    reason: "human_input_requested".to_string(),
+                });
+            }
+            BudgetAction::RouteLocal => {
+                scheduler.queue_budget_reroute(pid);
+            }
+        }
+        pending_events.push(KernelEvent::LobbyChanged {
+            reason: "budget_exhausted".to_string(),
+        });
+    }
+}
+
+fn budget_exhausted_event(
+    pid: u64,
+    session_id: Option<String>,
+    exhaustion: &BudgetExhaustion,
+) -> KernelEvent {
+    KernelEvent::BudgetExhausted {
+        pid,
+        session_id,
+        budget_id: exhaustion.budget_id.clone(),
+        scope: exhaustion.scope.as_str().to_string(),
+        scope_key: exhaustion.scope_key.clone(),
+        window: exhaustion.window.as_str().to_string(),
+        spent_usd: exhaustion.spent_usd,
+        limit_usd: exhaustion.limit_usd,
+        action: exhaustion.action.as_str().to_string(),
+    }
+}
@@ -5,7 +5,7 @@ use crate::diagnostics::audit::{self, AuditContext};
 use crate::inference_worker::InferenceCmd;
 use crate::runtime::TurnAssemblyStore;
 use crate::runtimes::RuntimeRegistry;
-use crate::scheduler::{CheckedOutProcessMetadata, ProcessScheduler};
+use crate::scheduler::{CheckedOutProcessMetadata, FairnessScope, ProcessScheduler};
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 
@@ -21,13 +21,45 @@ pub(crate) fn checkout_active_processes(
     storage: &mut StorageService,
 ) -> usize {
     let active_pids = runtime_registry.all_active_pids();
+    if scheduler.fair_share_config().scope != FairnessScope::Process {
+        for &pid in &active_pids {
+            let owner_id = runtime_registry
+                .runtime_id_for_pid(pid)
+                .and_then(|runtime_id| runtime_registry.engine(runtime_id))
+                .and_then(|engine| engine.processes.get(&pid))
+                .map(|process| process.owner_id);
+            if let Some(owner_id) = owner_id {
+                scheduler.assign_fairness_group(
+                    pid,
+                    owner_id,
+                    session_registry.session_id_for_pid(pid),
+                );
+            }
+        }
+    }
+    let runnable_pids = active_pids
+        .iter()
+        .copied()
+        .filter(|pid| {
+            in_flight.contains(pid)
+                || runtime_registry
+                    .runtime_id_for_pid(*pid)
+                    .and_then(|runtime_id| runtime_registry.engine(runtime_id))
+                    .and_then(|engine| engine.processes.get(pid))
+                    .is_some_and(|process| is_checkout_eligible(&process.state))
+        })
+        .collect::<Vec<_>>();
+    scheduler.update_runnable(&runnable_pids);
     let ordered_pids = scheduler.scheduling_order(&active_pids);
     let mut checked_out_count = 0usize;
 
     for pid in ordered_pids {
-        if in_flight.contains(&pid) {
+        if in_flight.contains(&pid) || scheduler.budget_reroute_pending(pid) {
             continue;
         }
+        if !scheduler.has_dispatch_capacity(in_flight.len()) {
+            break;
+        }
         let Some(runtime_id) = runtime_registry
             .runtime_id_for_pid(pid)
             .map(ToString::to_string)
@@ -44,7 +76,15 @@ pub(crate) fn checkout_active_processes(
                 engine.processes.insert(pid, process);
                 continue;
             }
-            if let Some(event) = process.enforce_context_budget() {
+            // Con un riassuntore a modello la compattazione gira sul worker,
+            // subito prima dello step.
+            let compact_on_worker = process.defers_compaction_to_worker();
+            if compact_on_worker {
+                tracing::debug!(
+                    pid,
+                    "CONTEXT: summary compaction deferred to inference worker"
+                );
+            } else if let Some(event) = process.enforce_context_budget() {
                 tracing::info!(
                     pid,
                     strategy = event.strategy.label(),
@@ -103,6 +143,7 @@ pub(crate) fn checkout_active_processes(
                 );
             }
             in_flight.insert(pid);
+            scheduler.record_dispatch(pid);
             checked_out_count = checked_out_count.saturating_add(1);
             let rendered_prompt = turn_assembly.render_inference_prompt(
                 pid,
@@ -114,6 +155,7 @@ pub(crate) fn checkout_active_processes(
                 process: Box::new(process),
                 rendered_prompt: rendered_prompt.full_prompt,
                 resident_prompt_suffix: rendered_prompt.resident_prompt_suffix,
+                compact_context: compact_on_worker,
                 eos_token_id: eos,
                 eot_token_id: eot,
             });
@@ -1,4 +1,9 @@
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::engine::LLMEngine;
+use crate::process::ProcessState;
 use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::QuotaCheck;
+use crate::storage::StorageService;
 
 pub(super) fn termination_reason_for_pid(runtime_registry: &RuntimeRegistry, pid: u64) -> String {
     runtime_registry
@@ -8,3 +13,50 @@ pub(super) fn termination_reason_for_pid(runtime_registry: &RuntimeRegistry, pid
         .and_then(|process| process.termination_reason.clone())
         .unwrap_or_else(|| "completed".to_string())
 }
+
+/// Act on a quota check: inject the soft warning into the process or stop it
+/// with the dimension's termination reason. Returns `true` when stopped.
+pub(crate) fn apply_quota_check(
+    engine: &mut LLMEngine,
+    storage: &mut StorageService,
+    pid: u64,
+    check: QuotaCheck,
+    audit_context: AuditContext,
+) -> bool {
+    match check {
+        QuotaCheck::Within => false,
+        QuotaCheck::Warning { dimension, .. } => {
+            let Some(message) = check.warning_message() else {
+                return false;
+            };
+            if let Err(err) = engine.inject_context(pid, &engine.format_system_message(&message)) {
+                tracing::warn!(pid, %err, "SCHEDULER: failed to inject quota warning");
+            }
+            audit::record(
+                storage,
+                audit::PROCESS_QUOTA_WARNING,
+                format!("dimension={} {}", dimension.as_str(), message),
+                audit_context,
+            );
+            false
+        }
+        QuotaCheck::Exceeded { dimension } => {
+            tracing::warn!(
+                pid,
+                dimension = dimension.as_str(),
+                "SCHEDULER: process quota exceeded — terminating process"
+            );
+            if let Some(process) = engine.processes.get_mut(&pid) {
+                process.state = ProcessState::Finished;
+                process.termination_reason = Some(dimension.termination_reason().to_string());
+            }
+            audit::record(
+                storage,
+                audit::PROCESS_QUOTA_EXCEEDED,
+                format!("dimension={}", dimension.as_str()),
+                audit_context,
+            );
+            true
+        }
+    }
+}
@@ -1,7 +1,10 @@
+mod budget;
 pub(crate) mod checkout;
 pub(crate) mod finish;
 mod lifecycle;
 mod waiting_states;
 
+pub(crate) use budget::enforce_spending_budgets;
 pub(crate) use checkout::checkout_active_processes;
 pub(crate) use finish::handle_finished_processes;
+pub(crate) use lifecycle::apply_quota_check;
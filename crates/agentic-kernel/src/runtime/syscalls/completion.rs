@@ -17,7 +17,8 @@ use crate::services::process_runtime::kill_managed_process_with_session;
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 
-use super::invocation_events::emit_invocation_updated;
+use super::super::process::apply_quota_check;
+use super::invocation_events::{emit_invocation_updated, emit_tool_invocation_finished};
 use super::tool_history::complete_tool_invocation_from_outcome;
 use super::worker::SyscallCompletion;
 
@@ -185,14 +186,27 @@ pub(crate) fn drain_syscall_results(
                     );
                     continue;
                 };
+                let quota_check = scheduler.record_tool_time(
+                    pid,
+                    u64::try_from(completion.outcome.duration_ms).unwrap_or(u64::MAX),
+                );
                 match engine.inject_context(
                     pid,
                     &engine
                         .format_system_message(&format!("Output:\n{}", completion.outcome.output)),
                 ) {
                     Ok(()) => {
+                        let quota_stopped = apply_quota_check(
+                            engine,
+                            storage,
+                            pid,
+                            quota_check,
+                            audit_context.clone(),
+                        );
                         if let Some(process) = engine.processes.get_mut(&pid) {
-                            process.state = ProcessState::Ready;
+                            if !quota_stopped {
+                                process.state = ProcessState::Ready;
+                            }
                             if let Err(err) = record_live_debug_checkpoint(
                                 storage,
                                 session_registry,
@@ -227,17 +241,17 @@ pub(crate) fn drain_syscall_results(
                             pid,
                             reason: "syscall_completed".to_string(),
                         });
-                        emit_invocation_updated(
+                        emit_tool_invocation_finished(
                             pending_events,
                             pid,
                             &completion.tool_call_id,
-                            InvocationKind::Tool,
                             &completion.command,
                             if completion.outcome.success {
                                 InvocationStatus::Completed
                             } else {
                                 InvocationStatus::Failed
                             },
+                            completion.outcome.cache_hit,
                         );
                     }
                     Err(err) => {
@@ -270,11 +284,16 @@ pub(crate) fn drain_syscall_results(
                     storage,
                     spec,
                     format!(
-                        "tool_call_id={} command={} caller={} transport=text duration_ms={} detail={}{}{}",
+                        "tool_call_id={} command={} caller={} transport=text duration_ms={}{} detail={}{}{}",
                         completion.tool_call_id,
                         completion.command,
                         completion.caller.as_str(),
                         completion.outcome.duration_ms,
+                        if completion.outcome.cache_hit {
+                            " cache_hit=true"
+                        } else {
+                            ""
+                        },
                         completion.outcome.output,
                         error_kind_audit_suffix(completion.outcome.error_kind.as_deref()),
                         mcp_audit_suffix(completion.outcome.output_json.as_ref())
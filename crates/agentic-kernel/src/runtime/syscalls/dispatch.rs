@@ -1,4 +1,4 @@
-use std::sync::mpsc;
+use std::sync::{mpsc, Mutex};
 
 use serde_json::json;
 
@@ -22,6 +22,7 @@ use crate::tool_registry::ToolRegistry;
 use crate::tools::invocation::{
     PathGrantAccessMode, ProcessPathGrant, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
 };
+use crate::tools::SyscallRateMap;
 
 use super::human::{dispatch_mcp_tool_approval_request, dispatch_native_human_input_request};
 use super::ids::{next_action_call_id, next_tool_call_id};
@@ -29,6 +30,7 @@ use super::invocation_events::emit_invocation_updated;
 use super::ipc::{
     dispatch_ack_action, dispatch_receive_action, dispatch_send_action, non_empty_input_str,
 };
+use super::memory::native_memory_completion;
 use super::parser::{self, ActionInvocation, ActionName};
 use super::replay::replay_stubbed_completion;
 use super::tool_history::{
@@ -86,6 +88,7 @@ pub(crate) fn dispatch_process_syscall(
     content: &str,
     turn_assembly: &TurnAssemblyStore,
     syscall_cmd_tx: &mpsc::Sender<SyscallCmd>,
+    syscall_rates: &Mutex<SyscallRateMap>,
     session_registry: &mut SessionRegistry,
     storage: &mut StorageService,
     pending_events: &mut Vec<KernelEvent>,
@@ -334,6 +337,20 @@ pub(crate) fn dispatch_process_syscall(
         }
         let queued_command =
             replay_stubbed_completion(scheduler, pid, &tool_call_id, content, &caller)
+                .or_else(|| {
+                    native_memory_completion(
+                        pid,
+                        &tool_call_id,
+                        content,
+                        &caller,
+                        &permissions,
+                        tool_registry,
+                        orchestrator,
+                        session_registry,
+                        storage,
+                        syscall_rates,
+                    )
+                })
                 .map(|completion| SyscallCmd::ReplayCompletion { completion })
                 .unwrap_or_else(|| SyscallCmd::Execute {
                     pid,
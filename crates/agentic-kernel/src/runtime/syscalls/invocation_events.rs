@@ -15,6 +15,29 @@ pub(super) fn emit_invocation_updated(
             kind,
             command: command.into(),
             status,
+            cache_hit: false,
+        },
+    });
+}
+
+/// Terminal update of a tool invocation, flagging outputs served from the
+/// tool result cache.
+pub(super) fn emit_tool_invocation_finished(
+    pending_events: &mut Vec<KernelEvent>,
+    pid: u64,
+    invocation_id: impl Into<String>,
+    command: impl Into<String>,
+    status: InvocationStatus,
+    cache_hit: bool,
+) {
+    pending_events.push(KernelEvent::InvocationUpdated {
+        pid,
+        invocation: InvocationEvent {
+            invocation_id: invocation_id.into(),
+            kind: InvocationKind::Tool,
+            command: command.into(),
+            status,
+            cache_hit,
         },
     });
 }
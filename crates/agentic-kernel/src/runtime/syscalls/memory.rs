@@ -0,0 +1,106 @@
+use std::sync::Mutex;
+
+use crate::orchestrator::Orchestrator;
+use crate::session::SessionRegistry;
+use crate::storage::StorageService;
+use crate::tool_registry::{ToolRegistry, ToolSource};
+use crate::tools::dispatcher::authorize_invocation;
+use crate::tools::governance::govern_native_tool_execution;
+use crate::tools::invocation::{
+    ProcessPermissionPolicy, ToolCaller, ToolContext, ToolInvocationTransport,
+};
+use crate::tools::memory_tools::{
+    execute_memory_tool, is_memory_tool, workflow_scope_key, MemoryScopeContext,
+};
+use crate::tools::{SysCallOutcome, SyscallRateMap};
+
+use super::worker::SyscallCompletion;
+
+/// Serve the builtin memory tools on the event loop, where storage and the
+/// session provenance live. Rate limiting and the tool audit log go through
+/// the same governance as the worker; the completion is then replayed through
+/// the worker so history and context injection follow the usual path.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn native_memory_completion(
+    pid: u64,
+    tool_call_id: &str,
+    content: &str,
+    caller: &ToolCaller,
+    permissions: &ProcessPermissionPolicy,
+    tool_registry: &ToolRegistry,
+    orchestrator: &Orchestrator,
+    session_registry: &SessionRegistry,
+    storage: &mut StorageService,
+    syscall_rates: &Mutex<SyscallRateMap>,
+) -> Option<SyscallCompletion> {
+    let invocation = crate::tools::parser::parse_text_invocation(content.trim()).ok()?;
+    let entry = tool_registry.resolve_invocation_name(&invocation.name)?;
+    if entry.descriptor.source != ToolSource::BuiltIn || !is_memory_tool(&entry.descriptor.name) {
+        return None;
+    }
+
+    let session_id = session_registry.session_id_for_pid(pid).map(str::to_string);
+    let context = ToolContext {
+        pid: Some(pid),
+        session_id: session_id.clone(),
+        caller: caller.clone(),
+        permissions: permissions.clone(),
+        transport: ToolInvocationTransport::Text,
+        call_id: Some(tool_call_id.to_string()),
+    };
+    let execute = || {
+        let entry = authorize_invocation(&invocation, &context, tool_registry)?;
+        let mut invocation = invocation.clone();
+        invocation.name = entry.descriptor.name.clone();
+        let config = &crate::config::kernel_config().memory.long_term;
+        let mut scopes = MemoryScopeContext::for_kernel(config);
+        scopes.session_id = session_id;
+        scopes.turn_id = session_registry.active_turn_id_for_pid(pid);
+        scopes.pid = Some(pid);
+        if let Some(binding) = orchestrator.task_binding_for_pid(pid) {
+            if let Some(orchestration) = orchestrator.get(binding.orch_id) {
+                scopes.agent = orchestration
+                    .tasks
+                    .get(&binding.task_id)
+                    .and_then(|task| task.role.clone())
+                    .or_else(|| Some(binding.task_id.clone()));
+                scopes.workflow = Some(workflow_scope_key(
+                    orchestration
+                        .tasks
+                        .values()
+                        .map(|task| (task.id.as_str(), task.role.as_deref())),
+                ));
+            }
+        }
+        execute_memory_tool(storage, &invocation, &scopes, config)
+    };
+    let mut rate_map = syscall_rates.lock().ok()?;
+    let result = govern_native_tool_execution(
+        &invocation,
+        &context,
+        tool_registry,
+        pid,
+        &mut rate_map,
+        execute,
+    );
+
+    let outcome = SysCallOutcome {
+        output: result.output,
+        success: result.success,
+        duration_ms: result.duration_ms,
+        should_kill_process: result.should_kill_process,
+        output_json: result.output_json,
+        warnings: result.warnings,
+        error_kind: result.error_kind,
+        effects: result.effects,
+        cache_hit: result.cache_hit,
+    };
+
+    Some(SyscallCompletion {
+        pid,
+        tool_call_id: tool_call_id.to_string(),
+        command: content.to_string(),
+        caller: caller.clone(),
+        outcome,
+    })
+}
@@ -4,6 +4,7 @@ mod human;
 mod ids;
 mod invocation_events;
 mod ipc;
+mod memory;
 pub(crate) mod parser;
 mod replay;
 mod tool_history;
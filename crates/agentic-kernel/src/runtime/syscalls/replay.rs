@@ -55,5 +55,6 @@ fn missing_replay_stub_outcome(command: &str) -> SysCallOutcome {
         warnings: vec![format!("replay_stub_missing_command={}", command.trim())],
         error_kind: Some("replay_stub_missing".to_string()),
         effects: Vec::new(),
+        cache_hit: false,
     }
 }
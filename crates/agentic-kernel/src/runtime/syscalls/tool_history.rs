@@ -16,6 +16,7 @@ pub(crate) struct ToolInvocationCompletionData {
     pub(crate) effects: Vec<Value>,
     pub(crate) duration_ms: Option<u128>,
     pub(crate) kill: bool,
+    pub(crate) cache_hit: bool,
 }
 
 pub(crate) fn record_tool_invocation_dispatched(
@@ -68,6 +69,7 @@ pub(crate) fn complete_tool_invocation_from_outcome(
             effects: outcome.effects.clone(),
             duration_ms: Some(outcome.duration_ms),
             kill: outcome.should_kill_process,
+            cache_hit: outcome.cache_hit,
         },
     )
 }
@@ -112,6 +114,7 @@ pub(crate) fn complete_tool_invocation(
             effect_json,
             duration_ms: completion.duration_ms,
             kill: completion.kill,
+            cache_hit: completion.cache_hit,
         })
         .map_err(|err| err.to_string())
 }
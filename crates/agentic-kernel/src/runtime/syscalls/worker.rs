@@ -74,6 +74,7 @@ pub(crate) fn spawn_syscall_worker(
                                 warnings: Vec::new(),
                                 error_kind: Some("worker_unavailable".to_string()),
                                 effects: Vec::new(),
+                                cache_hit: false,
                             },
                         };
                         if result_tx
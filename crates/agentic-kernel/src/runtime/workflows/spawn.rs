@@ -126,7 +126,7 @@ pub(super) fn spawn_orchestrator_request(
                 priority: ProcessPriority::Normal,
                 lifecycle_policy: ProcessLifecyclePolicy::Ephemeral,
                 context_policy: Some(effective_context_policy),
-                quota_override: None,
+                quota_override: req.quota_override(),
             },
         )
     };
@@ -142,6 +142,7 @@ pub(super) fn spawn_orchestrator_request(
                     "ORCHESTRATOR: failed to register spawned pid"
                 );
             }
+            scheduler.bind_budget_orchestration(pid, req.orch_id);
             record_spawn_start(
                 storage,
                 orchestrator,
@@ -0,0 +1,375 @@
+//! Spending budgets on remote inference.
+//!
+//! The ledger keeps the cost of recent remote requests in memory, seeded from
+//! `accounting_events` at boot, and answers before every remote step whether
+//! a budget covering the process is already spent. Budgets apply globally,
+//! per provider, model, session or orchestration, over daily, monthly or
+//! rolling windows.
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
+
+use chrono::{Datelike, TimeZone, Utc};
+
+use crate::config::{BudgetAction, BudgetScope, BudgetWindow, BudgetsConfig};
+
+use super::ProcessScheduler;
+
+/// Cost of one remote request, as charged to the budgets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpendRecord {
+    pub at_ms: i64,
+    pub provider_id: Option<String>,
+    pub model_id: Option<String>,
+    pub session_id: Option<String>,
+    pub orchestration_id: Option<u64>,
+    pub cost_usd: f64,
+}
+
+/// Provider, model and session a remote step would be charged to.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BudgetSubject<'a> {
+    pub provider_id: Option<&'a str>,
+    pub model_id: Option<&'a str>,
+    pub session_id: Option<&'a str>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetExhaustion {
+    pub budget_id: String,
+    pub scope: BudgetScope,
+    /// Provider, model, session or orchestration the spend was summed over.
+    pub scope_key: Option<String>,
+    pub window: BudgetWindow,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+    pub action: BudgetAction,
+}
+
+/// Current spend of one budget, for STATUS.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BudgetState {
+    pub budget_id: String,
+    pub scope: BudgetScope,
+    pub target: Option<String>,
+    pub scope_key: Option<String>,
+    pub window: BudgetWindow,
+    pub window_started_at_ms: i64,
+    pub spent_usd: f64,
+    pub limit_usd: f64,
+    pub on_exhausted: BudgetAction,
+}
+
+#[derive(Debug, Clone)]
+struct BudgetLimit {
+    id: String,
+    scope: BudgetScope,
+    target: Option<String>,
+    window: BudgetWindow,
+    rolling_ms: i64,
+    max_usd: f64,
+    action: BudgetAction,
+}
+
+impl BudgetLimit {
+    fn window_start_ms(&self, now_ms: i64) -> i64 {
+        match self.window {
+            BudgetWindow::Rolling => now_ms.saturating_sub(self.rolling_ms),
+            BudgetWindow::Daily | BudgetWindow::Monthly => {
+                let Some(now) = Utc.timestamp_millis_opt(now_ms).single() else {
+                    return now_ms;
+                };
+                let mut day = now.date_naive();
+                if self.window == BudgetWindow::Monthly {
+                    day = day.with_day(1).unwrap_or(day);
+                }
+                day.and_hms_opt(0, 0, 0)
+                    .map(|start| start.and_utc().timestamp_millis())
+                    .unwrap_or(now_ms)
+            }
+        }
+    }
+
+    /// The value spend is grouped by for `subject`; `None` when the budget
+    /// does not cover it, `Some(None)` for budgets with a single account.
+    fn scope_key(
+        &self,
+        subject: &BudgetSubject<'_>,
+        orchestration: Option<u64>,
+    ) -> Option<Option<String>> {
+        let value = match self.scope {
+            BudgetScope::Global => return Some(None),
+            BudgetScope::Provider => subject.provider_id.map(ToString::to_string),
+            BudgetScope::Model => subject.model_id.map(ToString::to_string),
+            BudgetScope::Session => subject.session_id.map(ToString::to_string),
+            BudgetScope::Orchestration => orchestration.map(|id| id.to_string()),
+        }?;
+        match self.target.as_deref() {
+            Some(target) if target == value => Some(None),
+            Some(_) => None,
+            None => Some(Some(value)),
+        }
+    }
+
+    fn charges(&self, record: &SpendRecord, key: Option<&str>) -> bool {
+        let Some(expected) = key.or(self.target.as_deref()) else {
+            return true;
+        };
+        match self.scope {
+            BudgetScope::Global => true,
+            BudgetScope::Provider => record.provider_id.as_deref() == Some(expected),
+            BudgetScope::Model => record.model_id.as_deref() == Some(expected),
+            BudgetScope::Session => record.session_id.as_deref() == Some(expected),
+            BudgetScope::Orchestration => record
+                .orchestration_id
+                .is_some_and(|id| id.to_string() == expected),
+        }
+    }
+
+    fn record_key(&self, record: &SpendRecord) -> Option<String> {
+        match self.scope {
+            BudgetScope::Global => None,
+            BudgetScope::Provider => record.provider_id.clone(),
+            BudgetScope::Model => record.model_id.clone(),
+            BudgetScope::Session => record.session_id.clone(),
+            BudgetScope::Orchestration => record.orchestration_id.map(|id| id.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct BudgetLedger {
+    limits: Vec<BudgetLimit>,
+    spend: VecDeque<SpendRecord>,
+    orchestrations: HashMap<u64, u64>,
+    /// Budgets a human let a process exceed.
+    approved: HashSet<(u64, String)>,
+    /// Outstanding approval requests: pid -> (request id, budget id).
+    pending_approvals: HashMap<u64, (String, String)>,
+    reroutes: BTreeSet<u64>,
+}
+
+impl BudgetLedger {
+    pub(super) fn configure(&mut self, config: &BudgetsConfig) {
+        self.limits = config
+            .limits
+            .iter()
+            .filter_map(|limit| {
+                if !limit.max_usd.is_finite() || limit.max_usd <= 0.0 {
+                    tracing::warn!(
+                        budget = %limit.id,
+                        max_usd = limit.max_usd,
+                        "BUDGET: ignoring budget without a positive max_usd"
+                    );
+                    return None;
+                }
+                let id = if limit.id.trim().is_empty() {
+                    format!(
+                        "{}:{}:{}",
+                        limit.scope.as_str(),
+                        limit.target.as_deref().unwrap_or("*"),
+                        limit.window.as_str()
+                    )
+                } else {
+                    limit.id.trim().to_string()
+                };
+                Some(BudgetLimit {
+                    id,
+                    scope: limit.scope,
+                    target: limit.target.clone(),
+                    window: limit.window,
+                    rolling_ms: i64::try_from(limit.rolling_secs.max(1))
+                        .unwrap_or(i64::MAX / 1_000)
+                        .saturating_mul(1_000),
+                    max_usd: limit.max_usd,
+                    action: limit.on_exhausted.unwrap_or(config.on_exhausted),
+                })
+            })
+            .collect();
+    }
+
+    pub(super) fn is_enabled(&self) -> bool {
+        !self.limits.is_empty()
+    }
+
+    /// Oldest timestamp any configured window can still reach at `now_ms`.
+    pub(super) fn retention_start_ms(&self, now_ms: i64) -> i64 {
+        self.limits
+            .iter()
+            .map(|limit| limit.window_start_ms(now_ms))
+            .min()
+            .unwrap_or(now_ms)
+    }
+
+    pub(super) fn record(&mut self, mut record: SpendRecord, pid: Option<u64>) {
+        if !self.is_enabled() || record.cost_usd <= 0.0 {
+            return;
+        }
+        if record.orchestration_id.is_none() {
+            record.orchestration_id = pid.and_then(|pid| self.orchestrations.get(&pid).copied());
+        }
+        let now_ms = record.at_ms;
+        self.spend.push_back(record);
+        let retention_start = self.retention_start_ms(now_ms);
+        while self
+            .spend
+            .front()
+            .is_some_and(|oldest| oldest.at_ms < retention_start)
+        {
+            self.spend.pop_front();
+        }
+    }
+
+    fn spent(&self, limit: &BudgetLimit, key: Option<&str>, now_ms: i64) -> f64 {
+        let start = limit.window_start_ms(now_ms);
+        self.spend
+            .iter()
+            .filter(|record| record.at_ms >= start && limit.charges(record, key))
+            .map(|record| record.cost_usd)
+            .sum()
+    }
+
+    /// First budget covering `subject` that is already spent, skipping the
+    /// ones a human approved for `pid`.
+    pub(super) fn check(
+        &self,
+        pid: u64,
+        subject: &BudgetSubject<'_>,
+        now_ms: i64,
+    ) -> Option<BudgetExhaustion> {
+        let orchestration = self.orchestrations.get(&pid).copied();
+        self.limits.iter().find_map(|limit| {
+            if self.approved.contains(&(pid, limit.id.clone())) {
+                return None;
+            }
+            let key = limit.scope_key(subject, orchestration)?;
+            let spent_usd = self.spent(limit, key.as_deref(), now_ms);
+            (spent_usd >= limit.max_usd).then(|| BudgetExhaustion {
+                budget_id: limit.id.clone(),
+                scope: limit.scope,
+                scope_key: key.or_else(|| limit.target.clone()),
+                window: limit.window,
+                spent_usd,
+                limit_usd: limit.max_usd,
+                action: limit.action,
+            })
+        })
+    }
+
+    pub(super) fn states(&self, now_ms: i64) -> Vec<BudgetState> {
+        let mut states = Vec::new();
+        for limit in &self.limits {
+            let window_started_at_ms = limit.window_start_ms(now_ms);
+            let keys: Vec<Option<String>> =
+                if limit.scope == BudgetScope::Global || limit.target.is_some() {
+                    vec![limit.target.clone()]
+                } else {
+                    self.spend
+                        .iter()
+                        .filter(|record| record.at_ms >= window_started_at_ms)
+                        .filter_map(|record| limit.record_key(record))
+                        .collect::<BTreeSet<_>>()
+                        .into_iter()
+                        .map(Some)
+                        .collect()
+                };
+            for key in keys {
+                states.push(BudgetState {
+                    budget_id: limit.id.clone(),
+                    scope: limit.scope,
+                    target: limit.target.clone(),
+                    spent_usd: self.spent(limit, key.as_deref(), now_ms),
+                    scope_key: key,
+                    window: limit.window,
+                    window_started_at_ms,
+                    limit_usd: limit.max_usd,
+                    on_exhausted: limit.action,
+                });
+            }
+        }
+        states
+    }
+
+    pub(super) fn forget(&mut self, pid: u64) {
+        self.orchestrations.remove(&pid);
+        self.approved
+            .retain(|(approved_pid, _)| *approved_pid != pid);
+        self.pending_approvals.remove(&pid);
+        self.reroutes.remove(&pid);
+    }
+}
+
+impl ProcessScheduler {
+    pub fn configure_budgets(&mut self, config: &BudgetsConfig) {
+        self.budgets.configure(config);
+    }
+
+    pub fn budgets_enabled(&self) -> bool {
+        self.budgets.is_enabled()
+    }
+
+    /// Oldest spend the configured windows need, for seeding at boot.
+    pub fn budget_retention_start_ms(&self, now_ms: i64) -> i64 {
+        self.budgets.retention_start_ms(now_ms)
+    }
+
+    pub fn record_budget_spend(&mut self, pid: Option<u64>, record: SpendRecord) {
+        self.budgets.record(record, pid);
+    }
+
+    pub fn bind_budget_orchestration(&mut self, pid: u64, orchestration_id: u64) {
+        self.budgets.orchestrations.insert(pid, orchestration_id);
+    }
+
+    pub fn check_budgets(
+        &self,
+        pid: u64,
+        subject: &BudgetSubject<'_>,
+        now_ms: i64,
+    ) -> Option<BudgetExhaustion> {
+        self.budgets.check(pid, subject, now_ms)
+    }
+
+    pub fn budget_states(&self, now_ms: i64) -> Vec<BudgetState> {
+        self.budgets.states(now_ms)
+    }
+
+    pub fn request_budget_approval(&mut self, pid: u64, request_id: &str, budget_id: &str) {
+        self.budgets
+            .pending_approvals
+            .insert(pid, (request_id.to_string(), budget_id.to_string()));
+    }
+
+    /// Settle the budget approval `request_id` of `pid`. Returns the budget
+    /// id when the request was a budget approval.
+    pub fn resolve_budget_approval(
+        &mut self,
+        pid: u64,
+        request_id: &str,
+        approved: bool,
+    ) -> Option<String> {
+        let (pending_request, budget_id) = self.budgets.pending_approvals.get(&pid)?;
+        if pending_request != request_id {
+            return None;
+        }
+        let budget_id = budget_id.clone();
+        self.budgets.pending_approvals.remove(&pid);
+        if approved {
+            self.budgets.approved.insert((pid, budget_id.clone()));
+        }
+        Some(budget_id)
+    }
+
+    pub fn queue_budget_reroute(&mut self, pid: u64) {
+        self.budgets.reroutes.insert(pid);
+    }
+
+    /// Processes waiting to be moved onto a local model stay off the worker.
+    pub fn budget_reroute_pending(&self, pid: u64) -> bool {
+        self.budgets.reroutes.contains(&pid)
+    }
+
+    pub fn take_budget_reroutes(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.budgets.reroutes)
+            .into_iter()
+            .collect()
+    }
+}
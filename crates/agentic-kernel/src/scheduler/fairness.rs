@@ -0,0 +1,408 @@
+//! Fair-share dispatch ordering.
+//!
+//! Every dispatch charges the process (and its fairness group) a service
+//! cost inversely proportional to its priority weight; processes are then
+//! ordered by accumulated service, so higher priorities get a larger share
+//! of steps without starving lower ones. Time spent waiting earns service
+//! credit (aging), and the `deadline` policy steps processes bound to a
+//! timed run earliest-deadline-first. A process that was blocked (waiting for
+//! input or a tool) rejoins no lower than the least-served runnable process,
+//! so it cannot spend the time it slept as a burst of steps.
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::config::SchedulerConfig;
+
+use super::ProcessPriority;
+
+/// Service charged for one step of a `Low` process; higher priorities pay
+/// proportionally less (`STEP_SERVICE / weight`).
+const STEP_SERVICE: u64 = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedulingPolicy {
+    /// Strict priority, PID as tie-break.
+    Priority,
+    /// Weighted fair queuing with aging.
+    FairShare,
+    /// Earliest deadline first for timed runs, fair share for the rest.
+    Deadline,
+}
+
+impl SchedulingPolicy {
+    pub fn from_str_loose(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "priority" | "strict" => Some(Self::Priority),
+            "fair_share" | "fair" => Some(Self::FairShare),
+            "deadline" => Some(Self::Deadline),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Priority => "priority",
+            Self::FairShare => "fair_share",
+            Self::Deadline => "deadline",
+        }
+    }
+}
+
+/// Which processes share one fair-share account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FairnessScope {
+    Process,
+    Owner,
+    Session,
+}
+
+impl FairnessScope {
+    pub fn from_str_loose(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "process" | "pid" => Some(Self::Process),
+            "owner" => Some(Self::Owner),
+            "session" => Some(Self::Session),
+            _ => None,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Process => "process",
+            Self::Owner => "owner",
+            Self::Session => "session",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FairShareConfig {
+    pub policy: SchedulingPolicy,
+    pub scope: FairnessScope,
+    pub aging_ms: u64,
+    pub max_in_flight: usize,
+}
+
+impl Default for FairShareConfig {
+    fn default() -> Self {
+        Self {
+            policy: SchedulingPolicy::Priority,
+            scope: FairnessScope::Process,
+            aging_ms: 2_000,
+            max_in_flight: 0,
+        }
+    }
+}
+
+impl FairShareConfig {
+    pub fn from_config(config: &SchedulerConfig) -> Self {
+        let defaults = Self::default();
+        let policy = SchedulingPolicy::from_str_loose(&config.policy).unwrap_or_else(|| {
+            tracing::warn!(
+                policy = %config.policy,
+                "SCHEDULER: unknown scheduling policy, using priority"
+            );
+            defaults.policy
+        });
+        let scope = FairnessScope::from_str_loose(&config.fairness_scope).unwrap_or_else(|| {
+            tracing::warn!(
+                scope = %config.fairness_scope,
+                "SCHEDULER: unknown fairness scope, using process"
+            );
+            defaults.scope
+        });
+        Self {
+            policy,
+            scope,
+            aging_ms: config.aging_ms.max(1),
+            max_in_flight: config.max_in_flight,
+        }
+    }
+}
+
+const fn priority_weight(priority: ProcessPriority) -> u64 {
+    match priority {
+        ProcessPriority::Low => 1,
+        ProcessPriority::Normal => 2,
+        ProcessPriority::High => 4,
+        ProcessPriority::Critical => 8,
+    }
+}
+
+const fn priority_index(priority: ProcessPriority) -> usize {
+    match priority {
+        ProcessPriority::Critical => 0,
+        ProcessPriority::High => 1,
+        ProcessPriority::Normal => 2,
+        ProcessPriority::Low => 3,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ProcessShare {
+    virtual_service: u64,
+    dispatches: u64,
+    waiting_since: Instant,
+    group: Option<String>,
+    /// Unix-ms deadline of the timed run the process belongs to.
+    deadline_at_ms: Option<i64>,
+}
+
+/// Accumulated fair-share state, keyed by PID and fairness group.
+#[derive(Debug, Default)]
+pub(super) struct FairShareState {
+    shares: HashMap<u64, ProcessShare>,
+    groups: HashMap<String, u64>,
+    /// Processes that could be stepped at the last `update_runnable`.
+    runnable: HashSet<u64>,
+    dispatches_total: u64,
+    aged_dispatches: u64,
+    deadline_dispatches: u64,
+    dispatches_by_priority: [u64; 4],
+}
+
+/// Read-only fairness metrics for STATUS.
+#[derive(Debug, Clone, Default)]
+pub struct FairnessSnapshot {
+    pub dispatches_total: u64,
+    pub aged_dispatches: u64,
+    pub deadline_dispatches: u64,
+    /// Dispatch counts ordered critical, high, normal, low.
+    pub dispatches_by_priority: [u64; 4],
+    /// Jain's index over the weighted service of tracked processes (1.0 = even).
+    pub fairness_index: f64,
+    pub max_wait_ms: u64,
+    pub groups: Vec<(String, usize, u64)>,
+}
+
+impl FairShareState {
+    /// Start a new process level with the least-served one, so it neither
+    /// jumps ahead of nor waits behind the service others accumulated.
+    pub(super) fn register(&mut self, pid: u64, now: Instant) {
+        let floor = self
+            .shares
+            .iter()
+            .filter(|(other, _)| **other != pid)
+            .map(|(_, share)| share.virtual_service)
+            .min()
+            .unwrap_or(0);
+        self.shares.insert(
+            pid,
+            ProcessShare {
+                virtual_service: floor,
+                dispatches: 0,
+                waiting_since: now,
+                group: None,
+                deadline_at_ms: None,
+            },
+        );
+    }
+
+    pub(super) fn unregister(&mut self, pid: u64) {
+        self.runnable.remove(&pid);
+        let Some(share) = self.shares.remove(&pid) else {
+            return;
+        };
+        if let Some(group) = share.group {
+            let still_used = self
+                .shares
+                .values()
+                .any(|share| share.group.as_deref() == Some(group.as_str()));
+            if !still_used {
+                self.groups.remove(&group);
+            }
+        }
+    }
+
+    pub(super) fn assign_group(&mut self, pid: u64, group: Option<String>) {
+        let floor = self.groups.values().copied().min().unwrap_or(0);
+        let Some(share) = self.shares.get_mut(&pid) else {
+            return;
+        };
+        if let Some(group) = group.as_ref() {
+            self.groups.entry(group.clone()).or_insert(floor);
+        }
+        share.group = group;
+    }
+
+    /// Replace the deadlines of all tracked processes.
+    pub(super) fn set_deadlines(&mut self, deadlines: &HashMap<u64, i64>) {
+        for (pid, share) in &mut self.shares {
+            share.deadline_at_ms = deadlines.get(pid).copied();
+        }
+    }
+
+    pub(super) fn mark_waiting(&mut self, pid: u64, now: Instant) {
+        if let Some(share) = self.shares.get_mut(&pid) {
+            share.waiting_since = now;
+        }
+    }
+
+    /// Replace the set of runnable processes. Those that were blocked since
+    /// the previous call are raised to the minimum service of the processes
+    /// that stayed runnable, and their wait (hence their aging) restarts now.
+    pub(super) fn update_runnable(&mut self, runnable: &[u64], now: Instant) {
+        let runnable = runnable.iter().copied().collect::<HashSet<_>>();
+        let floor = runnable
+            .intersection(&self.runnable)
+            .filter_map(|pid| self.shares.get(pid))
+            .map(|share| share.virtual_service)
+            .min();
+        for pid in runnable.difference(&self.runnable) {
+            let Some(share) = self.shares.get_mut(pid) else {
+                continue;
+            };
+            if let Some(floor) = floor {
+                share.virtual_service = share.virtual_service.max(floor);
+            }
+            share.waiting_since = now;
+        }
+        self.runnable = runnable;
+    }
+
+    fn aging_credit(&self, share: &ProcessShare, aging_ms: u64, now: Instant) -> u64 {
+        let waited_ms = now
+            .saturating_duration_since(share.waiting_since)
+            .as_millis() as u64;
+        (waited_ms / aging_ms.max(1)).saturating_mul(STEP_SERVICE)
+    }
+
+    /// Order `pids` for dispatch under `config`.
+    pub(super) fn order(
+        &self,
+        config: &FairShareConfig,
+        pids: &[(u64, ProcessPriority)],
+        now: Instant,
+    ) -> Vec<u64> {
+        let mut keyed: Vec<_> = pids
+            .iter()
+            .map(|&(pid, priority)| {
+                let share = self.shares.get(&pid);
+                let deadline = share
+                    .filter(|_| config.policy == SchedulingPolicy::Deadline)
+                    .and_then(|share| share.deadline_at_ms)
+                    .unwrap_or(i64::MAX);
+                let (group_service, own_service) = match share {
+                    Some(share) => {
+                        let credit = self.aging_credit(share, config.aging_ms, now);
+                        let group_service = share
+                            .group
+                            .as_ref()
+                            .and_then(|group| self.groups.get(group))
+                            .copied()
+                            .unwrap_or(0);
+                        (
+                            group_service.saturating_sub(credit),
+                            share.virtual_service.saturating_sub(credit),
+                        )
+                    }
+                    None => (0, 0),
+                };
+                (
+                    deadline,
+                    group_service,
+                    own_service,
+                    std::cmp::Reverse(priority),
+                    pid,
+                )
+            })
+            .collect();
+        keyed.sort_unstable();
+        keyed.into_iter().map(|key| key.4).collect()
+    }
+
+    pub(super) fn record_dispatch(
+        &mut self,
+        pid: u64,
+        priority: ProcessPriority,
+        aging_ms: u64,
+        now: Instant,
+    ) {
+        let Some(share) = self.shares.get(&pid) else {
+            return;
+        };
+        let aged = self.aging_credit(share, aging_ms, now) > 0;
+        let cost = STEP_SERVICE / priority_weight(priority);
+        let Some(share) = self.shares.get_mut(&pid) else {
+            return;
+        };
+        share.virtual_service = share.virtual_service.saturating_add(cost);
+        share.dispatches = share.dispatches.saturating_add(1);
+        share.waiting_since = now;
+        if share.deadline_at_ms.is_some() {
+            self.deadline_dispatches = self.deadline_dispatches.saturating_add(1);
+        }
+        if let Some(group) = share.group.as_ref() {
+            if let Some(service) = self.groups.get_mut(group) {
+                *service = service.saturating_add(cost);
+            }
+        }
+        self.dispatches_total = self.dispatches_total.saturating_add(1);
+        if aged {
+            self.aged_dispatches = self.aged_dispatches.saturating_add(1);
+        }
+        let slot = &mut self.dispatches_by_priority[priority_index(priority)];
+        *slot = slot.saturating_add(1);
+    }
+
+    #[cfg(test)]
+    pub(super) fn virtual_service(&self, pid: u64) -> u64 {
+        self.shares
+            .get(&pid)
+            .map_or(0, |share| share.virtual_service)
+    }
+
+    /// `waiting(pid)` tells whether the process is currently waiting for a
+    /// step (as opposed to running on the worker).
+    pub(super) fn snapshot(
+        &self,
+        priority_of: impl Fn(u64) -> ProcessPriority,
+        waiting: impl Fn(u64) -> bool,
+        now: Instant,
+    ) -> FairnessSnapshot {
+        let normalized: Vec<f64> = self
+            .shares
+            .iter()
+            .map(|(pid, share)| share.dispatches as f64 / priority_weight(priority_of(*pid)) as f64)
+            .collect();
+        let sum: f64 = normalized.iter().sum();
+        let sum_sq: f64 = normalized.iter().map(|value| value * value).sum();
+        let fairness_index = if sum_sq > 0.0 {
+            (sum * sum) / (normalized.len() as f64 * sum_sq)
+        } else {
+            1.0
+        };
+        let max_wait_ms = self
+            .shares
+            .iter()
+            .filter(|(pid, _)| waiting(**pid))
+            .map(|(_, share)| {
+                now.saturating_duration_since(share.waiting_since)
+                    .as_millis() as u64
+            })
+            .max()
+            .unwrap_or(0);
+        let mut groups: Vec<(String, usize, u64)> = self
+            .groups
+            .iter()
+            .map(|(group, service)| {
+                let members = self
+                    .shares
+                    .values()
+                    .filter(|share| share.group.as_deref() == Some(group.as_str()))
+                    .count();
+                (group.clone(), members, *service)
+            })
+            .collect();
+        groups.sort();
+        FairnessSnapshot {
+            dispatches_total: self.dispatches_total,
+            aged_dispatches: self.aged_dispatches,
+            deadline_dispatches: self.deadline_dispatches,
+            dispatches_by_priority: self.dispatches_by_priority,
+            fairness_index,
+            max_wait_ms,
+            groups,
+        }
+    }
+}
@@ -7,11 +7,22 @@
 /// - The scheduler does **not** own processes; it maintains metadata (priority,
 ///   quotas, accounting counters) keyed by PID.
 /// - `run_engine_tick` queries `scheduling_order()` to step processes in
-///   descending priority order.
+///   fair-share order (see `fairness`), or strict priority order when the
+///   `priority` policy is configured.
 /// - Quota enforcement is checked *before* each step / syscall.
 use std::collections::{HashMap, VecDeque};
 use std::time::Instant;
 
+mod budget;
+mod fairness;
+mod quota;
+
+use budget::BudgetLedger;
+pub use budget::{BudgetExhaustion, BudgetSubject, SpendRecord};
+use fairness::FairShareState;
+pub use fairness::{FairShareConfig, FairnessScope, FairnessSnapshot, SchedulingPolicy};
+pub use quota::{QuotaCheck, QuotaLimits};
+
 use crate::backend::BackendCapabilities;
 use crate::model_catalog::WorkloadClass;
 use crate::process::{ContextPolicy, ContextState, ContextStatusSnapshot, HumanInputRequest};
@@ -62,6 +73,8 @@ pub struct ProcessQuota {
     pub max_tokens: usize,
     /// Maximum number of syscalls (tool invocations) allowed.
     pub max_syscalls: usize,
+    /// Wall-clock, spend, remote-input and tool-time ceilings.
+    pub limits: QuotaLimits,
 }
 
 impl ProcessQuota {
@@ -72,6 +85,7 @@ impl ProcessQuota {
         Self {
             max_tokens,
             max_syscalls,
+            limits: crate::policy::scheduler_quota_limit_defaults(workload),
         }
     }
 }
@@ -85,6 +99,11 @@ pub struct ResourceAccounting {
     pub syscalls_used: usize,
     pub started_at: Instant,
     pub workload: WorkloadClass,
+    pub cost_usd: f64,
+    pub remote_input_tokens: u64,
+    pub tool_wall_ms: u64,
+    /// Bitset of `QuotaDimension`s that already issued their soft warning.
+    quota_warnings: u8,
 }
 
 impl ResourceAccounting {
@@ -94,6 +113,10 @@ impl ResourceAccounting {
             syscalls_used: 0,
             started_at: Instant::now(),
             workload,
+            cost_usd: 0.0,
+            remote_input_tokens: 0,
+            tool_wall_ms: 0,
+            quota_warnings: 0,
         }
     }
 
@@ -114,6 +137,9 @@ pub struct ProcessSchedulerSnapshot {
     pub syscalls_used: usize,
     pub elapsed_secs: f64,
     pub workload: WorkloadClass,
+    pub cost_usd: f64,
+    pub remote_input_tokens: u64,
+    pub tool_wall_ms: u64,
 }
 
 #[derive(Debug, Clone)]
@@ -213,10 +239,17 @@ pub struct ProcessScheduler {
     restored_processes: HashMap<u64, RestoredProcessMetadata>,
     checked_out_processes: HashMap<u64, CheckedOutProcessMetadata>,
     replay_processes: HashMap<u64, ReplayProcessMetadata>,
+    fair_share_config: FairShareConfig,
+    fair_share: FairShareState,
+    budgets: BudgetLedger,
 }
 
 impl ProcessScheduler {
     pub fn new() -> Self {
+        Self::with_fair_share(FairShareConfig::default())
+    }
+
+    pub fn with_fair_share(fair_share_config: FairShareConfig) -> Self {
         Self {
             priorities: HashMap::new(),
             quotas: HashMap::new(),
@@ -224,6 +257,9 @@ impl ProcessScheduler {
             restored_processes: HashMap::new(),
             checked_out_processes: HashMap::new(),
             replay_processes: HashMap::new(),
+            fair_share_config,
+            fair_share: FairShareState::default(),
+            budgets: BudgetLedger::default(),
         }
     }
 
@@ -240,6 +276,7 @@ impl ProcessScheduler {
         self.restored_processes.remove(&pid);
         self.checked_out_processes.remove(&pid);
         self.replay_processes.remove(&pid);
+        self.fair_share.register(pid, Instant::now());
     }
 
     /// Remove all scheduler state for a process.
@@ -250,6 +287,8 @@ impl ProcessScheduler {
         self.restored_processes.remove(&pid);
         self.checked_out_processes.remove(&pid);
         self.replay_processes.remove(&pid);
+        self.fair_share.unregister(pid);
+        self.budgets.forget(pid);
     }
 
     pub fn clear_restored_processes(&mut self) {
@@ -276,9 +315,11 @@ impl ProcessScheduler {
 
     pub fn clear_checked_out_process(&mut self, pid: u64) {
         self.checked_out_processes.remove(&pid);
+        self.fair_share.mark_waiting(pid, Instant::now());
     }
 
     pub fn take_checked_out_process(&mut self, pid: u64) -> Option<CheckedOutProcessMetadata> {
+        self.fair_share.mark_waiting(pid, Instant::now());
         self.checked_out_processes.remove(&pid)
     }
 
@@ -380,18 +421,88 @@ impl ProcessScheduler {
 
     // ── Scheduling order ────────────────────────────────────────────────
 
-    /// Given a set of active PIDs, return them sorted by descending priority.
-    /// Ties are broken by PID (lower PID goes first — FIFO-ish).
+    /// Given a set of active PIDs, return them in dispatch order.
+    /// Processes with equal service go by descending priority, then PID.
     pub fn scheduling_order(&self, active_pids: &[u64]) -> Vec<u64> {
+        self.scheduling_order_at(active_pids, Instant::now())
+    }
+
+    pub fn scheduling_order_at(&self, active_pids: &[u64], now: Instant) -> Vec<u64> {
         let mut ordered: Vec<(u64, ProcessPriority)> = active_pids
             .iter()
             .map(|&pid| (pid, self.priority(pid)))
             .collect();
+        if self.fair_share_config.policy != SchedulingPolicy::Priority {
+            return self
+                .fair_share
+                .order(&self.fair_share_config, &ordered, now);
+        }
         // Sort descending by priority, then ascending by PID for stability.
         ordered.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
         ordered.into_iter().map(|(pid, _)| pid).collect()
     }
 
+    pub fn fair_share_config(&self) -> &FairShareConfig {
+        &self.fair_share_config
+    }
+
+    /// Whether another process may be queued at the inference worker.
+    pub fn has_dispatch_capacity(&self, in_flight: usize) -> bool {
+        let limit = self.fair_share_config.max_in_flight;
+        limit == 0 || in_flight < limit
+    }
+
+    /// Bind `pid` to the fair-share account of its owner or session, as
+    /// the configured scope requires.
+    pub fn assign_fairness_group(&mut self, pid: u64, owner_id: usize, session_id: Option<&str>) {
+        let group = match self.fair_share_config.scope {
+            FairnessScope::Process => None,
+            FairnessScope::Owner => Some(format!("owner:{owner_id}")),
+            FairnessScope::Session => session_id.map(|session_id| format!("session:{session_id}")),
+        };
+        self.fair_share.assign_group(pid, group);
+    }
+
+    /// Replace the run deadlines (unix ms) used by the `deadline` policy.
+    pub fn set_run_deadlines(&mut self, deadlines: &HashMap<u64, i64>) {
+        self.fair_share.set_deadlines(deadlines);
+    }
+
+    /// Report which processes can be stepped (ready, or already on the
+    /// worker); see `FairShareState::update_runnable`.
+    pub fn update_runnable(&mut self, runnable_pids: &[u64]) {
+        self.update_runnable_at(runnable_pids, Instant::now());
+    }
+
+    pub fn update_runnable_at(&mut self, runnable_pids: &[u64], now: Instant) {
+        self.fair_share.update_runnable(runnable_pids, now);
+    }
+
+    /// Charge one dispatched step to the fair-share account of `pid`.
+    pub fn record_dispatch(&mut self, pid: u64) {
+        self.record_dispatch_at(pid, Instant::now());
+    }
+
+    pub fn record_dispatch_at(&mut self, pid: u64, now: Instant) {
+        let priority = self.priority(pid);
+        self.fair_share
+            .record_dispatch(pid, priority, self.fair_share_config.aging_ms, now);
+    }
+
+    /// Used in tests to verify fair-share accounting.
+    #[cfg(test)]
+    pub fn virtual_service(&self, pid: u64) -> u64 {
+        self.fair_share.virtual_service(pid)
+    }
+
+    pub fn fairness_snapshot(&self) -> FairnessSnapshot {
+        self.fair_share.snapshot(
+            |pid| self.priority(pid),
+            |pid| !self.checked_out_processes.contains_key(&pid),
+            Instant::now(),
+        )
+    }
+
     // ── Snapshot ────────────────────────────────────────────────────────
 
     /// Return a sorted list of all PIDs tracked by the scheduler.
@@ -414,6 +525,9 @@ impl ProcessScheduler {
             syscalls_used: acc.syscalls_used,
             elapsed_secs: acc.elapsed_secs(),
             workload: acc.workload,
+            cost_usd: acc.cost_usd,
+            remote_input_tokens: acc.remote_input_tokens,
+            tool_wall_ms: acc.tool_wall_ms,
         })
     }
 
@@ -450,5 +564,5 @@ impl ProcessScheduler {
 // ── Tests ───────────────────────────────────────────────────────────────
 
 #[cfg(test)]
-#[path = "tests/scheduler.rs"]
+#[path = "tests/mod.rs"]
 mod tests;
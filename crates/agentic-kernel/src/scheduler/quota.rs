@@ -0,0 +1,290 @@
+//! Quota dimensions beyond generated tokens and syscalls: wall-clock time,
+//! remote spend, input tokens sent to remote providers and tool wall time.
+//!
+//! Each dimension warns the process once when usage crosses the configured
+//! warning ratio, then stops it at the limit.
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{ProcessScheduler, ResourceAccounting};
+
+/// Optional quota ceilings; `None` leaves a dimension unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+pub struct QuotaLimits {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_wall_clock_secs: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_remote_input_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_wall_ms: Option<u64>,
+}
+
+impl QuotaLimits {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// `other` wins wherever it sets a limit.
+    pub fn overridden_by(self, other: QuotaLimits) -> Self {
+        Self {
+            max_wall_clock_secs: other.max_wall_clock_secs.or(self.max_wall_clock_secs),
+            max_cost_usd: other.max_cost_usd.or(self.max_cost_usd),
+            max_remote_input_tokens: other
+                .max_remote_input_tokens
+                .or(self.max_remote_input_tokens),
+            max_tool_wall_ms: other.max_tool_wall_ms.or(self.max_tool_wall_ms),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        for (label, value) in [
+            ("max_wall_clock_secs", self.max_wall_clock_secs),
+            ("max_remote_input_tokens", self.max_remote_input_tokens),
+            ("max_tool_wall_ms", self.max_tool_wall_ms),
+        ] {
+            if value == Some(0) {
+                return Err(format!("{label} must be greater than zero when specified"));
+            }
+        }
+        if self
+            .max_cost_usd
+            .is_some_and(|cost| !cost.is_finite() || cost <= 0.0)
+        {
+            return Err("max_cost_usd must be a positive amount when specified".to_string());
+        }
+        Ok(())
+    }
+
+    /// Apply one `key=value` pair of a SET_QUOTA payload; `none` clears the
+    /// limit. Returns `Ok(false)` for keys this struct does not own.
+    pub fn apply_setting(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        let value = value.trim();
+        let clear = value.eq_ignore_ascii_case("none");
+        let parse_u64 = |value: &str| {
+            value
+                .parse::<u64>()
+                .map_err(|_| format!("{key} expects a whole number or 'none'"))
+        };
+        match key {
+            "max_wall_clock_secs" => {
+                self.max_wall_clock_secs = if clear { None } else { Some(parse_u64(value)?) };
+            }
+            "max_remote_input_tokens" => {
+                self.max_remote_input_tokens = if clear { None } else { Some(parse_u64(value)?) };
+            }
+            "max_tool_wall_ms" => {
+                self.max_tool_wall_ms = if clear { None } else { Some(parse_u64(value)?) };
+            }
+            "max_cost_usd" => {
+                self.max_cost_usd = if clear {
+                    None
+                } else {
+                    Some(
+                        value
+                            .parse::<f64>()
+                            .map_err(|_| "max_cost_usd expects an amount or 'none'".to_string())?,
+                    )
+                };
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuotaDimension {
+    WallClock,
+    Cost,
+    RemoteInputTokens,
+    ToolWallTime,
+}
+
+impl QuotaDimension {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::WallClock => "wall_clock",
+            Self::Cost => "cost",
+            Self::RemoteInputTokens => "remote_input_tokens",
+            Self::ToolWallTime => "tool_wall_time",
+        }
+    }
+
+    /// Termination reason recorded when the dimension stops a process.
+    pub const fn termination_reason(self) -> &'static str {
+        match self {
+            Self::WallClock => "wall_clock_quota_reached",
+            Self::Cost => "cost_quota_reached",
+            Self::RemoteInputTokens => "remote_input_quota_reached",
+            Self::ToolWallTime => "tool_time_quota_reached",
+        }
+    }
+
+    const fn bit(self) -> u8 {
+        match self {
+            Self::WallClock => 1,
+            Self::Cost => 2,
+            Self::RemoteInputTokens => 4,
+            Self::ToolWallTime => 8,
+        }
+    }
+
+    fn format_amount(self, value: f64) -> String {
+        match self {
+            Self::WallClock => format!("{value:.0}s"),
+            Self::Cost => format!("${value:.4}"),
+            Self::RemoteInputTokens => format!("{value:.0} input tokens"),
+            Self::ToolWallTime => format!("{value:.0}ms of tool time"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuotaCheck {
+    Within,
+    /// First crossing of the warning ratio; the process keeps running.
+    Warning {
+        dimension: QuotaDimension,
+        used: f64,
+        limit: f64,
+    },
+    Exceeded {
+        dimension: QuotaDimension,
+    },
+}
+
+impl QuotaCheck {
+    /// System message injected into the process on a soft warning.
+    pub fn warning_message(&self) -> Option<String> {
+        let Self::Warning {
+            dimension,
+            used,
+            limit,
+        } = *self
+        else {
+            return None;
+        };
+        Some(format!(
+            "Quota warning: {} of {} used ({}). The process is stopped when the limit is reached; wrap up the task now.",
+            dimension.format_amount(used),
+            dimension.format_amount(limit),
+            dimension.as_str(),
+        ))
+    }
+
+    /// The more severe of two checks; the first wins ties.
+    fn or(self, other: QuotaCheck) -> QuotaCheck {
+        match (self, other) {
+            (Self::Exceeded { .. }, _) => self,
+            (_, Self::Exceeded { .. }) => other,
+            (Self::Warning { .. }, _) => self,
+            _ => other,
+        }
+    }
+}
+
+fn evaluate(
+    accounting: &mut ResourceAccounting,
+    dimension: QuotaDimension,
+    used: f64,
+    limit: Option<f64>,
+) -> QuotaCheck {
+    let Some(limit) = limit else {
+        return QuotaCheck::Within;
+    };
+    if used >= limit {
+        return QuotaCheck::Exceeded { dimension };
+    }
+    if accounting.quota_warnings & dimension.bit() == 0
+        && used >= limit * crate::policy::quota_warning_ratio()
+    {
+        accounting.quota_warnings |= dimension.bit();
+        return QuotaCheck::Warning {
+            dimension,
+            used,
+            limit,
+        };
+    }
+    QuotaCheck::Within
+}
+
+impl ProcessScheduler {
+    fn quota_limits(&self, pid: u64) -> QuotaLimits {
+        self.quotas
+            .get(&pid)
+            .map(|quota| quota.limits)
+            .unwrap_or_default()
+    }
+
+    /// Account one remote request (input tokens and estimated spend) and
+    /// check it together with the wall-clock limit.
+    pub fn record_remote_usage(
+        &mut self,
+        pid: u64,
+        input_tokens: u64,
+        cost_usd: f64,
+    ) -> QuotaCheck {
+        let limits = self.quota_limits(pid);
+        let Some(acc) = self.accounting.get_mut(&pid) else {
+            return QuotaCheck::Within;
+        };
+        acc.remote_input_tokens = acc.remote_input_tokens.saturating_add(input_tokens);
+        acc.cost_usd += cost_usd.max(0.0);
+        let cost = evaluate(acc, QuotaDimension::Cost, acc.cost_usd, limits.max_cost_usd);
+        let input = evaluate(
+            acc,
+            QuotaDimension::RemoteInputTokens,
+            acc.remote_input_tokens as f64,
+            limits.max_remote_input_tokens.map(|limit| limit as f64),
+        );
+        cost.or(input).or(self.check_wall_clock(pid))
+    }
+
+    /// Account the wall time of one completed tool call.
+    pub fn record_tool_time(&mut self, pid: u64, duration_ms: u64) -> QuotaCheck {
+        let limits = self.quota_limits(pid);
+        let Some(acc) = self.accounting.get_mut(&pid) else {
+            return QuotaCheck::Within;
+        };
+        acc.tool_wall_ms = acc.tool_wall_ms.saturating_add(duration_ms);
+        let tool = evaluate(
+            acc,
+            QuotaDimension::ToolWallTime,
+            acc.tool_wall_ms as f64,
+            limits.max_tool_wall_ms.map(|limit| limit as f64),
+        );
+        tool.or(self.check_wall_clock(pid))
+    }
+
+    pub fn check_wall_clock(&mut self, pid: u64) -> QuotaCheck {
+        let limits = self.quota_limits(pid);
+        let Some(acc) = self.accounting.get_mut(&pid) else {
+            return QuotaCheck::Within;
+        };
+        let elapsed = acc.elapsed_secs();
+        evaluate(
+            acc,
+            QuotaDimension::WallClock,
+            elapsed,
+            limits.max_wall_clock_secs.map(|limit| limit as f64),
+        )
+    }
+
+    /// When each pid's wall-clock limit next needs checking: the warning point
+    /// until the soft warning fires, then the limit itself.
+    pub fn wall_clock_quota_deadlines(&self) -> Vec<(u64, Instant)> {
+        let ratio = crate::policy::quota_warning_ratio();
+        self.accounting
+            .iter()
+            .filter_map(|(pid, acc)| {
+                let limit = self.quota_limits(*pid).max_wall_clock_secs? as f64;
+                let warned = acc.quota_warnings & QuotaDimension::WallClock.bit() != 0;
+                let secs = if warned { limit } else { limit * ratio };
+                Some((*pid, acc.started_at + Duration::from_secs_f64(secs)))
+            })
+            .collect()
+    }
+}
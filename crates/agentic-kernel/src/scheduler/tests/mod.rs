@@ -0,0 +1,645 @@
+use super::*;
+
+#[test]
+fn register_sets_defaults() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::Fast, ProcessPriority::Normal);
+
+    assert_eq!(sched.priority(1), ProcessPriority::Normal);
+    let q = sched.quota(1).expect("quota exists");
+    assert_eq!(q.max_tokens, 512);
+    assert_eq!(q.max_syscalls, 2);
+    assert_eq!(sched.tokens_generated(1), 0);
+}
+
+#[test]
+fn unregister_cleans_up() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::High);
+    sched.unregister(1);
+    assert!(sched.quota(1).is_none());
+    assert!(sched.snapshot(1).is_none());
+}
+
+#[test]
+fn record_token_enforces_quota() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::Fast, ProcessPriority::Normal);
+    // Fast workload max_tokens = 512
+    for _ in 0..511 {
+        assert!(!sched.record_token(1));
+    }
+    // 512th token -> quota exceeded
+    assert!(sched.record_token(1));
+}
+
+#[test]
+fn record_syscall_enforces_quota() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::Fast, ProcessPriority::Normal);
+    // Fast workload max_syscalls = 2
+    assert!(!sched.record_syscall(1));
+    assert!(sched.record_syscall(1));
+}
+
+#[test]
+fn scheduling_order_respects_priority() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Low);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Critical);
+    sched.register(3, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(4, WorkloadClass::General, ProcessPriority::High);
+
+    let order = sched.scheduling_order(&[1, 2, 3, 4]);
+    assert_eq!(order, vec![2, 4, 3, 1]);
+}
+
+#[test]
+fn scheduling_order_tie_breaks_by_pid() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(5, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(3, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(7, WorkloadClass::General, ProcessPriority::Normal);
+
+    let order = sched.scheduling_order(&[5, 3, 7]);
+    assert_eq!(order, vec![3, 5, 7]);
+}
+
+#[test]
+fn set_priority_changes_order() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Normal);
+
+    assert!(sched.set_priority(2, ProcessPriority::High));
+    let order = sched.scheduling_order(&[1, 2]);
+    assert_eq!(order, vec![2, 1]);
+}
+
+#[test]
+fn set_quota_override() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::Fast, ProcessPriority::Normal);
+
+    let custom = ProcessQuota {
+        max_tokens: 100,
+        max_syscalls: 3,
+        limits: QuotaLimits::default(),
+    };
+    assert!(sched.set_quota(1, custom));
+
+    // max_syscalls = 3: first two calls ok, third exceeds
+    assert!(!sched.record_syscall(1));
+    assert!(!sched.record_syscall(1));
+    assert!(sched.record_syscall(1));
+}
+
+#[test]
+fn workload_defaults_vary() {
+    let fast = ProcessQuota::defaults_for(WorkloadClass::Fast);
+    let reasoning = ProcessQuota::defaults_for(WorkloadClass::Reasoning);
+    assert!(reasoning.max_tokens > fast.max_tokens);
+}
+
+#[test]
+fn priority_from_str_loose() {
+    assert_eq!(
+        ProcessPriority::from_str_loose("HIGH"),
+        Some(ProcessPriority::High)
+    );
+    assert_eq!(
+        ProcessPriority::from_str_loose("low"),
+        Some(ProcessPriority::Low)
+    );
+    assert_eq!(ProcessPriority::from_str_loose("unknown"), None);
+}
+
+#[test]
+fn snapshot_returns_correct_data() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(42, WorkloadClass::Code, ProcessPriority::High);
+    sched.record_token(42);
+    sched.record_token(42);
+    sched.record_syscall(42);
+
+    let snap = sched.snapshot(42).expect("snapshot should exist");
+    assert_eq!(snap.tokens_generated, 2);
+    assert_eq!(snap.syscalls_used, 1);
+    assert_eq!(snap.priority, ProcessPriority::High);
+    assert!(matches!(snap.workload, WorkloadClass::Code));
+}
+
+#[test]
+fn summary_counts_priorities() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(2, WorkloadClass::General, ProcessPriority::High);
+    sched.register(3, WorkloadClass::General, ProcessPriority::High);
+
+    let s = sched.summary();
+    assert!(s.contains("scheduler_tracked=3"));
+    assert!(s.contains("priority_high=2"));
+    assert!(s.contains("priority_normal=1"));
+}
+
+#[test]
+fn registered_pids_returns_sorted() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(5, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(1, WorkloadClass::Code, ProcessPriority::High);
+    sched.register(3, WorkloadClass::Fast, ProcessPriority::Low);
+
+    let pids = sched.registered_pids();
+    assert_eq!(pids, vec![1, 3, 5]);
+
+    sched.unregister(3);
+    let pids = sched.registered_pids();
+    assert_eq!(pids, vec![1, 5]);
+}
+
+fn fair_share(policy: SchedulingPolicy, scope: FairnessScope) -> ProcessScheduler {
+    ProcessScheduler::with_fair_share(FairShareConfig {
+        policy,
+        scope,
+        aging_ms: 1_000,
+        max_in_flight: 0,
+    })
+}
+
+/// Dispatch `rounds` single steps, always to the head of the order.
+fn dispatch_rounds(sched: &mut ProcessScheduler, pids: &[u64], rounds: usize) -> Vec<u64> {
+    let now = std::time::Instant::now();
+    (0..rounds)
+        .map(|_| {
+            let pid = sched.scheduling_order_at(pids, now)[0];
+            sched.record_dispatch_at(pid, now);
+            pid
+        })
+        .collect()
+}
+
+#[test]
+fn fair_share_gives_low_priority_a_weighted_share() {
+    let mut sched = fair_share(SchedulingPolicy::FairShare, FairnessScope::Process);
+    sched.register(1, WorkloadClass::General, ProcessPriority::High);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Low);
+
+    let picks = dispatch_rounds(&mut sched, &[1, 2], 20);
+    let low = picks.iter().filter(|&&pid| pid == 2).count();
+    assert_eq!(low, 4, "low gets one step for every four high steps");
+    assert_eq!(sched.virtual_service(1), sched.virtual_service(2));
+
+    let mut strict = fair_share(SchedulingPolicy::Priority, FairnessScope::Process);
+    strict.register(1, WorkloadClass::General, ProcessPriority::High);
+    strict.register(2, WorkloadClass::General, ProcessPriority::Low);
+    let picks = dispatch_rounds(&mut strict, &[1, 2], 20);
+    assert!(picks.iter().all(|&pid| pid == 1));
+}
+
+#[test]
+fn aging_moves_a_long_waiting_process_ahead() {
+    let mut sched = fair_share(SchedulingPolicy::FairShare, FairnessScope::Process);
+    sched.register(1, WorkloadClass::General, ProcessPriority::Critical);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Low);
+    let t0 = std::time::Instant::now();
+    sched.record_dispatch_at(2, t0);
+
+    let later = t0 + std::time::Duration::from_secs(5);
+    for _ in 0..8 {
+        sched.record_dispatch_at(1, later);
+    }
+    assert_eq!(sched.virtual_service(1), sched.virtual_service(2));
+    assert_eq!(sched.scheduling_order_at(&[1, 2], later), vec![2, 1]);
+    sched.record_dispatch_at(2, later);
+
+    let snapshot = sched.fairness_snapshot();
+    assert_eq!(snapshot.dispatches_total, 10);
+    // Both the first critical step and the last low step had waited 5s.
+    assert_eq!(snapshot.aged_dispatches, 2);
+    assert_eq!(snapshot.dispatches_by_priority, [8, 0, 0, 2]);
+}
+
+#[test]
+fn process_waking_up_rejoins_at_the_least_served_runnable_process() {
+    let mut sched = fair_share(SchedulingPolicy::FairShare, FairnessScope::Process);
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(3, WorkloadClass::General, ProcessPriority::Normal);
+    let now = std::time::Instant::now();
+    sched.update_runnable_at(&[1, 2, 3], now);
+
+    // pid 3 waits for input while the others keep stepping.
+    sched.update_runnable_at(&[1, 2], now);
+    let picks = dispatch_rounds(&mut sched, &[1, 2], 10);
+    assert_eq!(picks.iter().filter(|&&pid| pid == 1).count(), 5);
+    assert_eq!(sched.virtual_service(3), 0);
+
+    sched.update_runnable_at(&[1, 2, 3], now);
+    assert_eq!(sched.virtual_service(3), sched.virtual_service(1));
+    let picks = dispatch_rounds(&mut sched, &[1, 2, 3], 6);
+    for pid in [1, 2, 3] {
+        assert_eq!(picks.iter().filter(|&&pick| pick == pid).count(), 2);
+    }
+}
+
+#[test]
+fn owner_scope_splits_steps_between_owners_not_processes() {
+    let mut sched = fair_share(SchedulingPolicy::FairShare, FairnessScope::Owner);
+    for pid in [1, 2, 3] {
+        sched.register(pid, WorkloadClass::General, ProcessPriority::Normal);
+    }
+    sched.assign_fairness_group(1, 7, None);
+    sched.assign_fairness_group(2, 7, None);
+    sched.assign_fairness_group(3, 9, Some("sess-1"));
+
+    let picks = dispatch_rounds(&mut sched, &[1, 2, 3], 8);
+    assert_eq!(picks.iter().filter(|&&pid| pid == 3).count(), 4);
+
+    let snapshot = sched.fairness_snapshot();
+    assert_eq!(
+        snapshot.groups,
+        vec![
+            ("owner:7".to_string(), 2, 16),
+            ("owner:9".to_string(), 1, 16)
+        ]
+    );
+    assert!(snapshot.fairness_index < 1.0);
+}
+
+#[test]
+fn deadline_policy_steps_timed_runs_first() {
+    let mut sched = fair_share(SchedulingPolicy::Deadline, FairnessScope::Process);
+    sched.register(1, WorkloadClass::General, ProcessPriority::High);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Low);
+    sched.register(3, WorkloadClass::General, ProcessPriority::Low);
+    sched.set_run_deadlines(&HashMap::from([(2, 5_000), (3, 4_000)]));
+
+    assert_eq!(sched.scheduling_order(&[1, 2, 3]), vec![3, 2, 1]);
+
+    let mut fair = fair_share(SchedulingPolicy::FairShare, FairnessScope::Process);
+    fair.register(1, WorkloadClass::General, ProcessPriority::High);
+    fair.register(2, WorkloadClass::General, ProcessPriority::Low);
+    fair.set_run_deadlines(&HashMap::from([(2, 5_000)]));
+    assert_eq!(fair.scheduling_order(&[1, 2]), vec![1, 2]);
+}
+
+#[test]
+fn max_in_flight_bounds_dispatch_capacity() {
+    let unbounded = ProcessScheduler::new();
+    assert!(unbounded.has_dispatch_capacity(64));
+
+    let bounded = ProcessScheduler::with_fair_share(FairShareConfig {
+        max_in_flight: 2,
+        ..FairShareConfig::default()
+    });
+    assert!(bounded.has_dispatch_capacity(1));
+    assert!(!bounded.has_dispatch_capacity(2));
+    assert_eq!(
+        SchedulingPolicy::from_str_loose("Deadline"),
+        Some(SchedulingPolicy::Deadline)
+    );
+    assert_eq!(FairnessScope::from_str_loose("bogus"), None);
+}
+
+fn with_limits(sched: &mut ProcessScheduler, pid: u64, limits: QuotaLimits) {
+    let quota = ProcessQuota {
+        limits,
+        ..*sched.quota(pid).expect("quota exists")
+    };
+    assert!(sched.set_quota(pid, quota));
+}
+
+#[test]
+fn cost_quota_warns_once_then_stops() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    with_limits(
+        &mut sched,
+        1,
+        QuotaLimits {
+            max_cost_usd: Some(1.0),
+            ..QuotaLimits::default()
+        },
+    );
+
+    assert_eq!(sched.record_remote_usage(1, 100, 0.5), QuotaCheck::Within);
+    let warning = sched.record_remote_usage(1, 100, 0.35);
+    assert!(matches!(
+        warning,
+        QuotaCheck::Warning {
+            dimension: quota::QuotaDimension::Cost,
+            ..
+        }
+    ));
+    assert!(warning
+        .warning_message()
+        .is_some_and(|message| message.contains("$1.0000")));
+    // Already warned: the next request below the limit stays silent.
+    assert_eq!(sched.record_remote_usage(1, 100, 0.1), QuotaCheck::Within);
+    let exceeded = sched.record_remote_usage(1, 100, 0.1);
+    assert_eq!(
+        exceeded,
+        QuotaCheck::Exceeded {
+            dimension: quota::QuotaDimension::Cost
+        }
+    );
+
+    let snap = sched.snapshot(1).expect("snapshot");
+    assert_eq!(snap.remote_input_tokens, 400);
+    assert!((snap.cost_usd - 1.05).abs() < 1e-9);
+}
+
+#[test]
+fn tool_time_and_input_token_quotas_stop_the_process() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    with_limits(
+        &mut sched,
+        1,
+        QuotaLimits {
+            max_remote_input_tokens: Some(1_000),
+            max_tool_wall_ms: Some(500),
+            ..QuotaLimits::default()
+        },
+    );
+
+    assert_eq!(sched.record_tool_time(1, 100), QuotaCheck::Within);
+    assert_eq!(
+        sched.record_tool_time(1, 400),
+        QuotaCheck::Exceeded {
+            dimension: quota::QuotaDimension::ToolWallTime
+        }
+    );
+    assert_eq!(
+        sched.record_remote_usage(1, 1_200, 0.0),
+        QuotaCheck::Exceeded {
+            dimension: quota::QuotaDimension::RemoteInputTokens
+        }
+    );
+}
+
+#[test]
+fn unlimited_dimensions_never_trigger() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    assert_eq!(
+        sched.record_remote_usage(1, u64::MAX, 1e9),
+        QuotaCheck::Within
+    );
+    assert_eq!(sched.record_tool_time(1, u64::MAX), QuotaCheck::Within);
+    assert_eq!(sched.check_wall_clock(1), QuotaCheck::Within);
+}
+
+#[test]
+fn wall_clock_deadline_moves_from_warning_point_to_limit() {
+    let mut sched = ProcessScheduler::new();
+    sched.register(1, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(2, WorkloadClass::General, ProcessPriority::Normal);
+    with_limits(
+        &mut sched,
+        1,
+        QuotaLimits {
+            max_wall_clock_secs: Some(100),
+            ..QuotaLimits::default()
+        },
+    );
+
+    let deadlines = sched.wall_clock_quota_deadlines();
+    assert_eq!(deadlines.len(), 1, "unlimited pids have no deadline");
+    let (pid, warning_at) = deadlines[0];
+    assert_eq!(pid, 1);
+    let started_at = sched.accounting[&1].started_at;
+    let ratio = crate::policy::quota_warning_ratio();
+    assert_eq!(
+        warning_at,
+        started_at + std::time::Duration::from_secs_f64(100.0 * ratio)
+    );
+
+    sched.accounting.get_mut(&1).expect("accounting").started_at =
+        std::time::Instant::now() - std::time::Duration::from_secs_f64(100.0 * ratio);
+    assert!(matches!(
+        sched.check_wall_clock(1),
+        QuotaCheck::Warning {
+            dimension: quota::QuotaDimension::WallClock,
+            ..
+        }
+    ));
+    let started_at = sched.accounting[&1].started_at;
+    assert_eq!(
+        sched.wall_clock_quota_deadlines(),
+        vec![(1, started_at + std::time::Duration::from_secs(100))]
+    );
+}
+
+#[test]
+fn quota_limits_parse_set_quota_settings() {
+    let mut limits = QuotaLimits::default();
+    assert_eq!(limits.apply_setting("max_cost_usd", "2.5"), Ok(true));
+    assert_eq!(limits.apply_setting("max_wall_clock_secs", "600"), Ok(true));
+    assert_eq!(limits.apply_setting("max_tokens", "10"), Ok(false));
+    assert!(limits.apply_setting("max_tool_wall_ms", "soon").is_err());
+    assert_eq!(limits.max_cost_usd, Some(2.5));
+    assert_eq!(limits.max_wall_clock_secs, Some(600));
+
+    assert_eq!(limits.apply_setting("max_cost_usd", "none"), Ok(true));
+    assert_eq!(limits.max_cost_usd, None);
+
+    assert!(limits.validate().is_ok());
+    limits.max_remote_input_tokens = Some(0);
+    assert!(limits.validate().is_err());
+    limits.max_remote_input_tokens = None;
+    limits.max_cost_usd = Some(-1.0);
+    assert!(limits.validate().is_err());
+
+    let defaults = QuotaLimits {
+        max_wall_clock_secs: Some(60),
+        max_tool_wall_ms: Some(1_000),
+        ..QuotaLimits::default()
+    };
+    let merged = defaults.overridden_by(QuotaLimits {
+        max_wall_clock_secs: Some(120),
+        ..QuotaLimits::default()
+    });
+    assert_eq!(merged.max_wall_clock_secs, Some(120));
+    assert_eq!(merged.max_tool_wall_ms, Some(1_000));
+}
+
+fn spend(at_ms: i64, provider: &str, session: &str, cost_usd: f64) -> SpendRecord {
+    SpendRecord {
+        at_ms,
+        provider_id: Some(provider.to_string()),
+        model_id: Some(format!("{provider}-model")),
+        session_id: Some(session.to_string()),
+        orchestration_id: None,
+        cost_usd,
+    }
+}
+
+fn budget_scheduler(limits: Vec<crate::config::BudgetLimitConfig>) -> ProcessScheduler {
+    let mut sched = ProcessScheduler::new();
+    sched.configure_budgets(&crate::config::BudgetsConfig {
+        on_exhausted: crate::config::BudgetAction::Fail,
+        limits,
+    });
+    sched
+}
+
+// 2026-03-15T12:00:00Z
+const BUDGET_NOW_MS: i64 = 1_773_576_000_000;
+
+#[test]
+fn budgets_stop_remote_steps_once_the_window_spend_reaches_the_limit() {
+    use crate::config::{BudgetLimitConfig, BudgetScope, BudgetWindow};
+
+    let mut sched = budget_scheduler(vec![
+        BudgetLimitConfig {
+            id: "daily".to_string(),
+            max_usd: 1.0,
+            ..BudgetLimitConfig::default()
+        },
+        BudgetLimitConfig {
+            scope: BudgetScope::Provider,
+            window: BudgetWindow::Rolling,
+            rolling_secs: 600,
+            max_usd: 0.5,
+            ..BudgetLimitConfig::default()
+        },
+        // Ignorato: nessun tetto positivo.
+        BudgetLimitConfig::default(),
+    ]);
+    assert!(sched.budgets_enabled());
+    let subject = BudgetSubject {
+        provider_id: Some("openai"),
+        model_id: Some("openai-model"),
+        session_id: Some("sess-1"),
+    };
+    let groq = BudgetSubject {
+        provider_id: Some("groq"),
+        ..subject
+    };
+
+    // La spesa di ieri non conta nella finestra giornaliera.
+    sched.record_budget_spend(
+        None,
+        spend(BUDGET_NOW_MS - 86_400_000, "openai", "sess-1", 5.0),
+    );
+    sched.record_budget_spend(
+        Some(1),
+        spend(BUDGET_NOW_MS - 1_200_000, "openai", "sess-1", 0.4),
+    );
+    assert_eq!(sched.check_budgets(1, &subject, BUDGET_NOW_MS), None);
+
+    sched.record_budget_spend(
+        Some(1),
+        spend(BUDGET_NOW_MS - 60_000, "openai", "sess-1", 0.5),
+    );
+    let exhausted = sched
+        .check_budgets(1, &subject, BUDGET_NOW_MS)
+        .expect("rolling provider budget exhausted");
+    assert_eq!(exhausted.budget_id, "provider:*:rolling");
+    assert_eq!(exhausted.scope_key.as_deref(), Some("openai"));
+    assert!((exhausted.spent_usd - 0.5).abs() < 1e-9);
+    assert_eq!(sched.check_budgets(1, &groq, BUDGET_NOW_MS), None);
+
+    sched.record_budget_spend(
+        Some(2),
+        spend(BUDGET_NOW_MS - 30_000, "groq", "sess-2", 0.2),
+    );
+    let exhausted = sched
+        .check_budgets(2, &groq, BUDGET_NOW_MS)
+        .expect("global daily budget exhausted");
+    assert_eq!(exhausted.budget_id, "daily");
+    assert!((exhausted.spent_usd - 1.1).abs() < 1e-9);
+
+    let states = sched.budget_states(BUDGET_NOW_MS);
+    assert_eq!(states.len(), 3, "one global state, one per provider");
+    assert!(states
+        .iter()
+        .any(|state| state.scope_key.as_deref() == Some("groq")));
+}
+
+#[test]
+fn budgets_charge_sessions_and_bound_orchestrations() {
+    use crate::config::{BudgetLimitConfig, BudgetScope, BudgetWindow};
+
+    let mut sched = budget_scheduler(vec![
+        BudgetLimitConfig {
+            scope: BudgetScope::Session,
+            target: Some("sess-1".to_string()),
+            window: BudgetWindow::Monthly,
+            max_usd: 1.0,
+            ..BudgetLimitConfig::default()
+        },
+        BudgetLimitConfig {
+            scope: BudgetScope::Orchestration,
+            max_usd: 0.3,
+            ..BudgetLimitConfig::default()
+        },
+    ]);
+    let session = |id| BudgetSubject {
+        provider_id: Some("openai"),
+        model_id: None,
+        session_id: Some(id),
+    };
+
+    sched.record_budget_spend(
+        None,
+        spend(BUDGET_NOW_MS - 3_600_000, "openai", "sess-1", 1.0),
+    );
+    assert!(sched
+        .check_budgets(1, &session("sess-1"), BUDGET_NOW_MS)
+        .is_some());
+    assert!(sched
+        .check_budgets(1, &session("sess-2"), BUDGET_NOW_MS)
+        .is_none());
+
+    sched.register(7, WorkloadClass::General, ProcessPriority::Normal);
+    sched.register(8, WorkloadClass::General, ProcessPriority::Normal);
+    sched.bind_budget_orchestration(7, 42);
+    sched.bind_budget_orchestration(8, 42);
+    sched.record_budget_spend(
+        Some(7),
+        spend(BUDGET_NOW_MS - 1_000, "openai", "task-a", 0.3),
+    );
+    let exhausted = sched
+        .check_budgets(8, &session("task-b"), BUDGET_NOW_MS)
+        .expect("sibling task shares the orchestration budget");
+    assert_eq!(exhausted.scope_key.as_deref(), Some("42"));
+
+    sched.unregister(8);
+    assert!(sched
+        .check_budgets(8, &session("task-b"), BUDGET_NOW_MS)
+        .is_none());
+}
+
+#[test]
+fn budget_approvals_and_reroutes_are_tracked_per_process() {
+    use crate::config::BudgetLimitConfig;
+
+    let mut sched = budget_scheduler(vec![BudgetLimitConfig {
+        id: "daily".to_string(),
+        max_usd: 0.1,
+        ..BudgetLimitConfig::default()
+    }]);
+    let subject = BudgetSubject::default();
+    sched.record_budget_spend(None, spend(BUDGET_NOW_MS - 1_000, "openai", "sess-1", 0.2));
+    assert!(sched.check_budgets(3, &subject, BUDGET_NOW_MS).is_some());
+
+    sched.request_budget_approval(3, "hreq-1", "daily");
+    assert_eq!(sched.resolve_budget_approval(3, "hreq-other", true), None);
+    assert_eq!(
+        sched.resolve_budget_approval(3, "hreq-1", true).as_deref(),
+        Some("daily")
+    );
+    assert!(sched.check_budgets(3, &subject, BUDGET_NOW_MS).is_none());
+    assert!(sched.check_budgets(4, &subject, BUDGET_NOW_MS).is_some());
+
+    sched.request_budget_approval(4, "hreq-2", "daily");
+    assert!(sched.resolve_budget_approval(4, "hreq-2", false).is_some());
+    assert!(sched.check_budgets(4, &subject, BUDGET_NOW_MS).is_some());
+
+    sched.queue_budget_reroute(4);
+    assert!(sched.budget_reroute_pending(4));
+    assert_eq!(sched.take_budget_reroutes(), vec![4]);
+    assert!(!sched.budget_reroute_pending(4));
+}
@@ -0,0 +1,213 @@
+use agentic_control_models::KernelEvent;
+
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::memory::NeuralMemory;
+use crate::model_catalog::ModelCatalog;
+use crate::process::{ProcessLifecyclePolicy, ProcessState};
+use crate::resource_governor::ResourceGovernor;
+use crate::runtime::{AssistantTurnRuntimeBoundary, TurnAssemblyStore};
+use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::ProcessScheduler;
+use crate::session::SessionRegistry;
+use crate::storage::StorageService;
+use crate::tool_registry::ToolRegistry;
+
+use super::model_runtime::activate_model_target;
+use super::process_runtime::kill_managed_process_with_session;
+use super::session_runtime::respawn_session_from_history;
+
+/// Move the processes whose remote budget ran out (`on_exhausted =
+/// "route_local"`) onto a local model.
+///
+/// The session is respawned from its replay history on the local runtime and
+/// the running turn follows the new pid. Processes that cannot be moved —
+/// workflow tasks, no local model in the catalog, a failed load — stop with
+/// `budget_exhausted` as if the budget action were `fail`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn reroute_budget_exhausted_processes(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    model_catalog: &mut ModelCatalog,
+    memory: &mut NeuralMemory,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    turn_assembly: &mut TurnAssemblyStore,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+) {
+    for pid in scheduler.take_budget_reroutes() {
+        let Some(runtime_id) = runtime_registry
+            .runtime_id_for_pid(pid)
+            .map(ToString::to_string)
+        else {
+            continue;
+        };
+        let Some((owner_id, interactive)) = runtime_registry
+            .engine(&runtime_id)
+            .and_then(|engine| engine.processes.get(&pid))
+            .map(|process| {
+                (
+                    process.owner_id,
+                    process.lifecycle_policy == ProcessLifecyclePolicy::Interactive,
+                )
+            })
+        else {
+            continue;
+        };
+        let session_id = session_registry
+            .session_id_for_pid(pid)
+            .map(ToString::to_string);
+
+        let rerouted = match session_id {
+            Some(session_id) if interactive => load_local_runtime(
+                runtime_registry,
+                resource_governor,
+                model_catalog,
+                scheduler,
+                session_registry,
+                storage,
+                pid,
+            )
+            .map(|local_runtime_id| (session_id, local_runtime_id)),
+            _ => Err("only interactive sessions can move to a local model".to_string()),
+        };
+        let (session_id, local_runtime_id) = match rerouted {
+            Ok(rerouted) => rerouted,
+            Err(reason) => {
+                tracing::warn!(pid, %reason, "BUDGET: reroute to local model failed — stopping process");
+                fail_budget_reroute(runtime_registry, &runtime_id, pid, pending_events);
+                continue;
+            }
+        };
+
+        let turn_id = session_registry.clear_active_turn(pid);
+        if let Some(engine) = runtime_registry.engine_mut(&runtime_id) {
+            kill_managed_process_with_session(
+                engine,
+                memory,
+                scheduler,
+                session_registry,
+                storage,
+                pid,
+                "budget_rerouted",
+            );
+        }
+        if let Err(err) = runtime_registry.release_pid(storage, pid) {
+            tracing::warn!(pid, %err, "BUDGET: failed to release rerouted runtime binding");
+        }
+        pending_events.push(KernelEvent::WorkspaceChanged {
+            pid,
+            reason: "budget_rerouted".to_string(),
+        });
+
+        let target = match respawn_session_from_history(
+            runtime_registry,
+            memory,
+            scheduler,
+            session_registry,
+            storage,
+            pending_events,
+            tool_registry,
+            owner_id,
+            &session_id,
+            local_runtime_id,
+        ) {
+            Ok(target) => target,
+            Err((_, detail)) => {
+                tracing::error!(pid, session_id, %detail, "BUDGET: local respawn failed");
+                if let Some(turn_id) = turn_id {
+                    if let Err(err) =
+                        storage.finish_turn(turn_id, "errored", "budget_exhausted", Some(&detail))
+                    {
+                        tracing::warn!(pid, turn_id, %err, "BUDGET: failed to close rerouted turn");
+                    }
+                }
+                continue;
+            }
+        };
+
+        // Il turno in corso prosegue sul nuovo processo locale.
+        if let Some(turn_id) = turn_id {
+            if let Err(err) = storage.reassign_turn_pid(turn_id, target.pid) {
+                tracing::warn!(pid = target.pid, turn_id, %err, "BUDGET: failed to move turn to local pid");
+            }
+            session_registry.remember_active_turn(target.pid, turn_id);
+            if let Some(process) = runtime_registry
+                .engine_mut(&target.runtime_id)
+                .and_then(|engine| engine.processes.get_mut(&target.pid))
+            {
+                process.state = ProcessState::Ready;
+            }
+            turn_assembly
+                .apply_runtime_boundary(target.pid, AssistantTurnRuntimeBoundary::RuntimeClosed);
+        }
+        audit::record(
+            storage,
+            audit::PROCESS_BUDGET_EXHAUSTED,
+            format!(
+                "rerouted_from_pid={} from_runtime={} to_runtime={} turn_resumed={}",
+                pid,
+                runtime_id,
+                target.runtime_id,
+                turn_id.is_some()
+            ),
+            AuditContext::for_process(Some(&session_id), target.pid, Some(&target.runtime_id)),
+        );
+        tracing::info!(
+            pid,
+            new_pid = target.pid,
+            runtime_id = %target.runtime_id,
+            "BUDGET: process moved to local model"
+        );
+    }
+}
+
+/// Load (or reuse) the local model the catalog picks for the workload of `pid`.
+fn load_local_runtime(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    model_catalog: &mut ModelCatalog,
+    scheduler: &ProcessScheduler,
+    session_registry: &SessionRegistry,
+    storage: &mut StorageService,
+    pid: u64,
+) -> Result<String, String> {
+    let workload = scheduler
+        .snapshot(pid)
+        .map(|snapshot| snapshot.workload)
+        .unwrap_or_default();
+    let target = model_catalog
+        .resolve_workload_target(workload)
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "no local model in the catalog".to_string())?;
+    activate_model_target(
+        runtime_registry,
+        resource_governor,
+        session_registry,
+        storage,
+        model_catalog,
+        &target,
+    )
+    .map(|loaded| loaded.runtime_id)
+    .map_err(|err| err.message().to_string())
+}
+
+fn fail_budget_reroute(
+    runtime_registry: &mut RuntimeRegistry,
+    runtime_id: &str,
+    pid: u64,
+    pending_events: &mut Vec<KernelEvent>,
+) {
+    if let Some(process) = runtime_registry
+        .engine_mut(runtime_id)
+        .and_then(|engine| engine.processes.get_mut(&pid))
+    {
+        process.state = ProcessState::Finished;
+        process.termination_reason = Some("budget_exhausted".to_string());
+    }
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid,
+        reason: "budget_exhausted".to_string(),
+    });
+}
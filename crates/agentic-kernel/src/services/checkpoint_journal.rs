@@ -0,0 +1,224 @@
+//! Write-ahead checkpoint journal in SQLite.
+//!
+//! A generation stores a full `KernelSnapshot`; from then on every tick of
+//! the event loop compares a fingerprint of each checkpointed PID with the
+//! previous one and journals a per-process delta (`upsert` or `remove`) for
+//! what changed, so a crash loses at most one tick of metadata. Compaction
+//! opens a new generation from a fresh snapshot on the checkpoint interval,
+//! on a manual `CHECKPOINT` and once a generation collects too many deltas;
+//! only the newest `retained_generations` are kept.
+
+use std::collections::BTreeMap;
+
+use crate::checkpoint::{self, KernelSnapshot};
+use crate::commands::MetricsState;
+use crate::config::CheckpointConfig;
+use crate::engine::LLMEngine;
+use crate::memory::NeuralMemory;
+use crate::model_catalog::ModelCatalog;
+use crate::scheduler::ProcessScheduler;
+use crate::session::SessionRegistry;
+use crate::storage::{NewCheckpointDelta, NewCheckpointGeneration, StorageService};
+
+pub(crate) struct CheckpointSnapshotDeps<'a> {
+    pub engine_state: Option<&'a LLMEngine>,
+    pub model_catalog: &'a ModelCatalog,
+    pub scheduler: &'a ProcessScheduler,
+    pub session_registry: &'a SessionRegistry,
+    pub metrics: &'a MetricsState,
+    pub memory: &'a NeuralMemory,
+}
+
+impl CheckpointSnapshotDeps<'_> {
+    pub(crate) fn build_snapshot(&self) -> KernelSnapshot {
+        checkpoint::build_kernel_snapshot(
+            self.engine_state,
+            self.model_catalog,
+            self.scheduler,
+            self.session_registry,
+            self.metrics,
+            self.memory,
+        )
+    }
+
+    fn fingerprints(&self) -> BTreeMap<u64, u64> {
+        checkpoint::process_fingerprints(self.engine_state, self.scheduler, self.session_registry)
+    }
+}
+
+/// Store `snapshot` as a new generation of the current boot.
+pub(crate) fn write_checkpoint_generation(
+    storage: &mut StorageService,
+    boot_id: i64,
+    reason: &str,
+    snapshot: &KernelSnapshot,
+    retained_generations: usize,
+) -> Result<i64, String> {
+    let snapshot_json =
+        serde_json::to_string(snapshot).map_err(|e| format!("serialization error: {}", e))?;
+    storage
+        .create_checkpoint_generation(
+            &NewCheckpointGeneration {
+                boot_id: Some(boot_id),
+                reason: reason.to_string(),
+                kernel_version: snapshot.version.clone(),
+                snapshot_json,
+                process_count: snapshot.processes.len(),
+            },
+            retained_generations,
+        )
+        .map_err(|e| format!("checkpoint generation write failed: {}", e))
+}
+
+#[derive(Debug)]
+pub(crate) struct CheckpointJournal {
+    enabled: bool,
+    retained_generations: usize,
+    max_deltas_per_generation: usize,
+    generation: Option<i64>,
+    deltas_in_generation: usize,
+    fingerprints: BTreeMap<u64, u64>,
+}
+
+impl CheckpointJournal {
+    pub(crate) fn new(config: &CheckpointConfig) -> Self {
+        Self {
+            enabled: config.journal_enabled,
+            retained_generations: config.retained_generations.max(1),
+            max_deltas_per_generation: config.max_deltas_per_generation.max(1),
+            generation: None,
+            deltas_in_generation: 0,
+            fingerprints: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Journal the PIDs whose fingerprint changed since the last tick and
+    /// return how many deltas were written.
+    ///
+    /// The first change of a boot opens a generation instead, so an idle
+    /// kernel never pushes a known-good generation out of retention.
+    pub(crate) fn record_changes(
+        &mut self,
+        deps: &CheckpointSnapshotDeps<'_>,
+        storage: &mut StorageService,
+    ) -> Result<usize, String> {
+        if !self.enabled {
+            return Ok(0);
+        }
+        let mut fingerprints = deps.fingerprints();
+        // A process checked out to an inference worker is not in the engine
+        // for the duration of the step; it keeps its last journaled state.
+        for (pid, fingerprint) in &self.fingerprints {
+            if deps.scheduler.checked_out_process(*pid).is_some() {
+                fingerprints.entry(*pid).or_insert(*fingerprint);
+            }
+        }
+        if fingerprints == self.fingerprints {
+            return Ok(0);
+        }
+        self.adopt_newer_generation(deps.session_registry.boot_id(), storage);
+        let Some(generation) = self.generation else {
+            self.compact("journal_open", deps, storage)?;
+            return Ok(0);
+        };
+        if self.deltas_in_generation >= self.max_deltas_per_generation {
+            self.compact("delta_limit", deps, storage)?;
+            return Ok(0);
+        }
+
+        let mut deltas = Vec::new();
+        for (pid, fingerprint) in &fingerprints {
+            if self.fingerprints.get(pid) == Some(fingerprint) {
+                continue;
+            }
+            let Some(process) = checkpoint::snapshot_process(
+                deps.engine_state,
+                deps.scheduler,
+                deps.session_registry,
+                *pid,
+            ) else {
+                continue;
+            };
+            let scheduler_entry = checkpoint::snapshot_scheduler_entry(deps.scheduler, *pid);
+            deltas.push(NewCheckpointDelta {
+                pid: *pid,
+                delta_kind: "upsert".to_string(),
+                process_json: Some(
+                    serde_json::to_string(&process)
+                        .map_err(|e| format!("serialization error: {}", e))?,
+                ),
+                scheduler_json: scheduler_entry
+                    .map(|entry| serde_json::to_string(&entry))
+                    .transpose()
+                    .map_err(|e| format!("serialization error: {}", e))?,
+            });
+        }
+        for pid in self.fingerprints.keys() {
+            if !fingerprints.contains_key(pid) {
+                deltas.push(NewCheckpointDelta {
+                    pid: *pid,
+                    delta_kind: "remove".to_string(),
+                    process_json: None,
+                    scheduler_json: None,
+                });
+            }
+        }
+
+        storage
+            .append_checkpoint_deltas(generation, &deltas)
+            .map_err(|e| format!("checkpoint journal write failed: {}", e))?;
+        self.deltas_in_generation += deltas.len();
+        self.fingerprints = fingerprints;
+        Ok(deltas.len())
+    }
+
+    /// Open a new generation from a full snapshot of the kernel.
+    pub(crate) fn compact(
+        &mut self,
+        reason: &str,
+        deps: &CheckpointSnapshotDeps<'_>,
+        storage: &mut StorageService,
+    ) -> Result<i64, String> {
+        let snapshot = deps.build_snapshot();
+        let generation = write_checkpoint_generation(
+            storage,
+            deps.session_registry.boot_id(),
+            reason,
+            &snapshot,
+            self.retained_generations,
+        )?;
+        self.generation = Some(generation);
+        self.deltas_in_generation = 0;
+        self.fingerprints = deps.fingerprints();
+        Ok(generation)
+    }
+
+    /// Follow a generation a manual `CHECKPOINT` opened in this boot: its
+    /// base already covers the state, later deltas must land on it.
+    fn adopt_newer_generation(&mut self, boot_id: i64, storage: &StorageService) {
+        let latest = match storage.latest_checkpoint_generation() {
+            Ok(latest) => latest,
+            Err(err) => {
+                tracing::warn!(%err, "CHECKPOINT: failed to read the latest generation");
+                return;
+            }
+        };
+        if let Some(latest) = latest.filter(|latest| {
+            latest.boot_id == Some(boot_id)
+                && self
+                    .generation
+                    .is_none_or(|generation| latest.generation > generation)
+        }) {
+            self.generation = Some(latest.generation);
+            self.deltas_in_generation = latest.delta_count;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/checkpoint_journal.rs"]
+mod tests;
@@ -0,0 +1,321 @@
+//! Rehydration of checkpointed processes on `RESTORE`.
+//!
+//! Each process bound to a session is spawned again on its runtime (loaded
+//! through the `RuntimeRegistry` when needed) from the turns the checkpoint
+//! carries in its `ContextState`, or from the session replay history when the
+//! checkpoint predates it. A process parked with a swap snapshot also gets
+//! its KV slot reloaded, so the prompt is not evaluated again. The process
+//! then resumes in the state it was checkpointed in.
+
+use agentic_control_models::KernelEvent;
+
+use crate::checkpoint::ProcessSnapshot;
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::engine::LLMEngine;
+use crate::memory::{NeuralMemory, SlotPersistenceKind};
+use crate::model_catalog::ModelCatalog;
+use crate::process::ProcessState;
+use crate::resource_governor::ResourceGovernor;
+use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::{ProcessPriority, ProcessScheduler};
+use crate::session::SessionRegistry;
+use crate::storage::StorageService;
+use crate::tool_registry::ToolRegistry;
+
+use super::process_runtime::{
+    spawn_restored_managed_process_with_session, RestoredManagedProcessRequest,
+};
+use super::session_runtime::{ensure_runtime_loaded, render_prompt_from_replay_history};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CheckpointPromptSource {
+    /// Segments of the checkpointed `ContextState`, compactions included.
+    ContextState,
+    /// Session replay history persisted in SQLite.
+    SessionHistory,
+}
+
+impl CheckpointPromptSource {
+    pub(crate) const fn as_str(self) -> &'static str {
+        match self {
+            Self::ContextState => "context_state",
+            Self::SessionHistory => "session_history",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct RehydratedProcess {
+    pub(crate) checkpoint_pid: u64,
+    pub(crate) pid: u64,
+    pub(crate) session_id: String,
+    pub(crate) runtime_id: String,
+    pub(crate) state: ProcessState,
+    pub(crate) prompt_source: CheckpointPromptSource,
+    pub(crate) kv_restored: bool,
+}
+
+/// State a checkpointed process resumes in, `None` when it cannot resume.
+///
+/// Work that was running or parked starts its turn again from the restored
+/// context; a pending syscall cannot be replayed, so those stay orphaned.
+pub(crate) fn resume_state_for(snapshot: &ProcessSnapshot) -> Option<ProcessState> {
+    match snapshot.state.as_str() {
+        "Ready" | "Running" | "AwaitingTurnDecision" | "Parked" => Some(ProcessState::Ready),
+        "WaitingForInput" => Some(ProcessState::WaitingForInput),
+        "WaitingForHumanInput" if snapshot.pending_human_request.is_some() => {
+            Some(ProcessState::WaitingForHumanInput)
+        }
+        "WaitingForHumanInput" => Some(ProcessState::WaitingForInput),
+        _ => None,
+    }
+}
+
+/// Prompt the checkpointed context renders to, when the checkpoint has one.
+pub(crate) fn checkpoint_context_prompt(snapshot: &ProcessSnapshot) -> Option<String> {
+    let segments = &snapshot.context_state.segments;
+    (!segments.is_empty()).then(|| {
+        segments
+            .iter()
+            .map(|segment| segment.text.as_str())
+            .collect()
+    })
+}
+
+/// Spawn `snapshot` again on its runtime and put it back in its prior state.
+///
+/// `pid_floor` keeps the new PIDs above every checkpointed one, so the
+/// metadata of processes that stay orphaned never collides with a live PID.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn rehydrate_checkpoint_process(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    model_catalog: &mut ModelCatalog,
+    memory: &mut NeuralMemory,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+    owner_id: usize,
+    pid_floor: u64,
+    snapshot: &ProcessSnapshot,
+) -> Result<RehydratedProcess, String> {
+    let state = resume_state_for(snapshot)
+        .ok_or_else(|| format!("state '{}' cannot be resumed", snapshot.state))?;
+    let (Some(session_id), Some(checkpoint_runtime_id)) = (
+        snapshot.session_id.as_deref(),
+        snapshot.runtime_id.as_deref(),
+    ) else {
+        return Err("checkpoint has no session/runtime binding for this process".to_string());
+    };
+    if session_registry.session(session_id).is_none() {
+        return Err(format!("session '{}' no longer exists", session_id));
+    }
+    if let Some(stale_pid) = session_registry.active_pid_for_session(session_id) {
+        if let Err(err) = session_registry.release_pid(storage, stale_pid, "interrupted") {
+            tracing::warn!(
+                session_id,
+                pid = stale_pid,
+                %err,
+                "CHECKPOINT: failed to clear stale live binding before restore"
+            );
+        }
+    }
+
+    let runtime_id = ensure_runtime_loaded(
+        runtime_registry,
+        resource_governor,
+        model_catalog,
+        session_registry,
+        storage,
+        session_id,
+        checkpoint_runtime_id,
+    )
+    .map_err(|(_, detail)| detail)?;
+
+    let (rendered_prompt, prompt_source) = match checkpoint_context_prompt(snapshot) {
+        Some(prompt) => (prompt, CheckpointPromptSource::ContextState),
+        None => {
+            let replay_messages = storage
+                .load_replay_messages_for_session(session_id)
+                .map_err(|err| format!("failed to load persisted history: {}", err))?;
+            let system_prompt = crate::agent_prompt::build_agent_system_prompt_with_allowed_tools(
+                tool_registry,
+                snapshot.tool_caller.clone(),
+                Some(&snapshot.permission_policy.allowed_tools),
+            );
+            let engine = runtime_registry
+                .engine(&runtime_id)
+                .ok_or_else(|| format!("runtime '{}' is not loaded", runtime_id))?;
+            let prompt =
+                render_prompt_from_replay_history(&replay_messages, &system_prompt, engine)?;
+            (prompt, CheckpointPromptSource::SessionHistory)
+        }
+    };
+
+    // `apply_restore_snapshot` registered the checkpointed scheduler entry.
+    let scheduling = scheduler.snapshot(snapshot.pid);
+    let spawned = {
+        let engine = runtime_registry
+            .engine_mut(&runtime_id)
+            .ok_or_else(|| format!("runtime '{}' is not loaded", runtime_id))?;
+        spawn_restored_managed_process_with_session(
+            &runtime_id,
+            session_id,
+            pid_floor,
+            engine,
+            memory,
+            scheduler,
+            session_registry,
+            storage,
+            RestoredManagedProcessRequest {
+                rendered_prompt,
+                owner_id,
+                tool_caller: snapshot.tool_caller.clone(),
+                permission_policy: Some(snapshot.permission_policy.clone()),
+                workload: scheduling
+                    .as_ref()
+                    .map(|entry| entry.workload)
+                    .unwrap_or_default(),
+                required_backend_class: None,
+                priority: scheduling
+                    .as_ref()
+                    .map(|entry| entry.priority)
+                    .unwrap_or(ProcessPriority::Normal),
+                lifecycle_policy: snapshot.lifecycle_policy,
+                context_policy: Some(snapshot.context_policy.clone()),
+            },
+        )?
+    };
+    let pid = spawned.pid;
+    if let Some(entry) = scheduling {
+        scheduler.set_quota(pid, entry.quota);
+    }
+    if let Err(err) = runtime_registry.register_pid(storage, &runtime_id, pid) {
+        tracing::warn!(pid, runtime_id, %err, "CHECKPOINT: failed to register restored pid");
+    }
+
+    let kv_restored = {
+        let Some(engine) = runtime_registry.engine_mut(&runtime_id) else {
+            return Err(format!("runtime '{}' is not loaded", runtime_id));
+        };
+        if let Some(process) = engine.processes.get_mut(&pid) {
+            if prompt_source == CheckpointPromptSource::ContextState {
+                process.context_state = snapshot.context_state.clone();
+            }
+            if state == ProcessState::WaitingForHumanInput {
+                if let Some(request) = snapshot.pending_human_request.clone() {
+                    process.set_pending_human_request(request);
+                }
+            }
+            process.state = state.clone();
+        }
+        prompt_source == CheckpointPromptSource::ContextState
+            && restore_kv_snapshot(engine, memory, pid, snapshot)
+    };
+
+    if state == ProcessState::Ready {
+        resume_latest_turn(session_registry, storage, session_id, pid);
+    }
+
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid,
+        reason: "checkpoint_restored".to_string(),
+    });
+    audit::record(
+        storage,
+        audit::PROCESS_RESTORED,
+        format!(
+            "checkpoint_pid={} pid={} state={:?} prompt_source={} kv_restored={}",
+            snapshot.pid,
+            pid,
+            state,
+            prompt_source.as_str(),
+            kv_restored
+        ),
+        AuditContext::for_process(Some(session_id), pid, Some(&runtime_id)),
+    );
+
+    Ok(RehydratedProcess {
+        checkpoint_pid: snapshot.pid,
+        pid,
+        session_id: session_id.to_string(),
+        runtime_id,
+        state,
+        prompt_source,
+        kv_restored,
+    })
+}
+
+/// Reload the parked KV slot of `snapshot` into the slot of `pid`.
+///
+/// A failure only costs the prompt evaluation the snapshot would have saved.
+fn restore_kv_snapshot(
+    engine: &mut LLMEngine,
+    memory: &mut NeuralMemory,
+    pid: u64,
+    snapshot: &ProcessSnapshot,
+) -> bool {
+    let Some(path) = snapshot.resident_slot_snapshot_path.as_deref() else {
+        return false;
+    };
+    let Some(slot_id) = memory.slot_for_pid(pid) else {
+        return false;
+    };
+    if let Err(err) = memory.restore_swapped_pid(
+        pid,
+        slot_id,
+        SlotPersistenceKind::BackendSlotSnapshot,
+        Some(path),
+    ) {
+        tracing::warn!(pid, %err, "CHECKPOINT: swap snapshot could not be staged");
+        return false;
+    }
+
+    let loaded = engine.load_process_context_slot(pid, path);
+    memory.discard_staged_snapshot(path);
+    match loaded {
+        Ok(()) => {
+            memory.release_swap_snapshots(snapshot.pid);
+            true
+        }
+        Err(err) => {
+            tracing::warn!(
+                pid,
+                snapshot = %path.display(),
+                %err,
+                "CHECKPOINT: KV slot restore failed; the prompt will be evaluated again"
+            );
+            false
+        }
+    }
+}
+
+/// Reopen the last turn of the session so the output of a process that
+/// resumes mid-turn keeps landing in it.
+fn resume_latest_turn(
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    session_id: &str,
+    pid: u64,
+) {
+    let turn_id = match storage.latest_session_turn_id(session_id) {
+        Ok(Some(turn_id)) => turn_id,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(pid, session_id, %err, "CHECKPOINT: failed to look up the last turn");
+            return;
+        }
+    };
+    match storage.resume_turn(turn_id) {
+        Ok(()) => session_registry.remember_active_turn(pid, turn_id),
+        Err(err) => {
+            tracing::warn!(pid, turn_id, %err, "CHECKPOINT: failed to reopen the last turn");
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/checkpoint_runtime.rs"]
+mod tests;
@@ -0,0 +1,343 @@
+use std::sync::{mpsc, Arc, Mutex};
+
+use agentic_control_models::KernelEvent;
+
+use crate::memory::NeuralMemory;
+use crate::model_catalog::{parse_workload_label, ModelCatalog};
+use crate::policy::resolve_exec_policy;
+use crate::process::ProcessLifecyclePolicy;
+use crate::resource_governor::ResourceGovernor;
+use crate::runtime::TurnAssemblyStore;
+use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::{ProcessPriority, ProcessQuota, ProcessScheduler};
+use crate::session::SessionRegistry;
+use crate::storage::StorageService;
+use crate::tool_registry::ToolRegistry;
+use crate::tools::governance::govern_tool_execution;
+use crate::tools::invocation::{
+    ProcessPermissionOverrides, ProcessPermissionPolicy, ToolCaller, ToolContext, ToolInvocation,
+    ToolInvocationTransport,
+};
+use crate::tools::SyscallRateMap;
+
+use super::job_scheduler::{
+    ExecJobTarget, SendInputJobTarget, ToolJobTarget, SCHEDULER_SYSTEM_OWNER_ID,
+};
+use super::orchestration_runtime::{resolve_runtime_for_workload, OrchestrationStartError};
+use super::process_runtime::{spawn_managed_process_with_session, ManagedProcessRequest};
+use super::session_runtime::{append_session_input, ensure_live_session};
+
+/// Rate-limit bucket shared by scheduled tool calls, which have no process.
+const SCHEDULED_TOOL_PID: u64 = 0;
+/// Upper bound on the tool output kept as the error of a failed run.
+const MAX_TOOL_ERROR_CHARS: usize = 512;
+
+/// Spawn the ephemeral process of an `exec` job target and return its PID.
+///
+/// `prompt` is the target prompt with the trigger event already appended.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_exec_job(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    memory: &mut NeuralMemory,
+    model_catalog: &mut ModelCatalog,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+    target: &ExecJobTarget,
+    prompt: &str,
+) -> Result<u64, String> {
+    let resolved = resolve_exec_policy(prompt);
+    let workload = target
+        .workload
+        .as_deref()
+        .and_then(parse_workload_label)
+        .unwrap_or(resolved.workload);
+    let runtime_id = resolve_runtime_for_workload(
+        runtime_registry,
+        resource_governor,
+        storage,
+        model_catalog,
+        session_registry,
+        workload,
+        None,
+    )
+    .map_err(|err| match err {
+        OrchestrationStartError::NoModelLoaded => "no_model_loaded".to_string(),
+        other => other.to_string(),
+    })?;
+
+    let permission_overrides = (target.allowed_tools.is_some()
+        || target.path_scopes.is_some()
+        || target.path_grants.is_some())
+    .then(|| ProcessPermissionOverrides {
+        allowed_tools: target.allowed_tools.clone(),
+        path_scopes: target.path_scopes.clone(),
+        path_grants: target.path_grants.clone(),
+        ..ProcessPermissionOverrides::default()
+    });
+    let permission_policy = ProcessPermissionPolicy::interactive_chat_with_overrides(
+        tool_registry,
+        permission_overrides.as_ref(),
+    )?;
+    let system_prompt = crate::agent_prompt::build_agent_system_prompt_with_allowed_tools(
+        tool_registry,
+        ToolCaller::AgentText,
+        Some(&permission_policy.allowed_tools),
+    );
+    let quota_override =
+        (target.max_tokens.is_some() || target.max_syscalls.is_some() || !target.limits.is_empty())
+            .then(|| {
+                let limit = |value: Option<u64>| {
+                    value
+                        .and_then(|value| usize::try_from(value).ok())
+                        .unwrap_or(usize::MAX)
+                };
+                ProcessQuota {
+                    max_tokens: limit(target.max_tokens),
+                    max_syscalls: limit(target.max_syscalls),
+                    limits: target.limits,
+                }
+            });
+
+    let pid_floor = runtime_registry.next_pid_floor();
+    let spawned = {
+        let Some(engine) = runtime_registry.engine_mut(&runtime_id) else {
+            return Err("no_model_loaded".to_string());
+        };
+        let context_policy = resolved
+            .context_policy
+            .align_to_runtime_window_if_default(engine.effective_context_window_tokens());
+        spawn_managed_process_with_session(
+            &runtime_id,
+            pid_floor,
+            engine,
+            memory,
+            scheduler,
+            session_registry,
+            storage,
+            ManagedProcessRequest {
+                prompt: resolved.prompt.clone(),
+                system_prompt: Some(system_prompt),
+                owner_id: SCHEDULER_SYSTEM_OWNER_ID,
+                tool_caller: ToolCaller::AgentText,
+                permission_policy: Some(permission_policy),
+                workload,
+                required_backend_class: None,
+                priority: ProcessPriority::Normal,
+                lifecycle_policy: ProcessLifecyclePolicy::Ephemeral,
+                context_policy: Some(context_policy),
+                quota_override,
+            },
+        )?
+    };
+
+    if let Err(err) = runtime_registry.register_pid(storage, &runtime_id, spawned.pid) {
+        tracing::warn!(
+            pid = spawned.pid,
+            runtime_id,
+            %err,
+            "SCHEDULER: failed to register exec job pid in runtime registry"
+        );
+    }
+    pending_events.push(KernelEvent::SessionStarted {
+        session_id: spawned.session_id,
+        pid: spawned.pid,
+        workload: format!("{:?}", workload).to_lowercase(),
+        prompt: resolved.prompt,
+    });
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid: spawned.pid,
+        reason: "scheduled_job_started".to_string(),
+    });
+    Ok(spawned.pid)
+}
+
+/// Append the prompt of a `send_input` job target to its session, resuming
+/// the session from history when it has no live process. Returns the PID.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn start_session_input_job(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    memory: &mut NeuralMemory,
+    model_catalog: &mut ModelCatalog,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    turn_assembly: &mut TurnAssemblyStore,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+    target: &SendInputJobTarget,
+    prompt: &str,
+) -> Result<u64, String> {
+    let live = ensure_live_session(
+        runtime_registry,
+        resource_governor,
+        model_catalog,
+        memory,
+        scheduler,
+        session_registry,
+        storage,
+        pending_events,
+        tool_registry,
+        SCHEDULER_SYSTEM_OWNER_ID,
+        &target.session_id,
+    )
+    .map_err(|(_, detail)| detail)?;
+    append_session_input(
+        runtime_registry,
+        scheduler,
+        session_registry,
+        storage,
+        turn_assembly,
+        pending_events,
+        &live,
+        prompt,
+        "scheduled_job",
+    )
+    .map_err(|(_, detail)| detail)?;
+    Ok(live.pid)
+}
+
+/// Canonical name of a tool that scheduled jobs may call directly: enabled,
+/// open to programmatic callers and never waiting on a human.
+pub(crate) fn scheduled_tool_name(registry: &ToolRegistry, name: &str) -> Result<String, String> {
+    let entry = registry
+        .resolve_invocation_name(name)
+        .ok_or_else(|| format!("Tool '{}' is not registered", name))?;
+    let descriptor = &entry.descriptor;
+    if !descriptor.enabled {
+        return Err(format!("Tool '{}' is disabled", descriptor.name));
+    }
+    if !descriptor
+        .allowed_callers
+        .contains(&ToolCaller::Programmatic)
+    {
+        return Err(format!(
+            "Tool '{}' cannot be called programmatically",
+            descriptor.name
+        ));
+    }
+    if descriptor.approval_required
+        || descriptor
+            .capabilities
+            .iter()
+            .any(|capability| capability == "hitl")
+    {
+        return Err(format!(
+            "Tool '{}' needs a human in the loop and cannot run from a scheduled job",
+            descriptor.name
+        ));
+    }
+    Ok(descriptor.name.clone())
+}
+
+pub(crate) struct JobToolOutcome {
+    pub job_id: u64,
+    pub run_id: u64,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Runs the tool calls of scheduled `tool` jobs off the event loop, one
+/// thread per call, and hands the outcomes back through a channel.
+pub(crate) struct JobToolRunner {
+    rates: Arc<Mutex<SyscallRateMap>>,
+    waker: Option<Arc<mio::Waker>>,
+    result_tx: mpsc::Sender<JobToolOutcome>,
+    result_rx: mpsc::Receiver<JobToolOutcome>,
+}
+
+impl JobToolRunner {
+    pub(crate) fn new(rates: Arc<Mutex<SyscallRateMap>>, waker: Option<Arc<mio::Waker>>) -> Self {
+        let (result_tx, result_rx) = mpsc::channel();
+        Self {
+            rates,
+            waker,
+            result_tx,
+            result_rx,
+        }
+    }
+
+    /// Check that `target` can run now and build its invocation.
+    pub(crate) fn prepare(
+        registry: &ToolRegistry,
+        target: &ToolJobTarget,
+    ) -> Result<(ToolInvocation, ToolContext), String> {
+        let name = scheduled_tool_name(registry, &target.tool)?;
+        let permissions = ProcessPermissionPolicy::scheduled_job_tool(registry, &name)?;
+        let call_id = Some(format!("scheduled-job-{}", name));
+        let invocation = ToolInvocation::new(name, target.arguments.clone(), call_id.clone())
+            .map_err(|err| err.to_string())?;
+        let context = ToolContext {
+            pid: None,
+            session_id: None,
+            caller: ToolCaller::Programmatic,
+            permissions,
+            transport: ToolInvocationTransport::Structured,
+            call_id,
+        };
+        Ok((invocation, context))
+    }
+
+    pub(crate) fn spawn(
+        &self,
+        registry: &ToolRegistry,
+        job_id: u64,
+        run_id: u64,
+        invocation: ToolInvocation,
+        context: ToolContext,
+    ) -> Result<(), String> {
+        let registry = registry.clone();
+        let rates = Arc::clone(&self.rates);
+        let result_tx = self.result_tx.clone();
+        let waker = self.waker.clone();
+        std::thread::Builder::new()
+            .name(format!("scheduled-tool-{job_id}"))
+            .spawn(move || {
+                let outcome = match rates.lock() {
+                    Ok(mut rates) => {
+                        let result = govern_tool_execution(
+                            &invocation,
+                            &context,
+                            &registry,
+                            SCHEDULED_TOOL_PID,
+                            &mut rates,
+                        );
+                        JobToolOutcome {
+                            job_id,
+                            run_id,
+                            success: result.success,
+                            error: (!result.success).then(|| {
+                                let detail = result.output.chars().take(MAX_TOOL_ERROR_CHARS);
+                                match result.error_kind {
+                                    Some(kind) => {
+                                        format!("{}: {}", kind, detail.collect::<String>())
+                                    }
+                                    None => detail.collect(),
+                                }
+                            }),
+                        }
+                    }
+                    Err(_) => JobToolOutcome {
+                        job_id,
+                        run_id,
+                        success: false,
+                        error: Some("syscall rate-limit state is unavailable".to_string()),
+                    },
+                };
+                let _ = result_tx.send(outcome);
+                if let Some(waker) = waker {
+                    let _ = waker.wake();
+                }
+            })
+            .map(|_| ())
+            .map_err(|err| format!("failed to start tool thread: {}", err))
+    }
+
+    pub(crate) fn drain(&self) -> Vec<JobToolOutcome> {
+        self.result_rx.try_iter().collect()
+    }
+}
@@ -0,0 +1,623 @@
+use agentic_control_models::{KernelEvent, ScheduledJobView};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::{current_timestamp_ms, StorageService};
+
+use super::scheduler::{
+    DueJobDispatch, JobScheduler, OverlapRun, ScheduledJob, ScheduledJobRun, ScheduledJobState,
+    ScheduledJobTarget, ScheduledRunHandle,
+};
+
+pub(super) const DEFAULT_MAX_CONCURRENT_JOB_RUNS: usize = 4;
+/// Occurrences a `queue` job holds back beyond this are skipped.
+const MAX_QUEUED_RUNS: usize = 16;
+const MAX_CONCURRENT_RUNS_PER_JOB: u32 = 16;
+
+/// What happens when a job comes due while one of its runs is still in flight.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum ConcurrencyPolicy {
+    /// Skip the new occurrence.
+    #[default]
+    Forbid,
+    /// Hold the new occurrence until the active run settles.
+    Queue,
+    /// Cancel the active run and start the new occurrence.
+    Replace,
+    /// Start the new occurrence next to the active one, up to `max_concurrent` runs.
+    Allow { max_concurrent: u32 },
+}
+
+/// Outcome of `admit_due_job`.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub(crate) enum JobAdmission {
+    /// Start `plan`. `replaced` is the run cancelled to make room for it,
+    /// whose processes the caller must stop.
+    Start {
+        plan: DueJobDispatch,
+        replaced: Option<ScheduledRunHandle>,
+    },
+    /// Nothing starts; the decision was recorded in `recent_runs`.
+    Held,
+    /// Still waiting for a free slot; the deferral is already recorded.
+    Waiting,
+}
+
+impl ConcurrencyPolicy {
+    pub(crate) fn label(self) -> String {
+        match self {
+            Self::Forbid => "forbid".to_string(),
+            Self::Queue => "queue".to_string(),
+            Self::Replace => "replace".to_string(),
+            Self::Allow { max_concurrent } => format!("allow:{max_concurrent}"),
+        }
+    }
+
+    pub(crate) fn from_label(label: &str) -> Result<Self, String> {
+        match label {
+            "forbid" => Ok(Self::Forbid),
+            "queue" => Ok(Self::Queue),
+            "replace" => Ok(Self::Replace),
+            other => other
+                .strip_prefix("allow:")
+                .and_then(|value| value.parse().ok())
+                .map(|max_concurrent| Self::Allow { max_concurrent })
+                .ok_or_else(|| format!("Unsupported concurrency policy '{}'", other)),
+        }
+    }
+
+    pub(super) fn validate_for(self, target: &ScheduledJobTarget) -> Result<(), String> {
+        if let Self::Allow { max_concurrent } = self {
+            if !(1..=MAX_CONCURRENT_RUNS_PER_JOB).contains(&max_concurrent) {
+                return Err(format!(
+                    "allow.max_concurrent must be between 1 and {}",
+                    MAX_CONCURRENT_RUNS_PER_JOB
+                ));
+            }
+        }
+        // A session runs one turn at a time and its process is not owned by the job.
+        if matches!(target, ScheduledJobTarget::SendInput(_))
+            && matches!(self, Self::Replace | Self::Allow { .. })
+        {
+            return Err(format!(
+                "send_input targets only support the forbid and queue concurrency policies, not '{}'",
+                self.label()
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl JobScheduler {
+    pub fn set_max_concurrent_runs(&mut self, limit: usize) {
+        self.max_concurrent_runs = limit.max(1);
+    }
+
+    /// Runs in flight across all jobs, overlap runs included.
+    pub fn running_run_count(&self) -> usize {
+        self.jobs.values().map(ScheduledJob::active_run_count).sum()
+    }
+
+    pub(super) fn at_run_capacity(&self) -> bool {
+        self.running_run_count() >= self.max_concurrent_runs
+    }
+
+    /// Apply the global run cap and the job's concurrency policy to a due job.
+    pub fn admit_due_job(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+    ) -> Result<JobAdmission, String> {
+        let now_ms = current_timestamp_ms();
+        let at_capacity = self.at_run_capacity();
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Err(format!("Scheduled job {} not found", job_id));
+        };
+
+        if job.state != ScheduledJobState::Running {
+            if at_capacity {
+                return job.record_deferral(storage, now_ms);
+            }
+            return self.dispatch_plan(job_id).map(|plan| JobAdmission::Start {
+                plan,
+                replaced: None,
+            });
+        }
+
+        let occurrence_at_ms = job.next_run_at_ms.unwrap_or(now_ms);
+        let manual = std::mem::take(&mut job.manual_run_requested);
+        let by_event = job.trigger.is_event_driven() && !manual;
+        match job.concurrency_policy {
+            ConcurrencyPolicy::Forbid => {
+                if by_event {
+                    let started_at_ms = job.last_run_started_at_ms.unwrap_or(i64::MIN);
+                    job.pending_trigger_events
+                        .retain(|event| event.occurred_at_ms < started_at_ms);
+                }
+                job.record_decision(
+                    storage,
+                    occurrence_at_ms,
+                    "skipped",
+                    "overlap_forbidden",
+                    now_ms,
+                )?;
+            }
+            ConcurrencyPolicy::Queue => {
+                // Queued events already wait in `pending_trigger_events`.
+                let (status, reason) = if by_event {
+                    ("queued", "overlap_queued")
+                } else if job.queued_runs.len() >= MAX_QUEUED_RUNS {
+                    ("skipped", "queue_full")
+                } else {
+                    job.queued_runs.push_back(occurrence_at_ms);
+                    ("queued", "overlap_queued")
+                };
+                job.record_decision(storage, occurrence_at_ms, status, reason, now_ms)?;
+            }
+            ConcurrencyPolicy::Replace => {
+                if by_event {
+                    // Latest event wins: the superseded ones go with the cancelled run.
+                    while job.pending_trigger_events.len() > 1 {
+                        job.pending_trigger_events.pop_front();
+                    }
+                }
+                let replaced =
+                    self.cancel_active_run(storage, job_id, "replaced_by_newer_run", now_ms)?;
+                if let Some(job) = self.jobs.get_mut(&job_id) {
+                    job.next_run_at_ms = Some(occurrence_at_ms);
+                    job.start_reason = Some(if manual {
+                        "manual_run"
+                    } else {
+                        "replaced_previous_run"
+                    });
+                }
+                return self
+                    .dispatch_plan(job_id)
+                    .map(|plan| JobAdmission::Start { plan, replaced });
+            }
+            ConcurrencyPolicy::Allow { max_concurrent } => {
+                if job.active_run_count() >= max_concurrent as usize {
+                    if by_event {
+                        job.pending_trigger_events.pop_front();
+                    }
+                    job.record_decision(
+                        storage,
+                        occurrence_at_ms,
+                        "skipped",
+                        "max_concurrency_reached",
+                        now_ms,
+                    )?;
+                } else if at_capacity {
+                    job.manual_run_requested = manual;
+                    return job.record_deferral(storage, now_ms);
+                } else {
+                    let plan = DueJobDispatch {
+                        job_id,
+                        trigger_at_ms: occurrence_at_ms,
+                        attempt: 1,
+                        target: job.target.clone(),
+                        trigger_event: if by_event {
+                            job.pending_trigger_events.pop_front()
+                        } else {
+                            None
+                        },
+                        overlap: true,
+                    };
+                    job.rule_occurrence(occurrence_at_ms, now_ms);
+                    return Ok(JobAdmission::Start {
+                        plan,
+                        replaced: None,
+                    });
+                }
+            }
+        }
+
+        job.rule_occurrence(occurrence_at_ms, now_ms);
+        job.updated_at_ms = now_ms;
+        storage
+            .save_scheduled_job(&job.to_stored())
+            .map_err(|err| err.to_string())?;
+        Ok(JobAdmission::Held)
+    }
+
+    /// RUN_JOB_NOW: make the job due immediately. The run still goes through
+    /// `admit_due_job`, so the concurrency policy and the global cap apply.
+    pub fn run_now(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+    ) -> Result<ScheduledJobView, String> {
+        let now_ms = current_timestamp_ms();
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Err(format!("Scheduled job {} not found", job_id));
+        };
+        if !job.enabled {
+            return Err(format!(
+                "Scheduled job {} is disabled; enable it before running it",
+                job_id
+            ));
+        }
+
+        job.manual_run_requested = true;
+        if job.state != ScheduledJobState::Running {
+            job.start_reason = Some("manual_run");
+        }
+        job.next_run_at_ms = Some(now_ms);
+        job.updated_at_ms = now_ms;
+        storage
+            .save_scheduled_job(&job.to_stored())
+            .map_err(|err| err.to_string())?;
+        Ok(job.to_view())
+    }
+
+    pub fn mark_overlap_started(
+        &mut self,
+        storage: &mut StorageService,
+        plan: &DueJobDispatch,
+        handle: ScheduledRunHandle,
+    ) -> Result<u64, String> {
+        let now_ms = current_timestamp_ms();
+        let job_id = plan.job_id;
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Err(format!("Scheduled job {} not found", job_id));
+        };
+        let deadline_at_ms = now_ms + job.timeout_ms as i64;
+        let mut run = ScheduledJobRun {
+            run_id: 0,
+            trigger_at_ms: plan.trigger_at_ms,
+            attempt: plan.attempt,
+            status: "running".to_string(),
+            started_at_ms: Some(now_ms),
+            completed_at_ms: None,
+            orchestration_id: handle.orchestration_id(),
+            deadline_at_ms: Some(deadline_at_ms),
+            error: None,
+            reason: Some("overlap_allowed".to_string()),
+        };
+        run.run_id = storage
+            .insert_scheduled_job_run(&run.to_stored(job_id))
+            .map_err(|err| err.to_string())?;
+        let run_id = run.run_id;
+
+        job.push_recent_run(run.clone());
+        job.overlap_runs.push(OverlapRun { handle, run });
+        job.deferred_at_ms = None;
+        job.last_run_started_at_ms = Some(now_ms);
+        job.updated_at_ms = now_ms;
+        storage
+            .save_scheduled_job(&job.to_stored())
+            .map_err(|err| err.to_string())?;
+        if let Some(orchestration_id) = handle.orchestration_id() {
+            self.orchestration_to_job.insert(orchestration_id, job_id);
+        }
+        if let Some(pid) = handle.pid() {
+            self.process_to_job.insert(pid, job_id);
+        }
+        self.lifecycle_events.push(KernelEvent::JobRunStarted {
+            job_id,
+            run_id,
+            attempt: plan.attempt,
+            trigger_at_ms: plan.trigger_at_ms,
+            orchestration_id: handle.orchestration_id(),
+            pid: handle.pid(),
+        });
+        Ok(run_id)
+    }
+
+    pub fn mark_overlap_dispatch_failed(
+        &mut self,
+        storage: &mut StorageService,
+        plan: &DueJobDispatch,
+        error: &str,
+    ) -> Result<(), String> {
+        let now_ms = current_timestamp_ms();
+        let job_id = plan.job_id;
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Err(format!("Scheduled job {} not found", job_id));
+        };
+        let mut run = ScheduledJobRun {
+            run_id: 0,
+            trigger_at_ms: plan.trigger_at_ms,
+            attempt: plan.attempt,
+            status: "failed".to_string(),
+            started_at_ms: Some(now_ms),
+            completed_at_ms: Some(now_ms),
+            orchestration_id: None,
+            deadline_at_ms: None,
+            error: Some(error.to_string()),
+            reason: Some("overlap_allowed".to_string()),
+        };
+        run.run_id = storage
+            .insert_scheduled_job_run(&run.to_stored(job_id))
+            .map_err(|err| err.to_string())?;
+        let run_id = run.run_id;
+        job.push_recent_run(run);
+        job.updated_at_ms = now_ms;
+        storage
+            .save_scheduled_job(&job.to_stored())
+            .map_err(|err| err.to_string())?;
+        self.lifecycle_events.push(KernelEvent::JobRunFinished {
+            job_id,
+            run_id,
+            attempt: plan.attempt,
+            status: "failed".to_string(),
+            error: Some(error.to_string()),
+        });
+        Ok(())
+    }
+
+    pub(super) fn overlap_run_index(
+        &self,
+        job_id: u64,
+        matches: impl Fn(&OverlapRun) -> bool,
+    ) -> Option<usize> {
+        self.jobs
+            .get(&job_id)
+            .and_then(|job| job.overlap_runs.iter().position(matches))
+    }
+
+    pub(super) fn finish_overlap_run(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+        index: usize,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        let now_ms = current_timestamp_ms();
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Ok(());
+        };
+        let OverlapRun { handle, mut run } = job.overlap_runs.remove(index);
+        run.status = status.to_string();
+        run.completed_at_ms = Some(now_ms);
+        run.error = error.map(ToOwned::to_owned);
+        storage
+            .save_scheduled_job_run(&run.to_stored(job_id))
+            .map_err(|err| err.to_string())?;
+        let finished_event = KernelEvent::JobRunFinished {
+            job_id,
+            run_id: run.run_id,
+            attempt: run.attempt,
+            status: run.status.clone(),
+            error: run.error.clone(),
+        };
+        job.push_recent_run(run);
+        job.last_run_completed_at_ms = Some(now_ms);
+        job.updated_at_ms = now_ms;
+        storage
+            .save_scheduled_job(&job.to_stored())
+            .map_err(|err| err.to_string())?;
+        if let Some(orchestration_id) = handle.orchestration_id() {
+            self.orchestration_to_job.remove(&orchestration_id);
+        }
+        if let Some(pid) = handle.pid() {
+            self.process_to_job.remove(&pid);
+        }
+        self.lifecycle_events.push(finished_event);
+        Ok(())
+    }
+
+    /// Time out the overlap runs past their deadline and return their handles,
+    /// whose processes the caller must stop.
+    pub fn expire_overlap_runs(
+        &mut self,
+        storage: &mut StorageService,
+        now_ms: i64,
+    ) -> Vec<ScheduledRunHandle> {
+        let expired = self
+            .jobs
+            .values()
+            .flat_map(|job| {
+                job.overlap_runs
+                    .iter()
+                    .filter(|overlap| {
+                        overlap
+                            .run
+                            .deadline_at_ms
+                            .is_some_and(|deadline| deadline <= now_ms)
+                    })
+                    .map(|overlap| (job.job_id, overlap.run.run_id))
+            })
+            .collect::<Vec<_>>();
+
+        let mut handles = Vec::new();
+        for (job_id, run_id) in expired {
+            let Some(index) =
+                self.overlap_run_index(job_id, |overlap| overlap.run.run_id == run_id)
+            else {
+                continue;
+            };
+            let handle = self.jobs[&job_id].overlap_runs[index].handle;
+            match self.finish_overlap_run(
+                storage,
+                job_id,
+                index,
+                "timed_out",
+                Some("scheduler_timeout"),
+            ) {
+                Ok(()) => handles.push(handle),
+                Err(err) => {
+                    tracing::error!(job_id, run_id, %err, "SCHEDULER: failed to time out overlap run")
+                }
+            }
+        }
+        handles
+    }
+
+    /// Cancel the active run of `job_id` so that a newer occurrence can start.
+    fn cancel_active_run(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+        reason: &str,
+        now_ms: i64,
+    ) -> Result<Option<ScheduledRunHandle>, String> {
+        let Some(job) = self.jobs.get_mut(&job_id) else {
+            return Ok(None);
+        };
+        let handle = job.active_handle.or(job
+            .active_orchestration_id
+            .map(ScheduledRunHandle::Orchestration));
+        if let Some(orchestration_id) = handle.and_then(ScheduledRunHandle::orchestration_id) {
+            self.orchestration_to_job.remove(&orchestration_id);
+        }
+        if let Some(pid) = handle.and_then(ScheduledRunHandle::pid) {
+            self.process_to_job.remove(&pid);
+        }
+        if let Some(run_id) = job.active_run_id {
+            if let Some(run) = job.recent_runs.iter_mut().find(|run| run.run_id == run_id) {
+                run.status = "cancelled".to_string();
+                run.completed_at_ms = Some(now_ms);
+                run.reason = Some(reason.to_string());
+                storage
+                    .save_scheduled_job_run(&run.to_stored(job_id))
+                    .map_err(|err| err.to_string())?;
+                self.lifecycle_events.push(KernelEvent::JobRunFinished {
+                    job_id,
+                    run_id,
+                    attempt: run.attempt,
+                    status: run.status.clone(),
+                    error: None,
+                });
+            }
+        }
+
+        job.state = ScheduledJobState::Idle;
+        job.current_trigger_at_ms = None;
+        job.current_attempt = 0;
+        job.current_trigger_event = None;
+        job.active_run_id = None;
+        job.active_orchestration_id = None;
+        job.active_deadline_at_ms = None;
+        job.active_handle = None;
+        job.last_run_completed_at_ms = Some(now_ms);
+        job.last_run_status = Some("cancelled".to_string());
+        job.last_error = None;
+        job.updated_at_ms = now_ms;
+        Ok(handle)
+    }
+}
+
+impl ScheduledJob {
+    /// The active run, if any, plus the overlap runs.
+    pub(crate) fn active_run_count(&self) -> usize {
+        usize::from(self.state == ScheduledJobState::Running) + self.overlap_runs.len()
+    }
+
+    /// Next occurrence the concurrency policy rules on while a run is in
+    /// flight. Event-driven jobs are re-armed by `enqueue_trigger_event`.
+    pub(super) fn next_overlap_occurrence(&self, now_ms: i64) -> Option<i64> {
+        if self.trigger.is_event_driven() {
+            None
+        } else {
+            self.trigger.next_after(now_ms)
+        }
+    }
+
+    /// Next run once the active one is final: the oldest queued occurrence
+    /// first, then the schedule resumed past the occurrences already ruled on.
+    pub(super) fn next_scheduled_run(&mut self, now_ms: i64) -> Option<i64> {
+        if let Some(queued_at_ms) = self.queued_runs.pop_front() {
+            self.start_reason = Some("dequeued");
+            return Some(queued_at_ms);
+        }
+        let next = self
+            .trigger
+            .next_after_run(self.current_trigger_at_ms, now_ms);
+        let Some((first_ms, last_ms)) = self.ruled_occurrences else {
+            return next;
+        };
+        match next {
+            // Backlog older than the ruled occurrences is still replayed.
+            Some(next_ms) if next_ms < first_ms => next,
+            Some(next_ms) if next_ms <= last_ms => {
+                self.ruled_occurrences = None;
+                self.trigger.next_after_run(Some(last_ms), now_ms)
+            }
+            _ => {
+                self.ruled_occurrences = None;
+                next
+            }
+        }
+    }
+
+    fn rule_occurrence(&mut self, occurrence_at_ms: i64, now_ms: i64) {
+        self.ruled_occurrences = Some(match self.ruled_occurrences {
+            Some((first_ms, last_ms)) => (
+                first_ms.min(occurrence_at_ms),
+                last_ms.max(occurrence_at_ms),
+            ),
+            None => (occurrence_at_ms, occurrence_at_ms),
+        });
+        self.next_run_at_ms = if self.trigger.is_event_driven() {
+            (matches!(self.concurrency_policy, ConcurrencyPolicy::Allow { .. })
+                && !self.pending_trigger_events.is_empty())
+            .then_some(now_ms)
+        } else {
+            self.next_overlap_occurrence(now_ms)
+        };
+    }
+
+    fn record_decision(
+        &mut self,
+        storage: &mut StorageService,
+        trigger_at_ms: i64,
+        status: &str,
+        reason: &str,
+        now_ms: i64,
+    ) -> Result<(), String> {
+        let mut run = ScheduledJobRun {
+            run_id: 0,
+            trigger_at_ms,
+            attempt: 0,
+            status: status.to_string(),
+            started_at_ms: None,
+            completed_at_ms: Some(now_ms),
+            orchestration_id: None,
+            deadline_at_ms: None,
+            error: None,
+            reason: Some(reason.to_string()),
+        };
+        run.run_id = storage
+            .insert_scheduled_job_run(&run.to_stored(self.job_id))
+            .map_err(|err| err.to_string())?;
+        tracing::info!(
+            job_id = self.job_id,
+            run_id = run.run_id,
+            trigger_at_ms,
+            status,
+            reason,
+            "SCHEDULER: recorded concurrency decision"
+        );
+        self.push_recent_run(run);
+        Ok(())
+    }
+
+    /// Record once per occurrence that the global run cap holds the job back.
+    fn record_deferral(
+        &mut self,
+        storage: &mut StorageService,
+        now_ms: i64,
+    ) -> Result<JobAdmission, String> {
+        if self.deferred_at_ms.is_some() && self.deferred_at_ms == self.next_run_at_ms {
+            return Ok(JobAdmission::Waiting);
+        }
+        let trigger_at_ms = if self.state == ScheduledJobState::Running {
+            self.next_run_at_ms
+        } else {
+            self.current_trigger_at_ms.or(self.next_run_at_ms)
+        }
+        .unwrap_or(now_ms);
+        self.record_decision(
+            storage,
+            trigger_at_ms,
+            "deferred",
+            "global_concurrency_limit",
+            now_ms,
+        )?;
+        self.deferred_at_ms = self.next_run_at_ms;
+        Ok(JobAdmission::Held)
+    }
+}
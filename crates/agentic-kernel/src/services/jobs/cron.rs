@@ -1,10 +1,17 @@
-use chrono::{DateTime, Datelike, Duration as ChronoDuration, Timelike, Utc};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, LocalResult, NaiveDate, NaiveDateTime,
+    TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
 use serde::Deserialize;
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, VecDeque};
 
 use super::scheduler::{
-    CronField, CronSchedule, ScheduledJobState, ScheduledJobTrigger, ScheduledJobTriggerInput,
+    CompletionFilter, CronField, CronSchedule, CronWeekdayRule, MissedRunPolicy, ScheduledJobState,
+    ScheduledJobTrigger, ScheduledJobTriggerInput, MAX_CATCH_UP_RUNS,
 };
+use super::triggers::DEFAULT_FILE_DEBOUNCE_MS;
+use crate::tools::path_guard::{normalize_relative_path, workspace_root};
 
 impl ScheduledJobTrigger {
     pub(crate) fn from_request(
@@ -21,6 +28,7 @@ impl ScheduledJobTrigger {
             ScheduledJobTriggerInput::Interval {
                 every_ms,
                 starts_at_ms,
+                missed_run_policy,
             } => {
                 if every_ms == 0 {
                     return Err("every_ms must be > 0".to_string());
@@ -28,15 +36,70 @@ impl ScheduledJobTrigger {
                 Ok(Self::Interval {
                     every_ms,
                     anchor_ms: starts_at_ms.unwrap_or(now_ms),
+                    missed_run_policy,
                 })
             }
-            ScheduledJobTriggerInput::Cron { expression } => {
-                let schedule = CronSchedule::parse(&expression)?;
+            ScheduledJobTriggerInput::Cron {
+                expression,
+                timezone,
+                missed_run_policy,
+            } => {
+                let timezone = timezone
+                    .map(|timezone| timezone.trim().to_string())
+                    .filter(|timezone| !timezone.is_empty());
+                let schedule = Box::new(CronSchedule::parse(&expression, timezone.as_deref())?);
                 Ok(Self::Cron {
                     expression,
+                    timezone,
+                    missed_run_policy,
                     schedule,
                 })
             }
+            ScheduledJobTriggerInput::FileChange {
+                path,
+                glob,
+                debounce_ms,
+            } => {
+                let path = path.trim().to_string();
+                normalize_relative_path(&workspace_root()?, &path)?;
+                let glob = glob
+                    .map(|glob| glob.trim().to_string())
+                    .filter(|glob| !glob.is_empty());
+                if let Some(pattern) = glob.as_deref() {
+                    glob::Pattern::new(pattern)
+                        .map_err(|err| format!("Invalid glob '{}': {}", pattern, err))?;
+                }
+                Ok(Self::FileChange {
+                    path,
+                    glob,
+                    debounce_ms: debounce_ms.unwrap_or(DEFAULT_FILE_DEBOUNCE_MS),
+                })
+            }
+            ScheduledJobTriggerInput::Completion {
+                job_id,
+                orchestration_id,
+                on,
+            } => match (job_id, orchestration_id) {
+                (Some(_), Some(_)) => Err(
+                    "completion trigger accepts either job_id or orchestration_id, not both"
+                        .to_string(),
+                ),
+                (None, None) => {
+                    Err("completion trigger requires job_id or orchestration_id".to_string())
+                }
+                _ => Ok(Self::Completion {
+                    job_id,
+                    orchestration_id,
+                    on,
+                }),
+            },
+            ScheduledJobTriggerInput::IpcMessage { channel } => {
+                let channel = channel.trim().to_string();
+                if channel.is_empty() {
+                    return Err("channel must not be empty".to_string());
+                }
+                Ok(Self::IpcMessage { channel })
+            }
         }
     }
 
@@ -58,27 +121,85 @@ impl ScheduledJobTrigger {
                 struct IntervalPayload {
                     every_ms: u64,
                     anchor_ms: i64,
+                    #[serde(default)]
+                    missed_run_policy: MissedRunPolicy,
                 }
                 let parsed = serde_json::from_str::<IntervalPayload>(payload)
                     .map_err(|err| err.to_string())?;
                 Ok(Self::Interval {
                     every_ms: parsed.every_ms.max(1),
                     anchor_ms: parsed.anchor_ms,
+                    missed_run_policy: parsed.missed_run_policy,
                 })
             }
             "cron" => {
                 #[derive(Deserialize)]
                 struct CronPayload {
                     expression: String,
+                    #[serde(default)]
+                    timezone: Option<String>,
+                    #[serde(default)]
+                    missed_run_policy: MissedRunPolicy,
                 }
                 let parsed =
                     serde_json::from_str::<CronPayload>(payload).map_err(|err| err.to_string())?;
-                let schedule = CronSchedule::parse(&parsed.expression)?;
+                let schedule = Box::new(CronSchedule::parse(
+                    &parsed.expression,
+                    parsed.timezone.as_deref(),
+                )?);
                 Ok(Self::Cron {
                     expression: parsed.expression,
+                    timezone: parsed.timezone,
+                    missed_run_policy: parsed.missed_run_policy,
                     schedule,
                 })
             }
+            "file_change" => {
+                #[derive(Deserialize)]
+                struct FileChangePayload {
+                    path: String,
+                    #[serde(default)]
+                    glob: Option<String>,
+                    #[serde(default)]
+                    debounce_ms: Option<u64>,
+                }
+                let parsed = serde_json::from_str::<FileChangePayload>(payload)
+                    .map_err(|err| err.to_string())?;
+                Ok(Self::FileChange {
+                    path: parsed.path,
+                    glob: parsed.glob,
+                    debounce_ms: parsed.debounce_ms.unwrap_or(DEFAULT_FILE_DEBOUNCE_MS),
+                })
+            }
+            "completion" => {
+                #[derive(Deserialize)]
+                struct CompletionPayload {
+                    #[serde(default)]
+                    job_id: Option<u64>,
+                    #[serde(default)]
+                    orchestration_id: Option<u64>,
+                    #[serde(default)]
+                    on: CompletionFilter,
+                }
+                let parsed = serde_json::from_str::<CompletionPayload>(payload)
+                    .map_err(|err| err.to_string())?;
+                Ok(Self::Completion {
+                    job_id: parsed.job_id,
+                    orchestration_id: parsed.orchestration_id,
+                    on: parsed.on,
+                })
+            }
+            "ipc_message" => {
+                #[derive(Deserialize)]
+                struct IpcMessagePayload {
+                    channel: String,
+                }
+                let parsed = serde_json::from_str::<IpcMessagePayload>(payload)
+                    .map_err(|err| err.to_string())?;
+                Ok(Self::IpcMessage {
+                    channel: parsed.channel,
+                })
+            }
             other => Err(format!("Unsupported trigger kind '{}'", other)),
         }
     }
@@ -88,14 +209,57 @@ impl ScheduledJobTrigger {
             Self::At { .. } => "at",
             Self::Interval { .. } => "interval",
             Self::Cron { .. } => "cron",
+            Self::FileChange { .. } => "file_change",
+            Self::Completion { .. } => "completion",
+            Self::IpcMessage { .. } => "ipc_message",
         }
     }
 
+    /// Event-driven triggers have no clock: runs are queued by the event
+    /// sources in `triggers.rs` instead of `next_after`.
+    pub(crate) fn is_event_driven(&self) -> bool {
+        matches!(
+            self,
+            Self::FileChange { .. } | Self::Completion { .. } | Self::IpcMessage { .. }
+        )
+    }
+
     pub(crate) fn label(&self) -> String {
-        match self {
+        let label = match self {
             Self::At { at_ms } => format!("at {}", at_ms),
             Self::Interval { every_ms, .. } => format!("every {}s", every_ms / 1_000),
+            Self::Cron {
+                expression,
+                timezone: Some(timezone),
+                ..
+            } => format!("cron {} ({})", expression, timezone),
             Self::Cron { expression, .. } => format!("cron {}", expression),
+            Self::FileChange {
+                path,
+                glob: Some(glob),
+                ..
+            } => format!("on file change {} ({})", path, glob),
+            Self::FileChange { path, .. } => format!("on file change {}", path),
+            Self::Completion {
+                job_id,
+                orchestration_id,
+                on,
+            } => {
+                let source = match (job_id, orchestration_id) {
+                    (Some(job_id), _) => format!("job {}", job_id),
+                    (None, Some(orchestration_id)) => {
+                        format!("orchestration {}", orchestration_id)
+                    }
+                    (None, None) => "unknown source".to_string(),
+                };
+                format!("after {} ({})", source, on.as_str())
+            }
+            Self::IpcMessage { channel } => format!("on ipc channel {}", channel),
+        };
+        match self.missed_run_policy() {
+            MissedRunPolicy::RunOnce => label,
+            MissedRunPolicy::Skip => format!("{label}, missed: skip"),
+            MissedRunPolicy::CatchUpAll => format!("{label}, missed: catch_up_all"),
         }
     }
 
@@ -106,15 +270,63 @@ impl ScheduledJobTrigger {
             Self::Interval {
                 every_ms,
                 anchor_ms,
+                missed_run_policy,
             } => serde_json::to_string(&serde_json::json!({
                 "every_ms": every_ms,
                 "anchor_ms": anchor_ms,
+                "missed_run_policy": missed_run_policy,
             }))
             .map_err(|err| err.to_string()),
-            Self::Cron { expression, .. } => serde_json::to_string(&serde_json::json!({
+            Self::Cron {
+                expression,
+                timezone,
+                missed_run_policy,
+                ..
+            } => serde_json::to_string(&serde_json::json!({
                 "expression": expression,
+                "timezone": timezone,
+                "missed_run_policy": missed_run_policy,
+            }))
+            .map_err(|err| err.to_string()),
+            Self::FileChange {
+                path,
+                glob,
+                debounce_ms,
+            } => serde_json::to_string(&serde_json::json!({
+                "path": path,
+                "glob": glob,
+                "debounce_ms": debounce_ms,
+            }))
+            .map_err(|err| err.to_string()),
+            Self::Completion {
+                job_id,
+                orchestration_id,
+                on,
+            } => serde_json::to_string(&serde_json::json!({
+                "job_id": job_id,
+                "orchestration_id": orchestration_id,
+                "on": on,
             }))
             .map_err(|err| err.to_string()),
+            Self::IpcMessage { channel } => {
+                serde_json::to_string(&serde_json::json!({ "channel": channel }))
+                    .map_err(|err| err.to_string())
+            }
+        }
+    }
+
+    pub(crate) fn missed_run_policy(&self) -> MissedRunPolicy {
+        match self {
+            Self::At { .. }
+            | Self::FileChange { .. }
+            | Self::Completion { .. }
+            | Self::IpcMessage { .. } => MissedRunPolicy::RunOnce,
+            Self::Interval {
+                missed_run_policy, ..
+            }
+            | Self::Cron {
+                missed_run_policy, ..
+            } => *missed_run_policy,
         }
     }
 
@@ -124,6 +336,7 @@ impl ScheduledJobTrigger {
             Self::Interval {
                 every_ms,
                 anchor_ms,
+                ..
             } => {
                 if after_ms < *anchor_ms {
                     return Some(*anchor_ms);
@@ -134,6 +347,64 @@ impl ScheduledJobTrigger {
                 Some(anchor_ms.saturating_add(ticks.saturating_mul(every_ms)))
             }
             Self::Cron { schedule, .. } => schedule.next_after(after_ms),
+            Self::FileChange { .. } | Self::Completion { .. } | Self::IpcMessage { .. } => None,
+        }
+    }
+
+    /// Next run once the occurrence due at `trigger_at_ms` is settled.
+    /// `catch_up_all` keeps walking the occurrences that are already due,
+    /// every other policy resumes from `now_ms`.
+    pub(crate) fn next_after_run(&self, trigger_at_ms: Option<i64>, now_ms: i64) -> Option<i64> {
+        match (self.missed_run_policy(), trigger_at_ms) {
+            (MissedRunPolicy::CatchUpAll, Some(trigger_at_ms)) => {
+                let next = self.next_after(trigger_at_ms)?;
+                if next <= now_ms {
+                    Some(self.catch_up_start(next, now_ms, MAX_CATCH_UP_RUNS))
+                } else {
+                    Some(next)
+                }
+            }
+            _ => self.next_after(now_ms),
+        }
+    }
+
+    /// Apply the missed-run policy to a run that was due at `missed_at_ms`
+    /// while the kernel was down.
+    pub(crate) fn resume_missed(&self, missed_at_ms: i64, now_ms: i64) -> Option<i64> {
+        match self.missed_run_policy() {
+            MissedRunPolicy::Skip => self.next_after(now_ms),
+            MissedRunPolicy::RunOnce => Some(self.catch_up_start(missed_at_ms, now_ms, 1)),
+            MissedRunPolicy::CatchUpAll => {
+                Some(self.catch_up_start(missed_at_ms, now_ms, MAX_CATCH_UP_RUNS))
+            }
+        }
+    }
+
+    /// Oldest of the last `limit` occurrences in `[first_missed_ms, now_ms]`.
+    /// The window grows backwards from `now_ms` so a dense schedule after a
+    /// long downtime never enumerates more than roughly `2 * limit` runs.
+    fn catch_up_start(&self, first_missed_ms: i64, now_ms: i64, limit: usize) -> i64 {
+        let limit = limit.max(1);
+        let mut window_ms: i64 = 1_000;
+        loop {
+            let floor_ms = now_ms
+                .saturating_sub(window_ms)
+                .max(first_missed_ms.saturating_sub(1));
+            let mut recent = VecDeque::with_capacity(limit);
+            let mut found = 0usize;
+            let mut cursor_ms = floor_ms;
+            while let Some(next) = self.next_after(cursor_ms).filter(|next| *next <= now_ms) {
+                if recent.len() == limit {
+                    recent.pop_front();
+                }
+                recent.push_back(next);
+                found += 1;
+                cursor_ms = next;
+            }
+            if found >= limit || floor_ms < first_missed_ms {
+                return recent.front().copied().unwrap_or(first_missed_ms);
+            }
+            window_ms = window_ms.saturating_mul(2);
         }
     }
 }
@@ -161,47 +432,121 @@ impl ScheduledJobState {
 }
 
 impl CronSchedule {
-    pub(crate) fn parse(expression: &str) -> Result<Self, String> {
+    pub(crate) fn parse(expression: &str, timezone: Option<&str>) -> Result<Self, String> {
+        let timezone = match timezone {
+            Some(name) => name
+                .parse::<Tz>()
+                .map_err(|_| format!("Unknown IANA timezone '{}'", name))?,
+            None => Tz::UTC,
+        };
+        let expression = expand_cron_macro(expression.trim())?;
         let parts = expression.split_whitespace().collect::<Vec<_>>();
-        if parts.len() != 5 {
-            return Err("Cron expression must have 5 fields: min hour dom month dow".to_string());
-        }
+        let (second, fields) = match parts.len() {
+            5 => ("0", parts.as_slice()),
+            6 => (parts[0], &parts[1..]),
+            _ => {
+                return Err(
+                    "Cron expression must have 5 or 6 fields: [sec] min hour dom month dow"
+                        .to_string(),
+                )
+            }
+        };
+        let (day_of_month, last_day_of_month) = parse_day_of_month(fields[2])?;
+        let (day_of_week, weekday_rules) = parse_day_of_week(fields[4])?;
         Ok(Self {
-            minute: CronField::parse(parts[0], 0, 59)?,
-            hour: CronField::parse(parts[1], 0, 23)?,
-            day_of_month: CronField::parse(parts[2], 1, 31)?,
-            month: CronField::parse(parts[3], 1, 12)?,
-            day_of_week: CronField::parse(parts[4], 0, 6)?,
+            second: CronField::parse(second, 0, 59, &[])?,
+            minute: CronField::parse(fields[0], 0, 59, &[])?,
+            hour: CronField::parse(fields[1], 0, 23, &[])?,
+            day_of_month,
+            month: CronField::parse(fields[3], 1, 12, MONTH_NAMES)?,
+            day_of_week,
+            last_day_of_month,
+            weekday_rules,
+            timezone,
         })
     }
 
+    /// First fire time strictly after `after_ms`, evaluated on the wall
+    /// clock of the schedule timezone. A local time skipped by a DST jump
+    /// fires at the first instant after the gap; a local time repeated when
+    /// clocks go back fires only on its first occurrence.
     fn next_after(&self, after_ms: i64) -> Option<i64> {
-        let next_minute_ms = after_ms.div_euclid(60_000).saturating_add(1) * 60_000;
-        let mut candidate = DateTime::<Utc>::from_timestamp_millis(next_minute_ms)?;
-        for _ in 0..(366 * 24 * 60) {
-            if self.matches(candidate) {
-                return Some(candidate.timestamp_millis());
+        let after = DateTime::<Utc>::from_timestamp_millis(after_ms)?;
+        let start = after
+            .with_timezone(&self.timezone)
+            .naive_local()
+            .with_nanosecond(0)?
+            + ChronoDuration::seconds(1);
+        let mut date = start.date();
+        for _ in 0..MAX_CRON_SEARCH_DAYS {
+            if self.matches_date(date) {
+                let first_day = date == start.date();
+                for hour in self
+                    .hour
+                    .values_from(if first_day { start.hour() } else { 0 })
+                {
+                    let first_hour = first_day && hour == start.hour();
+                    for minute in
+                        self.minute
+                            .values_from(if first_hour { start.minute() } else { 0 })
+                    {
+                        let first_minute = first_hour && minute == start.minute();
+                        for second in
+                            self.second
+                                .values_from(if first_minute { start.second() } else { 0 })
+                        {
+                            let local = date.and_hms_opt(hour, minute, second)?;
+                            let Some(fire_at) = resolve_local_time(&self.timezone, local) else {
+                                continue;
+                            };
+                            if fire_at.timestamp_millis() > after_ms {
+                                return Some(fire_at.timestamp_millis());
+                            }
+                        }
+                    }
+                }
             }
-            candidate += ChronoDuration::minutes(1);
+            date = date.succ_opt()?;
         }
         None
     }
 
-    fn matches(&self, candidate: DateTime<Utc>) -> bool {
-        self.minute.matches(candidate.minute())
-            && self.hour.matches(candidate.hour())
-            && self.day_of_month.matches(candidate.day())
-            && self.month.matches(candidate.month())
-            && self
-                .day_of_week
-                .matches(candidate.weekday().num_days_from_sunday())
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        let last_day = last_day_of_month(date);
+        let weekday = date.weekday().num_days_from_sunday();
+        let day_of_month_matches = self.day_of_month.matches(date.day())
+            || (self.last_day_of_month && date.day() == last_day);
+        let day_of_week_matches = self.day_of_week.matches(weekday)
+            || self.weekday_rules.iter().any(|rule| match *rule {
+                CronWeekdayRule::Last { weekday: wanted } => {
+                    weekday == wanted && date.day() + 7 > last_day
+                }
+                CronWeekdayRule::Nth {
+                    weekday: wanted,
+                    nth,
+                } => weekday == wanted && (date.day() - 1) / 7 + 1 == nth,
+            });
+        self.month.matches(date.month()) && day_of_month_matches && day_of_week_matches
     }
 }
 
 impl CronField {
-    fn parse(input: &str, min: u32, max: u32) -> Result<Self, String> {
+    fn parse(input: &str, min: u32, max: u32, names: &[&str]) -> Result<Self, String> {
+        let field = Self::from_parts(input.split(','), min, max, names)?;
+        if field.values.is_empty() {
+            return Err("Cron field resolved to an empty set".to_string());
+        }
+        Ok(field)
+    }
+
+    fn from_parts<'a>(
+        parts: impl Iterator<Item = &'a str>,
+        min: u32,
+        max: u32,
+        names: &[&str],
+    ) -> Result<Self, String> {
         let mut values = BTreeSet::new();
-        for part in input.split(',') {
+        for part in parts {
             let part = part.trim();
             if part.is_empty() {
                 return Err("Cron field cannot be empty".to_string());
@@ -213,7 +558,10 @@ impl CronField {
                 if step == 0 {
                     return Err("Cron step must be > 0".to_string());
                 }
-                let (range_start, range_end) = parse_range(base, min, max)?;
+                let (range_start, mut range_end) = parse_range(base, min, max, names)?;
+                if !base.contains('-') && base != "*" && base != "?" {
+                    range_end = max;
+                }
                 let mut current = range_start;
                 while current <= range_end {
                     values.insert(current);
@@ -225,44 +573,117 @@ impl CronField {
                 continue;
             }
 
-            let (range_start, range_end) = parse_range(part, min, max)?;
+            let (range_start, range_end) = parse_range(part, min, max, names)?;
             for value in range_start..=range_end {
                 values.insert(value);
             }
         }
 
-        if values.is_empty() {
-            return Err("Cron field resolved to an empty set".to_string());
-        }
-
         Ok(Self { values })
     }
 
     fn matches(&self, value: u32) -> bool {
         self.values.contains(&value)
     }
+
+    fn values_from(&self, start: u32) -> impl Iterator<Item = u32> + '_ {
+        self.values.range(start..).copied()
+    }
+}
+
+const MONTH_NAMES: &[&str] = &[
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
+];
+const WEEKDAY_NAMES: &[&str] = &["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+/// Long enough for a leap day that must also fall on a given weekday.
+const MAX_CRON_SEARCH_DAYS: usize = 366 * 28;
+
+fn expand_cron_macro(expression: &str) -> Result<&str, String> {
+    if !expression.starts_with('@') {
+        return Ok(expression);
+    }
+    match expression.to_ascii_lowercase().as_str() {
+        "@yearly" | "@annually" => Ok("0 0 1 1 *"),
+        "@monthly" => Ok("0 0 1 * *"),
+        "@weekly" => Ok("0 0 * * 0"),
+        "@daily" | "@midnight" => Ok("0 0 * * *"),
+        "@hourly" => Ok("0 * * * *"),
+        _ => Err(format!("Unknown cron macro '{}'", expression)),
+    }
 }
 
-fn parse_range(input: &str, min: u32, max: u32) -> Result<(u32, u32), String> {
-    if input == "*" {
+fn parse_day_of_month(input: &str) -> Result<(CronField, bool), String> {
+    let mut last_day = false;
+    let parts = input
+        .split(',')
+        .filter(|part| {
+            let is_last = part.trim().eq_ignore_ascii_case("L");
+            last_day |= is_last;
+            !is_last
+        })
+        .collect::<Vec<_>>();
+    let field = CronField::from_parts(parts.into_iter(), 1, 31, &[])?;
+    if field.values.is_empty() && !last_day {
+        return Err("Cron field resolved to an empty set".to_string());
+    }
+    Ok((field, last_day))
+}
+
+fn parse_day_of_week(input: &str) -> Result<(CronField, Vec<CronWeekdayRule>), String> {
+    let mut rules = Vec::new();
+    let mut plain = Vec::new();
+    for part in input.split(',') {
+        let trimmed = part.trim();
+        if let Some((weekday_raw, nth_raw)) = trimmed.split_once('#') {
+            let weekday = parse_cron_number(weekday_raw, 0, 7, WEEKDAY_NAMES)? % 7;
+            let nth = parse_cron_number(nth_raw, 1, 5, &[])?;
+            rules.push(CronWeekdayRule::Nth { weekday, nth });
+        } else if trimmed.len() > 1
+            && trimmed.ends_with(['L', 'l'])
+            && !trimmed.contains(['-', '/', '*'])
+        {
+            let weekday =
+                parse_cron_number(&trimmed[..trimmed.len() - 1], 0, 7, WEEKDAY_NAMES)? % 7;
+            rules.push(CronWeekdayRule::Last { weekday });
+        } else {
+            plain.push(trimmed);
+        }
+    }
+    let mut field = CronField::from_parts(plain.into_iter(), 0, 7, WEEKDAY_NAMES)?;
+    if field.values.remove(&7) {
+        field.values.insert(0);
+    }
+    if field.values.is_empty() && rules.is_empty() {
+        return Err("Cron field resolved to an empty set".to_string());
+    }
+    Ok((field, rules))
+}
+
+fn parse_range(input: &str, min: u32, max: u32, names: &[&str]) -> Result<(u32, u32), String> {
+    if input == "*" || input == "?" {
         return Ok((min, max));
     }
     if let Some((start_raw, end_raw)) = input.split_once('-') {
-        let start = parse_cron_number(start_raw, min, max)?;
-        let end = parse_cron_number(end_raw, min, max)?;
+        let start = parse_cron_number(start_raw, min, max, names)?;
+        let end = parse_cron_number(end_raw, min, max, names)?;
         if start > end {
             return Err(format!("Invalid cron range '{}'", input));
         }
         return Ok((start, end));
     }
-    let value = parse_cron_number(input, min, max)?;
+    let value = parse_cron_number(input, min, max, names)?;
     Ok((value, value))
 }
 
-fn parse_cron_number(raw: &str, min: u32, max: u32) -> Result<u32, String> {
-    let value = raw
-        .parse::<u32>()
-        .map_err(|_| format!("Invalid cron value '{}'", raw))?;
+fn parse_cron_number(raw: &str, min: u32, max: u32, names: &[&str]) -> Result<u32, String> {
+    let value = match raw.parse::<u32>() {
+        Ok(value) => value,
+        Err(_) => names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(raw))
+            .map(|index| index as u32 + min)
+            .ok_or_else(|| format!("Invalid cron value '{}'", raw))?,
+    };
     if value < min || value > max {
         return Err(format!(
             "Cron value '{}' out of range {}..={}",
@@ -271,3 +692,143 @@ fn parse_cron_number(raw: &str, min: u32, max: u32) -> Result<u32, String> {
     }
     Ok(value)
 }
+
+fn last_day_of_month(date: NaiveDate) -> u32 {
+    let (year, month) = if date.month() == 12 {
+        (date.year() + 1, 1)
+    } else {
+        (date.year(), date.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
+
+fn resolve_local_time(timezone: &Tz, local: NaiveDateTime) -> Option<DateTime<Tz>> {
+    match timezone.from_local_datetime(&local) {
+        LocalResult::Single(fire_at) => Some(fire_at),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            let mut probe = local.with_second(0)?;
+            for _ in 0..(24 * 60) {
+                probe += ChronoDuration::minutes(1);
+                if let Some(fire_at) = timezone.from_local_datetime(&probe).earliest() {
+                    return Some(fire_at);
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc_ms(value: &str) -> i64 {
+        DateTime::parse_from_rfc3339(value)
+            .expect("rfc3339 timestamp")
+            .timestamp_millis()
+    }
+
+    fn next(expression: &str, timezone: Option<&str>, after: &str) -> String {
+        let schedule = CronSchedule::parse(expression, timezone).expect("parse cron");
+        let next_ms = schedule.next_after(utc_ms(after)).expect("next fire");
+        DateTime::<Utc>::from_timestamp_millis(next_ms)
+            .expect("timestamp")
+            .to_rfc3339()
+    }
+
+    #[test]
+    fn weekday_schedule_keeps_local_time_across_dst() {
+        let rome = Some("Europe/Rome");
+        assert_eq!(
+            next("0 9 * * MON-FRI", rome, "2026-03-27T06:00:00Z"),
+            "2026-03-27T08:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 9 * * MON-FRI", rome, "2026-03-27T12:00:00Z"),
+            "2026-03-30T07:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn dst_gap_fires_after_jump_and_overlap_fires_once() {
+        let rome = Some("Europe/Rome");
+        assert_eq!(
+            next("30 2 * * *", rome, "2026-03-29T00:00:00Z"),
+            "2026-03-29T01:00:00+00:00"
+        );
+        assert_eq!(
+            next("30 2 * * *", rome, "2026-03-29T01:00:00Z"),
+            "2026-03-30T00:30:00+00:00"
+        );
+        assert_eq!(
+            next("30 2 * * *", rome, "2026-10-24T23:00:00Z"),
+            "2026-10-25T00:30:00+00:00"
+        );
+        assert_eq!(
+            next("30 2 * * *", rome, "2026-10-25T00:30:00Z"),
+            "2026-10-26T01:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn parses_names_macros_seconds_and_day_modifiers() {
+        let utc = None;
+        assert_eq!(
+            next("@hourly", utc, "2026-01-01T10:15:00Z"),
+            "2026-01-01T11:00:00+00:00"
+        );
+        assert_eq!(
+            next("*/15 * * * * *", utc, "2026-01-01T00:00:07Z"),
+            "2026-01-01T00:00:15+00:00"
+        );
+        assert_eq!(
+            next("0 0 1 jan,JUL *", utc, "2026-02-01T00:00:00Z"),
+            "2026-07-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 L * *", utc, "2028-02-10T00:00:00Z"),
+            "2028-02-29T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 * * 5L", utc, "2026-01-01T00:00:00Z"),
+            "2026-01-30T00:00:00+00:00"
+        );
+        assert_eq!(
+            next("0 0 * * MON#2", utc, "2026-02-01T00:00:00Z"),
+            "2026-02-09T00:00:00+00:00"
+        );
+        assert!(CronSchedule::parse("0 9 * * *", Some("Mars/Olympus")).is_err());
+        assert!(CronSchedule::parse("@fortnightly", None).is_err());
+        assert!(CronSchedule::parse("0 0 * * MON#6", None).is_err());
+    }
+
+    #[test]
+    fn missed_run_policies_pick_the_resume_point() {
+        let trigger = |missed_run_policy| ScheduledJobTrigger::Interval {
+            every_ms: 1_000,
+            anchor_ms: 0,
+            missed_run_policy,
+        };
+        let skip = trigger(MissedRunPolicy::Skip);
+        let run_once = trigger(MissedRunPolicy::RunOnce);
+        let catch_up = trigger(MissedRunPolicy::CatchUpAll);
+
+        assert_eq!(skip.resume_missed(1_000, 10_500), Some(11_000));
+        assert_eq!(run_once.resume_missed(1_000, 10_500), Some(10_000));
+        assert_eq!(catch_up.resume_missed(1_000, 10_500), Some(1_000));
+        assert_eq!(catch_up.next_after_run(Some(1_000), 10_500), Some(2_000));
+        assert_eq!(run_once.next_after_run(Some(1_000), 10_500), Some(11_000));
+
+        let dense = ScheduledJobTrigger::Interval {
+            every_ms: 1,
+            anchor_ms: 0,
+            missed_run_policy: MissedRunPolicy::CatchUpAll,
+        };
+        let start = dense.resume_missed(1, 1_000_000).expect("catch-up start");
+        assert_eq!(start, 1_000_000 - MAX_CATCH_UP_RUNS as i64 + 1);
+    }
+}
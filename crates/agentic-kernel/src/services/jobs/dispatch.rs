@@ -1,7 +1,10 @@
-use crate::orchestrator::TaskGraphDef;
+use agentic_control_models::KernelEvent;
+
 use crate::storage::{current_timestamp_ms, StorageService};
 
-use super::scheduler::{DueJobDispatch, JobScheduler, ScheduledJobRun, ScheduledJobState};
+use super::scheduler::{
+    DueJobDispatch, JobScheduler, ScheduledJobRun, ScheduledJobState, ScheduledRunHandle,
+};
 
 impl JobScheduler {
     pub fn dispatch_plan(&self, job_id: u64) -> Result<DueJobDispatch, String> {
@@ -13,13 +16,16 @@ impl JobScheduler {
             .or(job.next_run_at_ms)
             .ok_or_else(|| format!("Scheduled job {} has no trigger time", job_id))?;
         let attempt = job.current_attempt.saturating_add(1);
-        let workflow = serde_json::from_str::<TaskGraphDef>(&job.workflow_payload)
-            .map_err(|err| format!("Invalid persisted workflow payload: {}", err))?;
         Ok(DueJobDispatch {
             job_id,
             trigger_at_ms,
             attempt,
-            workflow,
+            target: job.target.clone(),
+            trigger_event: job
+                .current_trigger_event
+                .clone()
+                .or_else(|| job.pending_trigger_events.front().cloned()),
+            overlap: false,
         })
     }
 
@@ -29,13 +35,15 @@ impl JobScheduler {
         job_id: u64,
         trigger_at_ms: i64,
         attempt: u32,
-        orchestration_id: u64,
-    ) -> Result<(), String> {
+        handle: ScheduledRunHandle,
+    ) -> Result<u64, String> {
         let now_ms = current_timestamp_ms();
         let Some(job) = self.jobs.get_mut(&job_id) else {
             return Err(format!("Scheduled job {} not found", job_id));
         };
         let deadline_at_ms = now_ms + job.timeout_ms as i64;
+        let orchestration_id = handle.orchestration_id();
+        let pid = handle.pid();
         let mut run = ScheduledJobRun {
             run_id: 0,
             trigger_at_ms,
@@ -43,33 +51,53 @@ impl JobScheduler {
             status: "running".to_string(),
             started_at_ms: Some(now_ms),
             completed_at_ms: None,
-            orchestration_id: Some(orchestration_id),
+            orchestration_id,
             deadline_at_ms: Some(deadline_at_ms),
             error: None,
+            reason: job.start_reason.take().map(ToOwned::to_owned),
         };
         let run_id = storage
             .insert_scheduled_job_run(&run.to_stored(job_id))
             .map_err(|err| err.to_string())?;
         run.run_id = run_id;
 
+        job.take_trigger_event();
         job.state = ScheduledJobState::Running;
         job.current_trigger_at_ms = Some(trigger_at_ms);
         job.current_attempt = attempt;
         job.active_run_id = Some(run_id);
-        job.active_orchestration_id = Some(orchestration_id);
+        job.active_orchestration_id = orchestration_id;
         job.active_deadline_at_ms = Some(deadline_at_ms);
+        job.active_handle = Some(handle);
         job.last_run_started_at_ms = Some(now_ms);
         job.last_run_status = Some("running".to_string());
         job.last_error = None;
-        job.next_run_at_ms = None;
+        job.manual_run_requested = false;
+        job.deferred_at_ms = None;
+        // The schedule keeps ticking while the run is in flight, so that the
+        // concurrency policy can rule on the occurrences that fall meanwhile.
+        job.next_run_at_ms = job.next_overlap_occurrence(now_ms);
         job.updated_at_ms = now_ms;
         job.push_recent_run(run);
 
         storage
             .save_scheduled_job(&job.to_stored())
             .map_err(|err| err.to_string())?;
-        self.orchestration_to_job.insert(orchestration_id, job_id);
-        Ok(())
+        if let Some(orchestration_id) = orchestration_id {
+            self.orchestration_to_job.insert(orchestration_id, job_id);
+        }
+        if let Some(pid) = pid {
+            self.process_to_job.insert(pid, job_id);
+        }
+        self.lifecycle_events.push(KernelEvent::JobRunStarted {
+            job_id,
+            run_id,
+            attempt,
+            trigger_at_ms,
+            orchestration_id,
+            pid,
+        });
+        Ok(run_id)
     }
 
     pub fn mark_dispatch_failed(
@@ -94,18 +122,29 @@ impl JobScheduler {
             orchestration_id: None,
             deadline_at_ms: None,
             error: Some(error.to_string()),
+            reason: job.start_reason.take().map(ToOwned::to_owned),
         };
         let run_id = storage
             .insert_scheduled_job_run(&run.to_stored(job_id))
             .map_err(|err| err.to_string())?;
         run.run_id = run_id;
         job.push_recent_run(run);
+        job.take_trigger_event();
+        job.manual_run_requested = false;
+        job.deferred_at_ms = None;
         job.current_trigger_at_ms = Some(trigger_at_ms);
         job.current_attempt = attempt;
         job.transition_after_failure("failed", error, now_ms);
         storage
             .save_scheduled_job(&job.to_stored())
             .map_err(|err| err.to_string())?;
+        self.lifecycle_events.push(KernelEvent::JobRunFinished {
+            job_id,
+            run_id,
+            attempt,
+            status: "failed".to_string(),
+            error: Some(error.to_string()),
+        });
         Ok(())
     }
 }
@@ -1,8 +1,24 @@
-use agentic_control_models::ScheduledJobRunView;
+use agentic_control_models::{KernelEvent, ScheduledJobRunView};
 
 use crate::storage::{current_timestamp_ms, StorageService, StoredScheduledJobRun};
 
-use super::scheduler::{JobScheduler, ScheduledJob, ScheduledJobRun, ScheduledJobState};
+use super::scheduler::{
+    JobScheduler, ScheduledJob, ScheduledJobRun, ScheduledJobState, ScheduledRunHandle,
+};
+
+/// `SessionFinished` reasons that end an `exec` or `send_input` run as failed;
+/// any other reason means the turn ran to completion.
+const FAILED_PROCESS_REASONS: &[&str] = &[
+    "terminated",
+    "killed",
+    "syscall_timeout",
+    "syscall_killed",
+    "scheduled_job_timeout",
+    "scheduled_job_replaced",
+    "orchestrator_killed",
+    "orchestration_stopped",
+    "token_quota_reached",
+];
 
 impl JobScheduler {
     pub fn complete_orchestration(
@@ -15,6 +31,97 @@ impl JobScheduler {
         let Some(job_id) = self.orchestration_to_job.remove(&orchestration_id) else {
             return Ok(());
         };
+        if let Some(index) = self.overlap_run_index(job_id, |overlap| {
+            overlap.handle.orchestration_id() == Some(orchestration_id)
+        }) {
+            return self.finish_overlap_run(storage, job_id, index, status, error);
+        }
+        self.finish_active_run(storage, job_id, status, error)
+    }
+
+    pub fn complete_process_run(
+        &mut self,
+        storage: &mut StorageService,
+        pid: u64,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        let Some(job_id) = self.process_to_job.remove(&pid) else {
+            return Ok(());
+        };
+        if let Some(index) =
+            self.overlap_run_index(job_id, |overlap| overlap.handle.pid() == Some(pid))
+        {
+            return self.finish_overlap_run(storage, job_id, index, status, error);
+        }
+        self.finish_active_run(storage, job_id, status, error)
+    }
+
+    /// Settles a tool run; results of runs that already timed out are dropped.
+    pub fn complete_tool_run(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+        run_id: u64,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(index) = self.overlap_run_index(job_id, |overlap| {
+            overlap.run.run_id == run_id && overlap.handle == ScheduledRunHandle::Tool
+        }) {
+            return self.finish_overlap_run(storage, job_id, index, status, error);
+        }
+        let is_active = self.jobs.get(&job_id).is_some_and(|job| {
+            job.active_run_id == Some(run_id) && job.active_handle == Some(ScheduledRunHandle::Tool)
+        });
+        if !is_active {
+            return Ok(());
+        }
+        self.finish_active_run(storage, job_id, status, error)
+    }
+
+    /// Settles the `exec` and `send_input` runs whose process finished or
+    /// errored in `events`. Returns how many runs were settled.
+    pub fn observe_process_events(
+        &mut self,
+        storage: &mut StorageService,
+        events: &[KernelEvent],
+    ) -> usize {
+        let mut settled = 0usize;
+        for event in events {
+            let (pid, status, error) = match event {
+                KernelEvent::SessionFinished { pid, reason, .. } => {
+                    if FAILED_PROCESS_REASONS.contains(&reason.as_str()) {
+                        (*pid, "failed", Some(reason.clone()))
+                    } else {
+                        (*pid, "completed", None)
+                    }
+                }
+                KernelEvent::SessionErrored { pid, message } => {
+                    (*pid, "failed", Some(message.clone()))
+                }
+                _ => continue,
+            };
+            if !self.process_to_job.contains_key(&pid) {
+                continue;
+            }
+            match self.complete_process_run(storage, pid, status, error.as_deref()) {
+                Ok(()) => settled += 1,
+                Err(err) => {
+                    tracing::error!(pid, %err, "SCHEDULER: failed to finalize process job run")
+                }
+            }
+        }
+        settled
+    }
+
+    fn finish_active_run(
+        &mut self,
+        storage: &mut StorageService,
+        job_id: u64,
+        status: &str,
+        error: Option<&str>,
+    ) -> Result<(), String> {
         let now_ms = current_timestamp_ms();
         let Some(job) = self.jobs.get_mut(&job_id) else {
             return Ok(());
@@ -34,6 +141,13 @@ impl JobScheduler {
         storage
             .save_scheduled_job_run(&run.to_stored(job_id))
             .map_err(|err| err.to_string())?;
+        let finished_event = KernelEvent::JobRunFinished {
+            job_id,
+            run_id,
+            attempt: run.attempt,
+            status: status.to_string(),
+            error: run.error.clone(),
+        };
 
         if status == "completed" {
             job.transition_after_success(now_ms);
@@ -44,6 +158,7 @@ impl JobScheduler {
         storage
             .save_scheduled_job(&job.to_stored())
             .map_err(|err| err.to_string())?;
+        self.lifecycle_events.push(finished_event);
         Ok(())
     }
 
@@ -51,14 +166,22 @@ impl JobScheduler {
         &mut self,
         storage: &mut StorageService,
         job_id: u64,
-    ) -> Result<Option<u64>, String> {
+    ) -> Result<Option<ScheduledRunHandle>, String> {
         let now_ms = current_timestamp_ms();
         let Some(job) = self.jobs.get_mut(&job_id) else {
             return Ok(None);
         };
-        let orchestration_id = job.active_orchestration_id;
-        if let Some(active_orchestration_id) = orchestration_id {
-            self.orchestration_to_job.remove(&active_orchestration_id);
+        let handle = job.active_handle.or(job
+            .active_orchestration_id
+            .map(ScheduledRunHandle::Orchestration));
+        match handle {
+            Some(ScheduledRunHandle::Orchestration(orchestration_id)) => {
+                self.orchestration_to_job.remove(&orchestration_id);
+            }
+            Some(ScheduledRunHandle::Process(pid) | ScheduledRunHandle::SessionInput(pid)) => {
+                self.process_to_job.remove(&pid);
+            }
+            Some(ScheduledRunHandle::Tool) | None => {}
         }
         let run_id = job
             .active_run_id
@@ -75,11 +198,19 @@ impl JobScheduler {
         storage
             .save_scheduled_job_run(&run.to_stored(job_id))
             .map_err(|err| err.to_string())?;
+        let finished_event = KernelEvent::JobRunFinished {
+            job_id,
+            run_id,
+            attempt: run.attempt,
+            status: run.status.clone(),
+            error: run.error.clone(),
+        };
         job.transition_after_failure("timed_out", "scheduler_timeout", now_ms);
         storage
             .save_scheduled_job(&job.to_stored())
             .map_err(|err| err.to_string())?;
-        Ok(orchestration_id)
+        self.lifecycle_events.push(finished_event);
+        Ok(handle)
     }
 }
 
@@ -89,8 +220,14 @@ impl ScheduledJob {
             .retain(|existing| existing.run_id != run.run_id);
         self.recent_runs.insert(0, run);
         self.recent_runs
-            .sort_by(|left, right| right.run_id.cmp(&left.run_id));
-        self.recent_runs.truncate(super::scheduler::MAX_RECENT_RUNS);
+            .sort_by_key(|run| std::cmp::Reverse(run.run_id));
+        // The active run stays even when newer decisions push it out of the window.
+        let active_run_id = self.active_run_id;
+        let mut kept = 0usize;
+        self.recent_runs.retain(|run| {
+            kept += 1;
+            kept <= super::scheduler::MAX_RECENT_RUNS || Some(run.run_id) == active_run_id
+        });
     }
 
     pub(super) fn transition_after_success(&mut self, now_ms: i64) {
@@ -104,7 +241,7 @@ impl ScheduledJob {
             ScheduledJobState::Disabled
         };
         self.next_run_at_ms = if self.enabled && !is_one_shot {
-            self.trigger.next_after(now_ms)
+            self.next_scheduled_run(now_ms)
         } else {
             None
         };
@@ -113,6 +250,7 @@ impl ScheduledJob {
         self.active_run_id = None;
         self.active_orchestration_id = None;
         self.active_deadline_at_ms = None;
+        self.active_handle = None;
         self.last_run_completed_at_ms = Some(now_ms);
         self.last_run_status = Some("completed".to_string());
         self.last_error = None;
@@ -122,6 +260,7 @@ impl ScheduledJob {
             self.enabled = false;
             self.state = ScheduledJobState::Completed;
         }
+        self.settle_trigger_event(now_ms);
     }
 
     pub(super) fn transition_after_failure(&mut self, status: &str, error: &str, now_ms: i64) {
@@ -132,6 +271,7 @@ impl ScheduledJob {
         self.active_run_id = None;
         self.active_orchestration_id = None;
         self.active_deadline_at_ms = None;
+        self.active_handle = None;
         self.last_run_completed_at_ms = Some(now_ms);
         self.last_run_status = Some(status.to_string());
         self.last_error = Some(error.to_string());
@@ -144,21 +284,23 @@ impl ScheduledJob {
             return;
         }
 
-        self.current_attempt = 0;
-        self.current_trigger_at_ms = None;
         self.next_run_at_ms = if self.enabled && !is_one_shot {
-            self.trigger.next_after(now_ms)
+            self.next_scheduled_run(now_ms)
         } else {
             None
         };
-        self.state = if self.enabled && self.next_run_at_ms.is_some() {
-            ScheduledJobState::Idle
-        } else if is_one_shot {
-            self.enabled = false;
-            ScheduledJobState::Completed
-        } else {
-            ScheduledJobState::Disabled
-        };
+        self.current_attempt = 0;
+        self.current_trigger_at_ms = None;
+        self.state =
+            if self.enabled && (self.next_run_at_ms.is_some() || self.trigger.is_event_driven()) {
+                ScheduledJobState::Idle
+            } else if is_one_shot {
+                self.enabled = false;
+                ScheduledJobState::Completed
+            } else {
+                ScheduledJobState::Disabled
+            };
+        self.settle_trigger_event(now_ms);
     }
 }
 
@@ -175,6 +317,7 @@ impl ScheduledJobRun {
             orchestration_id: self.orchestration_id,
             deadline_at_ms: self.deadline_at_ms,
             error: self.error.clone(),
+            reason: self.reason.clone(),
         }
     }
 }
@@ -191,6 +334,7 @@ impl From<StoredScheduledJobRun> for ScheduledJobRun {
             orchestration_id: value.orchestration_id,
             deadline_at_ms: value.deadline_at_ms,
             error: value.error,
+            reason: value.reason,
         }
     }
 }
@@ -207,6 +351,7 @@ impl From<ScheduledJobRun> for ScheduledJobRunView {
             orchestration_id: value.orchestration_id,
             deadline_at_ms: value.deadline_at_ms,
             error: value.error,
+            reason: value.reason,
         }
     }
 }
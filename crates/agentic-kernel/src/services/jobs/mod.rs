@@ -1,5 +1,8 @@
+mod concurrency;
 mod cron;
 mod dispatch;
 mod history;
 pub(crate) mod scheduler;
 mod state;
+mod targets;
+mod triggers;
@@ -1,30 +1,129 @@
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 
-use agentic_control_models::{ScheduleJobResult, ScheduledJobView};
+use agentic_control_models::{KernelEvent, ScheduleJobResult, ScheduledJobView};
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
 
-use crate::orchestrator::{FailurePolicy, Orchestrator, TaskGraphDef};
+use crate::orchestrator::{FailurePolicy, Orchestrator, TaskGraphDef, WorkflowTrigger};
+use crate::scheduler::QuotaLimits;
 use crate::storage::{
     current_timestamp_ms, NewScheduledJobRecord, StorageService, StoredScheduledJob,
 };
+use crate::tools::invocation::ProcessPathGrant;
+
+pub(crate) use super::concurrency::{ConcurrencyPolicy, JobAdmission};
 
 const DEFAULT_JOB_TIMEOUT_MS: u64 = 15 * 60 * 1_000;
 const DEFAULT_JOB_BACKOFF_MS: u64 = 30 * 1_000;
 pub(super) const MAX_RECENT_RUNS: usize = 8;
 pub(crate) const SCHEDULER_SYSTEM_OWNER_ID: usize = 0;
+/// Upper bound on the occurrences replayed by `catch_up_all` after downtime.
+pub(super) const MAX_CATCH_UP_RUNS: usize = 100;
 
 #[derive(Debug, Clone)]
-pub(crate) struct ScheduledWorkflowJobRequest {
+pub(crate) struct ScheduledJobRequest {
     pub name: String,
-    pub workflow: TaskGraphDef,
-    pub workflow_payload: String,
+    pub target: ScheduledJobTarget,
     pub trigger: ScheduledJobTriggerInput,
     pub timeout_ms: Option<u64>,
     pub max_retries: Option<u32>,
     pub backoff_ms: Option<u64>,
+    pub concurrency_policy: ConcurrencyPolicy,
     pub enabled: bool,
 }
 
+/// `target` of a SCHEDULE_JOB request.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ScheduledJobTargetInput {
+    Workflow { workflow: serde_json::Value },
+    Exec(ExecJobTarget),
+    SendInput(SendInputJobTarget),
+    Tool(ToolJobTarget),
+}
+
+/// What a scheduled job runs when it fires.
+#[derive(Debug, Clone)]
+pub(crate) enum ScheduledJobTarget {
+    /// Task graph started as an orchestration; `payload` keeps the submitted JSON.
+    Workflow {
+        workflow: TaskGraphDef,
+        payload: String,
+    },
+    /// One-shot ephemeral agent process, as spawned by EXEC.
+    Exec(ExecJobTarget),
+    /// New user turn appended to an existing session, as by SEND_INPUT.
+    SendInput(SendInputJobTarget),
+    /// Direct call of a registered tool, outside any process.
+    Tool(ToolJobTarget),
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ExecJobTarget {
+    pub prompt: String,
+    /// `fast`, `code`, `reasoning` or `general`; inferred from the prompt when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workload: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_syscalls: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_scopes: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_grants: Option<Vec<ProcessPathGrant>>,
+    /// Wall-clock, spend, remote-input and tool-time limits of the process.
+    #[serde(flatten)]
+    pub limits: QuotaLimits,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SendInputJobTarget {
+    pub session_id: String,
+    pub prompt: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct ToolJobTarget {
+    pub tool: String,
+    #[serde(default = "empty_tool_arguments")]
+    pub arguments: serde_json::Value,
+}
+
+fn empty_tool_arguments() -> serde_json::Value {
+    serde_json::Value::Object(serde_json::Map::new())
+}
+
+/// What a running job attempt is bound to, as handed to `mark_started`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScheduledRunHandle {
+    Orchestration(u64),
+    /// Ephemeral process owned by the run; killed when the run times out.
+    Process(u64),
+    /// Turn appended to a session the job does not own; never killed.
+    SessionInput(u64),
+    /// Tool call running on a worker thread.
+    Tool,
+}
+
+impl ScheduledRunHandle {
+    pub(crate) fn orchestration_id(self) -> Option<u64> {
+        match self {
+            Self::Orchestration(orchestration_id) => Some(orchestration_id),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn pid(self) -> Option<u64> {
+        match self {
+            Self::Process(pid) | Self::SessionInput(pid) => Some(pid),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum ScheduledJobTriggerInput {
@@ -35,28 +134,90 @@ pub enum ScheduledJobTriggerInput {
         every_ms: u64,
         #[serde(default)]
         starts_at_ms: Option<i64>,
+        #[serde(default)]
+        missed_run_policy: MissedRunPolicy,
     },
     Cron {
         expression: String,
+        /// IANA timezone the expression is evaluated in; UTC when omitted.
+        #[serde(default)]
+        timezone: Option<String>,
+        #[serde(default)]
+        missed_run_policy: MissedRunPolicy,
     },
+    /// Runs after files under a workspace-relative `path` change and then
+    /// stay quiet for `debounce_ms`.
+    FileChange {
+        path: String,
+        /// Matched against paths relative to `path`, e.g. `**/*.md`.
+        #[serde(default)]
+        glob: Option<String>,
+        #[serde(default)]
+        debounce_ms: Option<u64>,
+    },
+    /// Runs when another scheduled job or an orchestration finishes.
+    Completion {
+        #[serde(default)]
+        job_id: Option<u64>,
+        #[serde(default)]
+        orchestration_id: Option<u64>,
+        #[serde(default)]
+        on: CompletionFilter,
+    },
+    /// Runs once per message delivered on a named IPC channel.
+    IpcMessage {
+        channel: String,
+    },
+}
+
+/// Which outcomes of the watched job or orchestration fire a completion trigger.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompletionFilter {
+    Success,
+    Failure,
+    #[default]
+    Any,
+}
+
+/// What to do with occurrences that fell while the kernel was down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MissedRunPolicy {
+    /// Drop missed occurrences and wait for the next future one.
+    Skip,
+    /// Run once for the most recent missed occurrence.
+    #[default]
+    RunOnce,
+    /// Replay every missed occurrence in order, up to `MAX_CATCH_UP_RUNS`.
+    CatchUpAll,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct JobScheduler {
     pub(super) jobs: BTreeMap<u64, ScheduledJob>,
     pub(super) orchestration_to_job: HashMap<u64, u64>,
+    /// PID of the process behind an `exec` or `send_input` run -> job id.
+    pub(super) process_to_job: HashMap<u64, u64>,
+    pub(super) lifecycle_events: Vec<KernelEvent>,
+    pub(super) file_watches: BTreeMap<u64, FileWatchState>,
+    /// Last IPC message seen by channel triggers; `None` until a channel job exists.
+    pub(super) ipc_cursor: Option<i64>,
+    pub(super) last_event_poll_ms: i64,
+    /// Runs allowed in flight at once across all jobs.
+    pub(super) max_concurrent_runs: usize,
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct ScheduledJob {
     pub job_id: u64,
     pub name: String,
-    pub target_kind: String,
-    pub workflow_payload: String,
+    pub target: ScheduledJobTarget,
     pub trigger: ScheduledJobTrigger,
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub backoff_ms: u64,
+    pub concurrency_policy: ConcurrencyPolicy,
     pub enabled: bool,
     pub state: ScheduledJobState,
     pub next_run_at_ms: Option<i64>,
@@ -65,6 +226,8 @@ pub(crate) struct ScheduledJob {
     pub active_run_id: Option<u64>,
     pub active_orchestration_id: Option<u64>,
     pub active_deadline_at_ms: Option<i64>,
+    /// Handle of the run in flight; not persisted, running jobs fail on restart.
+    pub active_handle: Option<ScheduledRunHandle>,
     pub last_run_started_at_ms: Option<i64>,
     pub last_run_completed_at_ms: Option<i64>,
     pub last_run_status: Option<String>,
@@ -73,6 +236,45 @@ pub(crate) struct ScheduledJob {
     pub created_at_ms: i64,
     pub updated_at_ms: i64,
     pub recent_runs: Vec<ScheduledJobRun>,
+    /// Events waiting for a run; kept in memory only.
+    pub pending_trigger_events: VecDeque<JobTriggerEvent>,
+    /// Event consumed by the run in flight, replayed on retries.
+    pub current_trigger_event: Option<JobTriggerEvent>,
+    /// Extra runs started by the `allow` policy next to the active one.
+    pub overlap_runs: Vec<OverlapRun>,
+    /// Occurrences held back by the `queue` policy, oldest first.
+    pub queued_runs: VecDeque<i64>,
+    /// First and last occurrence ruled on while a run was in flight; the
+    /// schedule resumes past them once the active run settles.
+    pub ruled_occurrences: Option<(i64, i64)>,
+    /// Set by RUN_JOB_NOW until the requested occurrence is dispatched or ruled on.
+    pub manual_run_requested: bool,
+    /// Reason recorded on the next run started for this job.
+    pub start_reason: Option<&'static str>,
+    /// `next_run_at_ms` for which a `deferred` run is already recorded.
+    pub deferred_at_ms: Option<i64>,
+}
+
+/// Run started by the `allow` policy while the job already had one in flight.
+/// Overlap runs never retry and do not move the job schedule.
+#[derive(Debug, Clone)]
+pub(crate) struct OverlapRun {
+    pub handle: ScheduledRunHandle,
+    pub run: ScheduledJobRun,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct JobTriggerEvent {
+    pub occurred_at_ms: i64,
+    pub payload: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct FileWatchState {
+    /// Relative path -> (mtime ns, size) at the last scan.
+    pub(super) snapshot: BTreeMap<String, (u128, u64)>,
+    pub(super) changed: BTreeSet<String>,
+    pub(super) last_change_ms: Option<i64>,
 }
 
 #[derive(Debug, Clone)]
@@ -86,6 +288,7 @@ pub(crate) struct ScheduledJobRun {
     pub orchestration_id: Option<u64>,
     pub deadline_at_ms: Option<i64>,
     pub error: Option<String>,
+    pub reason: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -93,7 +296,29 @@ pub(crate) struct DueJobDispatch {
     pub job_id: u64,
     pub trigger_at_ms: i64,
     pub attempt: u32,
-    pub workflow: TaskGraphDef,
+    pub target: ScheduledJobTarget,
+    pub trigger_event: Option<JobTriggerEvent>,
+    /// Extra run admitted by the `allow` policy; settled with `mark_overlap_*`.
+    pub overlap: bool,
+}
+
+impl DueJobDispatch {
+    /// Trigger event handed to the workflow as its input artifact.
+    pub(crate) fn workflow_trigger(&self) -> Option<WorkflowTrigger> {
+        self.trigger_event.as_ref().map(|event| WorkflowTrigger {
+            mime_type: "application/json".to_string(),
+            content_text: serde_json::to_string_pretty(&event.payload)
+                .unwrap_or_else(|_| event.payload.to_string()),
+        })
+    }
+
+    /// `prompt` followed by the trigger event, for `exec` and `send_input` runs.
+    pub(crate) fn prompt_with_trigger(&self, prompt: &str) -> String {
+        match self.workflow_trigger() {
+            Some(trigger) => format!("{prompt}\n\n[Job trigger event]\n{}", trigger.content_text),
+            None => prompt.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -104,20 +329,42 @@ pub(crate) enum ScheduledJobTrigger {
     Interval {
         every_ms: u64,
         anchor_ms: i64,
+        missed_run_policy: MissedRunPolicy,
     },
     Cron {
         expression: String,
-        schedule: CronSchedule,
+        timezone: Option<String>,
+        missed_run_policy: MissedRunPolicy,
+        schedule: Box<CronSchedule>,
+    },
+    FileChange {
+        path: String,
+        glob: Option<String>,
+        debounce_ms: u64,
+    },
+    Completion {
+        job_id: Option<u64>,
+        orchestration_id: Option<u64>,
+        on: CompletionFilter,
+    },
+    IpcMessage {
+        channel: String,
     },
 }
 
 #[derive(Debug, Clone)]
 pub(crate) struct CronSchedule {
+    pub(super) second: CronField,
     pub(super) minute: CronField,
     pub(super) hour: CronField,
     pub(super) day_of_month: CronField,
     pub(super) month: CronField,
     pub(super) day_of_week: CronField,
+    /// `L` in the day-of-month field.
+    pub(super) last_day_of_month: bool,
+    /// `5L` / `1#2` entries of the day-of-week field.
+    pub(super) weekday_rules: Vec<CronWeekdayRule>,
+    pub(super) timezone: Tz,
 }
 
 #[derive(Debug, Clone)]
@@ -125,6 +372,12 @@ pub(super) struct CronField {
     pub(super) values: BTreeSet<u32>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CronWeekdayRule {
+    Last { weekday: u32 },
+    Nth { weekday: u32, nth: u32 },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum ScheduledJobState {
     Idle,
@@ -139,9 +392,19 @@ impl JobScheduler {
         Self {
             jobs: BTreeMap::new(),
             orchestration_to_job: HashMap::new(),
+            process_to_job: HashMap::new(),
+            lifecycle_events: Vec::new(),
+            file_watches: BTreeMap::new(),
+            ipc_cursor: None,
+            last_event_poll_ms: 0,
+            max_concurrent_runs: super::concurrency::DEFAULT_MAX_CONCURRENT_JOB_RUNS,
         }
     }
 
+    pub(crate) fn take_lifecycle_events(&mut self) -> Vec<KernelEvent> {
+        std::mem::take(&mut self.lifecycle_events)
+    }
+
     pub fn load(storage: &mut StorageService) -> Result<Self, String> {
         let now_ms = current_timestamp_ms();
         let mut scheduler = Self::new();
@@ -172,24 +435,34 @@ impl JobScheduler {
         Ok(scheduler)
     }
 
-    pub fn schedule_workflow_job(
+    pub fn schedule_job(
         &mut self,
         storage: &mut StorageService,
-        request: ScheduledWorkflowJobRequest,
+        request: ScheduledJobRequest,
     ) -> Result<ScheduleJobResult, String> {
-        validate_workflow_definition(&request.workflow)?;
+        request.target.validate()?;
+        request.concurrency_policy.validate_for(&request.target)?;
 
         let now_ms = current_timestamp_ms();
         let trigger = ScheduledJobTrigger::from_request(request.trigger, now_ms)
             .map_err(|err| err.to_string())?;
         let timeout_ms = request.timeout_ms.unwrap_or(DEFAULT_JOB_TIMEOUT_MS).max(1);
         let backoff_ms = request.backoff_ms.unwrap_or(DEFAULT_JOB_BACKOFF_MS).max(1);
-        let next_run_at_ms = if request.enabled {
-            trigger
-                .next_after(now_ms - 1)
-                .ok_or_else(|| "The requested trigger does not produce a future run.".to_string())?
+        if let ScheduledJobTrigger::Completion {
+            job_id: Some(source_job_id),
+            ..
+        } = &trigger
+        {
+            if !self.jobs.contains_key(source_job_id) {
+                return Err(format!("Scheduled job {} not found", source_job_id));
+            }
+        }
+        let next_run_at_ms = if request.enabled && !trigger.is_event_driven() {
+            Some(trigger.next_after(now_ms - 1).ok_or_else(|| {
+                "The requested trigger does not produce a future run.".to_string()
+            })?)
         } else {
-            0
+            None
         };
         let state = if request.enabled {
             ScheduledJobState::Idle
@@ -199,20 +472,17 @@ impl JobScheduler {
         let stored = storage
             .insert_scheduled_job(&NewScheduledJobRecord {
                 name: request.name.trim().to_string(),
-                target_kind: "workflow".to_string(),
-                workflow_payload: request.workflow_payload,
+                target_kind: request.target.kind().to_string(),
+                workflow_payload: request.target.to_payload(),
                 trigger_kind: trigger.kind().to_string(),
                 trigger_payload: trigger.to_payload_json()?,
                 timeout_ms,
                 max_retries: request.max_retries.unwrap_or(0),
                 backoff_ms,
+                concurrency_policy: request.concurrency_policy.label(),
                 enabled: request.enabled,
                 state: state.as_str().to_string(),
-                next_run_at_ms: if request.enabled {
-                    Some(next_run_at_ms)
-                } else {
-                    None
-                },
+                next_run_at_ms,
                 current_trigger_at_ms: None,
                 current_attempt: 0,
                 active_run_id: None,
@@ -246,15 +516,17 @@ impl ScheduledJob {
     ) -> Result<(Self, bool), String> {
         let trigger =
             ScheduledJobTrigger::from_stored(&stored.trigger_kind, &stored.trigger_payload)?;
+        let target =
+            ScheduledJobTarget::from_stored(&stored.target_kind, &stored.workflow_payload)?;
         let mut job = Self {
             job_id: stored.job_id,
             name: stored.name,
-            target_kind: stored.target_kind,
-            workflow_payload: stored.workflow_payload,
+            target,
             trigger,
             timeout_ms: stored.timeout_ms.max(1),
             max_retries: stored.max_retries,
             backoff_ms: stored.backoff_ms.max(1),
+            concurrency_policy: ConcurrencyPolicy::from_label(&stored.concurrency_policy)?,
             enabled: stored.enabled,
             state: ScheduledJobState::from_str(&stored.state),
             next_run_at_ms: stored.next_run_at_ms,
@@ -263,6 +535,7 @@ impl ScheduledJob {
             active_run_id: stored.active_run_id,
             active_orchestration_id: stored.active_orchestration_id,
             active_deadline_at_ms: stored.active_deadline_at_ms,
+            active_handle: None,
             last_run_started_at_ms: stored.last_run_started_at_ms,
             last_run_completed_at_ms: stored.last_run_completed_at_ms,
             last_run_status: stored.last_run_status,
@@ -271,6 +544,14 @@ impl ScheduledJob {
             created_at_ms: stored.created_at_ms,
             updated_at_ms: stored.updated_at_ms,
             recent_runs,
+            pending_trigger_events: VecDeque::new(),
+            current_trigger_event: None,
+            overlap_runs: Vec::new(),
+            queued_runs: VecDeque::new(),
+            ruled_occurrences: None,
+            manual_run_requested: false,
+            start_reason: None,
+            deferred_at_ms: None,
         };
         let mut needs_sync = false;
 
@@ -284,6 +565,17 @@ impl ScheduledJob {
         } else if job.state == ScheduledJobState::Running {
             job.transition_after_failure("interrupted", "kernel_restarted", now_ms);
             needs_sync = true;
+        } else if job.trigger.is_event_driven() {
+            // Queued events do not survive a restart; only retries keep a clock.
+            if job.state == ScheduledJobState::Idle && job.next_run_at_ms.is_some() {
+                job.next_run_at_ms = None;
+                job.updated_at_ms = now_ms;
+                needs_sync = true;
+            } else if job.state == ScheduledJobState::RetryWait && job.next_run_at_ms.is_none() {
+                job.next_run_at_ms = Some(now_ms);
+                job.updated_at_ms = now_ms;
+                needs_sync = true;
+            }
         } else if matches!(
             job.state,
             ScheduledJobState::Idle | ScheduledJobState::RetryWait
@@ -292,6 +584,15 @@ impl ScheduledJob {
             job.next_run_at_ms = job.trigger.next_after(now_ms);
             job.updated_at_ms = now_ms;
             needs_sync = true;
+        } else if job.state == ScheduledJobState::Idle {
+            if let Some(missed_at_ms) = job.next_run_at_ms.filter(|next| *next < now_ms) {
+                let resumed = job.trigger.resume_missed(missed_at_ms, now_ms);
+                if resumed != job.next_run_at_ms {
+                    job.next_run_at_ms = resumed;
+                    job.updated_at_ms = now_ms;
+                    needs_sync = true;
+                }
+            }
         }
 
         Ok((job, needs_sync))
@@ -301,7 +602,7 @@ impl ScheduledJob {
         ScheduledJobView {
             job_id: self.job_id,
             name: self.name.clone(),
-            target_kind: self.target_kind.clone(),
+            target_kind: self.target.kind().to_string(),
             trigger_kind: self.trigger.kind().to_string(),
             trigger_label: self.trigger.label(),
             enabled: self.enabled,
@@ -312,6 +613,8 @@ impl ScheduledJob {
             timeout_ms: self.timeout_ms,
             max_retries: self.max_retries,
             backoff_ms: self.backoff_ms,
+            concurrency_policy: self.concurrency_policy.label(),
+            active_runs: self.active_run_count() as u32,
             last_run_started_at_ms: self.last_run_started_at_ms,
             last_run_completed_at_ms: self.last_run_completed_at_ms,
             last_run_status: self.last_run_status.clone(),
@@ -326,8 +629,8 @@ impl ScheduledJob {
         StoredScheduledJob {
             job_id: self.job_id,
             name: self.name.clone(),
-            target_kind: self.target_kind.clone(),
-            workflow_payload: self.workflow_payload.clone(),
+            target_kind: self.target.kind().to_string(),
+            workflow_payload: self.target.to_payload(),
             trigger_kind: self.trigger.kind().to_string(),
             trigger_payload: self
                 .trigger
@@ -336,6 +639,7 @@ impl ScheduledJob {
             timeout_ms: self.timeout_ms,
             max_retries: self.max_retries,
             backoff_ms: self.backoff_ms,
+            concurrency_policy: self.concurrency_policy.label(),
             enabled: self.enabled,
             state: self.state.as_str().to_string(),
             next_run_at_ms: self.next_run_at_ms,
@@ -355,7 +659,7 @@ impl ScheduledJob {
     }
 }
 
-fn validate_workflow_definition(workflow: &TaskGraphDef) -> Result<(), String> {
+pub(super) fn validate_workflow_definition(workflow: &TaskGraphDef) -> Result<(), String> {
     let mut validator = Orchestrator::new();
     validator
         .register(
@@ -384,25 +688,24 @@ mod tests {
     fn interval_jobs_persist_and_reload_with_next_run() {
         let dir = make_temp_dir("agenticos_job_scheduler_reload");
         let db_path = dir.join("agenticos.db");
-        let workflow_json = sample_workflow_json();
 
         let mut storage = StorageService::open(&db_path).expect("open storage");
         let mut scheduler = JobScheduler::new();
         let result = scheduler
-            .schedule_workflow_job(
+            .schedule_job(
                 &mut storage,
-                ScheduledWorkflowJobRequest {
+                ScheduledJobRequest {
                     name: "heartbeat".to_string(),
-                    workflow: sample_workflow(),
-                    workflow_payload: serde_json::to_string(&workflow_json)
-                        .expect("serialize workflow"),
+                    target: sample_target(),
                     trigger: ScheduledJobTriggerInput::Interval {
                         every_ms: 1_000,
                         starts_at_ms: None,
+                        missed_run_policy: MissedRunPolicy::RunOnce,
                     },
                     timeout_ms: Some(2_000),
                     max_retries: Some(1),
                     backoff_ms: Some(500),
+                    concurrency_policy: ConcurrencyPolicy::default(),
                     enabled: true,
                 },
             )
@@ -437,20 +740,20 @@ mod tests {
         let mut storage = StorageService::open(&db_path).expect("open storage");
         let mut scheduler = JobScheduler::new();
         let result = scheduler
-            .schedule_workflow_job(
+            .schedule_job(
                 &mut storage,
-                ScheduledWorkflowJobRequest {
+                ScheduledJobRequest {
                     name: "retryable".to_string(),
-                    workflow: sample_workflow(),
-                    workflow_payload: serde_json::to_string(&sample_workflow_json())
-                        .expect("serialize workflow"),
+                    target: sample_target(),
                     trigger: ScheduledJobTriggerInput::Interval {
                         every_ms: 10_000,
                         starts_at_ms: None,
+                        missed_run_policy: MissedRunPolicy::RunOnce,
                     },
                     timeout_ms: Some(5_000),
                     max_retries: Some(2),
                     backoff_ms: Some(250),
+                    concurrency_policy: ConcurrencyPolicy::default(),
                     enabled: true,
                 },
             )
@@ -494,20 +797,20 @@ mod tests {
         let mut storage = StorageService::open(&db_path).expect("open storage");
         let mut scheduler = JobScheduler::new();
         let result = scheduler
-            .schedule_workflow_job(
+            .schedule_job(
                 &mut storage,
-                ScheduledWorkflowJobRequest {
+                ScheduledJobRequest {
                     name: "timed".to_string(),
-                    workflow: sample_workflow(),
-                    workflow_payload: serde_json::to_string(&sample_workflow_json())
-                        .expect("serialize workflow"),
+                    target: sample_target(),
                     trigger: ScheduledJobTriggerInput::Interval {
                         every_ms: 5_000,
                         starts_at_ms: None,
+                        missed_run_policy: MissedRunPolicy::RunOnce,
                     },
                     timeout_ms: Some(1_000),
                     max_retries: Some(1),
                     backoff_ms: Some(300),
+                    concurrency_policy: ConcurrencyPolicy::default(),
                     enabled: true,
                 },
             )
@@ -522,7 +825,7 @@ mod tests {
                 plan.job_id,
                 plan.trigger_at_ms,
                 plan.attempt,
-                77,
+                ScheduledRunHandle::Orchestration(77),
             )
             .expect("mark started");
 
@@ -535,7 +838,7 @@ mod tests {
             .into_iter()
             .find(|job| job.job_id == result.job_id)
             .expect("job exists");
-        assert_eq!(timed_out_orch, Some(77));
+        assert_eq!(timed_out_orch, Some(ScheduledRunHandle::Orchestration(77)));
         assert_eq!(job.state, ScheduledJobState::RetryWait);
         assert_eq!(job.last_run_status.as_deref(), Some("timed_out"));
         assert_eq!(job.last_error.as_deref(), Some("scheduler_timeout"));
@@ -545,6 +848,18 @@ mod tests {
             Some("timed_out")
         );
 
+        let events = scheduler.take_lifecycle_events();
+        assert!(matches!(
+            events.as_slice(),
+            [
+                agentic_control_models::KernelEvent::JobRunStarted {
+                    orchestration_id: Some(77),
+                    ..
+                },
+                agentic_control_models::KernelEvent::JobRunFinished { status, .. },
+            ] if status == "timed_out"
+        ));
+
         let _ = fs::remove_dir_all(dir);
     }
 
@@ -557,19 +872,18 @@ mod tests {
         let mut scheduler = JobScheduler::new();
         let now_ms = current_timestamp_ms();
         let result = scheduler
-            .schedule_workflow_job(
+            .schedule_job(
                 &mut storage,
-                ScheduledWorkflowJobRequest {
+                ScheduledJobRequest {
                     name: "one-shot".to_string(),
-                    workflow: sample_workflow(),
-                    workflow_payload: serde_json::to_string(&sample_workflow_json())
-                        .expect("serialize workflow"),
+                    target: sample_target(),
                     trigger: ScheduledJobTriggerInput::At {
                         at_ms: now_ms + 60_000,
                     },
                     timeout_ms: Some(2_000),
                     max_retries: Some(0),
                     backoff_ms: Some(250),
+                    concurrency_policy: ConcurrencyPolicy::default(),
                     enabled: true,
                 },
             )
@@ -584,7 +898,7 @@ mod tests {
                 plan.job_id,
                 plan.trigger_at_ms,
                 plan.attempt,
-                55,
+                ScheduledRunHandle::Orchestration(55),
             )
             .expect("mark started");
         scheduler
@@ -608,8 +922,637 @@ mod tests {
         let _ = fs::remove_dir_all(dir);
     }
 
-    fn sample_workflow() -> TaskGraphDef {
-        serde_json::from_value(sample_workflow_json()).expect("workflow json")
+    #[test]
+    fn missed_interval_runs_follow_policy_on_reload() {
+        let dir = make_temp_dir("agenticos_job_scheduler_missed");
+        let db_path = dir.join("agenticos.db");
+
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        let mut job_ids = Vec::new();
+        for policy in [
+            MissedRunPolicy::Skip,
+            MissedRunPolicy::RunOnce,
+            MissedRunPolicy::CatchUpAll,
+        ] {
+            let result = scheduler
+                .schedule_job(
+                    &mut storage,
+                    ScheduledJobRequest {
+                        name: format!("{policy:?}"),
+                        target: sample_target(),
+                        trigger: ScheduledJobTriggerInput::Interval {
+                            every_ms: 60_000,
+                            starts_at_ms: Some(0),
+                            missed_run_policy: policy,
+                        },
+                        timeout_ms: Some(2_000),
+                        max_retries: Some(0),
+                        backoff_ms: Some(250),
+                        concurrency_policy: ConcurrencyPolicy::default(),
+                        enabled: true,
+                    },
+                )
+                .expect("schedule interval job");
+            job_ids.push(result.job_id);
+        }
+
+        let now_ms = current_timestamp_ms();
+        let downtime_started_ms = now_ms - 10 * 60_000;
+        for job in scheduler.scheduled_jobs() {
+            let mut stored = job.to_stored();
+            stored.next_run_at_ms = job.trigger.next_after(downtime_started_ms);
+            storage
+                .save_scheduled_job(&stored)
+                .expect("rewind next run");
+        }
+        drop(scheduler);
+
+        let reloaded = JobScheduler::load(&mut storage).expect("reload scheduler");
+        let next_run = |job_id: u64| {
+            reloaded
+                .scheduled_jobs()
+                .into_iter()
+                .find(|job| job.job_id == job_id)
+                .and_then(|job| job.next_run_at_ms)
+                .expect("next run")
+        };
+        let latest_missed = (now_ms - 1).div_euclid(60_000) * 60_000;
+        assert!(next_run(job_ids[0]) > now_ms);
+        assert!(next_run(job_ids[1]) >= latest_missed && next_run(job_ids[1]) <= now_ms);
+        assert!(next_run(job_ids[2]) <= downtime_started_ms + 60_000);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn completion_trigger_queues_event_for_the_dependent_job() {
+        let dir = make_temp_dir("agenticos_job_scheduler_completion");
+        let db_path = dir.join("agenticos.db");
+
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        let schedule = |scheduler: &mut JobScheduler,
+                        storage: &mut StorageService,
+                        name: &str,
+                        trigger: ScheduledJobTriggerInput| {
+            scheduler
+                .schedule_job(
+                    storage,
+                    ScheduledJobRequest {
+                        name: name.to_string(),
+                        target: sample_target(),
+                        trigger,
+                        timeout_ms: Some(2_000),
+                        max_retries: Some(0),
+                        backoff_ms: Some(250),
+                        concurrency_policy: ConcurrencyPolicy::default(),
+                        enabled: true,
+                    },
+                )
+                .expect("schedule job")
+                .job_id
+        };
+        let source_id = schedule(
+            &mut scheduler,
+            &mut storage,
+            "source",
+            ScheduledJobTriggerInput::Interval {
+                every_ms: 60_000,
+                starts_at_ms: None,
+                missed_run_policy: MissedRunPolicy::RunOnce,
+            },
+        );
+        let dependent_id = schedule(
+            &mut scheduler,
+            &mut storage,
+            "dependent",
+            ScheduledJobTriggerInput::Completion {
+                job_id: Some(source_id),
+                orchestration_id: None,
+                on: CompletionFilter::Success,
+            },
+        );
+        assert!(scheduler.jobs[&dependent_id].next_run_at_ms.is_none());
+
+        let plan = scheduler.dispatch_plan(source_id).expect("source plan");
+        assert!(plan.workflow_trigger().is_none());
+        scheduler
+            .mark_started(
+                &mut storage,
+                source_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(40),
+            )
+            .expect("mark source started");
+        scheduler
+            .complete_orchestration(&mut storage, 40, "completed", None)
+            .expect("complete source");
+        let events = scheduler.take_lifecycle_events();
+        scheduler.observe_lifecycle_events(&events);
+
+        assert!(scheduler
+            .due_job_ids(current_timestamp_ms())
+            .contains(&dependent_id));
+        let plan = scheduler
+            .dispatch_plan(dependent_id)
+            .expect("dependent plan");
+        let trigger = plan.workflow_trigger().expect("workflow trigger");
+        assert_eq!(trigger.mime_type, "application/json");
+        let payload: serde_json::Value =
+            serde_json::from_str(&trigger.content_text).expect("trigger json");
+        assert_eq!(payload["kind"], "completion");
+        assert_eq!(payload["job_id"], source_id);
+        assert_eq!(payload["status"], "completed");
+
+        scheduler
+            .mark_started(
+                &mut storage,
+                dependent_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(41),
+            )
+            .expect("mark dependent started");
+        scheduler
+            .complete_orchestration(&mut storage, 41, "failed", Some("boom"))
+            .expect("fail dependent");
+        let job = &scheduler.jobs[&dependent_id];
+        assert_eq!(job.state, ScheduledJobState::Idle);
+        assert!(job.enabled);
+        assert!(job.next_run_at_ms.is_none());
+        assert!(job.current_trigger_event.is_none());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn exec_job_runs_settle_from_process_events_and_reload_target() {
+        let dir = make_temp_dir("agenticos_job_scheduler_exec");
+        let db_path = dir.join("agenticos.db");
+
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        let target = ScheduledJobTarget::from_request(ScheduledJobTargetInput::Exec(
+            serde_json::from_value(serde_json::json!({
+                "prompt": "Summarize yesterday's commits.",
+                "workload": " Code ",
+                "max_tokens": 512
+            }))
+            .expect("exec target json"),
+        ))
+        .expect("exec target");
+        let result = scheduler
+            .schedule_job(
+                &mut storage,
+                ScheduledJobRequest {
+                    name: "digest".to_string(),
+                    target,
+                    trigger: ScheduledJobTriggerInput::Interval {
+                        every_ms: 60_000,
+                        starts_at_ms: None,
+                        missed_run_policy: MissedRunPolicy::RunOnce,
+                    },
+                    timeout_ms: Some(2_000),
+                    max_retries: Some(1),
+                    backoff_ms: Some(250),
+                    concurrency_policy: ConcurrencyPolicy::default(),
+                    enabled: true,
+                },
+            )
+            .expect("schedule exec job");
+
+        let plan = scheduler.dispatch_plan(result.job_id).expect("plan");
+        let ScheduledJobTarget::Exec(exec) = &plan.target else {
+            panic!("expected exec target");
+        };
+        assert_eq!(exec.workload.as_deref(), Some("code"));
+        assert_eq!(plan.prompt_with_trigger(&exec.prompt), exec.prompt);
+
+        scheduler
+            .mark_started(
+                &mut storage,
+                plan.job_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Process(9),
+            )
+            .expect("mark started");
+        let finished = |pid: u64, reason: &str| KernelEvent::SessionFinished {
+            pid,
+            tokens_generated: None,
+            elapsed_secs: None,
+            reason: reason.to_string(),
+        };
+        // Events of processes the scheduler does not track are ignored.
+        assert_eq!(
+            scheduler.observe_process_events(&mut storage, &[finished(8, "completed")]),
+            0
+        );
+        assert_eq!(
+            scheduler.observe_process_events(&mut storage, &[finished(9, "terminated")]),
+            1
+        );
+        let job = &scheduler.jobs[&result.job_id];
+        assert_eq!(job.state, ScheduledJobState::RetryWait);
+        assert_eq!(job.last_error.as_deref(), Some("terminated"));
+        assert!(job.active_handle.is_none());
+
+        let plan = scheduler.dispatch_plan(result.job_id).expect("retry plan");
+        scheduler
+            .mark_started(
+                &mut storage,
+                plan.job_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Process(10),
+            )
+            .expect("mark retry started");
+        scheduler.observe_process_events(&mut storage, &[finished(10, "completed")]);
+        let job = &scheduler.jobs[&result.job_id];
+        assert_eq!(job.state, ScheduledJobState::Idle);
+        assert_eq!(job.last_run_status.as_deref(), Some("completed"));
+        assert!(scheduler.process_to_job.is_empty());
+
+        let reloaded = JobScheduler::load(&mut storage).expect("reload scheduler");
+        let job = &reloaded.jobs[&result.job_id];
+        assert_eq!(job.to_view().target_kind, "exec");
+        assert!(matches!(
+            &job.target,
+            ScheduledJobTarget::Exec(exec) if exec.max_tokens == Some(512)
+        ));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn job_targets_reject_incomplete_requests() {
+        let send_input = ScheduledJobTargetInput::SendInput(SendInputJobTarget {
+            session_id: "  ".to_string(),
+            prompt: "stand-up".to_string(),
+        });
+        assert!(ScheduledJobTarget::from_request(send_input).is_err());
+
+        let tool = ScheduledJobTargetInput::Tool(ToolJobTarget {
+            tool: "read_file".to_string(),
+            arguments: serde_json::json!(["not", "an", "object"]),
+        });
+        assert!(ScheduledJobTarget::from_request(tool).is_err());
+
+        let exec = ScheduledJobTargetInput::Exec(ExecJobTarget {
+            prompt: "run".to_string(),
+            workload: Some("gpu".to_string()),
+            max_tokens: None,
+            max_syscalls: None,
+            allowed_tools: None,
+            path_scopes: None,
+            path_grants: None,
+            limits: QuotaLimits::default(),
+        });
+        assert!(ScheduledJobTarget::from_request(exec).is_err());
+    }
+
+    #[test]
+    fn forbid_and_queue_rule_on_occurrences_due_while_running() {
+        let dir = make_temp_dir("agenticos_job_scheduler_overlap_queue");
+        let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        let forbid_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "forbid",
+            ConcurrencyPolicy::Forbid,
+        );
+        let queue_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "queue",
+            ConcurrencyPolicy::Queue,
+        );
+        start_run(&mut scheduler, &mut storage, forbid_id, 1);
+        start_run(&mut scheduler, &mut storage, queue_id, 2);
+
+        let now_ms = current_timestamp_ms();
+        for job_id in [forbid_id, queue_id] {
+            let job = scheduler.jobs.get_mut(&job_id).expect("job");
+            assert!(job.next_run_at_ms.is_some_and(|next| next > now_ms));
+            job.next_run_at_ms = Some(now_ms - 10);
+        }
+        let due = scheduler.due_job_ids(now_ms);
+        assert!(due.contains(&forbid_id) && due.contains(&queue_id));
+        for job_id in [forbid_id, queue_id] {
+            assert!(matches!(
+                scheduler.admit_due_job(&mut storage, job_id),
+                Ok(JobAdmission::Held)
+            ));
+        }
+
+        let forbid = &scheduler.jobs[&forbid_id];
+        let decision = forbid.recent_runs.first().expect("forbid decision");
+        assert_eq!(decision.status, "skipped");
+        assert_eq!(decision.reason.as_deref(), Some("overlap_forbidden"));
+        assert!(forbid.next_run_at_ms.is_some_and(|next| next > now_ms));
+        let queue = &scheduler.jobs[&queue_id];
+        assert_eq!(
+            queue.recent_runs.first().map(|run| run.status.as_str()),
+            Some("queued")
+        );
+        assert_eq!(queue.queued_runs, VecDeque::from([now_ms - 10]));
+        assert!(scheduler.due_job_ids(now_ms).is_empty());
+
+        scheduler
+            .complete_orchestration(&mut storage, 2, "completed", None)
+            .expect("complete queue run");
+        let queue = &scheduler.jobs[&queue_id];
+        assert_eq!(queue.state, ScheduledJobState::Idle);
+        assert_eq!(queue.next_run_at_ms, Some(now_ms - 10));
+        let plan = scheduler.dispatch_plan(queue_id).expect("dequeued plan");
+        assert_eq!(plan.trigger_at_ms, now_ms - 10);
+        scheduler
+            .mark_started(
+                &mut storage,
+                queue_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(3),
+            )
+            .expect("start dequeued run");
+        assert_eq!(
+            scheduler.jobs[&queue_id].recent_runs[0].reason.as_deref(),
+            Some("dequeued")
+        );
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn replace_cancels_the_active_run_and_allow_caps_overlap_runs() {
+        let dir = make_temp_dir("agenticos_job_scheduler_overlap_replace");
+        let db_path = dir.join("agenticos.db");
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        let replace_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "replace",
+            ConcurrencyPolicy::Replace,
+        );
+        let allow_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "allow",
+            ConcurrencyPolicy::Allow { max_concurrent: 2 },
+        );
+        start_run(&mut scheduler, &mut storage, replace_id, 5);
+        start_run(&mut scheduler, &mut storage, allow_id, 7);
+
+        let now_ms = current_timestamp_ms();
+        scheduler
+            .jobs
+            .get_mut(&replace_id)
+            .expect("job")
+            .next_run_at_ms = Some(now_ms);
+        let Ok(JobAdmission::Start { plan, replaced }) =
+            scheduler.admit_due_job(&mut storage, replace_id)
+        else {
+            panic!("replace admits the new occurrence");
+        };
+        assert_eq!(replaced, Some(ScheduledRunHandle::Orchestration(5)));
+        assert!(!plan.overlap);
+        let cancelled = &scheduler.jobs[&replace_id].recent_runs[0];
+        assert_eq!(cancelled.status, "cancelled");
+        assert_eq!(cancelled.reason.as_deref(), Some("replaced_by_newer_run"));
+        scheduler
+            .mark_started(
+                &mut storage,
+                replace_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(6),
+            )
+            .expect("start replacement");
+        // The cancelled orchestration no longer settles the job.
+        scheduler
+            .complete_orchestration(&mut storage, 5, "failed", Some("killed"))
+            .expect("late completion");
+        let job = &scheduler.jobs[&replace_id];
+        assert_eq!(job.state, ScheduledJobState::Running);
+        assert_eq!(
+            job.recent_runs[0].reason.as_deref(),
+            Some("replaced_previous_run")
+        );
+
+        scheduler
+            .jobs
+            .get_mut(&allow_id)
+            .expect("job")
+            .next_run_at_ms = Some(now_ms);
+        let Ok(JobAdmission::Start { plan, replaced }) =
+            scheduler.admit_due_job(&mut storage, allow_id)
+        else {
+            panic!("allow admits an overlap run");
+        };
+        assert!(plan.overlap && replaced.is_none());
+        scheduler
+            .mark_overlap_started(&mut storage, &plan, ScheduledRunHandle::Process(11))
+            .expect("start overlap run");
+        assert_eq!(scheduler.jobs[&allow_id].to_view().active_runs, 2);
+
+        scheduler
+            .jobs
+            .get_mut(&allow_id)
+            .expect("job")
+            .next_run_at_ms = Some(now_ms);
+        assert!(matches!(
+            scheduler.admit_due_job(&mut storage, allow_id),
+            Ok(JobAdmission::Held)
+        ));
+        assert_eq!(
+            scheduler.jobs[&allow_id].recent_runs[0].reason.as_deref(),
+            Some("max_concurrency_reached")
+        );
+
+        let finished = KernelEvent::SessionFinished {
+            pid: 11,
+            tokens_generated: None,
+            elapsed_secs: None,
+            reason: "completed".to_string(),
+        };
+        assert_eq!(
+            scheduler.observe_process_events(&mut storage, &[finished]),
+            1
+        );
+        let job = &scheduler.jobs[&allow_id];
+        assert!(job.overlap_runs.is_empty());
+        assert_eq!(job.state, ScheduledJobState::Running);
+        assert!(job.recent_runs.iter().any(
+            |run| run.status == "completed" && run.reason.as_deref() == Some("overlap_allowed")
+        ));
+
+        drop(scheduler);
+        let reloaded = JobScheduler::load(&mut storage).expect("reload scheduler");
+        let view = reloaded.jobs[&allow_id].to_view();
+        assert_eq!(view.concurrency_policy, "allow:2");
+        assert!(view
+            .recent_runs
+            .iter()
+            .any(|run| run.reason.as_deref() == Some("max_concurrency_reached")));
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn global_cap_defers_due_jobs_and_run_now_respects_it() {
+        let dir = make_temp_dir("agenticos_job_scheduler_global_cap");
+        let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+        let mut scheduler = JobScheduler::new();
+        scheduler.set_max_concurrent_runs(1);
+        let busy_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "busy",
+            ConcurrencyPolicy::Forbid,
+        );
+        let waiting_id = schedule_interval(
+            &mut scheduler,
+            &mut storage,
+            "waiting",
+            ConcurrencyPolicy::Forbid,
+        );
+        start_run(&mut scheduler, &mut storage, busy_id, 21);
+
+        scheduler
+            .run_now(&mut storage, waiting_id)
+            .expect("run waiting job now");
+        let now_ms = current_timestamp_ms();
+        assert!(scheduler.due_job_ids(now_ms).contains(&waiting_id));
+        assert!(matches!(
+            scheduler.admit_due_job(&mut storage, waiting_id),
+            Ok(JobAdmission::Held)
+        ));
+        let deferred = &scheduler.jobs[&waiting_id].recent_runs[0];
+        assert_eq!(deferred.status, "deferred");
+        assert_eq!(deferred.reason.as_deref(), Some("global_concurrency_limit"));
+        // The deferral is recorded once and no longer wakes the loop.
+        assert!(matches!(
+            scheduler.admit_due_job(&mut storage, waiting_id),
+            Ok(JobAdmission::Waiting)
+        ));
+        assert!(scheduler
+            .next_due_at_ms()
+            .is_none_or(|next| next > current_timestamp_ms()));
+
+        scheduler
+            .complete_orchestration(&mut storage, 21, "completed", None)
+            .expect("complete busy run");
+        let Ok(JobAdmission::Start { plan, .. }) =
+            scheduler.admit_due_job(&mut storage, waiting_id)
+        else {
+            panic!("free slot admits the deferred job");
+        };
+        scheduler
+            .mark_started(
+                &mut storage,
+                waiting_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(22),
+            )
+            .expect("start manual run");
+        assert_eq!(
+            scheduler.jobs[&waiting_id].recent_runs[0].reason.as_deref(),
+            Some("manual_run")
+        );
+
+        scheduler
+            .set_enabled(&mut storage, busy_id, false)
+            .expect("disable busy job");
+        assert!(scheduler.run_now(&mut storage, busy_id).is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn concurrency_policies_round_trip_and_reject_unsafe_targets() {
+        for policy in [
+            ConcurrencyPolicy::Forbid,
+            ConcurrencyPolicy::Queue,
+            ConcurrencyPolicy::Replace,
+            ConcurrencyPolicy::Allow { max_concurrent: 3 },
+        ] {
+            assert_eq!(ConcurrencyPolicy::from_label(&policy.label()), Ok(policy));
+        }
+        assert_eq!(
+            serde_json::from_value::<ConcurrencyPolicy>(
+                serde_json::json!({"mode": "allow", "max_concurrent": 2})
+            )
+            .expect("allow policy"),
+            ConcurrencyPolicy::Allow { max_concurrent: 2 }
+        );
+
+        let session = ScheduledJobTarget::SendInput(SendInputJobTarget {
+            session_id: "sess-1".to_string(),
+            prompt: "stand-up".to_string(),
+        });
+        assert!(ConcurrencyPolicy::Queue.validate_for(&session).is_ok());
+        assert!(ConcurrencyPolicy::Replace.validate_for(&session).is_err());
+        assert!(ConcurrencyPolicy::Allow { max_concurrent: 0 }
+            .validate_for(&sample_target())
+            .is_err());
+    }
+
+    fn schedule_interval(
+        scheduler: &mut JobScheduler,
+        storage: &mut StorageService,
+        name: &str,
+        concurrency_policy: ConcurrencyPolicy,
+    ) -> u64 {
+        scheduler
+            .schedule_job(
+                storage,
+                ScheduledJobRequest {
+                    name: name.to_string(),
+                    target: sample_target(),
+                    trigger: ScheduledJobTriggerInput::Interval {
+                        every_ms: 60_000,
+                        starts_at_ms: None,
+                        missed_run_policy: MissedRunPolicy::RunOnce,
+                    },
+                    timeout_ms: Some(30_000),
+                    max_retries: Some(0),
+                    backoff_ms: Some(1_000),
+                    concurrency_policy,
+                    enabled: true,
+                },
+            )
+            .expect("schedule interval job")
+            .job_id
+    }
+
+    fn start_run(
+        scheduler: &mut JobScheduler,
+        storage: &mut StorageService,
+        job_id: u64,
+        orchestration_id: u64,
+    ) {
+        let plan = scheduler.dispatch_plan(job_id).expect("dispatch plan");
+        scheduler
+            .mark_started(
+                storage,
+                job_id,
+                plan.trigger_at_ms,
+                plan.attempt,
+                ScheduledRunHandle::Orchestration(orchestration_id),
+            )
+            .expect("mark started");
+    }
+
+    fn sample_target() -> ScheduledJobTarget {
+        ScheduledJobTarget::from_request(ScheduledJobTargetInput::Workflow {
+            workflow: sample_workflow_json(),
+        })
+        .expect("workflow target")
     }
 
     fn sample_workflow_json() -> serde_json::Value {
@@ -2,18 +2,19 @@ use agentic_control_models::ScheduledJobView;
 
 use crate::storage::{current_timestamp_ms, StorageService};
 
-use super::scheduler::{JobScheduler, ScheduledJobState};
+use super::scheduler::{
+    ConcurrencyPolicy, JobScheduler, ScheduledJob, ScheduledJobState, ScheduledRunHandle,
+};
 
 impl JobScheduler {
+    /// Jobs due at `now_ms`, running ones included so that their concurrency
+    /// policy can rule on the new occurrence.
     pub fn due_job_ids(&self, now_ms: i64) -> Vec<u64> {
+        let at_capacity = self.at_run_capacity();
         self.jobs
             .values()
             .filter(|job| {
-                job.enabled
-                    && matches!(
-                        job.state,
-                        ScheduledJobState::Idle | ScheduledJobState::RetryWait
-                    )
+                awaits_admission(job, at_capacity)
                     && job.next_run_at_ms.is_some_and(|next| next <= now_ms)
             })
             .map(|job| job.job_id)
@@ -35,37 +36,54 @@ impl JobScheduler {
     }
 
     pub fn next_due_at_ms(&self) -> Option<i64> {
+        let at_capacity = self.at_run_capacity();
         self.jobs
             .values()
-            .filter_map(|job| {
-                if job.enabled
-                    && matches!(
-                        job.state,
-                        ScheduledJobState::Idle | ScheduledJobState::RetryWait
-                    )
-                {
-                    job.next_run_at_ms
-                } else {
-                    None
-                }
-            })
+            .filter(|job| awaits_admission(job, at_capacity))
+            .filter_map(|job| job.next_run_at_ms)
             .min()
     }
 
     pub fn next_timeout_at_ms(&self) -> Option<(u64, i64)> {
         self.jobs
             .values()
-            .filter_map(|job| {
-                if job.enabled && job.state == ScheduledJobState::Running {
-                    job.active_deadline_at_ms
-                        .map(|deadline| (job.job_id, deadline))
-                } else {
-                    None
-                }
+            .flat_map(|job| {
+                let active = (job.enabled && job.state == ScheduledJobState::Running)
+                    .then_some(job.active_deadline_at_ms)
+                    .flatten();
+                active
+                    .into_iter()
+                    .chain(
+                        job.overlap_runs
+                            .iter()
+                            .filter_map(|overlap| overlap.run.deadline_at_ms),
+                    )
+                    .map(|deadline| (job.job_id, deadline))
             })
             .min_by_key(|(_, deadline)| *deadline)
     }
 
+    /// Deadlines of every run in flight, primary and overlapping.
+    pub(crate) fn run_deadlines(&self) -> Vec<(ScheduledRunHandle, i64)> {
+        self.jobs
+            .values()
+            .flat_map(|job| {
+                let active = job
+                    .active_handle
+                    .zip(job.active_deadline_at_ms)
+                    .filter(|_| job.state == ScheduledJobState::Running);
+                active
+                    .into_iter()
+                    .chain(job.overlap_runs.iter().filter_map(|overlap| {
+                        overlap
+                            .run
+                            .deadline_at_ms
+                            .map(|deadline| (overlap.handle, deadline))
+                    }))
+            })
+            .collect()
+    }
+
     pub fn orchestration_ids(&self) -> Vec<u64> {
         self.orchestration_to_job.keys().copied().collect()
     }
@@ -80,7 +98,7 @@ impl JobScheduler {
         let Some(job) = self.jobs.get_mut(&job_id) else {
             return Err(format!("Scheduled job {} not found", job_id));
         };
-        if job.state == ScheduledJobState::Running {
+        if job.active_run_count() > 0 {
             return Err(format!(
                 "Scheduled job {} is running and cannot change enabled state",
                 job_id
@@ -96,6 +114,14 @@ impl JobScheduler {
             job.next_run_at_ms = None;
             job.current_trigger_at_ms = None;
             job.current_attempt = 0;
+            job.current_trigger_event = None;
+            job.pending_trigger_events.clear();
+            job.queued_runs.clear();
+            job.ruled_occurrences = None;
+            job.manual_run_requested = false;
+            job.start_reason = None;
+            job.deferred_at_ms = None;
+            self.file_watches.remove(&job_id);
         }
         job.updated_at_ms = now_ms;
         storage
@@ -108,14 +134,15 @@ impl JobScheduler {
         let Some(job) = self.jobs.get(&job_id) else {
             return Err(format!("Scheduled job {} not found", job_id));
         };
-        if job.state == ScheduledJobState::Running || job.active_orchestration_id.is_some() {
+        if job.active_run_count() > 0 || job.active_orchestration_id.is_some() {
             return Err(format!(
-                "Scheduled job {} is still running; stop the active orchestration first",
+                "Scheduled job {} is still running; wait for the active run to finish first",
                 job_id
             ));
         }
 
         self.jobs.remove(&job_id);
+        self.file_watches.remove(&job_id);
         storage
             .delete_scheduled_job(job_id)
             .map_err(|err| err.to_string())?;
@@ -126,3 +153,20 @@ impl JobScheduler {
         self.jobs.values().collect()
     }
 }
+
+/// Whether `job` has an occurrence for `admit_due_job` to handle. At the
+/// global cap, jobs that would start a run only come due once, to record
+/// their deferral; the loop picks them up again when a run settles.
+fn awaits_admission(job: &ScheduledJob, at_capacity: bool) -> bool {
+    if !job.enabled || job.next_run_at_ms.is_none() {
+        return false;
+    }
+    let starts_run = match job.state {
+        ScheduledJobState::Idle | ScheduledJobState::RetryWait => true,
+        ScheduledJobState::Running => {
+            matches!(job.concurrency_policy, ConcurrencyPolicy::Allow { .. })
+        }
+        ScheduledJobState::Completed | ScheduledJobState::Disabled => return false,
+    };
+    !(at_capacity && starts_run && job.deferred_at_ms == job.next_run_at_ms)
+}
@@ -0,0 +1,126 @@
+use crate::model_catalog::parse_workload_label;
+use crate::orchestrator::TaskGraphDef;
+
+use super::scheduler::{
+    validate_workflow_definition, ExecJobTarget, ScheduledJobTarget, ScheduledJobTargetInput,
+    SendInputJobTarget, ToolJobTarget,
+};
+
+impl ScheduledJobTarget {
+    pub(crate) fn from_request(request: ScheduledJobTargetInput) -> Result<Self, String> {
+        let target = match request {
+            ScheduledJobTargetInput::Workflow { workflow } => {
+                let payload = serde_json::to_string(&workflow)
+                    .map_err(|err| format!("Invalid workflow payload: {}", err))?;
+                let workflow = serde_json::from_value::<TaskGraphDef>(workflow)
+                    .map_err(|err| format!("Workflow definition is invalid: {}", err))?;
+                Self::Workflow { workflow, payload }
+            }
+            ScheduledJobTargetInput::Exec(mut exec) => {
+                exec.workload = exec
+                    .workload
+                    .map(|workload| workload.trim().to_ascii_lowercase())
+                    .filter(|workload| !workload.is_empty());
+                Self::Exec(exec)
+            }
+            ScheduledJobTargetInput::SendInput(mut input) => {
+                input.session_id = input.session_id.trim().to_string();
+                Self::SendInput(input)
+            }
+            ScheduledJobTargetInput::Tool(mut tool) => {
+                tool.tool = tool.tool.trim().to_string();
+                Self::Tool(tool)
+            }
+        };
+        target.validate()?;
+        Ok(target)
+    }
+
+    pub(crate) fn from_stored(kind: &str, payload: &str) -> Result<Self, String> {
+        let invalid =
+            |err: serde_json::Error| format!("Invalid persisted {} target: {}", kind, err);
+        match kind {
+            "workflow" => Ok(Self::Workflow {
+                workflow: serde_json::from_str(payload).map_err(invalid)?,
+                payload: payload.to_string(),
+            }),
+            "exec" => Ok(Self::Exec(serde_json::from_str(payload).map_err(invalid)?)),
+            "send_input" => Ok(Self::SendInput(
+                serde_json::from_str(payload).map_err(invalid)?,
+            )),
+            "tool" => Ok(Self::Tool(serde_json::from_str(payload).map_err(invalid)?)),
+            other => Err(format!("Unsupported scheduled job target '{}'", other)),
+        }
+    }
+
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::Workflow { .. } => "workflow",
+            Self::Exec(_) => "exec",
+            Self::SendInput(_) => "send_input",
+            Self::Tool(_) => "tool",
+        }
+    }
+
+    pub(crate) fn to_payload(&self) -> String {
+        let encoded = match self {
+            Self::Workflow { payload, .. } => return payload.clone(),
+            Self::Exec(exec) => serde_json::to_string(exec),
+            Self::SendInput(input) => serde_json::to_string(input),
+            Self::Tool(tool) => serde_json::to_string(tool),
+        };
+        encoded.unwrap_or_else(|_| "{}".to_string())
+    }
+
+    pub(super) fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Workflow { workflow, .. } => validate_workflow_definition(workflow),
+            Self::Exec(exec) => validate_exec_target(exec),
+            Self::SendInput(input) => validate_send_input_target(input),
+            Self::Tool(tool) => validate_tool_target(tool),
+        }
+    }
+}
+
+fn validate_exec_target(exec: &ExecJobTarget) -> Result<(), String> {
+    if exec.prompt.trim().is_empty() {
+        return Err("exec target requires a non-empty prompt".to_string());
+    }
+    if let Some(workload) = exec.workload.as_deref() {
+        if parse_workload_label(workload).is_none() {
+            return Err(format!(
+                "Unknown workload '{}'; expected fast, code, reasoning or general",
+                workload
+            ));
+        }
+    }
+    for (label, value) in [
+        ("max_tokens", exec.max_tokens),
+        ("max_syscalls", exec.max_syscalls),
+    ] {
+        if value == Some(0) {
+            return Err(format!("{label} must be greater than zero when specified"));
+        }
+    }
+    exec.limits.validate()
+}
+
+fn validate_send_input_target(input: &SendInputJobTarget) -> Result<(), String> {
+    if input.session_id.is_empty() {
+        return Err("send_input target requires a session_id".to_string());
+    }
+    if input.prompt.trim().is_empty() {
+        return Err("send_input target requires a non-empty prompt".to_string());
+    }
+    Ok(())
+}
+
+fn validate_tool_target(tool: &ToolJobTarget) -> Result<(), String> {
+    if tool.tool.is_empty() {
+        return Err("tool target requires a tool name".to_string());
+    }
+    if !tool.arguments.is_object() {
+        return Err("tool target arguments must be a JSON object".to_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,467 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use agentic_control_models::KernelEvent;
+use glob::{MatchOptions, Pattern};
+use serde_json::json;
+
+use crate::storage::{current_timestamp_ms, StorageService, StoredIpcMessage};
+use crate::tools::path_guard::{normalize_relative_path, workspace_root};
+
+use super::scheduler::{
+    CompletionFilter, FileWatchState, JobScheduler, JobTriggerEvent, ScheduledJob,
+    ScheduledJobState, ScheduledJobTrigger,
+};
+
+pub(super) const DEFAULT_FILE_DEBOUNCE_MS: u64 = 1_000;
+/// Cadence of the file/IPC scan while at least one such trigger is enabled.
+const EVENT_POLL_INTERVAL_MS: i64 = 1_000;
+/// Events queued per job beyond this drop the oldest one.
+const MAX_PENDING_TRIGGER_EVENTS: usize = 32;
+const MAX_WATCHED_FILES: usize = 10_000;
+const MAX_REPORTED_CHANGES: usize = 100;
+const MAX_IPC_MESSAGES_PER_POLL: usize = 256;
+
+type FileSnapshot = BTreeMap<String, (u128, u64)>;
+
+impl JobScheduler {
+    /// Next scan of file and IPC sources; `None` when no enabled job needs one.
+    pub fn next_event_poll_at_ms(&self) -> Option<i64> {
+        self.jobs
+            .values()
+            .any(|job| {
+                job.enabled
+                    && matches!(
+                        job.trigger,
+                        ScheduledJobTrigger::FileChange { .. }
+                            | ScheduledJobTrigger::IpcMessage { .. }
+                    )
+            })
+            .then_some(self.last_event_poll_ms + EVENT_POLL_INTERVAL_MS)
+    }
+
+    /// Scan watched paths and new channel messages, queueing trigger events.
+    pub fn poll_event_sources(&mut self, storage: &StorageService) {
+        let now_ms = current_timestamp_ms();
+        self.last_event_poll_ms = now_ms;
+        self.poll_file_watches(now_ms);
+        self.poll_ipc_channels(storage, now_ms);
+    }
+
+    /// Feed job and workflow completions to `completion` triggers.
+    pub(crate) fn observe_lifecycle_events(&mut self, events: &[KernelEvent]) {
+        let now_ms = current_timestamp_ms();
+        for event in events {
+            let (source_job_id, source_orchestration_id, status, error) = match event {
+                KernelEvent::JobRunFinished {
+                    job_id,
+                    status,
+                    error,
+                    ..
+                } => {
+                    // A pending retry is not a completion yet, nor is a run
+                    // cancelled to make room for a newer one.
+                    if status == "cancelled" {
+                        continue;
+                    }
+                    if self
+                        .jobs
+                        .get(job_id)
+                        .is_some_and(|job| job.state == ScheduledJobState::RetryWait)
+                    {
+                        continue;
+                    }
+                    (Some(*job_id), None, status.as_str(), error.clone())
+                }
+                KernelEvent::WorkflowFinished {
+                    orchestration_id,
+                    status,
+                    ..
+                } => (None, Some(*orchestration_id), status.as_str(), None),
+                _ => continue,
+            };
+            let succeeded = status == "completed";
+
+            for job in self.jobs.values_mut() {
+                let ScheduledJobTrigger::Completion {
+                    job_id,
+                    orchestration_id,
+                    on,
+                } = &job.trigger
+                else {
+                    continue;
+                };
+                let watches_source = (job_id.is_some() && *job_id == source_job_id)
+                    || (orchestration_id.is_some() && *orchestration_id == source_orchestration_id);
+                if !watches_source || Some(job.job_id) == source_job_id || !on.accepts(succeeded) {
+                    continue;
+                }
+                job.enqueue_trigger_event(JobTriggerEvent {
+                    occurred_at_ms: now_ms,
+                    payload: json!({
+                        "kind": "completion",
+                        "job_id": source_job_id,
+                        "orchestration_id": source_orchestration_id,
+                        "status": status,
+                        "error": error,
+                    }),
+                });
+            }
+        }
+    }
+
+    fn poll_file_watches(&mut self, now_ms: i64) {
+        let jobs = &self.jobs;
+        self.file_watches.retain(|job_id, _| {
+            jobs.get(job_id).is_some_and(|job| {
+                job.enabled && matches!(job.trigger, ScheduledJobTrigger::FileChange { .. })
+            })
+        });
+        let has_watches = self.jobs.values().any(|job| {
+            job.enabled && matches!(job.trigger, ScheduledJobTrigger::FileChange { .. })
+        });
+        if !has_watches {
+            return;
+        }
+        let root = match workspace_root() {
+            Ok(root) => root,
+            Err(err) => {
+                tracing::warn!(%err, "JOB_TRIGGERS: workspace root unavailable");
+                return;
+            }
+        };
+
+        for job in self.jobs.values_mut() {
+            if !job.enabled {
+                continue;
+            }
+            let ScheduledJobTrigger::FileChange {
+                path,
+                glob,
+                debounce_ms,
+            } = &job.trigger
+            else {
+                continue;
+            };
+            let snapshot = match scan_watch_target(&root, path, glob.as_deref()) {
+                Ok(snapshot) => snapshot,
+                Err(err) => {
+                    tracing::warn!(job_id = job.job_id, %err, "JOB_TRIGGERS: file watch scan failed");
+                    continue;
+                }
+            };
+            // The first scan is the baseline: existing files are not changes.
+            let Some(watch) = self.file_watches.get_mut(&job.job_id) else {
+                self.file_watches.insert(
+                    job.job_id,
+                    FileWatchState {
+                        snapshot,
+                        ..FileWatchState::default()
+                    },
+                );
+                continue;
+            };
+
+            let changed = diff_snapshots(&watch.snapshot, &snapshot);
+            if !changed.is_empty() {
+                watch.changed.extend(changed);
+                watch.last_change_ms = Some(now_ms);
+            }
+            watch.snapshot = snapshot;
+
+            let quiet = watch
+                .last_change_ms
+                .is_some_and(|last| now_ms - last >= *debounce_ms as i64);
+            if !quiet || watch.changed.is_empty() {
+                continue;
+            }
+            let changed = std::mem::take(&mut watch.changed);
+            watch.last_change_ms = None;
+            let payload = json!({
+                "kind": "file_change",
+                "path": path,
+                "glob": glob,
+                "changed_count": changed.len(),
+                "changed": changed.into_iter().take(MAX_REPORTED_CHANGES).collect::<Vec<_>>(),
+            });
+            job.enqueue_trigger_event(JobTriggerEvent {
+                occurred_at_ms: now_ms,
+                payload,
+            });
+        }
+    }
+
+    fn poll_ipc_channels(&mut self, storage: &StorageService, now_ms: i64) {
+        let has_channel_jobs = self.jobs.values().any(|job| {
+            job.enabled && matches!(job.trigger, ScheduledJobTrigger::IpcMessage { .. })
+        });
+        if !has_channel_jobs {
+            // Restart from the tail next time so old traffic never fires new jobs.
+            self.ipc_cursor = None;
+            return;
+        }
+        let Some(cursor) = self.ipc_cursor else {
+            match storage.latest_ipc_message_seq() {
+                Ok(seq) => self.ipc_cursor = Some(seq),
+                Err(err) => tracing::warn!(%err, "JOB_TRIGGERS: failed to read ipc cursor"),
+            }
+            return;
+        };
+        let messages =
+            match storage.load_channel_ipc_messages_after(cursor, MAX_IPC_MESSAGES_PER_POLL) {
+                Ok(messages) => messages,
+                Err(err) => {
+                    tracing::warn!(%err, "JOB_TRIGGERS: failed to load channel messages");
+                    return;
+                }
+            };
+
+        for (seq, message) in messages {
+            self.ipc_cursor = Some(seq);
+            let Some(channel) = message.channel.as_deref() else {
+                continue;
+            };
+            for job in self.jobs.values_mut() {
+                let matches_channel = matches!(
+                    &job.trigger,
+                    ScheduledJobTrigger::IpcMessage { channel: wanted } if wanted == channel
+                );
+                if matches_channel {
+                    job.enqueue_trigger_event(JobTriggerEvent {
+                        occurred_at_ms: now_ms,
+                        payload: ipc_message_payload(&message),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl ScheduledJob {
+    pub(super) fn enqueue_trigger_event(&mut self, event: JobTriggerEvent) {
+        if !self.enabled {
+            return;
+        }
+        if self.pending_trigger_events.len() >= MAX_PENDING_TRIGGER_EVENTS {
+            self.pending_trigger_events.pop_front();
+            tracing::warn!(
+                job_id = self.job_id,
+                "JOB_TRIGGERS: event queue full, dropping oldest event"
+            );
+        }
+        // While a run is in flight the event is handed to the concurrency policy.
+        if matches!(
+            self.state,
+            ScheduledJobState::Idle | ScheduledJobState::Running
+        ) && self.next_run_at_ms.is_none()
+        {
+            self.next_run_at_ms = Some(event.occurred_at_ms);
+        }
+        self.pending_trigger_events.push_back(event);
+    }
+
+    /// Move the oldest queued event into the run being started.
+    pub(super) fn take_trigger_event(&mut self) {
+        if self.current_trigger_event.is_none() {
+            self.current_trigger_event = self.pending_trigger_events.pop_front();
+        }
+    }
+
+    /// Called once a run is final: drop its event and schedule the next queued one.
+    pub(super) fn settle_trigger_event(&mut self, now_ms: i64) {
+        self.current_trigger_event = None;
+        if self.enabled
+            && self.state == ScheduledJobState::Idle
+            && self.next_run_at_ms.is_none()
+            && !self.pending_trigger_events.is_empty()
+        {
+            self.next_run_at_ms = Some(now_ms);
+        }
+    }
+}
+
+impl CompletionFilter {
+    pub(super) fn accepts(self, succeeded: bool) -> bool {
+        match self {
+            Self::Success => succeeded,
+            Self::Failure => !succeeded,
+            Self::Any => true,
+        }
+    }
+
+    pub(super) fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Failure => "failure",
+            Self::Any => "any",
+        }
+    }
+}
+
+fn ipc_message_payload(message: &StoredIpcMessage) -> serde_json::Value {
+    let payload = serde_json::from_str::<serde_json::Value>(&message.payload_text)
+        .unwrap_or_else(|_| serde_json::Value::String(message.payload_text.clone()));
+    json!({
+        "kind": "ipc_message",
+        "channel": message.channel,
+        "message_id": message.message_id,
+        "message_type": message.message_type,
+        "orchestration_id": message.orchestration_id,
+        "sender_pid": message.sender_pid,
+        "sender_task_id": message.sender_task_id,
+        "payload": payload,
+        "created_at_ms": message.created_at_ms,
+    })
+}
+
+/// Files under `path` (or `path` itself) keyed by workspace-relative path.
+/// A missing target is an empty snapshot, so creating it counts as a change.
+fn scan_watch_target(root: &Path, path: &str, glob: Option<&str>) -> Result<FileSnapshot, String> {
+    let base = normalize_relative_path(root, path)?;
+    let pattern = glob
+        .map(|glob| Pattern::new(glob).map_err(|err| err.to_string()))
+        .transpose()?;
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::default()
+    };
+    let mut snapshot = FileSnapshot::new();
+
+    let metadata = match fs::symlink_metadata(&base) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(snapshot),
+        Err(err) => return Err(err.to_string()),
+    };
+    if metadata.is_file() {
+        snapshot.insert(relative_key(root, &base), file_fingerprint(&metadata));
+        return Ok(snapshot);
+    }
+    if !metadata.is_dir() {
+        return Ok(snapshot);
+    }
+
+    let mut pending_dirs = vec![base.clone()];
+    while let Some(dir) = pending_dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let entry_path = entry.path();
+            if file_type.is_dir() {
+                pending_dirs.push(entry_path);
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            if let Some(pattern) = pattern.as_ref() {
+                let relative = entry_path
+                    .strip_prefix(&base)
+                    .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default();
+                if !pattern.matches_with(&relative, options) {
+                    continue;
+                }
+            }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            snapshot.insert(relative_key(root, &entry_path), file_fingerprint(&metadata));
+            if snapshot.len() >= MAX_WATCHED_FILES {
+                return Ok(snapshot);
+            }
+        }
+    }
+    Ok(snapshot)
+}
+
+fn diff_snapshots(previous: &FileSnapshot, current: &FileSnapshot) -> Vec<String> {
+    let mut changed = current
+        .iter()
+        .filter(|(path, fingerprint)| previous.get(*path) != Some(fingerprint))
+        .map(|(path, _)| path.clone())
+        .collect::<Vec<_>>();
+    changed.extend(
+        previous
+            .keys()
+            .filter(|path| !current.contains_key(*path))
+            .cloned(),
+    );
+    changed
+}
+
+fn relative_key(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn file_fingerprint(metadata: &fs::Metadata) -> (u128, u64) {
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0);
+    (modified_ns, metadata.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_diff_reports_added_modified_and_removed_files() {
+        let previous = FileSnapshot::from([
+            ("docs/a.md".to_string(), (1, 10)),
+            ("docs/b.md".to_string(), (1, 10)),
+        ]);
+        let current = FileSnapshot::from([
+            ("docs/a.md".to_string(), (2, 10)),
+            ("docs/c.md".to_string(), (1, 3)),
+        ]);
+
+        let mut changed = diff_snapshots(&previous, &current);
+        changed.sort();
+        assert_eq!(changed, vec!["docs/a.md", "docs/b.md", "docs/c.md"]);
+        assert!(diff_snapshots(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn scan_applies_glob_relative_to_the_watched_path() {
+        let root = std::env::temp_dir().join(format!(
+            "agenticos_job_triggers_scan_{}_{}",
+            std::process::id(),
+            current_timestamp_ms()
+        ));
+        fs::create_dir_all(root.join("notes/deep")).expect("create watch dir");
+        fs::write(root.join("notes/top.md"), "top").expect("write top");
+        fs::write(root.join("notes/deep/inner.md"), "inner").expect("write inner");
+        fs::write(root.join("notes/skip.txt"), "skip").expect("write skip");
+
+        let snapshot = scan_watch_target(&root, "notes", Some("**/*.md")).expect("scan");
+        assert_eq!(
+            snapshot.keys().cloned().collect::<Vec<_>>(),
+            vec!["notes/deep/inner.md", "notes/top.md"]
+        );
+        let shallow = scan_watch_target(&root, "notes", Some("*.md")).expect("scan");
+        assert_eq!(
+            shallow.keys().cloned().collect::<Vec<_>>(),
+            vec!["notes/top.md"]
+        );
+        assert!(scan_watch_target(&root, "missing", None)
+            .expect("scan missing")
+            .is_empty());
+        assert!(scan_watch_target(&root, "../outside", None).is_err());
+
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -1,9 +1,14 @@
 pub mod accounting;
+pub(crate) mod budget_runtime;
+pub(crate) mod checkpoint_journal;
+pub(crate) mod checkpoint_runtime;
+pub(crate) mod job_runtime;
 pub mod jobs;
 pub mod model_runtime;
 pub mod orchestration_runtime;
 pub mod process_control;
 pub mod process_runtime;
+pub(crate) mod session_runtime;
 pub mod status;
 
 #[allow(unused_imports)]
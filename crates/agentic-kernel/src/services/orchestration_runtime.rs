@@ -1,10 +1,11 @@
 use agentic_control_models::{KernelEvent, OrchestrationControlResult, RetryTaskResult};
 use thiserror::Error;
 
+use crate::backend::BackendClass;
 use crate::errors::OrchestratorError;
 use crate::memory::NeuralMemory;
-use crate::model_catalog::ModelCatalog;
-use crate::orchestrator::{Orchestrator, TaskGraphDef};
+use crate::model_catalog::{ModelCatalog, WorkloadClass};
+use crate::orchestrator::{Orchestrator, TaskGraphDef, WorkflowTrigger};
 use crate::process::ProcessLifecyclePolicy;
 use crate::resource_governor::ResourceGovernor;
 use crate::runtimes::RuntimeRegistry;
@@ -74,9 +75,25 @@ pub fn start_orchestration(
     tool_registry: &ToolRegistry,
     owner_id: usize,
     graph: TaskGraphDef,
+    trigger: Option<WorkflowTrigger>,
 ) -> Result<OrchestrationStartResult, OrchestrationStartError> {
     let total_tasks = graph.tasks.len();
-    let (orch_id, spawn_requests) = orchestrator.register(graph, owner_id)?;
+    let (orch_id, spawn_requests) = orchestrator.register_triggered(graph, owner_id, trigger)?;
+    if let Some(artifact) = orchestrator
+        .get(orch_id)
+        .and_then(|orch| orch.trigger_artifact.as_ref())
+    {
+        if let Err(err) = storage.record_workflow_trigger_artifact(
+            orch_id,
+            &artifact.artifact_id,
+            &artifact.producer_task_id,
+            &artifact.mime_type,
+            &artifact.content_text,
+            current_timestamp_ms(),
+        ) {
+            tracing::warn!(orch_id, %err, "ORCHESTRATION: failed to persist trigger artifact");
+        }
+    }
     let spawned = spawn_workflow_requests(
         runtime_registry,
         resource_governor,
@@ -298,7 +315,29 @@ pub(crate) fn resolve_runtime_for_spawn_request(
     session_registry: &SessionRegistry,
     req: &crate::orchestrator::SpawnRequest,
 ) -> Result<String, OrchestrationStartError> {
-    if let Some(required_backend_class) = req.required_backend_class {
+    resolve_runtime_for_workload(
+        runtime_registry,
+        resource_governor,
+        storage,
+        model_catalog,
+        session_registry,
+        req.workload,
+        req.required_backend_class,
+    )
+}
+
+/// Runtime for a new process of `workload`: the current runtime when it fits,
+/// otherwise the catalog target for the workload, loaded on demand.
+pub(crate) fn resolve_runtime_for_workload(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    storage: &mut StorageService,
+    model_catalog: &mut ModelCatalog,
+    session_registry: &SessionRegistry,
+    workload: WorkloadClass,
+    required_backend_class: Option<BackendClass>,
+) -> Result<String, OrchestrationStartError> {
+    if let Some(required_backend_class) = required_backend_class {
         if let Some(current_runtime_id) = runtime_registry.current_runtime_id() {
             if runtime_registry
                 .descriptor(current_runtime_id)
@@ -315,13 +354,13 @@ pub(crate) fn resolve_runtime_for_spawn_request(
         }
 
         let target = model_catalog
-            .resolve_workload_target(req.workload)
+            .resolve_workload_target(workload)
             .map_err(|err| OrchestrationStartError::RoutingFailed(err.to_string()))?
             .filter(|target| target.driver_resolution().backend_class == required_backend_class)
             .ok_or_else(|| {
                 OrchestrationStartError::RoutingFailed(format!(
                     "No runtime target available for workload '{:?}' and backend class '{}'.",
-                    req.workload,
+                    workload,
                     required_backend_class.as_str()
                 ))
             })?;
@@ -342,7 +381,7 @@ pub(crate) fn resolve_runtime_for_spawn_request(
     }
 
     if let Some(target) = model_catalog
-        .resolve_workload_target(req.workload)
+        .resolve_workload_target(workload)
         .map_err(|err| OrchestrationStartError::RoutingFailed(err.to_string()))?
     {
         let loaded = activate_model_target(
@@ -443,7 +482,7 @@ fn spawn_workflow_requests(
                     priority: ProcessPriority::Normal,
                     lifecycle_policy: ProcessLifecyclePolicy::Ephemeral,
                     context_policy: Some(effective_context_policy),
-                    quota_override: None,
+                    quota_override: req.quota_override(),
                 },
             )
         };
@@ -478,6 +517,7 @@ fn spawn_workflow_requests(
                             .collect::<Vec<_>>(),
                     )
                     .map_err(|err| err.to_string())?;
+                scheduler.bind_budget_orchestration(spawned_process.pid, req.orch_id);
                 orchestrator.register_pid(
                     spawned_process.pid,
                     req.orch_id,
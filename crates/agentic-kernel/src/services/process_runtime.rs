@@ -8,6 +8,7 @@ use crate::scheduler::{ProcessPriority, ProcessQuota, ProcessScheduler};
 use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 use crate::tools::invocation::{ProcessPermissionPolicy, ToolCaller};
+use crate::tools::memory_tools::{startup_memory_digest, MemoryScopeContext, MemoryScopeKind};
 
 #[derive(Debug)]
 pub struct ManagedProcessSpawn {
@@ -240,6 +241,7 @@ pub fn spawn_managed_process_with_session(
         .map_err(|err| err.to_string())?;
     let request_workload = request.workload;
     let request_lifecycle = request.lifecycle_policy;
+    let first_prompt = request.prompt.clone();
 
     match spawn_managed_process(engine, memory, scheduler, request) {
         Ok(mut spawned) => {
@@ -269,6 +271,7 @@ pub fn spawn_managed_process_with_session(
                 ),
                 AuditContext::for_process(Some(&spawned.session_id), spawned.pid, Some(runtime_id)),
             );
+            inject_startup_memories(engine, storage, runtime_id, &spawned, &first_prompt);
             Ok(spawned)
         }
         Err(err) => {
@@ -284,6 +287,55 @@ pub fn spawn_managed_process_with_session(
     }
 }
 
+/// Open a new session with the long-term memories (user and workspace scopes)
+/// relevant to its first prompt, when `[memory.long_term].auto_inject` is on.
+fn inject_startup_memories(
+    engine: &mut LLMEngine,
+    storage: &mut StorageService,
+    runtime_id: &str,
+    spawned: &ManagedProcessSpawn,
+    prompt: &str,
+) {
+    let config = &crate::config::kernel_config().memory.long_term;
+    if !config.enabled || !config.auto_inject {
+        return;
+    }
+    let may_recall = engine
+        .processes
+        .get(&spawned.pid)
+        .is_some_and(|process| process.permission_policy.allows_tool("memory_recall"));
+    if !may_recall {
+        return;
+    }
+    let scopes = MemoryScopeContext::for_kernel(config);
+    let visible = [MemoryScopeKind::User, MemoryScopeKind::Workspace]
+        .into_iter()
+        .filter_map(|kind| scopes.scope(kind))
+        .collect::<Vec<_>>();
+    let (digest, memories) = match startup_memory_digest(storage, prompt, &visible, config) {
+        Ok(Some(digest)) => digest,
+        Ok(None) => return,
+        Err(err) => {
+            tracing::warn!(pid = spawned.pid, %err, "PROCESS_RUNTIME: failed to load startup memories");
+            return;
+        }
+    };
+    match engine.inject_retrieved_memory(spawned.pid, &digest) {
+        Ok(tokens) => audit::record(
+            storage,
+            audit::PROCESS_MEMORY_INJECTED,
+            format!(
+                "pid={} memories={} tokens={}",
+                spawned.pid, memories, tokens
+            ),
+            AuditContext::for_process(Some(&spawned.session_id), spawned.pid, Some(runtime_id)),
+        ),
+        Err(err) => {
+            tracing::warn!(pid = spawned.pid, %err, "PROCESS_RUNTIME: failed to inject startup memories");
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn spawn_restored_managed_process_with_session(
     runtime_id: &str,
@@ -364,7 +416,12 @@ fn attach_process_resources(
     quota_override: Option<ProcessQuota>,
 ) -> Result<(), String> {
     let default_quota = ProcessQuota::defaults_for(workload);
-    let scheduler_quota = quota_override.unwrap_or(default_quota);
+    let scheduler_quota = quota_override
+        .map(|quota| ProcessQuota {
+            limits: default_quota.limits.overridden_by(quota.limits),
+            ..quota
+        })
+        .unwrap_or(default_quota);
     let process_turn_budget = default_quota.max_tokens.min(scheduler_quota.max_tokens);
     let _ = engine.set_process_max_tokens(pid, process_turn_budget);
     let backend_capabilities = engine.loaded_backend_capabilities();
@@ -399,6 +456,7 @@ fn attach_process_resources(
     scheduler.register(pid, workload, priority);
     if scheduler_quota.max_tokens != default_quota.max_tokens
         || scheduler_quota.max_syscalls != default_quota.max_syscalls
+        || scheduler_quota.limits != default_quota.limits
     {
         scheduler.set_quota(pid, scheduler_quota);
     }
@@ -0,0 +1,477 @@
+use agentic_control_models::KernelEvent;
+use agentic_protocol::ControlErrorCode;
+
+use crate::commands::runtime_selector_for_session;
+use crate::diagnostics::audit::{self, AuditContext};
+use crate::memory::NeuralMemory;
+use crate::model_catalog::{parse_workload_label, ModelCatalog};
+use crate::process::{ProcessLifecyclePolicy, ProcessState};
+use crate::prompting::{format_initial_prompt_with_metadata, format_user_message_with_metadata};
+use crate::resource_governor::ResourceGovernor;
+use crate::runtime::{AssistantTurnRuntimeBoundary, TurnAssemblyStore};
+use crate::runtimes::RuntimeRegistry;
+use crate::scheduler::{ProcessPriority, ProcessScheduler};
+use crate::session::SessionRegistry;
+use crate::storage::{StorageService, StoredReplayMessage};
+use crate::tool_registry::ToolRegistry;
+use crate::tools::invocation::{ProcessPermissionPolicy, ToolCaller};
+
+use super::model_runtime::activate_model_target;
+use super::process_runtime::{
+    spawn_restored_managed_process_with_session, RestoredManagedProcessRequest,
+};
+
+pub(crate) struct SessionContinuationTarget {
+    pub(crate) session_id: String,
+    pub(crate) runtime_id: String,
+    pub(crate) pid: u64,
+    pub(crate) resumed_from_history: bool,
+}
+
+/// Bind `session_id` to a live process, respawning it from the persisted
+/// history (and loading its runtime) when no live PID is attached.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn ensure_live_session(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    model_catalog: &mut ModelCatalog,
+    memory: &mut NeuralMemory,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+    owner_id: usize,
+    session_id: &str,
+) -> Result<SessionContinuationTarget, (ControlErrorCode, String)> {
+    if let Some(active_pid) = session_registry.active_pid_for_session(session_id) {
+        let runtime_id = runtime_registry
+            .runtime_id_for_pid(active_pid)
+            .or_else(|| session_registry.runtime_id_for_pid(active_pid))
+            .map(ToString::to_string);
+        let has_live_process = runtime_id
+            .as_deref()
+            .and_then(|runtime_id| runtime_registry.engine(runtime_id))
+            .is_some_and(|engine| engine.processes.contains_key(&active_pid));
+
+        if has_live_process {
+            return Ok(SessionContinuationTarget {
+                session_id: session_id.to_string(),
+                runtime_id: runtime_id.unwrap_or_default(),
+                pid: active_pid,
+                resumed_from_history: false,
+            });
+        }
+
+        if let Err(err) = session_registry.release_pid(storage, active_pid, "interrupted") {
+            tracing::warn!(
+                session_id,
+                pid = active_pid,
+                %err,
+                "PROCESS_CMD: failed to clear stale live binding before session resume"
+            );
+        }
+    }
+
+    let Some(session_record) = session_registry.session(session_id).cloned() else {
+        return Err((
+            ControlErrorCode::Generic,
+            format!("Session '{}' not found", session_id),
+        ));
+    };
+
+    let runtime_id = session_record.runtime_id.clone().or_else(|| {
+        runtime_registry
+            .current_runtime_id()
+            .map(ToString::to_string)
+    });
+
+    let Some(candidate_runtime_id) = runtime_id else {
+        return Err((
+            ControlErrorCode::NoModel,
+            format!(
+                "Session '{}' has no persisted runtime binding and no runtime is currently loaded",
+                session_id
+            ),
+        ));
+    };
+
+    let runtime_id = ensure_runtime_loaded(
+        runtime_registry,
+        resource_governor,
+        model_catalog,
+        session_registry,
+        storage,
+        session_id,
+        &candidate_runtime_id,
+    )?;
+
+    respawn_session_from_history(
+        runtime_registry,
+        memory,
+        scheduler,
+        session_registry,
+        storage,
+        pending_events,
+        tool_registry,
+        owner_id,
+        session_id,
+        runtime_id,
+    )
+}
+
+/// Load `runtime_id` through the model catalog unless it is already resident,
+/// returning the id of the runtime that now serves `session_id`.
+pub(crate) fn ensure_runtime_loaded(
+    runtime_registry: &mut RuntimeRegistry,
+    resource_governor: &mut ResourceGovernor,
+    model_catalog: &mut ModelCatalog,
+    session_registry: &SessionRegistry,
+    storage: &mut StorageService,
+    session_id: &str,
+    runtime_id: &str,
+) -> Result<String, (ControlErrorCode, String)> {
+    if runtime_registry.is_runtime_loaded(runtime_id) {
+        return Ok(runtime_id.to_string());
+    }
+
+    let selector = runtime_selector_for_session(runtime_registry, runtime_id)
+        .map_err(|detail| (ControlErrorCode::NoModel, detail))?;
+
+    if let Err(err) = model_catalog.refresh() {
+        tracing::warn!(
+            session_id,
+            runtime_id,
+            %err,
+            "PROCESS_CMD: failed to refresh model catalog before session resume"
+        );
+    }
+
+    let target = model_catalog
+        .resolve_load_target(&selector)
+        .map_err(|err| {
+            (
+                ControlErrorCode::LoadFailed,
+                format!(
+                    "Failed to resolve runtime '{}' for session '{}': {}",
+                    runtime_id, session_id, err
+                ),
+            )
+        })?;
+
+    activate_model_target(
+        runtime_registry,
+        resource_governor,
+        session_registry,
+        storage,
+        model_catalog,
+        &target,
+    )
+    .map(|loaded| loaded.runtime_id)
+    .map_err(|err| (ControlErrorCode::LoadFailed, err.message().to_string()))
+}
+
+/// Spawn a fresh interactive process for `session_id` on `runtime_id`,
+/// rebuilding its context from the persisted replay history.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn respawn_session_from_history(
+    runtime_registry: &mut RuntimeRegistry,
+    memory: &mut NeuralMemory,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    pending_events: &mut Vec<KernelEvent>,
+    tool_registry: &ToolRegistry,
+    owner_id: usize,
+    session_id: &str,
+    runtime_id: String,
+) -> Result<SessionContinuationTarget, (ControlErrorCode, String)> {
+    let replay_messages = storage
+        .load_replay_messages_for_session(session_id)
+        .map_err(|err| {
+            (
+                ControlErrorCode::Generic,
+                format!(
+                    "Failed to load persisted history for session '{}': {}",
+                    session_id, err
+                ),
+            )
+        })?;
+
+    let permission_policy = ProcessPermissionPolicy::interactive_chat(tool_registry)
+        .map_err(|err| (ControlErrorCode::SpawnFailed, err))?;
+    let system_prompt = crate::agent_prompt::build_agent_system_prompt_with_allowed_tools(
+        tool_registry,
+        ToolCaller::AgentText,
+        Some(&permission_policy.allowed_tools),
+    );
+    let rendered_prompt = {
+        let Some(engine) = runtime_registry.engine(&runtime_id) else {
+            return Err((
+                ControlErrorCode::NoModel,
+                format!(
+                    "Runtime '{}' is not loaded after activation for session '{}'",
+                    runtime_id, session_id
+                ),
+            ));
+        };
+
+        render_prompt_from_replay_history(&replay_messages, &system_prompt, engine)
+            .map_err(|detail| (ControlErrorCode::Generic, detail))?
+    };
+
+    let workload = storage
+        .latest_workload_for_session(session_id)
+        .ok()
+        .flatten()
+        .and_then(|value| parse_workload_label(&value))
+        .unwrap_or_default();
+    let pid_floor = runtime_registry.next_pid_floor();
+
+    let spawn_result = {
+        let Some(engine) = runtime_registry.engine_mut(&runtime_id) else {
+            return Err((
+                ControlErrorCode::NoModel,
+                format!(
+                    "Runtime '{}' is not available for session resume",
+                    runtime_id
+                ),
+            ));
+        };
+
+        spawn_restored_managed_process_with_session(
+            &runtime_id,
+            session_id,
+            pid_floor,
+            engine,
+            memory,
+            scheduler,
+            session_registry,
+            storage,
+            RestoredManagedProcessRequest {
+                rendered_prompt,
+                owner_id,
+                tool_caller: ToolCaller::AgentText,
+                permission_policy: Some(permission_policy),
+                workload,
+                required_backend_class: None,
+                priority: ProcessPriority::Normal,
+                lifecycle_policy: ProcessLifecyclePolicy::Interactive,
+                context_policy: None,
+            },
+        )
+        .map_err(|err| (ControlErrorCode::SpawnFailed, err))
+    }?;
+
+    if let Err(err) = runtime_registry.register_pid(storage, &runtime_id, spawn_result.pid) {
+        tracing::warn!(
+            pid = spawn_result.pid,
+            runtime_id,
+            %err,
+            "PROCESS_CMD: failed to register resumed pid in runtime registry"
+        );
+    }
+
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid: spawn_result.pid,
+        reason: "session_resumed".to_string(),
+    });
+    pending_events.push(KernelEvent::LobbyChanged {
+        reason: "session_resumed".to_string(),
+    });
+
+    Ok(SessionContinuationTarget {
+        session_id: spawn_result.session_id,
+        runtime_id,
+        pid: spawn_result.pid,
+        resumed_from_history: true,
+    })
+}
+
+/// Append a user turn to the live process behind `target`.
+///
+/// `source` labels the turn in storage and audit (`send_input`, `scheduled_job`).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn append_session_input(
+    runtime_registry: &mut RuntimeRegistry,
+    scheduler: &mut ProcessScheduler,
+    session_registry: &mut SessionRegistry,
+    storage: &mut StorageService,
+    turn_assembly: &mut TurnAssemblyStore,
+    pending_events: &mut Vec<KernelEvent>,
+    target: &SessionContinuationTarget,
+    prompt: &str,
+    source: &str,
+) -> Result<(), (ControlErrorCode, String)> {
+    let Some(engine) = runtime_registry.engine_mut(&target.runtime_id) else {
+        return Err((ControlErrorCode::NoModel, "No Model Loaded".to_string()));
+    };
+
+    let Some(process) = engine.processes.get(&target.pid) else {
+        return Err((
+            ControlErrorCode::PidNotFound,
+            format!("PID {} not found", target.pid),
+        ));
+    };
+
+    let had_pending_human_request = process.pending_human_request.is_some();
+
+    if !matches!(
+        process.state,
+        ProcessState::WaitingForInput | ProcessState::WaitingForHumanInput
+    ) {
+        return Err((
+            ControlErrorCode::InvalidSessionState,
+            format!(
+                "PID {} is not waiting for input (state={:?})",
+                target.pid, process.state
+            ),
+        ));
+    }
+
+    // Le richieste di approvazione dei budget di spesa non entrano nel
+    // contesto: la risposta sblocca o chiude il turno già in corso.
+    if let Some(request_id) = process
+        .pending_human_request
+        .as_ref()
+        .map(|request| request.request_id.clone())
+    {
+        let approved = matches!(
+            prompt.trim().to_ascii_lowercase().as_str(),
+            "approve" | "approved" | "yes" | "y"
+        );
+        if let Some(budget_id) =
+            scheduler.resolve_budget_approval(target.pid, &request_id, approved)
+        {
+            if let Some(process) = engine.processes.get_mut(&target.pid) {
+                process.clear_pending_human_request();
+                if approved {
+                    process.state = ProcessState::Ready;
+                } else {
+                    process.state = ProcessState::Finished;
+                    process.termination_reason = Some("budget_exhausted".to_string());
+                }
+            }
+            pending_events.push(KernelEvent::WorkspaceChanged {
+                pid: target.pid,
+                reason: "human_input_received".to_string(),
+            });
+            pending_events.push(KernelEvent::LobbyChanged {
+                reason: "human_input_received".to_string(),
+            });
+            audit::record(
+                storage,
+                audit::PROCESS_HUMAN_INPUT_RECEIVED,
+                format!(
+                    "source={} budget={} decision={}",
+                    source,
+                    budget_id,
+                    if approved { "approve" } else { "reject" }
+                ),
+                AuditContext::for_process(
+                    session_registry.session_id_for_pid(target.pid),
+                    target.pid,
+                    Some(&target.runtime_id),
+                ),
+            );
+            return Ok(());
+        }
+    }
+
+    engine
+        .send_user_input(target.pid, prompt)
+        .map_err(|err| (ControlErrorCode::InvalidSessionState, err.to_string()))?;
+
+    turn_assembly.apply_runtime_boundary(target.pid, AssistantTurnRuntimeBoundary::RuntimeClosed);
+    let session_id = session_registry
+        .session_id_for_pid(target.pid)
+        .map(ToString::to_string);
+    let workload = scheduler
+        .snapshot(target.pid)
+        .map(|snapshot| format!("{:?}", snapshot.workload).to_lowercase())
+        .unwrap_or_else(|| "general".to_string());
+    if let Some(session_id_ref) = session_id.as_deref() {
+        match storage.start_session_turn(
+            session_id_ref,
+            target.pid,
+            &workload,
+            source,
+            prompt,
+            "input",
+        ) {
+            Ok(turn_id) => session_registry.remember_active_turn(target.pid, turn_id),
+            Err(err) => {
+                tracing::warn!(
+                    pid = target.pid,
+                    session_id = session_id_ref,
+                    %err,
+                    "PROCESS_CMD: failed to persist SEND_INPUT turn"
+                );
+            }
+        }
+    }
+    pending_events.push(KernelEvent::WorkspaceChanged {
+        pid: target.pid,
+        reason: "input_received".to_string(),
+    });
+    pending_events.push(KernelEvent::LobbyChanged {
+        reason: "input_received".to_string(),
+    });
+    audit::record(
+        storage,
+        if had_pending_human_request {
+            audit::PROCESS_HUMAN_INPUT_RECEIVED
+        } else {
+            audit::PROCESS_INPUT_RECEIVED
+        },
+        format!(
+            "source={} chars={} hitl={}",
+            source,
+            prompt.chars().count(),
+            had_pending_human_request
+        ),
+        AuditContext::for_process(session_id.as_deref(), target.pid, Some(&target.runtime_id)),
+    );
+    Ok(())
+}
+
+pub(crate) fn render_prompt_from_replay_history(
+    replay_messages: &[StoredReplayMessage],
+    system_prompt: &str,
+    engine: &crate::engine::LLMEngine,
+) -> Result<String, String> {
+    let mut rendered = String::new();
+    let mut saw_user_message = false;
+
+    for message in replay_messages {
+        match message.role.as_str() {
+            "user" => {
+                if !saw_user_message {
+                    rendered.push_str(&format_initial_prompt_with_metadata(
+                        Some(system_prompt),
+                        &message.content,
+                        engine.loaded_family(),
+                        engine.model_metadata(),
+                    ));
+                    saw_user_message = true;
+                } else {
+                    rendered.push_str(&format_user_message_with_metadata(
+                        &message.content,
+                        engine.loaded_family(),
+                        engine.model_metadata(),
+                    ));
+                }
+            }
+            "assistant" if message.kind != "thinking" => {
+                rendered.push_str(&message.content);
+            }
+            _ => {}
+        }
+    }
+
+    if !saw_user_message {
+        return Err("Session has no persisted user messages to reconstruct for resume".to_string());
+    }
+
+    Ok(rendered)
+}
@@ -1,7 +1,7 @@
 use agentic_control_models::{
-    ContextStatusSnapshot as ControlContextStatusSnapshot, HumanInputRequestView,
-    PathGrantAccessMode as ControlPathGrantAccessMode, PathGrantView, PidStatusResponse,
-    ProcessPermissionsView,
+    ContextSegmentView, ContextStatusSnapshot as ControlContextStatusSnapshot,
+    HumanInputRequestView, PathGrantAccessMode as ControlPathGrantAccessMode, PathGrantView,
+    PidStatusResponse, ProcessPermissionsView,
 };
 
 use crate::engine::LLMEngine;
@@ -297,6 +297,8 @@ fn map_context_snapshot(
         context_strategy: snapshot.context_strategy,
         context_tokens_used: snapshot.context_tokens_used,
         context_window_size: snapshot.context_window_size,
+        compaction_trigger_tokens: snapshot.compaction_trigger_tokens,
+        compaction_target_tokens: snapshot.compaction_target_tokens,
         context_compressions: snapshot.context_compressions,
         context_retrieval_hits: snapshot.context_retrieval_hits,
         context_retrieval_requests: snapshot.context_retrieval_requests,
@@ -316,6 +318,21 @@ fn map_context_snapshot(
         retrieve_candidate_limit: snapshot.retrieve_candidate_limit,
         retrieve_max_segment_chars: snapshot.retrieve_max_segment_chars,
         retrieve_min_score: snapshot.retrieve_min_score,
+        pinned_segments: snapshot.pinned_segments,
+        pinned_tokens: snapshot.pinned_tokens,
+        policy_updates: snapshot.policy_updates,
+        manual_compactions: snapshot.manual_compactions,
+        dropped_segments: snapshot.dropped_segments,
+        segments: snapshot
+            .segments
+            .into_iter()
+            .map(|segment| ContextSegmentView {
+                index: segment.index,
+                kind: segment.kind,
+                token_count: segment.token_count,
+                pinned: segment.pinned,
+            })
+            .collect(),
     }
 }
 
@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 
 use agentic_control_models::{
-    GenerationStatus, JobsStatus, MemoryStatus, ModelStatus, OrchestrationsStatus, ProcessesStatus,
-    ScheduledJobListResponse, SchedulerStatus, StatusResponse,
+    BudgetStatusView, GenerationStatus, JobsStatus, MemoryStatus, ModelStatus,
+    OrchestrationsStatus, ProcessesStatus, ScheduledJobListResponse, SchedulerFairnessGroupView,
+    SchedulerFairnessStatus, SchedulerStatus, StatusResponse,
 };
 
 use crate::backend::runtime_backend_telemetry;
@@ -15,7 +16,7 @@ use crate::runtimes::RuntimeRegistry;
 use crate::scheduler::ProcessScheduler;
 use crate::services::job_scheduler::JobScheduler;
 use crate::session::SessionRegistry;
-use crate::storage::StorageService;
+use crate::storage::{current_timestamp_ms, StorageService};
 
 use super::mcp::build_mcp_status_view;
 use super::orchestration::build_orchestration_summaries;
@@ -50,6 +51,7 @@ pub struct StatusSnapshotDeps<'a> {
     pub session_registry: &'a SessionRegistry,
     pub storage: &'a StorageService,
     pub mcp_bridge: Option<&'a crate::mcp::bridge::McpBridgeRuntime>,
+    pub tool_manifests: Option<&'a crate::tools::manifests::ToolManifestLoader>,
 }
 
 pub fn build_global_status(deps: &StatusSnapshotDeps<'_>) -> StatusResponse {
@@ -234,6 +236,12 @@ pub fn build_global_status(deps: &StatusSnapshotDeps<'_>) -> StatusResponse {
             parked_pids: mem.parked_pids,
             oom_events: mem.oom_events,
             swap_worker_crashes: mem.swap_worker_crashes,
+            swap_snapshots: mem.swap_snapshots,
+            swap_disk_bytes: mem.swap_disk_bytes,
+            swap_raw_bytes: mem.swap_raw_bytes,
+            swap_evictions: mem.swap_evictions,
+            swap_orphans_removed: mem.swap_orphans_removed,
+            swap_integrity_failures: mem.swap_integrity_failures,
         },
         scheduler: SchedulerStatus {
             tracked: sched_tracked,
@@ -241,6 +249,7 @@ pub fn build_global_status(deps: &StatusSnapshotDeps<'_>) -> StatusResponse {
             priority_high: sched_high,
             priority_normal: sched_norm,
             priority_low: sched_low,
+            fairness: build_scheduler_fairness(deps.scheduler),
         },
         jobs: JobsStatus {
             scheduled_jobs: deps
@@ -254,6 +263,8 @@ pub fn build_global_status(deps: &StatusSnapshotDeps<'_>) -> StatusResponse {
             active_orchestrations: build_orchestration_summaries(deps),
         },
         processes: processes_status,
+        budgets: build_budget_status(deps.scheduler),
+        tool_manifests: deps.tool_manifests.map(|manifests| manifests.status_view()),
     }
 }
 
@@ -271,3 +282,53 @@ pub fn build_scheduled_job_list(deps: &StatusSnapshotDeps<'_>) -> ScheduledJobLi
 #[cfg(test)]
 #[path = "../tests/status_view.rs"]
 mod tests;
+
+fn build_budget_status(scheduler: &ProcessScheduler) -> Vec<BudgetStatusView> {
+    scheduler
+        .budget_states(current_timestamp_ms())
+        .into_iter()
+        .map(|state| BudgetStatusView {
+            exhausted: state.spent_usd >= state.limit_usd,
+            budget_id: state.budget_id,
+            scope: state.scope.as_str().to_string(),
+            target: state.target,
+            scope_key: state.scope_key,
+            window: state.window.as_str().to_string(),
+            window_started_at_ms: state.window_started_at_ms,
+            spent_usd: state.spent_usd,
+            limit_usd: state.limit_usd,
+            on_exhausted: state.on_exhausted.as_str().to_string(),
+        })
+        .collect()
+}
+
+fn build_scheduler_fairness(scheduler: &ProcessScheduler) -> SchedulerFairnessStatus {
+    let config = scheduler.fair_share_config();
+    let snapshot = scheduler.fairness_snapshot();
+    let [critical, high, normal, low] = snapshot.dispatches_by_priority;
+    SchedulerFairnessStatus {
+        policy: config.policy.as_str().to_string(),
+        scope: config.scope.as_str().to_string(),
+        max_in_flight: config.max_in_flight,
+        dispatches_total: snapshot.dispatches_total,
+        aged_dispatches: snapshot.aged_dispatches,
+        deadline_dispatches: snapshot.deadline_dispatches,
+        dispatches_critical: critical,
+        dispatches_high: high,
+        dispatches_normal: normal,
+        dispatches_low: low,
+        fairness_index: snapshot.fairness_index,
+        max_wait_ms: snapshot.max_wait_ms,
+        groups: snapshot
+            .groups
+            .into_iter()
+            .map(
+                |(group, processes, virtual_service)| SchedulerFairnessGroupView {
+                    group,
+                    processes,
+                    virtual_service,
+                },
+            )
+            .collect(),
+    }
+}
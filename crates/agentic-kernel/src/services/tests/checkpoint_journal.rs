@@ -0,0 +1,203 @@
+use super::{write_checkpoint_generation, CheckpointJournal, CheckpointSnapshotDeps};
+use crate::checkpoint::materialize_generation;
+use crate::commands::MetricsState;
+use crate::config::CheckpointConfig;
+use crate::memory::NeuralMemory;
+use crate::model_catalog::{ModelCatalog, WorkloadClass};
+use crate::process::{ContextPolicy, ContextState};
+use crate::scheduler::{ProcessPriority, ProcessScheduler, RestoredProcessMetadata};
+use crate::session::SessionRegistry;
+use crate::storage::StorageService;
+use crate::tools::invocation::{
+    default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
+};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+struct Fixture {
+    dir: PathBuf,
+    storage: StorageService,
+    session_registry: SessionRegistry,
+    model_catalog: ModelCatalog,
+    scheduler: ProcessScheduler,
+    metrics: MetricsState,
+    memory: NeuralMemory,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!(
+            "agenticos_checkpoint_journal_{}_{unique}",
+            std::process::id()
+        ));
+        fs::create_dir_all(dir.join("models")).expect("create temp dir");
+        let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+        let boot = storage
+            .record_kernel_boot("checkpoint-journal-test")
+            .expect("record boot");
+        let session_registry =
+            SessionRegistry::load(&mut storage, boot.boot_id).expect("load session registry");
+        Self {
+            model_catalog: ModelCatalog::discover(dir.join("models")).expect("discover models"),
+            dir,
+            storage,
+            session_registry,
+            scheduler: ProcessScheduler::new(),
+            metrics: MetricsState::new(),
+            memory: NeuralMemory::new().expect("memory init"),
+        }
+    }
+
+    fn set_process(&mut self, pid: u64, state: &str) {
+        if self.scheduler.snapshot(pid).is_none() {
+            self.scheduler
+                .register(pid, WorkloadClass::General, ProcessPriority::Normal);
+        }
+        self.scheduler.record_restored_process(
+            pid,
+            RestoredProcessMetadata {
+                owner_id: 1,
+                tool_caller: ToolCaller::AgentText,
+                permission_policy: ProcessPermissionPolicy {
+                    trust_scope: ProcessTrustScope::InteractiveChat,
+                    actions_allowed: false,
+                    allowed_tools: Vec::new(),
+                    path_grants: default_path_grants(),
+                    path_scopes: vec![".".to_string()],
+                },
+                state: state.to_string(),
+                token_count: 0,
+                max_tokens: 256,
+                context_slot_id: None,
+                resident_slot_policy: None,
+                resident_slot_state: None,
+                resident_slot_snapshot_path: None,
+                backend_id: None,
+                backend_class: None,
+                backend_capabilities: None,
+                context_policy: ContextPolicy::from_kernel_defaults(),
+                context_state: ContextState::default(),
+                pending_human_request: None,
+            },
+        );
+    }
+
+    fn record(&mut self, journal: &mut CheckpointJournal) -> usize {
+        let deps = CheckpointSnapshotDeps {
+            engine_state: None,
+            model_catalog: &self.model_catalog,
+            scheduler: &self.scheduler,
+            session_registry: &self.session_registry,
+            metrics: &self.metrics,
+            memory: &self.memory,
+        };
+        journal
+            .record_changes(&deps, &mut self.storage)
+            .expect("record changes")
+    }
+
+    fn latest_states(&self) -> Vec<(u64, String)> {
+        let loaded = self
+            .storage
+            .load_checkpoint_generation(None)
+            .expect("load latest")
+            .expect("latest generation");
+        let mut states = materialize_generation(&loaded)
+            .expect("materialize")
+            .processes
+            .into_iter()
+            .map(|process| (process.pid, process.state))
+            .collect::<Vec<_>>();
+        states.sort();
+        states
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn journal() -> CheckpointJournal {
+    CheckpointJournal::new(&CheckpointConfig {
+        retained_generations: 3,
+        max_deltas_per_generation: 8,
+        ..CheckpointConfig::default()
+    })
+}
+
+#[test]
+fn state_transitions_are_journaled_and_replayed_on_the_generation() {
+    let mut fixture = Fixture::new();
+    let mut journal = journal();
+
+    // An idle kernel does not open a generation.
+    assert_eq!(fixture.record(&mut journal), 0);
+    assert!(fixture
+        .storage
+        .list_checkpoint_generations()
+        .expect("list")
+        .is_empty());
+
+    fixture.set_process(7, "WaitingForInput");
+    assert_eq!(fixture.record(&mut journal), 0);
+    let opened = fixture
+        .storage
+        .latest_checkpoint_generation()
+        .expect("latest")
+        .expect("opened generation");
+    assert_eq!(opened.reason, "journal_open");
+    assert_eq!(opened.process_count, 1);
+    assert_eq!(fixture.record(&mut journal), 0);
+
+    fixture.set_process(7, "Ready");
+    fixture.set_process(8, "WaitingForInput");
+    assert_eq!(fixture.record(&mut journal), 2);
+    fixture.scheduler.unregister(7);
+    assert_eq!(fixture.record(&mut journal), 1);
+
+    assert_eq!(
+        fixture.latest_states(),
+        vec![(8, "WaitingForInput".to_string())]
+    );
+    let listed = fixture.storage.list_checkpoint_generations().expect("list");
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].delta_count, 3);
+}
+
+#[test]
+fn deltas_follow_a_manual_generation_opened_in_the_same_boot() {
+    let mut fixture = Fixture::new();
+    let mut journal = journal();
+    fixture.set_process(3, "WaitingForInput");
+    fixture.record(&mut journal);
+
+    let snapshot = CheckpointSnapshotDeps {
+        engine_state: None,
+        model_catalog: &fixture.model_catalog,
+        scheduler: &fixture.scheduler,
+        session_registry: &fixture.session_registry,
+        metrics: &fixture.metrics,
+        memory: &fixture.memory,
+    }
+    .build_snapshot();
+    let boot_id = fixture.session_registry.boot_id();
+    let manual = write_checkpoint_generation(&mut fixture.storage, boot_id, "manual", &snapshot, 3)
+        .expect("manual generation");
+
+    fixture.set_process(3, "Ready");
+    assert_eq!(fixture.record(&mut journal), 1);
+    let loaded = fixture
+        .storage
+        .load_checkpoint_generation(Some(manual))
+        .expect("load manual")
+        .expect("manual generation");
+    assert_eq!(loaded.deltas.len(), 1);
+    assert_eq!(fixture.latest_states(), vec![(3, "Ready".to_string())]);
+}
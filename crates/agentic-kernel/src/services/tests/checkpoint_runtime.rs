@@ -0,0 +1,96 @@
+use super::{checkpoint_context_prompt, resume_state_for};
+use crate::checkpoint::ProcessSnapshot;
+use crate::process::{
+    ContextPolicy, ContextSegment, ContextSegmentKind, ContextState, HumanInputRequest,
+    HumanInputRequestKind, ProcessLifecyclePolicy, ProcessState,
+};
+use crate::tools::invocation::{
+    default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
+};
+
+fn snapshot(state: &str) -> ProcessSnapshot {
+    ProcessSnapshot {
+        pid: 7,
+        owner_id: 1,
+        tool_caller: ToolCaller::AgentText,
+        permission_policy: ProcessPermissionPolicy {
+            trust_scope: ProcessTrustScope::InteractiveChat,
+            actions_allowed: false,
+            allowed_tools: Vec::new(),
+            path_grants: default_path_grants(),
+            path_scopes: vec![".".to_string()],
+        },
+        state: state.to_string(),
+        token_count: 0,
+        max_tokens: 256,
+        context_policy: ContextPolicy::from_kernel_defaults(),
+        context_state: ContextState::default(),
+        pending_human_request: None,
+        session_id: Some("sess-7".to_string()),
+        runtime_id: Some("rt-qwen".to_string()),
+        lifecycle_policy: ProcessLifecyclePolicy::Interactive,
+        resident_slot_snapshot_path: None,
+    }
+}
+
+#[test]
+fn checkpointed_states_map_to_the_state_the_process_resumes_in() {
+    for state in ["Ready", "Running", "AwaitingTurnDecision", "Parked"] {
+        assert_eq!(
+            resume_state_for(&snapshot(state)),
+            Some(ProcessState::Ready)
+        );
+    }
+    assert_eq!(
+        resume_state_for(&snapshot("WaitingForInput")),
+        Some(ProcessState::WaitingForInput)
+    );
+
+    // Without the pending request there is nothing to answer: plain input.
+    let mut waiting = snapshot("WaitingForHumanInput");
+    assert_eq!(
+        resume_state_for(&waiting),
+        Some(ProcessState::WaitingForInput)
+    );
+    waiting.pending_human_request = Some(HumanInputRequest {
+        request_id: "hr-1".to_string(),
+        kind: HumanInputRequestKind::Approval,
+        question: "Deploy to staging?".to_string(),
+        details: None,
+        choices: vec!["approve".to_string(), "reject".to_string()],
+        allow_free_text: false,
+        placeholder: None,
+        requested_at_ms: 1,
+    });
+    assert_eq!(
+        resume_state_for(&waiting),
+        Some(ProcessState::WaitingForHumanInput)
+    );
+
+    for state in ["WaitingForSyscall", "Finished", "Orphaned"] {
+        assert_eq!(resume_state_for(&snapshot(state)), None);
+    }
+}
+
+#[test]
+fn checkpoint_context_renders_its_segments_in_order() {
+    let mut process = snapshot("WaitingForInput");
+    assert_eq!(checkpoint_context_prompt(&process), None);
+
+    process.context_state.segments = vec![
+        ContextSegment::new(
+            ContextSegmentKind::InjectedContext,
+            4,
+            "<|system|>\nbe brief\n".to_string(),
+        ),
+        ContextSegment::new(
+            ContextSegmentKind::UserTurn,
+            3,
+            "<|user|>\nhello\n".to_string(),
+        ),
+    ];
+    assert_eq!(
+        checkpoint_context_prompt(&process).as_deref(),
+        Some("<|system|>\nbe brief\n<|user|>\nhello\n")
+    );
+}
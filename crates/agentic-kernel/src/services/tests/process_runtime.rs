@@ -174,6 +174,7 @@ fn quota_override_can_make_scheduler_unbounded_without_expanding_turn_budget() {
             quota_override: Some(ProcessQuota {
                 max_tokens: usize::MAX,
                 max_syscalls: usize::MAX,
+                limits: Default::default(),
             }),
         },
     )
@@ -240,6 +241,7 @@ fn quota_override_can_reduce_turn_budget_when_limit_is_lower_than_default() {
             quota_override: Some(ProcessQuota {
                 max_tokens: 64,
                 max_syscalls: 1,
+                limits: Default::default(),
             }),
         },
     )
@@ -19,7 +19,8 @@ use crate::resource_governor::ResourceGovernor;
 use crate::runtimes::{RuntimeRegistry, RuntimeReservation};
 use crate::scheduler::{CheckedOutProcessMetadata, ProcessScheduler, RestoredProcessMetadata};
 use crate::services::job_scheduler::{
-    JobScheduler, ScheduledJobTriggerInput, ScheduledWorkflowJobRequest,
+    ConcurrencyPolicy, JobScheduler, ScheduledJobRequest, ScheduledJobTarget,
+    ScheduledJobTargetInput, ScheduledJobTriggerInput,
 };
 use crate::session::SessionRegistry;
 use crate::storage::{current_timestamp_ms, StorageService};
@@ -259,6 +260,7 @@ fn global_status_surfaces_cloud_backend_metadata_for_lobby() {
         session_registry: &session_registry,
         storage: &runtime_storage,
         mcp_bridge: None,
+        tool_manifests: None,
     });
 
     assert!(status.model.loaded);
@@ -339,6 +341,7 @@ fn orchestration_list_returns_active_orchestrations() {
         session_registry: &session_registry,
         storage: &runtime_storage,
         mcp_bridge: None,
+        tool_manifests: None,
     });
 
     assert_eq!(response.orchestrations.len(), 1);
@@ -361,23 +364,24 @@ fn scheduled_job_list_surfaces_scheduled_workflow_jobs() {
     let session_registry = fresh_session_registry();
     let (mut runtime_storage, runtime_registry, resource_governor) = fresh_runtime_registry();
 
-    let workflow_payload = sample_workflow_json();
-    let workflow =
-        serde_json::from_str::<TaskGraphDef>(&workflow_payload).expect("parse sample workflow");
+    let target = ScheduledJobTarget::from_request(ScheduledJobTargetInput::Workflow {
+        workflow: serde_json::from_str(&sample_workflow_json()).expect("parse sample workflow"),
+    })
+    .expect("workflow target");
     let now_ms = current_timestamp_ms();
     job_scheduler
-        .schedule_workflow_job(
+        .schedule_job(
             &mut runtime_storage,
-            ScheduledWorkflowJobRequest {
+            ScheduledJobRequest {
                 name: "nightly-review".to_string(),
-                workflow,
-                workflow_payload,
+                target,
                 trigger: ScheduledJobTriggerInput::At {
                     at_ms: now_ms + 60_000,
                 },
                 timeout_ms: Some(30_000),
                 max_retries: Some(2),
                 backoff_ms: Some(5_000),
+                concurrency_policy: ConcurrencyPolicy::default(),
                 enabled: true,
             },
         )
@@ -396,6 +400,7 @@ fn scheduled_job_list_surfaces_scheduled_workflow_jobs() {
         session_registry: &session_registry,
         storage: &runtime_storage,
         mcp_bridge: None,
+        tool_manifests: None,
     });
 
     assert_eq!(response.jobs.len(), 1);
@@ -467,6 +472,7 @@ fn artifact_list_filters_by_task_and_rejects_missing_orchestrations() {
         session_registry: &session_registry,
         storage: &runtime_storage,
         mcp_bridge: None,
+        tool_manifests: None,
     };
 
     let filtered = build_artifact_list(&deps, orch_id, Some("draft")).expect("artifact list");
@@ -557,6 +563,7 @@ fn sample_workflow_graph() -> TaskGraphDef {
                 allowed_tools: None,
                 path_scopes: None,
                 path_grants: None,
+                quota_limits: Default::default(),
                 deps: Vec::new(),
             },
             TaskNodeDef {
@@ -575,6 +582,7 @@ fn sample_workflow_graph() -> TaskGraphDef {
                 allowed_tools: None,
                 path_scopes: None,
                 path_grants: None,
+                quota_limits: Default::default(),
                 deps: vec!["plan".to_string()],
             },
         ],
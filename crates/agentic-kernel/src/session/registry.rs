@@ -198,6 +198,10 @@ impl SessionRegistry {
         self.pid_to_session.get(&pid).map(String::as_str)
     }
 
+    pub(crate) fn boot_id(&self) -> i64 {
+        self.boot_id
+    }
+
     pub(crate) fn session_count(&self) -> usize {
         self.sessions.len()
     }
@@ -1,5 +1,6 @@
 use rusqlite::{params, params_from_iter, OptionalExtension};
 
+use crate::scheduler::SpendRecord;
 use crate::services::accounting::{AccountingEventStatus, AccountingSummary};
 
 use crate::storage::{current_timestamp_ms, StorageError, StorageService};
@@ -107,6 +108,33 @@ impl StorageService {
         )
     }
 
+    /// Billable remote spend recorded since `since_ms`, oldest first, used to
+    /// seed the budget ledger at boot.
+    pub(crate) fn accounting_spend_since(
+        &self,
+        since_ms: i64,
+    ) -> Result<Vec<SpendRecord>, StorageError> {
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT recorded_at_ms, provider_id, model_id, session_id, estimated_cost_usd
+            FROM accounting_events
+            WHERE recorded_at_ms >= ?1 AND estimated_cost_usd > 0
+            ORDER BY recorded_at_ms ASC, event_id ASC
+            "#,
+        )?;
+        let rows = statement.query_map(params![since_ms], |row| {
+            Ok(SpendRecord {
+                at_ms: row.get(0)?,
+                provider_id: row.get(1)?,
+                model_id: row.get(2)?,
+                session_id: row.get(3)?,
+                orchestration_id: None,
+                cost_usd: row.get(4)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
     #[cfg(test)]
     pub(crate) fn accounting_event_count(&self) -> Result<i64, StorageError> {
         Ok(self
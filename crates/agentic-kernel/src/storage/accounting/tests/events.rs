@@ -91,6 +91,16 @@ fn accounting_summary_survives_reopen() {
     assert_eq!(global.last_error.as_deref(), Some("rate limited"));
     assert_eq!(session, backend);
 
+    let spend = reopened.accounting_spend_since(0).expect("spend since");
+    assert_eq!(spend.len(), 1, "zero-cost failures are not billable");
+    assert_eq!(spend[0].provider_id.as_deref(), Some("openai-responses"));
+    assert_eq!(spend[0].session_id.as_deref(), Some("sess-1"));
+    assert!((spend[0].cost_usd - 0.00042).abs() < 1e-12);
+    assert!(reopened
+        .accounting_spend_since(i64::MAX)
+        .expect("future spend")
+        .is_empty());
+
     let _ = fs::remove_dir_all(dir);
 }
 
@@ -0,0 +1,235 @@
+use rusqlite::{params, OptionalExtension, Row};
+
+use crate::storage::{current_timestamp_ms, StorageError, StorageService};
+
+/// Full kernel snapshot that opens a checkpoint generation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NewCheckpointGeneration {
+    pub boot_id: Option<i64>,
+    pub reason: String,
+    pub kernel_version: String,
+    pub snapshot_json: String,
+    pub process_count: usize,
+}
+
+/// Per-process change journaled on top of a generation: `upsert` carries the
+/// process (and its scheduler entry), `remove` only the PID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NewCheckpointDelta {
+    pub pid: u64,
+    pub delta_kind: String,
+    pub process_json: Option<String>,
+    pub scheduler_json: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredCheckpointGeneration {
+    pub generation: i64,
+    pub boot_id: Option<i64>,
+    pub created_at_ms: i64,
+    pub reason: String,
+    pub kernel_version: String,
+    pub process_count: usize,
+    pub delta_count: usize,
+    pub last_delta_at_ms: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredCheckpointDelta {
+    pub delta_id: i64,
+    pub pid: u64,
+    pub delta_kind: String,
+    pub process_json: Option<String>,
+    pub scheduler_json: Option<String>,
+    pub recorded_at_ms: i64,
+}
+
+/// A generation with everything needed to materialize it: the base snapshot
+/// and its deltas in the order they were recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LoadedCheckpointGeneration {
+    pub summary: StoredCheckpointGeneration,
+    pub snapshot_json: String,
+    pub deltas: Vec<StoredCheckpointDelta>,
+}
+
+const GENERATION_SUMMARY_SELECT: &str = r#"
+    SELECT g.generation, g.boot_id, g.created_at_ms, g.reason, g.kernel_version,
+           g.process_count, COUNT(d.delta_id), MAX(d.recorded_at_ms)
+    FROM checkpoint_generations g
+    LEFT JOIN checkpoint_deltas d ON d.generation = g.generation
+"#;
+
+impl StorageService {
+    /// Open a new generation from a full snapshot and return its number.
+    ///
+    /// Only the newest `retained_generations` are kept; older ones are
+    /// dropped together with their deltas.
+    pub(crate) fn create_checkpoint_generation(
+        &mut self,
+        generation: &NewCheckpointGeneration,
+        retained_generations: usize,
+    ) -> Result<i64, StorageError> {
+        let transaction = self.connection.transaction()?;
+        transaction.execute(
+            r#"
+            INSERT INTO checkpoint_generations (
+                boot_id, created_at_ms, reason, kernel_version, snapshot_json, process_count
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            params![
+                generation.boot_id,
+                current_timestamp_ms(),
+                generation.reason,
+                generation.kernel_version,
+                generation.snapshot_json,
+                generation.process_count as i64
+            ],
+        )?;
+        let generation_id = transaction.last_insert_rowid();
+        transaction.execute(
+            r#"
+            DELETE FROM checkpoint_generations
+            WHERE generation IN (
+                SELECT generation FROM checkpoint_generations
+                ORDER BY generation DESC
+                LIMIT -1 OFFSET ?1
+            )
+            "#,
+            params![retained_generations.max(1) as i64],
+        )?;
+        transaction.commit()?;
+        Ok(generation_id)
+    }
+
+    /// Journal a batch of deltas on `generation` in a single transaction.
+    pub(crate) fn append_checkpoint_deltas(
+        &mut self,
+        generation: i64,
+        deltas: &[NewCheckpointDelta],
+    ) -> Result<(), StorageError> {
+        if deltas.is_empty() {
+            return Ok(());
+        }
+        let now_ms = current_timestamp_ms();
+        let transaction = self.connection.transaction()?;
+        for delta in deltas {
+            transaction.execute(
+                r#"
+                INSERT INTO checkpoint_deltas (
+                    generation, pid, delta_kind, process_json, scheduler_json, recorded_at_ms
+                )
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                "#,
+                params![
+                    generation,
+                    delta.pid as i64,
+                    delta.delta_kind,
+                    delta.process_json,
+                    delta.scheduler_json,
+                    now_ms
+                ],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Retained generations, newest first.
+    pub(crate) fn list_checkpoint_generations(
+        &self,
+    ) -> Result<Vec<StoredCheckpointGeneration>, StorageError> {
+        let mut statement = self.connection.prepare(&format!(
+            "{GENERATION_SUMMARY_SELECT} GROUP BY g.generation ORDER BY g.generation DESC"
+        ))?;
+        let rows = statement.query_map([], stored_generation_from_row)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub(crate) fn latest_checkpoint_generation(
+        &self,
+    ) -> Result<Option<StoredCheckpointGeneration>, StorageError> {
+        Ok(self
+            .connection
+            .query_row(
+                &format!(
+                    "{GENERATION_SUMMARY_SELECT} GROUP BY g.generation \
+                     ORDER BY g.generation DESC LIMIT 1"
+                ),
+                [],
+                stored_generation_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Load `generation`, or the newest one when `None`.
+    pub(crate) fn load_checkpoint_generation(
+        &self,
+        generation: Option<i64>,
+    ) -> Result<Option<LoadedCheckpointGeneration>, StorageError> {
+        let summary = self
+            .connection
+            .query_row(
+                &format!(
+                    "{GENERATION_SUMMARY_SELECT} \
+                     WHERE g.generation = COALESCE(?1, \
+                         (SELECT MAX(generation) FROM checkpoint_generations)) \
+                     GROUP BY g.generation"
+                ),
+                params![generation],
+                stored_generation_from_row,
+            )
+            .optional()?;
+        let Some(summary) = summary else {
+            return Ok(None);
+        };
+        let snapshot_json = self.connection.query_row(
+            "SELECT snapshot_json FROM checkpoint_generations WHERE generation = ?1",
+            params![summary.generation],
+            |row| row.get::<_, String>(0),
+        )?;
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT delta_id, pid, delta_kind, process_json, scheduler_json, recorded_at_ms
+            FROM checkpoint_deltas
+            WHERE generation = ?1
+            ORDER BY delta_id ASC
+            "#,
+        )?;
+        let deltas = statement
+            .query_map(params![summary.generation], |row| {
+                Ok(StoredCheckpointDelta {
+                    delta_id: row.get(0)?,
+                    pid: row.get::<_, i64>(1)? as u64,
+                    delta_kind: row.get(2)?,
+                    process_json: row.get(3)?,
+                    scheduler_json: row.get(4)?,
+                    recorded_at_ms: row.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(LoadedCheckpointGeneration {
+            summary,
+            snapshot_json,
+            deltas,
+        }))
+    }
+}
+
+fn stored_generation_from_row(row: &Row<'_>) -> rusqlite::Result<StoredCheckpointGeneration> {
+    Ok(StoredCheckpointGeneration {
+        generation: row.get(0)?,
+        boot_id: row.get(1)?,
+        created_at_ms: row.get(2)?,
+        reason: row.get(3)?,
+        kernel_version: row.get(4)?,
+        process_count: row.get::<_, i64>(5)? as usize,
+        delta_count: row.get::<_, i64>(6)? as usize,
+        last_delta_at_ms: row.get(7)?,
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/generations.rs"]
+mod tests;
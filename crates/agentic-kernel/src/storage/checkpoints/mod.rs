@@ -0,0 +1,6 @@
+mod generations;
+
+pub(crate) use generations::{
+    LoadedCheckpointGeneration, NewCheckpointDelta, NewCheckpointGeneration, StoredCheckpointDelta,
+    StoredCheckpointGeneration,
+};
@@ -0,0 +1,126 @@
+use super::{NewCheckpointDelta, NewCheckpointGeneration, StorageService};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn base(reason: &str) -> NewCheckpointGeneration {
+    NewCheckpointGeneration {
+        boot_id: Some(1),
+        reason: reason.to_string(),
+        kernel_version: "0.5.0-test".to_string(),
+        snapshot_json: format!("{{\"reason\":\"{reason}\"}}"),
+        process_count: 1,
+    }
+}
+
+fn upsert(pid: u64, state: &str) -> NewCheckpointDelta {
+    NewCheckpointDelta {
+        pid,
+        delta_kind: "upsert".to_string(),
+        process_json: Some(format!("{{\"pid\":{pid},\"state\":\"{state}\"}}")),
+        scheduler_json: None,
+    }
+}
+
+#[test]
+fn generations_keep_their_deltas_in_order_across_reopen() {
+    let dir = make_temp_dir("agenticos_checkpoint_generations");
+    let db_path = dir.join("agenticos.db");
+
+    let generation = {
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        assert!(storage
+            .load_checkpoint_generation(None)
+            .expect("load empty")
+            .is_none());
+        let generation = storage
+            .create_checkpoint_generation(&base("interval"), 4)
+            .expect("create generation");
+        storage
+            .append_checkpoint_deltas(generation, &[upsert(3, "Running"), upsert(4, "Ready")])
+            .expect("append first batch");
+        storage
+            .append_checkpoint_deltas(
+                generation,
+                &[NewCheckpointDelta {
+                    pid: 4,
+                    delta_kind: "remove".to_string(),
+                    process_json: None,
+                    scheduler_json: None,
+                }],
+            )
+            .expect("append second batch");
+        generation
+    };
+
+    let storage = StorageService::open(&db_path).expect("reopen storage");
+    let loaded = storage
+        .load_checkpoint_generation(None)
+        .expect("load latest")
+        .expect("latest generation");
+    assert_eq!(loaded.summary.generation, generation);
+    assert_eq!(loaded.summary.reason, "interval");
+    assert_eq!(loaded.summary.delta_count, 3);
+    assert!(loaded.summary.last_delta_at_ms.is_some());
+    assert_eq!(loaded.snapshot_json, "{\"reason\":\"interval\"}");
+    let kinds = loaded
+        .deltas
+        .iter()
+        .map(|delta| (delta.pid, delta.delta_kind.as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, vec![(3, "upsert"), (4, "upsert"), (4, "remove")]);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn only_the_newest_generations_are_retained() {
+    let dir = make_temp_dir("agenticos_checkpoint_retention");
+    let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+
+    let first = storage
+        .create_checkpoint_generation(&base("boot"), 2)
+        .expect("first generation");
+    storage
+        .append_checkpoint_deltas(first, &[upsert(1, "Ready")])
+        .expect("journal on first");
+    let second = storage
+        .create_checkpoint_generation(&base("interval"), 2)
+        .expect("second generation");
+    let third = storage
+        .create_checkpoint_generation(&base("manual"), 2)
+        .expect("third generation");
+
+    let listed = storage
+        .list_checkpoint_generations()
+        .expect("list generations")
+        .into_iter()
+        .map(|generation| (generation.generation, generation.delta_count))
+        .collect::<Vec<_>>();
+    assert_eq!(listed, vec![(third, 0), (second, 0)]);
+    assert!(storage
+        .load_checkpoint_generation(Some(first))
+        .expect("load pruned")
+        .is_none());
+    assert_eq!(
+        storage
+            .load_checkpoint_generation(Some(second))
+            .expect("load second")
+            .expect("second generation")
+            .summary
+            .reason,
+        "interval"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn make_temp_dir(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}_{}_{}", std::process::id(), timestamp));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
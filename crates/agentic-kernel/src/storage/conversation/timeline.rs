@@ -195,6 +195,16 @@ impl StorageService {
         Ok(turn_id)
     }
 
+    pub(crate) fn latest_session_turn_id(
+        &mut self,
+        session_id: &str,
+    ) -> Result<Option<i64>, StorageError> {
+        let transaction = self.connection.transaction()?;
+        let turn_id = latest_turn_id_for_session(&transaction, session_id)?;
+        transaction.commit()?;
+        Ok(turn_id)
+    }
+
     pub(crate) fn resume_turn(&mut self, turn_id: i64) -> Result<(), StorageError> {
         let updated_at_ms = current_timestamp_ms();
         let transaction = self.connection.transaction()?;
@@ -221,6 +231,36 @@ impl StorageService {
         Ok(())
     }
 
+    /// Move a running turn onto the live run of `pid`, used when the kernel
+    /// replaces the process serving the turn (budget reroute to a local model).
+    pub(crate) fn reassign_turn_pid(&mut self, turn_id: i64, pid: u64) -> Result<(), StorageError> {
+        let updated_at_ms = current_timestamp_ms();
+        let transaction = self.connection.transaction()?;
+        let (session_id, _) =
+            turn_identity(&transaction, turn_id)?.ok_or(StorageError::MissingTurn { turn_id })?;
+        let run_id =
+            active_run_id_for_session_pid(&transaction, &session_id, pid)?.ok_or_else(|| {
+                StorageError::MissingProcessRun {
+                    session_id: session_id.clone(),
+                    pid,
+                }
+            })?;
+
+        transaction.execute(
+            r#"
+            UPDATE session_turns
+            SET pid = ?2,
+                run_id = ?3,
+                updated_at_ms = ?4
+            WHERE turn_id = ?1
+            "#,
+            params![turn_id, pid, run_id, updated_at_ms],
+        )?;
+        transaction.commit()?;
+
+        Ok(())
+    }
+
     pub(crate) fn mark_turn_awaiting_decision(&mut self, turn_id: i64) -> Result<(), StorageError> {
         let updated_at_ms = current_timestamp_ms();
         let transaction = self.connection.transaction()?;
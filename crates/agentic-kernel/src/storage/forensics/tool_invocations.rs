@@ -28,6 +28,8 @@ pub(crate) struct CompletedToolInvocationRecord {
     pub(crate) effect_json: Option<String>,
     pub(crate) duration_ms: Option<u128>,
     pub(crate) kill: bool,
+    /// The output was served from the tool result cache.
+    pub(crate) cache_hit: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -53,6 +55,8 @@ pub(crate) struct StoredToolInvocationRecord {
     pub(crate) effect_json: Option<String>,
     pub(crate) duration_ms: Option<u128>,
     pub(crate) kill: bool,
+    /// The output was served from the tool result cache.
+    pub(crate) cache_hit: bool,
 }
 
 impl StorageService {
@@ -122,7 +126,8 @@ impl StorageService {
                 error_text = ?8,
                 effect_json = ?9,
                 duration_ms = ?10,
-                kill = ?11
+                kill = ?11,
+                cache_hit = ?12
             WHERE tool_call_id = ?1
             "#,
             params![
@@ -139,6 +144,7 @@ impl StorageService {
                     .duration_ms
                     .map(|value| value.min(i64::MAX as u128) as i64),
                 if record.kill { 1 } else { 0 },
+                if record.cache_hit { 1 } else { 0 },
             ],
         )?;
         Ok(())
@@ -173,7 +179,8 @@ impl StorageService {
                 error_text,
                 effect_json,
                 duration_ms,
-                kill
+                kill,
+                cache_hit
             FROM tool_invocation_history
             WHERE pid = ?1
             ORDER BY recorded_at_ms DESC, invocation_id DESC
@@ -260,5 +267,6 @@ fn map_tool_invocation_row(
             .get::<_, Option<i64>>(19)?
             .map(|value| value.max(0) as u128),
         kill: row.get::<_, i64>(20)? != 0,
+        cache_hit: row.get::<_, i64>(21)? != 0,
     })
 }
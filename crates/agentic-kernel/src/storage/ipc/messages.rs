@@ -392,6 +392,85 @@ impl StorageService {
         Ok(values)
     }
 
+    /// Highest insertion sequence in `ipc_messages`; channel watchers start
+    /// after it so messages recorded before boot never re-trigger jobs.
+    pub(crate) fn latest_ipc_message_seq(&self) -> Result<i64, StorageError> {
+        Ok(self.connection.query_row(
+            "SELECT COALESCE(MAX(rowid), 0) FROM ipc_messages",
+            [],
+            |row| row.get(0),
+        )?)
+    }
+
+    /// Channel messages recorded after `after_seq`, oldest first, paired
+    /// with their insertion sequence.
+    pub(crate) fn load_channel_ipc_messages_after(
+        &self,
+        after_seq: i64,
+        limit: usize,
+    ) -> Result<Vec<(i64, StoredIpcMessage)>, StorageError> {
+        let limit = limit.min(i64::MAX as usize) as i64;
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT
+                message_id,
+                orchestration_id,
+                sender_pid,
+                sender_task_id,
+                sender_attempt,
+                receiver_pid,
+                receiver_task_id,
+                receiver_attempt,
+                receiver_role,
+                message_type,
+                channel,
+                payload_preview,
+                payload_text,
+                status,
+                created_at_ms,
+                delivered_at_ms,
+                consumed_at_ms,
+                failed_at_ms,
+                rowid
+            FROM ipc_messages
+            WHERE rowid > ?1 AND channel IS NOT NULL
+            ORDER BY rowid ASC
+            LIMIT ?2
+            "#,
+        )?;
+        let rows = statement.query_map(params![after_seq, limit], |row| {
+            Ok((
+                row.get(18)?,
+                StoredIpcMessage {
+                    message_id: row.get(0)?,
+                    orchestration_id: row.get(1)?,
+                    sender_pid: row.get(2)?,
+                    sender_task_id: row.get(3)?,
+                    sender_attempt: row.get(4)?,
+                    receiver_pid: row.get(5)?,
+                    receiver_task_id: row.get(6)?,
+                    receiver_attempt: row.get(7)?,
+                    receiver_role: row.get(8)?,
+                    message_type: row.get(9)?,
+                    channel: row.get(10)?,
+                    payload_preview: row.get(11)?,
+                    payload_text: row.get(12)?,
+                    status: row.get(13)?,
+                    created_at_ms: row.get(14)?,
+                    delivered_at_ms: row.get(15)?,
+                    consumed_at_ms: row.get(16)?,
+                    failed_at_ms: row.get(17)?,
+                },
+            ))
+        })?;
+
+        let mut values = Vec::new();
+        for row in rows {
+            values.push(row?);
+        }
+        Ok(values)
+    }
+
     pub(crate) fn delete_ipc_messages_for_orchestration(
         &mut self,
         orchestration_id: u64,
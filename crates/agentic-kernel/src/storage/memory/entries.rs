@@ -0,0 +1,229 @@
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+
+use crate::storage::{current_timestamp_ms, StorageError, StorageService};
+
+/// A memory scope as stored: `scope_kind` is `user`, `workspace`, `agent` or
+/// `workflow`, `scope_key` identifies the instance of that kind.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct MemoryScopeRef {
+    pub scope_kind: String,
+    pub scope_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NewMemoryEntry {
+    pub scope: MemoryScopeRef,
+    pub entry_kind: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub source_session_id: Option<String>,
+    pub source_turn_id: Option<i64>,
+    pub source_pid: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredMemoryEntry {
+    pub memory_id: i64,
+    pub scope: MemoryScopeRef,
+    pub entry_kind: String,
+    pub content: String,
+    pub tags: Vec<String>,
+    pub source_session_id: Option<String>,
+    pub source_turn_id: Option<i64>,
+    pub source_pid: Option<u64>,
+    pub created_at_ms: i64,
+    pub updated_at_ms: i64,
+    pub last_recalled_at_ms: Option<i64>,
+    pub recall_count: i64,
+}
+
+const MEMORY_ENTRY_COLUMNS: &str = "memory_id, scope_kind, scope_key, entry_kind, content, \
+     tags_json, source_session_id, source_turn_id, source_pid, created_at_ms, updated_at_ms, \
+     last_recalled_at_ms, recall_count";
+
+impl StorageService {
+    /// Store a memory in its scope and return its id.
+    ///
+    /// Remembering the same content twice in a scope refreshes the existing
+    /// entry (tags, provenance, timestamp) instead of duplicating it. Scopes
+    /// are capped at `max_entries_per_scope`: the entries recalled or updated
+    /// least recently are dropped first.
+    pub(crate) fn remember_memory_entry(
+        &mut self,
+        entry: &NewMemoryEntry,
+        max_entries_per_scope: usize,
+    ) -> Result<i64, StorageError> {
+        let now_ms = current_timestamp_ms();
+        let tags_json = serde_json::to_string(&entry.tags).unwrap_or_else(|_| "[]".to_string());
+        let source_pid = entry.source_pid.map(|pid| pid as i64);
+        let transaction = self.connection.transaction()?;
+
+        let existing = transaction
+            .query_row(
+                r#"
+                SELECT memory_id FROM memory_entries
+                WHERE scope_kind = ?1 AND scope_key = ?2 AND content = ?3
+                "#,
+                params![entry.scope.scope_kind, entry.scope.scope_key, entry.content],
+                |row| row.get::<_, i64>(0),
+            )
+            .optional()?;
+        let memory_id = match existing {
+            Some(memory_id) => {
+                transaction.execute(
+                    r#"
+                    UPDATE memory_entries
+                    SET entry_kind = ?2, tags_json = ?3, source_session_id = ?4,
+                        source_turn_id = ?5, source_pid = ?6, updated_at_ms = ?7
+                    WHERE memory_id = ?1
+                    "#,
+                    params![
+                        memory_id,
+                        entry.entry_kind,
+                        tags_json,
+                        entry.source_session_id,
+                        entry.source_turn_id,
+                        source_pid,
+                        now_ms
+                    ],
+                )?;
+                memory_id
+            }
+            None => {
+                transaction.execute(
+                    r#"
+                    INSERT INTO memory_entries (
+                        scope_kind, scope_key, entry_kind, content, tags_json,
+                        source_session_id, source_turn_id, source_pid,
+                        created_at_ms, updated_at_ms
+                    )
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?9)
+                    "#,
+                    params![
+                        entry.scope.scope_kind,
+                        entry.scope.scope_key,
+                        entry.entry_kind,
+                        entry.content,
+                        tags_json,
+                        entry.source_session_id,
+                        entry.source_turn_id,
+                        source_pid,
+                        now_ms
+                    ],
+                )?;
+                transaction.last_insert_rowid()
+            }
+        };
+
+        transaction.execute(
+            r#"
+            DELETE FROM memory_entries
+            WHERE scope_kind = ?1 AND scope_key = ?2 AND memory_id IN (
+                SELECT memory_id FROM memory_entries
+                WHERE scope_kind = ?1 AND scope_key = ?2
+                ORDER BY MAX(updated_at_ms, COALESCE(last_recalled_at_ms, 0)) DESC,
+                         memory_id DESC
+                LIMIT -1 OFFSET ?3
+            )
+            "#,
+            params![
+                entry.scope.scope_kind,
+                entry.scope.scope_key,
+                max_entries_per_scope.max(1) as i64
+            ],
+        )?;
+        transaction.commit()?;
+        Ok(memory_id)
+    }
+
+    /// Most recently updated entries of `scopes`, newest first.
+    pub(crate) fn memory_entries_in_scopes(
+        &self,
+        scopes: &[MemoryScopeRef],
+        limit: usize,
+    ) -> Result<Vec<StoredMemoryEntry>, StorageError> {
+        if scopes.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let filter = vec!["(scope_kind = ? AND scope_key = ?)"; scopes.len()].join(" OR ");
+        let mut statement = self.connection.prepare(&format!(
+            "SELECT {MEMORY_ENTRY_COLUMNS} FROM memory_entries WHERE {filter} \
+             ORDER BY updated_at_ms DESC, memory_id DESC LIMIT {limit}"
+        ))?;
+        let values = scopes
+            .iter()
+            .flat_map(|scope| [scope.scope_kind.as_str(), scope.scope_key.as_str()]);
+        let rows = statement.query_map(params_from_iter(values), stored_memory_entry_from_row)?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+
+    pub(crate) fn mark_memory_entries_recalled(
+        &mut self,
+        memory_ids: &[i64],
+    ) -> Result<(), StorageError> {
+        let now_ms = current_timestamp_ms();
+        let transaction = self.connection.transaction()?;
+        for memory_id in memory_ids {
+            transaction.execute(
+                r#"
+                UPDATE memory_entries
+                SET last_recalled_at_ms = ?2, recall_count = recall_count + 1
+                WHERE memory_id = ?1
+                "#,
+                params![memory_id, now_ms],
+            )?;
+        }
+        transaction.commit()?;
+        Ok(())
+    }
+
+    /// Delete `memory_id` if it belongs to one of `scopes`; returns the
+    /// deleted entry.
+    pub(crate) fn forget_memory_entry(
+        &mut self,
+        memory_id: i64,
+        scopes: &[MemoryScopeRef],
+    ) -> Result<Option<StoredMemoryEntry>, StorageError> {
+        let entry = self
+            .connection
+            .query_row(
+                &format!("SELECT {MEMORY_ENTRY_COLUMNS} FROM memory_entries WHERE memory_id = ?1"),
+                params![memory_id],
+                stored_memory_entry_from_row,
+            )
+            .optional()?;
+        let Some(entry) = entry.filter(|entry| scopes.contains(&entry.scope)) else {
+            return Ok(None);
+        };
+        self.connection.execute(
+            "DELETE FROM memory_entries WHERE memory_id = ?1",
+            params![memory_id],
+        )?;
+        Ok(Some(entry))
+    }
+}
+
+fn stored_memory_entry_from_row(row: &Row<'_>) -> rusqlite::Result<StoredMemoryEntry> {
+    let tags_json: String = row.get(5)?;
+    Ok(StoredMemoryEntry {
+        memory_id: row.get(0)?,
+        scope: MemoryScopeRef {
+            scope_kind: row.get(1)?,
+            scope_key: row.get(2)?,
+        },
+        entry_kind: row.get(3)?,
+        content: row.get(4)?,
+        tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+        source_session_id: row.get(6)?,
+        source_turn_id: row.get(7)?,
+        source_pid: row.get::<_, Option<i64>>(8)?.map(|pid| pid as u64),
+        created_at_ms: row.get(9)?,
+        updated_at_ms: row.get(10)?,
+        last_recalled_at_ms: row.get(11)?,
+        recall_count: row.get(12)?,
+    })
+}
+
+#[cfg(test)]
+#[path = "tests/entries.rs"]
+mod tests;
@@ -0,0 +1,3 @@
+mod entries;
+
+pub(crate) use entries::{MemoryScopeRef, NewMemoryEntry, StoredMemoryEntry};
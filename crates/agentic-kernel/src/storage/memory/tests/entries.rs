@@ -0,0 +1,120 @@
+use super::{MemoryScopeRef, NewMemoryEntry, StorageService};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn scope(kind: &str, key: &str) -> MemoryScopeRef {
+    MemoryScopeRef {
+        scope_kind: kind.to_string(),
+        scope_key: key.to_string(),
+    }
+}
+
+fn note(scope: &MemoryScopeRef, content: &str) -> NewMemoryEntry {
+    NewMemoryEntry {
+        scope: scope.clone(),
+        entry_kind: "fact".to_string(),
+        content: content.to_string(),
+        tags: vec!["deploy".to_string()],
+        source_session_id: Some("sess-1".to_string()),
+        source_turn_id: Some(4),
+        source_pid: Some(9),
+    }
+}
+
+#[test]
+fn memory_entries_survive_reopen_and_stay_in_their_scope() {
+    let dir = make_temp_dir("agenticos_memory_entries");
+    let db_path = dir.join("agenticos.db");
+    let workspace = scope("workspace", "/repo");
+    let user = scope("user", "local");
+
+    {
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let first = storage
+            .remember_memory_entry(&note(&workspace, "deploys go through staging"), 16)
+            .expect("remember workspace fact");
+        let again = storage
+            .remember_memory_entry(&note(&workspace, "deploys go through staging"), 16)
+            .expect("remember duplicate");
+        assert_eq!(first, again);
+        storage
+            .remember_memory_entry(&note(&user, "prefers Italian replies"), 16)
+            .expect("remember user fact");
+    }
+
+    let mut storage = StorageService::open(&db_path).expect("reopen storage");
+    let entries = storage
+        .memory_entries_in_scopes(std::slice::from_ref(&workspace), 10)
+        .expect("load workspace memories");
+    assert_eq!(entries.len(), 1);
+    let entry = &entries[0];
+    assert_eq!(entry.content, "deploys go through staging");
+    assert_eq!(entry.tags, vec!["deploy".to_string()]);
+    assert_eq!(entry.source_session_id.as_deref(), Some("sess-1"));
+    assert_eq!(entry.source_turn_id, Some(4));
+    assert_eq!(entry.source_pid, Some(9));
+
+    storage
+        .mark_memory_entries_recalled(&[entry.memory_id])
+        .expect("mark recalled");
+    let both = storage
+        .memory_entries_in_scopes(&[workspace.clone(), user.clone()], 10)
+        .expect("load both scopes");
+    assert_eq!(both.len(), 2);
+    let recalled = both
+        .iter()
+        .find(|candidate| candidate.memory_id == entry.memory_id)
+        .expect("recalled entry");
+    assert_eq!(recalled.recall_count, 1);
+    assert!(recalled.last_recalled_at_ms.is_some());
+
+    assert!(storage
+        .forget_memory_entry(entry.memory_id, std::slice::from_ref(&user))
+        .expect("forget outside scope")
+        .is_none());
+    assert!(storage
+        .forget_memory_entry(entry.memory_id, std::slice::from_ref(&workspace))
+        .expect("forget in scope")
+        .is_some());
+    assert!(storage
+        .memory_entries_in_scopes(std::slice::from_ref(&workspace), 10)
+        .expect("reload workspace")
+        .is_empty());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn memory_scopes_are_capped_by_least_recent_use() {
+    let dir = make_temp_dir("agenticos_memory_cap");
+    let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+    let workflow = scope("workflow", "orch-1");
+
+    for index in 0..4 {
+        storage
+            .remember_memory_entry(&note(&workflow, &format!("step {index} done")), 3)
+            .expect("remember step");
+    }
+
+    let contents = storage
+        .memory_entries_in_scopes(std::slice::from_ref(&workflow), 10)
+        .expect("load workflow memories")
+        .into_iter()
+        .map(|entry| entry.content)
+        .collect::<Vec<_>>();
+    assert_eq!(contents.len(), 3);
+    assert!(!contents.contains(&"step 0 done".to_string()));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn make_temp_dir(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}_{}_{}", std::process::id(), timestamp));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
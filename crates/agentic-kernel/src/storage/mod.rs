@@ -12,14 +12,21 @@
 
 mod accounting;
 mod audit;
+mod checkpoints;
 mod conversation;
 mod forensics;
 mod ipc;
+mod memory;
 mod schema;
+mod tools;
 mod workflows;
 
 pub(crate) use accounting::StoredAccountingEvent;
 pub(crate) use audit::{NewAuditEvent, StoredAuditEvent};
+pub(crate) use checkpoints::{
+    LoadedCheckpointGeneration, NewCheckpointDelta, NewCheckpointGeneration, StoredCheckpointDelta,
+    StoredCheckpointGeneration,
+};
 pub(crate) use conversation::StoredReplayMessage;
 pub(crate) use conversation::StoredSessionRecord;
 pub(crate) use forensics::{
@@ -27,10 +34,12 @@ pub(crate) use forensics::{
     NewReplayBranchRecord, NewToolInvocationRecord, StoredCoreDumpRecord,
 };
 pub(crate) use ipc::{IpcMailboxSelector, NewIpcMessage, StoredIpcMessage};
+pub(crate) use memory::{MemoryScopeRef, NewMemoryEntry, StoredMemoryEntry};
 #[allow(unused_imports)]
 pub(crate) use schema::{
     current_timestamp_ms, BootRecoveryReport, KernelBootRecord, StorageError, StorageService,
 };
+pub(crate) use tools::StoredRuntimeTool;
 pub(crate) use workflows::{NewScheduledJobRecord, StoredScheduledJob, StoredScheduledJobRun};
 pub(crate) use workflows::{
     StoredWorkflowArtifact, StoredWorkflowArtifactInput, StoredWorkflowTaskAttempt,
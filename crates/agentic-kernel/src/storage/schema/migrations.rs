@@ -2,7 +2,7 @@ use rusqlite::{Connection, OptionalExtension, Transaction};
 
 use super::service::StorageError;
 
-pub(crate) const LATEST_SCHEMA_VERSION: i32 = 14;
+pub(crate) const LATEST_SCHEMA_VERSION: i32 = 19;
 
 const LEGACY_TABLES: &[&str] = &[
     "kernel_meta",
@@ -14,6 +14,10 @@ const LEGACY_TABLES: &[&str] = &[
     "runtime_instances",
     "runtime_load_queue",
     "accounting_events",
+    "core_dump_index",
+    "debug_checkpoints",
+    "tool_invocation_history",
+    "replay_branch_index",
     "audit_events",
     "workflow_task_attempts",
     "workflow_artifacts",
@@ -21,6 +25,10 @@ const LEGACY_TABLES: &[&str] = &[
     "scheduled_jobs",
     "scheduled_job_runs",
     "ipc_messages",
+    "runtime_tools",
+    "memory_entries",
+    "checkpoint_generations",
+    "checkpoint_deltas",
 ];
 
 pub(super) fn apply_pending_migrations(connection: &mut Connection) -> Result<(), StorageError> {
@@ -65,6 +73,9 @@ fn rebaseline_existing_schema(connection: &mut Connection) -> Result<(), Storage
             transaction.execute(&format!("ALTER TABLE {table} RENAME TO {legacy}"), [])?;
         }
     }
+    // Gli indici seguono la tabella rinominata: vanno liberati prima che la
+    // baseline li ricrei con lo stesso nome.
+    drop_legacy_indexes(&transaction)?;
 
     create_baseline_schema(&transaction)?;
     copy_legacy_rows(&transaction)?;
@@ -310,7 +321,8 @@ fn create_baseline_schema(transaction: &Transaction<'_>) -> Result<(), StorageEr
             error_text TEXT NULL,
             effect_json TEXT NULL,
             duration_ms INTEGER NULL,
-            kill INTEGER NOT NULL DEFAULT 0
+            kill INTEGER NOT NULL DEFAULT 0,
+            cache_hit INTEGER NOT NULL DEFAULT 0
         );
 
         CREATE INDEX idx_tool_invocation_history_pid_recorded
@@ -437,6 +449,7 @@ fn create_baseline_schema(transaction: &Transaction<'_>) -> Result<(), StorageEr
             timeout_ms INTEGER NOT NULL,
             max_retries INTEGER NOT NULL,
             backoff_ms INTEGER NOT NULL,
+            concurrency_policy TEXT NOT NULL DEFAULT 'forbid',
             enabled INTEGER NOT NULL DEFAULT 1,
             state TEXT NOT NULL,
             next_run_at_ms INTEGER NULL,
@@ -470,6 +483,7 @@ fn create_baseline_schema(transaction: &Transaction<'_>) -> Result<(), StorageEr
             orchestration_id INTEGER NULL,
             deadline_at_ms INTEGER NULL,
             error TEXT NULL,
+            reason TEXT NULL,
             FOREIGN KEY(job_id) REFERENCES scheduled_jobs(job_id) ON DELETE CASCADE
         );
 
@@ -513,6 +527,54 @@ fn create_baseline_schema(transaction: &Transaction<'_>) -> Result<(), StorageEr
             ON ipc_messages(orchestration_id, receiver_role, created_at_ms ASC);
         CREATE INDEX idx_ipc_messages_channel_created
             ON ipc_messages(orchestration_id, channel, created_at_ms ASC);
+
+        CREATE TABLE runtime_tools (
+            tool_name TEXT PRIMARY KEY,
+            entry_json TEXT NOT NULL,
+            registered_at_ms INTEGER NOT NULL,
+            updated_at_ms INTEGER NOT NULL
+        );
+
+        CREATE TABLE memory_entries (
+            memory_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            scope_kind TEXT NOT NULL,
+            scope_key TEXT NOT NULL,
+            entry_kind TEXT NOT NULL,
+            content TEXT NOT NULL,
+            tags_json TEXT NOT NULL DEFAULT '[]',
+            source_session_id TEXT,
+            source_turn_id INTEGER,
+            source_pid INTEGER,
+            created_at_ms INTEGER NOT NULL,
+            updated_at_ms INTEGER NOT NULL,
+            last_recalled_at_ms INTEGER,
+            recall_count INTEGER NOT NULL DEFAULT 0
+        );
+        CREATE INDEX idx_memory_entries_scope_updated
+            ON memory_entries(scope_kind, scope_key, updated_at_ms DESC);
+
+        CREATE TABLE checkpoint_generations (
+            generation INTEGER PRIMARY KEY AUTOINCREMENT,
+            boot_id INTEGER,
+            created_at_ms INTEGER NOT NULL,
+            reason TEXT NOT NULL,
+            kernel_version TEXT NOT NULL,
+            snapshot_json TEXT NOT NULL,
+            process_count INTEGER NOT NULL DEFAULT 0
+        );
+
+        CREATE TABLE checkpoint_deltas (
+            delta_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            generation INTEGER NOT NULL,
+            pid INTEGER NOT NULL,
+            delta_kind TEXT NOT NULL,
+            process_json TEXT,
+            scheduler_json TEXT,
+            recorded_at_ms INTEGER NOT NULL,
+            FOREIGN KEY(generation) REFERENCES checkpoint_generations(generation) ON DELETE CASCADE
+        );
+        CREATE INDEX idx_checkpoint_deltas_generation
+            ON checkpoint_deltas(generation, delta_id);
         "#,
     )?;
     Ok(())
@@ -530,6 +592,10 @@ fn copy_legacy_rows(transaction: &Transaction<'_>) -> Result<(), StorageError> {
     copy_runtime_instances(transaction)?;
     copy_runtime_load_queue(transaction)?;
     copy_accounting_events(transaction)?;
+    copy_core_dump_index(transaction)?;
+    copy_debug_checkpoints(transaction)?;
+    copy_tool_invocation_history(transaction)?;
+    copy_replay_branch_index(transaction)?;
     copy_audit_events(transaction)?;
     copy_workflow_task_attempts(transaction)?;
     copy_workflow_artifacts(transaction)?;
@@ -537,6 +603,10 @@ fn copy_legacy_rows(transaction: &Transaction<'_>) -> Result<(), StorageError> {
     copy_scheduled_jobs(transaction)?;
     copy_scheduled_job_runs(transaction)?;
     copy_ipc_messages(transaction)?;
+    copy_runtime_tools(transaction)?;
+    copy_memory_entries(transaction)?;
+    copy_checkpoint_generations(transaction)?;
+    copy_checkpoint_deltas(transaction)?;
     Ok(())
 }
 
@@ -771,6 +841,83 @@ fn copy_accounting_events(transaction: &Transaction<'_>) -> Result<(), StorageEr
     )
 }
 
+fn copy_core_dump_index(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "core_dump_index",
+        &[
+            "dump_id",
+            "created_at_ms",
+            "session_id",
+            "pid",
+            "reason",
+            "fidelity",
+            "path",
+            "bytes",
+            "sha256",
+            "note",
+        ],
+    )
+}
+
+fn copy_debug_checkpoints(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "debug_checkpoints",
+        &[
+            "checkpoint_id",
+            "recorded_at_ms",
+            "session_id",
+            "pid",
+            "runtime_id",
+            "boundary",
+            "state",
+            "snapshot_json",
+        ],
+    )
+}
+
+fn copy_tool_invocation_history(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    let legacy = legacy_table_name("tool_invocation_history");
+    if !table_exists(transaction, &legacy)? {
+        return Ok(());
+    }
+    let cache_hit_expr = legacy_column_expr(transaction, &legacy, "cache_hit", "0")?;
+    let columns = "invocation_id, tool_call_id, recorded_at_ms, updated_at_ms, session_id, pid, runtime_id, tool_name, caller, transport, status, command_text, input_json, output_json, output_text, warnings_json, error_kind, error_text, effect_json, duration_ms, kill";
+    transaction.execute(
+        &format!(
+            "INSERT INTO tool_invocation_history ({columns}, cache_hit) \
+             SELECT {columns}, {cache_hit_expr} FROM {legacy}"
+        ),
+        [],
+    )?;
+    Ok(())
+}
+
+fn copy_replay_branch_index(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "replay_branch_index",
+        &[
+            "session_id",
+            "created_at_ms",
+            "pid",
+            "source_dump_id",
+            "source_session_id",
+            "source_pid",
+            "source_fidelity",
+            "replay_mode",
+            "tool_mode",
+            "initial_state",
+            "patched_context_segments",
+            "patched_episodic_segments",
+            "stubbed_invocations",
+            "overridden_invocations",
+            "baseline_json",
+        ],
+    )
+}
+
 fn copy_audit_events(transaction: &Transaction<'_>) -> Result<(), StorageError> {
     copy_same_columns_if_table_exists(
         transaction,
@@ -841,55 +988,37 @@ fn copy_workflow_artifact_inputs(transaction: &Transaction<'_>) -> Result<(), St
 }
 
 fn copy_scheduled_jobs(transaction: &Transaction<'_>) -> Result<(), StorageError> {
-    copy_same_columns_if_table_exists(
-        transaction,
-        "scheduled_jobs",
-        &[
-            "job_id",
-            "name",
-            "target_kind",
-            "workflow_payload",
-            "trigger_kind",
-            "trigger_payload",
-            "timeout_ms",
-            "max_retries",
-            "backoff_ms",
-            "enabled",
-            "state",
-            "next_run_at_ms",
-            "current_trigger_at_ms",
-            "current_attempt",
-            "active_run_id",
-            "active_orchestration_id",
-            "active_deadline_at_ms",
-            "last_run_started_at_ms",
-            "last_run_completed_at_ms",
-            "last_run_status",
-            "last_error",
-            "consecutive_failures",
-            "created_at_ms",
-            "updated_at_ms",
-        ],
-    )
+    let legacy = legacy_table_name("scheduled_jobs");
+    if !table_exists(transaction, &legacy)? {
+        return Ok(());
+    }
+    let policy_expr = legacy_column_expr(transaction, &legacy, "concurrency_policy", "'forbid'")?;
+    let columns = "job_id, name, target_kind, workflow_payload, trigger_kind, trigger_payload, timeout_ms, max_retries, backoff_ms, enabled, state, next_run_at_ms, current_trigger_at_ms, current_attempt, active_run_id, active_orchestration_id, active_deadline_at_ms, last_run_started_at_ms, last_run_completed_at_ms, last_run_status, last_error, consecutive_failures, created_at_ms, updated_at_ms";
+    transaction.execute(
+        &format!(
+            "INSERT INTO scheduled_jobs ({columns}, concurrency_policy) \
+             SELECT {columns}, {policy_expr} FROM {legacy}"
+        ),
+        [],
+    )?;
+    Ok(())
 }
 
 fn copy_scheduled_job_runs(transaction: &Transaction<'_>) -> Result<(), StorageError> {
-    copy_same_columns_if_table_exists(
-        transaction,
-        "scheduled_job_runs",
-        &[
-            "run_id",
-            "job_id",
-            "trigger_at_ms",
-            "attempt",
-            "status",
-            "started_at_ms",
-            "completed_at_ms",
-            "orchestration_id",
-            "deadline_at_ms",
-            "error",
-        ],
-    )
+    let legacy = legacy_table_name("scheduled_job_runs");
+    if !table_exists(transaction, &legacy)? {
+        return Ok(());
+    }
+    let reason_expr = legacy_column_expr(transaction, &legacy, "reason", "NULL")?;
+    let columns = "run_id, job_id, trigger_at_ms, attempt, status, started_at_ms, completed_at_ms, orchestration_id, deadline_at_ms, error";
+    transaction.execute(
+        &format!(
+            "INSERT INTO scheduled_job_runs ({columns}, reason) \
+             SELECT {columns}, {reason_expr} FROM {legacy}"
+        ),
+        [],
+    )?;
+    Ok(())
 }
 
 fn copy_ipc_messages(transaction: &Transaction<'_>) -> Result<(), StorageError> {
@@ -909,6 +1038,73 @@ fn copy_ipc_messages(transaction: &Transaction<'_>) -> Result<(), StorageError>
     Ok(())
 }
 
+fn copy_runtime_tools(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "runtime_tools",
+        &[
+            "tool_name",
+            "entry_json",
+            "registered_at_ms",
+            "updated_at_ms",
+        ],
+    )
+}
+
+fn copy_memory_entries(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "memory_entries",
+        &[
+            "memory_id",
+            "scope_kind",
+            "scope_key",
+            "entry_kind",
+            "content",
+            "tags_json",
+            "source_session_id",
+            "source_turn_id",
+            "source_pid",
+            "created_at_ms",
+            "updated_at_ms",
+            "last_recalled_at_ms",
+            "recall_count",
+        ],
+    )
+}
+
+fn copy_checkpoint_generations(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "checkpoint_generations",
+        &[
+            "generation",
+            "boot_id",
+            "created_at_ms",
+            "reason",
+            "kernel_version",
+            "snapshot_json",
+            "process_count",
+        ],
+    )
+}
+
+fn copy_checkpoint_deltas(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    copy_same_columns_if_table_exists(
+        transaction,
+        "checkpoint_deltas",
+        &[
+            "delta_id",
+            "generation",
+            "pid",
+            "delta_kind",
+            "process_json",
+            "scheduler_json",
+            "recorded_at_ms",
+        ],
+    )
+}
+
 fn copy_same_columns_if_table_exists(
     transaction: &Transaction<'_>,
     table: &str,
@@ -936,6 +1132,21 @@ fn drop_legacy_tables(transaction: &Transaction<'_>) -> Result<(), StorageError>
     Ok(())
 }
 
+fn drop_legacy_indexes(transaction: &Transaction<'_>) -> Result<(), StorageError> {
+    let names = {
+        let mut statement = transaction.prepare(
+            "SELECT name FROM sqlite_master \
+             WHERE type = 'index' AND sql IS NOT NULL AND substr(tbl_name, 1, 9) = '__legacy_'",
+        )?;
+        let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+        rows.collect::<Result<Vec<_>, _>>()?
+    };
+    for name in names {
+        transaction.execute(&format!("DROP INDEX {name}"), [])?;
+    }
+    Ok(())
+}
+
 fn table_exists(transaction: &Transaction<'_>, table: &str) -> Result<bool, rusqlite::Error> {
     transaction
         .query_row(
@@ -408,3 +408,70 @@ fn make_temp_dir(prefix: &str) -> PathBuf {
     fs::create_dir_all(&dir).expect("create temp dir");
     dir
 }
+
+#[test]
+fn previous_schema_version_is_rebaselined_with_indexes_and_forensics() {
+    let dir = make_temp_dir("agenticos_storage_rebaseline_previous");
+    let db_path = dir.join("agenticos.db");
+
+    {
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        storage
+            .record_kernel_boot("0.5.0-test")
+            .expect("record kernel boot");
+    }
+    {
+        let connection = Connection::open(&db_path).expect("open raw db");
+        connection
+            .execute_batch(
+                r#"
+                    INSERT INTO tool_invocation_history (
+                        tool_call_id, recorded_at_ms, updated_at_ms, tool_name, caller,
+                        transport, status, command_text, input_json, output_json, kill
+                    ) VALUES (
+                        'call-1', 1, 2, 'read_file', 'agent_text', 'text', 'completed',
+                        'TOOL:read_file {"path":"a.txt"}', '{"path":"a.txt"}', '{"output":"a"}', 0
+                    );
+                    ALTER TABLE tool_invocation_history DROP COLUMN cache_hit;
+                "#,
+            )
+            .expect("seed previous schema");
+        connection
+            .pragma_update(None, "user_version", LATEST_SCHEMA_VERSION - 1)
+            .expect("downgrade schema version");
+    }
+
+    let storage = StorageService::open(&db_path).expect("reopen after rebaseline");
+    assert_eq!(
+        storage.schema_version().expect("schema version"),
+        LATEST_SCHEMA_VERSION
+    );
+    assert_eq!(storage.boot_count().expect("boot count"), 1);
+    drop(storage);
+
+    let connection = Connection::open(&db_path).expect("open migrated db");
+    assert!(table_has_column(
+        &connection,
+        "tool_invocation_history",
+        "cache_hit"
+    ));
+    let (output_json, cache_hit): (String, i64) = connection
+        .query_row(
+            "SELECT output_json, cache_hit FROM tool_invocation_history WHERE tool_call_id = 'call-1'",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .expect("migrated invocation row");
+    assert_eq!(output_json, r#"{"output":"a"}"#);
+    assert_eq!(cache_hit, 0);
+    let legacy_objects: i64 = connection
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE substr(name, 1, 9) = '__legacy_'",
+            [],
+            |row| row.get(0),
+        )
+        .expect("count legacy objects");
+    assert_eq!(legacy_objects, 0);
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,67 @@
+use rusqlite::params;
+
+use crate::storage::{current_timestamp_ms, StorageError, StorageService};
+
+/// A `REGISTER_TOOL` registration as persisted in `runtime_tools`; the entry
+/// is kept as the JSON of its `ToolRegistryEntry`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StoredRuntimeTool {
+    pub tool_name: String,
+    pub entry_json: String,
+    pub registered_at_ms: i64,
+    pub updated_at_ms: i64,
+}
+
+impl StorageService {
+    pub(crate) fn upsert_runtime_tool(
+        &mut self,
+        tool_name: &str,
+        entry_json: &str,
+    ) -> Result<(), StorageError> {
+        let now_ms = current_timestamp_ms();
+        self.connection.execute(
+            r#"
+            INSERT INTO runtime_tools (tool_name, entry_json, registered_at_ms, updated_at_ms)
+            VALUES (?1, ?2, ?3, ?3)
+            ON CONFLICT(tool_name) DO UPDATE SET
+                entry_json = excluded.entry_json,
+                updated_at_ms = excluded.updated_at_ms
+            "#,
+            params![tool_name, entry_json, now_ms],
+        )?;
+        Ok(())
+    }
+
+    /// Returns whether a registration was stored under `tool_name`.
+    pub(crate) fn delete_runtime_tool(&mut self, tool_name: &str) -> Result<bool, StorageError> {
+        let deleted = self.connection.execute(
+            "DELETE FROM runtime_tools WHERE tool_name = ?1",
+            params![tool_name],
+        )?;
+        Ok(deleted > 0)
+    }
+
+    /// Persisted runtime tools in registration order.
+    pub(crate) fn load_runtime_tools(&self) -> Result<Vec<StoredRuntimeTool>, StorageError> {
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT tool_name, entry_json, registered_at_ms, updated_at_ms
+            FROM runtime_tools
+            ORDER BY registered_at_ms ASC, tool_name ASC
+            "#,
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok(StoredRuntimeTool {
+                tool_name: row.get(0)?,
+                entry_json: row.get(1)?,
+                registered_at_ms: row.get(2)?,
+                updated_at_ms: row.get(3)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<Vec<_>, _>>()?)
+    }
+}
+
+#[cfg(test)]
+#[path = "tests/registry.rs"]
+mod tests;
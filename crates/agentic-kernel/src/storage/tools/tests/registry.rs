@@ -0,0 +1,59 @@
+use super::StorageService;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[test]
+fn runtime_tools_upsert_reload_and_delete() {
+    let dir = make_temp_dir("agenticos_runtime_tools");
+    let db_path = dir.join("agenticos.db");
+
+    {
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        storage
+            .upsert_runtime_tool("weather_lookup", r#"{"v":1}"#)
+            .expect("insert tool");
+        storage
+            .upsert_runtime_tool("stock_quote", r#"{"v":1}"#)
+            .expect("insert second tool");
+        storage
+            .upsert_runtime_tool("weather_lookup", r#"{"v":2}"#)
+            .expect("update tool");
+    }
+
+    let mut storage = StorageService::open(&db_path).expect("reopen storage");
+    let tools = storage.load_runtime_tools().expect("load tools");
+    assert_eq!(tools.len(), 2);
+    let weather = tools
+        .iter()
+        .find(|tool| tool.tool_name == "weather_lookup")
+        .expect("weather tool persisted");
+    assert_eq!(weather.entry_json, r#"{"v":2}"#);
+    assert!(weather.updated_at_ms >= weather.registered_at_ms);
+
+    assert!(storage
+        .delete_runtime_tool("weather_lookup")
+        .expect("delete tool"));
+    assert!(!storage
+        .delete_runtime_tool("weather_lookup")
+        .expect("delete missing tool"));
+    let names: Vec<String> = storage
+        .load_runtime_tools()
+        .expect("reload tools")
+        .into_iter()
+        .map(|tool| tool.tool_name)
+        .collect();
+    assert_eq!(names, vec!["stock_quote".to_string()]);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn make_temp_dir(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}_{}_{}", std::process::id(), timestamp));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
@@ -58,24 +58,107 @@ impl StorageService {
             ORDER BY created_at_ms DESC, artifact_id DESC
             "#,
         )?;
-        let rows = statement.query_map(params![orchestration_id], |row| {
-            Ok(StoredWorkflowArtifact {
-                artifact_id: row.get(0)?,
-                orchestration_id: row.get(1)?,
-                producer_task_id: row.get(2)?,
-                producer_attempt: row.get(3)?,
-                kind: row.get(4)?,
-                label: row.get(5)?,
-                mime_type: row.get(6)?,
-                content_text: row.get(7)?,
-                preview: row.get(8)?,
-                bytes: row.get::<_, i64>(9)? as usize,
-                created_at_ms: row.get(10)?,
-            })
-        })?;
+        let rows = statement.query_map(params![orchestration_id], map_workflow_artifact_row)?;
         collect_rows(rows)
     }
 
+    pub(crate) fn load_recent_workflow_artifacts(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<StoredWorkflowArtifact>, StorageError> {
+        let limit = limit.min(i64::MAX as usize) as i64;
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT
+                artifact_id,
+                orchestration_id,
+                producer_task_id,
+                producer_attempt,
+                kind,
+                label,
+                mime_type,
+                content_text,
+                preview,
+                bytes,
+                created_at_ms
+            FROM workflow_artifacts
+            ORDER BY created_at_ms DESC, artifact_id DESC
+            LIMIT ?1
+            "#,
+        )?;
+        let rows = statement.query_map(params![limit], map_workflow_artifact_row)?;
+        collect_rows(rows)
+    }
+
+    pub(crate) fn workflow_artifact(
+        &self,
+        artifact_id: &str,
+    ) -> Result<Option<StoredWorkflowArtifact>, StorageError> {
+        let mut statement = self.connection.prepare(
+            r#"
+            SELECT
+                artifact_id,
+                orchestration_id,
+                producer_task_id,
+                producer_attempt,
+                kind,
+                label,
+                mime_type,
+                content_text,
+                preview,
+                bytes,
+                created_at_ms
+            FROM workflow_artifacts
+            WHERE artifact_id = ?1
+            LIMIT 1
+            "#,
+        )?;
+        let mut rows = statement.query_map(params![artifact_id], map_workflow_artifact_row)?;
+        rows.next().transpose().map_err(StorageError::from)
+    }
+
+    /// Persist the event payload that started a triggered workflow so root
+    /// tasks can reference it like any upstream artifact.
+    pub(crate) fn record_workflow_trigger_artifact(
+        &mut self,
+        orchestration_id: u64,
+        artifact_id: &str,
+        producer_task_id: &str,
+        mime_type: &str,
+        content_text: &str,
+        created_at_ms: i64,
+    ) -> Result<(), StorageError> {
+        self.connection.execute(
+            r#"
+            INSERT INTO workflow_artifacts (
+                artifact_id,
+                orchestration_id,
+                producer_task_id,
+                producer_attempt,
+                kind,
+                label,
+                mime_type,
+                content_text,
+                preview,
+                bytes,
+                created_at_ms
+            ) VALUES (?1, ?2, ?3, 1, 'trigger_event', 'workflow trigger', ?4, ?5, ?6, ?7, ?8)
+            ON CONFLICT(artifact_id) DO NOTHING
+            "#,
+            params![
+                artifact_id,
+                orchestration_id,
+                producer_task_id,
+                mime_type,
+                content_text,
+                preview_text(content_text),
+                content_text.len() as i64,
+                created_at_ms,
+            ],
+        )?;
+        Ok(())
+    }
+
     pub(super) fn load_workflow_artifact_inputs(
         &self,
         orchestration_id: u64,
@@ -268,6 +351,22 @@ fn looks_like_section_boundary(line: &str) -> bool {
     line.starts_with('[') || line.starts_with('#')
 }
 
+fn map_workflow_artifact_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<StoredWorkflowArtifact> {
+    Ok(StoredWorkflowArtifact {
+        artifact_id: row.get(0)?,
+        orchestration_id: row.get(1)?,
+        producer_task_id: row.get(2)?,
+        producer_attempt: row.get(3)?,
+        kind: row.get(4)?,
+        label: row.get(5)?,
+        mime_type: row.get(6)?,
+        content_text: row.get(7)?,
+        preview: row.get(8)?,
+        bytes: row.get::<_, i64>(9)? as usize,
+        created_at_ms: row.get(10)?,
+    })
+}
+
 fn collect_rows<T>(
     rows: rusqlite::MappedRows<'_, impl FnMut(&rusqlite::Row<'_>) -> rusqlite::Result<T>>,
 ) -> Result<Vec<T>, StorageError> {
@@ -12,6 +12,7 @@ pub(crate) struct NewScheduledJobRecord {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub backoff_ms: u64,
+    pub concurrency_policy: String,
     pub enabled: bool,
     pub state: String,
     pub next_run_at_ms: Option<i64>,
@@ -40,6 +41,7 @@ pub(crate) struct StoredScheduledJob {
     pub timeout_ms: u64,
     pub max_retries: u32,
     pub backoff_ms: u64,
+    pub concurrency_policy: String,
     pub enabled: bool,
     pub state: String,
     pub next_run_at_ms: Option<i64>,
@@ -69,6 +71,7 @@ pub(crate) struct StoredScheduledJobRun {
     pub orchestration_id: Option<u64>,
     pub deadline_at_ms: Option<i64>,
     pub error: Option<String>,
+    pub reason: Option<String>,
 }
 
 impl StorageService {
@@ -101,10 +104,11 @@ impl StorageService {
                 last_error,
                 consecutive_failures,
                 created_at_ms,
-                updated_at_ms
+                updated_at_ms,
+                concurrency_policy
             ) VALUES (
                 ?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18,
-                ?19, ?20, ?21, ?22, ?23
+                ?19, ?20, ?21, ?22, ?23, ?24
             )
             "#,
             params![
@@ -131,6 +135,7 @@ impl StorageService {
                 new_job.consecutive_failures as i64,
                 new_job.created_at_ms,
                 new_job.updated_at_ms,
+                new_job.concurrency_policy,
             ],
         )?;
 
@@ -169,7 +174,8 @@ impl StorageService {
                 last_error = ?21,
                 consecutive_failures = ?22,
                 created_at_ms = ?23,
-                updated_at_ms = ?24
+                updated_at_ms = ?24,
+                concurrency_policy = ?25
             WHERE job_id = ?1
             "#,
             params![
@@ -197,6 +203,7 @@ impl StorageService {
                 job.consecutive_failures as i64,
                 job.created_at_ms,
                 job.updated_at_ms,
+                job.concurrency_policy,
             ],
         )?;
         Ok(())
@@ -237,7 +244,8 @@ impl StorageService {
                 last_error,
                 consecutive_failures,
                 created_at_ms,
-                updated_at_ms
+                updated_at_ms,
+                concurrency_policy
             FROM scheduled_jobs
             ORDER BY created_at_ms ASC, job_id ASC
             "#,
@@ -268,7 +276,8 @@ impl StorageService {
                 completed_at_ms,
                 orchestration_id,
                 deadline_at_ms,
-                error
+                error,
+                reason
             FROM scheduled_job_runs
             WHERE job_id = ?1
             ORDER BY run_id DESC
@@ -299,8 +308,9 @@ impl StorageService {
                 completed_at_ms,
                 orchestration_id,
                 deadline_at_ms,
-                error
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+                error,
+                reason
+            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
             "#,
             params![
                 run.job_id,
@@ -312,6 +322,7 @@ impl StorageService {
                 run.orchestration_id,
                 run.deadline_at_ms,
                 run.error,
+                run.reason,
             ],
         )?;
         Ok(self.connection.last_insert_rowid() as u64)
@@ -333,7 +344,8 @@ impl StorageService {
                 completed_at_ms = ?7,
                 orchestration_id = ?8,
                 deadline_at_ms = ?9,
-                error = ?10
+                error = ?10,
+                reason = ?11
             WHERE run_id = ?1
             "#,
             params![
@@ -347,6 +359,7 @@ impl StorageService {
                 run.orchestration_id,
                 run.deadline_at_ms,
                 run.error,
+                run.reason,
             ],
         )?;
         Ok(())
@@ -384,7 +397,8 @@ impl StorageService {
                     last_error,
                     consecutive_failures,
                     created_at_ms,
-                    updated_at_ms
+                    updated_at_ms,
+                    concurrency_policy
                 FROM scheduled_jobs
                 WHERE job_id = ?1
                 "#,
@@ -421,6 +435,7 @@ fn map_scheduled_job_row(row: &rusqlite::Row<'_>) -> Result<StoredScheduledJob,
         consecutive_failures: row.get::<_, i64>(21)?.max(0) as u32,
         created_at_ms: row.get(22)?,
         updated_at_ms: row.get(23)?,
+        concurrency_policy: row.get(24)?,
     })
 }
 
@@ -438,6 +453,7 @@ fn map_scheduled_job_run_row(
         orchestration_id: row.get(7)?,
         deadline_at_ms: row.get(8)?,
         error: row.get(9)?,
+        reason: row.get(10)?,
     })
 }
 
@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::{Read, Write};
 use std::net::TcpListener;
-use std::sync::mpsc;
+use std::sync::{mpsc, Mutex};
 use std::thread;
 use std::time::Instant;
 
@@ -38,7 +38,7 @@ use crate::session::SessionRegistry;
 use crate::storage::StorageService;
 use crate::tool_registry::ToolRegistry;
 use crate::tools::invocation::{ProcessPermissionPolicy, ToolCaller};
-use crate::tools::SysCallOutcome;
+use crate::tools::{SysCallOutcome, SyscallRateMap};
 use crate::transport::Client;
 
 use super::helpers::{create_temp_dir, remove_temp_dir};
@@ -240,6 +240,7 @@ pub struct KernelE2eHarness {
     result_tx: mpsc::Sender<InferenceResult>,
     result_rx: mpsc::Receiver<InferenceResult>,
     syscall_cmd_tx: mpsc::Sender<SyscallCmd>,
+    syscall_rates: Mutex<SyscallRateMap>,
     syscall_cmd_rx: mpsc::Receiver<SyscallCmd>,
     syscall_result_tx: mpsc::Sender<SyscallCompletion>,
     syscall_result_rx: mpsc::Receiver<SyscallCompletion>,
@@ -288,6 +289,7 @@ impl KernelE2eHarness {
             result_tx,
             result_rx,
             syscall_cmd_tx,
+            syscall_rates: Mutex::new(SyscallRateMap::new()),
             syscall_cmd_rx,
             syscall_result_tx,
             syscall_result_rx,
@@ -443,6 +445,7 @@ impl KernelE2eHarness {
                 finished,
                 finish_reason,
                 accounting_event: None,
+                compaction: None,
             })
             .map_err(|err| err.to_string())
     }
@@ -478,6 +481,7 @@ impl KernelE2eHarness {
             &mut self.orchestrator,
             &self.result_rx,
             &self.syscall_cmd_tx,
+            &self.syscall_rates,
             &mut self.session_registry,
             &mut self.storage,
             &mut self.turn_assembly,
@@ -540,6 +544,7 @@ impl KernelE2eHarness {
                     warnings,
                     error_kind,
                     effects,
+                    cache_hit: false,
                 },
             })
             .map_err(|err| err.to_string())
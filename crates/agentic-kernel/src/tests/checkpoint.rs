@@ -27,6 +27,10 @@ fn make_test_snapshot() -> KernelSnapshot {
                 context_policy: ContextPolicy::from_kernel_defaults(),
                 context_state: ContextState::default(),
                 pending_human_request: None,
+                session_id: Some("sess-1".to_string()),
+                runtime_id: Some("rt-llama".to_string()),
+                lifecycle_policy: ProcessLifecyclePolicy::Interactive,
+                resident_slot_snapshot_path: Some(PathBuf::from("pid_1_slot_0_3.swap")),
             },
             ProcessSnapshot {
                 pid: 2,
@@ -39,6 +43,10 @@ fn make_test_snapshot() -> KernelSnapshot {
                 context_policy: ContextPolicy::from_kernel_defaults(),
                 context_state: ContextState::default(),
                 pending_human_request: None,
+                session_id: None,
+                runtime_id: None,
+                lifecycle_policy: ProcessLifecyclePolicy::Ephemeral,
+                resident_slot_snapshot_path: None,
             },
         ],
         scheduler: SchedulerStateSnapshot {
@@ -89,6 +97,91 @@ fn serialization_roundtrip() {
     assert_eq!(restored.metrics.total_commands, 42);
     assert_eq!(restored.memory.total_blocks, 256);
     assert_eq!(restored.generation.as_ref().unwrap().temperature, 0.7);
+    assert_eq!(restored.processes[0].session_id.as_deref(), Some("sess-1"));
+    assert_eq!(
+        restored.processes[0].resident_slot_snapshot_path,
+        Some(PathBuf::from("pid_1_slot_0_3.swap"))
+    );
+}
+
+#[test]
+fn generation_deltas_replace_and_remove_processes_in_order() {
+    let base = make_test_snapshot();
+    let mut running = base.processes[0].clone();
+    running.state = "WaitingForInput".to_string();
+    let mut scheduling = base.scheduler.entries[0].clone();
+    scheduling.priority = "low".to_string();
+    let delta = |delta_id: i64, pid: u64, kind: &str, process: Option<String>| {
+        crate::storage::StoredCheckpointDelta {
+            delta_id,
+            pid,
+            delta_kind: kind.to_string(),
+            process_json: process,
+            scheduler_json: (kind == "upsert" && pid == 1)
+                .then(|| serde_json::to_string(&scheduling).expect("serialize entry")),
+            recorded_at_ms: delta_id,
+        }
+    };
+    let loaded = crate::storage::LoadedCheckpointGeneration {
+        summary: crate::storage::StoredCheckpointGeneration {
+            generation: 4,
+            boot_id: Some(1),
+            created_at_ms: 0,
+            reason: "interval".to_string(),
+            kernel_version: "0.5.0".to_string(),
+            process_count: 2,
+            delta_count: 2,
+            last_delta_at_ms: Some(2),
+        },
+        snapshot_json: serde_json::to_string(&base).expect("serialize base"),
+        deltas: vec![
+            delta(
+                1,
+                1,
+                "upsert",
+                Some(serde_json::to_string(&running).expect("serialize process")),
+            ),
+            delta(2, 2, "remove", None),
+        ],
+    };
+
+    let snapshot = materialize_generation(&loaded).expect("materialize");
+    assert_eq!(snapshot.processes.len(), 1);
+    assert_eq!(snapshot.processes[0].pid, 1);
+    assert_eq!(snapshot.processes[0].state, "WaitingForInput");
+    assert_eq!(snapshot.scheduler.entries.len(), 1);
+    assert_eq!(snapshot.scheduler.entries[0].priority, "low");
+
+    let mut corrupt = loaded.clone();
+    corrupt.deltas = vec![delta(3, 1, "rename", None)];
+    assert!(materialize_generation(&corrupt)
+        .expect_err("unknown delta kind")
+        .contains("unknown kind"));
+}
+
+#[test]
+fn checkpoints_without_session_bindings_still_load() {
+    let mut value = serde_json::to_value(make_test_snapshot()).expect("serialize");
+    for process in value["processes"].as_array_mut().expect("processes") {
+        let process = process.as_object_mut().expect("process object");
+        for field in [
+            "session_id",
+            "runtime_id",
+            "lifecycle_policy",
+            "resident_slot_snapshot_path",
+        ] {
+            process.remove(field);
+        }
+    }
+
+    let restored: KernelSnapshot = serde_json::from_value(value).expect("deserialize");
+    assert_eq!(restored.processes[0].session_id, None);
+    assert_eq!(restored.processes[0].runtime_id, None);
+    assert_eq!(
+        restored.processes[1].lifecycle_policy,
+        ProcessLifecyclePolicy::Interactive
+    );
+    assert_eq!(restored.processes[0].resident_slot_snapshot_path, None);
 }
 
 #[test]
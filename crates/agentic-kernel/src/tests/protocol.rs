@@ -100,6 +100,23 @@ fn parse_checkpoint_opcodes() {
 
     let rs = CommandHeader::parse("RESTORE 1 0").expect("RESTORE should parse");
     assert!(matches!(rs.opcode, OpCode::Restore));
+
+    let ls = CommandHeader::parse("LIST_CHECKPOINTS 1 0").expect("LIST_CHECKPOINTS should parse");
+    assert!(matches!(ls.opcode, OpCode::ListCheckpoints));
+}
+
+#[test]
+fn parse_context_control_opcodes() {
+    for (raw, expected) in [
+        ("SET_CONTEXT_POLICY 1 32", OpCode::SetContextPolicy),
+        ("COMPACT_NOW 1 9", OpCode::CompactNow),
+        ("PIN_SEGMENT 1 20", OpCode::PinSegment),
+        ("DROP_SEGMENT 1 20", OpCode::DropSegment),
+    ] {
+        let header = CommandHeader::parse(raw).expect("context control opcode should parse");
+        assert_eq!(header.opcode, expected);
+        assert_eq!(raw.split_whitespace().next(), Some(header.opcode.as_str()));
+    }
 }
 
 #[test]
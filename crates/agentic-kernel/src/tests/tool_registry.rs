@@ -29,8 +29,12 @@ fn builtins_are_registered_and_sorted() {
             "get_time",
             "http_get_json",
             "inspect_document",
+            "kb_search",
             "list_files",
             "list_tree",
+            "memory_forget",
+            "memory_recall",
+            "memory_remember",
             "mkdir",
             "path_info",
             "python",
@@ -125,6 +129,53 @@ fn rejects_runtime_host_backend_registration() {
         .contains("not supported for dynamic execution yet"));
 }
 
+#[test]
+fn rejects_manifest_host_backend_but_accepts_manifest_wasm() {
+    let mut registry = ToolRegistry::new();
+    let descriptor = |name: &str, backend_kind: ToolBackendKind| ToolDescriptor {
+        name: name.to_string(),
+        aliases: vec![],
+        description: "manifest tool".to_string(),
+        input_schema: json!({"type": "object"}),
+        input_example: None,
+        output_schema: json!({"type": "object"}),
+        allowed_callers: vec![ToolCaller::AgentText],
+        backend_kind,
+        capabilities: vec![],
+        dangerous: false,
+        enabled: true,
+        default_allowlisted: true,
+        approval_required: false,
+        interop: None,
+        source: ToolSource::Manifest,
+    };
+
+    let host = registry.register(ToolRegistryEntry {
+        descriptor: descriptor("manifest_host_tool", ToolBackendKind::Host),
+        backend: ToolBackendConfig::Host {
+            executor: HostExecutor::Dynamic("python".to_string()),
+        },
+    });
+    assert!(host
+        .unwrap_err()
+        .contains("host backends are reserved to built-in tools"));
+
+    registry
+        .register(ToolRegistryEntry {
+            descriptor: descriptor("manifest_wasm_tool", ToolBackendKind::Wasm),
+            backend: ToolBackendConfig::Wasm {
+                module: "tool.wasm".to_string(),
+                export: "run".to_string(),
+            },
+        })
+        .expect("manifest wasm tool registers");
+    let events = registry.take_lifecycle_events();
+    assert!(matches!(
+        events.as_slice(),
+        [agentic_control_models::KernelEvent::ToolRegistryChanged { source, .. }] if source == "manifest"
+    ));
+}
+
 #[test]
 fn rejects_runtime_wasm_backend_registration() {
     let mut registry = ToolRegistry::new();
@@ -157,3 +208,61 @@ fn rejects_runtime_wasm_backend_registration() {
         .unwrap_err()
         .contains("not supported for dynamic execution yet"));
 }
+
+#[test]
+fn runtime_registration_changes_are_reported_once() {
+    let mut registry = ToolRegistry::with_builtins();
+    assert!(registry.take_lifecycle_events().is_empty());
+
+    registry
+        .register(ToolRegistryEntry {
+            descriptor: ToolDescriptor {
+                name: "runtime_echo".to_string(),
+                aliases: vec![],
+                description: "echo".to_string(),
+                input_schema: json!({"type": "object"}),
+                input_example: None,
+                output_schema: json!({"type": "object"}),
+                allowed_callers: vec![ToolCaller::AgentText],
+                backend_kind: ToolBackendKind::RemoteHttp,
+                capabilities: vec![],
+                dangerous: false,
+                enabled: true,
+                default_allowlisted: true,
+                approval_required: false,
+                interop: None,
+                source: ToolSource::Runtime,
+            },
+            backend: ToolBackendConfig::RemoteHttp {
+                url: "http://127.0.0.1:8080/tool".to_string(),
+                method: "POST".to_string(),
+                timeout_ms: 1000,
+                headers: HashMap::new(),
+            },
+        })
+        .expect("register runtime tool");
+    registry
+        .unregister("runtime_echo")
+        .expect("unregister runtime tool");
+
+    let changes: Vec<String> = registry
+        .take_lifecycle_events()
+        .into_iter()
+        .filter_map(|event| match event {
+            agentic_control_models::KernelEvent::ToolRegistryChanged {
+                tool_name,
+                change,
+                source,
+            } => Some(format!("{tool_name}:{change}:{source}")),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        vec![
+            "runtime_echo:registered:runtime",
+            "runtime_echo:unregistered:runtime"
+        ]
+    );
+    assert!(registry.take_lifecycle_events().is_empty());
+}
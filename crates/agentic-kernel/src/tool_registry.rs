@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use agentic_control_models::KernelEvent;
 use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
@@ -12,6 +13,7 @@ pub enum ToolBackendKind {
     Host,
     Wasm,
     RemoteHttp,
+    Command,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -19,6 +21,18 @@ pub enum ToolBackendKind {
 pub enum ToolSource {
     BuiltIn,
     Runtime,
+    /// Declared by a manifest file in `tools.manifest_dir` (`tools.d/`).
+    Manifest,
+}
+
+impl ToolSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::BuiltIn => "built_in",
+            Self::Runtime => "runtime",
+            Self::Manifest => "manifest",
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -74,6 +88,45 @@ pub enum ToolBackendConfig {
         timeout_ms: u64,
         headers: HashMap<String, String>,
     },
+    /// Local CLI run like `exec_command`: `args` is an argv template whose
+    /// `{field}` placeholders are filled from the validated tool input.
+    /// Always registered as dangerous, with the `exec` capability and off the
+    /// default allowlist.
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Workspace-relative working directory; defaults to the process grant root.
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        /// Input fields holding workspace paths, checked against the process
+        /// path grants and passed to the program as absolute paths.
+        #[serde(default)]
+        path_fields: Vec<String>,
+        /// Path fields the program writes to: checked against the write
+        /// grants instead of the read ones.
+        #[serde(default)]
+        write_path_fields: Vec<String>,
+        #[serde(default = "default_success_exit_codes")]
+        success_exit_codes: Vec<i32>,
+        #[serde(default)]
+        output: CommandOutputFormat,
+    },
+}
+
+/// How the stdout of a `Command` backend becomes the tool output.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandOutputFormat {
+    /// `{"output": stdout, "stderr": ..., "exit_code": ..., "truncated": ...}`.
+    #[default]
+    Text,
+    /// Stdout parsed as one JSON document.
+    Json,
+    /// Every non-empty stdout line parsed as JSON, collected in an array.
+    JsonLines,
 }
 
 impl ToolBackendConfig {
@@ -82,6 +135,7 @@ impl ToolBackendConfig {
             Self::Host { .. } => ToolBackendKind::Host,
             Self::Wasm { .. } => ToolBackendKind::Wasm,
             Self::RemoteHttp { .. } => ToolBackendKind::RemoteHttp,
+            Self::Command { .. } => ToolBackendKind::Command,
         }
     }
 }
@@ -152,6 +206,7 @@ pub struct ToolRegistryEntry {
 pub struct ToolRegistry {
     entries: HashMap<String, ToolRegistryEntry>,
     aliases: HashMap<String, String>,
+    lifecycle_events: Vec<KernelEvent>,
 }
 
 impl ToolRegistry {
@@ -199,6 +254,17 @@ impl ToolRegistry {
 
         let mut stored = entry;
         stored.descriptor.name = canonical_name.clone();
+        if matches!(stored.backend, ToolBackendConfig::Command { .. }) {
+            crate::tools::command_tools::harden_command_descriptor(&mut stored.descriptor);
+        }
+        if stored.descriptor.source != ToolSource::BuiltIn {
+            self.lifecycle_events
+                .push(KernelEvent::ToolRegistryChanged {
+                    tool_name: canonical_name.clone(),
+                    change: "registered".to_string(),
+                    source: stored.descriptor.source.as_str().to_string(),
+                });
+        }
         self.entries.insert(canonical_name.clone(), stored);
         for alias in normalized_aliases {
             self.aliases.insert(alias, canonical_name.clone());
@@ -220,9 +286,21 @@ impl ToolRegistry {
             ));
         }
         self.aliases.retain(|_, target| target != &canonical);
-        self.entries
+        let removed = self
+            .entries
             .remove(&canonical)
-            .ok_or_else(|| format!("Tool '{}' is not registered.", name))
+            .ok_or_else(|| format!("Tool '{}' is not registered.", name))?;
+        self.lifecycle_events
+            .push(KernelEvent::ToolRegistryChanged {
+                tool_name: canonical,
+                change: "unregistered".to_string(),
+                source: removed.descriptor.source.as_str().to_string(),
+            });
+        Ok(removed)
+    }
+
+    pub(crate) fn take_lifecycle_events(&mut self) -> Vec<KernelEvent> {
+        std::mem::take(&mut self.lifecycle_events)
     }
 
     pub fn get(&self, name: &str) -> Option<&ToolRegistryEntry> {
@@ -284,7 +362,10 @@ fn validate_entry(entry: &ToolRegistryEntry) -> Result<(), String> {
     }
 
     if descriptor.source == ToolSource::Runtime
-        && !matches!(entry.backend, ToolBackendConfig::RemoteHttp { .. })
+        && !matches!(
+            entry.backend,
+            ToolBackendConfig::RemoteHttp { .. } | ToolBackendConfig::Command { .. }
+        )
     {
         return Err(format!(
             "Tool '{}' is runtime-registered but backend '{:?}' is not supported for dynamic execution yet.",
@@ -293,6 +374,15 @@ fn validate_entry(entry: &ToolRegistryEntry) -> Result<(), String> {
         ));
     }
 
+    if descriptor.source == ToolSource::Manifest
+        && matches!(entry.backend, ToolBackendConfig::Host { .. })
+    {
+        return Err(format!(
+            "Tool '{}' comes from a manifest but host backends are reserved to built-in tools.",
+            descriptor.name
+        ));
+    }
+
     match &entry.backend {
         ToolBackendConfig::Host { executor } => {
             normalize_tool_name(executor.as_str()).map_err(|err| {
@@ -355,15 +445,26 @@ fn validate_entry(entry: &ToolRegistryEntry) -> Result<(), String> {
                 }
             }
         }
+        ToolBackendConfig::Command { .. } => {
+            crate::tools::command_tools::validate_command_backend(
+                &descriptor.name,
+                &entry.backend,
+                &descriptor.input_schema,
+            )?;
+        }
     }
 
     Ok(())
 }
 
-fn default_allowed_callers() -> Vec<ToolCaller> {
+pub(crate) fn default_allowed_callers() -> Vec<ToolCaller> {
     vec![ToolCaller::AgentText, ToolCaller::AgentSupervisor]
 }
 
+pub(crate) fn default_success_exit_codes() -> Vec<i32> {
+    vec![0]
+}
+
 fn default_default_allowlisted() -> bool {
     true
 }
@@ -20,6 +20,7 @@ struct AuditLogLine<'a> {
     tool_name: Option<&'a str>,
     success: bool,
     kill: bool,
+    cache_hit: bool,
     duration_ms: u128,
     cmd: &'a str,
     detail: &'a str,
@@ -36,6 +37,7 @@ pub(crate) struct ToolAuditRecord<'a> {
     pub(crate) context: &'a ToolContext,
     pub(crate) tool_call_id: Option<&'a str>,
     pub(crate) tool_name: Option<&'a str>,
+    pub(crate) cache_hit: bool,
 }
 
 pub(crate) fn append_audit_log(record: ToolAuditRecord<'_>) {
@@ -61,13 +63,14 @@ pub(crate) fn append_audit_log(record: ToolAuditRecord<'_>) {
         tool_name: record.tool_name,
         success: record.success,
         kill: record.should_kill,
+        cache_hit: record.cache_hit,
         duration_ms: record.duration_ms,
         cmd: record.command,
         detail: record.detail,
     })
     .unwrap_or_else(|_| {
         format!(
-            "{{\"format\":\"jsonl-v1\",\"ts_ms\":{},\"pid\":{},\"mode\":\"{:?}\",\"caller\":{:?},\"transport\":{:?},\"tool_call_id\":{:?},\"tool_name\":{:?},\"success\":{},\"kill\":{},\"duration_ms\":{},\"cmd\":{:?},\"detail\":{:?}}}",
+            "{{\"format\":\"jsonl-v1\",\"ts_ms\":{},\"pid\":{},\"mode\":\"{:?}\",\"caller\":{:?},\"transport\":{:?},\"tool_call_id\":{:?},\"tool_name\":{:?},\"success\":{},\"kill\":{},\"cache_hit\":{},\"duration_ms\":{},\"cmd\":{:?},\"detail\":{:?}}}",
             ts,
             record.pid,
             record.mode,
@@ -77,6 +80,7 @@ pub(crate) fn append_audit_log(record: ToolAuditRecord<'_>) {
             record.tool_name,
             record.success,
             record.should_kill,
+            record.cache_hit,
             record.duration_ms,
             record.command,
             record.detail
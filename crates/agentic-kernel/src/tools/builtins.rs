@@ -39,6 +39,9 @@ pub(crate) fn host_builtin_registrations() -> Vec<HostBuiltinRegistration> {
         crate::tools::command_tools::exec_command_host_builtin_registration(),
         crate::tools::system_tools::get_time_host_builtin_registration(),
         crate::tools::human_tools::ask_human_host_builtin_registration(),
+        crate::tools::memory_tools::memory_remember_host_builtin_registration(),
+        crate::tools::memory_tools::memory_recall_host_builtin_registration(),
+        crate::tools::memory_tools::memory_forget_host_builtin_registration(),
         crate::tools::network_tools::http_get_json_host_builtin_registration(),
         crate::tools::network_tools::download_url_host_builtin_registration(),
         crate::tools::network_tools::web_fetch_host_builtin_registration(),
@@ -47,6 +50,7 @@ pub(crate) fn host_builtin_registrations() -> Vec<HostBuiltinRegistration> {
         crate::tools::workspace_tools::path_info_host_builtin_registration(),
         crate::tools::workspace_tools::find_files_host_builtin_registration(),
         crate::tools::workspace_tools::search_text_host_builtin_registration(),
+        crate::tools::knowledge_tools::kb_search_host_builtin_registration(),
         crate::tools::workspace_tools::read_file_range_host_builtin_registration(),
         crate::tools::workspace_tools::mkdir_host_builtin_registration(),
         crate::tools::workspace_edit_tools::append_file_host_builtin_registration(),
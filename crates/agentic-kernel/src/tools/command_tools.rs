@@ -5,17 +5,26 @@ use agentic_kernel_macros::agentic_tool;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use serde_json::{json, Map, Value};
+
 use crate::config::kernel_config;
+use crate::tool_registry::{CommandOutputFormat, ToolBackendConfig, ToolDescriptor};
 
+use super::api::{Tool, ToolResult};
 use super::error::ToolError;
 use super::host_exec::run_with_timeout;
-use super::invocation::ToolContext;
+use super::invocation::{ToolContext, ToolInvocation};
+use super::path_guard::{resolve_safe_read_path_for_context, resolve_safe_write_path_for_context};
 use super::workspace_tools::resolve_search_root;
 
 const INTERACTIVE_PROGRAMS: &[&str] = &[
     "nano", "vim", "vi", "view", "less", "more", "top", "htop", "tmux", "screen",
 ];
 const SHELL_PROGRAMS: &[&str] = &["sh", "bash", "zsh", "fish"];
+/// Programs that would run an arbitrary command line out of a template.
+const WRAPPER_PROGRAMS: &[&str] = &["env", "sudo", "nohup", "setsid", "xargs", "timeout"];
+/// Upper bound on the stdout parsed by `json` / `json_lines` command backends.
+const MAX_STRUCTURED_STDOUT_BYTES: usize = 1024 * 1024;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -239,6 +248,430 @@ fn render_command(program: &str, args: &[String]) -> String {
     }
 }
 
+/// A registered tool backed by a local CLI (`ToolBackendConfig::Command`).
+///
+/// The argv template is filled from the validated input and the program runs
+/// through the same scoped cwd and timeout wrapper as `exec_command`.
+pub(crate) struct CommandTemplateTool {
+    pub name: String,
+    pub backend: ToolBackendConfig,
+}
+
+impl Tool for CommandTemplateTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn execute(
+        &self,
+        invocation: &ToolInvocation,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
+        let ToolBackendConfig::Command {
+            program,
+            args,
+            cwd,
+            timeout_ms,
+            path_fields,
+            write_path_fields,
+            success_exit_codes,
+            output,
+        } = &self.backend
+        else {
+            return Err(ToolError::Internal(format!(
+                "Wrong backend for CommandTemplateTool: {:?}",
+                self.backend
+            )));
+        };
+        let tool_name = self.name.as_str();
+        let input = invocation.input.as_object().ok_or_else(|| {
+            ToolError::InvalidInput(tool_name.into(), "input must be a JSON object".into())
+        })?;
+
+        let path_fields = PathFields {
+            read: path_fields,
+            write: write_path_fields,
+        };
+        let argv = render_argv_template(tool_name, args, input, &path_fields, context)?;
+        let cwd_root = resolve_search_root(tool_name, cwd.as_deref(), context)?;
+        if !cwd_root.absolute.is_dir() {
+            return Err(ToolError::InvalidInput(
+                tool_name.into(),
+                format!("cwd '{}' is not a directory", cwd_root.display),
+            ));
+        }
+
+        let timeout_ms = timeout_ms.unwrap_or_else(default_timeout_ms).max(1);
+        let start = Instant::now();
+        let result = run_with_timeout(
+            &cwd_root.absolute,
+            program,
+            &argv,
+            timeout_ms.div_ceil(1000),
+        )
+        .map_err(|err| classify_command_failure(tool_name, &err, timeout_ms))?;
+        let duration_ms = start.elapsed().as_millis() as u64;
+        if matches!(result.status.code(), Some(124 | 137)) {
+            return Err(ToolError::Timeout(tool_name.into(), timeout_ms));
+        }
+
+        let exit_code = result.status.code().unwrap_or(-1);
+        let stdout = String::from_utf8_lossy(&result.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&result.stderr).into_owned();
+        let (stderr, _) = truncate_stream(&stderr);
+        if !success_exit_codes.contains(&exit_code) {
+            let (stdout, _) = truncate_stream(&stdout);
+            return Err(ToolError::ExecutionFailed(
+                tool_name.into(),
+                render_command_output(
+                    program,
+                    &argv,
+                    exit_code,
+                    duration_ms,
+                    &stdout,
+                    &stderr,
+                    false,
+                ),
+            ));
+        }
+
+        match output {
+            CommandOutputFormat::Text => {
+                let (stdout, truncated) = truncate_stream(&stdout);
+                Ok(ToolResult::json_with_text(
+                    json!({
+                        "output": stdout,
+                        "stderr": stderr,
+                        "exit_code": exit_code,
+                        "truncated": truncated,
+                    }),
+                    stdout.clone(),
+                ))
+            }
+            CommandOutputFormat::Json | CommandOutputFormat::JsonLines => {
+                if stdout.len() > MAX_STRUCTURED_STDOUT_BYTES {
+                    return Err(ToolError::ExecutionFailed(
+                        tool_name.into(),
+                        format!(
+                            "stdout exceeds the {} byte limit for JSON output",
+                            MAX_STRUCTURED_STDOUT_BYTES
+                        ),
+                    ));
+                }
+                let parsed = parse_structured_stdout(*output, &stdout).map_err(|err| {
+                    ToolError::ExecutionFailed(
+                        tool_name.into(),
+                        format!("stdout is not valid JSON: {err}"),
+                    )
+                })?;
+                let (display, _) = truncate_stream(&stdout);
+                Ok(ToolResult::json_with_text(parsed, display))
+            }
+        }
+    }
+}
+
+fn parse_structured_stdout(
+    format: CommandOutputFormat,
+    stdout: &str,
+) -> Result<Value, serde_json::Error> {
+    if format == CommandOutputFormat::JsonLines {
+        return stdout
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Value>, _>>()
+            .map(Value::Array);
+    }
+    if stdout.trim().is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_str(stdout.trim())
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArgPart {
+    Literal(String),
+    Field(String),
+}
+
+/// Split one argv template entry into literals and `{field}` placeholders;
+/// `{{` and `}}` stand for literal braces.
+fn parse_arg_template(arg: &str) -> Result<Vec<ArgPart>, String> {
+    let mut parts = Vec::new();
+    let mut literal = String::new();
+    let mut chars = arg.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(ch) if ch.is_ascii_alphanumeric() || ch == '_' => field.push(ch),
+                        Some(ch) => {
+                            return Err(format!(
+                                "invalid character '{ch}' in placeholder of argument '{arg}'"
+                            ))
+                        }
+                        None => return Err(format!("unclosed placeholder in argument '{arg}'")),
+                    }
+                }
+                if field.is_empty() {
+                    return Err(format!("empty placeholder in argument '{arg}'"));
+                }
+                if !literal.is_empty() {
+                    parts.push(ArgPart::Literal(std::mem::take(&mut literal)));
+                }
+                parts.push(ArgPart::Field(field));
+            }
+            '}' => return Err(format!("unmatched '}}' in argument '{arg}'")),
+            ch => literal.push(ch),
+        }
+    }
+    if !literal.is_empty() || parts.is_empty() {
+        parts.push(ArgPart::Literal(literal));
+    }
+    Ok(parts)
+}
+
+/// A command backend runs a local program with the process grants, whatever
+/// its manifest claims: it is always dangerous, carries the `exec` capability
+/// (so replay, MCP export and the result cache treat it like `exec_command`)
+/// and stays off the default allowlist until a process is granted it.
+pub(crate) fn harden_command_descriptor(descriptor: &mut ToolDescriptor) {
+    descriptor.dangerous = true;
+    descriptor.default_allowlisted = false;
+    if !descriptor
+        .capabilities
+        .iter()
+        .any(|capability| capability == "exec")
+    {
+        descriptor.capabilities.push("exec".to_string());
+    }
+}
+
+/// Registration-time checks of a `Command` backend.
+pub(crate) fn validate_command_backend(
+    tool_name: &str,
+    backend: &ToolBackendConfig,
+    input_schema: &Value,
+) -> Result<(), String> {
+    let ToolBackendConfig::Command {
+        program,
+        args,
+        timeout_ms,
+        path_fields,
+        write_path_fields,
+        success_exit_codes,
+        ..
+    } = backend
+    else {
+        return Ok(());
+    };
+    let program = program.trim();
+    if program.is_empty() {
+        return Err(format!(
+            "Tool '{tool_name}' command backend must define a program."
+        ));
+    }
+    if program.contains(['{', '}']) {
+        return Err(format!(
+            "Tool '{tool_name}' command program cannot contain placeholders."
+        ));
+    }
+    if SHELL_PROGRAMS
+        .iter()
+        .chain(INTERACTIVE_PROGRAMS)
+        .chain(WRAPPER_PROGRAMS)
+        .any(|candidate| candidate.eq_ignore_ascii_case(basename(program)))
+    {
+        return Err(format!(
+            "Tool '{tool_name}' command backend cannot wrap shell, wrapper or interactive program '{program}'."
+        ));
+    }
+    if *timeout_ms == Some(0) {
+        return Err(format!(
+            "Tool '{tool_name}' command backend timeout must be > 0."
+        ));
+    }
+    if success_exit_codes.is_empty() {
+        return Err(format!(
+            "Tool '{tool_name}' command backend needs at least one success exit code."
+        ));
+    }
+
+    let properties = input_schema.get("properties").and_then(Value::as_object);
+    let declared = |field: &str| properties.is_some_and(|props| props.contains_key(field));
+    for arg in args {
+        let parts = parse_arg_template(arg).map_err(|err| format!("Tool '{tool_name}' {err}."))?;
+        for part in parts {
+            if let ArgPart::Field(field) = part {
+                if !declared(&field) {
+                    return Err(format!(
+                        "Tool '{tool_name}' argument placeholder '{{{field}}}' is not an input_schema property."
+                    ));
+                }
+            }
+        }
+    }
+    for field in path_fields.iter().chain(write_path_fields) {
+        if !declared(field) {
+            return Err(format!(
+                "Tool '{tool_name}' path field '{field}' is not an input_schema property."
+            ));
+        }
+    }
+    if let Some(field) = write_path_fields
+        .iter()
+        .find(|field| path_fields.contains(field))
+    {
+        return Err(format!(
+            "Tool '{tool_name}' path field '{field}' cannot be both a read and a write path."
+        ));
+    }
+    Ok(())
+}
+
+/// Input fields of a `Command` backend that hold workspace paths, by access mode.
+struct PathFields<'a> {
+    read: &'a [String],
+    write: &'a [String],
+}
+
+/// Fill the argv template from the tool input.
+///
+/// A placeholder alone in its argument expands to one argument per array item
+/// and drops the argument when the field is missing or null; embedded
+/// placeholders need scalar values. Values may not start with `-` (they would
+/// turn into flags) unless the template puts a literal `--` before them.
+fn render_argv_template(
+    tool_name: &str,
+    template: &[String],
+    input: &Map<String, Value>,
+    path_fields: &PathFields<'_>,
+    context: &ToolContext,
+) -> Result<Vec<String>, ToolError> {
+    let invalid = |detail: String| ToolError::InvalidInput(tool_name.into(), detail);
+    let mut argv = Vec::new();
+    let mut after_separator = false;
+
+    for arg in template {
+        let parts = parse_arg_template(arg).map_err(invalid)?;
+        if let [ArgPart::Field(field)] = parts.as_slice() {
+            let values = match input.get(field) {
+                None | Some(Value::Null) => continue,
+                Some(Value::Array(items)) => items.iter().collect::<Vec<_>>(),
+                Some(value) => vec![value],
+            };
+            for value in values {
+                let rendered =
+                    render_field_value(tool_name, field, value, path_fields, context, true)?;
+                ensure_not_flag(tool_name, field, &rendered, after_separator)?;
+                argv.push(rendered);
+            }
+            continue;
+        }
+
+        let mut rendered = String::new();
+        let mut skipped = false;
+        for (index, part) in parts.iter().enumerate() {
+            match part {
+                ArgPart::Literal(text) => rendered.push_str(text),
+                ArgPart::Field(field) => {
+                    let Some(value) = input.get(field).filter(|value| !value.is_null()) else {
+                        skipped = true;
+                        break;
+                    };
+                    let value =
+                        render_field_value(tool_name, field, value, path_fields, context, false)?;
+                    if index == 0 {
+                        ensure_not_flag(tool_name, field, &value, after_separator)?;
+                    }
+                    rendered.push_str(&value);
+                }
+            }
+        }
+        if skipped {
+            continue;
+        }
+        if rendered == "--" && parts.len() == 1 {
+            after_separator = true;
+        }
+        argv.push(rendered);
+    }
+    Ok(argv)
+}
+
+fn render_field_value(
+    tool_name: &str,
+    field: &str,
+    value: &Value,
+    path_fields: &PathFields<'_>,
+    context: &ToolContext,
+    standalone: bool,
+) -> Result<String, ToolError> {
+    let rendered = match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => number.to_string(),
+        Value::Bool(flag) => flag.to_string(),
+        Value::Object(_) if standalone => value.to_string(),
+        _ => {
+            return Err(ToolError::InvalidInput(
+                tool_name.into(),
+                format!("field '{field}' cannot be expanded inside this argument"),
+            ))
+        }
+    };
+    if rendered.contains('\0') {
+        return Err(ToolError::InvalidInput(
+            tool_name.into(),
+            format!("field '{field}' contains a NUL byte"),
+        ));
+    }
+    let resolve = if path_fields
+        .write
+        .iter()
+        .any(|path_field| path_field == field)
+    {
+        resolve_safe_write_path_for_context
+    } else if path_fields
+        .read
+        .iter()
+        .any(|path_field| path_field == field)
+    {
+        resolve_safe_read_path_for_context
+    } else {
+        return Ok(rendered);
+    };
+    let absolute = resolve(&rendered, context)
+        .map_err(|err| ToolError::PolicyDenied(tool_name.into(), err))?;
+    Ok(absolute.display().to_string())
+}
+
+fn ensure_not_flag(
+    tool_name: &str,
+    field: &str,
+    value: &str,
+    after_separator: bool,
+) -> Result<(), ToolError> {
+    if !after_separator && value.starts_with('-') {
+        return Err(ToolError::InvalidInput(
+            tool_name.into(),
+            format!("field '{field}' cannot start with '-'"),
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[path = "tests/command.rs"]
 mod tests;
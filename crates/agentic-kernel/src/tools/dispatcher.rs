@@ -1,7 +1,10 @@
-use crate::tool_registry::{HostExecutor, ToolBackendConfig, ToolRegistry};
+use crate::config::kernel_config;
+use crate::storage::current_timestamp_ms;
+use crate::tool_registry::{HostExecutor, ToolBackendConfig, ToolRegistry, ToolRegistryEntry};
 use crate::tools::api::{Tool, ToolResult};
 use crate::tools::error::ToolError;
 use crate::tools::invocation::{ToolContext, ToolInvocation};
+use crate::tools::result_cache::{cache_key, cache_policy_for, mutates_workspace, ToolResultCache};
 use crate::tools::schema::validate_value;
 use std::collections::HashMap;
 
@@ -22,44 +25,49 @@ impl ToolDispatcher {
         context: &ToolContext,
         registry: &ToolRegistry,
     ) -> Result<ToolResult, ToolError> {
-        let entry = registry
-            .resolve_invocation_name(&invocation.name)
-            .ok_or_else(|| ToolError::NotFound(invocation.name.clone()))?;
-
-        if !entry.descriptor.enabled {
-            return Err(ToolError::Disabled(invocation.name.clone()));
-        }
+        let entry = authorize_invocation(invocation, context, registry)?;
+        self.execute_entry(entry, invocation, context)
+    }
 
-        if !entry
-            .descriptor
-            .allowed_callers
-            .iter()
-            .any(|caller| caller == &context.caller)
-        {
-            return Err(ToolError::PolicyDenied(
-                invocation.name.clone(),
-                format!("caller '{}' is not allowed", context.caller),
-            ));
-        }
+    /// Like [`dispatch`](Self::dispatch), but serves cacheable tools from
+    /// `cache` once the invocation passed the policy checks. The flag is
+    /// `true` when the result is a cache hit.
+    pub(crate) fn dispatch_cached(
+        &self,
+        invocation: &ToolInvocation,
+        context: &ToolContext,
+        registry: &ToolRegistry,
+        cache: &mut ToolResultCache,
+    ) -> Result<(ToolResult, bool), ToolError> {
+        let entry = authorize_invocation(invocation, context, registry)?;
+        let config = &kernel_config().tools.result_cache;
+        let Some(policy) = cache_policy_for(entry, config) else {
+            let result = self.execute_entry(entry, invocation, context)?;
+            if mutates_workspace(entry) {
+                cache.invalidate_workspace();
+            }
+            return Ok((result, false));
+        };
 
-        if !context.permissions.allows_tool(&invocation.name) {
-            return Err(ToolError::PolicyDenied(
-                invocation.name.clone(),
-                format!(
-                    "tool '{}' is outside the allowlist for trust scope '{}'",
-                    invocation.name, context.permissions.trust_scope
-                ),
-            ));
+        let key = cache_key(&entry.descriptor.name, &invocation.input, &policy, context);
+        if let Some(key) = key.as_deref() {
+            if let Some(result) = cache.lookup(key, current_timestamp_ms()) {
+                return Ok((result, true));
+            }
         }
-
-        if let Err(detail) = validate_value(
-            &entry.descriptor.input_schema,
-            &invocation.input,
-            &format!("tool '{}'.input_schema", invocation.name),
-        ) {
-            return Err(ToolError::SchemaViolation(invocation.name.clone(), detail));
+        let result = self.execute_entry(entry, invocation, context)?;
+        if let Some(key) = key {
+            cache.store(key, &result, &policy, config, current_timestamp_ms());
         }
+        Ok((result, false))
+    }
 
+    fn execute_entry(
+        &self,
+        entry: &ToolRegistryEntry,
+        invocation: &ToolInvocation,
+        context: &ToolContext,
+    ) -> Result<ToolResult, ToolError> {
         // Dispatch based on backend configuration
         let result = match &entry.backend {
             ToolBackendConfig::Host { executor } => {
@@ -84,6 +92,13 @@ impl ToolDispatcher {
                 };
                 tool.execute(invocation, context)
             }
+            ToolBackendConfig::Command { .. } => {
+                let tool = crate::tools::command_tools::CommandTemplateTool {
+                    name: invocation.name.clone(),
+                    backend: entry.backend.clone(),
+                };
+                tool.execute(invocation, context)
+            }
         }?;
 
         if let Err(detail) = validate_value(
@@ -101,6 +116,54 @@ impl ToolDispatcher {
     }
 }
 
+/// Resolve `invocation` and check it against the registry entry, the caller
+/// and the process permissions, then validate its input schema.
+pub(crate) fn authorize_invocation<'a>(
+    invocation: &ToolInvocation,
+    context: &ToolContext,
+    registry: &'a ToolRegistry,
+) -> Result<&'a ToolRegistryEntry, ToolError> {
+    let entry = registry
+        .resolve_invocation_name(&invocation.name)
+        .ok_or_else(|| ToolError::NotFound(invocation.name.clone()))?;
+
+    if !entry.descriptor.enabled {
+        return Err(ToolError::Disabled(invocation.name.clone()));
+    }
+
+    if !entry
+        .descriptor
+        .allowed_callers
+        .iter()
+        .any(|caller| caller == &context.caller)
+    {
+        return Err(ToolError::PolicyDenied(
+            invocation.name.clone(),
+            format!("caller '{}' is not allowed", context.caller),
+        ));
+    }
+
+    if !context.permissions.allows_tool(&invocation.name) {
+        return Err(ToolError::PolicyDenied(
+            invocation.name.clone(),
+            format!(
+                "tool '{}' is outside the allowlist for trust scope '{}'",
+                invocation.name, context.permissions.trust_scope
+            ),
+        ));
+    }
+
+    if let Err(detail) = validate_value(
+        &entry.descriptor.input_schema,
+        &invocation.input,
+        &format!("tool '{}'.input_schema", invocation.name),
+    ) {
+        return Err(ToolError::SchemaViolation(invocation.name.clone(), detail));
+    }
+
+    Ok(entry)
+}
+
 #[cfg(test)]
 mod tests {
     use serde_json::json;
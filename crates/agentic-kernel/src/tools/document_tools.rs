@@ -26,7 +26,7 @@ struct InspectDocumentInput {
 
 #[derive(Debug, Clone, Copy, Serialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-enum DocumentKind {
+pub(crate) enum DocumentKind {
     Text,
     Json,
     Csv,
@@ -237,7 +237,10 @@ fn build_summary(
     )
 }
 
-fn detect_document_kind(path: &Path, preview_bytes: &[u8]) -> (DocumentKind, Option<String>) {
+pub(crate) fn detect_document_kind(
+    path: &Path,
+    preview_bytes: &[u8],
+) -> (DocumentKind, Option<String>) {
     if preview_bytes.starts_with(b"%PDF-") {
         return (DocumentKind::Pdf, Some("application/pdf".to_string()));
     }
@@ -2,6 +2,7 @@ use std::time::Instant;
 
 use crate::tool_registry::ToolRegistry;
 
+use super::api::ToolResult;
 use super::audit::{append_audit_log, ToolAuditRecord};
 use super::dispatcher::ToolDispatcher;
 use super::effects::{summarize_tool_effects, tool_error_kind};
@@ -24,11 +25,14 @@ pub struct GovernedToolResult {
     pub warnings: Vec<String>,
     pub error_kind: Option<String>,
     pub effects: Vec<serde_json::Value>,
+    /// The output was served from the tool result cache.
+    pub cache_hit: bool,
 }
 
 /// Execute a tool invocation through the full governance pipeline:
 ///   1. Rate-limit precheck
-///   2. Dispatch via `ToolDispatcher`
+///   2. Dispatch via `ToolDispatcher`, served from the result cache when the
+///      tool is idempotent or read-only
 ///   3. Rate-limit postcheck (burst detection)
 ///   4. Audit log
 ///
@@ -41,6 +45,40 @@ pub fn govern_tool_execution(
     registry: &ToolRegistry,
     pid: u64,
     rate_map: &mut SyscallRateMap,
+) -> GovernedToolResult {
+    govern_with(invocation, context, registry, pid, rate_map, |rate_map| {
+        ToolDispatcher::new().dispatch_cached(
+            invocation,
+            context,
+            registry,
+            &mut rate_map.result_cache,
+        )
+    })
+}
+
+/// Same pipeline for tools the kernel serves outside the dispatcher (the
+/// memory tools run on the event loop, next to storage): `execute` takes the
+/// place of step 2 and is never cached.
+pub(crate) fn govern_native_tool_execution(
+    invocation: &ToolInvocation,
+    context: &ToolContext,
+    registry: &ToolRegistry,
+    pid: u64,
+    rate_map: &mut SyscallRateMap,
+    execute: impl FnOnce() -> Result<ToolResult, ToolError>,
+) -> GovernedToolResult {
+    govern_with(invocation, context, registry, pid, rate_map, |_| {
+        execute().map(|result| (result, false))
+    })
+}
+
+fn govern_with(
+    invocation: &ToolInvocation,
+    context: &ToolContext,
+    registry: &ToolRegistry,
+    pid: u64,
+    rate_map: &mut SyscallRateMap,
+    execute: impl FnOnce(&mut SyscallRateMap) -> Result<(ToolResult, bool), ToolError>,
 ) -> GovernedToolResult {
     let cfg = syscall_config();
     let start = Instant::now();
@@ -58,6 +96,7 @@ pub fn govern_tool_execution(
             start,
             context,
             None,
+            false,
         );
         return GovernedToolResult {
             output: err.to_string(),
@@ -68,16 +107,18 @@ pub fn govern_tool_execution(
             warnings: Vec::new(),
             error_kind: Some(tool_error_kind(&err).to_string()),
             effects: Vec::new(),
+            cache_hit: false,
         };
     }
 
     // — Dispatch —
-    let dispatcher = ToolDispatcher::new();
-    let exec_result = dispatcher.dispatch(invocation, context, registry);
+    let exec_result = execute(rate_map);
 
+    let mut cache_hit = false;
     let (success, output, output_json, warnings, error_kind, effects, tool_name) = match exec_result
     {
-        Ok(result) => {
+        Ok((result, hit)) => {
+            cache_hit = hit;
             let rendered = if let Some(text) = result.display_text.clone() {
                 text
             } else {
@@ -123,6 +164,7 @@ pub fn govern_tool_execution(
         start,
         context,
         tool_name.as_deref(),
+        cache_hit,
     );
 
     GovernedToolResult {
@@ -134,6 +176,7 @@ pub fn govern_tool_execution(
         warnings,
         error_kind,
         effects,
+        cache_hit,
     }
 }
 
@@ -148,6 +191,7 @@ fn append_governed_audit(
     start: Instant,
     context: &ToolContext,
     tool_name: Option<&str>,
+    cache_hit: bool,
 ) {
     let command = format!(
         "TOOL:{} {}",
@@ -165,5 +209,6 @@ fn append_governed_audit(
         context,
         tool_call_id: invocation.call_id.as_deref(),
         tool_name,
+        cache_hit,
     });
 }
@@ -43,6 +43,7 @@ pub enum ProcessTrustScope {
     WorkflowSupervisor,
     Programmatic,
     ControlPlane,
+    McpExport,
 }
 
 impl ProcessTrustScope {
@@ -52,6 +53,7 @@ impl ProcessTrustScope {
             Self::WorkflowSupervisor => "workflow_supervisor",
             Self::Programmatic => "programmatic",
             Self::ControlPlane => "control_plane",
+            Self::McpExport => "mcp_export",
         }
     }
 }
@@ -191,6 +193,38 @@ impl ProcessPermissionPolicy {
         )
     }
 
+    /// Policy applied to external MCP clients calling tools exported by the
+    /// kernel: programmatic caller, no actions, restricted to `exposed_tools`.
+    pub fn mcp_export(registry: &ToolRegistry, exposed_tools: Vec<String>) -> Result<Self, String> {
+        let overrides = ProcessPermissionOverrides {
+            allowed_tools: Some(exposed_tools),
+            ..ProcessPermissionOverrides::default()
+        };
+        Self::build_for_caller(
+            registry,
+            &ToolCaller::Programmatic,
+            ProcessTrustScope::McpExport,
+            false,
+            Some(&overrides),
+        )
+    }
+
+    /// Policy for a scheduled `tool` job: programmatic caller, no actions,
+    /// only the scheduled tool allowed.
+    pub fn scheduled_job_tool(registry: &ToolRegistry, tool_name: &str) -> Result<Self, String> {
+        let overrides = ProcessPermissionOverrides {
+            allowed_tools: Some(vec![tool_name.to_string()]),
+            ..ProcessPermissionOverrides::default()
+        };
+        Self::build_for_caller(
+            registry,
+            &ToolCaller::Programmatic,
+            ProcessTrustScope::Programmatic,
+            false,
+            Some(&overrides),
+        )
+    }
+
     pub fn build_for_caller(
         registry: &ToolRegistry,
         caller: &ToolCaller,
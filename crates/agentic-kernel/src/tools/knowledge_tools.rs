@@ -0,0 +1,568 @@
+//! Workspace knowledge base behind `kb_search`.
+//!
+//! Files under the searched roots are split into overlapping line chunks and
+//! kept in a process-wide index on the syscall worker. Every search re-walks
+//! the roots (at most once per `refresh_interval_ms`): files whose mtime and
+//! size are unchanged keep their chunks, the others are re-hashed and only
+//! re-chunked when the content really changed. Hits are filtered through
+//! `path_guard`, so a process only sees chunks inside its own grants.
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Instant, SystemTime};
+
+use agentic_kernel_macros::agentic_tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::backend::embeddings::{embedding_provider_from_config, EmbeddingProvider};
+use crate::config::{kernel_config, KnowledgeBaseConfig};
+use crate::process::{normalized_terms, SegmentVectorIndex};
+
+use super::document_tools::{detect_document_kind, DocumentKind};
+use super::error::ToolError;
+use super::invocation::ToolContext;
+use super::path_guard::{
+    display_path, ensure_path_access, resolve_context_grant_roots, workspace_root, PathAccessIntent,
+};
+use super::workspace_tools::resolve_search_root;
+
+const MAX_KB_RESULTS_CAP: usize = 50;
+const EXCERPT_CHARS: usize = 240;
+const DETECT_PREFIX_BYTES: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub(crate) struct KnowledgeChunk {
+    pub chunk_id: String,
+    pub path: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub text: String,
+    terms: HashSet<String>,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedFile {
+    modified: Option<SystemTime>,
+    len: u64,
+    sha256: String,
+    chunks: Vec<KnowledgeChunk>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RefreshStats {
+    pub indexed: usize,
+    pub unchanged: usize,
+    pub removed: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct KnowledgeHit {
+    pub chunk: KnowledgeChunk,
+    pub score: f64,
+}
+
+pub(crate) struct KnowledgeSearch {
+    pub hits: Vec<KnowledgeHit>,
+    pub hybrid: bool,
+    pub indexed_chunks: usize,
+}
+
+pub(crate) struct KnowledgeBaseIndex {
+    config: KnowledgeBaseConfig,
+    files: BTreeMap<PathBuf, IndexedFile>,
+    refreshed_at: HashMap<PathBuf, Instant>,
+    embeddings: Option<Box<dyn EmbeddingProvider>>,
+    vectors: SegmentVectorIndex,
+}
+
+impl KnowledgeBaseIndex {
+    pub(crate) fn new(
+        config: KnowledgeBaseConfig,
+        embeddings: Option<Box<dyn EmbeddingProvider>>,
+    ) -> Self {
+        let vectors = SegmentVectorIndex::new(config.max_cached_vectors);
+        Self {
+            config,
+            files: BTreeMap::new(),
+            refreshed_at: HashMap::new(),
+            embeddings,
+            vectors,
+        }
+    }
+
+    fn from_kernel_config() -> Self {
+        let config = kernel_config();
+        let knowledge_base = config.tools.knowledge_base.clone();
+        let embeddings = if knowledge_base.embeddings {
+            embedding_provider_from_config(&config.context.embeddings)
+        } else {
+            None
+        };
+        Self::new(knowledge_base, embeddings)
+    }
+
+    /// Bring the chunks under `root` in line with the filesystem.
+    pub(crate) fn refresh(&mut self, root: &Path) -> RefreshStats {
+        let mut stats = RefreshStats::default();
+        let files = self.walk(root);
+        let seen = files.iter().cloned().collect::<HashSet<_>>();
+
+        let stale = self
+            .files
+            .keys()
+            .filter(|path| path.starts_with(root) && !seen.contains(*path))
+            .cloned()
+            .collect::<Vec<_>>();
+        for path in stale {
+            self.files.remove(&path);
+            stats.removed += 1;
+        }
+
+        for path in files {
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let modified = metadata.modified().ok();
+            if let Some(known) = self.files.get(&path) {
+                if known.modified == modified && known.len == metadata.len() {
+                    stats.unchanged += 1;
+                    continue;
+                }
+            }
+            // Skip oversized files before reading and hashing them.
+            if metadata.len() > self.config.max_file_bytes {
+                if self.files.remove(&path).is_some() {
+                    stats.removed += 1;
+                }
+                continue;
+            }
+            let Ok(bytes) = fs::read(&path) else {
+                continue;
+            };
+            let sha256 = format!("{:x}", Sha256::digest(&bytes));
+            if let Some(known) = self.files.get_mut(&path) {
+                if known.sha256 == sha256 {
+                    known.modified = modified;
+                    known.len = metadata.len();
+                    stats.unchanged += 1;
+                    continue;
+                }
+            }
+            match self.chunk_file(&path, &bytes) {
+                Some(chunks) => {
+                    self.files.insert(
+                        path,
+                        IndexedFile {
+                            modified,
+                            len: metadata.len(),
+                            sha256,
+                            chunks,
+                        },
+                    );
+                    stats.indexed += 1;
+                }
+                None => {
+                    if self.files.remove(&path).is_some() {
+                        stats.removed += 1;
+                    }
+                }
+            }
+        }
+        self.refreshed_at.insert(root.to_path_buf(), Instant::now());
+        stats
+    }
+
+    fn refresh_if_due(&mut self, root: &Path) {
+        let due = self.refreshed_at.get(root).is_none_or(|at| {
+            at.elapsed().as_millis() >= u128::from(self.config.refresh_interval_ms)
+        });
+        if due {
+            self.refresh(root);
+        }
+    }
+
+    /// Rank the chunks under `roots` against `query`; `visible` drops the
+    /// chunks the caller may not read.
+    pub(crate) fn search(
+        &mut self,
+        query: &str,
+        roots: &[PathBuf],
+        limit: usize,
+        visible: &dyn Fn(&Path) -> bool,
+    ) -> KnowledgeSearch {
+        for root in roots {
+            self.refresh_if_due(root);
+        }
+        let Self {
+            config,
+            files,
+            embeddings,
+            vectors,
+            ..
+        } = self;
+        let candidates = files
+            .iter()
+            .filter(|(path, _)| roots.iter().any(|root| path.starts_with(root)) && visible(path))
+            .flat_map(|(_, file)| file.chunks.iter())
+            .collect::<Vec<_>>();
+        let indexed_chunks = candidates.len();
+
+        let query_terms = normalized_terms(query)
+            .into_iter()
+            .collect::<HashSet<String>>();
+        let idf = query_terms
+            .iter()
+            .map(|term| {
+                let df = candidates
+                    .iter()
+                    .filter(|chunk| chunk.terms.contains(term))
+                    .count();
+                let idf = (1.0 + indexed_chunks as f64 / (df as f64 + 1.0)).ln();
+                (term.as_str(), idf)
+            })
+            .collect::<Vec<_>>();
+        let idf_total = idf.iter().map(|(_, weight)| weight).sum::<f64>();
+        let lexical = candidates
+            .iter()
+            .map(|chunk| {
+                if idf_total <= 0.0 {
+                    return 0.0;
+                }
+                idf.iter()
+                    .filter(|(term, _)| chunk.terms.contains(*term))
+                    .map(|(_, weight)| weight)
+                    .sum::<f64>()
+                    / idf_total
+            })
+            .collect::<Vec<_>>();
+
+        let similarity = embeddings.as_mut().and_then(|provider| {
+            vector_similarities(
+                provider.as_mut(),
+                vectors,
+                config.max_embeddings_per_search,
+                query,
+                &candidates,
+                &lexical,
+            )
+        });
+        let weight = config.vector_weight.clamp(0.0, 1.0);
+        let mut hits = candidates
+            .iter()
+            .enumerate()
+            .map(|(idx, chunk)| {
+                let score = match similarity.as_ref() {
+                    Some(similarity) => {
+                        (1.0 - weight) * lexical[idx]
+                            + weight * similarity.get(&idx).copied().unwrap_or(0.0).max(0.0)
+                    }
+                    None => lexical[idx],
+                };
+                KnowledgeHit {
+                    chunk: (*chunk).clone(),
+                    score,
+                }
+            })
+            .filter(|hit| hit.score > 0.0)
+            .collect::<Vec<_>>();
+        hits.sort_by(|left, right| {
+            right
+                .score
+                .total_cmp(&left.score)
+                .then_with(|| left.chunk.path.cmp(&right.chunk.path))
+                .then_with(|| left.chunk.start_line.cmp(&right.chunk.start_line))
+        });
+        hits.truncate(limit);
+
+        KnowledgeSearch {
+            hits,
+            hybrid: similarity.is_some(),
+            indexed_chunks,
+        }
+    }
+
+    fn walk(&self, root: &Path) -> Vec<PathBuf> {
+        if root.is_file() {
+            return vec![root.to_path_buf()];
+        }
+        let mut directories = vec![root.to_path_buf()];
+        let mut files = Vec::new();
+        while let Some(dir) = directories.pop() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                if file_type.is_dir() {
+                    let name = entry.file_name();
+                    if !self
+                        .config
+                        .skip_dirs
+                        .iter()
+                        .any(|skip| name.to_string_lossy() == skip.as_str())
+                    {
+                        directories.push(path);
+                    }
+                } else if file_type.is_file() {
+                    files.push(path);
+                    if files.len() >= self.config.max_files {
+                        files.sort();
+                        return files;
+                    }
+                }
+            }
+        }
+        files.sort();
+        files
+    }
+
+    /// Line chunks of an indexable file; `None` for binaries, PDFs, images
+    /// and files over `max_file_bytes`.
+    fn chunk_file(&self, path: &Path, bytes: &[u8]) -> Option<Vec<KnowledgeChunk>> {
+        if bytes.len() as u64 > self.config.max_file_bytes {
+            return None;
+        }
+        let prefix = &bytes[..bytes.len().min(DETECT_PREFIX_BYTES)];
+        let (kind, _) = detect_document_kind(path, prefix);
+        if !matches!(
+            kind,
+            DocumentKind::Text | DocumentKind::Json | DocumentKind::Csv
+        ) {
+            return None;
+        }
+        let content = std::str::from_utf8(bytes).ok()?;
+        let display = display_path(path).unwrap_or_else(|_| path.display().to_string());
+        let lines = content.lines().collect::<Vec<_>>();
+        // CSV chunks repeat the header so every chunk names its columns.
+        let header = (kind == DocumentKind::Csv)
+            .then(|| lines.first().copied())
+            .flatten();
+
+        let chunk_lines = self.config.chunk_lines.max(1);
+        let step = chunk_lines
+            .saturating_sub(self.config.chunk_overlap_lines)
+            .max(1);
+        let path_terms = normalized_terms(&display);
+        let mut chunks = Vec::new();
+        let mut start = 0usize;
+        while start < lines.len() {
+            let end = (start + chunk_lines).min(lines.len());
+            let body = lines[start..end].join("\n");
+            if !body.trim().is_empty() {
+                let text = match header {
+                    Some(header) if start > 0 => format!("{header}\n{body}"),
+                    _ => body,
+                };
+                let mut terms = normalized_terms(&text).into_iter().collect::<HashSet<_>>();
+                terms.extend(path_terms.iter().cloned());
+                chunks.push(KnowledgeChunk {
+                    chunk_id: chunk_id(&display, start + 1, &text),
+                    path: path.to_path_buf(),
+                    start_line: start + 1,
+                    end_line: end,
+                    text,
+                    terms,
+                });
+            }
+            if end == lines.len() {
+                break;
+            }
+            start += step;
+        }
+        Some(chunks)
+    }
+}
+
+/// Cosine similarities by candidate index. Chunks already embedded are always
+/// scored; at most `max_new` new ones are sent to the provider, best lexical
+/// matches first.
+fn vector_similarities(
+    provider: &mut dyn EmbeddingProvider,
+    vectors: &mut SegmentVectorIndex,
+    max_new: usize,
+    query: &str,
+    candidates: &[&KnowledgeChunk],
+    lexical: &[f64],
+) -> Option<HashMap<usize, f64>> {
+    let provider_id = provider.id();
+    let mut pending = (0..candidates.len())
+        .filter(|idx| !vectors.contains(&provider_id, &candidates[*idx].text))
+        .collect::<Vec<_>>();
+    pending.sort_by(|left, right| lexical[*right].total_cmp(&lexical[*left]));
+    pending.truncate(max_new);
+    let pending = pending.into_iter().collect::<HashSet<_>>();
+
+    let selected = candidates
+        .iter()
+        .enumerate()
+        .filter(|(idx, chunk)| pending.contains(idx) || vectors.contains(&provider_id, &chunk.text))
+        .map(|(idx, chunk)| (idx, chunk.text.clone()))
+        .collect::<Vec<_>>();
+    match vectors.similarities(provider, query, &selected) {
+        Ok(similarity) => Some(similarity),
+        Err(err) => {
+            tracing::warn!(%err, "KB: embedding provider failed, ranking lexically");
+            None
+        }
+    }
+}
+
+fn chunk_id(display_path: &str, start_line: usize, text: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(display_path.as_bytes());
+    hasher.update(start_line.to_le_bytes());
+    hasher.update(text.as_bytes());
+    let digest = hasher.finalize();
+    let hex = digest
+        .iter()
+        .take(6)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("kb-{hex}")
+}
+
+fn shared_knowledge_base() -> &'static Mutex<KnowledgeBaseIndex> {
+    static KNOWLEDGE_BASE: OnceLock<Mutex<KnowledgeBaseIndex>> = OnceLock::new();
+    KNOWLEDGE_BASE.get_or_init(|| Mutex::new(KnowledgeBaseIndex::from_kernel_config()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+struct KbSearchInput {
+    query: String,
+    path: Option<String>,
+    max_results: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+struct KbSearchHit {
+    chunk_id: String,
+    path: String,
+    start_line: u64,
+    end_line: u64,
+    score: f64,
+    excerpt: String,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq)]
+struct KbSearchOutput {
+    output: String,
+    query: String,
+    results: Vec<KbSearchHit>,
+    scoring: String,
+    indexed_chunks: u64,
+}
+
+#[agentic_tool(
+    name = "kb_search",
+    description = "Search the workspace knowledge base (text, markdown, code, CSV and JSON chunks under the granted roots) and return chunk ids, paths and line ranges to read.",
+    input_example = serde_json::json!({"query": "how are tool results cached", "path": "crates/agentic-kernel/src", "max_results": 5}),
+    capabilities = ["fs", "search", "rag"],
+    allowed_callers = [AgentText, AgentSupervisor, Programmatic]
+)]
+fn kb_search(input: KbSearchInput, ctx: &ToolContext) -> Result<KbSearchOutput, ToolError> {
+    let config = &kernel_config().tools.knowledge_base;
+    if !config.enabled {
+        return Err(ToolError::Disabled("kb_search".into()));
+    }
+    let mut index = shared_knowledge_base()
+        .lock()
+        .map_err(|_| ToolError::Internal("knowledge base lock poisoned".into()))?;
+    search_knowledge_base(&mut index, input, ctx)
+}
+
+fn search_knowledge_base(
+    index: &mut KnowledgeBaseIndex,
+    input: KbSearchInput,
+    ctx: &ToolContext,
+) -> Result<KbSearchOutput, ToolError> {
+    if input.query.trim().is_empty() {
+        return Err(ToolError::InvalidInput(
+            "kb_search".into(),
+            "field 'query' cannot be empty".into(),
+        ));
+    }
+    let roots = match input.path.as_deref().map(str::trim) {
+        Some(path) if !path.is_empty() => {
+            vec![resolve_search_root("kb_search", Some(path), ctx)?.absolute]
+        }
+        _ => resolve_context_grant_roots(ctx)
+            .map_err(|err| ToolError::ExecutionFailed("kb_search".into(), err))?,
+    };
+    let workspace =
+        workspace_root().map_err(|err| ToolError::ExecutionFailed("kb_search".into(), err))?;
+    let limit = input
+        .max_results
+        .map(|value| value as usize)
+        .unwrap_or(index.config.default_max_results)
+        .clamp(1, MAX_KB_RESULTS_CAP);
+
+    let search = index.search(&input.query, &roots, limit, &|path| {
+        ensure_path_access(&workspace, path, ctx, PathAccessIntent::Read).is_ok()
+    });
+
+    let results = search
+        .hits
+        .into_iter()
+        .map(|hit| KbSearchHit {
+            path: display_path(&hit.chunk.path)
+                .unwrap_or_else(|_| hit.chunk.path.display().to_string()),
+            chunk_id: hit.chunk.chunk_id,
+            start_line: hit.chunk.start_line as u64,
+            end_line: hit.chunk.end_line as u64,
+            score: (hit.score * 1000.0).round() / 1000.0,
+            excerpt: excerpt(&hit.chunk.text),
+        })
+        .collect::<Vec<_>>();
+    let output = if results.is_empty() {
+        format!("No knowledge base chunks match '{}'.", input.query)
+    } else {
+        let lines = results
+            .iter()
+            .map(|hit| {
+                format!(
+                    "{} {}:{}-{} (score {:.3}) {}",
+                    hit.chunk_id, hit.path, hit.start_line, hit.end_line, hit.score, hit.excerpt
+                )
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "Knowledge base chunks for '{}':\n{}",
+            input.query,
+            lines.join("\n")
+        )
+    };
+
+    Ok(KbSearchOutput {
+        output,
+        query: input.query,
+        results,
+        scoring: if search.hybrid { "hybrid" } else { "lexical" }.to_string(),
+        indexed_chunks: search.indexed_chunks as u64,
+    })
+}
+
+fn excerpt(text: &str) -> String {
+    let flat = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    if flat.chars().count() <= EXCERPT_CHARS {
+        return flat;
+    }
+    let mut cut = flat.chars().take(EXCERPT_CHARS).collect::<String>();
+    cut.push_str("...");
+    cut
+}
+
+#[cfg(test)]
+#[path = "tests/knowledge.rs"]
+mod tests;
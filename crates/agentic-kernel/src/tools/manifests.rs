@@ -0,0 +1,414 @@
+//! Declarative tool manifests.
+//!
+//! Every `*.toml` or `*.json` file in `tools.manifest_dir` declares one tool:
+//! the descriptor fields plus a `remote_http`, `wasm` or `command` backend.
+//! Manifests are registered with `ToolSource::Manifest` at boot and the
+//! directory is polled afterwards, so editing, adding or deleting a file
+//! re-registers or drops its tool without a restart. A manifest that fails to
+//! parse or validate keeps its error until the file changes and is reported
+//! in `STATUS`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use agentic_control_models::{ToolManifestView, ToolManifestsStatusView};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::storage::current_timestamp_ms;
+use crate::tool_registry::{
+    default_allowed_callers, default_success_exit_codes, CommandOutputFormat, ToolBackendConfig,
+    ToolDescriptor, ToolRegistry, ToolRegistryEntry, ToolSource,
+};
+use crate::tools::invocation::ToolCaller;
+
+/// Cadence of the directory scan after boot.
+const MANIFEST_POLL_INTERVAL_MS: i64 = 2_000;
+const MAX_MANIFEST_BYTES: u64 = 256 * 1024;
+const DEFAULT_REMOTE_HTTP_TIMEOUT_MS: u64 = 10_000;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ToolManifest {
+    name: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    description: String,
+    #[serde(default = "default_object_schema")]
+    input_schema: Value,
+    #[serde(default)]
+    input_example: Option<Value>,
+    #[serde(default = "default_object_schema")]
+    output_schema: Value,
+    #[serde(default = "default_allowed_callers")]
+    allowed_callers: Vec<ToolCaller>,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    dangerous: bool,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    #[serde(default = "default_true")]
+    default_allowlisted: bool,
+    #[serde(default)]
+    approval_required: bool,
+    backend: ManifestBackend,
+}
+
+/// Backends a manifest may declare; host executors stay reserved to built-ins.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", deny_unknown_fields)]
+enum ManifestBackend {
+    RemoteHttp {
+        url: String,
+        #[serde(default = "default_remote_http_method")]
+        method: String,
+        #[serde(default = "default_remote_http_timeout_ms")]
+        timeout_ms: u64,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+    },
+    Wasm {
+        /// Module path, relative to the manifest directory unless absolute.
+        module: String,
+        #[serde(default = "default_wasm_export")]
+        export: String,
+    },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        cwd: Option<String>,
+        #[serde(default)]
+        timeout_ms: Option<u64>,
+        #[serde(default)]
+        path_fields: Vec<String>,
+        #[serde(default)]
+        write_path_fields: Vec<String>,
+        #[serde(default = "default_success_exit_codes")]
+        success_exit_codes: Vec<i32>,
+        #[serde(default)]
+        output: CommandOutputFormat,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileFingerprint {
+    modified_ns: u128,
+    len: u64,
+}
+
+#[derive(Debug, Clone)]
+struct LoadedManifest {
+    fingerprint: FileFingerprint,
+    tool_name: Option<String>,
+    error: Option<String>,
+}
+
+/// Tracks the manifests of `tools.manifest_dir` and the tools they registered.
+#[derive(Debug)]
+pub(crate) struct ToolManifestLoader {
+    dir: PathBuf,
+    manifests: BTreeMap<PathBuf, LoadedManifest>,
+    dir_error: Option<String>,
+    last_scan_ms: Option<i64>,
+}
+
+impl ToolManifestLoader {
+    pub(crate) fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            manifests: BTreeMap::new(),
+            dir_error: None,
+            last_scan_ms: None,
+        }
+    }
+
+    pub(crate) fn next_poll_at_ms(&self) -> i64 {
+        self.last_scan_ms
+            .map(|last| last + MANIFEST_POLL_INTERVAL_MS)
+            .unwrap_or(0)
+    }
+
+    /// Rescan the directory and apply added, changed and removed manifests.
+    /// Returns whether the registry changed.
+    pub(crate) fn reload(&mut self, registry: &mut ToolRegistry) -> bool {
+        self.last_scan_ms = Some(current_timestamp_ms());
+        let current = match scan_manifest_dir(&self.dir) {
+            Ok(current) => {
+                self.dir_error = None;
+                current
+            }
+            Err(err) => {
+                if self.dir_error.as_deref() != Some(err.as_str()) {
+                    tracing::warn!(dir = %self.dir.display(), %err, "TOOL_MANIFESTS: scan failed");
+                }
+                self.dir_error = Some(err);
+                return false;
+            }
+        };
+
+        let stale: Vec<PathBuf> = self
+            .manifests
+            .iter()
+            .filter(|(path, loaded)| current.get(*path) != Some(&loaded.fingerprint))
+            .map(|(path, _)| path.clone())
+            .collect();
+        let mut changed = false;
+        for path in &stale {
+            let Some(loaded) = self.manifests.remove(path) else {
+                continue;
+            };
+            if let Some(tool_name) = loaded.tool_name {
+                changed |= unregister_manifest_tool(registry, &tool_name);
+            }
+        }
+
+        // A failed manifest (e.g. name already taken) retries when another one changes.
+        let retry_failed = changed;
+        for (path, fingerprint) in current {
+            let retry = retry_failed
+                && self
+                    .manifests
+                    .get(&path)
+                    .is_some_and(|loaded| loaded.tool_name.is_none());
+            if self.manifests.contains_key(&path) && !retry {
+                continue;
+            }
+            let loaded = match register_manifest(registry, &self.dir, &path) {
+                Ok(tool_name) => {
+                    tracing::info!(path = %path.display(), tool = %tool_name, "TOOL_MANIFESTS: tool registered");
+                    changed = true;
+                    LoadedManifest {
+                        fingerprint,
+                        tool_name: Some(tool_name),
+                        error: None,
+                    }
+                }
+                Err(err) => {
+                    tracing::warn!(path = %path.display(), %err, "TOOL_MANIFESTS: invalid manifest");
+                    LoadedManifest {
+                        fingerprint,
+                        tool_name: None,
+                        error: Some(err),
+                    }
+                }
+            };
+            self.manifests.insert(path, loaded);
+        }
+        changed
+    }
+
+    pub(crate) fn status_view(&self) -> ToolManifestsStatusView {
+        let mut manifests: Vec<ToolManifestView> = self
+            .manifests
+            .iter()
+            .map(|(path, loaded)| ToolManifestView {
+                path: path.display().to_string(),
+                tool_name: loaded.tool_name.clone(),
+                error: loaded.error.clone(),
+            })
+            .collect();
+        if let Some(err) = self.dir_error.as_ref() {
+            manifests.insert(
+                0,
+                ToolManifestView {
+                    path: self.dir.display().to_string(),
+                    tool_name: None,
+                    error: Some(err.clone()),
+                },
+            );
+        }
+        ToolManifestsStatusView {
+            manifest_dir: self.dir.display().to_string(),
+            manifests,
+            last_scan_at_ms: self.last_scan_ms,
+        }
+    }
+}
+
+fn unregister_manifest_tool(registry: &mut ToolRegistry, tool_name: &str) -> bool {
+    let owned = registry
+        .get(tool_name)
+        .is_some_and(|entry| entry.descriptor.source == ToolSource::Manifest);
+    if !owned {
+        return false;
+    }
+    match registry.unregister(tool_name) {
+        Ok(_) => {
+            tracing::info!(tool = tool_name, "TOOL_MANIFESTS: tool unregistered");
+            true
+        }
+        Err(err) => {
+            tracing::warn!(tool = tool_name, %err, "TOOL_MANIFESTS: failed to unregister tool");
+            false
+        }
+    }
+}
+
+fn register_manifest(
+    registry: &mut ToolRegistry,
+    dir: &Path,
+    path: &Path,
+) -> Result<String, String> {
+    let len = fs::metadata(path)
+        .map_err(|err| format!("failed to read manifest: {err}"))?
+        .len();
+    if len > MAX_MANIFEST_BYTES {
+        return Err(format!(
+            "manifest exceeds the {MAX_MANIFEST_BYTES} byte limit"
+        ));
+    }
+    let raw = fs::read_to_string(path).map_err(|err| format!("failed to read manifest: {err}"))?;
+    let manifest = parse_tool_manifest(path, &raw)?;
+    let entry = manifest_registry_entry(manifest, dir);
+    let requested = entry.descriptor.name.clone();
+    registry.register(entry)?;
+    Ok(registry
+        .get(&requested)
+        .map(|entry| entry.descriptor.name.clone())
+        .unwrap_or(requested))
+}
+
+fn parse_tool_manifest(path: &Path, raw: &str) -> Result<ToolManifest, String> {
+    match manifest_extension(path) {
+        Some("toml") => toml::from_str(raw).map_err(|err| format!("invalid TOML manifest: {err}")),
+        Some("json") => {
+            serde_json::from_str(raw).map_err(|err| format!("invalid JSON manifest: {err}"))
+        }
+        _ => Err("manifest must be a .toml or .json file".to_string()),
+    }
+}
+
+fn manifest_registry_entry(manifest: ToolManifest, dir: &Path) -> ToolRegistryEntry {
+    let backend = match manifest.backend {
+        ManifestBackend::RemoteHttp {
+            url,
+            method,
+            timeout_ms,
+            headers,
+        } => ToolBackendConfig::RemoteHttp {
+            url,
+            method,
+            timeout_ms,
+            headers,
+        },
+        ManifestBackend::Wasm { module, export } => {
+            let module_path = Path::new(&module);
+            let module = if module_path.is_relative() && !module.trim().is_empty() {
+                dir.join(module_path).display().to_string()
+            } else {
+                module
+            };
+            ToolBackendConfig::Wasm { module, export }
+        }
+        ManifestBackend::Command {
+            program,
+            args,
+            cwd,
+            timeout_ms,
+            path_fields,
+            write_path_fields,
+            success_exit_codes,
+            output,
+        } => ToolBackendConfig::Command {
+            program,
+            args,
+            cwd,
+            timeout_ms,
+            path_fields,
+            write_path_fields,
+            success_exit_codes,
+            output,
+        },
+    };
+    ToolRegistryEntry {
+        descriptor: ToolDescriptor {
+            name: manifest.name,
+            aliases: manifest.aliases,
+            description: manifest.description,
+            input_schema: manifest.input_schema,
+            input_example: manifest.input_example,
+            output_schema: manifest.output_schema,
+            allowed_callers: manifest.allowed_callers,
+            backend_kind: backend.kind(),
+            capabilities: manifest.capabilities,
+            dangerous: manifest.dangerous,
+            enabled: manifest.enabled,
+            default_allowlisted: manifest.default_allowlisted,
+            approval_required: manifest.approval_required,
+            interop: None,
+            source: ToolSource::Manifest,
+        },
+        backend,
+    }
+}
+
+/// Fingerprint of every manifest file directly inside `dir`. A missing
+/// directory simply means no manifests are installed.
+fn scan_manifest_dir(dir: &Path) -> Result<BTreeMap<PathBuf, FileFingerprint>, String> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(err) => return Err(format!("failed to read manifest directory: {err}")),
+    };
+    let mut snapshot = BTreeMap::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !matches!(manifest_extension(&path), Some("toml" | "json")) {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let modified_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or(0);
+        snapshot.insert(
+            path,
+            FileFingerprint {
+                modified_ns,
+                len: metadata.len(),
+            },
+        );
+    }
+    Ok(snapshot)
+}
+
+fn manifest_extension(path: &Path) -> Option<&str> {
+    path.extension().and_then(|ext| ext.to_str())
+}
+
+fn default_object_schema() -> Value {
+    json!({"type": "object"})
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_remote_http_method() -> String {
+    "POST".to_string()
+}
+
+fn default_remote_http_timeout_ms() -> u64 {
+    DEFAULT_REMOTE_HTTP_TIMEOUT_MS
+}
+
+fn default_wasm_export() -> String {
+    "run".to_string()
+}
+
+#[cfg(test)]
+#[path = "tests/manifests.rs"]
+mod tests;
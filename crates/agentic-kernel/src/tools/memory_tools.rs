@@ -0,0 +1,578 @@
+//! Long-term memory tools.
+//!
+//! `memory_remember`, `memory_recall` and `memory_forget` read and write the
+//! `memory_entries` table, so the kernel serves them on the event loop (see
+//! `runtime::syscalls::memory`) where storage and the session provenance are
+//! at hand. The registered executors only exist for discovery and schema
+//! validation: a call reaching the syscall worker fails as unavailable, so
+//! the tools stay closed to programmatic callers (jobs, MCP export), which
+//! only reach the worker.
+use agentic_kernel_macros::agentic_tool;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+use crate::config::LongTermMemoryConfig;
+use crate::process::normalized_terms;
+use crate::storage::{MemoryScopeRef, NewMemoryEntry, StorageService, StoredMemoryEntry};
+
+use super::api::{typed_output_to_tool_result, ToolResult};
+use super::error::ToolError;
+use super::invocation::{ToolContext, ToolInvocation};
+use super::path_guard::workspace_root;
+
+pub(crate) const MEMORY_TOOL_NAMES: [&str; 3] =
+    ["memory_remember", "memory_recall", "memory_forget"];
+
+const MAX_RECALL_LIMIT: usize = 50;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MemoryScopeKind {
+    /// Shared by every session of the operator.
+    User,
+    /// Shared by the sessions working on the current workspace.
+    Workspace,
+    /// Shared by the workflow tasks running the same role.
+    Agent,
+    /// Shared by the runs of the same workflow graph.
+    Workflow,
+}
+
+impl MemoryScopeKind {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::User => "user",
+            Self::Workspace => "workspace",
+            Self::Agent => "agent",
+            Self::Workflow => "workflow",
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "user" => Some(Self::User),
+            "workspace" => Some(Self::Workspace),
+            "agent" => Some(Self::Agent),
+            "workflow" => Some(Self::Workflow),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MemoryEntryKind {
+    #[default]
+    Fact,
+    Note,
+}
+
+impl MemoryEntryKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Fact => "fact",
+            Self::Note => "note",
+        }
+    }
+}
+
+/// Scope keys and provenance of the process calling a memory tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct MemoryScopeContext {
+    pub user: String,
+    pub workspace: String,
+    /// Role (or task id) of the workflow task, when the process runs one.
+    pub agent: Option<String>,
+    /// Stable key of the workflow graph, when the process runs a task.
+    pub workflow: Option<String>,
+    pub session_id: Option<String>,
+    pub turn_id: Option<i64>,
+    pub pid: Option<u64>,
+}
+
+impl MemoryScopeContext {
+    /// User and workspace scopes of this kernel, without provenance.
+    pub(crate) fn for_kernel(config: &LongTermMemoryConfig) -> Self {
+        Self {
+            user: config.user_scope.trim().to_string(),
+            workspace: workspace_root()
+                .map(|root| root.display().to_string())
+                .unwrap_or_default(),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn scope(&self, kind: MemoryScopeKind) -> Option<MemoryScopeRef> {
+        let key = match kind {
+            MemoryScopeKind::User => Some(self.user.as_str()),
+            MemoryScopeKind::Workspace => Some(self.workspace.as_str()),
+            MemoryScopeKind::Agent => self.agent.as_deref(),
+            MemoryScopeKind::Workflow => self.workflow.as_deref(),
+        }?;
+        let key = key.trim();
+        (!key.is_empty()).then(|| MemoryScopeRef {
+            scope_kind: kind.as_str().to_string(),
+            scope_key: key.to_string(),
+        })
+    }
+
+    /// Every scope the process can read and forget from.
+    pub(crate) fn visible_scopes(&self) -> Vec<MemoryScopeRef> {
+        [
+            MemoryScopeKind::User,
+            MemoryScopeKind::Workspace,
+            MemoryScopeKind::Agent,
+            MemoryScopeKind::Workflow,
+        ]
+        .into_iter()
+        .filter_map(|kind| self.scope(kind))
+        .collect()
+    }
+}
+
+/// Scope key shared by every run of a workflow with the same task graph.
+pub(crate) fn workflow_scope_key<'a>(
+    tasks: impl IntoIterator<Item = (&'a str, Option<&'a str>)>,
+) -> String {
+    let mut nodes = tasks
+        .into_iter()
+        .map(|(task_id, role)| format!("{task_id}:{}", role.unwrap_or_default()))
+        .collect::<Vec<_>>();
+    nodes.sort();
+    let digest = Sha256::digest(nodes.join("\n").as_bytes());
+    let hex = digest
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("wf-{hex}")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MemoryRememberInput {
+    content: String,
+    #[serde(default)]
+    kind: MemoryEntryKind,
+    #[serde(default)]
+    scope: Option<MemoryScopeKind>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+struct MemoryRememberOutput {
+    output: String,
+    memory_id: i64,
+    scope: String,
+    kind: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MemoryRecallInput {
+    #[serde(default)]
+    query: String,
+    #[serde(default)]
+    scope: Option<MemoryScopeKind>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+struct RecalledMemory {
+    memory_id: i64,
+    scope: String,
+    kind: String,
+    content: String,
+    tags: Vec<String>,
+    source_session_id: Option<String>,
+    source_turn_id: Option<i64>,
+    source_pid: Option<u64>,
+    created_at_ms: i64,
+    updated_at_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+struct MemoryRecallOutput {
+    output: String,
+    memories: Vec<RecalledMemory>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct MemoryForgetInput {
+    memory_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema, PartialEq, Eq)]
+struct MemoryForgetOutput {
+    output: String,
+    memory_id: i64,
+    scope: String,
+}
+
+fn served_by_kernel(tool_name: &str) -> ToolError {
+    ToolError::BackendUnavailable(
+        tool_name.to_string(),
+        "memory tools are served by the kernel event loop".to_string(),
+    )
+}
+
+#[agentic_tool(
+    name = "memory_remember",
+    description = "Store a fact or note in long-term memory so later sessions can recall it. Scopes: user, workspace (default), agent, workflow.",
+    input_example = serde_json::json!({"content": "Deploys go through the staging branch", "kind": "fact", "scope": "workspace", "tags": ["deploy"]}),
+    capabilities = ["memory", "write"],
+    allowed_callers = [AgentText, AgentSupervisor]
+)]
+fn memory_remember(
+    _input: MemoryRememberInput,
+    _ctx: &ToolContext,
+) -> Result<MemoryRememberOutput, ToolError> {
+    Err(served_by_kernel("memory_remember"))
+}
+
+#[agentic_tool(
+    name = "memory_recall",
+    description = "Search long-term memory for facts and notes relevant to a query, across the scopes visible to this process or in one scope.",
+    input_example = serde_json::json!({"query": "how do deploys work", "limit": 5}),
+    capabilities = ["memory", "read"],
+    allowed_callers = [AgentText, AgentSupervisor]
+)]
+fn memory_recall(
+    _input: MemoryRecallInput,
+    _ctx: &ToolContext,
+) -> Result<MemoryRecallOutput, ToolError> {
+    Err(served_by_kernel("memory_recall"))
+}
+
+#[agentic_tool(
+    name = "memory_forget",
+    description = "Delete a long-term memory by id. Only memories in the scopes visible to this process can be forgotten.",
+    input_example = serde_json::json!({"memory_id": 12}),
+    capabilities = ["memory", "write"],
+    allowed_callers = [AgentText, AgentSupervisor]
+)]
+fn memory_forget(
+    _input: MemoryForgetInput,
+    _ctx: &ToolContext,
+) -> Result<MemoryForgetOutput, ToolError> {
+    Err(served_by_kernel("memory_forget"))
+}
+
+pub(crate) fn is_memory_tool(tool_name: &str) -> bool {
+    MEMORY_TOOL_NAMES.contains(&tool_name)
+}
+
+/// Run an authorized memory tool invocation against `storage`.
+pub(crate) fn execute_memory_tool(
+    storage: &mut StorageService,
+    invocation: &ToolInvocation,
+    scopes: &MemoryScopeContext,
+    config: &LongTermMemoryConfig,
+) -> Result<ToolResult, ToolError> {
+    let name = invocation.name.as_str();
+    if !config.enabled {
+        return Err(ToolError::Disabled(name.to_string()));
+    }
+    match name {
+        "memory_remember" => {
+            let output = remember(storage, parse_input(invocation)?, scopes, config)?;
+            typed_output_to_tool_result(name, output)
+        }
+        "memory_recall" => {
+            let output = recall(storage, parse_input(invocation)?, scopes, config)?;
+            typed_output_to_tool_result(name, output)
+        }
+        "memory_forget" => {
+            let output = forget(storage, parse_input(invocation)?, scopes)?;
+            typed_output_to_tool_result(name, output)
+        }
+        other => Err(ToolError::NotFound(other.to_string())),
+    }
+}
+
+fn parse_input<T: serde::de::DeserializeOwned>(
+    invocation: &ToolInvocation,
+) -> Result<T, ToolError> {
+    serde_json::from_value(invocation.input.clone())
+        .map_err(|err| ToolError::InvalidInput(invocation.name.clone(), err.to_string()))
+}
+
+fn storage_error(tool_name: &str, err: impl std::fmt::Display) -> ToolError {
+    ToolError::ExecutionFailed(tool_name.to_string(), err.to_string())
+}
+
+fn resolve_scope(
+    tool_name: &str,
+    scopes: &MemoryScopeContext,
+    kind: MemoryScopeKind,
+) -> Result<MemoryScopeRef, ToolError> {
+    scopes.scope(kind).ok_or_else(|| {
+        ToolError::InvalidInput(
+            tool_name.to_string(),
+            format!(
+                "scope '{}' is not available: this process does not run a workflow task",
+                kind.as_str()
+            ),
+        )
+    })
+}
+
+fn remember(
+    storage: &mut StorageService,
+    input: MemoryRememberInput,
+    scopes: &MemoryScopeContext,
+    config: &LongTermMemoryConfig,
+) -> Result<MemoryRememberOutput, ToolError> {
+    const TOOL: &str = "memory_remember";
+    let content = input.content.trim();
+    if content.is_empty() {
+        return Err(ToolError::InvalidInput(
+            TOOL.into(),
+            "field 'content' cannot be empty".into(),
+        ));
+    }
+    let content_chars = content.chars().count();
+    if content_chars > config.max_content_chars {
+        return Err(ToolError::InvalidInput(
+            TOOL.into(),
+            format!(
+                "field 'content' has {content_chars} chars, the limit is {}",
+                config.max_content_chars
+            ),
+        ));
+    }
+    let kind = input
+        .scope
+        .or_else(|| MemoryScopeKind::parse(&config.default_scope))
+        .unwrap_or(MemoryScopeKind::Workspace);
+    let scope = resolve_scope(TOOL, scopes, kind)?;
+    let mut tags = input
+        .tags
+        .iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty())
+        .collect::<Vec<_>>();
+    tags.sort();
+    tags.dedup();
+
+    let memory_id = storage
+        .remember_memory_entry(
+            &NewMemoryEntry {
+                scope: scope.clone(),
+                entry_kind: input.kind.as_str().to_string(),
+                content: content.to_string(),
+                tags,
+                source_session_id: scopes.session_id.clone(),
+                source_turn_id: scopes.turn_id,
+                source_pid: scopes.pid,
+            },
+            config.max_entries_per_scope,
+        )
+        .map_err(|err| storage_error(TOOL, err))?;
+
+    Ok(MemoryRememberOutput {
+        output: format!(
+            "Remembered {} #{memory_id} in the {} scope.",
+            input.kind.as_str(),
+            scope.scope_kind
+        ),
+        memory_id,
+        scope: scope.scope_kind,
+        kind: input.kind.as_str().to_string(),
+    })
+}
+
+fn recall(
+    storage: &mut StorageService,
+    input: MemoryRecallInput,
+    scopes: &MemoryScopeContext,
+    config: &LongTermMemoryConfig,
+) -> Result<MemoryRecallOutput, ToolError> {
+    const TOOL: &str = "memory_recall";
+    let searched = match input.scope {
+        Some(kind) => vec![resolve_scope(TOOL, scopes, kind)?],
+        None => scopes.visible_scopes(),
+    };
+    let limit = input
+        .limit
+        .unwrap_or(config.recall_limit)
+        .clamp(1, MAX_RECALL_LIMIT);
+    let memories = recall_ranked(storage, &searched, &input.query, limit, config)
+        .map_err(|err| storage_error(TOOL, err))?;
+
+    let output = if memories.is_empty() {
+        "No matching memories.".to_string()
+    } else {
+        memories
+            .iter()
+            .map(render_memory_line)
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+    Ok(MemoryRecallOutput {
+        output,
+        memories: memories
+            .into_iter()
+            .map(|entry| RecalledMemory {
+                memory_id: entry.memory_id,
+                scope: entry.scope.scope_kind,
+                kind: entry.entry_kind,
+                content: entry.content,
+                tags: entry.tags,
+                source_session_id: entry.source_session_id,
+                source_turn_id: entry.source_turn_id,
+                source_pid: entry.source_pid,
+                created_at_ms: entry.created_at_ms,
+                updated_at_ms: entry.updated_at_ms,
+            })
+            .collect(),
+    })
+}
+
+fn forget(
+    storage: &mut StorageService,
+    input: MemoryForgetInput,
+    scopes: &MemoryScopeContext,
+) -> Result<MemoryForgetOutput, ToolError> {
+    const TOOL: &str = "memory_forget";
+    let forgotten = storage
+        .forget_memory_entry(input.memory_id, &scopes.visible_scopes())
+        .map_err(|err| storage_error(TOOL, err))?
+        .ok_or_else(|| {
+            ToolError::InvalidInput(
+                TOOL.into(),
+                format!(
+                    "no memory #{} in the scopes visible to this process",
+                    input.memory_id
+                ),
+            )
+        })?;
+    Ok(MemoryForgetOutput {
+        output: format!(
+            "Forgot {} #{} from the {} scope.",
+            forgotten.entry_kind, forgotten.memory_id, forgotten.scope.scope_kind
+        ),
+        memory_id: forgotten.memory_id,
+        scope: forgotten.scope.scope_kind,
+    })
+}
+
+/// Memories of `scopes` ranked against `query` and marked as recalled.
+///
+/// Ranking is lexical (shared stemmed terms, tags count double) with the most
+/// recently updated entry winning ties; an empty query returns the newest
+/// entries.
+fn recall_ranked(
+    storage: &mut StorageService,
+    scopes: &[MemoryScopeRef],
+    query: &str,
+    limit: usize,
+    config: &LongTermMemoryConfig,
+) -> Result<Vec<StoredMemoryEntry>, crate::storage::StorageError> {
+    let candidates = storage.memory_entries_in_scopes(
+        scopes,
+        config
+            .max_entries_per_scope
+            .max(1)
+            .saturating_mul(scopes.len()),
+    )?;
+    let query_terms = normalized_terms(query).into_iter().collect::<HashSet<_>>();
+
+    let mut ranked = candidates
+        .into_iter()
+        .map(|entry| (memory_score(&query_terms, &entry), entry))
+        .filter(|(score, _)| query_terms.is_empty() || *score > 0.0)
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(left_score, left), (right_score, right)| {
+        right_score
+            .total_cmp(left_score)
+            .then_with(|| right.updated_at_ms.cmp(&left.updated_at_ms))
+            .then_with(|| right.memory_id.cmp(&left.memory_id))
+    });
+    let memories = ranked
+        .into_iter()
+        .take(limit)
+        .map(|(_, entry)| entry)
+        .collect::<Vec<_>>();
+
+    let ids = memories
+        .iter()
+        .map(|entry| entry.memory_id)
+        .collect::<Vec<_>>();
+    storage.mark_memory_entries_recalled(&ids)?;
+    Ok(memories)
+}
+
+fn memory_score(query_terms: &HashSet<String>, entry: &StoredMemoryEntry) -> f64 {
+    if query_terms.is_empty() {
+        return 0.0;
+    }
+    let content_terms = normalized_terms(&entry.content)
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let tag_terms = entry
+        .tags
+        .iter()
+        .flat_map(|tag| normalized_terms(tag))
+        .collect::<HashSet<_>>();
+    let matched = query_terms
+        .iter()
+        .map(|term| {
+            if tag_terms.contains(term) {
+                2.0
+            } else if content_terms.contains(term) {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .sum::<f64>();
+    matched / query_terms.len() as f64
+}
+
+fn render_memory_line(entry: &StoredMemoryEntry) -> String {
+    let mut line = format!(
+        "- #{} [{} {}] {}",
+        entry.memory_id, entry.scope.scope_kind, entry.entry_kind, entry.content
+    );
+    if !entry.tags.is_empty() {
+        line.push_str(&format!(" (tags: {})", entry.tags.join(", ")));
+    }
+    line
+}
+
+/// Digest of the memories relevant to the first prompt of a session, for the
+/// `RetrievedMemory` segment injected at spawn, with the number of memories
+/// it holds. `None` when nothing matches.
+pub(crate) fn startup_memory_digest(
+    storage: &mut StorageService,
+    prompt: &str,
+    scopes: &[MemoryScopeRef],
+    config: &LongTermMemoryConfig,
+) -> Result<Option<(String, usize)>, crate::storage::StorageError> {
+    if !config.enabled || config.auto_inject_top_k == 0 || prompt.trim().is_empty() {
+        return Ok(None);
+    }
+    let memories = recall_ranked(storage, scopes, prompt, config.auto_inject_top_k, config)?;
+    let mut digest = String::from("Long-term memory relevant to this session:");
+    let mut included = 0usize;
+    for entry in &memories {
+        let line = render_memory_line(entry);
+        if digest.chars().count() + line.chars().count() + 1 > config.auto_inject_max_chars {
+            continue;
+        }
+        digest.push('\n');
+        digest.push_str(&line);
+        included += 1;
+    }
+    Ok((included > 0).then_some((digest, included)))
+}
+
+#[cfg(test)]
+#[path = "tests/memory.rs"]
+mod tests;
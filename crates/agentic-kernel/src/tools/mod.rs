@@ -16,10 +16,15 @@ pub mod governance;
 pub(crate) mod host_exec;
 pub(crate) mod human_tools;
 pub mod invocation;
+pub(crate) mod knowledge_tools;
+pub(crate) mod manifests;
+pub(crate) mod memory_tools;
 pub(crate) mod network_tools;
 pub mod parser;
 pub mod path_guard;
 pub mod policy;
+pub(crate) mod registry_store;
+pub(crate) mod result_cache;
 pub mod runner;
 pub mod schema;
 pub(crate) mod system_tools;
@@ -64,6 +69,7 @@ pub struct SysCallOutcome {
     pub warnings: Vec<String>,
     pub error_kind: Option<String>,
     pub effects: Vec<serde_json::Value>,
+    pub cache_hit: bool,
 }
 
 pub fn handle_syscall(
@@ -97,6 +103,7 @@ pub fn handle_syscall(
                 warnings: Vec::new(),
                 error_kind: Some("malformed_invocation".to_string()),
                 effects: Vec::new(),
+                cache_hit: false,
             };
         }
     };
@@ -112,6 +119,7 @@ pub fn handle_syscall(
         warnings: result.warnings,
         error_kind: result.error_kind,
         effects: result.effects,
+        cache_hit: result.cache_hit,
     }
 }
 #[cfg(test)]
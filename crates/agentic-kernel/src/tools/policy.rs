@@ -5,6 +5,8 @@ use std::time::{Duration, Instant};
 
 use crate::config::{env_bool, env_u64, env_usize, kernel_config};
 
+use super::result_cache::ToolResultCache;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum SandboxMode {
     Host,
@@ -28,15 +30,18 @@ struct RateState {
     consecutive_errors: usize,
 }
 
-/// Per-process syscall rate-limiting state — owned by Kernel, no global statics.
+/// Per-process syscall rate-limiting state and the shared tool result cache —
+/// owned by Kernel, no global statics.
 pub(crate) struct SyscallRateMap {
     states: HashMap<u64, RateState>,
+    pub(crate) result_cache: ToolResultCache,
 }
 
 impl SyscallRateMap {
     pub fn new() -> Self {
         Self {
             states: HashMap::new(),
+            result_cache: ToolResultCache::new(),
         }
     }
 }
@@ -0,0 +1,59 @@
+//! Persistence of `REGISTER_TOOL` registrations in the `runtime_tools` table,
+//! so runtime tools survive a kernel restart.
+
+use crate::storage::{StorageService, StoredRuntimeTool};
+use crate::tool_registry::{ToolRegistry, ToolRegistryEntry, ToolSource};
+
+/// Store (or replace) the registration of a runtime tool.
+pub(crate) fn persist_runtime_tool(
+    storage: &mut StorageService,
+    entry: &ToolRegistryEntry,
+) -> Result<(), String> {
+    let entry_json = serde_json::to_string(entry)
+        .map_err(|err| format!("failed to serialize tool registration: {err}"))?;
+    storage
+        .upsert_runtime_tool(&entry.descriptor.name, &entry_json)
+        .map_err(|err| format!("failed to persist tool registration: {err}"))
+}
+
+pub(crate) fn forget_runtime_tool(storage: &mut StorageService, tool_name: &str) {
+    if let Err(err) = storage.delete_runtime_tool(tool_name) {
+        tracing::warn!(tool = tool_name, %err, "TOOL_REGISTRY: failed to delete persisted registration");
+    }
+}
+
+/// Re-register the persisted runtime tools at boot. Entries that no longer
+/// deserialize or validate are skipped with a warning and kept in storage.
+pub(crate) fn restore_runtime_tools(
+    registry: &mut ToolRegistry,
+    storage: &StorageService,
+) -> usize {
+    let stored = match storage.load_runtime_tools() {
+        Ok(stored) => stored,
+        Err(err) => {
+            tracing::warn!(%err, "TOOL_REGISTRY: failed to load persisted runtime tools");
+            return 0;
+        }
+    };
+    let mut restored = 0;
+    for StoredRuntimeTool {
+        tool_name,
+        entry_json,
+        ..
+    } in stored
+    {
+        let result = serde_json::from_str::<ToolRegistryEntry>(&entry_json)
+            .map_err(|err| err.to_string())
+            .and_then(|mut entry| {
+                entry.descriptor.source = ToolSource::Runtime;
+                registry.register(entry)
+            });
+        match result {
+            Ok(()) => restored += 1,
+            Err(err) => {
+                tracing::warn!(tool = %tool_name, %err, "TOOL_REGISTRY: skipped persisted runtime tool");
+            }
+        }
+    }
+    restored
+}
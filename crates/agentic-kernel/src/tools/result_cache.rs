@@ -0,0 +1,296 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::config::ToolResultCacheConfig;
+use crate::tool_registry::ToolRegistryEntry;
+
+use super::api::ToolResult;
+use super::invocation::ToolContext;
+use super::path_guard::resolve_safe_read_path_for_context;
+
+/// Entries visited while fingerprinting a directory argument; larger trees
+/// are not cached.
+const MAX_FINGERPRINT_ENTRIES: usize = 4_096;
+
+/// Capabilities of tools that can change the workspace behind the back of
+/// the fingerprints (same mtime granularity, scripts writing elsewhere).
+const WORKSPACE_MUTATING_CAPABILITIES: &[&str] = &["write", "mkdir", "exec", "python", "download"];
+
+/// Cache policy resolved for a single tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ToolCachePolicy {
+    pub(crate) ttl_ms: i64,
+    pub(crate) max_entry_bytes: usize,
+    pub(crate) path_fields: Vec<String>,
+}
+
+/// Resolve whether results of `entry` may be cached and for how long.
+///
+/// Explicit per-tool policies win; otherwise MCP and remote tools qualify
+/// through their read-only / idempotent interop hints. Dangerous tools and
+/// tools behind an approval are never cached.
+pub(crate) fn cache_policy_for(
+    entry: &ToolRegistryEntry,
+    config: &ToolResultCacheConfig,
+) -> Option<ToolCachePolicy> {
+    let descriptor = &entry.descriptor;
+    if !config.enabled || descriptor.dangerous || descriptor.approval_required {
+        return None;
+    }
+    if let Some(policy) = config.tools.get(&descriptor.name) {
+        return (policy.ttl_s > 0).then(|| ToolCachePolicy {
+            ttl_ms: ttl_ms(policy.ttl_s),
+            max_entry_bytes: policy.max_entry_bytes,
+            path_fields: policy.path_fields.clone(),
+        });
+    }
+    let hints = &descriptor.interop.as_ref()?.hints;
+    let cacheable = (hints.read_only_hint || hints.idempotent_hint) && !hints.destructive_hint;
+    (cacheable && config.hinted_ttl_s > 0).then(|| ToolCachePolicy {
+        ttl_ms: ttl_ms(config.hinted_ttl_s),
+        max_entry_bytes: config.hinted_max_entry_bytes,
+        path_fields: Vec::new(),
+    })
+}
+
+pub(crate) fn mutates_workspace(entry: &ToolRegistryEntry) -> bool {
+    entry
+        .descriptor
+        .capabilities
+        .iter()
+        .any(|capability| WORKSPACE_MUTATING_CAPABILITIES.contains(&capability.as_str()))
+}
+
+/// Content address of an invocation: tool name, canonical input and, for
+/// workspace reads, the size and mtime of every path the input names.
+///
+/// Returns `None` when a path cannot be fingerprinted (outside the process
+/// grants, too large a tree): the call then runs uncached and reports the
+/// error itself.
+pub(crate) fn cache_key(
+    tool_name: &str,
+    input: &Value,
+    policy: &ToolCachePolicy,
+    context: &ToolContext,
+) -> Option<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(tool_name.as_bytes());
+    hasher.update([0]);
+    let mut canonical = String::new();
+    write_canonical_json(input, &mut canonical);
+    hasher.update(canonical.as_bytes());
+
+    for field in &policy.path_fields {
+        let paths = match input.get(field) {
+            None | Some(Value::Null) => vec![".".to_string()],
+            Some(Value::String(path)) if path.trim().is_empty() => vec![".".to_string()],
+            Some(Value::String(path)) => vec![path.clone()],
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| item.as_str().map(ToString::to_string))
+                .collect::<Option<Vec<_>>>()?,
+            Some(_) => return None,
+        };
+        for path in paths {
+            let resolved = resolve_safe_read_path_for_context(&path, context).ok()?;
+            hasher.update([0]);
+            hasher.update(field.as_bytes());
+            hasher.update([0]);
+            hasher.update(resolved.to_string_lossy().as_bytes());
+            let mut budget = MAX_FINGERPRINT_ENTRIES;
+            fingerprint_path(&resolved, &mut hasher, &mut budget)?;
+        }
+    }
+
+    Some(format!("{:x}", hasher.finalize()))
+}
+
+fn ttl_ms(ttl_s: u64) -> i64 {
+    i64::try_from(ttl_s.saturating_mul(1_000)).unwrap_or(i64::MAX)
+}
+
+/// JSON with object keys sorted, so that equivalent inputs share a key.
+fn write_canonical_json(value: &Value, out: &mut String) {
+    match value {
+        Value::Object(map) => {
+            let mut keys = map.keys().collect::<Vec<_>>();
+            keys.sort();
+            out.push('{');
+            for (index, key) in keys.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical_json(&map[key], out);
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical_json(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn fingerprint_path(path: &Path, hasher: &mut Sha256, budget: &mut usize) -> Option<()> {
+    *budget = budget.checked_sub(1)?;
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        hasher.update(b"missing");
+        return Some(());
+    };
+    let modified_ns = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or(0);
+    hasher.update(metadata.len().to_le_bytes());
+    hasher.update(modified_ns.to_le_bytes());
+
+    if metadata.is_dir() {
+        let mut children = fs::read_dir(path)
+            .ok()?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+        children.sort();
+        for child in children {
+            hasher.update(child.file_name()?.to_string_lossy().as_bytes());
+            fingerprint_path(&child, hasher, budget)?;
+        }
+    }
+    Some(())
+}
+
+#[derive(Debug, Clone)]
+struct CachedToolResult {
+    result: ToolResult,
+    bytes: usize,
+    expires_at_ms: i64,
+    last_used: u64,
+    workspace_bound: bool,
+}
+
+/// Content-addressed cache of tool results, bounded by entry count and total
+/// bytes with least-recently-used eviction. Lives next to the syscall rate
+/// state so every transport shares it.
+#[derive(Debug, Default)]
+pub(crate) struct ToolResultCache {
+    entries: HashMap<String, CachedToolResult>,
+    total_bytes: usize,
+    clock: u64,
+}
+
+impl ToolResultCache {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn lookup(&mut self, key: &str, now_ms: i64) -> Option<ToolResult> {
+        let expired = self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.expires_at_ms <= now_ms);
+        if expired {
+            self.remove(key);
+            return None;
+        }
+        self.clock += 1;
+        let clock = self.clock;
+        let entry = self.entries.get_mut(key)?;
+        entry.last_used = clock;
+        Some(entry.result.clone())
+    }
+
+    pub(crate) fn store(
+        &mut self,
+        key: String,
+        result: &ToolResult,
+        policy: &ToolCachePolicy,
+        config: &ToolResultCacheConfig,
+        now_ms: i64,
+    ) {
+        let bytes = result_bytes(result);
+        if bytes > policy.max_entry_bytes || bytes > config.max_total_bytes {
+            return;
+        }
+        self.remove(&key);
+        while !self.entries.is_empty()
+            && (self.entries.len() >= config.max_entries
+                || self.total_bytes + bytes > config.max_total_bytes)
+        {
+            self.evict_least_recently_used();
+        }
+        if config.max_entries == 0 {
+            return;
+        }
+        self.clock += 1;
+        self.total_bytes += bytes;
+        self.entries.insert(
+            key,
+            CachedToolResult {
+                result: result.clone(),
+                bytes,
+                expires_at_ms: now_ms.saturating_add(policy.ttl_ms),
+                last_used: self.clock,
+                workspace_bound: !policy.path_fields.is_empty(),
+            },
+        );
+    }
+
+    /// Drop the entries keyed on workspace fingerprints.
+    pub(crate) fn invalidate_workspace(&mut self) {
+        self.entries.retain(|_, entry| !entry.workspace_bound);
+        self.total_bytes = self.entries.values().map(|entry| entry.bytes).sum();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn total_bytes(&self) -> usize {
+        self.total_bytes
+    }
+
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.total_bytes -= entry.bytes;
+        }
+    }
+
+    fn evict_least_recently_used(&mut self) {
+        let oldest = self
+            .entries
+            .iter()
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(key, _)| key.clone());
+        if let Some(key) = oldest {
+            self.remove(&key);
+        }
+    }
+}
+
+fn result_bytes(result: &ToolResult) -> usize {
+    serde_json::to_string(&result.output).map_or(0, |text| text.len())
+        + result.display_text.as_ref().map_or(0, String::len)
+        + result.warnings.iter().map(String::len).sum::<usize>()
+}
+
+#[cfg(test)]
+#[path = "tests/result_cache.rs"]
+mod tests;
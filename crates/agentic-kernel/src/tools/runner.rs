@@ -73,14 +73,14 @@ fn run_host_python(script_path: &Path, timeout_s: u64) -> Result<String, String>
             "SysCall Error: Python failed (status={:?}).\n{}{}",
             output.status.code(),
             if stdout.is_empty() {
-                ""
+                String::new()
             } else {
-                &format!("stdout:\n{}\n", stdout)
+                format!("stdout:\n{}\n", stdout)
             },
             if stderr.is_empty() {
-                ""
+                String::new()
             } else {
-                &format!("stderr:\n{}", stderr)
+                format!("stderr:\n{}", stderr)
             }
         )))
     }
@@ -131,14 +131,14 @@ fn run_container_python(script_path: &Path, timeout_s: u64) -> Result<String, St
             "SysCall Error: Container runner failed (status={:?}).\n{}{}",
             output.status.code(),
             if stdout.is_empty() {
-                ""
+                String::new()
             } else {
-                &format!("stdout:\n{}\n", stdout)
+                format!("stdout:\n{}\n", stdout)
             },
             if stderr.is_empty() {
-                ""
+                String::new()
             } else {
-                &format!("stderr:\n{}", stderr)
+                format!("stderr:\n{}", stderr)
             }
         )))
     }
@@ -4,11 +4,14 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use serde_json::json;
 
-use crate::tool_registry::ToolRegistry;
+use crate::tool_registry::{
+    CommandOutputFormat, ToolBackendConfig, ToolBackendKind, ToolDescriptor, ToolRegistry,
+    ToolRegistryEntry, ToolSource,
+};
 use crate::tools::executor::{build_structured_invocation, execute_structured_invocation};
 use crate::tools::invocation::{
-    default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller, ToolContext,
-    ToolInvocationTransport,
+    default_path_grants, PathGrantAccessMode, ProcessPathGrant, ProcessPermissionPolicy,
+    ProcessTrustScope, ToolCaller, ToolContext, ToolInvocationTransport,
 };
 use crate::tools::path_guard::workspace_root;
 
@@ -107,3 +110,353 @@ fn exec_command_rejects_interactive_programs() {
         crate::tools::error::ToolError::InvalidInput(_, _)
     ));
 }
+
+fn register_command_tool(
+    registry: &mut ToolRegistry,
+    name: &str,
+    program: &str,
+    args: &[&str],
+    path_fields: &[&str],
+    output: CommandOutputFormat,
+) -> Result<(), String> {
+    registry.register(command_tool_entry(name, program, args, path_fields, output))
+}
+
+fn command_tool_entry(
+    name: &str,
+    program: &str,
+    args: &[&str],
+    path_fields: &[&str],
+    output: CommandOutputFormat,
+) -> ToolRegistryEntry {
+    ToolRegistryEntry {
+        descriptor: ToolDescriptor {
+            name: name.to_string(),
+            aliases: vec![],
+            description: "command template tool".to_string(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "words": {"type": "array", "items": {"type": "string"}},
+                    "word": {"type": "string"},
+                    "path": {"type": "string"}
+                }
+            }),
+            input_example: None,
+            output_schema: json!({}),
+            allowed_callers: vec![ToolCaller::AgentText],
+            backend_kind: ToolBackendKind::Command,
+            capabilities: vec![],
+            dangerous: false,
+            enabled: true,
+            default_allowlisted: true,
+            approval_required: false,
+            interop: None,
+            source: ToolSource::Manifest,
+        },
+        backend: ToolBackendConfig::Command {
+            program: program.to_string(),
+            args: args.iter().map(ToString::to_string).collect(),
+            cwd: None,
+            timeout_ms: Some(5_000),
+            path_fields: path_fields.iter().map(ToString::to_string).collect(),
+            write_path_fields: vec![],
+            success_exit_codes: vec![0],
+            output,
+        },
+    }
+}
+
+fn command_context(tool: &str) -> ToolContext {
+    let mut context = text_context();
+    context.permissions.allowed_tools = vec![tool.to_string()];
+    context
+}
+
+#[test]
+fn command_backend_expands_argv_template_into_text_output() {
+    let mut registry = ToolRegistry::new();
+    register_command_tool(
+        &mut registry,
+        "echo_words",
+        "echo",
+        &["start", "{words}", "--tag={word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect("register command tool");
+
+    let execution = execute_structured_invocation(
+        build_structured_invocation(
+            "echo_words",
+            json!({"words": ["alpha", "beta"], "word": "x"}),
+            None,
+        )
+        .expect("invocation"),
+        &command_context("echo_words"),
+        &registry,
+    )
+    .expect("command tool executes");
+    assert_eq!(
+        execution.result.output["output"]
+            .as_str()
+            .map(str::trim_end),
+        Some("start alpha beta --tag=x")
+    );
+
+    let execution = execute_structured_invocation(
+        build_structured_invocation("echo_words", json!({"words": []}), None).expect("invocation"),
+        &command_context("echo_words"),
+        &registry,
+    )
+    .expect("optional fields drop their arguments");
+    assert_eq!(
+        execution.result.output["output"]
+            .as_str()
+            .map(str::trim_end),
+        Some("start")
+    );
+}
+
+#[test]
+fn command_backend_rejects_flag_injection_unless_after_separator() {
+    let mut registry = ToolRegistry::new();
+    register_command_tool(
+        &mut registry,
+        "echo_word",
+        "echo",
+        &["{word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect("register command tool");
+    register_command_tool(
+        &mut registry,
+        "echo_word_separated",
+        "echo",
+        &["--", "{word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect("register separated command tool");
+
+    let err = execute_structured_invocation(
+        build_structured_invocation("echo_word", json!({"word": "-n"}), None).expect("invocation"),
+        &command_context("echo_word"),
+        &registry,
+    )
+    .expect_err("flag-like value denied");
+    assert!(matches!(
+        err,
+        crate::tools::error::ToolError::InvalidInput(_, _)
+    ));
+
+    execute_structured_invocation(
+        build_structured_invocation("echo_word_separated", json!({"word": "-n"}), None)
+            .expect("invocation"),
+        &command_context("echo_word_separated"),
+        &registry,
+    )
+    .expect("value after '--' is allowed");
+}
+
+#[test]
+fn command_backend_parses_json_stdout_from_granted_paths() {
+    let fixture = WorkspaceFixture::new("command_backend_json");
+    fs::write(fixture.absolute.join("data.json"), r#"{"count": 3}"#).expect("write data");
+    let mut registry = ToolRegistry::new();
+    register_command_tool(
+        &mut registry,
+        "cat_json",
+        "cat",
+        &["{path}"],
+        &["path"],
+        CommandOutputFormat::Json,
+    )
+    .expect("register command tool");
+
+    let execution = execute_structured_invocation(
+        build_structured_invocation(
+            "cat_json",
+            json!({"path": format!("{}/data.json", fixture.relative)}),
+            None,
+        )
+        .expect("invocation"),
+        &command_context("cat_json"),
+        &registry,
+    )
+    .expect("json command executes");
+    assert_eq!(execution.result.output, json!({"count": 3}));
+
+    let err = execute_structured_invocation(
+        build_structured_invocation("cat_json", json!({"path": "/etc/hostname"}), None)
+            .expect("invocation"),
+        &command_context("cat_json"),
+        &registry,
+    )
+    .expect_err("path outside the grants is denied");
+    assert!(matches!(
+        err,
+        crate::tools::error::ToolError::PolicyDenied(_, _)
+    ));
+}
+
+#[test]
+fn command_backend_checks_write_path_fields_against_write_grants() {
+    let fixture = WorkspaceFixture::new("command_backend_write");
+    let mut entry = command_tool_entry(
+        "touch_path",
+        "touch",
+        &["{path}"],
+        &[],
+        CommandOutputFormat::Text,
+    );
+    if let ToolBackendConfig::Command {
+        write_path_fields, ..
+    } = &mut entry.backend
+    {
+        *write_path_fields = vec!["path".to_string()];
+    }
+    let mut registry = ToolRegistry::new();
+    registry.register(entry).expect("register command tool");
+    let target = format!("{}/created.txt", fixture.relative);
+
+    let mut read_only = command_context("touch_path");
+    read_only.permissions.path_grants = vec![ProcessPathGrant {
+        root: ".".to_string(),
+        access_mode: PathGrantAccessMode::ReadOnly,
+        capsule: None,
+        label: None,
+    }];
+    let err = execute_structured_invocation(
+        build_structured_invocation("touch_path", json!({"path": target}), None)
+            .expect("invocation"),
+        &read_only,
+        &registry,
+    )
+    .expect_err("read-only grant cannot back a write path");
+    assert!(matches!(
+        err,
+        crate::tools::error::ToolError::PolicyDenied(_, _)
+    ));
+    assert!(!fixture.absolute.join("created.txt").exists());
+
+    execute_structured_invocation(
+        build_structured_invocation("touch_path", json!({"path": target}), None)
+            .expect("invocation"),
+        &command_context("touch_path"),
+        &registry,
+    )
+    .expect("writable grant allows the write path");
+    assert!(fixture.absolute.join("created.txt").exists());
+
+    let mut both = command_tool_entry(
+        "both_path",
+        "touch",
+        &["{path}"],
+        &["path"],
+        CommandOutputFormat::Text,
+    );
+    if let ToolBackendConfig::Command {
+        write_path_fields, ..
+    } = &mut both.backend
+    {
+        *write_path_fields = vec!["path".to_string()];
+    }
+    let err = registry
+        .register(both)
+        .expect_err("a field has a single mode");
+    assert!(err.contains("both a read and a write path"), "{err}");
+}
+
+#[test]
+fn command_tools_are_never_replayed_live() {
+    let mut registry = ToolRegistry::new();
+    register_command_tool(
+        &mut registry,
+        "echo_word",
+        "echo",
+        &["{word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect("register command tool");
+
+    let descriptor = &registry.get("echo_word").expect("registered").descriptor;
+    assert!(descriptor.dangerous);
+    assert!(!descriptor.default_allowlisted);
+    assert!(descriptor
+        .capabilities
+        .iter()
+        .any(|capability| capability == "exec"));
+
+    let replay = command_context("echo_word")
+        .permissions
+        .derive_replay_safe(&registry);
+    assert!(!replay.allows_tool("echo_word"));
+}
+
+#[test]
+fn command_tools_invalidate_cached_workspace_reads() {
+    let mut registry = ToolRegistry::new();
+    register_command_tool(
+        &mut registry,
+        "echo_word",
+        "echo",
+        &["{word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect("register command tool");
+
+    assert!(crate::tools::result_cache::mutates_workspace(
+        registry.get("echo_word").expect("registered")
+    ));
+}
+
+#[test]
+fn command_backend_registration_is_validated() {
+    let mut registry = ToolRegistry::new();
+    let err = register_command_tool(
+        &mut registry,
+        "shell_tool",
+        "/bin/sh",
+        &["-c", "{word}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect_err("shell programs are refused");
+    assert!(err.contains("shell"), "{err}");
+
+    let err = register_command_tool(
+        &mut registry,
+        "undeclared_tool",
+        "echo",
+        &["{missing}"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect_err("undeclared placeholder is refused");
+    assert!(err.contains("{missing}"), "{err}");
+
+    let err = register_command_tool(
+        &mut registry,
+        "unclosed_tool",
+        "echo",
+        &["{word"],
+        &[],
+        CommandOutputFormat::Text,
+    )
+    .expect_err("unclosed placeholder is refused");
+    assert!(err.contains("unclosed"), "{err}");
+
+    register_command_tool(
+        &mut registry,
+        "braces_tool",
+        "echo",
+        &["{{literal}}"],
+        &[],
+        CommandOutputFormat::JsonLines,
+    )
+    .expect("escaped braces are literal");
+}
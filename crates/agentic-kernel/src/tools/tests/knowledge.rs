@@ -0,0 +1,243 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use super::{search_knowledge_base, KbSearchInput, KnowledgeBaseIndex, RefreshStats};
+use crate::backend::embeddings::EmbeddingProvider;
+use crate::config::KnowledgeBaseConfig;
+use crate::tools::error::ToolError;
+use crate::tools::invocation::{
+    PathGrantAccessMode, ProcessPathGrant, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller,
+    ToolContext, ToolInvocationTransport,
+};
+use crate::tools::path_guard::{ensure_path_access, workspace_root, PathAccessIntent};
+
+struct KnowledgeFixture {
+    absolute: PathBuf,
+    relative: String,
+}
+
+impl KnowledgeFixture {
+    fn new(prefix: &str) -> Self {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_nanos();
+        let relative = format!("{prefix}_{unique}");
+        let absolute = workspace_root().expect("workspace root").join(&relative);
+        fs::create_dir_all(absolute.join("docs")).expect("create docs");
+        fs::create_dir_all(absolute.join("private")).expect("create private");
+        Self { absolute, relative }
+    }
+
+    fn write(&self, child: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.absolute.join(child);
+        fs::write(&path, content).expect("write fixture file");
+        path
+    }
+}
+
+impl Drop for KnowledgeFixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.absolute);
+    }
+}
+
+fn scoped_context(root: String) -> ToolContext {
+    ToolContext {
+        pid: Some(1),
+        session_id: Some("knowledge-tools".to_string()),
+        caller: ToolCaller::AgentText,
+        permissions: ProcessPermissionPolicy {
+            trust_scope: ProcessTrustScope::InteractiveChat,
+            actions_allowed: false,
+            allowed_tools: vec!["kb_search".to_string()],
+            path_grants: vec![ProcessPathGrant {
+                root: root.clone(),
+                access_mode: PathGrantAccessMode::ReadOnly,
+                capsule: Some("workspace".to_string()),
+                label: Some("Knowledge test root".to_string()),
+            }],
+            path_scopes: vec![root],
+        },
+        transport: ToolInvocationTransport::Structured,
+        call_id: None,
+    }
+}
+
+fn test_config() -> KnowledgeBaseConfig {
+    KnowledgeBaseConfig {
+        chunk_lines: 4,
+        chunk_overlap_lines: 1,
+        refresh_interval_ms: 0,
+        ..KnowledgeBaseConfig::default()
+    }
+}
+
+fn query(text: &str) -> KbSearchInput {
+    KbSearchInput {
+        query: text.to_string(),
+        path: None,
+        max_results: Some(5),
+    }
+}
+
+#[test]
+fn kb_search_returns_chunks_with_line_ranges_inside_the_grants_only() {
+    let fixture = KnowledgeFixture::new("kb_grants");
+    fixture.write(
+        "docs/guide.md",
+        "# Guide\n\nIntro text.\nMore intro.\n\n## Deploy\nThe deploy pipeline promotes builds to staging.\nRollbacks restore the previous build.\n",
+    );
+    fixture.write(
+        "private/notes.md",
+        "The deploy pipeline secret token lives here.\n",
+    );
+    let mut index = KnowledgeBaseIndex::new(test_config(), None);
+
+    let docs = scoped_context(format!("{}/docs", fixture.relative));
+    let output = search_knowledge_base(&mut index, query("deploy pipeline staging"), &docs)
+        .expect("kb search");
+    assert!(!output.results.is_empty());
+    let top = &output.results[0];
+    assert_eq!(top.path, format!("{}/docs/guide.md", fixture.relative));
+    assert!(top.start_line <= 7 && top.end_line >= 7);
+    assert!(top.chunk_id.starts_with("kb-"));
+    assert!(output
+        .results
+        .iter()
+        .all(|hit| !hit.path.contains("private")));
+
+    // The whole fixture is indexed, yet the docs-only grant never sees it.
+    let everything = [fixture.absolute.clone()];
+    let workspace = workspace_root().expect("workspace root");
+    let hits = index.search("secret token", &everything, 5, &|path: &Path| {
+        ensure_path_access(&workspace, path, &docs, PathAccessIntent::Read).is_ok()
+    });
+    assert!(hits.hits.is_empty());
+    let unrestricted = index.search("secret token", &everything, 5, &|_: &Path| true);
+    assert_eq!(unrestricted.hits.len(), 1);
+
+    let outside = KbSearchInput {
+        path: Some(format!("{}/private", fixture.relative)),
+        ..query("secret")
+    };
+    assert!(matches!(
+        search_knowledge_base(&mut index, outside, &docs),
+        Err(ToolError::ExecutionFailed(_, _))
+    ));
+}
+
+#[test]
+fn refresh_reindexes_only_changed_files() {
+    let fixture = KnowledgeFixture::new("kb_refresh");
+    let root = fixture.absolute.join("docs");
+    let guide = fixture.write("docs/guide.md", "alpha\nbeta\n");
+    fixture.write(
+        "docs/data.csv",
+        "name,port\napi,8080\ndb,5432\nweb,80\ncache,6379\n",
+    );
+    fixture.write("docs/logo.png", b"\x89PNG\r\n\x1a\n\0\0\0binary");
+    let mut index = KnowledgeBaseIndex::new(test_config(), None);
+
+    assert_eq!(
+        index.refresh(&root),
+        RefreshStats {
+            indexed: 2,
+            unchanged: 0,
+            removed: 0
+        }
+    );
+    assert_eq!(index.refresh(&root).indexed, 0);
+
+    // Same bytes rewritten: new mtime, same hash, nothing re-chunked.
+    fs::write(&guide, "alpha\nbeta\n").expect("rewrite guide");
+    assert_eq!(index.refresh(&root).indexed, 0);
+    fs::write(&guide, "alpha\ngamma ray\n").expect("edit guide");
+    assert_eq!(index.refresh(&root).indexed, 1);
+    let hits = index.search("gamma", std::slice::from_ref(&root), 5, &|_: &Path| true);
+    assert_eq!(hits.hits.len(), 1);
+
+    // CSV chunks after the first repeat the header row.
+    let csv = index.search("cache", std::slice::from_ref(&root), 5, &|_: &Path| true);
+    assert!(csv.hits[0].chunk.text.starts_with("name,port\n"));
+
+    fs::remove_file(&guide).expect("remove guide");
+    assert_eq!(index.refresh(&root).removed, 1);
+    assert!(index
+        .search("gamma", &[root], 5, &|_: &Path| true)
+        .hits
+        .is_empty());
+}
+
+#[test]
+fn refresh_drops_files_that_grow_past_the_size_limit() {
+    let fixture = KnowledgeFixture::new("kb_oversized");
+    let root = fixture.absolute.join("docs");
+    let notes = fixture.write("docs/notes.md", "short\n");
+    let mut index = KnowledgeBaseIndex::new(
+        KnowledgeBaseConfig {
+            max_file_bytes: 16,
+            ..test_config()
+        },
+        None,
+    );
+
+    assert_eq!(index.refresh(&root).indexed, 1);
+    fs::write(&notes, "grown well beyond sixteen bytes\n").expect("grow notes");
+    assert_eq!(
+        index.refresh(&root),
+        RefreshStats {
+            indexed: 0,
+            unchanged: 0,
+            removed: 1
+        }
+    );
+    assert_eq!(index.refresh(&root), RefreshStats::default());
+}
+
+struct SynonymEmbedder;
+
+impl EmbeddingProvider for SynonymEmbedder {
+    fn id(&self) -> String {
+        "synonyms".to_string()
+    }
+
+    fn embed(&mut self, inputs: &[String]) -> Result<Vec<Vec<f32>>, String> {
+        Ok(inputs
+            .iter()
+            .map(|input| {
+                let text = input.to_ascii_lowercase();
+                vec![
+                    f32::from(text.contains("automobile") || text.contains("car")),
+                    f32::from(text.contains("invoice") || text.contains("billing")),
+                ]
+            })
+            .collect())
+    }
+}
+
+#[test]
+fn embeddings_find_chunks_without_shared_terms() {
+    let fixture = KnowledgeFixture::new("kb_hybrid");
+    let root = fixture.absolute.join("docs");
+    fixture.write("docs/fleet.md", "Every car is serviced yearly.\n");
+    fixture.write("docs/finance.md", "Billing runs monthly.\n");
+
+    let mut lexical = KnowledgeBaseIndex::new(test_config(), None);
+    assert!(lexical
+        .search(
+            "automobile maintenance",
+            std::slice::from_ref(&root),
+            5,
+            &|_: &Path| { true }
+        )
+        .hits
+        .is_empty());
+
+    let mut hybrid = KnowledgeBaseIndex::new(test_config(), Some(Box::new(SynonymEmbedder)));
+    let search = hybrid.search("automobile maintenance", &[root], 5, &|_: &Path| true);
+    assert!(search.hybrid);
+    assert_eq!(search.hits.len(), 1);
+    assert!(search.hits[0].chunk.path.ends_with("fleet.md"));
+}
@@ -0,0 +1,223 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::ToolManifestLoader;
+use crate::tool_registry::{CommandOutputFormat, ToolBackendConfig, ToolRegistry, ToolSource};
+
+const WEATHER_TOML: &str = r#"
+name = "weather_lookup"
+description = "Current weather for a city."
+capabilities = ["network"]
+
+[input_schema]
+type = "object"
+required = ["city"]
+
+[input_schema.properties.city]
+type = "string"
+
+[backend]
+kind = "remote_http"
+url = "http://127.0.0.1:9000/weather"
+"#;
+
+#[test]
+fn loads_toml_and_json_manifests_as_manifest_tools() {
+    let dir = make_temp_dir("agenticos_tool_manifests_load");
+    fs::write(dir.join("weather.toml"), WEATHER_TOML).expect("write toml manifest");
+    fs::write(
+        dir.join("resize.json"),
+        r#"{"name": "image_resize", "description": "Resize an image.",
+            "backend": {"kind": "wasm", "module": "resize.wasm"}}"#,
+    )
+    .expect("write json manifest");
+    fs::write(dir.join("notes.txt"), "ignored").expect("write unrelated file");
+
+    let mut registry = ToolRegistry::new();
+    let mut loader = ToolManifestLoader::new(dir.clone());
+    assert!(loader.reload(&mut registry));
+
+    let weather = registry.get("weather_lookup").expect("weather tool");
+    assert_eq!(weather.descriptor.source, ToolSource::Manifest);
+    match &weather.backend {
+        ToolBackendConfig::RemoteHttp {
+            method, timeout_ms, ..
+        } => {
+            assert_eq!(method, "POST");
+            assert!(*timeout_ms > 0);
+        }
+        other => panic!("unexpected backend: {other:?}"),
+    }
+    let resize = registry.get("image_resize").expect("wasm tool");
+    match &resize.backend {
+        ToolBackendConfig::Wasm { module, export } => {
+            assert_eq!(PathBuf::from(module), dir.join("resize.wasm"));
+            assert_eq!(export, "run");
+        }
+        other => panic!("unexpected backend: {other:?}"),
+    }
+
+    let status = loader.status_view();
+    assert_eq!(status.manifests.len(), 2);
+    assert!(status.manifests.iter().all(|view| view.error.is_none()));
+    assert!(
+        !loader.reload(&mut registry),
+        "unchanged directory is a no-op"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn loads_command_backend_manifest_with_defaults() {
+    let dir = make_temp_dir("agenticos_tool_manifests_command");
+    fs::write(
+        dir.join("rg.toml"),
+        r#"
+name = "ripgrep_search"
+description = "Search the workspace with ripgrep."
+input_schema = { type = "object", required = ["pattern"], properties = { pattern = { type = "string" }, path = { type = "string" } } }
+
+[backend]
+kind = "command"
+program = "rg"
+args = ["--json", "--", "{pattern}", "{path}"]
+path_fields = ["path"]
+success_exit_codes = [0, 1]
+output = "json_lines"
+"#,
+    )
+    .expect("write command manifest");
+
+    let mut registry = ToolRegistry::new();
+    let mut loader = ToolManifestLoader::new(dir.clone());
+    assert!(loader.reload(&mut registry));
+    let entry = registry.get("ripgrep_search").expect("command tool");
+    match &entry.backend {
+        ToolBackendConfig::Command {
+            program,
+            timeout_ms,
+            output,
+            success_exit_codes,
+            ..
+        } => {
+            assert_eq!(program, "rg");
+            assert_eq!(*timeout_ms, None);
+            assert_eq!(*output, CommandOutputFormat::JsonLines);
+            assert_eq!(success_exit_codes, &vec![0, 1]);
+        }
+        other => panic!("unexpected backend: {other:?}"),
+    }
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn reports_invalid_manifests_and_follows_file_changes() {
+    let dir = make_temp_dir("agenticos_tool_manifests_reload");
+    let path = dir.join("weather.toml");
+    fs::write(&path, WEATHER_TOML).expect("write manifest");
+    fs::write(
+        dir.join("host.json"),
+        r#"{"name": "sneaky", "description": "x", "backend": {"kind": "host", "executor": "python"}}"#,
+    )
+    .expect("write host manifest");
+
+    let mut registry = ToolRegistry::with_builtins();
+    let mut loader = ToolManifestLoader::new(dir.clone());
+    loader.reload(&mut registry);
+    assert!(registry.get("sneaky").is_none());
+    let status = loader.status_view();
+    let host_error = status
+        .manifests
+        .iter()
+        .find(|view| view.path.ends_with("host.json"))
+        .and_then(|view| view.error.clone())
+        .expect("host manifest is rejected");
+    assert!(host_error.contains("host"), "{host_error}");
+
+    fs::write(
+        &path,
+        WEATHER_TOML.replace("weather_lookup", "weather_forecast_lookup"),
+    )
+    .expect("rewrite manifest");
+    assert!(loader.reload(&mut registry));
+    assert!(registry.get("weather_lookup").is_none());
+    assert_eq!(
+        registry
+            .get("weather_forecast_lookup")
+            .expect("renamed tool")
+            .descriptor
+            .source,
+        ToolSource::Manifest
+    );
+
+    fs::remove_file(&path).expect("remove manifest");
+    assert!(loader.reload(&mut registry));
+    assert!(registry.get("weather_forecast_lookup").is_none());
+    assert_eq!(loader.status_view().manifests.len(), 1);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn manifest_cannot_shadow_builtin_tools() {
+    let dir = make_temp_dir("agenticos_tool_manifests_conflict");
+    fs::write(
+        dir.join("calc.toml"),
+        WEATHER_TOML.replace("weather_lookup", "calc"),
+    )
+    .expect("write manifest");
+
+    let mut registry = ToolRegistry::with_builtins();
+    let mut loader = ToolManifestLoader::new(dir.clone());
+    assert!(!loader.reload(&mut registry));
+    assert_eq!(
+        registry
+            .get("calc")
+            .expect("builtin calc")
+            .descriptor
+            .source,
+        ToolSource::BuiltIn
+    );
+    let status = loader.status_view();
+    assert!(status.manifests[0]
+        .error
+        .as_deref()
+        .is_some_and(|error| error.contains("already registered")));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn oversized_manifest_is_rejected_without_parsing() {
+    let dir = make_temp_dir("agenticos_tool_manifests_oversized");
+    let padding = "#".repeat(300 * 1024);
+    fs::write(
+        dir.join("huge.toml"),
+        format!("{WEATHER_TOML}\n{padding}\n"),
+    )
+    .expect("write oversized manifest");
+
+    let mut registry = ToolRegistry::with_builtins();
+    let mut loader = ToolManifestLoader::new(dir.clone());
+    loader.reload(&mut registry);
+    assert!(registry.get("weather_lookup").is_none());
+    assert!(loader.status_view().manifests[0]
+        .error
+        .as_deref()
+        .is_some_and(|error| error.contains("byte limit")));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn make_temp_dir(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}_{}_{}", std::process::id(), timestamp));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
@@ -0,0 +1,295 @@
+use serde_json::json;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::{
+    execute_memory_tool, startup_memory_digest, workflow_scope_key, MemoryScopeContext,
+    MemoryScopeKind,
+};
+use crate::config::LongTermMemoryConfig;
+use crate::storage::StorageService;
+use crate::tool_registry::ToolRegistry;
+use crate::tools::error::ToolError;
+use crate::tools::governance::govern_native_tool_execution;
+use crate::tools::invocation::{
+    default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller, ToolContext,
+    ToolInvocation, ToolInvocationTransport,
+};
+use crate::tools::SyscallRateMap;
+
+fn scopes(pid: u64, session_id: &str) -> MemoryScopeContext {
+    MemoryScopeContext {
+        user: "local".to_string(),
+        workspace: "/repo".to_string(),
+        agent: None,
+        workflow: None,
+        session_id: Some(session_id.to_string()),
+        turn_id: Some(3),
+        pid: Some(pid),
+    }
+}
+
+fn call(
+    storage: &mut StorageService,
+    scopes: &MemoryScopeContext,
+    name: &str,
+    input: serde_json::Value,
+) -> Result<serde_json::Value, ToolError> {
+    execute_memory_tool(
+        storage,
+        &ToolInvocation::new(name, input, None).expect("invocation"),
+        scopes,
+        &LongTermMemoryConfig::default(),
+    )
+    .map(|result| result.output)
+}
+
+#[test]
+fn memory_tools_are_registered_as_builtins() {
+    let registry = ToolRegistry::with_builtins();
+    for name in ["memory_remember", "memory_recall", "memory_forget"] {
+        let entry = registry.get(name).expect("memory builtin");
+        assert!(entry
+            .descriptor
+            .capabilities
+            .contains(&"memory".to_string()));
+    }
+}
+
+#[test]
+fn remembered_facts_are_recalled_by_later_sessions_with_provenance() {
+    let dir = make_temp_dir("agenticos_memory_tools");
+    let db_path = dir.join("agenticos.db");
+    {
+        let mut storage = StorageService::open(&db_path).expect("open storage");
+        let first = scopes(7, "sess-a");
+        call(
+            &mut storage,
+            &first,
+            "memory_remember",
+            json!({"content": "Deploys go through the staging branch", "tags": ["release"]}),
+        )
+        .expect("remember workspace fact");
+        call(
+            &mut storage,
+            &first,
+            "memory_remember",
+            json!({"content": "The operator prefers short answers", "kind": "note", "scope": "user"}),
+        )
+        .expect("remember user note");
+    }
+
+    let mut storage = StorageService::open(&db_path).expect("reopen storage");
+    let later = scopes(12, "sess-b");
+    let recalled = call(
+        &mut storage,
+        &later,
+        "memory_recall",
+        json!({"query": "how does a release deploy work?"}),
+    )
+    .expect("recall");
+    let memories = recalled["memories"].as_array().expect("memories");
+    assert_eq!(memories.len(), 1);
+    assert_eq!(
+        memories[0]["content"],
+        "Deploys go through the staging branch"
+    );
+    assert_eq!(memories[0]["scope"], "workspace");
+    assert_eq!(memories[0]["source_session_id"], "sess-a");
+    assert_eq!(memories[0]["source_turn_id"], 3);
+    assert_eq!(memories[0]["source_pid"], 7);
+
+    let only_user = call(
+        &mut storage,
+        &later,
+        "memory_recall",
+        json!({"query": "", "scope": "user"}),
+    )
+    .expect("recall user scope");
+    assert_eq!(only_user["memories"][0]["kind"], "note");
+
+    let memory_id = memories[0]["memory_id"].as_i64().expect("memory id");
+    let mut other_workspace = later.clone();
+    other_workspace.workspace = "/elsewhere".to_string();
+    assert!(matches!(
+        call(
+            &mut storage,
+            &other_workspace,
+            "memory_forget",
+            json!({"memory_id": memory_id}),
+        ),
+        Err(ToolError::InvalidInput(_, _))
+    ));
+    call(
+        &mut storage,
+        &later,
+        "memory_forget",
+        json!({"memory_id": memory_id}),
+    )
+    .expect("forget");
+    let after = call(
+        &mut storage,
+        &later,
+        "memory_recall",
+        json!({"query": "deploy staging"}),
+    )
+    .expect("recall after forget");
+    assert_eq!(after["memories"], json!([]));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn agent_and_workflow_scopes_need_a_workflow_task() {
+    let dir = make_temp_dir("agenticos_memory_scopes");
+    let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+    let interactive = scopes(3, "sess-chat");
+    assert!(matches!(
+        call(
+            &mut storage,
+            &interactive,
+            "memory_remember",
+            json!({"content": "review checklist", "scope": "agent"}),
+        ),
+        Err(ToolError::InvalidInput(_, _))
+    ));
+
+    let mut task = interactive.clone();
+    task.agent = Some("reviewer".to_string());
+    task.workflow = Some(workflow_scope_key([
+        ("review", Some("reviewer")),
+        ("build", None),
+    ]));
+    call(
+        &mut storage,
+        &task,
+        "memory_remember",
+        json!({"content": "review checklist lives in docs/review.md", "scope": "agent"}),
+    )
+    .expect("remember agent note");
+    assert_eq!(
+        task.scope(MemoryScopeKind::Workflow)
+            .expect("workflow scope")
+            .scope_key,
+        workflow_scope_key([("build", None), ("review", Some("reviewer"))])
+    );
+
+    let from_chat = call(
+        &mut storage,
+        &interactive,
+        "memory_recall",
+        json!({"query": "review checklist"}),
+    )
+    .expect("recall from chat");
+    assert_eq!(from_chat["memories"], json!([]));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn startup_digest_lists_the_memories_matching_the_first_prompt() {
+    let dir = make_temp_dir("agenticos_memory_digest");
+    let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+    let context = scopes(1, "sess-a");
+    for content in [
+        "Deploys go through the staging branch",
+        "The database runs on port 5433",
+    ] {
+        call(
+            &mut storage,
+            &context,
+            "memory_remember",
+            json!({"content": content}),
+        )
+        .expect("remember");
+    }
+
+    let config = LongTermMemoryConfig::default();
+    let (digest, count) = startup_memory_digest(
+        &mut storage,
+        "Which port does the database use?",
+        &context.visible_scopes(),
+        &config,
+    )
+    .expect("digest")
+    .expect("matching memories");
+    assert_eq!(count, 1);
+    assert!(digest.contains("port 5433"));
+    assert!(!digest.contains("staging"));
+
+    assert!(startup_memory_digest(
+        &mut storage,
+        "Write a haiku",
+        &context.visible_scopes(),
+        &config
+    )
+    .expect("digest")
+    .is_none());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn kernel_served_memory_calls_are_rate_limited() {
+    let dir = make_temp_dir("agenticos_memory_governed");
+    let mut storage = StorageService::open(dir.join("agenticos.db")).expect("open storage");
+    let registry = ToolRegistry::with_builtins();
+    let invocation = ToolInvocation::new("memory_recall", json!({"query": "deploys"}), None)
+        .expect("invocation");
+    let context = ToolContext {
+        pid: Some(9),
+        session_id: None,
+        caller: ToolCaller::AgentText,
+        permissions: ProcessPermissionPolicy {
+            trust_scope: ProcessTrustScope::InteractiveChat,
+            actions_allowed: false,
+            allowed_tools: vec!["memory_recall".to_string()],
+            path_grants: default_path_grants(),
+            path_scopes: vec![".".to_string()],
+        },
+        transport: ToolInvocationTransport::Text,
+        call_id: None,
+    };
+    let memory_scopes = scopes(9, "sess-governed");
+    let mut rate_map = SyscallRateMap::new();
+    let mut executed = 0usize;
+
+    let limited = (0..10_000)
+        .find_map(|_| {
+            let result = govern_native_tool_execution(
+                &invocation,
+                &context,
+                &registry,
+                9,
+                &mut rate_map,
+                || {
+                    executed += 1;
+                    execute_memory_tool(
+                        &mut storage,
+                        &invocation,
+                        &memory_scopes,
+                        &LongTermMemoryConfig::default(),
+                    )
+                },
+            );
+            (!result.success).then_some(result)
+        })
+        .expect("the rate limit stops the calls");
+
+    assert!(executed > 0);
+    assert_eq!(limited.error_kind.as_deref(), Some("rate_limited"));
+    assert!(limited.should_kill_process);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+fn make_temp_dir(prefix: &str) -> PathBuf {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("system time")
+        .as_nanos();
+    let dir = std::env::temp_dir().join(format!("{prefix}_{}_{}", std::process::id(), timestamp));
+    fs::create_dir_all(&dir).expect("create temp dir");
+    dir
+}
@@ -0,0 +1,374 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde_json::json;
+
+use super::{cache_key, cache_policy_for, mutates_workspace, ToolCachePolicy, ToolResultCache};
+use crate::config::ToolResultCacheConfig;
+use crate::tool_registry::{
+    ToolBackendConfig, ToolBackendKind, ToolDescriptor, ToolInteropDescriptor, ToolInteropHints,
+    ToolRegistry, ToolRegistryEntry, ToolSource,
+};
+use crate::tools::api::ToolResult;
+use crate::tools::governance::govern_tool_execution;
+use crate::tools::invocation::{
+    default_path_grants, ProcessPermissionPolicy, ProcessTrustScope, ToolCaller, ToolContext,
+    ToolInvocation, ToolInvocationTransport,
+};
+use crate::tools::path_guard::workspace_root;
+use crate::tools::SyscallRateMap;
+
+struct WorkspaceFixture {
+    absolute: PathBuf,
+    relative: String,
+}
+
+impl WorkspaceFixture {
+    fn new(prefix: &str) -> Self {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_else(|_| Duration::from_secs(0))
+            .as_nanos();
+        let relative = format!("{prefix}_{unique}");
+        let absolute = workspace_root().expect("workspace root").join(&relative);
+        fs::create_dir_all(&absolute).expect("create fixture directory");
+        Self { absolute, relative }
+    }
+
+    fn relative_path(&self, name: &str) -> String {
+        format!("{}/{}", self.relative, name)
+    }
+}
+
+impl Drop for WorkspaceFixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.absolute);
+    }
+}
+
+fn text_context() -> ToolContext {
+    ToolContext {
+        pid: Some(7),
+        session_id: None,
+        caller: ToolCaller::AgentText,
+        permissions: ProcessPermissionPolicy {
+            trust_scope: ProcessTrustScope::InteractiveChat,
+            actions_allowed: false,
+            allowed_tools: vec!["read_file_range".to_string(), "get_time".to_string()],
+            path_grants: default_path_grants(),
+            path_scopes: vec![".".to_string()],
+        },
+        transport: ToolInvocationTransport::Text,
+        call_id: None,
+    }
+}
+
+fn remote_entry(name: &str, hints: ToolInteropHints) -> ToolRegistryEntry {
+    ToolRegistryEntry {
+        descriptor: ToolDescriptor {
+            name: name.to_string(),
+            aliases: vec![],
+            description: "remote tool".to_string(),
+            input_schema: json!({"type": "object"}),
+            input_example: None,
+            output_schema: json!({"type": "object"}),
+            allowed_callers: vec![ToolCaller::AgentText],
+            backend_kind: ToolBackendKind::RemoteHttp,
+            capabilities: vec!["mcp".to_string()],
+            dangerous: false,
+            enabled: true,
+            default_allowlisted: true,
+            approval_required: false,
+            interop: Some(ToolInteropDescriptor {
+                provider: "mcp".to_string(),
+                server_id: "docs".to_string(),
+                server_label: None,
+                transport: "http".to_string(),
+                target_name: name.to_string(),
+                trust_level: "trusted".to_string(),
+                auth_mode: "none".to_string(),
+                default_allowlisted: true,
+                approval_required: false,
+                hints,
+            }),
+            source: ToolSource::Runtime,
+        },
+        backend: ToolBackendConfig::RemoteHttp {
+            url: "http://127.0.0.1:9/tool".to_string(),
+            method: "POST".to_string(),
+            timeout_ms: 1_000,
+            headers: Default::default(),
+        },
+    }
+}
+
+fn text_result(text: &str) -> ToolResult {
+    ToolResult {
+        output: json!({ "output": text }),
+        display_text: Some(text.to_string()),
+        warnings: Vec::new(),
+    }
+}
+
+fn plain_policy(ttl_ms: i64) -> ToolCachePolicy {
+    ToolCachePolicy {
+        ttl_ms,
+        max_entry_bytes: 1_024,
+        path_fields: Vec::new(),
+    }
+}
+
+#[test]
+fn repeated_workspace_read_is_served_from_cache_until_the_file_changes() {
+    let fixture = WorkspaceFixture::new("result_cache_read");
+    let notes = fixture.absolute.join("notes.txt");
+    fs::write(&notes, "one\ntwo\n").expect("write notes");
+
+    let registry = ToolRegistry::with_builtins();
+    let mut rates = SyscallRateMap::new();
+    let invocation = ToolInvocation {
+        name: "read_file_range".to_string(),
+        input: json!({ "path": fixture.relative_path("notes.txt"), "start_line": 1, "end_line": 5 }),
+        call_id: None,
+    };
+
+    let first = govern_tool_execution(&invocation, &text_context(), &registry, 7, &mut rates);
+    assert!(first.success, "{}", first.output);
+    assert!(!first.cache_hit);
+
+    let second = govern_tool_execution(&invocation, &text_context(), &registry, 7, &mut rates);
+    assert!(second.cache_hit);
+    assert_eq!(second.output, first.output);
+    assert_eq!(second.output_json, first.output_json);
+
+    fs::write(&notes, "one\ntwo\nthree\n").expect("rewrite notes");
+    let third = govern_tool_execution(&invocation, &text_context(), &registry, 7, &mut rates);
+    assert!(!third.cache_hit);
+    assert!(third.output.contains("three"), "{}", third.output);
+}
+
+#[test]
+fn tools_without_policy_are_not_cached() {
+    let registry = ToolRegistry::with_builtins();
+    let mut rates = SyscallRateMap::new();
+    let invocation = ToolInvocation {
+        name: "get_time".to_string(),
+        input: json!({}),
+        call_id: None,
+    };
+
+    for _ in 0..2 {
+        let result = govern_tool_execution(&invocation, &text_context(), &registry, 8, &mut rates);
+        assert!(result.success, "{}", result.output);
+        assert!(!result.cache_hit);
+    }
+    assert_eq!(rates.result_cache.len(), 0);
+}
+
+#[test]
+fn cache_key_ignores_object_key_order_but_not_tool_name() {
+    let policy = plain_policy(60_000);
+    let context = text_context();
+    let left = cache_key(
+        "web_fetch",
+        &json!({"url": "https://example.com", "max_chars": 10}),
+        &policy,
+        &context,
+    );
+    let right = cache_key(
+        "web_fetch",
+        &json!({"max_chars": 10, "url": "https://example.com"}),
+        &policy,
+        &context,
+    );
+    let other_tool = cache_key(
+        "http_get_json",
+        &json!({"url": "https://example.com", "max_chars": 10}),
+        &policy,
+        &context,
+    );
+
+    assert!(left.is_some());
+    assert_eq!(left, right);
+    assert_ne!(left, other_tool);
+}
+
+#[test]
+fn cache_key_refuses_paths_outside_the_workspace() {
+    let policy = ToolCachePolicy {
+        path_fields: vec!["path".to_string()],
+        ..plain_policy(60_000)
+    };
+
+    assert!(cache_key(
+        "read_file",
+        &json!({"path": "../../etc/passwd"}),
+        &policy,
+        &text_context()
+    )
+    .is_none());
+}
+
+#[test]
+fn entries_expire_and_respect_size_limits() {
+    let config = ToolResultCacheConfig::default();
+    let mut cache = ToolResultCache::new();
+
+    cache.store(
+        "ttl".to_string(),
+        &text_result("a"),
+        &plain_policy(100),
+        &config,
+        1_000,
+    );
+    assert!(cache.lookup("ttl", 1_050).is_some());
+    assert!(cache.lookup("ttl", 1_100).is_none());
+    assert_eq!(cache.len(), 0);
+    assert_eq!(cache.total_bytes(), 0);
+
+    let oversized = text_result(&"x".repeat(2_048));
+    cache.store(
+        "big".to_string(),
+        &oversized,
+        &plain_policy(60_000),
+        &config,
+        1_000,
+    );
+    assert!(cache.lookup("big", 1_000).is_none());
+}
+
+#[test]
+fn least_recently_used_entry_is_evicted_first() {
+    let config = ToolResultCacheConfig {
+        max_entries: 2,
+        ..ToolResultCacheConfig::default()
+    };
+    let policy = plain_policy(60_000);
+    let mut cache = ToolResultCache::new();
+
+    cache.store("a".to_string(), &text_result("a"), &policy, &config, 0);
+    cache.store("b".to_string(), &text_result("b"), &policy, &config, 0);
+    assert!(cache.lookup("a", 1).is_some());
+    cache.store("c".to_string(), &text_result("c"), &policy, &config, 2);
+
+    assert_eq!(cache.len(), 2);
+    assert!(cache.lookup("a", 3).is_some());
+    assert!(cache.lookup("b", 3).is_none());
+    assert!(cache.lookup("c", 3).is_some());
+}
+
+#[test]
+fn workspace_writes_drop_fingerprinted_entries_only() {
+    let config = ToolResultCacheConfig::default();
+    let mut cache = ToolResultCache::new();
+    let workspace_policy = ToolCachePolicy {
+        path_fields: vec!["path".to_string()],
+        ..plain_policy(60_000)
+    };
+    cache.store(
+        "read".to_string(),
+        &text_result("file"),
+        &workspace_policy,
+        &config,
+        0,
+    );
+    cache.store(
+        "fetch".to_string(),
+        &text_result("page"),
+        &plain_policy(60_000),
+        &config,
+        0,
+    );
+
+    let registry = ToolRegistry::with_builtins();
+    assert!(mutates_workspace(
+        registry.get("write_file").expect("write_file")
+    ));
+    assert!(!mutates_workspace(
+        registry.get("read_file_range").expect("read_file_range")
+    ));
+
+    cache.invalidate_workspace();
+    assert!(cache.lookup("read", 1).is_none());
+    assert!(cache.lookup("fetch", 1).is_some());
+}
+
+#[test]
+fn policies_come_from_config_or_interop_hints() {
+    let config = ToolResultCacheConfig::default();
+    let registry = ToolRegistry::with_builtins();
+
+    let read = cache_policy_for(registry.get("search_text").expect("search_text"), &config)
+        .expect("search_text is cached");
+    assert_eq!(read.path_fields, vec!["path".to_string()]);
+    assert!(cache_policy_for(registry.get("get_time").expect("get_time"), &config).is_none());
+    assert!(
+        cache_policy_for(registry.get("web_fetch").expect("web_fetch"), &config).is_none(),
+        "network tools are opt-in"
+    );
+
+    let read_only = remote_entry(
+        "mcp_docs_lookup",
+        ToolInteropHints {
+            read_only_hint: true,
+            ..ToolInteropHints::default()
+        },
+    );
+    assert!(
+        cache_policy_for(&read_only, &config).is_none(),
+        "hinted tools are opt-in"
+    );
+    let config = ToolResultCacheConfig {
+        hinted_ttl_s: 60,
+        ..config
+    };
+    assert_eq!(
+        cache_policy_for(&read_only, &config).map(|policy| policy.ttl_ms),
+        Some(60_000)
+    );
+
+    let destructive = remote_entry(
+        "mcp_docs_purge",
+        ToolInteropHints {
+            idempotent_hint: true,
+            destructive_hint: true,
+            ..ToolInteropHints::default()
+        },
+    );
+    assert!(cache_policy_for(&destructive, &config).is_none());
+
+    let mut dangerous = read_only.clone();
+    dangerous.descriptor.dangerous = true;
+    assert!(cache_policy_for(&dangerous, &config).is_none());
+
+    let disabled = ToolResultCacheConfig {
+        enabled: false,
+        ..ToolResultCacheConfig::default()
+    };
+    assert!(cache_policy_for(&read_only, &disabled).is_none());
+}
+
+#[test]
+fn configured_policies_merge_over_the_defaults() {
+    let config = toml::from_str::<ToolResultCacheConfig>(
+        r#"
+            [tools.read_file]
+            ttl_s = 30
+
+            [tools.web_fetch]
+            ttl_s = 300
+
+            [tools.kb_lookup]
+            ttl_s = 120
+        "#,
+    )
+    .expect("parse cache config");
+
+    let read_file = &config.tools["read_file"];
+    assert_eq!(read_file.ttl_s, 30);
+    assert_eq!(read_file.path_fields, vec!["path".to_string()]);
+    assert_eq!(config.tools["web_fetch"].ttl_s, 300);
+    assert_eq!(config.tools["kb_lookup"].ttl_s, 120);
+    assert!(config.tools.contains_key("search_text"));
+}
@@ -0,0 +1,282 @@
+//! Minimal HTTP/1.1 server plumbing shared by the kernel's side listeners
+//! (OpenAI facade, MCP export, MCP bridge): one request per connection,
+//! `Content-Length` bodies only, `Connection: close` responses.
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::TcpStream;
+use std::time::{Duration, Instant};
+
+/// Request line plus headers; larger heads are answered with 431.
+const DEFAULT_MAX_HEADER_BYTES: usize = 16 * 1024;
+/// The whole request must arrive within this window, so a client trickling
+/// bytes cannot pin a request thread.
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct HttpReadLimits {
+    pub max_header_bytes: usize,
+    pub max_body_bytes: usize,
+    pub read_timeout: Duration,
+}
+
+impl HttpReadLimits {
+    pub(crate) fn with_max_body_bytes(max_body_bytes: usize) -> Self {
+        Self {
+            max_header_bytes: DEFAULT_MAX_HEADER_BYTES,
+            max_body_bytes,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+        }
+    }
+}
+
+/// A request that could not be read, with the status to answer it with.
+#[derive(Debug)]
+pub(crate) struct HttpReadError {
+    pub status_code: u16,
+    pub message: String,
+}
+
+impl HttpReadError {
+    fn new(status_code: u16, message: impl Into<String>) -> Self {
+        Self {
+            status_code,
+            message: message.into(),
+        }
+    }
+}
+
+pub(crate) struct HttpRequest {
+    pub method: String,
+    pub path: String,
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    pub(crate) fn bearer_token(&self) -> Option<&str> {
+        let value = self.headers.get("authorization")?;
+        let (scheme, token) = value.split_once(' ')?;
+        let token = token.trim();
+        (scheme.eq_ignore_ascii_case("bearer") && !token.is_empty()).then_some(token)
+    }
+
+    /// Browsers always send `Origin`; only loopback pages may reach the server
+    /// (DNS-rebinding guard required by the streamable HTTP transport).
+    pub(crate) fn origin_allowed(&self) -> bool {
+        let Some(origin) = self.headers.get("origin") else {
+            return true;
+        };
+        let host = origin
+            .split_once("://")
+            .map(|(_, rest)| rest)
+            .unwrap_or(origin)
+            .trim_end_matches('/');
+        let host = if host.starts_with('[') {
+            host.split_once(']')
+                .map(|(addr, _)| &addr[1..])
+                .unwrap_or(host)
+        } else {
+            host.split(':').next().unwrap_or(host)
+        };
+        matches!(host, "localhost" | "127.0.0.1" | "::1")
+    }
+}
+
+pub(crate) fn read_http_request(
+    stream: &mut TcpStream,
+    limits: HttpReadLimits,
+) -> Result<HttpRequest, HttpReadError> {
+    let deadline = Instant::now() + limits.read_timeout;
+    let mut buffer = Vec::new();
+    let mut header_end = None;
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Err(request_timeout());
+        }
+        stream.set_read_timeout(Some(remaining)).map_err(|err| {
+            HttpReadError::new(400, format!("Failed to read HTTP request: {err}"))
+        })?;
+        let read = match stream.read(&mut chunk) {
+            Ok(read) => read,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Err(request_timeout());
+            }
+            Err(err) => {
+                return Err(HttpReadError::new(
+                    400,
+                    format!("Failed to read HTTP request: {err}"),
+                ))
+            }
+        };
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if header_end.is_none() {
+            header_end = find_header_end(&buffer);
+            if header_end.is_none() && buffer.len() > limits.max_header_bytes {
+                return Err(HttpReadError::new(
+                    431,
+                    format!(
+                        "HTTP request headers exceed {} bytes.",
+                        limits.max_header_bytes
+                    ),
+                ));
+            }
+        }
+        if let Some(end) = header_end {
+            if end > limits.max_header_bytes {
+                return Err(HttpReadError::new(
+                    431,
+                    format!(
+                        "HTTP request headers exceed {} bytes.",
+                        limits.max_header_bytes
+                    ),
+                ));
+            }
+            let headers_text = std::str::from_utf8(&buffer[..end]).map_err(|err| {
+                HttpReadError::new(400, format!("HTTP request headers are not UTF-8: {err}"))
+            })?;
+            let content_length =
+                parse_content_length(headers_text).map_err(|err| HttpReadError::new(400, err))?;
+            if content_length > limits.max_body_bytes {
+                return Err(HttpReadError::new(
+                    413,
+                    format!("HTTP request body exceeds {} bytes.", limits.max_body_bytes),
+                ));
+            }
+            let request_len = end
+                .checked_add(content_length)
+                .ok_or_else(|| HttpReadError::new(413, "HTTP request body length overflows."))?;
+            if buffer.len() >= request_len {
+                let body = buffer[end..request_len].to_vec();
+                return parse_http_request(headers_text, body)
+                    .map_err(|err| HttpReadError::new(400, err));
+            }
+        }
+    }
+
+    Err(HttpReadError::new(400, "Incomplete HTTP request."))
+}
+
+fn request_timeout() -> HttpReadError {
+    HttpReadError::new(408, "Timed out reading the HTTP request.")
+}
+
+fn parse_http_request(headers: &str, body: Vec<u8>) -> Result<HttpRequest, String> {
+    let mut lines = headers.lines();
+    let request_line = lines
+        .next()
+        .ok_or_else(|| "Missing HTTP request line.".to_string())?;
+    let mut request_parts = request_line.split_whitespace();
+    let method = request_parts
+        .next()
+        .ok_or_else(|| "Missing HTTP method.".to_string())?
+        .to_string();
+    let raw_path = request_parts
+        .next()
+        .ok_or_else(|| "Missing HTTP path.".to_string())?;
+    let path = raw_path
+        .split_once('?')
+        .map(|(path, _)| path)
+        .unwrap_or(raw_path)
+        .to_string();
+
+    let mut parsed_headers = HashMap::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(':') {
+            parsed_headers.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    Ok(HttpRequest {
+        method,
+        path,
+        headers: parsed_headers,
+        body,
+    })
+}
+
+pub(crate) fn write_json_response<T: serde::Serialize>(
+    stream: &mut TcpStream,
+    status_code: u16,
+    body: &T,
+) -> Result<(), String> {
+    let payload = serde_json::to_string(body)
+        .map_err(|err| format!("Failed to serialize HTTP response: {err}"))?;
+    let response = format!(
+        "HTTP/1.1 {status_code} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_text(status_code),
+        payload.len(),
+        payload
+    );
+    write_all(stream, response.as_bytes())
+}
+
+pub(crate) fn write_empty_response(stream: &mut TcpStream, status_code: u16) -> Result<(), String> {
+    let response = format!(
+        "HTTP/1.1 {status_code} {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status_text(status_code)
+    );
+    write_all(stream, response.as_bytes())
+}
+
+pub(crate) fn write_all(stream: &mut TcpStream, bytes: &[u8]) -> Result<(), String> {
+    stream
+        .write_all(bytes)
+        .and_then(|_| stream.flush())
+        .map_err(|err| format!("Failed to write HTTP response: {err}"))
+}
+
+fn status_text(status_code: u16) -> &'static str {
+    match status_code {
+        200 => "OK",
+        202 => "Accepted",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        431 => "Request Header Fields Too Large",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => "Error",
+    }
+}
+
+fn find_header_end(bytes: &[u8]) -> Option<usize> {
+    bytes
+        .windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|index| index + 4)
+}
+
+fn parse_content_length(headers: &str) -> Result<usize, String> {
+    for line in headers.lines().skip(1) {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("Content-Length") {
+            return value
+                .trim()
+                .parse::<usize>()
+                .map_err(|err| format!("Invalid Content-Length header: {err}"));
+        }
+    }
+
+    Ok(0)
+}
+
+#[cfg(test)]
+#[path = "tests/http.rs"]
+mod tests;
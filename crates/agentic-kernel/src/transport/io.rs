@@ -160,6 +160,7 @@ pub fn handle_read_with_test_state(
             &mut turn_assembly,
             auth_token,
             None,
+            None,
         )
     })
 }
@@ -186,6 +187,7 @@ pub fn handle_read_with_registry(
     turn_assembly: &mut TurnAssemblyStore,
     auth_token: &str,
     mcp_bridge: Option<&crate::mcp::bridge::McpBridgeRuntime>,
+    tool_manifests: Option<&crate::tools::manifests::ToolManifestLoader>,
 ) -> bool {
     let mut chunk = [0; 4096];
     match client.stream.read(&mut chunk) {
@@ -232,6 +234,7 @@ pub fn handle_read_with_registry(
                 metrics,
                 auth_token,
                 mcp_bridge,
+                tool_manifests,
             ),
             ParsedCommand::Err(e) => {
                 let request_id = client.allocate_request_id("transport");
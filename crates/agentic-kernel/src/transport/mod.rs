@@ -1,5 +1,6 @@
 mod client;
 mod framing;
+pub(crate) mod http;
 mod io;
 
 pub use client::{Client, ClientState, ParsedCommand};
@@ -0,0 +1,90 @@
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use super::{read_http_request, HttpReadError, HttpReadLimits, HttpRequest};
+
+/// Send `raw` from a client thread (keeping the socket open afterwards) and
+/// read it back with `limits` on the server side.
+fn read_raw(raw: Vec<u8>, limits: HttpReadLimits) -> Result<HttpRequest, HttpReadError> {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("bind test listener");
+    let addr = listener.local_addr().expect("listener addr");
+    let client = thread::spawn(move || {
+        let mut stream = TcpStream::connect(addr).expect("connect test client");
+        let _ = stream.write_all(&raw);
+        thread::sleep(Duration::from_millis(300));
+    });
+    let (mut stream, _) = listener.accept().expect("accept test client");
+    let result = read_http_request(&mut stream, limits);
+    client.join().expect("client thread");
+    result
+}
+
+fn limits() -> HttpReadLimits {
+    HttpReadLimits {
+        max_header_bytes: 256,
+        max_body_bytes: 32,
+        read_timeout: Duration::from_millis(100),
+    }
+}
+
+#[test]
+fn request_within_limits_is_parsed() {
+    let request = read_raw(
+        b"POST /mcp?x=1 HTTP/1.1\r\nAuthorization: Bearer tok\r\nContent-Length: 2\r\n\r\n{}"
+            .to_vec(),
+        limits(),
+    )
+    .expect("request");
+    assert_eq!(request.method, "POST");
+    assert_eq!(request.path, "/mcp");
+    assert_eq!(request.bearer_token(), Some("tok"));
+    assert_eq!(request.body, b"{}");
+}
+
+#[test]
+fn oversized_headers_are_rejected_with_431() {
+    let mut raw = b"GET / HTTP/1.1\r\nX-Filler: ".to_vec();
+    raw.extend(std::iter::repeat_n(b'a', 512));
+    raw.extend_from_slice(b"\r\n\r\n");
+    let err = read_raw(raw, limits()).err().expect("headers over the cap");
+    assert_eq!(err.status_code, 431);
+}
+
+#[test]
+fn oversized_body_is_rejected_with_413_before_it_arrives() {
+    let err = read_raw(
+        b"POST / HTTP/1.1\r\nContent-Length: 4096\r\n\r\n".to_vec(),
+        limits(),
+    )
+    .err()
+    .expect("body over the cap");
+    assert_eq!(err.status_code, 413);
+}
+
+#[test]
+fn stalled_client_times_out_with_408() {
+    let err = read_raw(
+        b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n{".to_vec(),
+        limits(),
+    )
+    .err()
+    .expect("incomplete body never completes");
+    assert_eq!(err.status_code, 408);
+}
+
+#[test]
+fn overflowing_content_length_is_rejected_with_413() {
+    let raw = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", usize::MAX);
+    let err = read_raw(
+        raw.into_bytes(),
+        HttpReadLimits {
+            max_body_bytes: usize::MAX,
+            ..limits()
+        },
+    )
+    .err()
+    .expect("length overflowing the buffer offset");
+    assert_eq!(err.status_code, 413);
+}
@@ -5,7 +5,7 @@ use std::thread;
 use mio::Waker;
 
 use crate::backend::{InferenceFinishReason, InferenceStepRequest};
-use crate::process::{AgentProcess, ProcessState};
+use crate::process::{AgentProcess, ContextCompactionEvent, ContextServices, ProcessState};
 use crate::services::accounting::BackendAccountingEvent;
 
 /// Command sent from the main thread to the inference worker.
@@ -16,6 +16,8 @@ pub enum InferenceCmd {
         process: Box<AgentProcess>,
         rendered_prompt: String,
         resident_prompt_suffix: String,
+        /// Run the pending context compaction (model summary) before the step.
+        compact_context: bool,
         eos_token_id: u32,
         eot_token_id: u32,
     },
@@ -41,6 +43,7 @@ pub enum InferenceResult {
         finished: bool,
         finish_reason: Option<InferenceFinishReason>,
         accounting_event: Option<BackendAccountingEvent>,
+        compaction: Option<InferenceCompaction>,
     },
     /// Inference failed — the process has been dropped (model weights freed).
     Error {
@@ -50,6 +53,13 @@ pub enum InferenceResult {
     },
 }
 
+/// Context compaction the worker ran ahead of an inference step.
+pub struct InferenceCompaction {
+    pub event: Option<ContextCompactionEvent>,
+    /// Usage of the summary requests, accounted separately from the step.
+    pub accounting_events: Vec<BackendAccountingEvent>,
+}
+
 /// Spawn the inference worker thread.
 ///
 /// The worker receives `InferenceCmd`s from `cmd_rx`, runs the forward pass,
@@ -63,6 +73,7 @@ pub fn spawn_worker(
         .name("inference-worker".into())
         .spawn(move || {
             tracing::info!("INFERENCE_WORKER: started");
+            let mut context_services = ContextServices::from_kernel_config();
             loop {
                 let cmd = match cmd_rx.recv() {
                     Ok(cmd) => cmd,
@@ -75,8 +86,9 @@ pub fn spawn_worker(
                     InferenceCmd::Step {
                         pid,
                         process,
-                        rendered_prompt,
-                        resident_prompt_suffix,
+                        mut rendered_prompt,
+                        mut resident_prompt_suffix,
+                        compact_context,
                         eos_token_id,
                         eot_token_id,
                     } => {
@@ -124,6 +136,7 @@ pub fn spawn_worker(
                                     finished: true,
                                     finish_reason: Some(InferenceFinishReason::TurnBudgetExhausted),
                                     accounting_event: None,
+                                    compaction: None,
                                 })
                                 .is_err()
                             {
@@ -135,6 +148,15 @@ pub fn spawn_worker(
                             continue;
                         }
 
+                        let compaction = compact_context.then(|| {
+                            compact_before_step(
+                                &mut process,
+                                &mut context_services,
+                                &mut rendered_prompt,
+                                &mut resident_prompt_suffix,
+                            )
+                        });
+
                         process.state = ProcessState::Running;
 
                         let step = match process.model.generate_step(InferenceStepRequest {
@@ -213,6 +235,7 @@ pub fn spawn_worker(
                                 finished,
                                 finish_reason,
                                 accounting_event,
+                                compaction,
                             })
                             .is_err()
                         {
@@ -233,3 +256,28 @@ pub fn spawn_worker(
         })
         .expect("failed to spawn inference worker thread")
 }
+
+/// Compact the context with the worker's services and re-render the prompt
+/// prepared at checkout: the in-flight assistant continuation is kept, the
+/// resident checkpoint is gone after the backend slot reset.
+fn compact_before_step(
+    process: &mut AgentProcess,
+    services: &mut ContextServices,
+    rendered_prompt: &mut String,
+    resident_prompt_suffix: &mut String,
+) -> InferenceCompaction {
+    let continuation = rendered_prompt
+        .strip_prefix(process.prompt_text())
+        .unwrap_or_default()
+        .to_string();
+    let event = process.enforce_context_budget_with(Some(services));
+    if event.is_some() {
+        *rendered_prompt = process.inference_prompt_text_with_continuation(&continuation);
+        *resident_prompt_suffix =
+            process.pending_inference_prompt_suffix_with_continuation(&continuation);
+    }
+    InferenceCompaction {
+        event,
+        accounting_events: services.take_accounting_events(),
+    }
+}
@@ -8,12 +8,14 @@ pub mod schema {
     pub const AUTH: &str = "agenticos.control.auth.v1";
     pub const BACKEND_DIAG: &str = "agenticos.control.backend_diag.v1";
     pub const CHECKPOINT: &str = "agenticos.control.checkpoint.v1";
+    pub const COMPACT_NOW: &str = "agenticos.control.compact_now.v1";
     pub const COREDUMP: &str = "agenticos.control.coredump.v1";
     pub const COREDUMP_INFO: &str = "agenticos.control.coredump_info.v1";
     pub const REPLAY_COREDUMP: &str = "agenticos.control.replay_coredump.v1";
     pub const CONTINUE_OUTPUT: &str = "agenticos.control.continue_output.v1";
     pub const DELETE_JOB: &str = "agenticos.control.delete_job.v1";
     pub const DELETE_ORCHESTRATION: &str = "agenticos.control.delete_orchestration.v1";
+    pub const DROP_SEGMENT: &str = "agenticos.control.drop_segment.v1";
     pub const EXEC: &str = "agenticos.control.exec.v1";
     pub const ERROR: &str = "agenticos.control.error.v1";
     pub const GET_GEN: &str = "agenticos.control.get_gen.v1";
@@ -25,6 +27,7 @@ pub mod schema {
     pub const LIST_ORCHESTRATIONS: &str = "agenticos.control.list_orchestrations.v1";
     pub const LIST_ARTIFACTS: &str = "agenticos.control.list_artifacts.v1";
     pub const LIST_COREDUMPS: &str = "agenticos.control.list_coredumps.v1";
+    pub const LIST_CHECKPOINTS: &str = "agenticos.control.list_checkpoints.v1";
     pub const LIST_TOOLS: &str = "agenticos.control.list_tools.v1";
     pub const LOAD: &str = "agenticos.control.load.v1";
     pub const MEMORY_WRITE: &str = "agenticos.control.memw.v1";
@@ -33,14 +36,17 @@ pub mod schema {
     pub const ORCHESTRATION_STATUS: &str = "agenticos.control.orchestration_status.v1";
     pub const ORCH_STATUS: &str = ORCHESTRATION_STATUS;
     pub const PID_STATUS: &str = "agenticos.control.pid_status.v1";
+    pub const PIN_SEGMENT: &str = "agenticos.control.pin_segment.v1";
     pub const PING: &str = "agenticos.control.ping.v1";
     pub const REGISTER_TOOL: &str = "agenticos.control.register_tool.v1";
     pub const RETRY_TASK: &str = "agenticos.control.retry_task.v1";
     pub const RESTORE: &str = "agenticos.control.restore.v1";
     pub const RESUME_SESSION: &str = "agenticos.control.resume_session.v1";
+    pub const RUN_JOB_NOW: &str = "agenticos.control.run_job_now.v1";
     pub const SCHEDULE_JOB: &str = "agenticos.control.schedule_job.v1";
     pub const SEND_INPUT: &str = "agenticos.control.send_input.v1";
     pub const SELECT_MODEL: &str = "agenticos.control.select_model.v1";
+    pub const SET_CONTEXT_POLICY: &str = "agenticos.control.set_context_policy.v1";
     pub const SET_GEN: &str = "agenticos.control.set_gen.v1";
     pub const SET_JOB_ENABLED: &str = "agenticos.control.set_job_enabled.v1";
     pub const SET_PRIORITY: &str = "agenticos.control.set_priority.v1";
@@ -63,6 +69,8 @@ pub enum ControlErrorCode {
     BackendDiag,
     CapabilityRequired,
     CheckpointFailed,
+    ContextControlFailed,
+    ContextControlInvalid,
     CoreDumpFailed,
     CoreDumpInfoInvalid,
     CoreDumpInvalid,
@@ -85,6 +93,7 @@ pub enum ControlErrorCode {
     LoadFailed,
     ListJobsInvalid,
     ListCoreDumpsInvalid,
+    ListCheckpointsInvalid,
     ListOrchestrationsInvalid,
     MemwFailed,
     MemwInvalid,
@@ -106,6 +115,7 @@ pub enum ControlErrorCode {
     RestoreBusy,
     RestoreFailed,
     ResumeSessionInvalid,
+    RunJobNowInvalid,
     ScheduleJobInvalid,
     SchedulerLoadFailed,
     SchedulerTargetFailed,
@@ -132,6 +142,8 @@ impl ControlErrorCode {
             Self::BackendDiag => "BACKEND_DIAG",
             Self::CapabilityRequired => "CAPABILITY_REQUIRED",
             Self::CheckpointFailed => "CHECKPOINT_FAILED",
+            Self::ContextControlFailed => "CONTEXT_CONTROL_FAILED",
+            Self::ContextControlInvalid => "CONTEXT_CONTROL_INVALID",
             Self::CoreDumpFailed => "COREDUMP_FAILED",
             Self::CoreDumpInfoInvalid => "COREDUMP_INFO_INVALID",
             Self::CoreDumpInvalid => "COREDUMP_INVALID",
@@ -154,6 +166,7 @@ impl ControlErrorCode {
             Self::LoadFailed => "LOAD_FAILED",
             Self::ListJobsInvalid => "LIST_JOBS_INVALID",
             Self::ListCoreDumpsInvalid => "LIST_COREDUMPS_INVALID",
+            Self::ListCheckpointsInvalid => "LIST_CHECKPOINTS_INVALID",
             Self::ListOrchestrationsInvalid => "LIST_ORCHESTRATIONS_INVALID",
             Self::MemwFailed => "MEMW_FAILED",
             Self::MemwInvalid => "MEMW_INVALID",
@@ -175,6 +188,7 @@ impl ControlErrorCode {
             Self::RestoreBusy => "RESTORE_BUSY",
             Self::RestoreFailed => "RESTORE_FAILED",
             Self::ResumeSessionInvalid => "RESUME_SESSION_INVALID",
+            Self::RunJobNowInvalid => "RUN_JOB_NOW_INVALID",
             Self::ScheduleJobInvalid => "SCHEDULE_JOB_INVALID",
             Self::SchedulerLoadFailed => "SCHEDULER_LOAD_FAILED",
             Self::SchedulerTargetFailed => "SCHEDULER_TARGET_FAILED",
@@ -267,6 +281,10 @@ pub enum OpCode {
     Shutdown,
     Subscribe,
     MemoryWrite,
+    SetContextPolicy,
+    CompactNow,
+    PinSegment,
+    DropSegment,
     ListModels,
     SelectModel,
     ModelInfo,
@@ -281,10 +299,12 @@ pub enum OpCode {
     CoreDumpInfo,
     ReplayCoreDump,
     Restore,
+    ListCheckpoints,
     ResumeSession,
     ScheduleJob,
     SetJobEnabled,
     DeleteJob,
+    RunJobNow,
     Orchestrate,
     ListOrchestrations,
     OrchestrationStatus,
@@ -317,6 +337,10 @@ impl OpCode {
             "SHUTDOWN" => Some(Self::Shutdown),
             "SUBSCRIBE" => Some(Self::Subscribe),
             "MEMW" => Some(Self::MemoryWrite),
+            "SET_CONTEXT_POLICY" => Some(Self::SetContextPolicy),
+            "COMPACT_NOW" => Some(Self::CompactNow),
+            "PIN_SEGMENT" => Some(Self::PinSegment),
+            "DROP_SEGMENT" => Some(Self::DropSegment),
             "LIST_MODELS" => Some(Self::ListModels),
             "SELECT_MODEL" => Some(Self::SelectModel),
             "MODEL_INFO" => Some(Self::ModelInfo),
@@ -331,10 +355,12 @@ impl OpCode {
             "COREDUMP_INFO" => Some(Self::CoreDumpInfo),
             "REPLAY_COREDUMP" => Some(Self::ReplayCoreDump),
             "RESTORE" => Some(Self::Restore),
+            "LIST_CHECKPOINTS" => Some(Self::ListCheckpoints),
             "RESUME_SESSION" => Some(Self::ResumeSession),
             "SCHEDULE_JOB" => Some(Self::ScheduleJob),
             "SET_JOB_ENABLED" => Some(Self::SetJobEnabled),
             "DELETE_JOB" => Some(Self::DeleteJob),
+            "RUN_JOB_NOW" => Some(Self::RunJobNow),
             "ORCHESTRATE" => Some(Self::Orchestrate),
             "LIST_ORCHESTRATIONS" => Some(Self::ListOrchestrations),
             "ORCHESTRATION_STATUS" => Some(Self::OrchestrationStatus),
@@ -368,6 +394,10 @@ impl OpCode {
             Self::Shutdown => "SHUTDOWN",
             Self::Subscribe => "SUBSCRIBE",
             Self::MemoryWrite => "MEMW",
+            Self::SetContextPolicy => "SET_CONTEXT_POLICY",
+            Self::CompactNow => "COMPACT_NOW",
+            Self::PinSegment => "PIN_SEGMENT",
+            Self::DropSegment => "DROP_SEGMENT",
             Self::ListModels => "LIST_MODELS",
             Self::SelectModel => "SELECT_MODEL",
             Self::ModelInfo => "MODEL_INFO",
@@ -382,10 +412,12 @@ impl OpCode {
             Self::CoreDumpInfo => "COREDUMP_INFO",
             Self::ReplayCoreDump => "REPLAY_COREDUMP",
             Self::Restore => "RESTORE",
+            Self::ListCheckpoints => "LIST_CHECKPOINTS",
             Self::ResumeSession => "RESUME_SESSION",
             Self::ScheduleJob => "SCHEDULE_JOB",
             Self::SetJobEnabled => "SET_JOB_ENABLED",
             Self::DeleteJob => "DELETE_JOB",
+            Self::RunJobNow => "RUN_JOB_NOW",
             Self::Orchestrate => "ORCHESTRATE",
             Self::ListOrchestrations => "LIST_ORCHESTRATIONS",
             Self::OrchestrationStatus => "ORCHESTRATION_STATUS",